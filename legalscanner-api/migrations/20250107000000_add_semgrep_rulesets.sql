@@ -0,0 +1,2 @@
+-- Add per-scan Semgrep ruleset override (JSON array of --config values)
+ALTER TABLE scans ADD COLUMN semgrep_rulesets TEXT;
@@ -0,0 +1,37 @@
+-- Add 'malware' result type for optional ClamAV scanning
+-- Malware findings store the detected signature name in raw_data and the
+-- scanner name in ecc_source
+
+-- SQLite doesn't support altering CHECK constraints, so recreate the table
+CREATE TABLE scan_results_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scan_id TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    result_type TEXT NOT NULL CHECK(result_type IN ('license', 'copyright', 'ecc', 'malware')),
+    license_name TEXT,
+    license_spdx_id TEXT,
+    copyright_statement TEXT,
+    copyright_holders TEXT,
+    copyright_years TEXT,
+    confidence REAL,
+    raw_data TEXT,
+    risk_severity TEXT CHECK(risk_severity IN ('low', 'medium', 'high', 'critical')),
+    ecc_source TEXT,
+    ecc_line_number INTEGER,
+    ecc_check_id TEXT,
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+-- Copy existing data
+INSERT INTO scan_results_new SELECT * FROM scan_results;
+
+-- Drop old table
+DROP TABLE scan_results;
+
+-- Rename new table
+ALTER TABLE scan_results_new RENAME TO scan_results;
+
+-- Recreate indexes dropped with the old table
+CREATE INDEX IF NOT EXISTS idx_scan_results_scan_id ON scan_results(scan_id);
+CREATE INDEX IF NOT EXISTS idx_scan_results_type ON scan_results(result_type);
+CREATE INDEX IF NOT EXISTS idx_scan_results_file_path ON scan_results(file_path);
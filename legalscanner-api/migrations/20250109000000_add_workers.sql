@@ -0,0 +1,11 @@
+-- Track running API/worker processes so external autoscalers can see capacity
+CREATE TABLE IF NOT EXISTS workers (
+    id TEXT PRIMARY KEY,
+    hostname TEXT NOT NULL,
+    started_at DATETIME DEFAULT (datetime('now')),
+    last_heartbeat_at DATETIME DEFAULT (datetime('now')),
+    status TEXT NOT NULL DEFAULT 'active' CHECK(status IN ('active', 'draining', 'stopped'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_workers_status ON workers(status);
+CREATE INDEX IF NOT EXISTS idx_scans_completed_at ON scans(completed_at);
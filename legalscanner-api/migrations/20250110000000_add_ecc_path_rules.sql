@@ -0,0 +1,42 @@
+-- Path-class rules for weighting ECC findings by reachability
+-- Findings in shipped source keep their full weight; findings in tests,
+-- examples and documentation are down-weighted to reduce noise
+
+CREATE TABLE IF NOT EXISTS ecc_path_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    path_pattern TEXT NOT NULL UNIQUE,    -- SQL LIKE-style pattern matched against the file path
+    path_class TEXT NOT NULL CHECK(path_class IN ('shipped', 'test', 'example', 'docs', 'other')),
+    weight_multiplier REAL NOT NULL,      -- Multiplier applied to the ECC severity points
+    priority INTEGER NOT NULL DEFAULT 0,  -- Higher priority rules are evaluated first
+    description TEXT,
+    created_at DATETIME DEFAULT (datetime('now'))
+);
+
+-- Tests and fixtures
+INSERT INTO ecc_path_rules (path_pattern, path_class, weight_multiplier, priority, description) VALUES
+    ('test/%', 'test', 0.25, 20, 'Top-level test directory'),
+    ('tests/%', 'test', 0.25, 20, 'Top-level tests directory'),
+    ('%/test/%', 'test', 0.25, 20, 'Nested test directory'),
+    ('%/tests/%', 'test', 0.25, 20, 'Nested tests directory'),
+    ('%/__tests__/%', 'test', 0.25, 20, 'Jest test directory'),
+    ('%/spec/%', 'test', 0.25, 20, 'Spec directory'),
+    ('%/testdata/%', 'test', 0.25, 20, 'Test data directory'),
+    ('%/fixtures/%', 'test', 0.25, 20, 'Test fixtures'),
+    ('%_test.%', 'test', 0.25, 10, 'Go/Python style test file'),
+    ('%.test.%', 'test', 0.25, 10, 'JavaScript style test file'),
+    ('%.spec.%', 'test', 0.25, 10, 'JavaScript style spec file');
+
+-- Examples and samples
+INSERT INTO ecc_path_rules (path_pattern, path_class, weight_multiplier, priority, description) VALUES
+    ('examples/%', 'example', 0.3, 20, 'Top-level examples directory'),
+    ('%/examples/%', 'example', 0.3, 20, 'Nested examples directory'),
+    ('%/example/%', 'example', 0.3, 20, 'Nested example directory'),
+    ('%/samples/%', 'example', 0.3, 20, 'Samples directory');
+
+-- Documentation
+INSERT INTO ecc_path_rules (path_pattern, path_class, weight_multiplier, priority, description) VALUES
+    ('docs/%', 'docs', 0.1, 20, 'Top-level docs directory'),
+    ('%/docs/%', 'docs', 0.1, 20, 'Nested docs directory'),
+    ('%/doc/%', 'docs', 0.1, 20, 'Nested doc directory'),
+    ('%.md', 'docs', 0.1, 5, 'Markdown documentation'),
+    ('%.rst', 'docs', 0.1, 5, 'reStructuredText documentation');
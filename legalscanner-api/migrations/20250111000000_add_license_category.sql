@@ -0,0 +1,5 @@
+-- Distinguish code licenses from documentation and dataset licenses
+ALTER TABLE scan_results ADD COLUMN license_category TEXT
+    CHECK(license_category IN ('code', 'documentation', 'data'));
+
+UPDATE scan_results SET license_category = 'code' WHERE result_type = 'license';
@@ -0,0 +1,51 @@
+-- Add 'package' result type for Syft package inventory
+-- Package findings keep the manifest path in file_path and the declared
+-- license expression in license_spdx_id
+
+-- SQLite doesn't support altering CHECK constraints, so recreate the table
+CREATE TABLE scan_results_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scan_id TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    result_type TEXT NOT NULL CHECK(result_type IN ('license', 'copyright', 'ecc', 'malware', 'package')),
+    license_name TEXT,
+    license_spdx_id TEXT,
+    copyright_statement TEXT,
+    copyright_holders TEXT,
+    copyright_years TEXT,
+    confidence REAL,
+    raw_data TEXT,
+    risk_severity TEXT CHECK(risk_severity IN ('low', 'medium', 'high', 'critical')),
+    ecc_source TEXT,
+    ecc_line_number INTEGER,
+    ecc_check_id TEXT,
+    license_category TEXT CHECK(license_category IN ('code', 'documentation', 'data')),
+    package_name TEXT,
+    package_version TEXT,
+    package_ecosystem TEXT,
+    package_purl TEXT,
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+-- Copy existing data
+INSERT INTO scan_results_new (
+    id, scan_id, file_path, result_type, license_name, license_spdx_id,
+    copyright_statement, copyright_holders, copyright_years, confidence, raw_data,
+    risk_severity, ecc_source, ecc_line_number, ecc_check_id, license_category
+)
+SELECT
+    id, scan_id, file_path, result_type, license_name, license_spdx_id,
+    copyright_statement, copyright_holders, copyright_years, confidence, raw_data,
+    risk_severity, ecc_source, ecc_line_number, ecc_check_id, license_category
+FROM scan_results;
+
+-- Drop old table
+DROP TABLE scan_results;
+
+-- Rename new table
+ALTER TABLE scan_results_new RENAME TO scan_results;
+
+-- Recreate indexes dropped with the old table
+CREATE INDEX IF NOT EXISTS idx_scan_results_scan_id ON scan_results(scan_id);
+CREATE INDEX IF NOT EXISTS idx_scan_results_type ON scan_results(result_type);
+CREATE INDEX IF NOT EXISTS idx_scan_results_file_path ON scan_results(file_path);
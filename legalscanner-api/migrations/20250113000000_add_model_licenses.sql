@@ -0,0 +1,68 @@
+-- Add 'model_license' result type for ML model artifacts (safetensors, ONNX, GGUF)
+-- Model license findings keep the artifact format in ecc_source and the
+-- model card or license file the license was read from in raw_data
+
+-- SQLite doesn't support altering CHECK constraints, so recreate the table
+CREATE TABLE scan_results_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scan_id TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    result_type TEXT NOT NULL CHECK(result_type IN ('license', 'copyright', 'ecc', 'malware', 'package', 'model_license')),
+    license_name TEXT,
+    license_spdx_id TEXT,
+    copyright_statement TEXT,
+    copyright_holders TEXT,
+    copyright_years TEXT,
+    confidence REAL,
+    raw_data TEXT,
+    risk_severity TEXT CHECK(risk_severity IN ('low', 'medium', 'high', 'critical')),
+    ecc_source TEXT,
+    ecc_line_number INTEGER,
+    ecc_check_id TEXT,
+    license_category TEXT CHECK(license_category IN ('code', 'documentation', 'data')),
+    package_name TEXT,
+    package_version TEXT,
+    package_ecosystem TEXT,
+    package_purl TEXT,
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+-- Copy existing data
+INSERT INTO scan_results_new SELECT * FROM scan_results;
+
+-- Drop old table
+DROP TABLE scan_results;
+
+-- Rename new table
+ALTER TABLE scan_results_new RENAME TO scan_results;
+
+-- Recreate indexes dropped with the old table
+CREATE INDEX IF NOT EXISTS idx_scan_results_scan_id ON scan_results(scan_id);
+CREATE INDEX IF NOT EXISTS idx_scan_results_type ON scan_results(result_type);
+CREATE INDEX IF NOT EXISTS idx_scan_results_file_path ON scan_results(file_path);
+
+-- Model licenses get their own risk_config category
+CREATE TABLE risk_config_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    license_pattern TEXT NOT NULL UNIQUE,
+    risk_weight INTEGER NOT NULL,
+    category TEXT NOT NULL CHECK(category IN ('copyleft', 'permissive', 'proprietary', 'unknown', 'other', 'model')),
+    description TEXT,
+    created_at DATETIME DEFAULT (datetime('now')),
+    updated_at DATETIME DEFAULT (datetime('now'))
+);
+
+INSERT INTO risk_config_new SELECT * FROM risk_config;
+
+DROP TABLE risk_config;
+
+ALTER TABLE risk_config_new RENAME TO risk_config;
+
+CREATE INDEX IF NOT EXISTS idx_risk_config_pattern ON risk_config(license_pattern);
+
+-- Use-restricted model licenses
+INSERT INTO risk_config (license_pattern, risk_weight, category, description) VALUES
+    ('Llama-%', 8, 'model', 'Llama community license - acceptable use policy and user-count threshold'),
+    ('Gemma', 8, 'model', 'Gemma terms of use - prohibited use policy applies'),
+    ('%OpenRAIL%', 5, 'model', 'Open RAIL license - use-based restrictions must be passed downstream'),
+    ('BigScience-BLOOM-RAIL%', 5, 'model', 'BLOOM RAIL license - use-based restrictions must be passed downstream');
@@ -0,0 +1,17 @@
+-- Append-only history of scan lifecycle events, used for the scan timeline
+-- occurred_at keeps millisecond precision so short steps get useful durations
+CREATE TABLE IF NOT EXISTS scan_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scan_id TEXT NOT NULL,
+    event_type TEXT NOT NULL CHECK(event_type IN (
+        'created', 'queued', 'clone_started', 'clone_finished',
+        'scanner_started', 'scanner_finished', 'scanner_failed',
+        'results_stored', 'risk_computed', 'completed', 'failed'
+    )),
+    scanner TEXT,
+    detail TEXT,
+    occurred_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%f', 'now')),
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_scan_events_scan_id ON scan_events(scan_id, id);
@@ -0,0 +1,14 @@
+-- Read-only share links exposing a scan's summary report without authentication
+-- Only a hash of the share token is stored; the token itself is shown once on creation
+CREATE TABLE IF NOT EXISTS scan_shares (
+    id TEXT PRIMARY KEY,
+    scan_id TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at DATETIME DEFAULT (datetime('now')),
+    expires_at DATETIME,
+    revoked_at DATETIME,
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_scan_shares_scan_id ON scan_shares(scan_id);
+CREATE INDEX IF NOT EXISTS idx_scan_shares_token_hash ON scan_shares(token_hash);
@@ -0,0 +1,3 @@
+-- Record which merge strategy and scanner(s) produced each license finding,
+-- e.g. 'consensus:fossology+semgrep' or 'keep-all:semgrep'
+ALTER TABLE scan_results ADD COLUMN merge_provenance TEXT;
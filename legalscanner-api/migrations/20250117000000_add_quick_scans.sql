@@ -0,0 +1,27 @@
+-- Quick (preview) scans skip Fossology and only run fast analyses, so the
+-- overall status does not wait for fossology_status
+ALTER TABLE scans ADD COLUMN quick BOOLEAN NOT NULL DEFAULT 0;
+
+-- Timeline records the skipped Fossology run of a quick scan
+-- SQLite doesn't support altering CHECK constraints, so recreate the table
+CREATE TABLE scan_events_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scan_id TEXT NOT NULL,
+    event_type TEXT NOT NULL CHECK(event_type IN (
+        'created', 'queued', 'clone_started', 'clone_finished',
+        'scanner_started', 'scanner_finished', 'scanner_failed', 'scanner_skipped',
+        'results_stored', 'risk_computed', 'completed', 'failed'
+    )),
+    scanner TEXT,
+    detail TEXT,
+    occurred_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%f', 'now')),
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+INSERT INTO scan_events_new SELECT * FROM scan_events;
+
+DROP TABLE scan_events;
+
+ALTER TABLE scan_events_new RENAME TO scan_events;
+
+CREATE INDEX IF NOT EXISTS idx_scan_events_scan_id ON scan_events(scan_id, id);
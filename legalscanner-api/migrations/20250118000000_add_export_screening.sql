@@ -0,0 +1,17 @@
+-- Export control screenings requested from an external trade compliance
+-- system for scans with ECC findings at or above the configured severity.
+-- A scan has at most one screening; resubmitting replaces it.
+CREATE TABLE IF NOT EXISTS scan_screenings (
+    id TEXT PRIMARY KEY,
+    scan_id TEXT NOT NULL UNIQUE,
+    status TEXT NOT NULL CHECK(status IN ('pending', 'cleared', 'blocked', 'error')),
+    reference TEXT,
+    response_code INTEGER,
+    response_body TEXT,
+    requested_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+-- Approval is refused while a scan's screening has not cleared
+ALTER TABLE scans ADD COLUMN approved_at TEXT;
@@ -0,0 +1,3 @@
+-- Soft limit warnings (JSON array of messages) for scans that went ahead
+-- although the repository exceeded a configured size or file count
+ALTER TABLE scans ADD COLUMN warnings TEXT;
@@ -0,0 +1,24 @@
+-- Registry of copyright holders across all scans, keyed by a normalized name
+-- so that "Acme, Inc." and "ACME Inc" are one holder
+CREATE TABLE IF NOT EXISTS copyright_holders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    normalized_name TEXT NOT NULL UNIQUE,
+    display_name TEXT NOT NULL,
+    first_seen_at DATETIME NOT NULL,
+    last_seen_at DATETIME NOT NULL
+);
+
+-- Scans in which each holder appeared. first/last seen on the holder are kept
+-- when scans are deleted; project counts cover the scans still present.
+CREATE TABLE IF NOT EXISTS copyright_holder_scans (
+    holder_id INTEGER NOT NULL,
+    scan_id TEXT NOT NULL,
+    git_url TEXT NOT NULL,
+    file_count INTEGER NOT NULL,
+    seen_at DATETIME NOT NULL,
+    PRIMARY KEY (holder_id, scan_id),
+    FOREIGN KEY (holder_id) REFERENCES copyright_holders(id) ON DELETE CASCADE,
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_copyright_holder_scans_scan_id ON copyright_holder_scans(scan_id);
@@ -0,0 +1,5 @@
+-- Scans started with POST /api/v1/scans/:id/rerun point back at the scan
+-- they repeat
+ALTER TABLE scans ADD COLUMN parent_scan_id TEXT REFERENCES scans(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_scans_parent_scan_id ON scans(parent_scan_id);
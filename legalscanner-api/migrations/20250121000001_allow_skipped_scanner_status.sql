@@ -0,0 +1,15 @@
+-- Re-runs of only the failed scanners mark the others as skipped. SQLite
+-- doesn't support altering CHECK constraints, and a column only referenced
+-- by its own CHECK can be dropped, so swap in columns allowing 'skipped'
+ALTER TABLE scans ADD COLUMN fossology_status_new TEXT DEFAULT 'pending'
+    CHECK(fossology_status_new IN ('pending', 'in_progress', 'completed', 'failed', 'skipped'));
+ALTER TABLE scans ADD COLUMN semgrep_status_new TEXT DEFAULT 'pending'
+    CHECK(semgrep_status_new IN ('pending', 'in_progress', 'completed', 'failed', 'skipped'));
+
+UPDATE scans SET fossology_status_new = fossology_status, semgrep_status_new = semgrep_status;
+
+ALTER TABLE scans DROP COLUMN fossology_status;
+ALTER TABLE scans DROP COLUMN semgrep_status;
+
+ALTER TABLE scans RENAME COLUMN fossology_status_new TO fossology_status;
+ALTER TABLE scans RENAME COLUMN semgrep_status_new TO semgrep_status;
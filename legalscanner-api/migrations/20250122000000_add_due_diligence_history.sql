@@ -0,0 +1,17 @@
+-- Due-diligence scans also walk the git history
+ALTER TABLE scans ADD COLUMN due_diligence BOOLEAN NOT NULL DEFAULT 0;
+
+-- Licensed or third-party code found in the history but gone from HEAD
+CREATE TABLE IF NOT EXISTS scan_history_findings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scan_id TEXT NOT NULL,
+    kind TEXT NOT NULL, -- deleted_file, license_change, removed_third_party
+    file_path TEXT NOT NULL,
+    commit_sha TEXT NOT NULL,
+    commit_date TEXT NOT NULL,
+    license TEXT,
+    detail TEXT,
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_scan_history_findings_scan_id ON scan_history_findings(scan_id);
@@ -0,0 +1,19 @@
+-- Callback URL POSTed to when the scan completes or fails
+ALTER TABLE scans ADD COLUMN callback_url TEXT;
+
+-- Every callback delivery attempt, for auditing
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scan_id TEXT NOT NULL,
+    url TEXT NOT NULL,
+    event TEXT NOT NULL, -- scan.completed, scan.failed
+    attempt INTEGER NOT NULL,
+    status TEXT NOT NULL CHECK(status IN ('delivered', 'failed')),
+    response_code INTEGER,
+    response_body TEXT,
+    error TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_scan_id ON webhook_deliveries(scan_id);
@@ -0,0 +1,3 @@
+-- Subdirectory of the repository a scan is limited to; result paths are
+-- relative to it
+ALTER TABLE scans ADD COLUMN scan_path TEXT;
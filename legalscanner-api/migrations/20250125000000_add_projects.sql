@@ -0,0 +1,18 @@
+-- Projects group the scans of one repository, or of the set of repositories
+-- a product is built from. Scans belong to a project through their git URL.
+CREATE TABLE IF NOT EXISTS projects (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Repository URLs are stored normalized (lowercase, without a trailing
+-- slash or `.git`)
+CREATE TABLE IF NOT EXISTS project_repositories (
+    project_id TEXT NOT NULL,
+    git_url TEXT NOT NULL,
+    PRIMARY KEY (project_id, git_url),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Branch requested when the scan was created, NULL for the default branch.
+-- Together with the repository URL it keys the per-repository scan lock.
+ALTER TABLE scans ADD COLUMN branch TEXT;
@@ -0,0 +1,4 @@
+-- corroborated: another license scanner reported the same license for the
+-- file; disputed: other scanners reported licenses for the file, but not
+-- this one. NULL when only one scanner reported licenses for the file.
+ALTER TABLE scan_results ADD COLUMN license_agreement TEXT;
@@ -0,0 +1,9 @@
+-- Operator-defined callback payloads, one per event type. Events without a
+-- template are sent as the built-in JSON payload.
+CREATE TABLE IF NOT EXISTS notification_templates (
+    event TEXT PRIMARY KEY, -- scan.completed, scan.failed
+    content_type TEXT NOT NULL DEFAULT 'application/json',
+    body TEXT NOT NULL, -- {{variable}} placeholders, see crate::webhooks
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
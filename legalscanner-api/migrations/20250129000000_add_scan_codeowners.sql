@@ -0,0 +1,8 @@
+-- CODEOWNERS file of the scanned revision, captured at clone time so
+-- findings can be attributed to owning teams after the workspace is removed
+CREATE TABLE IF NOT EXISTS scan_codeowners (
+    scan_id TEXT PRIMARY KEY REFERENCES scans(id) ON DELETE CASCADE,
+    source_path TEXT NOT NULL, -- .github/CODEOWNERS, CODEOWNERS or docs/CODEOWNERS
+    content TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,17 @@
+-- License curations: reviewed conclusions that override what the scanners
+-- report for a file, e.g. clearing decisions imported from Fossology.
+-- Applied to every later scan of the repository.
+CREATE TABLE IF NOT EXISTS license_curations (
+    id TEXT PRIMARY KEY,
+    repository TEXT NOT NULL, -- normalized git URL
+    file_path TEXT NOT NULL, -- relative to the repository root
+    concluded_licenses TEXT, -- JSON array of license names, NULL for irrelevant files
+    irrelevant BOOLEAN NOT NULL DEFAULT 0, -- file does not count for licensing
+    source TEXT NOT NULL, -- fossology
+    source_reference TEXT, -- e.g. Fossology upload ID
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(repository, file_path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_license_curations_repository ON license_curations(repository);
@@ -0,0 +1,19 @@
+-- Waivers: accepted findings suppressed with a justification, optionally
+-- until an expiry date. Scoped to one scan or to every scan of a repository,
+-- they match findings by file path, ECC check ID and/or license.
+CREATE TABLE IF NOT EXISTS waivers (
+    id TEXT PRIMARY KEY,
+    scan_id TEXT REFERENCES scans(id) ON DELETE CASCADE,
+    repository TEXT, -- normalized git URL
+    file_path TEXT, -- relative to the repository root, a trailing `/` matches a directory
+    check_id TEXT,
+    license TEXT, -- SPDX ID or license name
+    justification TEXT NOT NULL,
+    expires_at TEXT, -- YYYY-MM-DD HH:MM:SS (UTC), NULL for no expiry
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    CHECK (scan_id IS NOT NULL OR repository IS NOT NULL),
+    CHECK (file_path IS NOT NULL OR check_id IS NOT NULL OR license IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_waivers_scan_id ON waivers(scan_id);
+CREATE INDEX IF NOT EXISTS idx_waivers_repository ON waivers(repository);
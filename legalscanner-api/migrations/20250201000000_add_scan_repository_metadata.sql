@@ -0,0 +1,13 @@
+-- Repository metadata captured when a scan clones the repository: from the
+-- checkout, and from the GitHub or GitLab API when the scan has a token
+CREATE TABLE IF NOT EXISTS scan_repository_metadata (
+    scan_id TEXT PRIMARY KEY REFERENCES scans(id) ON DELETE CASCADE,
+    provider TEXT, -- github or gitlab, NULL when only the checkout was read
+    display_name TEXT,
+    description TEXT,
+    default_branch TEXT,
+    topics TEXT, -- JSON array
+    latest_tag TEXT,
+    contributor_count INTEGER,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
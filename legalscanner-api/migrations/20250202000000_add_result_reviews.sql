@@ -0,0 +1,23 @@
+-- Legal review of individual findings: every finding starts pending until a
+-- reviewer approves or rejects it
+ALTER TABLE scan_results ADD COLUMN review_status TEXT NOT NULL DEFAULT 'pending'
+    CHECK (review_status IN ('pending', 'approved', 'rejected'));
+ALTER TABLE scan_results ADD COLUMN reviewed_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_scan_results_review ON scan_results(scan_id, review_status);
+
+-- Reviewer comments on a finding, oldest first
+CREATE TABLE IF NOT EXISTS scan_result_comments (
+    id TEXT PRIMARY KEY,
+    scan_id TEXT NOT NULL REFERENCES scans(id) ON DELETE CASCADE,
+    result_id INTEGER NOT NULL REFERENCES scan_results(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    author TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_scan_result_comments_result ON scan_result_comments(result_id);
+
+-- Set when a reviewer marks the scan reviewed, which requires every finding
+-- that trips a failing gate rule to be approved, rejected or waived
+ALTER TABLE scans ADD COLUMN reviewed_at TEXT;
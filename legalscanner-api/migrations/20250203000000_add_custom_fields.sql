@@ -0,0 +1,21 @@
+-- Custom fields: deployment-defined metadata (export classification,
+-- product line, contract ID) attached to scans and included in reports.
+CREATE TABLE IF NOT EXISTS custom_fields (
+    key TEXT PRIMARY KEY,
+    label TEXT NOT NULL,
+    field_type TEXT NOT NULL CHECK(field_type IN ('string', 'number', 'boolean', 'date', 'enum')),
+    options TEXT, -- JSON array of allowed values for enum fields
+    pattern TEXT, -- regular expression string values must match
+    required INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Values are stored JSON-encoded, typed by the field definition
+CREATE TABLE IF NOT EXISTS scan_custom_field_values (
+    scan_id TEXT NOT NULL REFERENCES scans(id) ON DELETE CASCADE,
+    field_key TEXT NOT NULL REFERENCES custom_fields(key) ON DELETE CASCADE,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (scan_id, field_key)
+);
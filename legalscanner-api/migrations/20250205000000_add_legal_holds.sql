@@ -0,0 +1,19 @@
+-- Legal holds: a held scan, or any scan of a held project's repositories,
+-- cannot be deleted until an admin lifts the hold
+ALTER TABLE scans ADD COLUMN legal_hold_at TEXT;
+ALTER TABLE scans ADD COLUMN legal_hold_reason TEXT;
+ALTER TABLE projects ADD COLUMN legal_hold_at TEXT;
+ALTER TABLE projects ADD COLUMN legal_hold_reason TEXT;
+
+-- Audit log of holds being placed and lifted. Kept once the scan or
+-- project is deleted, so no foreign key.
+CREATE TABLE IF NOT EXISTS legal_hold_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    target_type TEXT NOT NULL CHECK(target_type IN ('scan', 'project')),
+    target_id TEXT NOT NULL,
+    action TEXT NOT NULL CHECK(action IN ('placed', 'lifted')),
+    reason TEXT,
+    occurred_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_legal_hold_events_target ON legal_hold_events(target_type, target_id, id);
@@ -0,0 +1,19 @@
+-- A composite scan scans every repository of a project (a shipped product)
+-- at once, so its SBOM, risk assessment and NOTICE cover the whole product
+CREATE TABLE IF NOT EXISTS composite_scans (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_composite_scans_project_id ON composite_scans(project_id);
+
+-- One member scan per repository of the project at the time of the scan
+CREATE TABLE IF NOT EXISTS composite_scan_members (
+    composite_scan_id TEXT NOT NULL,
+    scan_id TEXT NOT NULL,
+    PRIMARY KEY (composite_scan_id, scan_id),
+    FOREIGN KEY (composite_scan_id) REFERENCES composite_scans(id) ON DELETE CASCADE,
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
@@ -0,0 +1,18 @@
+-- Scans can recursively check out git submodules so vendored code pulled in
+-- through them is scanned too. A separate token may be used for submodules
+-- hosted elsewhere; like git_token it is never returned by the API.
+ALTER TABLE scans ADD COLUMN submodules INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE scans ADD COLUMN submodule_token TEXT;
+
+-- Submodules checked out for a scan, with the commit they were at. Findings
+-- under `path` came from the submodule.
+CREATE TABLE IF NOT EXISTS scan_submodules (
+    scan_id TEXT NOT NULL,
+    path TEXT NOT NULL,
+    url TEXT,
+    commit_sha TEXT,
+    -- Set when the submodule could not be checked out
+    error TEXT,
+    PRIMARY KEY (scan_id, path),
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
@@ -0,0 +1,27 @@
+-- Lifecycle of findings across the scans of a repository, to track
+-- remediation rather than detection. A finding is identified by its type,
+-- repository-relative path and value (license, ECC check, malware signature
+-- or package). It is opened when a scan first finds it, may be triaged by a
+-- reviewer, and is resolved by hand or once a later scan no longer finds it.
+-- Scan IDs are kept without a foreign key, the lifecycle outlives its scans.
+CREATE TABLE IF NOT EXISTS tracked_findings (
+    id TEXT PRIMARY KEY,
+    repository TEXT NOT NULL, -- normalized git URL
+    result_type TEXT NOT NULL,
+    file_path TEXT NOT NULL, -- relative to the repository root
+    value TEXT NOT NULL,
+    severity TEXT,
+    state TEXT NOT NULL DEFAULT 'open' CHECK (state IN ('open', 'triaged', 'resolved')),
+    first_scan_id TEXT NOT NULL,
+    last_scan_id TEXT NOT NULL,
+    opened_at TEXT NOT NULL DEFAULT (datetime('now')),
+    triaged_at TEXT,
+    resolved_at TEXT,
+    resolved_scan_id TEXT, -- scan that no longer found it, NULL when resolved by hand
+    reopened_at TEXT,
+    note TEXT, -- reviewer note of the last transition
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (repository, result_type, file_path, value)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tracked_findings_repository ON tracked_findings(repository, state);
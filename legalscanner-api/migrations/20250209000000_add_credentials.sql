@@ -0,0 +1,20 @@
+-- Named git credentials, so scans reference a credential instead of carrying
+-- a raw token. Secrets are encrypted with CREDENTIALS_ENCRYPTION_KEY
+-- (AES-256-GCM, base64 of nonce and ciphertext) and never returned.
+CREATE TABLE IF NOT EXISTS credentials (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    provider TEXT NOT NULL CHECK (provider IN ('github', 'gitlab', 'azure_devops', 'ssh_key')),
+    -- HTTPS user name or SSH user; a provider default when NULL
+    username TEXT,
+    -- Token or private key
+    secret_encrypted TEXT NOT NULL,
+    -- Passphrase of an SSH private key
+    passphrase_encrypted TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    last_used_at TEXT
+);
+
+-- Scans authenticate with the credential instead of git_token when set
+ALTER TABLE scans ADD COLUMN credential_id TEXT;
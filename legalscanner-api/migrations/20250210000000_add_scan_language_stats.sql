@@ -0,0 +1,10 @@
+-- Files per programming language of the scanned checkout, by file
+-- extension, captured before the workspace is removed so license coverage
+-- can be reported per language
+CREATE TABLE IF NOT EXISTS scan_language_stats (
+    scan_id TEXT NOT NULL REFERENCES scans(id) ON DELETE CASCADE,
+    language TEXT NOT NULL,
+    file_count INTEGER NOT NULL,
+    total_bytes INTEGER NOT NULL,
+    PRIMARY KEY (scan_id, language)
+);
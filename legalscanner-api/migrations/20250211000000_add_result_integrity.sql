@@ -0,0 +1,25 @@
+-- Checksums of each scan's stored findings, sealed when the results are
+-- written and recomputed by the integrity job to detect silent corruption
+CREATE TABLE IF NOT EXISTS scan_result_checksums (
+    scan_id TEXT PRIMARY KEY REFERENCES scans(id) ON DELETE CASCADE,
+    result_count INTEGER NOT NULL,
+    checksum TEXT NOT NULL,
+    sealed_at TEXT NOT NULL DEFAULT (datetime('now')),
+    verified_at TEXT
+);
+
+-- Audit log of discrepancies found by integrity checks, and of checksums
+-- sealed again after an erasure. Kept once the scan is deleted, so no
+-- foreign key.
+CREATE TABLE IF NOT EXISTS integrity_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    target_type TEXT NOT NULL CHECK(target_type IN ('database', 'scan')),
+    target_id TEXT,
+    kind TEXT NOT NULL CHECK(kind IN ('database_corrupt', 'checksum_mismatch', 'unreadable', 'resealed')),
+    expected TEXT,
+    actual TEXT,
+    detail TEXT,
+    occurred_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_integrity_events_target ON integrity_events(target_id, id);
@@ -0,0 +1,8 @@
+-- Provenance of the scanned checkout: the HEAD commit, a tag pointing at
+-- it, and the size of the working tree
+ALTER TABLE scan_repository_metadata ADD COLUMN commit_sha TEXT;
+ALTER TABLE scan_repository_metadata ADD COLUMN commit_date TEXT;
+ALTER TABLE scan_repository_metadata ADD COLUMN commit_author TEXT;
+ALTER TABLE scan_repository_metadata ADD COLUMN head_tag TEXT;
+ALTER TABLE scan_repository_metadata ADD COLUMN size_bytes INTEGER;
+ALTER TABLE scan_repository_metadata ADD COLUMN file_count INTEGER;
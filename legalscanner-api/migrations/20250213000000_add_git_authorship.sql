@@ -0,0 +1,23 @@
+-- Scans that also attribute files to their commit authors
+ALTER TABLE scans ADD COLUMN git_authorship BOOLEAN NOT NULL DEFAULT 0;
+
+-- Commit authors of the files at HEAD, from the git history: copyright
+-- evidence separate from the notices Fossology finds in the files
+CREATE TABLE IF NOT EXISTS scan_file_authors (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scan_id TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    author_name TEXT NOT NULL,
+    author_email TEXT NOT NULL,
+    commit_count INTEGER NOT NULL,
+    first_commit_sha TEXT NOT NULL,
+    first_commit_date TEXT NOT NULL,
+    last_commit_sha TEXT NOT NULL,
+    last_commit_date TEXT NOT NULL,
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_scan_file_authors_scan_id ON scan_file_authors(scan_id);
+
+-- Files a holder committed to, next to the files whose notices name it
+ALTER TABLE copyright_holder_scans ADD COLUMN commit_file_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,15 @@
+-- Software Heritage content identifiers of the scanned files, with what the
+-- Software Heritage archive knows about them when it was asked
+CREATE TABLE IF NOT EXISTS scan_file_swhids (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scan_id TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    swhid TEXT NOT NULL,
+    -- NULL when the archive was not asked
+    known BOOLEAN,
+    -- Upstream repository the archive found the content in
+    origin TEXT,
+    FOREIGN KEY (scan_id) REFERENCES scans(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_scan_file_swhids_scan_id ON scan_file_swhids(scan_id);
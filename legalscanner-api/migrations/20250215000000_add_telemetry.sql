@@ -0,0 +1,11 @@
+-- Opt-in usage telemetry. A scan failure is classified by the type of the
+-- error, the message itself is never reported.
+ALTER TABLE scans ADD COLUMN error_class TEXT;
+
+-- Anonymous ID of this installation and the end of the period last reported,
+-- shared by the workers so only one of them reports each period
+CREATE TABLE IF NOT EXISTS telemetry_state (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    installation_id TEXT NOT NULL,
+    last_reported_at TEXT
+);
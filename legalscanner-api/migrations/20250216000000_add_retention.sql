@@ -0,0 +1,4 @@
+-- Retention purges the findings of old scans but keeps the scan itself. The
+-- summary of the findings is taken before they go.
+ALTER TABLE scans ADD COLUMN results_purged_at TEXT;
+ALTER TABLE scans ADD COLUMN results_summary TEXT; -- JSON ScanSummary
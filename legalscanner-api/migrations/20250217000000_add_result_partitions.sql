@@ -0,0 +1,40 @@
+-- Findings partitioned by the month of their scan: the table holding the
+-- findings of a scan, set when they are first stored. NULL is the original
+-- scan_results table, which keeps the findings stored before partitioning.
+ALTER TABLE scans ADD COLUMN results_table TEXT;
+
+-- Every finding across partitions, for queries over many scans. Recreated
+-- whenever a partition is added.
+CREATE VIEW IF NOT EXISTS all_scan_results AS SELECT * FROM scan_results;
+
+-- Finding IDs stay unique across partitions: they are allocated here rather
+-- than by each table's AUTOINCREMENT
+CREATE TABLE IF NOT EXISTS scan_result_sequence (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    last_id INTEGER NOT NULL
+);
+
+INSERT INTO scan_result_sequence (id, last_id)
+SELECT 1, MAX(
+    COALESCE((SELECT seq FROM sqlite_sequence WHERE name = 'scan_results'), 0),
+    COALESCE((SELECT MAX(id) FROM scan_results), 0)
+);
+
+-- Comments refer to findings in any partition, which a foreign key cannot;
+-- they are deleted along with the findings instead
+CREATE TABLE scan_result_comments_new (
+    id TEXT PRIMARY KEY,
+    scan_id TEXT NOT NULL REFERENCES scans(id) ON DELETE CASCADE,
+    result_id INTEGER NOT NULL,
+    body TEXT NOT NULL,
+    author TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT INTO scan_result_comments_new (id, scan_id, result_id, body, author, created_at)
+SELECT id, scan_id, result_id, body, author, created_at FROM scan_result_comments;
+
+DROP TABLE scan_result_comments;
+ALTER TABLE scan_result_comments_new RENAME TO scan_result_comments;
+
+CREATE INDEX IF NOT EXISTS idx_scan_result_comments_result ON scan_result_comments(result_id);
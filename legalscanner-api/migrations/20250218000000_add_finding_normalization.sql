@@ -0,0 +1,64 @@
+-- License and copyright strings repeat across thousands of files and scans;
+-- findings refer to one row per distinct value instead of repeating it.
+-- The per-scan summary tables of the initial schema were never filled.
+DROP TABLE IF EXISTS licenses;
+DROP TABLE IF EXISTS copyrights;
+
+CREATE TABLE licenses (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    spdx_id TEXT
+);
+
+CREATE UNIQUE INDEX idx_licenses_name ON licenses(name, IFNULL(spdx_id, ''));
+
+-- Holders and years are JSON arrays, as parsed from the statement
+CREATE TABLE copyright_statements (
+    id INTEGER PRIMARY KEY,
+    statement TEXT NOT NULL,
+    holders TEXT,
+    years TEXT
+);
+
+CREATE UNIQUE INDEX idx_copyright_statements_statement
+    ON copyright_statements(statement, IFNULL(holders, ''), IFNULL(years, ''));
+
+-- license_name, license_spdx_id and the copyright columns are left NULL on
+-- normalized findings. Package findings keep their declared expression in
+-- license_spdx_id.
+ALTER TABLE scan_results ADD COLUMN license_id INTEGER REFERENCES licenses(id);
+ALTER TABLE scan_results ADD COLUMN copyright_statement_id INTEGER REFERENCES copyright_statements(id);
+
+INSERT OR IGNORE INTO licenses (name, spdx_id)
+SELECT DISTINCT license_name, license_spdx_id FROM scan_results
+WHERE license_name IS NOT NULL;
+
+UPDATE scan_results SET
+    license_id = (
+        SELECT l.id FROM licenses l
+        WHERE l.name = scan_results.license_name
+          AND IFNULL(l.spdx_id, '') = IFNULL(scan_results.license_spdx_id, '')
+    ),
+    license_name = NULL,
+    license_spdx_id = NULL
+WHERE license_name IS NOT NULL;
+
+INSERT OR IGNORE INTO copyright_statements (statement, holders, years)
+SELECT DISTINCT copyright_statement, copyright_holders, copyright_years FROM scan_results
+WHERE copyright_statement IS NOT NULL;
+
+UPDATE scan_results SET
+    copyright_statement_id = (
+        SELECT c.id FROM copyright_statements c
+        WHERE c.statement = scan_results.copyright_statement
+          AND IFNULL(c.holders, '') = IFNULL(scan_results.copyright_holders, '')
+          AND IFNULL(c.years, '') = IFNULL(scan_results.copyright_years, '')
+    ),
+    copyright_statement = NULL,
+    copyright_holders = NULL,
+    copyright_years = NULL
+WHERE copyright_statement IS NOT NULL;
+
+-- Recreated with the partitions and the joins above once migrations ran,
+-- see result_partition::refresh
+DROP VIEW IF EXISTS all_scan_results;
@@ -0,0 +1,7 @@
+-- SPDX exception a license is granted with, e.g. Classpath-exception-2.0.
+-- The same license with and without an exception are separate rows.
+ALTER TABLE licenses ADD COLUMN exception TEXT;
+
+DROP INDEX idx_licenses_name;
+CREATE UNIQUE INDEX idx_licenses_name
+    ON licenses(name, IFNULL(spdx_id, ''), IFNULL(exception, ''));
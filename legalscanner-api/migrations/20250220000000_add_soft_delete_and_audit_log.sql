@@ -0,0 +1,21 @@
+-- Deleted scans are kept, hidden from the API, until an admin restores them
+-- or retention and erasure remove them for good
+ALTER TABLE scans ADD COLUMN deleted_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_scans_deleted_at ON scans(deleted_at);
+
+-- Who created, deleted and re-ran scans and API keys, and through which
+-- key. Kept once the target is gone, so no foreign keys.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    action TEXT NOT NULL,
+    target_type TEXT NOT NULL CHECK(target_type IN ('scan', 'api_key')),
+    target_id TEXT,
+    actor TEXT NOT NULL CHECK(actor IN ('api_key', 'admin', 'anonymous')),
+    api_key_id TEXT,
+    detail TEXT,
+    occurred_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_target ON audit_log(target_type, target_id, id);
+CREATE INDEX IF NOT EXISTS idx_audit_log_api_key ON audit_log(api_key_id, id);
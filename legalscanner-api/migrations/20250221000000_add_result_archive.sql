@@ -0,0 +1,5 @@
+-- Archival moves the findings of old scans to compressed JSON on disk or in
+-- S3, under results_archive_key, and reads them back from there on request.
+-- The summary of the findings is kept in results_summary.
+ALTER TABLE scans ADD COLUMN results_archived_at TEXT;
+ALTER TABLE scans ADD COLUMN results_archive_key TEXT;
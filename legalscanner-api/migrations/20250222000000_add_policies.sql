@@ -0,0 +1,27 @@
+-- License policies: named allow, deny and needs-review license lists with
+-- severity thresholds, assigned to projects or to single scans. A scan's
+-- own policy takes precedence over the policy of its project.
+CREATE TABLE IF NOT EXISTS policies (
+    name TEXT PRIMARY KEY,
+    description TEXT,
+    allowed_licenses TEXT NOT NULL DEFAULT '[]', -- JSON array of SPDX IDs or license names
+    denied_licenses TEXT NOT NULL DEFAULT '[]',
+    review_licenses TEXT NOT NULL DEFAULT '[]',
+    max_risk_score INTEGER, -- higher risk scores fail, NULL for no limit
+    -- ECC findings of this severity or higher fail, NULL for none
+    ecc_fail_severity TEXT CHECK (ecc_fail_severity IN ('low', 'medium', 'high', 'critical')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+ALTER TABLE projects ADD COLUMN policy TEXT REFERENCES policies(name) ON DELETE SET NULL;
+ALTER TABLE scans ADD COLUMN policy TEXT REFERENCES policies(name) ON DELETE SET NULL;
+
+-- Latest evaluation of a scan against the policy that applied to it
+CREATE TABLE IF NOT EXISTS policy_evaluations (
+    scan_id TEXT PRIMARY KEY REFERENCES scans(id) ON DELETE CASCADE,
+    policy TEXT NOT NULL, -- name at evaluation time, kept if the policy is deleted
+    outcome TEXT NOT NULL CHECK (outcome IN ('pass', 'warn', 'fail')),
+    violations TEXT NOT NULL DEFAULT '[]', -- JSON array
+    evaluated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,7 @@
+-- Version of the scoring behind each stored risk assessment, so a changed
+-- score can be traced to a new algorithm or a new risk configuration, and
+-- outdated assessments can be recalculated. NULL for assessments stored
+-- before versions were recorded.
+ALTER TABLE scans ADD COLUMN risk_algorithm_version INTEGER;
+ALTER TABLE scans ADD COLUMN risk_config_version TEXT; -- hash of risk_config and ecc_path_rules
+ALTER TABLE scans ADD COLUMN risk_assessed_at TEXT;
@@ -0,0 +1,3 @@
+-- Commit a scan checks out instead of the head of its branch, e.g. the
+-- commit a push webhook reported
+ALTER TABLE scans ADD COLUMN commit_sha TEXT;
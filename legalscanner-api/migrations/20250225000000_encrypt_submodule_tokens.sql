@@ -0,0 +1,5 @@
+-- Submodule tokens are encrypted with CREDENTIALS_ENCRYPTION_KEY, bound to
+-- the scan's ID. Tokens stored in plaintext are dropped; re-runs of their
+-- scans check out submodules with git_token.
+ALTER TABLE scans DROP COLUMN submodule_token;
+ALTER TABLE scans ADD COLUMN submodule_token_encrypted TEXT;
@@ -0,0 +1,4 @@
+-- The organization's default license policy applies to every scan. Project
+-- and scan policies override it rule by rule.
+ALTER TABLE policies ADD COLUMN is_default INTEGER NOT NULL DEFAULT 0;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_policies_default ON policies (is_default) WHERE is_default = 1;
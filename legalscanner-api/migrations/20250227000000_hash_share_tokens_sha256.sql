@@ -0,0 +1,6 @@
+-- Share tokens are now looked up by their SHA-256 instead of an Argon2 hash.
+-- Argon2 hashes cannot be converted, so links created before are revoked and
+-- have to be shared again.
+UPDATE scan_shares
+SET revoked_at = datetime('now')
+WHERE token_hash LIKE '$argon2%' AND revoked_at IS NULL;
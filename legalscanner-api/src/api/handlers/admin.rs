@@ -0,0 +1,572 @@
+use crate::{
+    api::models::{
+        ArchiveRequest, AuditLogResponse, ErasureRequest, IntegrityEventsResponse, LegalHoldEventsResponse,
+        LegalHoldRequest, LegalHoldResponse, PurgeRequest, RecalculateRiskRequest, ScanResponse,
+    },
+    archival::{self, ArchiveReport},
+    audit::{self, Actor},
+    db::models::{
+        audit_log::AuditLogFilter, AuditLogEntry, IntegrityEvent, LegalHoldEvent, Project, Scan,
+    },
+    erasure::{erase, ErasureReport},
+    error::AppError,
+    integrity::{self, IntegrityReport},
+    legal_hold,
+    rescoring::{self, RescoreReport},
+    retention::{self, PurgeReport, RetentionPolicy},
+    scan_archive::{self, ArchiveImportReport},
+    telemetry::{self, TelemetryReport},
+    AppState,
+};
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+/// POST /api/v1/admin/erasure - Irreversibly erase all data about a
+/// repository and/or an e-mail address, for data subject requests
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/erasure",
+    tag = "admin",
+    request_body = ErasureRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "What was erased", body = ErasureReport),
+        (status = 400, description = "Neither `git_url` nor a valid `email` given", body = ErrorResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+        (status = 409, description = "A scan of the repository is still running or under legal hold", body = ErrorResponse),
+    )
+)]
+pub async fn erase_data(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<ErasureRequest>,
+) -> Result<Json<ErasureReport>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    let non_empty = |v: Option<String>| v.filter(|v| !v.trim().is_empty());
+    let git_url = non_empty(payload.git_url);
+    let email = non_empty(payload.email);
+
+    if git_url.is_none() && email.is_none() {
+        return Err(AppError::Validation(
+            "git_url or email is required".to_string(),
+        ));
+    }
+    if let Some(email) = &email {
+        if !email.contains('@') {
+            return Err(AppError::Validation(format!("Invalid email '{}'", email)));
+        }
+    }
+
+    let report = erase(&state, git_url.as_deref(), email.as_deref()).await?;
+    Ok(Json(report))
+}
+
+/// PUT /api/v1/admin/scans/:id/legal-hold - Place a scan under legal hold,
+/// blocking its deletion until the hold is lifted
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/scans/{id}/legal-hold",
+    tag = "admin",
+    params(("id" = String, Path, description = "Scan ID")),
+    request_body = LegalHoldRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, body = LegalHoldResponse),
+        (status = 400, description = "Empty reason", body = ErrorResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn place_scan_legal_hold(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<LegalHoldRequest>,
+) -> Result<Json<LegalHoldResponse>, AppError> {
+    authorize_admin(&state, &headers)?;
+    let reason = validate_reason(&payload.reason)?;
+
+    let scan = legal_hold::set_scan_hold(&state.db, &id, Some(reason)).await?;
+    Ok(Json(hold_response(
+        "scan",
+        scan.id,
+        scan.legal_hold_at,
+        scan.legal_hold_reason,
+    )))
+}
+
+/// DELETE /api/v1/admin/scans/:id/legal-hold - Lift the legal hold of a scan
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/scans/{id}/legal-hold",
+    tag = "admin",
+    params(("id" = String, Path, description = "Scan ID")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, body = LegalHoldResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+        (status = 404, description = "Scan not found or not held", body = ErrorResponse),
+    )
+)]
+pub async fn lift_scan_legal_hold(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<LegalHoldResponse>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+    if scan.legal_hold_at.is_none() {
+        return Err(AppError::NotFound(format!(
+            "Scan {} is not under legal hold",
+            id
+        )));
+    }
+
+    let scan = legal_hold::set_scan_hold(&state.db, &id, None).await?;
+    Ok(Json(hold_response(
+        "scan",
+        scan.id,
+        scan.legal_hold_at,
+        scan.legal_hold_reason,
+    )))
+}
+
+/// PUT /api/v1/admin/projects/:id/legal-hold - Place a project under legal
+/// hold, blocking deletion of the project and every scan of its repositories
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/projects/{id}/legal-hold",
+    tag = "admin",
+    params(("id" = String, Path, description = "Project ID")),
+    request_body = LegalHoldRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, body = LegalHoldResponse),
+        (status = 400, description = "Empty reason", body = ErrorResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+    )
+)]
+pub async fn place_project_legal_hold(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<LegalHoldRequest>,
+) -> Result<Json<LegalHoldResponse>, AppError> {
+    authorize_admin(&state, &headers)?;
+    let reason = validate_reason(&payload.reason)?;
+
+    let project = legal_hold::set_project_hold(&state.db, &id, Some(reason)).await?;
+    Ok(Json(hold_response(
+        "project",
+        project.id,
+        project.legal_hold_at,
+        project.legal_hold_reason,
+    )))
+}
+
+/// DELETE /api/v1/admin/projects/:id/legal-hold - Lift the legal hold of a
+/// project
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/projects/{id}/legal-hold",
+    tag = "admin",
+    params(("id" = String, Path, description = "Project ID")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, body = LegalHoldResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+        (status = 404, description = "Project not found or not held", body = ErrorResponse),
+    )
+)]
+pub async fn lift_project_legal_hold(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<LegalHoldResponse>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    let project = Project::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Project {} not found", id)))?;
+    if project.legal_hold_at.is_none() {
+        return Err(AppError::NotFound(format!(
+            "Project {} is not under legal hold",
+            id
+        )));
+    }
+
+    let project = legal_hold::set_project_hold(&state.db, &id, None).await?;
+    Ok(Json(hold_response(
+        "project",
+        project.id,
+        project.legal_hold_at,
+        project.legal_hold_reason,
+    )))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LegalHoldEventsQueryParams {
+    /// Only events of this scan or project
+    pub target_id: Option<String>,
+}
+
+/// GET /api/v1/admin/legal-holds/events - Audit log of legal holds placed
+/// and lifted
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/legal-holds/events",
+    tag = "admin",
+    params(LegalHoldEventsQueryParams),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, body = LegalHoldEventsResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+    )
+)]
+pub async fn list_legal_hold_events(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<LegalHoldEventsQueryParams>,
+) -> Result<Json<LegalHoldEventsResponse>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    let events = LegalHoldEvent::list(&state.db, params.target_id.as_deref()).await?;
+    Ok(Json(LegalHoldEventsResponse { events }))
+}
+
+/// POST /api/v1/admin/integrity/verify - Check the database file and
+/// recompute the checksums of all stored results now, instead of waiting
+/// for the periodic check
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/integrity/verify",
+    tag = "admin",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Outcome of the check", body = IntegrityReport),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+    )
+)]
+pub async fn verify_integrity(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<IntegrityReport>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    Ok(Json(integrity::verify(&state).await?))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct IntegrityEventsQueryParams {
+    /// Only events of this scan
+    pub scan_id: Option<String>,
+}
+
+/// GET /api/v1/admin/integrity/events - Audit log of discrepancies found by
+/// integrity checks and of checksums sealed again after an erasure
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/integrity/events",
+    tag = "admin",
+    params(IntegrityEventsQueryParams),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, body = IntegrityEventsResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+    )
+)]
+pub async fn list_integrity_events(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<IntegrityEventsQueryParams>,
+) -> Result<Json<IntegrityEventsResponse>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    let events = IntegrityEvent::list(&state.db, params.scan_id.as_deref()).await?;
+    Ok(Json(IntegrityEventsResponse { events }))
+}
+
+/// POST /api/v1/admin/purge - Apply the retention policy now, or a
+/// stricter one given in the request. Scans under legal hold are kept.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/purge",
+    tag = "admin",
+    request_body = PurgeRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "What was purged", body = PurgeReport),
+        (status = 400, description = "No retention period configured or given", body = ErrorResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+    )
+)]
+pub async fn purge_data(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<PurgeRequest>,
+) -> Result<Json<PurgeReport>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    let configured = RetentionPolicy::from_config(&state.config);
+    let policy = RetentionPolicy {
+        results_days: payload
+            .results_older_than_days
+            .unwrap_or(configured.results_days),
+        scans_days: payload.scans_older_than_days.unwrap_or(configured.scans_days),
+    };
+    if policy.is_unlimited() && !payload.vacuum {
+        return Err(AppError::Validation(
+            "No retention period configured or given".to_string(),
+        ));
+    }
+
+    Ok(Json(
+        retention::purge(&state, policy, payload.dry_run, payload.vacuum).await?,
+    ))
+}
+
+/// POST /api/v1/admin/archive - Move the findings of old scans to the
+/// result archive now, or those of scans younger than configured
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/archive",
+    tag = "admin",
+    request_body = ArchiveRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "What was archived", body = ArchiveReport),
+        (status = 400, description = "No archive or archival age configured or given", body = ErrorResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+    )
+)]
+pub async fn archive_results(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<ArchiveRequest>,
+) -> Result<Json<ArchiveReport>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    let older_than_days = match payload.older_than_days {
+        Some(days) => days,
+        None if state.config.result_archive_after_days > 0 => {
+            state.config.result_archive_after_days
+        }
+        None => {
+            return Err(AppError::Validation(
+                "No archival age configured or given".to_string(),
+            ))
+        }
+    };
+
+    Ok(Json(
+        archival::archive(&state, older_than_days, payload.dry_run).await?,
+    ))
+}
+
+/// POST /api/v1/admin/risk/recalculate - Rescore the completed scans
+/// assessed with another version of the scoring algorithm or risk
+/// configuration, or without a risk assessment
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/risk/recalculate",
+    tag = "admin",
+    request_body = RecalculateRiskRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "What was rescored and which scores changed", body = RescoreReport),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+    )
+)]
+pub async fn recalculate_risk(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<RecalculateRiskRequest>,
+) -> Result<Json<RescoreReport>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    Ok(Json(
+        rescoring::recalculate_all(&state, !payload.all, payload.dry_run).await?,
+    ))
+}
+
+/// POST /api/v1/admin/scans/import - Import a scan archive exported from
+/// another instance with `GET /api/v1/scans/:id/archive`. The scan keeps its
+/// ID, its findings keep their review decisions.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/scans/import",
+    tag = "admin",
+    request_body(content = String, description = "Scan archive", content_type = "application/x-ndjson"),
+    security(("admin_token" = [])),
+    responses(
+        (status = 201, description = "What was imported", body = ArchiveImportReport),
+        (status = 400, description = "Invalid archive, or a scan that has not finished", body = ErrorResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+        (status = 409, description = "A scan with the ID already exists", body = ErrorResponse),
+    )
+)]
+pub async fn import_scan_archive(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<ArchiveImportReport>), AppError> {
+    authorize_admin(&state, &headers)?;
+
+    let content = std::str::from_utf8(&body)
+        .map_err(|_| AppError::Validation("The archive is not valid UTF-8".to_string()))?;
+    let archive = scan_archive::parse(content).map_err(AppError::Validation)?;
+    let report = scan_archive::import(&state, archive).await?;
+    audit::record(
+        &state,
+        &Actor::Admin,
+        "import",
+        "scan",
+        Some(&report.scan_id),
+        Some(&report.git_url),
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(report)))
+}
+
+/// POST /api/v1/admin/scans/:id/restore - Undo the deletion of a scan, with
+/// its findings, as long as retention or erasure has not removed it for good
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/scans/{id}/restore",
+    tag = "admin",
+    params(("id" = String, Path, description = "Scan ID")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, body = ScanResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+        (status = 404, description = "No deleted scan with the ID", body = ErrorResponse),
+    )
+)]
+pub async fn restore_scan(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<ScanResponse>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    let scan = Scan::restore(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("No deleted scan {}", id)))?;
+    audit::record(&state, &Actor::Admin, "restore", "scan", Some(&id), None).await?;
+    Ok(Json(ScanResponse::from(scan)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuditLogQueryParams {
+    /// Only entries about this scan or API key
+    pub target_id: Option<String>,
+    /// Only requests made with this API key
+    pub api_key_id: Option<String>,
+    /// Only this action, e.g. `delete`
+    pub action: Option<String>,
+    /// Only entries recorded at or after this time (`YYYY-MM-DD HH:MM:SS`, UTC)
+    pub since: Option<String>,
+}
+
+/// GET /api/v1/admin/audit-log - Who created, deleted, restored and re-ran
+/// scans and API keys, and with which API key
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit-log",
+    tag = "admin",
+    params(AuditLogQueryParams),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, body = AuditLogResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+    )
+)]
+pub async fn list_audit_log(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<AuditLogQueryParams>,
+) -> Result<Json<AuditLogResponse>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    let filter = AuditLogFilter {
+        target_id: params.target_id.as_deref(),
+        api_key_id: params.api_key_id.as_deref(),
+        action: params.action.as_deref(),
+        since: params.since.as_deref(),
+    };
+    let entries = AuditLogEntry::list(&state.db, &filter).await?;
+    Ok(Json(AuditLogResponse { entries }))
+}
+
+/// GET /api/v1/admin/telemetry - The usage report of the current period, as
+/// it would be sent to `TELEMETRY_URL`; available with telemetry disabled
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/telemetry",
+    tag = "admin",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, body = TelemetryReport),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+    )
+)]
+pub async fn preview_telemetry(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<TelemetryReport>, AppError> {
+    authorize_admin(&state, &headers)?;
+
+    Ok(Json(telemetry::preview(&state).await?))
+}
+
+fn validate_reason(reason: &str) -> Result<&str, AppError> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(AppError::Validation(
+            "A legal hold needs a reason".to_string(),
+        ));
+    }
+    Ok(reason)
+}
+
+fn hold_response(
+    target_type: &str,
+    target_id: String,
+    legal_hold_at: Option<String>,
+    legal_hold_reason: Option<String>,
+) -> LegalHoldResponse {
+    LegalHoldResponse {
+        target_type: target_type.to_string(),
+        target_id,
+        legal_hold_at,
+        legal_hold_reason,
+    }
+}
+
+/// Admin endpoints require `Authorization: Bearer <ADMIN_API_TOKEN>` and are
+/// disabled while no token is configured
+pub(crate) fn authorize_admin(state: &AppState, headers: &HeaderMap) -> Result<(), AppError> {
+    let Some(expected) = state.config.admin_api_token.as_deref() else {
+        return Err(AppError::Unauthorized);
+    };
+
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if provided != Some(expected) {
+        return Err(AppError::Unauthorized);
+    }
+    Ok(())
+}
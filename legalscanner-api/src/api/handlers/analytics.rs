@@ -0,0 +1,73 @@
+use crate::{
+    db::models::Scan,
+    error::AppError,
+    export::anonymized::{self, AnonymizedAnalytics},
+    AppState,
+};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AnonymizedAnalyticsQueryParams {
+    /// Only count scans completed on or after this date (YYYY-MM-DD)
+    since: Option<String>,
+}
+
+/// GET /api/v1/analytics/anonymized - License histogram and risk distribution
+/// across all completed scans, without paths or repository URLs
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/anonymized",
+    tag = "analytics",
+    params(AnonymizedAnalyticsQueryParams),
+    responses(
+        (status = 200, body = AnonymizedAnalytics),
+        (status = 404, description = "Anonymized analytics are disabled", body = ErrorResponse),
+    )
+)]
+pub async fn get_anonymized_analytics(
+    State(state): State<AppState>,
+    Query(params): Query<AnonymizedAnalyticsQueryParams>,
+) -> Result<Json<AnonymizedAnalytics>, AppError> {
+    if !state.config.anonymized_analytics_enabled {
+        return Err(AppError::NotFound(
+            "Anonymized analytics export is disabled".to_string(),
+        ));
+    }
+
+    let since = params
+        .since
+        .as_deref()
+        .map(|date| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .map_err(|_| {
+                    AppError::Validation(format!(
+                        "Invalid since date '{}' (expected YYYY-MM-DD)",
+                        date
+                    ))
+                })
+        })
+        .transpose()?;
+
+    let risk_levels = Scan::risk_level_counts(&state.db, since.as_deref()).await?;
+    let scan_ids: Vec<String> = Scan::completed_days(&state.db, since.as_deref(), true)
+        .await?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    let licenses = state.result_store.license_counts(&scan_ids).await?;
+
+    Ok(Json(anonymized::build_report(
+        state.config.analytics_instance_label.clone(),
+        since,
+        risk_levels,
+        licenses,
+    )))
+}
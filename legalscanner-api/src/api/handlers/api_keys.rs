@@ -1,5 +1,6 @@
 use crate::{
     api::models::{CreateApiKeyRequest, CreateApiKeyResponse},
+    audit::{self, Actor},
     db::models::ApiKey,
     error::AppError,
     utils::crypto,
@@ -7,15 +8,27 @@ use crate::{
 };
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     Json,
 };
 
 /// POST /api/v1/api-keys - Create a new API key
+#[utoipa::path(
+    post,
+    path = "/api/v1/api-keys",
+    tag = "api-keys",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "The key is only shown in this response", body = CreateApiKeyResponse),
+    )
+)]
 pub async fn create_api_key(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(payload): Json<CreateApiKeyRequest>,
 ) -> Result<(StatusCode, Json<CreateApiKeyResponse>), AppError> {
+    let actor = Actor::from_headers(&state, &headers).await?;
+
     // Generate a new API key
     let raw_key = crypto::generate_api_key();
 
@@ -25,6 +38,15 @@ pub async fn create_api_key(
 
     // Store in database
     let api_key = ApiKey::create(&state.db, payload.name, key_hash).await?;
+    audit::record(
+        &state,
+        &actor,
+        "create",
+        "api_key",
+        Some(&api_key.id),
+        Some(&api_key.name),
+    )
+    .await?;
 
     // Return the raw key (only time it will be shown)
     Ok((
@@ -40,6 +62,14 @@ pub async fn create_api_key(
 }
 
 /// GET /api/v1/api-keys - List all API keys
+#[utoipa::path(
+    get,
+    path = "/api/v1/api-keys",
+    tag = "api-keys",
+    responses(
+        (status = 200, body = Vec<ApiKey>),
+    )
+)]
 pub async fn list_api_keys(
     State(state): State<AppState>,
 ) -> Result<Json<Vec<ApiKey>>, AppError> {
@@ -48,16 +78,30 @@ pub async fn list_api_keys(
 }
 
 /// DELETE /api/v1/api-keys/:id - Delete an API key
+#[utoipa::path(
+    delete,
+    path = "/api/v1/api-keys/{id}",
+    tag = "api-keys",
+    params(("id" = String, Path, description = "API key ID")),
+    responses(
+        (status = 204, description = "API key deleted"),
+        (status = 404, description = "API key not found", body = ErrorResponse),
+    )
+)]
 pub async fn delete_api_key(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    headers: HeaderMap,
 ) -> Result<StatusCode, AppError> {
     // Check if key exists
-    let _ = ApiKey::find_by_id(&state.db, &id)
+    let api_key = ApiKey::find_by_id(&state.db, &id)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("API key {} not found", id)))?;
 
+    // Before deleting, a request made with the key itself is still attributed to it
+    let actor = Actor::from_headers(&state, &headers).await?;
     ApiKey::delete(&state.db, &id).await?;
+    audit::record(&state, &actor, "delete", "api_key", Some(&id), Some(&api_key.name)).await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
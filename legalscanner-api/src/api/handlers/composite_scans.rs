@@ -0,0 +1,334 @@
+use crate::{
+    api::{
+        handlers::{projects::find_project, risk::assess_results, sbom::export_response},
+        models::{CompositeScanResponse, CreateCompositeScanRequest, RiskAssessment, ScanResponse},
+    },
+    db::models::{CompositeScan, Credential, Project, Scan, ScanResult},
+    error::AppError,
+    export::{notice, pipeline::ExportPipeline, SbomFormat, SpdxVersion},
+    progress::record_event,
+    scan_diff::normalize_repository_url,
+    waiver, AppState,
+};
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, Response, StatusCode},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CompositeSbomQueryParams {
+    #[serde(default)]
+    format: SbomFormat,
+    #[serde(default)]
+    spdx_version: SpdxVersion,
+}
+
+/// POST /api/v1/projects/:id/composite-scans - Scan every repository of the
+/// project as one product. With `SCAN_LOCK_PER_REPOSITORY`, a running scan of
+/// a repository becomes the member scan instead of starting another.
+#[utoipa::path(
+    post,
+    path = "/api/v1/projects/{id}/composite-scans",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project ID")),
+    request_body = Option<CreateCompositeScanRequest>,
+    responses(
+        (status = 201, description = "Member scans started", body = CompositeScanResponse),
+        (status = 400, description = "Project without repositories", body = ErrorResponse),
+        (status = 404, description = "Project or credential not found", body = ErrorResponse),
+    )
+)]
+pub async fn create_composite_scan(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    payload: Option<Json<CreateCompositeScanRequest>>,
+) -> Result<(StatusCode, Json<CompositeScanResponse>), AppError> {
+    let (project, repositories) = find_project(&state, &id).await?;
+    if repositories.is_empty() {
+        return Err(AppError::Validation(format!(
+            "Project {} has no repositories",
+            id
+        )));
+    }
+    let request = payload.map(|Json(p)| p).unwrap_or_default();
+    let git_token = request.git_token;
+    let credential_id = request
+        .credential_id
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    if let Some(credential_id) = &credential_id {
+        if git_token.is_some() {
+            return Err(AppError::Validation(
+                "git_token cannot be combined with credential_id".to_string(),
+            ));
+        }
+        Credential::find_by_id(&state.db, credential_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Credential {} not found", credential_id)))?;
+    }
+
+    // Project URLs are normalized; clone with the spelling last scanned
+    let previous = Scan::find_by_repositories(&state.db, &repositories).await?;
+
+    let mut scans = Vec::with_capacity(repositories.len());
+    let mut started = Vec::new();
+    for repository in &repositories {
+        let git_url = previous
+            .iter()
+            .rev()
+            .find(|scan| normalize_repository_url(&scan.git_url) == *repository)
+            .map(|scan| scan.git_url.clone())
+            .unwrap_or_else(|| repository.clone());
+
+        let scan = if state.config.scan_lock_per_repository {
+            let (scan, created) =
+                Scan::create_unless_running(&state.db, git_url, None, git_token.clone(), None)
+                    .await?;
+            if created {
+                started.push(scan.id.clone());
+            }
+            scan
+        } else {
+            let scan = Scan::create(&state.db, git_url, git_token.clone(), None).await?;
+            started.push(scan.id.clone());
+            scan
+        };
+        scans.push(scan);
+    }
+
+    let scan_ids: Vec<String> = scans.iter().map(|s| s.id.clone()).collect();
+    let composite = CompositeScan::create(&state.db, &project.id, &scan_ids).await?;
+
+    let detail = format!("member of composite scan {}", composite.id);
+    for scan_id in started {
+        if let Some(credential_id) = &credential_id {
+            Scan::set_credential(&state.db, &scan_id, credential_id).await?;
+        }
+        let _ = record_event(&state, &scan_id, "created", None, Some(&detail)).await;
+        let _ = record_event(&state, &scan_id, "queued", None, None).await;
+
+        let state_clone = state.clone();
+        tokio::spawn(async move {
+            super::scan_job::execute_scan_job(scan_id, state_clone).await;
+        });
+    }
+
+    tracing::info!(
+        "Composite scan {} of project {} started {} scans",
+        composite.id,
+        project.name,
+        scans.len()
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(composite_response(composite, &project, scans, None)),
+    ))
+}
+
+/// GET /api/v1/projects/:id/composite-scans - Composite scans of a project,
+/// newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects/{id}/composite-scans",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = Vec<CompositeScanResponse>),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_composite_scans(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<CompositeScanResponse>>, AppError> {
+    let (project, _) = find_project(&state, &id).await?;
+
+    let mut responses = Vec::new();
+    for composite in CompositeScan::find_by_project_id(&state.db, &id).await? {
+        let scans = CompositeScan::scans(&state.db, &composite.id).await?;
+        responses.push(composite_response(composite, &project, scans, None));
+    }
+
+    Ok(Json(responses))
+}
+
+/// GET /api/v1/composite-scans/:id - Member scans and, once they all
+/// completed, the risk assessment of the product
+#[utoipa::path(
+    get,
+    path = "/api/v1/composite-scans/{id}",
+    tag = "projects",
+    params(("id" = String, Path, description = "Composite scan ID")),
+    responses(
+        (status = 200, body = CompositeScanResponse),
+        (status = 404, description = "Composite scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_composite_scan(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<CompositeScanResponse>, AppError> {
+    let (composite, project, scans) = find_composite_scan(&state, &id).await?;
+
+    let risk = if composite_status(&scans) == "completed" && !scans.is_empty() {
+        let mut results = Vec::new();
+        for (_, scan_results) in load_member_results(&state, &scans).await? {
+            results.extend(scan_results.into_iter().filter(|r| r.waiver_id.is_none()));
+        }
+        let mut risk = assess_results(&state.db, &results).await?;
+        risk.preliminary = scans.iter().any(|scan| scan.quick);
+        Some(risk)
+    } else {
+        None
+    };
+
+    Ok(Json(composite_response(composite, &project, scans, risk)))
+}
+
+/// GET /api/v1/composite-scans/:id/sbom - One SBOM of the shipped product,
+/// covering every member scan
+#[utoipa::path(
+    get,
+    path = "/api/v1/composite-scans/{id}/sbom",
+    tag = "projects",
+    params(("id" = String, Path, description = "Composite scan ID"), CompositeSbomQueryParams),
+    responses(
+        (status = 200, description = "SPDX document in the requested format", content_type = "application/json"),
+        (status = 400, description = "Member scans are not all completed", body = ErrorResponse),
+        (status = 404, description = "Composite scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_composite_scan_sbom(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<CompositeSbomQueryParams>,
+) -> Result<Response<Body>, AppError> {
+    let (composite, project, scans) = find_completed_composite_scan(&state, &id).await?;
+    let collected = load_member_results(&state, &scans).await?;
+
+    let output = ExportPipeline::from_config(&state.config).export_aggregate_sbom(
+        &project.name,
+        format!("https://legalscanner.io/spdx/composite/{}", composite.id),
+        &collected,
+        params.format,
+        params.spdx_version,
+    )?;
+
+    export_response(output)
+}
+
+/// GET /api/v1/composite-scans/:id/notice - Single attribution (NOTICE)
+/// document for the shipped product
+#[utoipa::path(
+    get,
+    path = "/api/v1/composite-scans/{id}/notice",
+    tag = "projects",
+    params(("id" = String, Path, description = "Composite scan ID")),
+    responses(
+        (status = 200, description = "NOTICE text", content_type = "text/plain"),
+        (status = 400, description = "Member scans are not all completed", body = ErrorResponse),
+        (status = 404, description = "Composite scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_composite_scan_notice(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Response<Body>, AppError> {
+    let (composite, project, scans) = find_completed_composite_scan(&state, &id).await?;
+    let collected = load_member_results(&state, &scans).await?;
+
+    let content = notice::build_product_notice(&project.name, &composite.id, &collected);
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}-NOTICE.txt\"", project.name),
+        )
+        .body(Body::from(content))
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))
+}
+
+async fn find_composite_scan(
+    state: &AppState,
+    id: &str,
+) -> Result<(CompositeScan, Project, Vec<Scan>), AppError> {
+    let composite = CompositeScan::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Composite scan {} not found", id)))?;
+    let (project, _) = find_project(state, &composite.project_id).await?;
+    let scans = CompositeScan::scans(&state.db, id).await?;
+    Ok((composite, project, scans))
+}
+
+/// Composite scan whose member scans all completed, for exports
+async fn find_completed_composite_scan(
+    state: &AppState,
+    id: &str,
+) -> Result<(CompositeScan, Project, Vec<Scan>), AppError> {
+    let (composite, project, scans) = find_composite_scan(state, id).await?;
+    if scans.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "Composite scan {} has no scans left",
+            id
+        )));
+    }
+    let status = composite_status(&scans);
+    if status != "completed" {
+        return Err(AppError::Validation(format!(
+            "Composite scan is not completed yet. Current status: {}",
+            status
+        )));
+    }
+    Ok((composite, project, scans))
+}
+
+/// Results of every member scan, with waived findings marked
+async fn load_member_results(
+    state: &AppState,
+    scans: &[Scan],
+) -> Result<Vec<(Scan, Vec<ScanResult>)>, AppError> {
+    let mut collected = Vec::with_capacity(scans.len());
+    for scan in scans {
+        let mut results = state.result_store.load_results(&scan.id).await?;
+        waiver::mark(&state.db, scan, &mut results).await?;
+        collected.push((scan.clone(), results));
+    }
+    Ok(collected)
+}
+
+fn composite_status(scans: &[Scan]) -> &'static str {
+    if scans
+        .iter()
+        .any(|scan| scan.status == "pending" || scan.status == "in_progress")
+    {
+        "in_progress"
+    } else if scans.iter().any(|scan| scan.status == "failed") {
+        "failed"
+    } else {
+        "completed"
+    }
+}
+
+fn composite_response(
+    composite: CompositeScan,
+    project: &Project,
+    scans: Vec<Scan>,
+    risk_assessment: Option<RiskAssessment>,
+) -> CompositeScanResponse {
+    CompositeScanResponse {
+        composite_scan_id: composite.id,
+        project_id: composite.project_id,
+        product: project.name.clone(),
+        status: composite_status(&scans).to_string(),
+        created_at: composite.created_at,
+        scans: scans.into_iter().map(ScanResponse::from).collect(),
+        risk_assessment,
+    }
+}
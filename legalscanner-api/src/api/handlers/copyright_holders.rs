@@ -0,0 +1,76 @@
+use crate::{
+    api::models::CopyrightHolderDetailResponse, copyright_holders::normalize_holder,
+    db::models::CopyrightHolder, error::AppError, AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+const MAX_SEARCH_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HolderSearchQueryParams {
+    /// Holder name or part of it; matched after the same normalization as
+    /// registered names
+    q: String,
+    limit: Option<i64>,
+}
+
+/// GET /api/v1/copyright-holders?q= - Search the cross-scan holder registry
+#[utoipa::path(
+    get,
+    path = "/api/v1/copyright-holders",
+    tag = "copyright-holders",
+    params(HolderSearchQueryParams),
+    responses(
+        (status = 200, body = Vec<CopyrightHolder>),
+        (status = 400, description = "Query contains no holder name", body = ErrorResponse),
+    )
+)]
+pub async fn search_copyright_holders(
+    State(state): State<AppState>,
+    Query(params): Query<HolderSearchQueryParams>,
+) -> Result<Json<Vec<CopyrightHolder>>, AppError> {
+    let query = normalize_holder(&params.q).ok_or_else(|| {
+        AppError::Validation("Search query must contain a holder name".to_string())
+    })?;
+
+    let limit = params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    if !(1..=MAX_SEARCH_LIMIT).contains(&limit) {
+        return Err(AppError::Validation(format!(
+            "limit must be between 1 and {}",
+            MAX_SEARCH_LIMIT
+        )));
+    }
+
+    let holders = CopyrightHolder::search(&state.db, &query, limit).await?;
+    Ok(Json(holders))
+}
+
+/// GET /api/v1/copyright-holders/:id - A holder and the repositories it appears in
+#[utoipa::path(
+    get,
+    path = "/api/v1/copyright-holders/{id}",
+    tag = "copyright-holders",
+    params(("id" = i64, Path, description = "Copyright holder ID")),
+    responses(
+        (status = 200, body = CopyrightHolderDetailResponse),
+        (status = 404, description = "Copyright holder not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_copyright_holder(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<CopyrightHolderDetailResponse>, AppError> {
+    let holder = CopyrightHolder::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Copyright holder {} not found", id)))?;
+    let projects = CopyrightHolder::projects(&state.db, id).await?;
+
+    Ok(Json(CopyrightHolderDetailResponse { holder, projects }))
+}
@@ -0,0 +1,99 @@
+use crate::{
+    api::models::CreateCredentialRequest, credentials, db::models::Credential, error::AppError,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+
+use super::admin::authorize_admin;
+
+/// POST /api/v1/credentials - Register a named git credential that scans
+/// can reference by ID. The secret is encrypted and never returned.
+#[utoipa::path(
+    post,
+    path = "/api/v1/credentials",
+    tag = "credentials",
+    request_body = CreateCredentialRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 201, body = Credential),
+        (status = 400, description = "Invalid credential, or no encryption key configured", body = ErrorResponse),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+        (status = 409, description = "Name already taken", body = ErrorResponse),
+    )
+)]
+pub async fn create_credential(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateCredentialRequest>,
+) -> Result<(StatusCode, Json<Credential>), AppError> {
+    authorize_admin(&state, &headers)?;
+    let credential = credentials::register(&state, payload).await?;
+    Ok((StatusCode::CREATED, Json(credential)))
+}
+
+/// GET /api/v1/credentials - Registered credentials, without their secrets
+#[utoipa::path(
+    get,
+    path = "/api/v1/credentials",
+    tag = "credentials",
+    responses(
+        (status = 200, body = Vec<Credential>),
+    )
+)]
+pub async fn list_credentials(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Credential>>, AppError> {
+    Ok(Json(Credential::list(&state.db).await?))
+}
+
+/// GET /api/v1/credentials/:id - A registered credential, without its secret
+#[utoipa::path(
+    get,
+    path = "/api/v1/credentials/{id}",
+    tag = "credentials",
+    params(("id" = String, Path, description = "Credential ID")),
+    responses(
+        (status = 200, body = Credential),
+        (status = 404, description = "Credential not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_credential(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Credential>, AppError> {
+    let credential = Credential::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Credential {} not found", id)))?;
+    Ok(Json(credential))
+}
+
+/// DELETE /api/v1/credentials/:id - Delete a credential. Scans that
+/// reference it can no longer be re-run.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/credentials/{id}",
+    tag = "credentials",
+    params(("id" = String, Path, description = "Credential ID")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 204, description = "Credential deleted"),
+        (status = 401, description = "Missing or wrong admin token", body = ErrorResponse),
+        (status = 404, description = "Credential not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_credential(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, AppError> {
+    authorize_admin(&state, &headers)?;
+    if !Credential::delete(&state.db, &id).await? {
+        return Err(AppError::NotFound(format!("Credential {} not found", id)));
+    }
+    tracing::info!("Credential {} deleted", id);
+    Ok(StatusCode::NO_CONTENT)
+}
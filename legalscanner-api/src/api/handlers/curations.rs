@@ -0,0 +1,140 @@
+use crate::{
+    api::models::{
+        ExportFossologyDecisionsRequest, ImportFossologyDecisionsRequest, LicenseCurationsResponse,
+    },
+    curation::{export_fossology, import_fossology, CurationExportReport, CurationImportReport},
+    db::models::LicenseCuration,
+    error::AppError,
+    scan_diff::normalize_repository_url,
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LicenseCurationsQueryParams {
+    /// Repository URL, compared regardless of case, a trailing slash or `.git`
+    git_url: String,
+}
+
+/// POST /api/v1/curations/import/fossology - Import the clearing decisions
+/// of a Fossology upload as license curations of a repository
+#[utoipa::path(
+    post,
+    path = "/api/v1/curations/import/fossology",
+    tag = "curations",
+    request_body = ImportFossologyDecisionsRequest,
+    responses(
+        (status = 200, description = "What was imported", body = CurationImportReport),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Fossology upload not found", body = ErrorResponse),
+    )
+)]
+pub async fn import_fossology_decisions(
+    State(state): State<AppState>,
+    Json(payload): Json<ImportFossologyDecisionsRequest>,
+) -> Result<Json<CurationImportReport>, AppError> {
+    validate_fossology_upload(&payload.git_url, payload.upload_id)?;
+
+    let report = import_fossology(
+        &state.db,
+        &state.config,
+        &payload.git_url,
+        payload.upload_id,
+        payload.path_prefix.as_deref(),
+    )
+    .await?;
+    Ok(Json(report))
+}
+
+/// POST /api/v1/curations/export/fossology - Record the license curations
+/// of a repository as clearing decisions on a Fossology upload
+#[utoipa::path(
+    post,
+    path = "/api/v1/curations/export/fossology",
+    tag = "curations",
+    request_body = ExportFossologyDecisionsRequest,
+    responses(
+        (status = 200, description = "What was exported", body = CurationExportReport),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Fossology upload not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_fossology_decisions(
+    State(state): State<AppState>,
+    Json(payload): Json<ExportFossologyDecisionsRequest>,
+) -> Result<Json<CurationExportReport>, AppError> {
+    validate_fossology_upload(&payload.git_url, payload.upload_id)?;
+
+    let report = export_fossology(
+        &state.db,
+        &state.config,
+        &payload.git_url,
+        payload.upload_id,
+        payload.path_prefix.as_deref(),
+    )
+    .await?;
+    Ok(Json(report))
+}
+
+fn validate_fossology_upload(git_url: &str, upload_id: i32) -> Result<(), AppError> {
+    if git_url.trim().is_empty() {
+        return Err(AppError::Validation("git_url is required".to_string()));
+    }
+    if upload_id <= 0 {
+        return Err(AppError::Validation(
+            "upload_id must be a Fossology upload ID".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// GET /api/v1/curations - License curations of a repository
+#[utoipa::path(
+    get,
+    path = "/api/v1/curations",
+    tag = "curations",
+    params(LicenseCurationsQueryParams),
+    responses((status = 200, body = LicenseCurationsResponse))
+)]
+pub async fn list_license_curations(
+    State(state): State<AppState>,
+    Query(params): Query<LicenseCurationsQueryParams>,
+) -> Result<Json<LicenseCurationsResponse>, AppError> {
+    let repository = normalize_repository_url(&params.git_url);
+    let curations = LicenseCuration::find_by_repository(&state.db, &repository).await?;
+
+    Ok(Json(LicenseCurationsResponse {
+        repository,
+        curations,
+    }))
+}
+
+/// DELETE /api/v1/curations/:id - Delete a curation; later scans report the
+/// file's licenses as detected again
+#[utoipa::path(
+    delete,
+    path = "/api/v1/curations/{id}",
+    tag = "curations",
+    params(("id" = String, Path, description = "Curation ID")),
+    responses(
+        (status = 204, description = "Curation deleted"),
+        (status = 404, description = "Curation not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_license_curation(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if !LicenseCuration::delete(&state.db, &id).await? {
+        return Err(AppError::NotFound(format!("Curation {} not found", id)));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,170 @@
+use crate::{
+    api::models::{
+        CustomFieldResponse, CustomFieldsResponse, ScanCustomFieldsResponse,
+        SetScanCustomFieldsRequest, UpsertCustomFieldRequest,
+    },
+    custom_fields::{self, FIELD_TYPES},
+    db::models::{CustomField, Scan},
+    error::AppError,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+/// GET /api/v1/custom-fields - List the custom field definitions
+#[utoipa::path(
+    get,
+    path = "/api/v1/custom-fields",
+    tag = "custom-fields",
+    responses((status = 200, body = CustomFieldsResponse))
+)]
+pub async fn list_custom_fields(
+    State(state): State<AppState>,
+) -> Result<Json<CustomFieldsResponse>, AppError> {
+    let fields = CustomField::list(&state.db).await?;
+
+    Ok(Json(CustomFieldsResponse {
+        fields: fields.into_iter().map(CustomFieldResponse::from).collect(),
+        types: FIELD_TYPES.iter().map(|t| t.to_string()).collect(),
+    }))
+}
+
+/// PUT /api/v1/custom-fields/:key - Define a custom field or replace its
+/// definition. Values already set on scans are not revalidated.
+#[utoipa::path(
+    put,
+    path = "/api/v1/custom-fields/{key}",
+    tag = "custom-fields",
+    params(("key" = String, Path, description = "Field key, e.g. contract_id")),
+    request_body = UpsertCustomFieldRequest,
+    responses(
+        (status = 200, body = CustomFieldResponse),
+        (status = 400, description = "Invalid key, type, options or pattern", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_custom_field(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Json(payload): Json<UpsertCustomFieldRequest>,
+) -> Result<Json<CustomFieldResponse>, AppError> {
+    let field_type = payload.field_type.trim().to_lowercase();
+    let options: Vec<String> = payload
+        .options
+        .iter()
+        .map(|o| o.trim().to_string())
+        .collect();
+    let pattern = payload.pattern.as_deref().filter(|p| !p.is_empty());
+    custom_fields::validate_definition(&key, &field_type, &options, pattern)
+        .map_err(AppError::Validation)?;
+
+    let label = payload
+        .label
+        .as_deref()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .unwrap_or(&key);
+
+    let field = CustomField::upsert(
+        &state.db,
+        &key,
+        label,
+        &field_type,
+        &options,
+        pattern,
+        payload.required,
+    )
+    .await?;
+    Ok(Json(CustomFieldResponse::from(field)))
+}
+
+/// DELETE /api/v1/custom-fields/:key - Delete a custom field and its values
+/// on all scans
+#[utoipa::path(
+    delete,
+    path = "/api/v1/custom-fields/{key}",
+    tag = "custom-fields",
+    params(("key" = String, Path, description = "Field key")),
+    responses(
+        (status = 204, description = "Field deleted"),
+        (status = 404, description = "Field not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_custom_field(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if !CustomField::delete(&state.db, &key).await? {
+        return Err(AppError::NotFound(format!(
+            "Custom field {} not found",
+            key
+        )));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/v1/scans/:id/custom-fields - Custom field values of a scan
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/custom-fields",
+    tag = "custom-fields",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanCustomFieldsResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_custom_fields(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanCustomFieldsResponse>, AppError> {
+    find_scan(&state, &id).await?;
+    let values = custom_fields::load(&state.db, &id).await?;
+
+    Ok(Json(ScanCustomFieldsResponse {
+        scan_id: id,
+        values,
+    }))
+}
+
+/// PUT /api/v1/scans/:id/custom-fields - Set or remove custom field values
+/// of a scan
+#[utoipa::path(
+    put,
+    path = "/api/v1/scans/{id}/custom-fields",
+    tag = "custom-fields",
+    params(("id" = String, Path, description = "Scan ID")),
+    request_body = SetScanCustomFieldsRequest,
+    responses(
+        (status = 200, body = ScanCustomFieldsResponse),
+        (status = 400, description = "Unknown field or invalid value", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn set_scan_custom_fields(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<SetScanCustomFieldsRequest>,
+) -> Result<Json<ScanCustomFieldsResponse>, AppError> {
+    find_scan(&state, &id).await?;
+
+    let fields = CustomField::list(&state.db).await?;
+    let values = custom_fields::validate_values(&fields, &payload.values, false)
+        .map_err(AppError::Validation)?;
+    custom_fields::apply(&state.db, &id, &values).await?;
+
+    let values = custom_fields::load(&state.db, &id).await?;
+    Ok(Json(ScanCustomFieldsResponse {
+        scan_id: id,
+        values,
+    }))
+}
+
+async fn find_scan(state: &AppState, id: &str) -> Result<Scan, AppError> {
+    Scan::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))
+}
@@ -0,0 +1,77 @@
+use crate::{
+    api::models::{RiskScoreDelta, ScanDiffResponse},
+    db::models::Scan,
+    error::AppError,
+    scan_diff::{diff_results, same_repository},
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+/// GET /api/v1/scans/:id/diff/:other_id - What changed between two scans of
+/// the same repository. `:id` is the baseline, usually the older scan.
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/diff/{other_id}",
+    tag = "results",
+    params(("id" = String, Path, description = "Baseline scan ID"), ("other_id" = String, Path, description = "Scan compared against the baseline")),
+    responses(
+        (status = 200, body = ScanDiffResponse),
+        (status = 400, description = "Scans are not completed or of different repositories", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_diff(
+    State(state): State<AppState>,
+    Path((id, other_id)): Path<(String, String)>,
+) -> Result<Json<ScanDiffResponse>, AppError> {
+    let scan = completed_scan(&state, &id).await?;
+    let other = completed_scan(&state, &other_id).await?;
+
+    if !same_repository(&scan.git_url, &other.git_url) {
+        return Err(AppError::Validation(format!(
+            "Scans are of different repositories: {} and {}",
+            scan.git_url, other.git_url
+        )));
+    }
+
+    let results = state.result_store.load_results(&scan.id).await?;
+    let other_results = state.result_store.load_results(&other.id).await?;
+    let diff = diff_results(&scan.id, &results, &other.id, &other_results);
+
+    let risk = RiskScoreDelta {
+        score: scan.risk_score,
+        other_score: other.risk_score,
+        delta: scan
+            .risk_score
+            .zip(other.risk_score)
+            .map(|(score, other_score)| other_score - score),
+        level: scan.risk_level,
+        other_level: other.risk_level,
+    };
+
+    Ok(Json(ScanDiffResponse {
+        scan_id: scan.id,
+        other_scan_id: other.id,
+        git_url: other.git_url,
+        risk,
+        diff,
+    }))
+}
+
+async fn completed_scan(state: &AppState, id: &str) -> Result<Scan, AppError> {
+    let scan = Scan::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    if scan.status != "completed" {
+        return Err(AppError::Validation(format!(
+            "Scan {} is not completed yet. Current status: {}",
+            id, scan.status
+        )));
+    }
+
+    Ok(scan)
+}
@@ -0,0 +1,69 @@
+use crate::{
+    api::models::{DisputedFile, DisputedLicense, LicenseDisputesResponse},
+    db::models::Scan,
+    error::AppError,
+    scanner::LicenseAgreement,
+    storage::ResultQuery,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::collections::BTreeMap;
+
+/// GET /api/v1/scans/:id/results/disputed - Files whose license findings
+/// the license scanners disagree on, for manual review
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/results/disputed",
+    tag = "results",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = LicenseDisputesResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_license_disputes(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<LicenseDisputesResponse>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let query = ResultQuery {
+        result_type: Some("license".to_string()),
+        ..Default::default()
+    };
+    let results = state.result_store.query_results(&id, &query).await?;
+
+    let mut files: BTreeMap<String, Vec<DisputedLicense>> = BTreeMap::new();
+    for result in results {
+        files
+            .entry(result.file_path)
+            .or_default()
+            .push(DisputedLicense {
+                license: result.license_name,
+                spdx_id: result.license_spdx_id,
+                confidence: result.confidence,
+                provenance: result.merge_provenance,
+                agreement: result.license_agreement,
+            });
+    }
+
+    let disputed = Some(LicenseAgreement::Disputed.as_str());
+    let files = files
+        .into_iter()
+        .filter(|(_, licenses)| licenses.iter().any(|l| l.agreement.as_deref() == disputed))
+        .map(|(file_path, licenses)| DisputedFile {
+            file_path,
+            licenses,
+        })
+        .collect();
+
+    Ok(Json(LicenseDisputesResponse {
+        scan_id: scan.id,
+        files,
+    }))
+}
@@ -0,0 +1,104 @@
+use crate::{
+    db::models::{Scan, ScanEvent},
+    error::AppError,
+    progress::ProgressUpdate,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures_util::stream::{self, Stream, StreamExt};
+use std::convert::Infallible;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+/// GET /api/v1/scans/:id/events - Server-Sent Events stream of a scan's
+/// progress. Timeline events recorded so far are replayed first, then live
+/// events and scanner progress messages follow until the scan completes or
+/// fails, at which point the stream ends.
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/events",
+    tag = "scans",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, description = "Server-Sent Events named after the timeline event type, or `progress`", content_type = "text/event-stream"),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn stream_scan_events(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    // Subscribe before reading the timeline so no event falls in between
+    let receiver = state.progress.subscribe();
+    let recorded: Vec<ProgressUpdate> = ScanEvent::find_by_scan_id(&state.db, &id)
+        .await?
+        .iter()
+        .map(ProgressUpdate::from_event)
+        .collect();
+
+    // Scans that finished before the timeline existed have no events at all
+    let finished = recorded.iter().any(ProgressUpdate::is_terminal)
+        || (recorded.is_empty() && (scan.status == "completed" || scan.status == "failed"));
+    let last_event_id = recorded.iter().filter_map(|u| u.event_id).max();
+
+    let live = live_updates((!finished).then_some(receiver), id, last_event_id);
+    let events = stream::iter(recorded)
+        .chain(live)
+        .map(|update| Ok(sse_event(&update)));
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Updates for `scan_id` from the broadcast channel, skipping timeline events
+/// already replayed and ending after the terminal event
+fn live_updates(
+    receiver: Option<Receiver<ProgressUpdate>>,
+    scan_id: String,
+    last_event_id: Option<i64>,
+) -> impl Stream<Item = ProgressUpdate> {
+    stream::unfold(receiver, move |receiver| {
+        let scan_id = scan_id.clone();
+        async move {
+            let mut receiver = receiver?;
+            loop {
+                match receiver.recv().await {
+                    Ok(update) if update.scan_id != scan_id => continue,
+                    Ok(update) if update.event_id.is_some() && update.event_id <= last_event_id => {
+                        continue
+                    }
+                    Ok(update) => {
+                        let next = (!update.is_terminal()).then_some(receiver);
+                        return Some((update, next));
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            "Event stream for scan {} skipped {} updates",
+                            scan_id,
+                            skipped
+                        );
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }
+    })
+}
+
+/// SSE event named after the timeline event type (or `progress`), with the
+/// update as JSON data. Timeline events carry their ID.
+fn sse_event(update: &ProgressUpdate) -> Event {
+    let event = Event::default().event(&update.event);
+    let event = match update.event_id {
+        Some(id) => event.id(id.to_string()),
+        None => event,
+    };
+    event
+        .json_data(update)
+        .unwrap_or_else(|_| Event::default().comment("unserializable update"))
+}
@@ -0,0 +1,356 @@
+use crate::{
+    api::models::{RiskAssessment, RiskFactor},
+    custom_fields,
+    db::models::{Scan, ScanHistoryFinding, ScanRepositoryMetadata, ScanScreening},
+    error::AppError,
+    export::{analytics, export_control, markdown, notice, sarif, spreadsheet, viewer, ResultsExportFormat},
+    remediation, scan_archive, waiver, AppState,
+};
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, Response, StatusCode},
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ResultsExportQueryParams {
+    #[serde(default)]
+    format: ResultsExportFormat,
+}
+
+/// GET /api/v1/scans/:id/results/export - Export raw findings for analytics ingestion
+/// GET /api/v1/scans/:id/export - Same findings as CSV or Excel for legal review
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/export",
+    tag = "exports",
+    params(("id" = String, Path, description = "Scan ID"), ResultsExportQueryParams),
+    responses(
+        (status = 200, description = "Findings as Parquet, CSV or Excel; also served at `/api/v1/scans/{id}/results/export`", content_type = "application/octet-stream"),
+        (status = 400, description = "Scan is not completed yet", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_scan_results(
+    State(state): State<AppState>,
+    Path(scan_id): Path<String>,
+    Query(params): Query<ResultsExportQueryParams>,
+) -> Result<Response<Body>, AppError> {
+    let (scan, repo_name) = completed_scan(&state, &scan_id).await?;
+
+    // Fetch all scan results, flagging waived findings
+    let mut results = state.result_store.load_results(&scan_id).await?;
+    waiver::mark(&state.db, &scan, &mut results).await?;
+
+    // Serialize to requested format
+    let body = match params.format {
+        // Parquet files of large scans are streamed as they are encoded
+        ResultsExportFormat::Parquet => Body::from_stream(analytics::stream_findings_parquet(results)),
+        ResultsExportFormat::Csv => Body::from(spreadsheet::write_findings_csv(&results)?),
+        ResultsExportFormat::Xlsx => Body::from(
+            tokio::task::spawn_blocking(move || spreadsheet::write_findings_xlsx(&results))
+                .await
+                .map_err(|e| AppError::Internal(format!("Excel export task failed: {}", e)))??,
+        ),
+    };
+
+    let filename = format!("{}-results.{}", repo_name, params.format.file_extension());
+
+    // Build response with proper headers
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, params.format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(body)
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))?;
+
+    Ok(response)
+}
+
+/// GET /api/v1/scans/:id/sarif - SARIF 2.1.0 report of ECC and malware findings
+/// for GitHub Code Scanning and other SARIF-aware dashboards
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/sarif",
+    tag = "exports",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, description = "SARIF 2.1.0 log", content_type = "application/sarif+json"),
+        (status = 400, description = "Scan is not completed yet", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_scan_sarif(
+    State(state): State<AppState>,
+    Path(scan_id): Path<String>,
+) -> Result<Response<Body>, AppError> {
+    let (scan, repo_name) = completed_scan(&state, &scan_id).await?;
+
+    let mut results = state.result_store.load_results(&scan_id).await?;
+    waiver::mark(&state.db, &scan, &mut results).await?;
+    let report = sarif::build_sarif_report(&scan, &results);
+
+    let content = serde_json::to_string_pretty(&report)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize SARIF report: {}", e)))?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/sarif+json")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.sarif\"", repo_name),
+        )
+        .body(Body::from(content))
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))
+}
+
+/// GET /api/v1/scans/:id/notice - Third-party attribution (NOTICE) file assembled
+/// from detected licenses and copyright holders, for shipping with distributions
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/notice",
+    tag = "exports",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, description = "NOTICE text", content_type = "text/plain"),
+        (status = 400, description = "Scan is not completed yet", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_scan_notice(
+    State(state): State<AppState>,
+    Path(scan_id): Path<String>,
+) -> Result<Response<Body>, AppError> {
+    let (scan, repo_name) = completed_scan(&state, &scan_id).await?;
+
+    let results = state.result_store.load_results(&scan_id).await?;
+    let content = notice::build_notice(&scan, &results);
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}-NOTICE.txt\"", repo_name),
+        )
+        .body(Body::from(content))
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))
+}
+
+/// GET /api/v1/scans/:id/markdown - Markdown summary (license breakdown, top risk
+/// factors, per-directory counts) for pasting into PRs, wikis and checklists
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/markdown",
+    tag = "exports",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, description = "Markdown summary", content_type = "text/markdown"),
+        (status = 400, description = "Scan is not completed yet", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_scan_markdown(
+    State(state): State<AppState>,
+    Path(scan_id): Path<String>,
+) -> Result<Response<Body>, AppError> {
+    let (scan, repo_name) = completed_scan(&state, &scan_id).await?;
+
+    let results = state.result_store.load_results(&scan_id).await?;
+
+    let risk = stored_risk(&scan);
+
+    let history = ScanHistoryFinding::find_by_scan_id(&state.db, &scan_id).await?;
+    let repository = ScanRepositoryMetadata::find_by_scan_id(&state.db, &scan_id).await?;
+    let custom_fields = custom_fields::report_fields(&state.db, &scan_id).await?;
+
+    let content = markdown::build_markdown_summary(
+        &scan,
+        &results,
+        risk.as_ref(),
+        &history,
+        repository.as_ref(),
+        &custom_fields,
+    );
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/markdown; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("inline; filename=\"{}-license-summary.md\"", repo_name),
+        )
+        .body(Body::from(content))
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))
+}
+
+/// GET /api/v1/scans/:id/export-control/report - Export control report for
+/// trade-compliance filings: crypto functionality, source references,
+/// classification rationale and reviewer sign-off blocks
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/export-control/report",
+    tag = "exports",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, description = "Export control report", content_type = "text/markdown"),
+        (status = 400, description = "Scan is not completed yet", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_scan_export_control_report(
+    State(state): State<AppState>,
+    Path(scan_id): Path<String>,
+) -> Result<Response<Body>, AppError> {
+    let (scan, repo_name) = completed_scan(&state, &scan_id).await?;
+
+    let mut results = state.result_store.load_results(&scan_id).await?;
+    waiver::mark(&state.db, &scan, &mut results).await?;
+
+    let screening = ScanScreening::find_by_scan_id(&state.db, &scan_id).await?;
+    let custom_fields = custom_fields::report_fields(&state.db, &scan_id).await?;
+    let generated_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let content = export_control::build_export_control_report(
+        &scan,
+        &results,
+        screening.as_ref(),
+        &custom_fields,
+        &generated_at,
+    );
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/markdown; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}-export-control.md\"", repo_name),
+        )
+        .body(Body::from(content))
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))
+}
+
+/// GET /api/v1/scans/:id/viewer - Single HTML file with the report and a small
+/// viewer (summary, license breakdown, filterable findings) that works offline
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/viewer",
+    tag = "exports",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, description = "Self-contained HTML report", content_type = "text/html"),
+        (status = 400, description = "Scan is not completed yet", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_scan_viewer(
+    State(state): State<AppState>,
+    Path(scan_id): Path<String>,
+) -> Result<Response<Body>, AppError> {
+    let (scan, repo_name) = completed_scan(&state, &scan_id).await?;
+
+    let mut results = state.result_store.load_results(&scan_id).await?;
+    waiver::mark(&state.db, &scan, &mut results).await?;
+
+    let custom_fields = custom_fields::report_fields(&state.db, &scan_id).await?;
+
+    let content =
+        viewer::build_viewer(&scan, &results, stored_risk(&scan).as_ref(), &custom_fields);
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}-report.html\"", repo_name),
+        )
+        .body(Body::from(content))
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))
+}
+
+/// The scan `scan_id` if it is completed, with the name of its repository
+/// for the file name of the export
+async fn completed_scan(state: &AppState, scan_id: &str) -> Result<(Scan, String), AppError> {
+    let scan = Scan::find_by_id(&state.db, scan_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan not found: {}", scan_id)))?;
+
+    if scan.status != "completed" {
+        return Err(AppError::Validation(format!(
+            "Scan is not completed yet. Current status: {}",
+            scan.status
+        )));
+    }
+
+    let repo_name = scan
+        .git_url
+        .trim_end_matches(".git")
+        .rsplit('/')
+        .next()
+        .unwrap_or("repository")
+        .to_string();
+    Ok((scan, repo_name))
+}
+
+/// Risk assessment stored on a completed scan, if any
+fn stored_risk(scan: &Scan) -> Option<RiskAssessment> {
+    match (scan.risk_score, &scan.risk_level, &scan.risk_factors) {
+        (Some(score), Some(level), Some(factors_json)) => {
+            serde_json::from_str::<Vec<RiskFactor>>(factors_json)
+                .map(|factors| RiskAssessment {
+                    score,
+                    level: level.clone(),
+                    remediation: remediation::for_factors(&factors),
+                    factors,
+                    preliminary: scan.quick,
+                    algorithm_version: scan.risk_algorithm_version,
+                    config_version: scan.risk_config_version.clone(),
+                    assessed_at: scan.risk_assessed_at.clone(),
+                })
+                .map_err(|e| tracing::error!("Failed to parse risk factors: {}", e))
+                .ok()
+        }
+        _ => None,
+    }
+}
+
+/// GET /api/v1/scans/:id/archive - The scan with its findings, review
+/// comments and timeline as a portable archive, for importing into another
+/// instance with `POST /api/v1/admin/scans/import`
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/archive",
+    tag = "exports",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, description = "Newline-delimited JSON, a `scan` record followed by `result`, `comment` and `event` records", content_type = "application/x-ndjson"),
+        (status = 400, description = "Scan is not finished yet", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_scan_archive(
+    State(state): State<AppState>,
+    Path(scan_id): Path<String>,
+) -> Result<Response<Body>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &scan_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan not found: {}", scan_id)))?;
+
+    let content = scan_archive::write(scan_archive::export(&state, &scan).await?)?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, scan_archive::CONTENT_TYPE)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"scan-{}.ndjson\"", scan.id),
+        )
+        .body(Body::from(content))
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))
+}
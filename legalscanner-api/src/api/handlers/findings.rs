@@ -0,0 +1,274 @@
+use crate::{
+    api::handlers::{projects::find_project, scans::parse_timestamp, scans::RESULT_TYPES},
+    api::models::{ProjectFindingsReport, TrackedFindingsResponse, TransitionFindingRequest},
+    db::models::{TrackedFinding, TrackedFindingFilter},
+    error::AppError,
+    lifecycle::{self, STATES},
+    scan_diff::normalize_repository_url,
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+/// Default reporting period of the project findings report, about a quarter
+const DEFAULT_REPORT_DAYS: i64 = 90;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FindingsQueryParams {
+    /// Only findings of this repository, compared regardless of case, a
+    /// trailing slash or `.git`
+    git_url: Option<String>,
+    /// Only findings of the repositories of this project
+    project_id: Option<String>,
+    /// open, triaged or resolved
+    state: Option<String>,
+    result_type: Option<String>,
+    /// License, ECC check, malware signature or package, case-insensitive
+    value: Option<String>,
+    /// Only findings opened before this date (YYYY-MM-DD or RFC 3339)
+    opened_before: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FindingsReportQueryParams {
+    /// Start of the reporting period (YYYY-MM-DD or RFC 3339), 90 days ago
+    /// by default
+    since: Option<String>,
+}
+
+/// GET /api/v1/findings - Findings tracked across scans, oldest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/findings",
+    tag = "findings",
+    params(FindingsQueryParams),
+    responses(
+        (status = 200, body = TrackedFindingsResponse),
+        (status = 400, description = "Invalid filter", body = ErrorResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_findings(
+    State(state): State<AppState>,
+    Query(params): Query<FindingsQueryParams>,
+) -> Result<Json<TrackedFindingsResponse>, AppError> {
+    let non_empty = |v: Option<String>| v.filter(|v| !v.trim().is_empty());
+
+    let mut repositories = None;
+    if let Some(project_id) = non_empty(params.project_id) {
+        let (_, project_repositories) = find_project(&state, &project_id).await?;
+        repositories = Some(project_repositories);
+    }
+    if let Some(git_url) = non_empty(params.git_url) {
+        let repository = normalize_repository_url(&git_url);
+        repositories = Some(match repositories {
+            Some(project) => project.into_iter().filter(|r| *r == repository).collect(),
+            None => vec![repository],
+        });
+    }
+
+    let finding_state = non_empty(params.state).map(|s| s.to_lowercase());
+    if let Some(finding_state) = &finding_state {
+        validate_state(finding_state)?;
+    }
+    let result_type = non_empty(params.result_type);
+    if let Some(result_type) = &result_type {
+        if !RESULT_TYPES.contains(&result_type.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Invalid result_type '{}'. Must be one of {}",
+                result_type,
+                RESULT_TYPES.join(", ")
+            )));
+        }
+    }
+    let opened_before = non_empty(params.opened_before)
+        .map(|value| parse_timestamp("opened_before", &value))
+        .transpose()?;
+
+    let filter = TrackedFindingFilter {
+        repositories,
+        state: finding_state,
+        result_type,
+        value: non_empty(params.value),
+        opened_before,
+    };
+    let findings = TrackedFinding::list(&state.db, &filter).await?;
+
+    Ok(Json(TrackedFindingsResponse { findings }))
+}
+
+/// GET /api/v1/findings/:id - A tracked finding
+#[utoipa::path(
+    get,
+    path = "/api/v1/findings/{id}",
+    tag = "findings",
+    params(("id" = String, Path, description = "Finding ID")),
+    responses(
+        (status = 200, body = TrackedFinding),
+        (status = 404, description = "Finding not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_finding(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<TrackedFinding>, AppError> {
+    Ok(Json(find_finding(&state, &id).await?))
+}
+
+/// POST /api/v1/findings/:id/triage - Acknowledge an open finding as being
+/// worked on
+#[utoipa::path(
+    post,
+    path = "/api/v1/findings/{id}/triage",
+    tag = "findings",
+    params(("id" = String, Path, description = "Finding ID")),
+    request_body = Option<TransitionFindingRequest>,
+    responses(
+        (status = 200, body = TrackedFinding),
+        (status = 404, description = "Finding not found", body = ErrorResponse),
+        (status = 409, description = "Finding is not open", body = ErrorResponse),
+    )
+)]
+pub async fn triage_finding(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    payload: Option<Json<TransitionFindingRequest>>,
+) -> Result<Json<TrackedFinding>, AppError> {
+    transition(&state, &id, "triaged", payload).await
+}
+
+/// POST /api/v1/findings/:id/resolve - Resolve an open or triaged finding.
+/// It is reopened if a later scan still finds it.
+#[utoipa::path(
+    post,
+    path = "/api/v1/findings/{id}/resolve",
+    tag = "findings",
+    params(("id" = String, Path, description = "Finding ID")),
+    request_body = Option<TransitionFindingRequest>,
+    responses(
+        (status = 200, body = TrackedFinding),
+        (status = 404, description = "Finding not found", body = ErrorResponse),
+        (status = 409, description = "Finding is already resolved", body = ErrorResponse),
+    )
+)]
+pub async fn resolve_finding(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    payload: Option<Json<TransitionFindingRequest>>,
+) -> Result<Json<TrackedFinding>, AppError> {
+    transition(&state, &id, "resolved", payload).await
+}
+
+/// POST /api/v1/findings/:id/reopen - Reopen a triaged or resolved finding
+#[utoipa::path(
+    post,
+    path = "/api/v1/findings/{id}/reopen",
+    tag = "findings",
+    params(("id" = String, Path, description = "Finding ID")),
+    request_body = Option<TransitionFindingRequest>,
+    responses(
+        (status = 200, body = TrackedFinding),
+        (status = 404, description = "Finding not found", body = ErrorResponse),
+        (status = 409, description = "Finding is already open", body = ErrorResponse),
+    )
+)]
+pub async fn reopen_finding(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    payload: Option<Json<TransitionFindingRequest>>,
+) -> Result<Json<TrackedFinding>, AppError> {
+    transition(&state, &id, "open", payload).await
+}
+
+/// GET /api/v1/projects/:id/findings/report - Remediation progress of the
+/// findings of a project's repositories: current states, findings opened
+/// and resolved since a date, and those open since before it, by license
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects/{id}/findings/report",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project ID"), FindingsReportQueryParams),
+    responses(
+        (status = 200, body = ProjectFindingsReport),
+        (status = 400, description = "Invalid date", body = ErrorResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_project_findings_report(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<FindingsReportQueryParams>,
+) -> Result<Json<ProjectFindingsReport>, AppError> {
+    let (project, repositories) = find_project(&state, &id).await?;
+
+    let since = match params
+        .since
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        Some(since) => parse_timestamp("since", since)?,
+        None => (Utc::now() - Duration::days(DEFAULT_REPORT_DAYS))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string(),
+    };
+
+    let filter = TrackedFindingFilter {
+        repositories: Some(repositories),
+        ..Default::default()
+    };
+    let findings = TrackedFinding::list(&state.db, &filter).await?;
+
+    Ok(Json(lifecycle::report(&project.id, &since, &findings)))
+}
+
+async fn transition(
+    state: &AppState,
+    id: &str,
+    to: &str,
+    payload: Option<Json<TransitionFindingRequest>>,
+) -> Result<Json<TrackedFinding>, AppError> {
+    let finding = find_finding(state, id).await?;
+    if !lifecycle::can_transition(&finding.state, to) {
+        return Err(AppError::Conflict(format!(
+            "Finding {} is {} and cannot be moved to {}",
+            id, finding.state, to
+        )));
+    }
+
+    let note = payload
+        .and_then(|Json(p)| p.note)
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+    let finding = TrackedFinding::transition(&state.db, id, to, note.as_deref())
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Finding {} not found", id)))?;
+    tracing::info!("Finding {} moved to {}", id, to);
+
+    Ok(Json(finding))
+}
+
+async fn find_finding(state: &AppState, id: &str) -> Result<TrackedFinding, AppError> {
+    TrackedFinding::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Finding {} not found", id)))
+}
+
+fn validate_state(value: &str) -> Result<(), AppError> {
+    if STATES.contains(&value) {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "Invalid state '{}'. Must be one of {}",
+            value,
+            STATES.join(", ")
+        )))
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    db::models::{Scan, ScanScreening},
+    error::AppError,
+    gate, policy, AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GateFormat {
+    #[default]
+    Json,
+    Text,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GateQueryParams {
+    #[serde(default)]
+    format: GateFormat,
+}
+
+/// GET /api/v1/scans/:id/gate - CI policy verdict for a finished scan.
+/// Responds 200 for every verdict; pipelines decide on the `verdict` field
+/// or the first line of the text rendering.
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/gate",
+    tag = "scans",
+    params(("id" = String, Path, description = "Scan ID"), GateQueryParams),
+    responses(
+        (status = 200, description = "Verdict as JSON, or as text with `format=text`", body = GateReport),
+        (status = 400, description = "Scan is still running", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_gate(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<GateQueryParams>,
+) -> Result<Response, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    if scan.status != "completed" && scan.status != "failed" {
+        return Err(AppError::Validation(format!(
+            "Scan is not finished yet. Current status: {}",
+            scan.status
+        )));
+    }
+
+    let screening = ScanScreening::find_by_scan_id(&state.db, &id).await?;
+    let policy = policy::load(&state.db, &id).await?;
+    let report = gate::evaluate(&scan, screening.as_ref(), policy.as_ref());
+
+    Ok(match params.format {
+        GateFormat::Json => Json(report).into_response(),
+        GateFormat::Text => (
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            gate::render_text(&report),
+        )
+            .into_response(),
+    })
+}
@@ -1,6 +1,14 @@
 use axum::Json;
 use serde_json::{json, Value};
 
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "Service is up", body = Object),
+    )
+)]
 pub async fn health_check() -> Json<Value> {
     Json(json!({
         "status": "healthy",
@@ -0,0 +1,68 @@
+use crate::{
+    api::models::{ScanAuthorsResponse, ScanHistoryResponse},
+    db::models::{Scan, ScanFileAuthor, ScanHistoryFinding},
+    error::AppError,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+/// GET /api/v1/scans/:id/history - Findings of a due-diligence scan's walk
+/// through the git history, newest commit first
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/history",
+    tag = "results",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanHistoryResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_history(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanHistoryResponse>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let findings = ScanHistoryFinding::find_by_scan_id(&state.db, &id).await?;
+
+    Ok(Json(ScanHistoryResponse {
+        scan_id: scan.id,
+        due_diligence: scan.due_diligence,
+        findings,
+    }))
+}
+
+/// GET /api/v1/scans/:id/authors - Commit authors of the scanned files, from
+/// a `git_authorship` scan's walk through the git history
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/authors",
+    tag = "results",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanAuthorsResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_authors(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanAuthorsResponse>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let files = ScanFileAuthor::find_by_scan_id(&state.db, &id).await?;
+
+    Ok(Json(ScanAuthorsResponse {
+        scan_id: scan.id,
+        git_authorship: scan.git_authorship,
+        files,
+    }))
+}
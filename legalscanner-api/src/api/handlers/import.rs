@@ -0,0 +1,211 @@
+use crate::{
+    api::models::{
+        GithubOrgImportResponse, ImportGithubOrgRequest, ImportRepositoriesRequest,
+        ImportedRepository, RepositoryImportResponse, ScanResponse,
+    },
+    bulk_import::{self, RepositoryFilter, VISIBILITIES},
+    credentials,
+    db::models::Scan,
+    error::AppError,
+    git::Provider,
+    progress::record_event,
+    AppState,
+};
+use axum::{extract::State, http::StatusCode, Json};
+
+/// POST /api/v1/import/github-org - Scan every repository of a GitHub
+/// organization that matches the filters. The token lists the repositories
+/// and clones them; with `SCAN_LOCK_PER_REPOSITORY`, a running scan of a
+/// repository is reported instead of starting another.
+#[utoipa::path(
+    post,
+    path = "/api/v1/import/github-org",
+    tag = "scans",
+    request_body = ImportGithubOrgRequest,
+    responses(
+        (status = 201, description = "Scans started", body = GithubOrgImportResponse),
+        (status = 200, description = "Dry run, matching repositories only", body = GithubOrgImportResponse),
+        (status = 400, description = "Invalid organization, filter or token", body = ErrorResponse),
+        (status = 404, description = "Organization or credential not found", body = ErrorResponse),
+        (status = 422, description = "GitHub rejected the token or could not be reached", body = ErrorResponse),
+    )
+)]
+pub async fn import_github_org(
+    State(state): State<AppState>,
+    Json(payload): Json<ImportGithubOrgRequest>,
+) -> Result<(StatusCode, Json<GithubOrgImportResponse>), AppError> {
+    let (status, response) = import(&state, payload.into()).await?;
+    Ok((
+        status,
+        Json(GithubOrgImportResponse {
+            org: response.namespace,
+            listed: response.listed,
+            dry_run: response.dry_run,
+            repositories: response.repositories,
+        }),
+    ))
+}
+
+/// POST /api/v1/import/repositories - Scan every repository of a GitHub
+/// organization, GitLab group, Bitbucket workspace or Azure DevOps project
+/// that matches the filters, like the GitHub organization import.
+#[utoipa::path(
+    post,
+    path = "/api/v1/import/repositories",
+    tag = "scans",
+    request_body = ImportRepositoriesRequest,
+    responses(
+        (status = 201, description = "Scans started", body = RepositoryImportResponse),
+        (status = 200, description = "Dry run, matching repositories only", body = RepositoryImportResponse),
+        (status = 400, description = "Invalid provider, namespace, filter or token", body = ErrorResponse),
+        (status = 404, description = "Namespace or credential not found", body = ErrorResponse),
+        (status = 422, description = "The provider rejected the token or could not be reached", body = ErrorResponse),
+    )
+)]
+pub async fn import_repositories(
+    State(state): State<AppState>,
+    Json(payload): Json<ImportRepositoriesRequest>,
+) -> Result<(StatusCode, Json<RepositoryImportResponse>), AppError> {
+    let (status, response) = import(&state, payload).await?;
+    Ok((status, Json(response)))
+}
+
+async fn import(
+    state: &AppState,
+    payload: ImportRepositoriesRequest,
+) -> Result<(StatusCode, RepositoryImportResponse), AppError> {
+    let provider: Provider = payload.provider.parse().map_err(AppError::Validation)?;
+    let namespace = payload.namespace.trim().trim_matches('/').to_string();
+    bulk_import::validate_namespace(provider, &namespace).map_err(AppError::Validation)?;
+
+    let visibility = payload
+        .visibility
+        .map(|v| v.trim().to_lowercase())
+        .filter(|v| !v.is_empty());
+    if let Some(visibility) = &visibility {
+        if !VISIBILITIES.contains(&visibility.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Invalid visibility '{}'. Must be one of {}",
+                visibility,
+                VISIBILITIES.join(", ")
+            )));
+        }
+    }
+    let filter = RepositoryFilter {
+        topics: payload
+            .topics
+            .iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+        visibility: visibility.filter(|v| v != "all"),
+        include_archived: payload.include_archived,
+        include_forks: payload.include_forks,
+    };
+
+    let git_token = payload
+        .git_token
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    let credential_id = payload
+        .credential_id
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    let api_auth = match (&git_token, &credential_id) {
+        (Some(_), Some(_)) => {
+            return Err(AppError::Validation(
+                "git_token cannot be combined with credential_id".to_string(),
+            ))
+        }
+        (None, None) => {
+            return Err(AppError::Validation(format!(
+                "A git_token or credential_id is required to list the {} repositories",
+                provider.label()
+            )))
+        }
+        (Some(token), None) => provider.token_auth(token.clone()),
+        (None, Some(id)) => {
+            let auth = credentials::resolve(state, id).await?;
+            if auth.api_token().is_none() {
+                return Err(AppError::Validation(format!(
+                    "Credential {} has no token to list repositories with",
+                    id
+                )));
+            }
+            auth
+        }
+    };
+
+    let listed =
+        bulk_import::list_repositories(&state.config, provider, &namespace, &api_auth).await?;
+    let matching: Vec<_> = listed.iter().filter(|r| filter.matches(r)).collect();
+
+    let mut repositories = Vec::with_capacity(matching.len());
+    let mut started = Vec::new();
+    for repository in matching {
+        let scan = if payload.dry_run {
+            None
+        } else {
+            let git_url = repository.clone_url.clone();
+            let (mut scan, created) = if state.config.scan_lock_per_repository {
+                Scan::create_unless_running(&state.db, git_url, None, git_token.clone(), None)
+                    .await?
+            } else {
+                (Scan::create(&state.db, git_url, git_token.clone(), None).await?, true)
+            };
+            if created {
+                if let Some(credential_id) = &credential_id {
+                    Scan::set_credential(&state.db, &scan.id, credential_id).await?;
+                    scan.credential_id = Some(credential_id.clone());
+                }
+                started.push(scan.id.clone());
+            }
+            Some(scan)
+        };
+
+        repositories.push(ImportedRepository {
+            name: repository.name.clone(),
+            git_url: repository.clone_url.clone(),
+            visibility: repository.visibility.clone(),
+            default_branch: repository.default_branch.clone(),
+            topics: repository.topics.clone(),
+            scan: scan.map(ScanResponse::from),
+        });
+    }
+
+    let detail = format!("imported from {} {}", provider.label(), namespace);
+    for scan_id in &started {
+        let _ = record_event(state, scan_id, "created", None, Some(&detail)).await;
+        let _ = record_event(state, scan_id, "queued", None, None).await;
+
+        let scan_id = scan_id.clone();
+        let state_clone = state.clone();
+        tokio::spawn(async move {
+            super::scan_job::execute_scan_job(scan_id, state_clone).await;
+        });
+    }
+
+    tracing::info!(
+        "{} {}: {} of {} repositories matched, {} scans started",
+        provider.label(),
+        namespace,
+        repositories.len(),
+        listed.len(),
+        started.len()
+    );
+    let status = if payload.dry_run {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    Ok((
+        status,
+        RepositoryImportResponse {
+            provider: payload.provider.trim().to_lowercase(),
+            namespace,
+            listed: listed.len(),
+            dry_run: payload.dry_run,
+            repositories,
+        },
+    ))
+}
@@ -0,0 +1,108 @@
+use crate::{
+    api::{
+        handlers::risk::{license_weight, load_risk_config},
+        models::{LicenseSummary, ScanCoverageResponse, ScanLicenseSummaryResponse},
+    },
+    coverage,
+    db::models::Scan,
+    error::AppError,
+    export::{component_license, sarif::relative_uri},
+    storage::ResultQuery,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+/// Example files listed per license
+const EXAMPLE_PATHS: usize = 5;
+
+/// GET /api/v1/scans/:id/licenses - Files per detected license with a few
+/// example paths and the license's risk weight, and the license concluded
+/// for the repository
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/licenses",
+    tag = "results",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanLicenseSummaryResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_license_summary(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanLicenseSummaryResponse>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let risk_config = load_risk_config(&state.db).await?;
+    let license_findings = ResultQuery {
+        result_type: Some("license".to_string()),
+        ..Default::default()
+    };
+    let results = state
+        .result_store
+        .query_results(&scan.id, &license_findings)
+        .await?;
+    let concluded_license = component_license::conclude(&scan.id, &results);
+
+    let licenses = state
+        .result_store
+        .license_summary(&scan.id, EXAMPLE_PATHS)
+        .await?
+        .into_iter()
+        .map(|row| {
+            let mut example_paths: Vec<String> = row
+                .example_paths
+                .iter()
+                .map(|path| relative_uri(path, &scan.id))
+                .collect();
+            example_paths.sort();
+            LicenseSummary {
+                risk_weight: license_weight(&risk_config, &row.license_name),
+                license: row.license_name,
+                spdx_id: row.license_spdx_id,
+                file_count: row.file_count,
+                example_paths,
+            }
+        })
+        .collect();
+
+    Ok(Json(ScanLicenseSummaryResponse {
+        scan_id: scan.id,
+        concluded_license,
+        licenses,
+    }))
+}
+
+/// GET /api/v1/scans/:id/coverage - Files per programming language and how
+/// many of them have license findings, with the licenses found per language
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/coverage",
+    tag = "results",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanCoverageResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_coverage(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanCoverageResponse>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let languages = coverage::report(&state, &scan.id).await?;
+
+    Ok(Json(ScanCoverageResponse {
+        scan_id: scan.id,
+        languages,
+    }))
+}
@@ -0,0 +1,45 @@
+use crate::{
+    db::models::{Scan, Worker},
+    error::AppError,
+    AppState,
+};
+use axum::{extract::State, Json};
+use serde_json::{json, Value};
+
+/// Throughput is averaged over this window
+const RATE_WINDOW_MINUTES: i64 = 15;
+
+/// Workers without a heartbeat for this long are not counted
+const WORKER_STALE_AFTER_SECS: i64 = 90;
+
+/// GET /api/v1/metrics/queue - Queue depth and processing rate for external autoscalers
+///
+/// Flat numeric fields so KEDA's metrics-api scaler (`valueLocation: queue_depth`)
+/// or an HPA external metrics adapter can read them directly.
+#[utoipa::path(
+    get,
+    path = "/api/v1/metrics/queue",
+    tag = "metrics",
+    responses(
+        (status = 200, description = "Queue depth, workers and throughput", body = Object),
+    )
+)]
+pub async fn queue_metrics(State(state): State<AppState>) -> Result<Json<Value>, AppError> {
+    let stats = Scan::queue_stats(&state.db, RATE_WINDOW_MINUTES).await?;
+    let active_workers = Worker::count_active(&state.db, WORKER_STALE_AFTER_SECS).await?;
+
+    let processing_rate = stats.completed_in_window as f64 / RATE_WINDOW_MINUTES as f64;
+    let outstanding = stats.pending + stats.in_progress;
+
+    Ok(Json(json!({
+        "queue_depth": stats.pending,
+        "in_progress": stats.in_progress,
+        "outstanding": outstanding,
+        "active_workers": active_workers,
+        "outstanding_per_worker": outstanding as f64 / active_workers.max(1) as f64,
+        "processing_rate_per_minute": processing_rate,
+        "completed_in_window": stats.completed_in_window,
+        "failed_in_window": stats.failed_in_window,
+        "window_minutes": RATE_WINDOW_MINUTES
+    })))
+}
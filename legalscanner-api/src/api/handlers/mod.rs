@@ -1,6 +1,40 @@
+pub mod admin;
+pub mod analytics;
 pub mod api_keys;
+pub mod composite_scans;
+pub mod copyright_holders;
+pub mod credentials;
+pub mod curations;
+pub mod custom_fields;
+pub mod diff;
+pub mod disputes;
+pub mod events;
+pub mod export;
+pub mod findings;
+pub mod gate;
 pub mod health;
+pub mod history;
+pub mod import;
+pub mod licenses;
+pub mod metrics;
+pub mod notification_templates;
+pub mod owners;
+pub mod policies;
+pub mod projects;
+pub mod push_hooks;
+pub mod remediation;
+pub mod repository;
+pub mod reviews;
 pub mod risk;
+pub mod sample;
 pub mod sbom;
 pub mod scan_job;
 pub mod scans;
+pub mod screening;
+pub mod shares;
+pub mod stats;
+pub mod timeline;
+pub mod upload;
+pub mod verify;
+pub mod waivers;
+pub mod webhooks;
@@ -0,0 +1,99 @@
+use crate::{
+    api::models::{NotificationTemplatesResponse, UpsertNotificationTemplateRequest},
+    db::models::NotificationTemplate,
+    error::AppError,
+    webhooks::{validate_template, CALLBACK_EVENTS, DEFAULT_CONTENT_TYPE, TEMPLATE_VARIABLES},
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+/// GET /api/v1/notification-templates - List stored callback templates
+#[utoipa::path(
+    get,
+    path = "/api/v1/notification-templates",
+    tag = "notifications",
+    responses((status = 200, body = NotificationTemplatesResponse))
+)]
+pub async fn list_notification_templates(
+    State(state): State<AppState>,
+) -> Result<Json<NotificationTemplatesResponse>, AppError> {
+    let templates = NotificationTemplate::list(&state.db).await?;
+
+    Ok(Json(NotificationTemplatesResponse {
+        templates,
+        events: CALLBACK_EVENTS.iter().map(|e| e.to_string()).collect(),
+        variables: TEMPLATE_VARIABLES.iter().map(|v| v.to_string()).collect(),
+    }))
+}
+
+/// PUT /api/v1/notification-templates/:event - Replace the callback payload
+/// of an event with a template
+#[utoipa::path(
+    put,
+    path = "/api/v1/notification-templates/{event}",
+    tag = "notifications",
+    params(("event" = String, Path, description = "Callback event, e.g. scan.completed")),
+    request_body = UpsertNotificationTemplateRequest,
+    responses(
+        (status = 200, body = NotificationTemplate),
+        (status = 400, description = "Unknown event or invalid template", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_notification_template(
+    State(state): State<AppState>,
+    Path(event): Path<String>,
+    Json(payload): Json<UpsertNotificationTemplateRequest>,
+) -> Result<Json<NotificationTemplate>, AppError> {
+    validate_event(&event)?;
+
+    let content_type = payload
+        .content_type
+        .filter(|c| !c.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_CONTENT_TYPE.to_string());
+    validate_template(&content_type, &payload.body).map_err(AppError::Validation)?;
+
+    let template =
+        NotificationTemplate::upsert(&state.db, &event, &content_type, &payload.body).await?;
+    Ok(Json(template))
+}
+
+/// DELETE /api/v1/notification-templates/:event - Restore the built-in JSON
+/// payload for an event
+#[utoipa::path(
+    delete,
+    path = "/api/v1/notification-templates/{event}",
+    tag = "notifications",
+    params(("event" = String, Path, description = "Callback event, e.g. scan.completed")),
+    responses(
+        (status = 204, description = "Template deleted"),
+        (status = 404, description = "Event has no template", body = ErrorResponse),
+    )
+)]
+pub async fn delete_notification_template(
+    State(state): State<AppState>,
+    Path(event): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if !NotificationTemplate::delete(&state.db, &event).await? {
+        return Err(AppError::NotFound(format!(
+            "No notification template for event {}",
+            event
+        )));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn validate_event(event: &str) -> Result<(), AppError> {
+    if !CALLBACK_EVENTS.contains(&event) {
+        return Err(AppError::Validation(format!(
+            "Unknown event '{}', expected one of: {}",
+            event,
+            CALLBACK_EVENTS.join(", ")
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::{
+    api::models::ScanOwnersResponse, codeowners::scan_owners, db::models::Scan, error::AppError,
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScanOwnersQueryParams {
+    /// Only this owner, e.g. `@acme/payments`, compared case-insensitively
+    owner: Option<String>,
+}
+
+/// GET /api/v1/scans/:id/owners - Findings and gate violations grouped by the
+/// teams that own the affected paths in the repository's CODEOWNERS
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/owners",
+    tag = "results",
+    params(("id" = String, Path, description = "Scan ID"), ScanOwnersQueryParams),
+    responses(
+        (status = 200, body = ScanOwnersResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_owners(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<ScanOwnersQueryParams>,
+) -> Result<Json<ScanOwnersResponse>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let (codeowners_path, mut owners) = match scan_owners(&state, &scan).await? {
+        Some((path, owners)) => (Some(path), owners),
+        None => (None, Vec::new()),
+    };
+
+    if let Some(wanted) = params.owner.filter(|o| !o.is_empty()) {
+        owners.retain(|s| {
+            s.owner
+                .as_deref()
+                .is_some_and(|o| o.eq_ignore_ascii_case(&wanted))
+        });
+    }
+
+    Ok(Json(ScanOwnersResponse {
+        scan_id: scan.id,
+        codeowners_path,
+        owners,
+    }))
+}
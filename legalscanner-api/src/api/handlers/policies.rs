@@ -0,0 +1,443 @@
+use crate::{
+    api::{
+        handlers::projects::{find_project, project_response},
+        models::{
+            AssignPolicyRequest, PoliciesResponse, PolicyResponse, ProjectResponse,
+            UpsertPolicyRequest,
+        },
+    },
+    db::models::{Policy, Project, Scan},
+    error::AppError,
+    policy::{self, EffectivePolicy, PolicyEvaluation, ECC_SEVERITIES},
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::collections::HashSet;
+
+/// GET /api/v1/policies - List the license policies
+#[utoipa::path(
+    get,
+    path = "/api/v1/policies",
+    tag = "policies",
+    responses((status = 200, body = PoliciesResponse))
+)]
+pub async fn list_policies(
+    State(state): State<AppState>,
+) -> Result<Json<PoliciesResponse>, AppError> {
+    let policies = Policy::list(&state.db).await?;
+
+    Ok(Json(PoliciesResponse {
+        policies: policies.into_iter().map(PolicyResponse::from).collect(),
+    }))
+}
+
+/// GET /api/v1/policies/:name - Get a license policy
+#[utoipa::path(
+    get,
+    path = "/api/v1/policies/{name}",
+    tag = "policies",
+    params(("name" = String, Path, description = "Policy name")),
+    responses(
+        (status = 200, body = PolicyResponse),
+        (status = 404, description = "Policy not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_policy(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<PolicyResponse>, AppError> {
+    Ok(Json(PolicyResponse::from(find_policy(&state, &name).await?)))
+}
+
+/// PUT /api/v1/policies/:name - Define a license policy or replace its
+/// definition. Scans already evaluated keep their evaluation until
+/// re-evaluated.
+#[utoipa::path(
+    put,
+    path = "/api/v1/policies/{name}",
+    tag = "policies",
+    params(("name" = String, Path, description = "Policy name, e.g. distribution")),
+    request_body = UpsertPolicyRequest,
+    responses(
+        (status = 200, body = PolicyResponse),
+        (status = 400, description = "Invalid name, license lists or thresholds", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_policy(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(payload): Json<UpsertPolicyRequest>,
+) -> Result<Json<PolicyResponse>, AppError> {
+    validate_name(&name)?;
+    let allowed = license_list(&payload.allowed_licenses);
+    let denied = license_list(&payload.denied_licenses);
+    let review = license_list(&payload.review_licenses);
+    for (list, other, names) in [
+        (&allowed, &denied, "allowed and denied"),
+        (&allowed, &review, "allowed and needs review"),
+        (&denied, &review, "denied and needs review"),
+    ] {
+        let other: HashSet<String> = other.iter().map(|l| l.to_lowercase()).collect();
+        if let Some(license) = list.iter().find(|l| other.contains(&l.to_lowercase())) {
+            return Err(AppError::Validation(format!(
+                "License {} is both {}",
+                license, names
+            )));
+        }
+    }
+
+    if let Some(score) = payload.max_risk_score {
+        if !(0..=100).contains(&score) {
+            return Err(AppError::Validation(
+                "max_risk_score must be between 0 and 100".to_string(),
+            ));
+        }
+    }
+    let severity = payload
+        .ecc_fail_severity
+        .as_deref()
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty());
+    if let Some(severity) = &severity {
+        if !ECC_SEVERITIES.contains(&severity.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Invalid ecc_fail_severity '{}'. Valid severities: {}",
+                severity,
+                ECC_SEVERITIES.join(", ")
+            )));
+        }
+    }
+    let description = payload
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+
+    let policy = Policy::upsert(
+        &state.db,
+        &name,
+        description,
+        &allowed,
+        &denied,
+        &review,
+        payload.max_risk_score,
+        severity.as_deref(),
+    )
+    .await?;
+    Ok(Json(PolicyResponse::from(policy)))
+}
+
+/// DELETE /api/v1/policies/:name - Delete a license policy. Projects and
+/// scans it was assigned to are left without a policy.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/policies/{name}",
+    tag = "policies",
+    params(("name" = String, Path, description = "Policy name")),
+    responses(
+        (status = 204, description = "Policy deleted"),
+        (status = 404, description = "Policy not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_policy(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if !Policy::delete(&state.db, &name).await? {
+        return Err(AppError::NotFound(format!("Policy {} not found", name)));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/v1/default-policy - The organization's default license policy
+#[utoipa::path(
+    get,
+    path = "/api/v1/default-policy",
+    tag = "policies",
+    responses(
+        (status = 200, body = PolicyResponse),
+        (status = 404, description = "No default policy", body = ErrorResponse),
+    )
+)]
+pub async fn get_default_policy(
+    State(state): State<AppState>,
+) -> Result<Json<PolicyResponse>, AppError> {
+    Policy::find_default(&state.db)
+        .await?
+        .map(|policy| Json(PolicyResponse::from(policy)))
+        .ok_or_else(|| AppError::NotFound("No default policy is set".to_string()))
+}
+
+/// PUT /api/v1/default-policy - Make a license policy the organization's
+/// default, applying to every scan beneath project and scan policies. Takes
+/// effect as scans complete or are re-evaluated.
+#[utoipa::path(
+    put,
+    path = "/api/v1/default-policy",
+    tag = "policies",
+    request_body = AssignPolicyRequest,
+    responses(
+        (status = 200, body = PolicyResponse),
+        (status = 404, description = "Policy not found", body = ErrorResponse),
+    )
+)]
+pub async fn set_default_policy(
+    State(state): State<AppState>,
+    Json(payload): Json<AssignPolicyRequest>,
+) -> Result<Json<PolicyResponse>, AppError> {
+    Policy::set_default(&state.db, &payload.policy)
+        .await?
+        .map(|policy| Json(PolicyResponse::from(policy)))
+        .ok_or_else(|| AppError::NotFound(format!("Policy {} not found", payload.policy)))
+}
+
+/// DELETE /api/v1/default-policy - Leave the organization without a default
+/// policy
+#[utoipa::path(
+    delete,
+    path = "/api/v1/default-policy",
+    tag = "policies",
+    responses(
+        (status = 204, description = "Default policy removed"),
+        (status = 404, description = "No default policy", body = ErrorResponse),
+    )
+)]
+pub async fn remove_default_policy(
+    State(state): State<AppState>,
+) -> Result<StatusCode, AppError> {
+    if !Policy::clear_default(&state.db).await? {
+        return Err(AppError::NotFound("No default policy is set".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// PUT /api/v1/projects/:id/policy - Assign a license policy to the scans of
+/// a project's repositories. Takes effect as their scans complete.
+#[utoipa::path(
+    put,
+    path = "/api/v1/projects/{id}/policy",
+    tag = "policies",
+    params(("id" = String, Path, description = "Project ID")),
+    request_body = AssignPolicyRequest,
+    responses(
+        (status = 200, body = ProjectResponse),
+        (status = 404, description = "Project or policy not found", body = ErrorResponse),
+    )
+)]
+pub async fn assign_project_policy(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<AssignPolicyRequest>,
+) -> Result<Json<ProjectResponse>, AppError> {
+    let policy = find_policy(&state, &payload.policy).await?;
+    set_project_policy(&state, &id, Some(&policy.name)).await
+}
+
+/// DELETE /api/v1/projects/:id/policy - Remove the license policy of a
+/// project
+#[utoipa::path(
+    delete,
+    path = "/api/v1/projects/{id}/policy",
+    tag = "policies",
+    params(("id" = String, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = ProjectResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+    )
+)]
+pub async fn remove_project_policy(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ProjectResponse>, AppError> {
+    set_project_policy(&state, &id, None).await
+}
+
+/// PUT /api/v1/scans/:id/policy - Assign a license policy to a scan,
+/// overriding the rules it sets in the policies of the organization and its
+/// projects. A completed scan is evaluated right away.
+#[utoipa::path(
+    put,
+    path = "/api/v1/scans/{id}/policy",
+    tag = "policies",
+    params(("id" = String, Path, description = "Scan ID")),
+    request_body = AssignPolicyRequest,
+    responses(
+        (status = 200, description = "Evaluation of the completed scan, or null", body = Option<PolicyEvaluation>),
+        (status = 404, description = "Scan or policy not found", body = ErrorResponse),
+    )
+)]
+pub async fn assign_scan_policy(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<AssignPolicyRequest>,
+) -> Result<Json<Option<PolicyEvaluation>>, AppError> {
+    let policy = find_policy(&state, &payload.policy).await?;
+    set_scan_policy(&state, &id, Some(&policy.name)).await
+}
+
+/// DELETE /api/v1/scans/:id/policy - Remove the license policy of a scan, so
+/// the policy of its projects applies again
+#[utoipa::path(
+    delete,
+    path = "/api/v1/scans/{id}/policy",
+    tag = "policies",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, description = "Evaluation of the completed scan against its project's policy, or null", body = Option<PolicyEvaluation>),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn remove_scan_policy(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Option<PolicyEvaluation>>, AppError> {
+    set_scan_policy(&state, &id, None).await
+}
+
+/// GET /api/v1/scans/:id/effective-policy - The organization, project and
+/// scan policies that apply to a scan, layered, with the policy each rule
+/// comes from
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/effective-policy",
+    tag = "policies",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = EffectivePolicy),
+        (status = 404, description = "Scan not found or no policy applies", body = ErrorResponse),
+    )
+)]
+pub async fn get_effective_policy(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<EffectivePolicy>, AppError> {
+    let scan = find_scan(&state, &id).await?;
+    policy::resolve(&state.db, &scan)
+        .await?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("No policy applies to scan {}", id)))
+}
+
+/// GET /api/v1/scans/:id/policy-evaluation - Outcome of the scan's policy
+/// evaluation and its violations
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/policy-evaluation",
+    tag = "policies",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = PolicyEvaluation),
+        (status = 404, description = "Scan not found or not evaluated", body = ErrorResponse),
+    )
+)]
+pub async fn get_policy_evaluation(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<PolicyEvaluation>, AppError> {
+    find_scan(&state, &id).await?;
+    policy::load(&state.db, &id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} has no policy evaluation", id)))
+}
+
+/// POST /api/v1/scans/:id/policy-evaluation - Re-evaluate a completed scan,
+/// e.g. after its policy changed
+#[utoipa::path(
+    post,
+    path = "/api/v1/scans/{id}/policy-evaluation",
+    tag = "policies",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = PolicyEvaluation),
+        (status = 404, description = "Scan not found or no policy applies", body = ErrorResponse),
+        (status = 409, description = "Scan not completed", body = ErrorResponse),
+    )
+)]
+pub async fn evaluate_scan_policy(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<PolicyEvaluation>, AppError> {
+    let scan = find_scan(&state, &id).await?;
+    if scan.status != "completed" {
+        return Err(AppError::Conflict(format!(
+            "Scan {} is {}, only completed scans are evaluated",
+            id, scan.status
+        )));
+    }
+    policy::evaluate_scan(&state, &id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("No policy applies to scan {}", id)))
+}
+
+async fn set_project_policy(
+    state: &AppState,
+    id: &str,
+    policy: Option<&str>,
+) -> Result<Json<ProjectResponse>, AppError> {
+    let project = Project::set_policy(&state.db, id, policy)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Project {} not found", id)))?;
+    let (_, repositories) = find_project(state, &project.id).await?;
+    Ok(Json(project_response(project, repositories)))
+}
+
+async fn set_scan_policy(
+    state: &AppState,
+    id: &str,
+    policy: Option<&str>,
+) -> Result<Json<Option<PolicyEvaluation>>, AppError> {
+    let scan = Scan::set_policy(&state.db, id, policy)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+    if scan.status != "completed" {
+        return Ok(Json(None));
+    }
+    Ok(Json(policy::evaluate_scan(state, id).await?))
+}
+
+async fn find_policy(state: &AppState, name: &str) -> Result<Policy, AppError> {
+    Policy::find_by_name(&state.db, name)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Policy {} not found", name)))
+}
+
+async fn find_scan(state: &AppState, id: &str) -> Result<Scan, AppError> {
+    Scan::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))
+}
+
+/// Names appear in URLs and reports
+fn validate_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "Invalid policy name '{}': use up to 64 letters, digits, '-', '_' or '.'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Trimmed, without blanks and case-insensitive duplicates
+fn license_list(licenses: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    licenses
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && seen.insert(l.to_lowercase()))
+        .map(str::to_string)
+        .collect()
+}
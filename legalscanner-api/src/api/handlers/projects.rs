@@ -0,0 +1,413 @@
+use crate::{
+    api::{
+        handlers::sbom::export_response,
+        models::{
+            CreateProjectRequest, ProjectLatestScansResponse, ProjectResponse,
+            ProjectRiskTrendResponse, RepositoryLatestScan, RiskTrendPoint, ScanResponse,
+            UpdateProjectRequest,
+        },
+    },
+    db::models::{Project, Scan, ScanRepositoryMetadata},
+    error::AppError,
+    export::{pipeline::ExportPipeline, SbomFormat, SpdxVersion},
+    git::info::repo_name_from_url,
+    repo_metadata,
+    scan_diff::normalize_repository_url,
+    AppState,
+};
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{Response, StatusCode},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProjectSbomQueryParams {
+    #[serde(default)]
+    format: SbomFormat,
+    #[serde(default)]
+    spdx_version: SpdxVersion,
+}
+
+/// POST /api/v1/projects - Create a project
+#[utoipa::path(
+    post,
+    path = "/api/v1/projects",
+    tag = "projects",
+    request_body = CreateProjectRequest,
+    responses(
+        (status = 201, body = ProjectResponse),
+        (status = 400, description = "Invalid name or repository URL", body = ErrorResponse),
+        (status = 409, description = "A project with this name exists", body = ErrorResponse),
+    )
+)]
+pub async fn create_project(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateProjectRequest>,
+) -> Result<(StatusCode, Json<ProjectResponse>), AppError> {
+    let repositories = normalize_repositories(&payload.repositories, &state.config.git_url_schemes)?;
+
+    // Unnamed or undescribed projects take after their first repository
+    let metadata = match repositories.first() {
+        Some(repository) if payload.name.is_none() || payload.description.is_none() => {
+            repo_metadata::latest_for_repository(&state.db, repository).await?
+        }
+        _ => None,
+    };
+    let name = match &payload.name {
+        Some(name) => validate_name(name)?.to_string(),
+        None => default_name(repositories.first(), metadata.as_ref())?,
+    };
+    let description = payload
+        .description
+        .or_else(|| metadata.and_then(|m| m.description));
+
+    let project = Project::create(&state.db, &name, description.as_deref(), &repositories)
+        .await
+        .map_err(|e| name_conflict(e, &name))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(project_response(project, repositories)),
+    ))
+}
+
+/// GET /api/v1/projects - List projects by name
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects",
+    tag = "projects",
+    responses(
+        (status = 200, body = Vec<ProjectResponse>),
+    )
+)]
+pub async fn list_projects(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ProjectResponse>>, AppError> {
+    let projects = Project::list(&state.db).await?;
+
+    let mut responses = Vec::with_capacity(projects.len());
+    for project in projects {
+        let repositories = Project::repositories(&state.db, &project.id).await?;
+        responses.push(project_response(project, repositories));
+    }
+
+    Ok(Json(responses))
+}
+
+/// GET /api/v1/projects/:id - Get a project
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects/{id}",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = ProjectResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_project(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ProjectResponse>, AppError> {
+    let (project, repositories) = find_project(&state, &id).await?;
+    Ok(Json(project_response(project, repositories)))
+}
+
+/// PUT /api/v1/projects/:id - Rename a project, change its description or
+/// replace its repositories
+#[utoipa::path(
+    put,
+    path = "/api/v1/projects/{id}",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project ID")),
+    request_body = UpdateProjectRequest,
+    responses(
+        (status = 200, body = ProjectResponse),
+        (status = 400, description = "Invalid name or repository URL", body = ErrorResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+        (status = 409, description = "A project with this name exists", body = ErrorResponse),
+    )
+)]
+pub async fn update_project(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateProjectRequest>,
+) -> Result<Json<ProjectResponse>, AppError> {
+    let name = payload.name.as_deref().map(validate_name).transpose()?;
+    let repositories = payload
+        .repositories
+        .as_deref()
+        .map(|repositories| normalize_repositories(repositories, &state.config.git_url_schemes))
+        .transpose()?;
+
+    let project = Project::update(
+        &state.db,
+        &id,
+        name,
+        payload.description.as_deref(),
+        repositories.as_deref(),
+    )
+    .await
+    .map_err(|e| name_conflict(e, name.unwrap_or_default()))?
+    .ok_or_else(|| AppError::NotFound(format!("Project {} not found", id)))?;
+
+    let repositories = Project::repositories(&state.db, &id).await?;
+    Ok(Json(project_response(project, repositories)))
+}
+
+/// DELETE /api/v1/projects/:id - Delete a project, keeping its scans
+#[utoipa::path(
+    delete,
+    path = "/api/v1/projects/{id}",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project ID")),
+    responses(
+        (status = 204, description = "Project deleted, its scans are kept"),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+        (status = 409, description = "Project is under legal hold", body = ErrorResponse),
+    )
+)]
+pub async fn delete_project(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let (project, _) = find_project(&state, &id).await?;
+    if project.legal_hold_at.is_some() {
+        return Err(AppError::Conflict(format!(
+            "Project {} is under legal hold",
+            id
+        )));
+    }
+
+    if !Project::delete(&state.db, &id).await? {
+        return Err(AppError::NotFound(format!("Project {} not found", id)));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/v1/projects/:id/scans/latest - Latest scan of every repository
+/// of the project
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects/{id}/scans/latest",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = ProjectLatestScansResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_project_latest_scans(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ProjectLatestScansResponse>, AppError> {
+    let (project, repositories) = find_project(&state, &id).await?;
+    let scans = Scan::find_by_repositories(&state.db, &repositories).await?;
+
+    let repositories = repositories
+        .into_iter()
+        .map(|git_url| {
+            let latest_scan = latest_scan(&scans, &git_url, |_| true);
+            let latest_completed_scan = latest_scan_for_sbom(&scans, &git_url);
+            RepositoryLatestScan {
+                latest_scan: latest_scan.cloned().map(ScanResponse::from),
+                latest_completed_scan: latest_completed_scan.cloned().map(ScanResponse::from),
+                git_url,
+            }
+        })
+        .collect();
+
+    Ok(Json(ProjectLatestScansResponse {
+        project_id: project.id,
+        repositories,
+    }))
+}
+
+/// GET /api/v1/projects/:id/risk-trend - Risk score of the project's
+/// completed full scans over time
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects/{id}/risk-trend",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project ID")),
+    responses(
+        (status = 200, body = ProjectRiskTrendResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_project_risk_trend(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ProjectRiskTrendResponse>, AppError> {
+    let (project, repositories) = find_project(&state, &id).await?;
+    let mut scans: Vec<Scan> = Scan::find_by_repositories(&state.db, &repositories)
+        .await?
+        .into_iter()
+        .filter(|scan| scan.status == "completed" && !scan.quick)
+        .collect();
+    scans.sort_by(|a, b| {
+        a.completed_at
+            .cmp(&b.completed_at)
+            .then_with(|| a.created_at.cmp(&b.created_at))
+    });
+
+    let points = scans
+        .into_iter()
+        .map(|scan| RiskTrendPoint {
+            scan_id: scan.id,
+            git_url: scan.git_url,
+            completed_at: scan.completed_at,
+            risk_score: scan.risk_score,
+            risk_level: scan.risk_level,
+        })
+        .collect();
+
+    Ok(Json(ProjectRiskTrendResponse {
+        project_id: project.id,
+        points,
+    }))
+}
+
+/// GET /api/v1/projects/:id/sbom - One SBOM covering the latest completed
+/// full scan of every repository of the project
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects/{id}/sbom",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project ID"), ProjectSbomQueryParams),
+    responses(
+        (status = 200, description = "SPDX document in the requested format", content_type = "application/json"),
+        (status = 404, description = "Project not found or without completed scans", body = ErrorResponse),
+    )
+)]
+pub async fn get_project_sbom(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<ProjectSbomQueryParams>,
+) -> Result<Response<Body>, AppError> {
+    let (project, repositories) = find_project(&state, &id).await?;
+    let scans = Scan::find_by_repositories(&state.db, &repositories).await?;
+
+    let mut collected = Vec::new();
+    for git_url in &repositories {
+        let Some(scan) = latest_scan_for_sbom(&scans, git_url) else {
+            continue;
+        };
+        let results = state.result_store.load_results(&scan.id).await?;
+        collected.push((scan.clone(), results));
+    }
+
+    if collected.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "Project {} has no completed scans",
+            id
+        )));
+    }
+
+    let output = ExportPipeline::from_config(&state.config).export_aggregate_sbom(
+        &project.name,
+        format!("https://legalscanner.io/spdx/project/{}", project.id),
+        &collected,
+        params.format,
+        params.spdx_version,
+    )?;
+
+    export_response(output)
+}
+
+pub(crate) async fn find_project(state: &AppState, id: &str) -> Result<(Project, Vec<String>), AppError> {
+    let project = Project::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Project {} not found", id)))?;
+    let repositories = Project::repositories(&state.db, id).await?;
+    Ok((project, repositories))
+}
+
+pub(crate) fn project_response(project: Project, repositories: Vec<String>) -> ProjectResponse {
+    ProjectResponse {
+        project_id: project.id,
+        name: project.name,
+        description: project.description,
+        repositories,
+        created_at: project.created_at,
+        updated_at: project.updated_at,
+        legal_hold_at: project.legal_hold_at,
+        policy: project.policy,
+    }
+}
+
+fn validate_name(name: &str) -> Result<&str, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation(
+            "Project name cannot be empty".to_string(),
+        ));
+    }
+    Ok(name)
+}
+
+/// Name of a project created without one: the display name captured from
+/// the repository's provider, else the repository name from its URL
+fn default_name(
+    repository: Option<&String>,
+    metadata: Option<&ScanRepositoryMetadata>,
+) -> Result<String, AppError> {
+    let repository = repository.ok_or_else(|| {
+        AppError::Validation("Project name is required without repositories".to_string())
+    })?;
+    Ok(metadata
+        .and_then(|m| m.display_name.clone())
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| repo_name_from_url(repository)))
+}
+
+/// Validate and normalize repository URLs, dropping duplicates
+fn normalize_repositories(
+    repositories: &[String],
+    schemes: &[String],
+) -> Result<Vec<String>, AppError> {
+    let mut normalized = Vec::with_capacity(repositories.len());
+    for url in repositories {
+        crate::git::validate_git_url(url.trim(), schemes).map_err(AppError::Validation)?;
+        let url = normalize_repository_url(&crate::git::normalize_git_url(url));
+        if !normalized.contains(&url) {
+            normalized.push(url);
+        }
+    }
+    normalized.sort();
+    Ok(normalized)
+}
+
+fn name_conflict(e: sqlx::Error, name: &str) -> AppError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AppError::Conflict(format!("A project named '{}' already exists", name))
+        }
+        _ => e.into(),
+    }
+}
+
+/// Newest scan of `git_url` matching `filter`; `scans` are oldest first
+fn latest_scan<'a>(
+    scans: &'a [Scan],
+    git_url: &str,
+    filter: impl Fn(&Scan) -> bool,
+) -> Option<&'a Scan> {
+    scans
+        .iter()
+        .rev()
+        .find(|scan| normalize_repository_url(&scan.git_url) == git_url && filter(scan))
+}
+
+/// Latest completed full scan, the one a project SBOM is built from
+fn latest_scan_for_sbom<'a>(scans: &'a [Scan], git_url: &str) -> Option<&'a Scan> {
+    latest_scan(scans, git_url, |scan| {
+        scan.status == "completed" && !scan.quick
+    })
+}
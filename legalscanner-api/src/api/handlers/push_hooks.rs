@@ -0,0 +1,158 @@
+use crate::{
+    api::models::{PushHookResponse, ScanResponse},
+    db::models::{Project, Scan},
+    error::AppError,
+    progress::record_event,
+    push_hooks::{self, HookEvent, PushProvider},
+    scan_diff::normalize_repository_url,
+    AppState,
+};
+use axum::{body::Bytes, extract::State, http::HeaderMap, http::StatusCode, Json};
+
+/// POST /api/v1/hooks/github - GitHub push webhook. A push to a branch of a
+/// repository that belongs to a project starts a scan of the branch.
+#[utoipa::path(
+    post,
+    path = "/api/v1/hooks/github",
+    tag = "scans",
+    request_body(content = String, description = "GitHub webhook payload", content_type = "application/json"),
+    responses(
+        (status = 202, description = "Scan started", body = PushHookResponse),
+        (status = 200, description = "Nothing to scan, or a scan of the branch is already running", body = PushHookResponse),
+        (status = 400, description = "Invalid push event", body = ErrorResponse),
+        (status = 401, description = "Missing or wrong signature", body = ErrorResponse),
+        (status = 404, description = "Push webhooks are not enabled", body = ErrorResponse),
+    )
+)]
+pub async fn github_push(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<PushHookResponse>), AppError> {
+    receive(&state, PushProvider::GitHub, &headers, &body).await
+}
+
+/// POST /api/v1/hooks/gitlab - GitLab push webhook, like the GitHub one
+#[utoipa::path(
+    post,
+    path = "/api/v1/hooks/gitlab",
+    tag = "scans",
+    request_body(content = String, description = "GitLab webhook payload", content_type = "application/json"),
+    responses(
+        (status = 202, description = "Scan started", body = PushHookResponse),
+        (status = 200, description = "Nothing to scan, or a scan of the branch is already running", body = PushHookResponse),
+        (status = 400, description = "Invalid push event", body = ErrorResponse),
+        (status = 401, description = "Missing or wrong secret token", body = ErrorResponse),
+        (status = 404, description = "Push webhooks are not enabled", body = ErrorResponse),
+    )
+)]
+pub async fn gitlab_push(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<PushHookResponse>), AppError> {
+    receive(&state, PushProvider::GitLab, &headers, &body).await
+}
+
+async fn receive(
+    state: &AppState,
+    provider: PushProvider,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<(StatusCode, Json<PushHookResponse>), AppError> {
+    let Some(secret) = state.config.push_webhook_secret.as_deref() else {
+        return Err(AppError::NotFound(
+            "Push webhooks are not enabled, set PUSH_WEBHOOK_SECRET".to_string(),
+        ));
+    };
+    if !push_hooks::verify(provider, headers, body, secret) {
+        return Err(AppError::Unauthorized);
+    }
+
+    let mut response = PushHookResponse {
+        provider: provider.as_str().to_string(),
+        git_url: None,
+        branch: None,
+        commit: None,
+        projects: Vec::new(),
+        scan: None,
+        ignored: None,
+    };
+    let push = match push_hooks::parse(provider, headers, body).map_err(AppError::Validation)? {
+        HookEvent::Push(push) => push,
+        HookEvent::Ignored(reason) => {
+            response.ignored = Some(reason);
+            return Ok((StatusCode::OK, Json(response)));
+        }
+    };
+
+    crate::git::validate_git_url(&push.repository, &state.config.git_url_schemes)
+        .map_err(AppError::Validation)?;
+    let git_url = crate::git::normalize_git_url(&push.repository);
+    let repository = normalize_repository_url(&git_url);
+    response.git_url = Some(git_url.clone());
+    response.branch = Some(push.branch.clone());
+    response.commit = Some(push.commit.clone());
+    response.projects = Project::find_by_repository(&state.db, &repository)
+        .await?
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    if response.projects.is_empty() {
+        response.ignored = Some("The repository does not belong to any project".to_string());
+        return Ok((StatusCode::OK, Json(response)));
+    }
+
+    // A private repository is cloned with the token or credential of its
+    // latest scan
+    let previous = Scan::find_by_repositories(&state.db, &[repository])
+        .await?
+        .pop();
+    let git_token = previous.as_ref().and_then(|s| s.git_token.clone());
+    let credential_id = previous.and_then(|s| s.credential_id);
+
+    let branch = Some(push.branch.clone());
+    let (mut scan, created) = if state.config.scan_lock_per_repository {
+        Scan::create_unless_running(&state.db, git_url, branch, git_token, None).await?
+    } else {
+        let mut scan = Scan::create(&state.db, git_url, git_token, None).await?;
+        Scan::set_branch(&state.db, &scan.id, &push.branch).await?;
+        scan.branch = branch;
+        (scan, true)
+    };
+    if !created {
+        tracing::info!(
+            "Scan {} of {} is still {}, not starting another for the push",
+            scan.id,
+            scan.git_url,
+            scan.status
+        );
+        response.scan = Some(ScanResponse::from(scan));
+        return Ok((StatusCode::OK, Json(response)));
+    }
+    if let Some(credential_id) = credential_id {
+        Scan::set_credential(&state.db, &scan.id, &credential_id).await?;
+        scan.credential_id = Some(credential_id);
+    }
+    // The pushed commit is scanned even if the branch moved on since
+    Scan::set_commit(&state.db, &scan.id, &push.commit).await?;
+    scan.commit_sha = Some(push.commit.clone());
+
+    let detail = format!(
+        "{} push to {} at {}",
+        provider.as_str(),
+        push.branch,
+        push.commit.get(..12).unwrap_or(&push.commit)
+    );
+    let _ = record_event(state, &scan.id, "created", None, Some(&detail)).await;
+    let _ = record_event(state, &scan.id, "queued", None, None).await;
+
+    let scan_id = scan.id.clone();
+    let state_clone = state.clone();
+    tokio::spawn(async move {
+        super::scan_job::execute_scan_job(scan_id, state_clone).await;
+    });
+
+    response.scan = Some(ScanResponse::from(scan));
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
@@ -0,0 +1,15 @@
+use crate::remediation::{self, Remediation};
+use axum::Json;
+
+/// GET /api/v1/remediation - Suggested actions for every risk factor category
+#[utoipa::path(
+    get,
+    path = "/api/v1/remediation",
+    tag = "scans",
+    responses(
+        (status = 200, description = "Guidance per risk factor category", body = Vec<Remediation>),
+    )
+)]
+pub async fn list_remediation() -> Json<Vec<Remediation>> {
+    Json(remediation::all())
+}
@@ -0,0 +1,110 @@
+use crate::{
+    api::models::{LanguageFiles, ScanRepositoryMetadataResponse, ScanSwhidsResponse},
+    db::models::{Scan, ScanFileSwhid, ScanLanguageStat, ScanRepositoryMetadata},
+    error::AppError,
+    swhid::revision_swhid,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use sqlx::SqlitePool;
+
+/// GET /api/v1/scans/:id/repository - Repository metadata captured when the
+/// scan cloned the repository
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/repository",
+    tag = "scans",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanRepositoryMetadataResponse),
+        (status = 404, description = "Scan not found or no metadata captured yet", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_repository(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanRepositoryMetadataResponse>, AppError> {
+    Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let metadata = metadata_response(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("No repository metadata for scan {} yet", id)))?;
+    Ok(Json(metadata))
+}
+
+/// GET /api/v1/scans/:id/swhids - Software Heritage identifiers of the
+/// scanned commit and files, with the upstream origins the archive knows
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/swhids",
+    tag = "scans",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanSwhidsResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_swhids(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanSwhidsResponse>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let revision = ScanRepositoryMetadata::find_by_scan_id(&state.db, &id)
+        .await?
+        .and_then(|m| m.commit_sha)
+        .map(|sha| revision_swhid(&sha));
+    let files = ScanFileSwhid::find_by_scan_id(&state.db, &id).await?;
+
+    Ok(Json(ScanSwhidsResponse {
+        scan_id: scan.id,
+        revision_swhid: revision,
+        files,
+    }))
+}
+
+/// Repository metadata and language breakdown of a scan, `None` before the
+/// scan cloned the repository
+pub(crate) async fn metadata_response(
+    pool: &SqlitePool,
+    scan_id: &str,
+) -> Result<Option<ScanRepositoryMetadataResponse>, AppError> {
+    let Some(metadata) = ScanRepositoryMetadata::find_by_scan_id(pool, scan_id).await? else {
+        return Ok(None);
+    };
+    let languages = ScanLanguageStat::find_by_scan_id(pool, scan_id)
+        .await?
+        .into_iter()
+        .map(|stat| LanguageFiles {
+            language: stat.language,
+            file_count: stat.file_count,
+            total_bytes: stat.total_bytes,
+        })
+        .collect();
+
+    Ok(Some(ScanRepositoryMetadataResponse {
+        topics: metadata.topic_list(),
+        scan_id: metadata.scan_id,
+        provider: metadata.provider,
+        display_name: metadata.display_name,
+        description: metadata.description,
+        default_branch: metadata.default_branch,
+        latest_tag: metadata.latest_tag,
+        contributor_count: metadata.contributor_count,
+        commit_sha: metadata.commit_sha,
+        commit_date: metadata.commit_date,
+        commit_author: metadata.commit_author,
+        head_tag: metadata.head_tag,
+        size_bytes: metadata.size_bytes,
+        file_count: metadata.file_count,
+        languages,
+        captured_at: metadata.created_at,
+    }))
+}
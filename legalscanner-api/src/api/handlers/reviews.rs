@@ -0,0 +1,280 @@
+use crate::{
+    api::handlers::scans::RESULT_TYPES,
+    api::models::{
+        CreateResultCommentRequest, ResultCommentsResponse, ReviewResultsRequest,
+        ReviewResultsResponse, ScanReviewResponse,
+    },
+    db::models::{Scan, ScanResultComment},
+    error::AppError,
+    review::{self, REVIEW_STATUSES},
+    storage::ResultQuery,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+/// GET /api/v1/scans/:id/review - Review progress of a scan's findings
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/review",
+    tag = "reviews",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanReviewResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_review(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanReviewResponse>, AppError> {
+    let scan = find_scan(&state, &id).await?;
+    Ok(Json(review::summary(&state, &scan).await?))
+}
+
+/// POST /api/v1/scans/:id/review - Mark a completed scan reviewed. Refused
+/// while a high-severity finding is pending review and not waived.
+#[utoipa::path(
+    post,
+    path = "/api/v1/scans/{id}/review",
+    tag = "reviews",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanReviewResponse),
+        (status = 400, description = "Scan is not completed yet", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+        (status = 409, description = "High-severity findings are pending review", body = ErrorResponse),
+    )
+)]
+pub async fn mark_scan_reviewed(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanReviewResponse>, AppError> {
+    let scan = find_scan(&state, &id).await?;
+    if scan.status != "completed" {
+        return Err(AppError::Validation(format!(
+            "Scan is not completed yet. Current status: {}",
+            scan.status
+        )));
+    }
+
+    let summary = review::summary(&state, &scan).await?;
+    if summary.unresolved_high_severity > 0 {
+        return Err(AppError::Conflict(format!(
+            "{} high-severity findings are pending review",
+            summary.unresolved_high_severity
+        )));
+    }
+
+    if Scan::mark_reviewed(&state.db, &id).await? {
+        tracing::info!("Scan {} marked reviewed", id);
+    }
+    let scan = find_scan(&state, &id).await?;
+
+    Ok(Json(ScanReviewResponse {
+        reviewed_at: scan.reviewed_at,
+        ..summary
+    }))
+}
+
+/// POST /api/v1/scans/:id/results/review - Approve, reject or reset findings,
+/// by ID or all pending findings of a type and severity
+#[utoipa::path(
+    post,
+    path = "/api/v1/scans/{id}/results/review",
+    tag = "reviews",
+    params(("id" = String, Path, description = "Scan ID")),
+    request_body = ReviewResultsRequest,
+    responses(
+        (status = 200, body = ReviewResultsResponse),
+        (status = 400, description = "Invalid status or selection", body = ErrorResponse),
+        (status = 404, description = "Scan or finding not found", body = ErrorResponse),
+        (status = 409, description = "Findings of the scan are archived", body = ErrorResponse),
+    )
+)]
+pub async fn review_scan_results(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<ReviewResultsRequest>,
+) -> Result<Json<ReviewResultsResponse>, AppError> {
+    let scan = find_scan(&state, &id).await?;
+    if scan.results_archived_at.is_some() {
+        return Err(AppError::Conflict(format!(
+            "Findings of scan {} are archived and can no longer be reviewed",
+            id
+        )));
+    }
+
+    let status = payload.status.trim().to_lowercase();
+    if !REVIEW_STATUSES.contains(&status.as_str()) {
+        return Err(AppError::Validation(format!(
+            "Invalid status '{}'. Must be one of {}",
+            payload.status,
+            REVIEW_STATUSES.join(", ")
+        )));
+    }
+
+    let ids = match payload.result_ids {
+        Some(ids) if ids.is_empty() => {
+            return Err(AppError::Validation(
+                "result_ids cannot be empty".to_string(),
+            ))
+        }
+        Some(ids) => {
+            let existing = state.result_store.existing_ids(&id, &ids).await?;
+            let missing: Vec<String> = ids
+                .iter()
+                .filter(|i| !existing.contains(i))
+                .map(i64::to_string)
+                .collect();
+            if !missing.is_empty() {
+                return Err(AppError::NotFound(format!(
+                    "Findings not found in scan {}: {}",
+                    id,
+                    missing.join(", ")
+                )));
+            }
+            existing
+        }
+        None => {
+            if let Some(result_type) = payload.result_type.as_deref() {
+                if !result_type.is_empty() && !RESULT_TYPES.contains(&result_type) {
+                    return Err(AppError::Validation(format!(
+                        "Invalid result_type '{}'. Must be one of {}",
+                        result_type,
+                        RESULT_TYPES.join(", ")
+                    )));
+                }
+            }
+            let non_empty = |v: Option<String>| v.filter(|v| !v.is_empty());
+            let query = ResultQuery {
+                result_type: non_empty(payload.result_type),
+                severity: non_empty(payload.severity),
+                review_status: Some("pending".to_string()),
+                ..Default::default()
+            };
+            state
+                .result_store
+                .query_results(&id, &query)
+                .await?
+                .into_iter()
+                .map(|r| r.id)
+                .collect()
+        }
+    };
+
+    let updated = state
+        .result_store
+        .set_review_status(&id, &ids, &status)
+        .await?;
+    if let Some(comment) = payload.comment.as_deref().map(str::trim) {
+        if !comment.is_empty() {
+            for result_id in &ids {
+                ScanResultComment::create(
+                    &state.db,
+                    &id,
+                    *result_id,
+                    comment,
+                    payload.author.as_deref(),
+                )
+                .await?;
+            }
+        }
+    }
+    tracing::info!("Set {} findings of scan {} to {}", updated, id, status);
+
+    // A high-severity finding back to pending withdraws the reviewed mark
+    let mut review = review::summary(&state, &scan).await?;
+    if review.reviewed_at.is_some() && review.unresolved_high_severity > 0 {
+        Scan::clear_reviewed(&state.db, &id).await?;
+        review.reviewed_at = None;
+    }
+
+    Ok(Json(ReviewResultsResponse { updated, review }))
+}
+
+/// GET /api/v1/scans/:id/results/:result_id/comments - Comments on a finding,
+/// oldest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/results/{result_id}/comments",
+    tag = "reviews",
+    params(
+        ("id" = String, Path, description = "Scan ID"),
+        ("result_id" = i64, Path, description = "Finding ID"),
+    ),
+    responses(
+        (status = 200, body = ResultCommentsResponse),
+        (status = 404, description = "Scan or finding not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_result_comments(
+    State(state): State<AppState>,
+    Path((id, result_id)): Path<(String, i64)>,
+) -> Result<Json<ResultCommentsResponse>, AppError> {
+    find_result(&state, &id, result_id).await?;
+    let comments = ScanResultComment::find_by_result_id(&state.db, result_id).await?;
+
+    Ok(Json(ResultCommentsResponse {
+        result_id,
+        comments,
+    }))
+}
+
+/// POST /api/v1/scans/:id/results/:result_id/comments - Comment on a finding
+#[utoipa::path(
+    post,
+    path = "/api/v1/scans/{id}/results/{result_id}/comments",
+    tag = "reviews",
+    params(
+        ("id" = String, Path, description = "Scan ID"),
+        ("result_id" = i64, Path, description = "Finding ID"),
+    ),
+    request_body = CreateResultCommentRequest,
+    responses(
+        (status = 201, body = ScanResultComment),
+        (status = 400, description = "Empty comment", body = ErrorResponse),
+        (status = 404, description = "Scan or finding not found", body = ErrorResponse),
+    )
+)]
+pub async fn add_result_comment(
+    State(state): State<AppState>,
+    Path((id, result_id)): Path<(String, i64)>,
+    Json(payload): Json<CreateResultCommentRequest>,
+) -> Result<(StatusCode, Json<ScanResultComment>), AppError> {
+    find_result(&state, &id, result_id).await?;
+
+    let body = payload.body.trim();
+    if body.is_empty() {
+        return Err(AppError::Validation(
+            "Comment body cannot be empty".to_string(),
+        ));
+    }
+
+    let comment =
+        ScanResultComment::create(&state.db, &id, result_id, body, payload.author.as_deref())
+            .await?;
+
+    Ok((StatusCode::CREATED, Json(comment)))
+}
+
+async fn find_scan(state: &AppState, id: &str) -> Result<Scan, AppError> {
+    Scan::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))
+}
+
+async fn find_result(state: &AppState, id: &str, result_id: i64) -> Result<(), AppError> {
+    find_scan(state, id).await?;
+    let found = state.result_store.existing_ids(id, &[result_id]).await?;
+    if found.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "Finding {} not found in scan {}",
+            result_id, id
+        )));
+    }
+    Ok(())
+}
@@ -1,22 +1,61 @@
 use crate::api::models::{RiskAssessment, RiskFactor};
 use crate::db::models::scan_result::ScanResult;
+use crate::db::models::Scan;
 use crate::error::AppError;
+use crate::export::license_expression::{self, LicenseTerm};
+use crate::remediation;
+use crate::waiver;
+use crate::AppState;
+use sha2::{Digest, Sha256};
 use sqlx::SqlitePool;
 use std::collections::HashMap;
 use tracing::{debug, info};
 
+/// Version of the scoring algorithm, stored with each assessment. Bump it
+/// whenever the same findings and risk configuration would score
+/// differently, so outdated assessments can be found and recalculated.
+pub const RISK_ALGORITHM_VERSION: i32 = 1;
+
 /// Calculate risk score for a completed scan
 pub async fn calculate_risk_score(
-    pool: &SqlitePool,
+    state: &AppState,
     scan_id: &str,
 ) -> Result<RiskAssessment, AppError> {
     info!("Calculating risk score for scan {}", scan_id);
+    let pool = &state.db;
 
     // Fetch all scan results for this scan
-    let results = ScanResult::find_by_scan_id(pool, scan_id).await?;
+    let mut results = state.result_store.load_results(scan_id).await?;
+
+    // Waived findings do not count towards the score
+    if let Some(scan) = Scan::find_by_id(pool, scan_id).await? {
+        let waived = waiver::mark(pool, &scan, &mut results).await?;
+        if waived > 0 {
+            debug!("Leaving {} waived findings out of the risk score", waived);
+            results.retain(|r| r.waiver_id.is_none());
+        }
+    }
+
+    let assessment = assess_results(pool, &results).await?;
+
+    debug!(
+        "Risk calculation complete for scan {}: score={}, level={}",
+        scan_id, assessment.score, assessment.level
+    );
 
+    Ok(assessment)
+}
+
+/// Score a set of findings, e.g. the results of one scan or of all member
+/// scans of a composite scan. Waived findings must be removed beforehand.
+pub async fn assess_results(
+    pool: &SqlitePool,
+    results: &[ScanResult],
+) -> Result<RiskAssessment, AppError> {
     // Load risk config from database
     let risk_config = load_risk_config(pool).await?;
+    let ecc_path_rules = load_ecc_path_rules(pool).await?;
+    let config_version = config_version(&risk_config, &ecc_path_rules);
 
     let mut base_score = 0;
     let mut risk_factors: Vec<RiskFactor> = Vec::new();
@@ -30,27 +69,42 @@ pub async fn calculate_risk_score(
     // 1. LICENSE TYPE RISK (max +40 points)
     let mut license_risk_map: HashMap<String, Vec<String>> = HashMap::new();
     let mut copyleft_licenses: Vec<String> = Vec::new();
+    // "<license> WITH <exception>" and the license it weighs as
+    let mut excepted_licenses: Vec<(String, String)> = Vec::new();
     let mut unknown_licenses: Vec<String> = Vec::new();
 
     for result in &license_results {
-        if let Some(license_name) = &result.license_name {
+        let Some(name) = &result.license_name else {
+            continue;
+        };
+        // An expression counts as the licenses it obliges to
+        for term in risk_terms(&risk_config, name, result.license_exception.as_deref()) {
+            let license_name = &term.license;
             // Try to find matching risk weight from config
             if let Some(weight) = get_license_weight(&risk_config, license_name) {
                 if weight > 0 {
+                    let label = match &term.exception {
+                        Some(exception) => format!("{} WITH {}", license_name, exception),
+                        None => license_name.to_string(),
+                    };
                     let entry = license_risk_map
-                        .entry(license_name.to_string())
+                        .entry(label.clone())
                         .or_insert_with(Vec::new);
                     if !entry.contains(&result.file_path) {
                         entry.push(result.file_path.clone());
                     }
 
                     // Categorize for risk factors
-                    if is_copyleft(license_name) {
-                        if !copyleft_licenses.contains(&license_name.to_string()) {
+                    if is_copyleft(license_name) && term.exception.is_some() {
+                        if !excepted_licenses.iter().any(|(l, _)| *l == label) {
+                            excepted_licenses.push((label, license_name.to_string()));
+                        }
+                    } else if is_copyleft(license_name) {
+                        if !copyleft_licenses.contains(license_name) {
                             copyleft_licenses.push(license_name.to_string());
                         }
                     } else if is_unknown_or_proprietary(license_name) {
-                        if !unknown_licenses.contains(&license_name.to_string()) {
+                        if !unknown_licenses.contains(license_name) {
                             unknown_licenses.push(license_name.to_string());
                         }
                     }
@@ -83,6 +137,31 @@ pub async fn calculate_risk_score(
         });
     }
 
+    // Exceptions such as Classpath or GCC runtime lift the copyleft
+    // obligations for code linking to the licensed code, so each counts
+    // half its license's weight
+    if !excepted_licenses.is_empty() {
+        let mut affected_count = 0;
+        let mut details: Vec<String> = Vec::new();
+        for (label, license) in &excepted_licenses {
+            if let Some(files) = license_risk_map.get(label) {
+                affected_count += files.len() as i32;
+                details.push(format!("{} ({} files)", label, files.len()));
+                if let Some(weight) = get_license_weight(&risk_config, license) {
+                    base_score += weight / 2;
+                }
+            }
+        }
+
+        risk_factors.push(RiskFactor {
+            category: "copyleft_license_exception".to_string(),
+            severity: "medium".to_string(),
+            description: "Copyleft licenses with exceptions detected - check that the exception covers how the code is used".to_string(),
+            affected_count,
+            details,
+        });
+    }
+
     // Add unknown/proprietary risk factor
     if !unknown_licenses.is_empty() {
         let mut affected_count = 0;
@@ -107,11 +186,12 @@ pub async fn calculate_risk_score(
         });
     }
 
-    // 2. MISSING SPDX IDs (max +2 per file)
+    // 2. MISSING SPDX IDs (max +2 per file). Valid SPDX expressions are
+    // not ambiguous.
     let missing_spdx: Vec<&ScanResult> = license_results
         .iter()
         .filter(|r| {
-            r.license_name.is_some()
+            r.license_name.as_deref().is_some_and(|name| !is_spdx_expression(name))
                 && (r.license_spdx_id.is_none() || r.license_spdx_id.as_ref().unwrap().is_empty())
         })
         .copied()
@@ -193,7 +273,8 @@ pub async fn calculate_risk_score(
         .collect();
 
     if !ecc_results.is_empty() {
-        let mut ecc_points = 0;
+        let mut ecc_points = 0.0;
+        let mut down_weighted: HashMap<String, i32> = HashMap::new();
         let mut critical_count = 0;
         let mut high_count = 0;
         let mut medium_count = 0;
@@ -205,32 +286,56 @@ pub async fn calculate_risk_score(
         let mut low_details: Vec<String> = Vec::new();
 
         for result in &ecc_results {
+            // Weight by reachability: findings in tests/examples/docs count less
+            let (path_class, multiplier) = classify_ecc_path(&ecc_path_rules, &result.file_path);
+            if multiplier < 1.0 {
+                *down_weighted.entry(path_class.to_string()).or_insert(0) += 1;
+            }
+
             let severity = result.risk_severity.as_deref().unwrap_or("medium");
-            match severity {
+            let points = match severity {
                 "critical" => {
-                    ecc_points += 20;
                     critical_count += 1;
                     critical_details.push(result.file_path.clone());
+                    20.0
                 }
                 "high" => {
-                    ecc_points += 12;
                     high_count += 1;
                     high_details.push(result.file_path.clone());
+                    12.0
                 }
                 "medium" => {
-                    ecc_points += 6;
                     medium_count += 1;
                     medium_details.push(result.file_path.clone());
+                    6.0
                 }
                 _ => {
-                    ecc_points += 2;
                     low_count += 1;
                     low_details.push(result.file_path.clone());
+                    2.0
                 }
-            }
+            };
+            ecc_points += points * multiplier;
         }
 
-        base_score += ecc_points;
+        base_score += ecc_points.round() as i32;
+
+        // Note findings that were down-weighted by path class
+        if !down_weighted.is_empty() {
+            let mut details: Vec<String> = down_weighted
+                .iter()
+                .map(|(class, count)| format!("{}: {} findings", class, count))
+                .collect();
+            details.sort();
+
+            risk_factors.push(RiskFactor {
+                category: "ecc_non_shipped".to_string(),
+                severity: "low".to_string(),
+                description: "Export control findings in tests, examples or documentation - weighted lower as they are unlikely to ship".to_string(),
+                affected_count: down_weighted.values().sum(),
+                details,
+            });
+        }
 
         // Add risk factor for critical/high ECC findings
         if critical_count > 0 || high_count > 0 {
@@ -273,7 +378,73 @@ pub async fn calculate_risk_score(
         }
     }
 
-    // 5. LICENSE DIVERSITY (max +10 points)
+    // 5. MALWARE (+50 per infected file)
+    let malware_results: Vec<&ScanResult> = results
+        .iter()
+        .filter(|r| r.result_type == "malware")
+        .collect();
+
+    if !malware_results.is_empty() {
+        base_score += malware_results.len() as i32 * 50;
+
+        risk_factors.push(RiskFactor {
+            category: "malware".to_string(),
+            severity: "critical".to_string(),
+            description: "Malware signatures detected - infected files must be removed before use".to_string(),
+            affected_count: malware_results.len() as i32,
+            details: malware_results
+                .iter()
+                .map(|r| {
+                    format!(
+                        "{} ({})",
+                        r.file_path,
+                        r.raw_data.as_deref().unwrap_or("unknown signature")
+                    )
+                })
+                .collect(),
+        });
+    }
+
+    // 6. MODEL LICENSES (weights from the 'model' risk_config category)
+    let model_results: Vec<&ScanResult> = results
+        .iter()
+        .filter(|r| r.result_type == "model_license")
+        .collect();
+
+    if !model_results.is_empty() {
+        let mut model_counts: HashMap<String, i32> = HashMap::new();
+        for result in &model_results {
+            let license = result.license_name.clone().unwrap_or_else(|| "Unknown".to_string());
+            *model_counts.entry(license).or_insert(0) += 1;
+        }
+
+        let mut restricted_count = 0;
+        let mut details: Vec<String> = Vec::new();
+        for (license, count) in &model_counts {
+            // Each restricted license type counts once, like copyleft licenses
+            if let Some(weight) = get_license_weight(&risk_config, license) {
+                if weight > 0 {
+                    base_score += weight;
+                    restricted_count += count;
+                    details.push(format!("{} ({} artifacts)", license, count));
+                }
+            }
+        }
+        details.sort();
+
+        if restricted_count > 0 {
+            let unlicensed = model_counts.contains_key("No_license_found");
+            risk_factors.push(RiskFactor {
+                category: "model_license".to_string(),
+                severity: if unlicensed { "high" } else { "medium" }.to_string(),
+                description: "ML model weights under use-restricted or missing licenses - review acceptable use terms before shipping".to_string(),
+                affected_count: restricted_count,
+                details,
+            });
+        }
+    }
+
+    // 7. LICENSE DIVERSITY (max +10 points)
     let unique_licenses: std::collections::HashSet<String> = license_results
         .iter()
         .filter_map(|r| r.license_name.clone())
@@ -315,20 +486,67 @@ pub async fn calculate_risk_score(
         _ => "critical",
     };
 
-    debug!(
-        "Risk calculation complete for scan {}: score={}, level={}",
-        scan_id, final_score, risk_level
-    );
-
     Ok(RiskAssessment {
         score: final_score,
         level: risk_level.to_string(),
+        remediation: remediation::for_factors(&risk_factors),
         factors: risk_factors,
+        preliminary: false,
+        algorithm_version: Some(RISK_ALGORITHM_VERSION),
+        config_version: Some(config_version),
+        assessed_at: None,
     })
 }
 
+/// Store the assessment on the scan with the versions it was made with
+pub async fn store_risk_assessment(
+    pool: &SqlitePool,
+    scan_id: &str,
+    risk: &RiskAssessment,
+) -> Result<(), sqlx::Error> {
+    let factors_json = serde_json::to_string(&risk.factors).unwrap_or_else(|_| "[]".to_string());
+    Scan::update_risk_assessment(
+        pool,
+        scan_id,
+        risk.score,
+        &risk.level,
+        &factors_json,
+        risk.algorithm_version.unwrap_or(RISK_ALGORITHM_VERSION),
+        risk.config_version.as_deref().unwrap_or_default(),
+    )
+    .await
+}
+
+/// Version of the risk configuration now in the database, see
+/// [`config_version`]
+pub async fn current_config_version(pool: &SqlitePool) -> Result<String, AppError> {
+    Ok(config_version(
+        &load_risk_config(pool).await?,
+        &load_ecc_path_rules(pool).await?,
+    ))
+}
+
+/// Short hash of the license weights and ECC path rules. Weights are hashed
+/// in a fixed order, path rules in priority order since the first match wins.
+fn config_version(risk_config: &[(String, i32)], ecc_path_rules: &[(String, String, f64)]) -> String {
+    let mut weights: Vec<&(String, i32)> = risk_config.iter().collect();
+    weights.sort();
+
+    let mut hasher = Sha256::new();
+    for (pattern, weight) in weights {
+        hasher.update(format!("license\t{}\t{}\n", pattern, weight));
+    }
+    for (pattern, class, multiplier) in ecc_path_rules {
+        hasher.update(format!("ecc_path\t{}\t{}\t{}\n", pattern, class, multiplier));
+    }
+    hasher.finalize()[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
 /// Load risk configuration from database
-async fn load_risk_config(pool: &SqlitePool) -> Result<Vec<(String, i32)>, AppError> {
+pub(crate) async fn load_risk_config(pool: &SqlitePool) -> Result<Vec<(String, i32)>, AppError> {
     #[derive(sqlx::FromRow)]
     struct RiskConfigRow {
         license_pattern: String,
@@ -347,8 +565,64 @@ async fn load_risk_config(pool: &SqlitePool) -> Result<Vec<(String, i32)>, AppEr
         .collect())
 }
 
+/// Load ECC path-class rules from database, highest priority first
+async fn load_ecc_path_rules(pool: &SqlitePool) -> Result<Vec<(String, String, f64)>, AppError> {
+    #[derive(sqlx::FromRow)]
+    struct EccPathRuleRow {
+        path_pattern: String,
+        path_class: String,
+        weight_multiplier: f64,
+    }
+
+    let records = sqlx::query_as::<_, EccPathRuleRow>(
+        "SELECT path_pattern, path_class, weight_multiplier FROM ecc_path_rules ORDER BY priority DESC, id"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(records
+        .into_iter()
+        .map(|r| (r.path_pattern, r.path_class, r.weight_multiplier))
+        .collect())
+}
+
+/// Classify a file path using the first matching rule.
+/// Paths without a matching rule are treated as shipped source with full weight.
+fn classify_ecc_path<'a>(rules: &'a [(String, String, f64)], file_path: &str) -> (&'a str, f64) {
+    let path = file_path.trim_start_matches('/').to_lowercase();
+    rules
+        .iter()
+        .find(|(pattern, _, _)| like_match(&pattern.to_lowercase(), &path))
+        .map(|(_, class, multiplier)| (class.as_str(), *multiplier))
+        .unwrap_or(("shipped", 1.0))
+}
+
+/// Match text against a SQL LIKE-style pattern where `%` matches any sequence
+fn like_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('%').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+
+    let first = parts[0];
+    let last = parts[parts.len() - 1];
+    if !text.starts_with(first) || text.len() < first.len() + last.len() || !text.ends_with(last) {
+        return false;
+    }
+
+    // Match the middle parts in order within the remaining text
+    let mut remaining = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match remaining.find(part) {
+            Some(idx) => remaining = &remaining[idx + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
 /// Get risk weight for a license using pattern matching
-fn get_license_weight(config: &[(String, i32)], license_name: &str) -> Option<i32> {
+pub(crate) fn get_license_weight(config: &[(String, i32)], license_name: &str) -> Option<i32> {
     for (pattern, weight) in config {
         if pattern.ends_with('%') {
             // Prefix match
@@ -376,8 +650,67 @@ fn get_license_weight(config: &[(String, i32)], license_name: &str) -> Option<i3
     None
 }
 
+/// Licenses of a finding that count towards the risk score. A compound
+/// expression counts as the licenses it obliges to, each `OR` resolved to
+/// its lowest-weighted alternative: `GPL-2.0-only OR MIT` counts as MIT.
+/// Anything else counts as the license with the finding's exception.
+pub(crate) fn risk_terms(
+    config: &[(String, i32)],
+    license_name: &str,
+    exception: Option<&str>,
+) -> Vec<LicenseTerm> {
+    if exception.is_none() && license_expression::is_compound(license_name) {
+        if let Some(terms) =
+            license_expression::resolve(license_name, |term| term_weight(config, term))
+        {
+            return terms;
+        }
+    }
+    vec![LicenseTerm {
+        license: license_name.to_string(),
+        exception: exception.map(str::to_string),
+    }]
+}
+
+/// Weight of a license within the score; exceptions halve the weight of
+/// copyleft licenses
+fn term_weight(config: &[(String, i32)], term: &LicenseTerm) -> i32 {
+    let weight = get_license_weight(config, &term.license).unwrap_or(0);
+    if term.exception.is_some() && is_copyleft(&term.license) {
+        weight / 2
+    } else {
+        weight
+    }
+}
+
+/// Risk weight of a license name or expression, as counted in the score
+pub(crate) fn license_weight(config: &[(String, i32)], license_name: &str) -> i32 {
+    risk_terms(config, license_name, None)
+        .iter()
+        .map(|term| term_weight(config, term))
+        .sum()
+}
+
+fn is_spdx_expression(license_name: &str) -> bool {
+    license_expression::is_compound(license_name)
+        && license_expression::resolve(license_name, |_| 0).is_some()
+}
+
+/// Whether a license is copyleft. An expression is when it obliges to a
+/// copyleft license whichever `OR` alternative is chosen.
+pub(crate) fn is_copyleft_license(license_name: &str) -> bool {
+    if license_expression::is_compound(license_name) {
+        if let Some(terms) =
+            license_expression::resolve(license_name, |term| is_copyleft(&term.license) as i32)
+        {
+            return terms.iter().any(|term| is_copyleft(&term.license));
+        }
+    }
+    is_copyleft(license_name)
+}
+
 /// Check if license is copyleft
-fn is_copyleft(license_name: &str) -> bool {
+pub(crate) fn is_copyleft(license_name: &str) -> bool {
     let copyleft_patterns = [
         "GPL", "AGPL", "LGPL", "MPL", "EPL", "CDDL", "CPL", "Sleepycat",
     ];
@@ -400,3 +733,103 @@ fn is_unknown_or_proprietary(license_name: &str) -> bool {
         .iter()
         .any(|pattern| license_name.contains(pattern))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_like_match() {
+        assert!(like_match("%/tests/%", "crate/tests/crypto.rs"));
+        assert!(like_match("tests/%", "tests/crypto.rs"));
+        assert!(like_match("%.md", "docs/readme.md"));
+        assert!(like_match("%_test.%", "pkg/aes_test.go"));
+        assert!(!like_match("%/tests/%", "src/crypto.rs"));
+        assert!(like_match("mit", "mit"));
+    }
+
+    #[test]
+    fn test_classify_ecc_path() {
+        let rules = vec![
+            ("%/tests/%".to_string(), "test".to_string(), 0.25),
+            ("%.md".to_string(), "docs".to_string(), 0.1),
+        ];
+        assert_eq!(classify_ecc_path(&rules, "/scans/repo/tests/aes.rs"), ("test", 0.25));
+        assert_eq!(classify_ecc_path(&rules, "README.md"), ("docs", 0.1));
+        assert_eq!(classify_ecc_path(&rules, "src/aes.rs"), ("shipped", 1.0));
+    }
+
+    fn config() -> Vec<(String, i32)> {
+        [("AGPL%", 12), ("GPL-2.0%", 10), ("MPL%", 5), ("MIT", 0), ("Apache-2.0", 0)]
+            .iter()
+            .map(|(pattern, weight)| (pattern.to_string(), *weight))
+            .collect()
+    }
+
+    #[test]
+    fn test_or_alternatives_are_not_scored_as_copyleft() {
+        let config = config();
+        let licenses = |name: &str, exception: Option<&str>| -> Vec<(String, Option<String>)> {
+            risk_terms(&config, name, exception)
+                .into_iter()
+                .map(|t| (t.license, t.exception))
+                .collect()
+        };
+
+        assert_eq!(licenses("GPL-2.0 OR MIT", None), vec![("MIT".to_string(), None)]);
+        assert_eq!(licenses("MPL-2.0 or AGPL-3.0-only", None), vec![("MPL-2.0".to_string(), None)]);
+        assert_eq!(
+            licenses("GPL-2.0-only WITH Classpath-exception-2.0", None),
+            vec![(
+                "GPL-2.0-only".to_string(),
+                Some("Classpath-exception-2.0".to_string())
+            )]
+        );
+        assert_eq!(
+            licenses("GPL-2.0-only", Some("Classpath-exception-2.0")),
+            vec![(
+                "GPL-2.0-only".to_string(),
+                Some("Classpath-exception-2.0".to_string())
+            )]
+        );
+        // Names that are not expressions are left to the pattern matching
+        assert_eq!(licenses("GPL", None), vec![("GPL".to_string(), None)]);
+
+        assert_eq!(license_weight(&config, "GPL-2.0-only OR MIT"), 0);
+        assert_eq!(license_weight(&config, "GPL-2.0-only AND MPL-2.0"), 15);
+        assert_eq!(license_weight(&config, "GPL-2.0-only WITH Classpath-exception-2.0"), 5);
+        assert_eq!(license_weight(&config, "MIT/Apache-2.0"), 0);
+    }
+
+    #[test]
+    fn test_is_copyleft_license() {
+        assert!(is_copyleft_license("GPL-3.0-only"));
+        assert!(!is_copyleft_license("GPL-2.0-only OR MIT"));
+        assert!(!is_copyleft_license("MIT OR GPL-2.0-only"));
+        assert!(is_copyleft_license("GPL-2.0-only OR LGPL-2.1-only"));
+        assert!(is_copyleft_license("MIT AND GPL-2.0-only"));
+        assert!(is_spdx_expression("MIT OR Apache-2.0"));
+        assert!(!is_spdx_expression("MIT"));
+    }
+
+    #[test]
+    fn test_config_version() {
+        let weights = vec![("%GPL%".to_string(), 25), ("%MIT%".to_string(), 0)];
+        let rules = vec![
+            ("%/tests/%".to_string(), "test".to_string(), 0.2),
+            ("docs/%".to_string(), "docs".to_string(), 0.1),
+        ];
+        let version = config_version(&weights, &rules);
+        assert_eq!(version.len(), 16);
+
+        let reordered: Vec<_> = weights.iter().rev().cloned().collect();
+        assert_eq!(config_version(&reordered, &rules), version);
+
+        let mut reweighted = weights.clone();
+        reweighted[0].1 = 30;
+        assert_ne!(config_version(&reweighted, &rules), version);
+        // The first matching path rule wins, so their order counts
+        let reprioritized: Vec<_> = rules.iter().rev().cloned().collect();
+        assert_ne!(config_version(&weights, &reprioritized), version);
+    }
+}
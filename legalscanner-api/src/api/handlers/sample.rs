@@ -0,0 +1,160 @@
+use crate::{
+    api::models::{ResultSampleResponse, SampleStratum, SampledFinding},
+    db::models::{Scan, ScanResult},
+    error::AppError,
+    sampling::{self, STRATIFY_BY},
+    storage::ResultQuery,
+    waiver, AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::scans::RESULT_TYPES;
+
+const DEFAULT_SAMPLE_SIZE: usize = 50;
+const MAX_SAMPLE_SIZE: usize = 1000;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ResultSampleQueryParams {
+    /// Sample size (default 50, at most 1000)
+    n: Option<usize>,
+    /// license (default), type, severity or none
+    stratify: Option<String>,
+    /// Only sample findings of this result type
+    #[serde(rename = "type")]
+    result_type: Option<String>,
+    /// Draw the same sample again
+    seed: Option<u64>,
+}
+
+/// GET /api/v1/scans/:id/results/sample - Random sample of a scan's
+/// findings for spot-checking. Findings are grouped by `stratify` and the
+/// sample takes from each group in turn, so small groups are covered in
+/// full before large ones fill the rest.
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/results/sample",
+    tag = "results",
+    params(("id" = String, Path, description = "Scan ID"), ResultSampleQueryParams),
+    responses(
+        (status = 200, body = ResultSampleResponse),
+        (status = 400, description = "Invalid sample size, stratification or type", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_results_sample(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<ResultSampleQueryParams>,
+) -> Result<Json<ResultSampleResponse>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let n = params.n.unwrap_or(DEFAULT_SAMPLE_SIZE);
+    if !(1..=MAX_SAMPLE_SIZE).contains(&n) {
+        return Err(AppError::Validation(format!(
+            "n must be between 1 and {}",
+            MAX_SAMPLE_SIZE
+        )));
+    }
+
+    let stratify = params.stratify.unwrap_or_else(|| "license".to_string());
+    if !STRATIFY_BY.contains(&stratify.as_str()) {
+        return Err(AppError::Validation(format!(
+            "Invalid stratify '{}'. Must be one of {}",
+            stratify,
+            STRATIFY_BY.join(", ")
+        )));
+    }
+
+    if let Some(result_type) = params.result_type.as_deref() {
+        if !RESULT_TYPES.contains(&result_type) {
+            return Err(AppError::Validation(format!(
+                "Invalid type '{}'. Must be one of {}",
+                result_type,
+                RESULT_TYPES.join(", ")
+            )));
+        }
+    }
+
+    let query = ResultQuery {
+        result_type: params.result_type,
+        ..Default::default()
+    };
+    let results = state.result_store.query_results(&id, &query).await?;
+    let total = results.len();
+
+    let mut rng = match params.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let strata = sampling::sample(
+        results,
+        |r| sampling::stratum_key(r, &stratify),
+        n,
+        &mut rng,
+    );
+
+    let mut summaries = Vec::new();
+    let mut sampled = Vec::new();
+    let mut stratum_keys = Vec::new();
+    for stratum in strata {
+        let count = stratum.sampled.len();
+        stratum_keys.extend(std::iter::repeat_n(stratum.key.clone(), count));
+        sampled.extend(stratum.sampled);
+        summaries.push(SampleStratum {
+            key: stratum.key,
+            total: stratum.total,
+            sampled: count,
+        });
+    }
+
+    waiver::mark(&state.db, &scan, &mut sampled).await?;
+
+    let findings = sampled
+        .into_iter()
+        .zip(stratum_keys)
+        .map(|(result, stratum)| sampled_finding(result, stratum))
+        .collect();
+
+    Ok(Json(ResultSampleResponse {
+        scan_id: scan.id,
+        stratify,
+        total,
+        strata: summaries,
+        findings,
+    }))
+}
+
+fn sampled_finding(result: ScanResult, stratum: String) -> SampledFinding {
+    let package = result
+        .package_name
+        .map(|name| match result.package_version {
+            Some(version) => format!("{}@{}", name, version),
+            None => name,
+        });
+
+    SampledFinding {
+        id: result.id,
+        stratum,
+        result_type: result.result_type,
+        file_path: result.file_path,
+        license: result.license_name,
+        spdx_id: result.license_spdx_id,
+        confidence: result.confidence,
+        copyright: result.copyright_statement,
+        severity: result.risk_severity,
+        check_id: result.ecc_check_id,
+        line_number: result.ecc_line_number,
+        package,
+        review_status: result.review_status,
+        waiver_id: result.waiver_id,
+    }
+}
@@ -1,7 +1,14 @@
 use crate::{
-    db::models::{Scan, ScanResult},
+    custom_fields,
+    db::models::{Scan, ScanFileSwhid, ScanRepositoryMetadata, ScanResult},
     error::AppError,
-    export::{spdx, SbomFormat},
+    export::{
+        pipeline::{ExportOutput, ExportPipeline},
+        processors::{CustomFields, SoftwareHeritageIds},
+        sarif::relative_uri,
+        SbomFormat, SpdxVersion,
+    },
+    swhid::revision_swhid,
     AppState,
 };
 use axum::{
@@ -10,14 +17,33 @@ use axum::{
     http::{header, Response, StatusCode},
 };
 use serde::Deserialize;
+use utoipa::IntoParams;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SbomQueryParams {
     #[serde(default)]
     format: SbomFormat,
+    #[serde(default)]
+    spdx_version: SpdxVersion,
+    /// Only include files under this repository-relative directory
+    path_prefix: Option<String>,
+    /// Comma-separated repository-relative directories to leave out
+    exclude: Option<String>,
 }
 
 /// GET /api/v1/scans/:id/sbom - Export scan results as SPDX/SBOM
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/sbom",
+    tag = "exports",
+    params(("id" = String, Path, description = "Scan ID"), SbomQueryParams),
+    responses(
+        (status = 200, description = "SPDX document in the requested format", content_type = "application/json"),
+        (status = 400, description = "Scan is not completed yet", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
 pub async fn get_scan_sbom(
     State(state): State<AppState>,
     Path(scan_id): Path<String>,
@@ -37,49 +63,171 @@ pub async fn get_scan_sbom(
     }
 
     // Fetch all scan results
-    let results = ScanResult::find_by_scan_id(&state.db, &scan_id).await?;
+    let results = state.result_store.load_results(&scan_id).await?;
 
     if results.is_empty() {
         return Err(AppError::NotFound("No scan results found".to_string()));
     }
 
-    // Build SPDX document
-    let spdx_doc = spdx::build_spdx_document(&scan, &results)?;
-
-    // Serialize to requested format
-    let (content, content_type, extension) = match params.format {
-        SbomFormat::Json => {
-            let json = serde_json::to_string_pretty(&spdx_doc)
-                .map_err(|e| AppError::Internal(format!("Failed to serialize SPDX to JSON: {}", e)))?;
-            (json, params.format.content_type(), params.format.file_extension())
-        }
-        SbomFormat::Yaml => {
-            let yaml = serde_yaml::to_string(&spdx_doc)
-                .map_err(|e| AppError::Internal(format!("Failed to serialize SPDX to YAML: {}", e)))?;
-            (yaml, params.format.content_type(), params.format.file_extension())
-        }
-    };
+    // Restrict the document to a sub-directory of the repository if requested
+    let results = filter_by_path(results, &scan_id, &params);
+    if results.is_empty() {
+        return Err(AppError::NotFound(
+            "No scan results match the requested paths".to_string(),
+        ));
+    }
 
-    // Extract repository name for filename
-    let repo_name = scan
-        .git_url
-        .trim_end_matches(".git")
-        .rsplit('/')
-        .next()
-        .unwrap_or("repository");
+    // Normalize, post-process and serialize. The scan's custom field values
+    // follow the configured fields in the creator comment; the recorded
+    // SWHIDs identify the commit and files.
+    let custom_fields = custom_fields::report_fields(&state.db, &scan_id).await?;
+    let revision = ScanRepositoryMetadata::find_by_scan_id(&state.db, &scan_id)
+        .await?
+        .and_then(|m| m.commit_sha)
+        .map(|sha| revision_swhid(&sha));
+    let swhids = ScanFileSwhid::find_by_scan_id(&state.db, &scan_id).await?;
+    let output = ExportPipeline::from_config(&state.config)
+        .with_processor(CustomFields::new(custom_fields))
+        .with_processor(SoftwareHeritageIds::new(revision, swhids))
+        .export_sbom(&scan, &results, params.format, params.spdx_version)?;
 
-    let filename = format!("{}-sbom.spdx.{}", repo_name, extension);
+    export_response(output)
+}
 
-    // Build response with proper headers
-    let response = Response::builder()
+/// Send a serialized export as a file download
+pub(crate) fn export_response(output: ExportOutput) -> Result<Response<Body>, AppError> {
+    let mut response = Response::builder()
         .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CONTENT_TYPE, output.content_type)
         .header(
             header::CONTENT_DISPOSITION,
-            format!("attachment; filename=\"{}\"", filename),
-        )
-        .body(Body::from(content))
-        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))?;
+            format!("attachment; filename=\"{}\"", output.file_name),
+        );
+    for (name, value) in &output.headers {
+        response = response.header(name.as_str(), value.as_str());
+    }
+
+    response
+        .body(Body::from(output.content))
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))
+}
+
+/// Keep results whose repository-relative path lies under `path_prefix`
+/// and outside every `exclude` directory
+fn filter_by_path(
+    results: Vec<ScanResult>,
+    scan_id: &str,
+    params: &SbomQueryParams,
+) -> Vec<ScanResult> {
+    let prefix = params
+        .path_prefix
+        .as_deref()
+        .map(normalize_dir)
+        .filter(|p| !p.is_empty());
+    let excludes: Vec<&str> = params
+        .exclude
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(normalize_dir)
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    if prefix.is_none() && excludes.is_empty() {
+        return results;
+    }
+
+    results
+        .into_iter()
+        .filter(|result| {
+            let path = relative_uri(&result.file_path, scan_id);
+            prefix.is_none_or(|p| is_under(&path, p))
+                && !excludes.iter().any(|e| is_under(&path, e))
+        })
+        .collect()
+}
+
+fn normalize_dir(dir: &str) -> &str {
+    dir.trim()
+        .trim_start_matches("./")
+        .trim_start_matches('/')
+        .trim_end_matches('/')
+}
 
-    Ok(response)
+/// Whether `path` is `dir` itself or lies beneath it. Matches whole path
+/// components so `lib` does not match `library/`.
+fn is_under(path: &str, dir: &str) -> bool {
+    path.strip_prefix(dir)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(file: &str) -> ScanResult {
+        ScanResult {
+            license_name: Some("MIT".to_string()),
+            license_spdx_id: Some("MIT".to_string()),
+            ..ScanResult::for_test("license", file)
+        }
+    }
+
+    fn params(path_prefix: Option<&str>, exclude: Option<&str>) -> SbomQueryParams {
+        SbomQueryParams {
+            format: SbomFormat::default(),
+            spdx_version: SpdxVersion::default(),
+            path_prefix: path_prefix.map(str::to_string),
+            exclude: exclude.map(str::to_string),
+        }
+    }
+
+    fn paths(results: Vec<ScanResult>) -> Vec<String> {
+        results.into_iter().map(|r| r.file_path).collect()
+    }
+
+    fn sample() -> Vec<ScanResult> {
+        vec![
+            result("/tmp/workspace/scan-1/services/api/main.rs"),
+            result("/tmp/workspace/scan-1/services/api/vendor/lib.rs"),
+            result("/tmp/workspace/scan-1/services/apigw/main.rs"),
+            result("services/web/index.js"),
+        ]
+    }
+
+    #[test]
+    fn test_filter_by_path_prefix() {
+        let filtered = filter_by_path(sample(), "scan-1", &params(Some("./services/api/"), None));
+        assert_eq!(
+            paths(filtered),
+            vec![
+                "/tmp/workspace/scan-1/services/api/main.rs",
+                "/tmp/workspace/scan-1/services/api/vendor/lib.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filter_by_path_exclude() {
+        let filtered = filter_by_path(
+            sample(),
+            "scan-1",
+            &params(Some("services"), Some("services/api/vendor, services/web")),
+        );
+        assert_eq!(
+            paths(filtered),
+            vec![
+                "/tmp/workspace/scan-1/services/api/main.rs",
+                "/tmp/workspace/scan-1/services/apigw/main.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filter_by_path_without_params_keeps_everything() {
+        assert_eq!(
+            filter_by_path(sample(), "scan-1", &params(None, Some(""))).len(),
+            4
+        );
+    }
 }
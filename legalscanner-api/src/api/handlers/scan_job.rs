@@ -1,12 +1,35 @@
 use crate::{
-    api::handlers::risk::calculate_risk_score,
-    db::models::{Scan, ScanResult as DbScanResult},
-    git::{clone_repository, workspace::Workspace},
-    scanner::Scanner,
-    AppState,
+    api::handlers::risk::{calculate_risk_score, store_risk_assessment},
+    codeowners, copyright_holders, coverage, credentials, curation,
+    db::models::{Scan, ScanFileAuthor, ScanHistoryFinding},
+    git::{
+        clone_repository, clone_repository_sparse, validate_git_url, workspace::Workspace, GitAuth,
+        Revision,
+    },
+    lifecycle, local_scan,
+    orchestrator::ScanOutcome,
+    policy,
+    progress::record_event,
+    repo_metadata,
+    scanner::{
+        authorship::scan_authorship,
+        declared::{detect_declared_licenses, DeclaredLicense},
+        history::scan_history,
+        license_exception,
+        limits::{limit_warnings, measure_checkout, SoftLimits},
+        merge::{corroborate, tag_origin},
+        semgrep::SemgrepScanner,
+        LicenseCategory, LicenseFinding, ScanError, ScanSlot, Scanner,
+    },
+    screening::screen_if_required,
+    submodules, swhid, telemetry, upload, webhooks, AppState,
 };
-use sqlx::SqlitePool;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
+
+/// Timeline detail for scanners a partial re-run does not repeat
+const RERUN_SKIP_DETAIL: &str = "completed in the original scan";
 
 /// Execute a complete scan job in the background
 pub async fn execute_scan_job(scan_id: String, state: AppState) {
@@ -31,23 +54,47 @@ pub async fn execute_scan_job(scan_id: String, state: AppState) {
         return;
     }
 
-    // Execute the scan
-    if let Err(e) = execute_scan_internal(scan_id.clone(), scan.git_url, scan.git_token, state.clone()).await {
+    // Per-scan Semgrep ruleset override
+    let semgrep_rulesets: Option<Vec<String>> = scan
+        .semgrep_rulesets
+        .as_deref()
+        .and_then(|r| serde_json::from_str(r).ok());
+
+    // Execute the scan, with the decrypted credential or the raw token
+    let result = match credentials::scan_auth(&state, &scan).await {
+        Ok(auth) => execute_scan_internal(scan_id.clone(), scan.git_url, auth, semgrep_rulesets, scan.quick, scan.scan_path, state.clone()).await,
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = result {
         tracing::error!("Scan job failed: {}", e);
 
         // Update status to failed
         let _ = Scan::update_status(&state.db, &scan_id, "failed", Some(e.to_string())).await;
+        let _ = Scan::set_error_class(&state.db, &scan_id, &telemetry::error_class(e.as_ref())).await;
+        let _ = record_event(&state, &scan_id, "failed", None, Some(&e.to_string())).await;
+    } else {
+        let _ = record_event(&state, &scan_id, "completed", None, None).await;
     }
 
+    webhooks::notify(&state, &scan_id).await;
+
     tracing::info!("Scan job completed for scan {}", scan_id);
 }
 
 async fn execute_scan_internal(
     scan_id: String,
     git_url: String,
-    git_token: Option<String>,
+    auth: Option<GitAuth>,
+    semgrep_rulesets: Option<Vec<String>>,
+    quick: bool,
+    scan_path: Option<String>,
     state: AppState,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let semgrep_scanner: Arc<dyn Scanner> = match semgrep_rulesets {
+        Some(rulesets) => Arc::new(SemgrepScanner::from_config(&state.config).with_rulesets(rulesets)),
+        None => state.semgrep_scanner.clone(),
+    };
+
     // 1. Create workspace
     let workspace = Workspace::new(state.config.temp_workspace_dir.clone(), scan_id.clone());
     let workspace_path = workspace.create().await?;
@@ -55,114 +102,120 @@ async fn execute_scan_internal(
 
     // Ensure cleanup happens
     let cleanup_result = async {
-        // 2. Clone repository
-        tracing::info!("Cloning repository: {}", git_url);
-        clone_repository(&git_url, &workspace_path, git_token.as_deref()).await?;
-        tracing::info!("Repository cloned successfully");
-
-        // 3. Run both scanners in parallel
-        tracing::info!("Starting Fossology and Semgrep scans in parallel");
-
-        // Mark both scanners as in progress
-        let _ = Scan::update_fossology_status(&state.db, &scan_id, "in_progress", None).await;
-        let _ = Scan::update_semgrep_status(&state.db, &scan_id, "in_progress", None).await;
-        let _ = Scan::update_overall_status(&state.db, &scan_id).await;
-
-        // Clone state for parallel execution
-        let fossology_state = state.clone();
-        let semgrep_state = state.clone();
-        let fossology_scan_id = scan_id.clone();
-        let semgrep_scan_id = scan_id.clone();
-        let fossology_path = workspace_path.clone();
-        let semgrep_path = workspace_path.clone();
-
-        // Run scanners in parallel
-        let (fossology_result, semgrep_result) = tokio::join!(
-            async {
-                let result = fossology_state.fossology_scanner.scan(&fossology_path).await;
-                match &result {
-                    Ok(results) => {
-                        tracing::info!("Fossology scan completed with {} results", results.len());
-                        let _ = Scan::update_fossology_status(&fossology_state.db, &fossology_scan_id, "completed", None).await;
-                    }
-                    Err(e) => {
-                        tracing::error!("Fossology scan failed: {}", e);
-                        let _ = Scan::update_fossology_status(&fossology_state.db, &fossology_scan_id, "failed", Some(e.to_string())).await;
-                    }
-                }
-                let _ = Scan::update_overall_status(&fossology_state.db, &fossology_scan_id).await;
-                result
-            },
-            async {
-                let result = semgrep_state.semgrep_scanner.scan(&semgrep_path).await;
-                match &result {
-                    Ok(results) => {
-                        tracing::info!("Semgrep scan completed with {} results", results.len());
-                        let _ = Scan::update_semgrep_status(&semgrep_state.db, &semgrep_scan_id, "completed", None).await;
-                    }
-                    Err(e) => {
-                        tracing::error!("Semgrep scan failed: {}", e);
-                        let _ = Scan::update_semgrep_status(&semgrep_state.db, &semgrep_scan_id, "failed", Some(e.to_string())).await;
-                    }
-                }
-                let _ = Scan::update_overall_status(&semgrep_state.db, &semgrep_scan_id).await;
-                result
-            }
-        );
-
-        // Get results (fail if either scanner failed)
-        let mut scan_results = fossology_result?;
-        let semgrep_results = semgrep_result?;
-
-        tracing::info!("Parallel scans completed: {} Fossology results, {} Semgrep results",
-            scan_results.len(), semgrep_results.len());
-
-        // 4. Merge Semgrep results into Fossology results
-        merge_scan_results(&mut scan_results, semgrep_results);
-        tracing::info!("Merged results, total files: {}", scan_results.len());
-
-        // 5. Store results in database
-        tracing::info!("Storing results in database");
-        store_scan_results(&state.db, &scan_id, scan_results).await?;
-        tracing::info!("Results stored successfully");
-
-        // 6. Update overall status to completed (should already be set by individual scanners)
-        Scan::update_overall_status(&state.db, &scan_id).await?;
-        tracing::info!("Scan status updated to completed");
-
-        // 7. Calculate and store risk assessment
-        tracing::info!("Calculating risk assessment for scan {}", scan_id);
-        match calculate_risk_score(&state.db, &scan_id).await {
-            Ok(risk_assessment) => {
-                tracing::info!(
-                    "Risk assessment calculated: score={}, level={}",
-                    risk_assessment.score,
-                    risk_assessment.level
-                );
-
-                // Serialize risk factors to JSON
-                let risk_factors_json = serde_json::to_string(&risk_assessment.factors)
-                    .unwrap_or_else(|_| "[]".to_string());
-
-                // Update scan with risk assessment
-                if let Err(e) = Scan::update_risk_assessment(
-                    &state.db,
-                    &scan_id,
-                    risk_assessment.score,
-                    &risk_assessment.level,
-                    &risk_factors_json,
+        // 2. Clone repository, extract the uploaded archive, or scan a local
+        // directory in place
+        let local_root = if local_scan::is_local(&git_url) {
+            Some(local_scan::resolve(
+                &state.config.local_scan_base_dirs,
+                &git_url,
+            )?)
+        } else {
+            None
+        };
+
+        if let Some(root) = &local_root {
+            tracing::info!("Scanning local directory {:?} in place", root);
+        } else if upload::is_upload(&git_url) {
+            let detail = "extracting uploaded archive";
+            let _ = record_event(&state, &scan_id, "clone_started", None, Some(detail)).await;
+            let stats = upload::extract_upload(&state.config, &scan_id, &workspace_path).await?;
+            let detail = format!("{} files extracted", stats.files);
+            let _ = record_event(&state, &scan_id, "clone_finished", None, Some(&detail)).await;
+            tracing::info!(
+                "Archive extracted: {} files, {} bytes, {} links skipped",
+                stats.files,
+                stats.bytes,
+                stats.skipped
+            );
+        } else {
+            validate_git_url(&git_url, &state.config.git_url_schemes)
+                .map_err(|e| git2::Error::from_str(&e))?;
+            tracing::info!("Cloning repository: {}", git_url);
+            let _ = record_event(&state, &scan_id, "clone_started", None, None).await;
+            let progress = state.progress.reporter(&scan_id, "git");
+            let timeout = (state.config.clone_timeout_secs > 0)
+                .then(|| std::time::Duration::from_secs(state.config.clone_timeout_secs));
+            let scan = Scan::find_by_id(&state.db, &scan_id).await?;
+            let revision = Revision {
+                branch: scan.as_ref().and_then(|s| s.branch.as_deref()),
+                commit: scan.as_ref().and_then(|s| s.commit_sha.as_deref()),
+            };
+            if let Some(scan_path) = &scan_path {
+                // Only the subtree is scanned; CODEOWNERS is read from the root
+                let mut paths = vec![scan_path.as_str()];
+                paths.extend(codeowners::LOCATIONS);
+                clone_repository_sparse(
+                    &git_url,
+                    &workspace_path,
+                    auth.as_ref(),
+                    revision,
+                    &paths,
+                    &progress,
+                    timeout,
                 )
-                .await
-                {
-                    tracing::error!("Failed to store risk assessment: {}", e);
-                } else {
-                    tracing::info!("Risk assessment stored successfully");
-                }
+                .await?;
+            } else {
+                clone_repository(
+                    &git_url,
+                    &workspace_path,
+                    auth.as_ref(),
+                    revision,
+                    &progress,
+                    timeout,
+                )
+                .await?;
             }
-            Err(e) => {
-                tracing::error!("Failed to calculate risk assessment: {}", e);
-                // Don't fail the entire scan if risk calculation fails
+
+            // Submodules are checked out with their own token when one was given
+            if let Some(scan) = scan.filter(|s| s.submodules) {
+                let submodule_auth = credentials::submodule_token(&state, &scan)?
+                    .map(GitAuth::Token)
+                    .or(auth.clone());
+                let warnings =
+                    submodules::checkout(&state, &scan_id, &workspace_path, submodule_auth.as_ref())
+                        .await?;
+                append_warnings(&state, &scan_id, warnings).await;
+            }
+            let _ = record_event(&state, &scan_id, "clone_finished", None, None).await;
+            tracing::info!("Repository cloned successfully");
+        }
+
+        let mut scan_root = local_root.clone().unwrap_or_else(|| workspace_path.clone());
+
+        // Read before narrowing, CODEOWNERS lives at the repository root
+        codeowners::record(&state, &scan_id, &scan_root).await;
+        if local_root.is_none() && !upload::is_upload(&git_url) {
+            repo_metadata::record(
+                &state,
+                &scan_id,
+                &git_url,
+                auth.as_ref().and_then(GitAuth::api_token),
+                &workspace_path,
+            )
+            .await;
+        }
+
+        // Scans limited to a subdirectory only keep that subtree. Local
+        // directories are left untouched and scanned from the subdirectory.
+        if let Some(scan_path) = &scan_path {
+            if let Some(root) = &local_root {
+                let subdir = root.join(scan_path);
+                scan_root =
+                    local_scan::resolve(std::slice::from_ref(root), &subdir.to_string_lossy())?;
+            } else {
+                workspace.narrow_to(scan_path).await?;
             }
+            tracing::info!("Scan limited to {}", scan_path);
+        }
+
+        record_limit_warnings(&state, &scan_id, &scan_root).await;
+        coverage::record(&state, &scan_id, &scan_root).await;
+        swhid::record(&state, &scan_id, &git_url, &scan_root).await;
+
+        if quick {
+            quick_scan_checkout(&state, &scan_id, semgrep_scanner, &scan_root).await?;
+        } else {
+            scan_checkout(&state, &scan_id, semgrep_scanner, &scan_root).await?;
         }
 
         Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
@@ -177,7 +230,495 @@ async fn execute_scan_internal(
     cleanup_result
 }
 
-/// Merge Semgrep ECC results into Fossology results
+/// Flag scans of repositories above the configured soft limits. The scan
+/// goes ahead either way; measuring failures are only logged.
+async fn record_limit_warnings(state: &AppState, scan_id: &str, workspace_path: &Path) {
+    let limits = SoftLimits::from_config(&state.config);
+    if limits.is_disabled() {
+        return;
+    }
+
+    let stats = match measure_checkout(workspace_path).await {
+        Ok(stats) => stats,
+        Err(e) => {
+            tracing::warn!("Failed to measure checkout for scan {}: {}", scan_id, e);
+            return;
+        }
+    };
+
+    append_warnings(state, scan_id, limit_warnings(stats, limits)).await;
+}
+
+/// Add warnings to those already recorded for the scan, e.g. when it was
+/// created as a partial re-run
+async fn append_warnings(state: &AppState, scan_id: &str, warnings: Vec<String>) {
+    if warnings.is_empty() {
+        return;
+    }
+
+    for warning in &warnings {
+        tracing::warn!("Scan {}: {}", scan_id, warning);
+    }
+
+    let mut all_warnings = match Scan::find_by_id(&state.db, scan_id).await {
+        Ok(Some(scan)) => scan.warning_list(),
+        _ => Vec::new(),
+    };
+    all_warnings.extend(warnings);
+    if let Err(e) = Scan::set_warnings(&state.db, scan_id, &all_warnings).await {
+        tracing::error!("Failed to store warnings for scan {}: {}", scan_id, e);
+    }
+}
+
+/// Run every configured scanner over a checked-out repository, store the
+/// merged results and compute the risk assessment. Shared by the background
+/// scan job and the library-facing `ScanOrchestrator`.
+pub(crate) async fn scan_checkout(
+    state: &AppState,
+    scan_id: &str,
+    semgrep_scanner: Arc<dyn Scanner>,
+    workspace_path: &Path,
+) -> Result<ScanOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    // 3. Run both scanners in parallel
+    tracing::info!("Starting Fossology and Semgrep scans in parallel");
+
+    // Partial re-runs have the scanners that completed before marked as skipped
+    let scan = Scan::find_by_id(&state.db, scan_id).await.ok().flatten();
+    let skip_fossology = scan.as_ref().is_some_and(|s| s.fossology_status == "skipped");
+    let skip_semgrep = scan.as_ref().is_some_and(|s| s.semgrep_status == "skipped");
+    let due_diligence = scan.as_ref().is_some_and(|s| s.due_diligence);
+    let git_authorship = scan.as_ref().is_some_and(|s| s.git_authorship);
+
+    // Mark both scanners as in progress
+    if !skip_fossology {
+        let _ = Scan::update_fossology_status(&state.db, scan_id, "in_progress", None).await;
+    }
+    if !skip_semgrep {
+        let _ = Scan::update_semgrep_status(&state.db, scan_id, "in_progress", None).await;
+    }
+    let _ = Scan::update_overall_status(&state.db, scan_id).await;
+
+    // Clone state for parallel execution
+    let fossology_state = state.clone();
+    let semgrep_state = state.clone();
+    let fossology_scan_id = scan_id.to_string();
+    let semgrep_scan_id = scan_id.to_string();
+    let fossology_path = workspace_path.to_path_buf();
+    let semgrep_path = workspace_path.to_path_buf();
+
+    // Run scanners in parallel
+    let (fossology_result, semgrep_result, malware_result) = tokio::join!(
+        async {
+            if skip_fossology {
+                let _ = record_event(&fossology_state, &fossology_scan_id, "scanner_skipped", Some("fossology"), Some(RERUN_SKIP_DETAIL)).await;
+                return Ok(Vec::new());
+            }
+            // Fossology is shared by all scans, so wait for a slot first
+            let slot = fossology_state.fossology_scanner.reserve_slot().await;
+            let _ = record_event(&fossology_state, &fossology_scan_id, "scanner_started", Some("fossology"), slot_wait_detail(&slot).as_deref()).await;
+            let result = match slot {
+                Ok(_slot) => {
+                    let progress = fossology_state.progress.reporter(&fossology_scan_id, "fossology");
+                    fossology_state.fossology_scanner.scan_with_progress(&fossology_path, &progress).await
+                }
+                Err(e) => Err(e),
+            };
+            match &result {
+                Ok(results) => {
+                    tracing::info!("Fossology scan completed with {} results", results.len());
+                    let _ = Scan::update_fossology_status(&fossology_state.db, &fossology_scan_id, "completed", None).await;
+                    let _ = record_event(&fossology_state, &fossology_scan_id, "scanner_finished", Some("fossology"), None).await;
+                }
+                Err(e) => {
+                    tracing::error!("Fossology scan failed: {}", e);
+                    let _ = Scan::update_fossology_status(&fossology_state.db, &fossology_scan_id, "failed", Some(e.to_string())).await;
+                    let _ = record_event(&fossology_state, &fossology_scan_id, "scanner_failed", Some("fossology"), Some(&e.to_string())).await;
+                }
+            }
+            let _ = Scan::update_overall_status(&fossology_state.db, &fossology_scan_id).await;
+            result
+        },
+        async {
+            if skip_semgrep {
+                let _ = record_event(&semgrep_state, &semgrep_scan_id, "scanner_skipped", Some("semgrep"), Some(RERUN_SKIP_DETAIL)).await;
+                return Ok(Vec::new());
+            }
+            let _ = record_event(&semgrep_state, &semgrep_scan_id, "scanner_started", Some("semgrep"), None).await;
+            let result = semgrep_scanner.scan(&semgrep_path).await;
+            match &result {
+                Ok(results) => {
+                    tracing::info!("Semgrep scan completed with {} results", results.len());
+                    let _ = Scan::update_semgrep_status(&semgrep_state.db, &semgrep_scan_id, "completed", None).await;
+                    let _ = record_event(&semgrep_state, &semgrep_scan_id, "scanner_finished", Some("semgrep"), None).await;
+                }
+                Err(e) => {
+                    tracing::error!("Semgrep scan failed: {}", e);
+                    let _ = Scan::update_semgrep_status(&semgrep_state.db, &semgrep_scan_id, "failed", Some(e.to_string())).await;
+                    let _ = record_event(&semgrep_state, &semgrep_scan_id, "scanner_failed", Some("semgrep"), Some(&e.to_string())).await;
+                }
+            }
+            let _ = Scan::update_overall_status(&semgrep_state.db, &semgrep_scan_id).await;
+            result
+        },
+        async {
+            // Optional malware scan, does not have its own status column
+            match &state.malware_scanner {
+                Some(scanner) => {
+                    let _ = record_event(state, scan_id, "scanner_started", Some(scanner.name()), None).await;
+                    let result = scanner.scan(workspace_path).await;
+                    match &result {
+                        Ok(results) => {
+                            tracing::info!("Malware scan completed, {} infected files", results.len());
+                            let _ = record_event(state, scan_id, "scanner_finished", Some(scanner.name()), None).await;
+                        }
+                        Err(e) => {
+                            tracing::error!("Malware scan failed: {}", e);
+                            let _ = record_event(state, scan_id, "scanner_failed", Some(scanner.name()), Some(&e.to_string())).await;
+                        }
+                    }
+                    result
+                }
+                None => Ok(Vec::new()),
+            }
+        }
+    );
+
+    // Get results (fail if either scanner failed)
+    let mut scan_results = fossology_result?;
+    let semgrep_results = semgrep_result?;
+    let malware_results = malware_result.map_err(|e| format!("Malware scan failed: {}", e))?;
+
+    tracing::info!("Parallel scans completed: {} Fossology results, {} Semgrep results",
+        scan_results.len(), semgrep_results.len());
+
+    // 4. Merge Semgrep and malware results into Fossology results, flag where
+    // the scanners agree or disagree, then let the configured strategy
+    // decide which license findings survive
+    let mut semgrep_results = semgrep_results;
+    tag_origin(&mut scan_results, "fossology");
+    tag_origin(&mut semgrep_results, "semgrep");
+    merge_scan_results(&mut scan_results, semgrep_results);
+    merge_scan_results(&mut scan_results, malware_results);
+    corroborate(&mut scan_results);
+    state.config.merge_strategy.apply(&mut scan_results);
+
+    // Documentation and dataset licenses are best-effort, a failure does not fail the scan
+    if let Some(documentation_results) =
+        run_optional_scanner(state, scan_id, state.documentation_scanner.as_ref(), workspace_path).await
+    {
+        merge_content_licenses(&mut scan_results, documentation_results);
+    }
+
+    // ML model artifacts and the licenses covering them
+    if let Some(model_results) =
+        run_optional_scanner(state, scan_id, state.model_scanner.as_ref(), workspace_path).await
+    {
+        merge_scan_results(&mut scan_results, model_results);
+    }
+
+    // Package inventory is optional and best-effort like the documentation scan
+    if let Some(package_scanner) = &state.package_scanner {
+        if let Some(package_results) =
+            run_optional_scanner(state, scan_id, package_scanner.as_ref(), workspace_path).await
+        {
+            merge_scan_results(&mut scan_results, package_results);
+        }
+    }
+    tracing::info!("Merged results, total files: {}", scan_results.len());
+
+    if due_diligence {
+        run_history_scan(state, scan_id, workspace_path).await;
+    }
+    if git_authorship {
+        run_authorship_scan(state, scan_id, workspace_path).await;
+    }
+
+    store_and_assess(state, scan_id, scan_results, false).await
+}
+
+/// Due-diligence history scan. Best-effort like the optional scanners: a
+/// failure is recorded on the timeline but does not fail the scan.
+async fn run_history_scan(state: &AppState, scan_id: &str, workspace_path: &Path) {
+    let _ = record_event(state, scan_id, "scanner_started", Some("history"), None).await;
+    let findings = match scan_history(workspace_path, state.config.due_diligence_max_commits).await {
+        Ok(findings) => findings,
+        Err(e) => {
+            tracing::warn!("History scan failed: {}", e);
+            let _ = record_event(state, scan_id, "scanner_failed", Some("history"), Some(&e.to_string())).await;
+            return;
+        }
+    };
+
+    tracing::info!("History scan completed with {} findings", findings.len());
+    match ScanHistoryFinding::store(&state.db, scan_id, &findings).await {
+        Ok(()) => {
+            let _ = record_event(state, scan_id, "scanner_finished", Some("history"), None).await;
+        }
+        Err(e) => {
+            tracing::error!("Failed to store history findings: {}", e);
+            let _ = record_event(state, scan_id, "scanner_failed", Some("history"), Some(&e.to_string())).await;
+        }
+    }
+}
+
+/// Attribute the files to their commit authors. Best-effort like the history
+/// scan.
+async fn run_authorship_scan(state: &AppState, scan_id: &str, workspace_path: &Path) {
+    let _ = record_event(state, scan_id, "scanner_started", Some("authorship"), None).await;
+    let authors =
+        match scan_authorship(workspace_path, state.config.git_authorship_max_commits).await {
+            Ok(authors) => authors,
+            Err(e) => {
+                tracing::warn!("Authorship scan failed: {}", e);
+                let _ = record_event(state, scan_id, "scanner_failed", Some("authorship"), Some(&e.to_string())).await;
+                return;
+            }
+        };
+
+    tracing::info!("Authorship scan attributed {} file authors", authors.len());
+    match ScanFileAuthor::store(&state.db, scan_id, &authors).await {
+        Ok(()) => {
+            let _ = record_event(state, scan_id, "scanner_finished", Some("authorship"), None).await;
+        }
+        Err(e) => {
+            tracing::error!("Failed to store file authors: {}", e);
+            let _ = record_event(state, scan_id, "scanner_failed", Some("authorship"), Some(&e.to_string())).await;
+        }
+    }
+}
+
+/// Time-boxed preview scan: Semgrep and declared license detection (root
+/// license files and package manifests) only, skipping Fossology. The risk
+/// assessment is flagged as preliminary.
+async fn quick_scan_checkout(
+    state: &AppState,
+    scan_id: &str,
+    semgrep_scanner: Arc<dyn Scanner>,
+    workspace_path: &Path,
+) -> Result<ScanOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    let time_limit = Duration::from_secs(state.config.quick_scan_timeout_secs);
+    tracing::info!("Starting quick scan, time limit {:?}", time_limit);
+
+    let _ = record_event(state, scan_id, "scanner_skipped", Some("fossology"), Some("quick scan")).await;
+    let _ = Scan::update_semgrep_status(&state.db, scan_id, "in_progress", None).await;
+    let _ = Scan::update_overall_status(&state.db, scan_id).await;
+    let _ = record_event(state, scan_id, "scanner_started", Some("semgrep"), None).await;
+
+    let _ = record_event(state, scan_id, "scanner_started", Some("declared"), None).await;
+
+    let (semgrep_result, declared) = tokio::join!(
+        tokio::time::timeout(time_limit, semgrep_scanner.scan(workspace_path)),
+        tokio::time::timeout(time_limit, detect_declared_licenses(workspace_path))
+    );
+
+    let semgrep_result = semgrep_result.unwrap_or_else(|_| {
+        Err(ScanError::Failed(format!(
+            "Quick scan time limit of {}s exceeded",
+            time_limit.as_secs()
+        )))
+    });
+    let mut scan_results = match semgrep_result {
+        Ok(results) => {
+            tracing::info!("Semgrep scan completed with {} results", results.len());
+            let _ = Scan::update_semgrep_status(&state.db, scan_id, "completed", None).await;
+            let _ = record_event(state, scan_id, "scanner_finished", Some("semgrep"), None).await;
+            results
+        }
+        Err(e) => {
+            tracing::error!("Semgrep scan failed: {}", e);
+            let _ = Scan::update_semgrep_status(&state.db, scan_id, "failed", Some(e.to_string())).await;
+            let _ = record_event(state, scan_id, "scanner_failed", Some("semgrep"), Some(&e.to_string())).await;
+            let _ = Scan::update_overall_status(&state.db, scan_id).await;
+            return Err(e.into());
+        }
+    };
+    let _ = Scan::update_overall_status(&state.db, scan_id).await;
+
+    tag_origin(&mut scan_results, "semgrep");
+    match declared {
+        Ok(declared) => {
+            let _ = record_event(state, scan_id, "scanner_finished", Some("declared"), None).await;
+            merge_scan_results(&mut scan_results, declared_license_results(declared));
+        }
+        Err(_) => {
+            tracing::warn!("Declared license detection exceeded the quick scan time limit");
+            let _ = record_event(state, scan_id, "scanner_failed", Some("declared"), Some("quick scan time limit exceeded")).await;
+        }
+    }
+    tracing::info!("Quick scan results, total files: {}", scan_results.len());
+
+    store_and_assess(state, scan_id, scan_results, true).await
+}
+
+/// Declared licenses as license findings on the file that declares them
+fn declared_license_results(declared: Vec<DeclaredLicense>) -> Vec<crate::scanner::ScanResult> {
+    declared
+        .into_iter()
+        .map(|d| crate::scanner::ScanResult {
+            file_path: d.source,
+            licenses: vec![LicenseFinding {
+                name: d.license.clone(),
+                spdx_id: Some(d.license),
+                confidence: 1.0,
+                category: LicenseCategory::Code,
+                provenance: Some("declared".to_string()),
+                agreement: None,
+                exception: None,
+            }],
+            copyrights: Vec::new(),
+            ecc_findings: Vec::new(),
+            malware_findings: Vec::new(),
+            package_findings: Vec::new(),
+            model_licenses: Vec::new(),
+        })
+        .collect()
+}
+
+/// Store merged results and compute the risk assessment, which is marked
+/// preliminary when not every scanner ran
+async fn store_and_assess(
+    state: &AppState,
+    scan_id: &str,
+    mut scan_results: Vec<crate::scanner::ScanResult>,
+    preliminary: bool,
+) -> Result<ScanOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    // Reviewed license conclusions override the scanners
+    if let Some(scan) = Scan::find_by_id(&state.db, scan_id).await? {
+        let curated = curation::apply(&state.db, &scan, &mut scan_results).await?;
+        if curated > 0 {
+            tracing::info!("Applied license curations to {} files", curated);
+        }
+    }
+    let exceptions = license_exception::attach(&mut scan_results);
+    if exceptions > 0 {
+        tracing::info!("Recorded license exceptions on {} files", exceptions);
+    }
+
+    // 5. Store results via the configured result store
+    tracing::info!("Storing results in {} result store", state.result_store.name());
+    state.result_store.store_results(scan_id, scan_results.clone()).await?;
+    if let Err(e) = crate::integrity::seal(state, scan_id).await {
+        tracing::warn!("Failed to seal the results of scan {}: {}", scan_id, e);
+    }
+    let _ = record_event(state, scan_id, "results_stored", None, Some(state.result_store.name())).await;
+    tracing::info!("Results stored successfully");
+
+    // 6. Update overall status to completed (should already be set by individual scanners)
+    Scan::update_overall_status(&state.db, scan_id).await?;
+    tracing::info!("Scan status updated to completed");
+
+    // 7. Calculate and store risk assessment
+    tracing::info!("Calculating risk assessment for scan {}", scan_id);
+    let risk = match calculate_risk_score(state, scan_id).await {
+        Ok(mut risk_assessment) => {
+            risk_assessment.preliminary = preliminary;
+            tracing::info!(
+                "Risk assessment calculated: score={}, level={}",
+                risk_assessment.score,
+                risk_assessment.level
+            );
+
+            // Update scan with risk assessment
+            if let Err(e) = store_risk_assessment(&state.db, scan_id, &risk_assessment).await {
+                tracing::error!("Failed to store risk assessment: {}", e);
+            } else {
+                tracing::info!("Risk assessment stored successfully");
+                let mut detail = format!("score={}, level={}", risk_assessment.score, risk_assessment.level);
+                if preliminary {
+                    detail.push_str(", preliminary");
+                }
+                let _ = record_event(state, scan_id, "risk_computed", None, Some(&detail)).await;
+            }
+            Some(risk_assessment)
+        }
+        Err(e) => {
+            tracing::error!("Failed to calculate risk assessment: {}", e);
+            // Don't fail the entire scan if risk calculation fails
+            None
+        }
+    };
+
+    // 8. Add the scan's copyright holders to the cross-scan registry
+    match Scan::find_by_id(&state.db, scan_id).await {
+        Ok(Some(scan)) => match copyright_holders::update_registry(&state.db, state.result_store.as_ref(), &scan).await {
+            Ok(count) => tracing::info!("Recorded {} copyright holders in the registry", count),
+            Err(e) => tracing::error!("Failed to update copyright holder registry: {}", e),
+        },
+        Ok(None) => {}
+        Err(e) => tracing::error!("Failed to load scan {} for holder registry: {}", scan_id, e),
+    }
+
+    // 9. Export control screening of scans with ECC findings above the threshold
+    match screen_if_required(state, scan_id).await {
+        Ok(Some(screening)) => tracing::info!("Export control screening {}", screening.status),
+        Ok(None) => {}
+        Err(e) => tracing::error!("Failed to request export control screening: {}", e),
+    }
+
+    // 10. Follow the findings across the scans of the repository
+    match lifecycle::record(state, scan_id).await {
+        Ok(report) => tracing::info!(
+            "Findings opened: {}, reopened: {}, resolved: {}",
+            report.opened,
+            report.reopened,
+            report.resolved
+        ),
+        Err(e) => tracing::error!("Failed to update finding lifecycle: {}", e),
+    }
+
+    // 11. Evaluate the scan against its license policy
+    if let Err(e) = policy::evaluate_scan(state, scan_id).await {
+        tracing::error!("Failed to evaluate license policy: {}", e);
+    }
+
+    Ok(ScanOutcome {
+        scan_id: scan_id.to_string(),
+        results: scan_results,
+        risk,
+    })
+}
+
+/// Run a best-effort scanner, recording timeline events. Failures are logged
+/// and yield `None` instead of failing the scan.
+async fn run_optional_scanner(
+    state: &AppState,
+    scan_id: &str,
+    scanner: &dyn Scanner,
+    path: &std::path::Path,
+) -> Option<Vec<crate::scanner::ScanResult>> {
+    let slot = scanner.reserve_slot().await;
+    let _ = record_event(state, scan_id, "scanner_started", Some(scanner.name()), slot_wait_detail(&slot).as_deref()).await;
+    let result = match slot {
+        Ok(_slot) => {
+            let progress = state.progress.reporter(scan_id, scanner.name());
+            scanner.scan_with_progress(path, &progress).await
+        }
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(mut results) => {
+            tracing::info!("{} scan completed with {} results", scanner.name(), results.len());
+            tag_origin(&mut results, scanner.name());
+            let _ = record_event(state, scan_id, "scanner_finished", Some(scanner.name()), None).await;
+            Some(results)
+        }
+        Err(e) => {
+            tracing::warn!("{} scan failed: {}", scanner.name(), e);
+            let _ = record_event(state, scan_id, "scanner_failed", Some(scanner.name()), Some(&e.to_string())).await;
+            None
+        }
+    }
+}
+
+/// Timeline detail recording how long a scan queued for a backend slot
+fn slot_wait_detail(slot: &Result<Option<ScanSlot>, ScanError>) -> Option<String> {
+    match slot {
+        Ok(Some(slot)) => Some(format!("queue_wait_ms={}", slot.waited.as_millis())),
+        _ => None,
+    }
+}
+
+/// Merge results from another scanner into Fossology results
 /// This combines results from both scanners by file path
 fn merge_scan_results(
     fossology_results: &mut Vec<crate::scanner::ScanResult>,
@@ -196,8 +737,14 @@ fn merge_scan_results(
 
     for semgrep_result in semgrep_results {
         if let Some(&idx) = file_index_map.get(&semgrep_result.file_path) {
-            // File already has Fossology results, merge ECC findings
-            fossology_results[idx].ecc_findings.extend(semgrep_result.ecc_findings);
+            // File already has Fossology results, merge all findings
+            let existing = &mut fossology_results[idx];
+            existing.licenses.extend(semgrep_result.licenses);
+            existing.copyrights.extend(semgrep_result.copyrights);
+            existing.ecc_findings.extend(semgrep_result.ecc_findings);
+            existing.malware_findings.extend(semgrep_result.malware_findings);
+            existing.package_findings.extend(semgrep_result.package_findings);
+            existing.model_licenses.extend(semgrep_result.model_licenses);
         } else {
             // File only has Semgrep results, queue for addition
             results_to_add.push(semgrep_result);
@@ -208,54 +755,23 @@ fn merge_scan_results(
     fossology_results.extend(results_to_add);
 }
 
-/// Store scan results in the database
-async fn store_scan_results(
-    pool: &SqlitePool,
-    scan_id: &str,
-    scan_results: Vec<crate::scanner::ScanResult>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    for result in scan_results {
-        // Store licenses
-        for license in result.licenses {
-            DbScanResult::create_license(
-                pool,
-                scan_id,
-                &result.file_path,
-                &license.name,
-                license.spdx_id.as_deref(),
-                license.confidence,
-            )
-            .await?;
-        }
-
-        // Store copyrights
-        for copyright in result.copyrights {
-            DbScanResult::create_copyright(
-                pool,
-                scan_id,
-                &result.file_path,
-                &copyright.statement,
-                &copyright.holders,
-                &copyright.years,
-            )
-            .await?;
-        }
-
-        // Store ECC findings
-        for ecc_finding in result.ecc_findings {
-            DbScanResult::create_ecc(
-                pool,
-                scan_id,
-                &result.file_path,
-                &ecc_finding.content,
-                &ecc_finding.risk_severity,
-                ecc_finding.source.as_deref(),
-                ecc_finding.line_number,
-                ecc_finding.check_id.as_deref(),
-            )
-            .await?;
+/// Merge documentation/data license findings. When another scanner already
+/// reported the same license for the file, recategorize it instead of duplicating.
+fn merge_content_licenses(
+    scan_results: &mut Vec<crate::scanner::ScanResult>,
+    content_results: Vec<crate::scanner::ScanResult>,
+) {
+    for content_result in content_results {
+        match scan_results.iter_mut().find(|r| r.file_path == content_result.file_path) {
+            Some(existing) => {
+                for finding in content_result.licenses {
+                    match existing.licenses.iter_mut().find(|l| l.spdx_id == finding.spdx_id) {
+                        Some(license) => license.category = finding.category,
+                        None => existing.licenses.push(finding),
+                    }
+                }
+            }
+            None => scan_results.push(content_result),
         }
     }
-
-    Ok(())
 }
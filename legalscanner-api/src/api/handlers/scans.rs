@@ -1,31 +1,315 @@
 use crate::{
-    api::models::{CreateScanRequest, RiskAssessment, RiskFactor, ScanResponse, ScanResultsResponse},
-    db::models::{Scan, ScanResult},
+    api::models::{
+        ApproveScanResponse, CreateScanRequest, RerunScanRequest, RiskAssessment, RiskFactor,
+        ScanResponse, ScanResultsResponse,
+    },
+    audit::{self, Actor},
+    credentials, custom_fields,
+    db::models::{
+        scan::{ScanListFilter, ScanSort},
+        CustomField, PolicyEvaluationRecord, Project, Scan, ScanCustomFieldValue, ScanScreening, ScanSubmodule,
+    },
     error::AppError,
-    AppState,
+    git::GitAuth,
+    legal_hold, local_scan,
+    progress::record_event,
+    remediation,
+    rescoring::{self, RiskRecalculation},
+    review,
+    storage::ResultQuery,
+    submodules, waiver, AppState,
 };
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
     Json,
 };
+use chrono::{DateTime, NaiveDate, NaiveTime};
+use serde::Deserialize;
+use utoipa::IntoParams;
 
-/// POST /api/v1/scans - Create a new scan
+const DEFAULT_PER_PAGE: i64 = 100;
+const MAX_PER_PAGE: i64 = 500;
+
+const SCAN_STATUSES: &[&str] = &["pending", "in_progress", "completed", "failed"];
+
+const DEFAULT_RESULTS_LIMIT: i64 = 1000;
+const MAX_RESULTS_LIMIT: i64 = 10_000;
+
+pub(crate) const RESULT_TYPES: &[&str] = &[
+    "license",
+    "copyright",
+    "ecc",
+    "malware",
+    "package",
+    "model_license",
+];
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListScansQueryParams {
+    page: Option<i64>,
+    per_page: Option<i64>,
+    status: Option<String>,
+    git_url_contains: Option<String>,
+    /// Inclusive lower bound, `YYYY-MM-DD` or RFC 3339
+    created_after: Option<String>,
+    /// Exclusive upper bound, `YYYY-MM-DD` or RFC 3339
+    created_before: Option<String>,
+    sort: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScanResultsQueryParams {
+    #[serde(rename = "type")]
+    result_type: Option<String>,
+    license: Option<String>,
+    severity: Option<String>,
+    file_prefix: Option<String>,
+    /// pending, approved or rejected
+    review_status: Option<String>,
+    limit: Option<i64>,
+    /// `next_cursor` from the previous page
+    cursor: Option<String>,
+}
+
+/// POST /api/v1/scans - Create a new scan. With `SCAN_LOCK_PER_REPOSITORY`,
+/// a pending or in-progress scan of the same repository and branch is
+/// returned with 200 instead.
+#[utoipa::path(
+    post,
+    path = "/api/v1/scans",
+    tag = "scans",
+    request_body = CreateScanRequest,
+    responses(
+        (status = 201, description = "Scan created", body = ScanResponse),
+        (status = 200, description = "A scan of the repository and branch is already running", body = ScanResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Credential not found", body = ErrorResponse),
+        (status = 422, description = "Repository or branch is not reachable", body = ErrorResponse),
+    )
+)]
 pub async fn create_scan(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(payload): Json<CreateScanRequest>,
 ) -> Result<(StatusCode, Json<ScanResponse>), AppError> {
-    // Validate Git URL
-    if payload.git_url.is_empty() {
-        return Err(AppError::Validation("Git URL cannot be empty".to_string()));
+    let local_path = payload
+        .path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty());
+
+    let git_url = if let Some(path) = local_path {
+        // Scanning server directories is reserved to admins
+        super::admin::authorize_admin(&state, &headers)?;
+        if !payload.git_url.is_empty() {
+            return Err(AppError::Validation(
+                "git_url cannot be combined with path".to_string(),
+            ));
+        }
+        if payload.branch.is_some() {
+            return Err(AppError::Validation(
+                "branch cannot be combined with path, the directory is scanned as checked out"
+                    .to_string(),
+            ));
+        }
+        if payload.submodules {
+            return Err(AppError::Validation(
+                "submodules cannot be combined with path, the directory is scanned as checked out"
+                    .to_string(),
+            ));
+        }
+        if payload.credential_id.is_some() {
+            return Err(AppError::Validation(
+                "credential_id cannot be combined with path".to_string(),
+            ));
+        }
+        local_scan::check_semgrep_mode(state.config.semgrep_mode).map_err(AppError::Validation)?;
+        let resolved = local_scan::resolve(&state.config.local_scan_base_dirs, path)
+            .map_err(AppError::Validation)?;
+        local_scan::local_url(&resolved)
+    } else {
+        // Validate Git URL
+        if payload.git_url.is_empty() {
+            return Err(AppError::Validation("Git URL cannot be empty".to_string()));
+        }
+
+        // Validate Git URL format
+        crate::git::validate_git_url(&payload.git_url, &state.config.git_url_schemes)
+            .map_err(|e| AppError::Validation(e))?;
+        // Browser links and provider clone URLs name the same repository
+        crate::git::normalize_git_url(&payload.git_url)
+    };
+
+    if payload.submodule_token.is_some() && !payload.submodules {
+        return Err(AppError::Validation(
+            "submodule_token requires submodules".to_string(),
+        ));
+    }
+    if payload.submodule_token.is_some() && state.config.credentials_encryption_key.is_none() {
+        return Err(AppError::Validation(
+            "submodule_token is stored encrypted, set CREDENTIALS_ENCRYPTION_KEY".to_string(),
+        ));
     }
 
-    // Validate Git URL format
-    crate::git::validate_git_url(&payload.git_url)
-        .map_err(|e| AppError::Validation(e))?;
+    let credential_id = payload
+        .credential_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+    if credential_id.is_some() && payload.git_token.is_some() {
+        return Err(AppError::Validation(
+            "git_token cannot be combined with credential_id".to_string(),
+        ));
+    }
 
-    // Create scan in database
-    let scan = Scan::create(&state.db, payload.git_url.clone(), payload.git_token, None).await?;
+    // Quick scans are time-boxed, a history walk does not fit
+    if payload.quick && payload.due_diligence {
+        return Err(AppError::Validation(
+            "due_diligence cannot be combined with quick".to_string(),
+        ));
+    }
+    if payload.quick && payload.git_authorship {
+        return Err(AppError::Validation(
+            "git_authorship cannot be combined with quick".to_string(),
+        ));
+    }
+
+    if let Some(callback_url) = &payload.callback_url {
+        crate::webhooks::validate_callback_url(callback_url).map_err(AppError::Validation)?;
+    }
+
+    let scan_path = payload
+        .scan_path
+        .as_deref()
+        .map(crate::git::workspace::normalize_scan_path)
+        .transpose()
+        .map_err(AppError::Validation)?;
+
+    // The history walk covers the whole repository
+    if scan_path.is_some() && payload.due_diligence {
+        return Err(AppError::Validation(
+            "due_diligence cannot be combined with scan_path".to_string(),
+        ));
+    }
+    if scan_path.is_some() && payload.git_authorship {
+        return Err(AppError::Validation(
+            "git_authorship cannot be combined with scan_path".to_string(),
+        ));
+    }
+
+    // Validate Semgrep ruleset overrides
+    let semgrep_rulesets = payload.semgrep_rulesets.unwrap_or_default();
+    for ruleset in &semgrep_rulesets {
+        crate::scanner::semgrep::validate_ruleset(ruleset).map_err(AppError::Validation)?;
+    }
+
+    let branch = payload
+        .branch
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty());
+
+    let fields = CustomField::list(&state.db).await?;
+    let custom_field_values =
+        custom_fields::validate_values(&fields, &payload.custom_fields.unwrap_or_default(), true)
+            .map_err(AppError::Validation)?;
+
+    if local_path.is_none() {
+        let auth = match credential_id {
+            Some(id) => Some(credentials::resolve(&state, id).await?),
+            None => payload
+                .git_token
+                .clone()
+                .map(|token| GitAuth::for_token(&git_url, token)),
+        };
+        check_remote(&state, &git_url, branch.as_deref(), auth.as_ref()).await?;
+    }
+
+    let actor = Actor::from_headers(&state, &headers).await?;
+    let created_by_key_id = actor.api_key_id().map(str::to_string);
+
+    // Create scan in database, or hand out the scan of this repository and
+    // branch that is already running
+    let mut scan = if state.config.scan_lock_per_repository {
+        let (scan, created) = Scan::create_unless_running(
+            &state.db,
+            git_url,
+            branch,
+            payload.git_token,
+            created_by_key_id,
+        )
+        .await?;
+        if !created {
+            tracing::info!(
+                "Scan {} of {} is still {}, not starting another",
+                scan.id,
+                scan.git_url,
+                scan.status
+            );
+            return Ok((StatusCode::OK, Json(ScanResponse::from(scan))));
+        }
+        scan
+    } else {
+        let mut scan =
+            Scan::create(&state.db, git_url, payload.git_token, created_by_key_id).await?;
+        if let Some(branch) = branch {
+            Scan::set_branch(&state.db, &scan.id, &branch).await?;
+            scan.branch = Some(branch);
+        }
+        scan
+    };
+
+    if !semgrep_rulesets.is_empty() {
+        Scan::set_semgrep_rulesets(&state.db, &scan.id, &semgrep_rulesets).await?;
+    }
+
+    if payload.quick {
+        Scan::set_quick(&state.db, &scan.id).await?;
+        scan.quick = true;
+    }
+
+    if payload.due_diligence {
+        Scan::set_due_diligence(&state.db, &scan.id).await?;
+        scan.due_diligence = true;
+    }
+
+    if payload.git_authorship {
+        Scan::set_git_authorship(&state.db, &scan.id).await?;
+        scan.git_authorship = true;
+    }
+
+    if let Some(scan_path) = scan_path {
+        Scan::set_scan_path(&state.db, &scan.id, &scan_path).await?;
+        scan.scan_path = Some(scan_path);
+    }
+
+    if payload.submodules {
+        let token_encrypted = payload
+            .submodule_token
+            .as_deref()
+            .map(|token| credentials::seal_submodule_token(&state, &scan.id, token))
+            .transpose()?;
+        Scan::set_submodules(&state.db, &scan.id, token_encrypted.as_deref()).await?;
+        scan.submodules = true;
+    }
+
+    if let Some(credential_id) = credential_id {
+        Scan::set_credential(&state.db, &scan.id, credential_id).await?;
+        scan.credential_id = Some(credential_id.to_string());
+    }
+
+    if let Some(callback_url) = &payload.callback_url {
+        Scan::set_callback_url(&state.db, &scan.id, callback_url).await?;
+        scan.callback_url = Some(callback_url.clone());
+    }
+
+    custom_fields::apply(&state.db, &scan.id, &custom_field_values).await?;
+
+    let _ = record_event(&state, &scan.id, "created", None, None).await;
+    let _ = record_event(&state, &scan.id, "queued", None, None).await;
+    audit::record(&state, &actor, "create", "scan", Some(&scan.id), None).await?;
 
     // Spawn background task to execute the scan
     let scan_id = scan.id.clone();
@@ -36,45 +320,332 @@ pub async fn create_scan(
     });
 
     // Return immediately with pending status
-    Ok((
-        StatusCode::CREATED,
-        Json(ScanResponse {
-            scan_id: scan.id,
-            status: scan.status,
-            created_at: scan.created_at,
-            git_url: scan.git_url,
-            fossology_status: scan.fossology_status,
-            semgrep_status: scan.semgrep_status,
-            risk_score: scan.risk_score,
-            risk_level: scan.risk_level,
-        }),
-    ))
+    Ok((StatusCode::CREATED, Json(ScanResponse::from(scan))))
 }
 
-/// GET /api/v1/scans - List all scans
+/// POST /api/v1/scans/:id/rerun - Scan the same repository again with the
+/// original branch and commit, token or credential, Semgrep rulesets, scan modes,
+/// scan path and callback URL. With `failed_only`, the scanners that completed in the
+/// original scan are skipped. Like scan creation, returns a running scan of
+/// the repository and branch instead of starting another.
+#[utoipa::path(
+    post,
+    path = "/api/v1/scans/{id}/rerun",
+    tag = "scans",
+    params(("id" = String, Path, description = "Scan ID")),
+    request_body = Option<RerunScanRequest>,
+    responses(
+        (status = 201, description = "Scan created", body = ScanResponse),
+        (status = 200, description = "A scan of the repository and branch is already running", body = ScanResponse),
+        (status = 400, description = "Scan of an uploaded archive", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+        (status = 422, description = "Repository, branch or credential is no longer available", body = ErrorResponse),
+    )
+)]
+pub async fn rerun_scan(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    payload: Option<Json<RerunScanRequest>>,
+) -> Result<(StatusCode, Json<ScanResponse>), AppError> {
+    let parent = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    if parent.status != "completed" && parent.status != "failed" {
+        return Err(AppError::Conflict(format!(
+            "Scan {} is still {}",
+            id, parent.status
+        )));
+    }
+
+    if local_scan::is_local(&parent.git_url) {
+        super::admin::authorize_admin(&state, &headers)?;
+        local_scan::check_semgrep_mode(state.config.semgrep_mode)
+            .map_err(AppError::Unprocessable)?;
+    }
+
+    // The archive is deleted once extracted
+    if crate::upload::is_upload(&parent.git_url) {
+        return Err(AppError::Validation(
+            "Scans of uploaded archives cannot be re-run, upload the archive again".to_string(),
+        ));
+    }
+
+    let failed_only = payload.map(|Json(p)| p.failed_only).unwrap_or(false);
+    let (skip_fossology, skip_semgrep) = if failed_only {
+        if parent.status != "failed" {
+            return Err(AppError::Validation(
+                "failed_only requires a failed scan".to_string(),
+            ));
+        }
+        let skip = (
+            parent.fossology_status == "completed",
+            parent.semgrep_status == "completed",
+        );
+        if skip == (true, true) {
+            return Err(AppError::Validation(format!(
+                "No scanner failed in scan {}, re-run it without failed_only",
+                id
+            )));
+        }
+        skip
+    } else {
+        (false, false)
+    };
+
+    if !local_scan::is_local(&parent.git_url) {
+        // A deleted credential is not a missing scan
+        let auth = credentials::scan_auth(&state, &parent)
+            .await
+            .map_err(|e| match e {
+                AppError::NotFound(message) => AppError::Unprocessable(message),
+                e => e,
+            })?;
+        check_remote(&state, &parent.git_url, parent.branch.as_deref(), auth.as_ref()).await?;
+    }
+    // Sealed again for the new scan below
+    let submodule_token = credentials::submodule_token(&state, &parent)?;
+
+    let actor = Actor::from_headers(&state, &headers).await?;
+    let created_by_key_id = actor.api_key_id().map(str::to_string);
+
+    let scan = if state.config.scan_lock_per_repository {
+        let (scan, created) = Scan::create_unless_running(
+            &state.db,
+            parent.git_url.clone(),
+            parent.branch.clone(),
+            parent.git_token.clone(),
+            created_by_key_id,
+        )
+        .await?;
+        if !created {
+            return Ok((StatusCode::OK, Json(ScanResponse::from(scan))));
+        }
+        scan
+    } else {
+        let scan = Scan::create(
+            &state.db,
+            parent.git_url.clone(),
+            parent.git_token.clone(),
+            created_by_key_id,
+        )
+        .await?;
+        if let Some(branch) = &parent.branch {
+            Scan::set_branch(&state.db, &scan.id, branch).await?;
+        }
+        scan
+    };
+    Scan::set_parent(&state.db, &scan.id, &parent.id).await?;
+
+    let semgrep_rulesets: Vec<String> = parent
+        .semgrep_rulesets
+        .as_deref()
+        .and_then(|r| serde_json::from_str(r).ok())
+        .unwrap_or_default();
+    if !semgrep_rulesets.is_empty() {
+        Scan::set_semgrep_rulesets(&state.db, &scan.id, &semgrep_rulesets).await?;
+    }
+
+    if parent.quick {
+        Scan::set_quick(&state.db, &scan.id).await?;
+    }
+
+    if parent.due_diligence {
+        Scan::set_due_diligence(&state.db, &scan.id).await?;
+    }
+
+    if parent.git_authorship {
+        Scan::set_git_authorship(&state.db, &scan.id).await?;
+    }
+
+    if let Some(scan_path) = &parent.scan_path {
+        Scan::set_scan_path(&state.db, &scan.id, scan_path).await?;
+    }
+
+    if let Some(commit) = &parent.commit_sha {
+        Scan::set_commit(&state.db, &scan.id, commit).await?;
+    }
+
+    if parent.submodules {
+        let token_encrypted = submodule_token
+            .as_deref()
+            .map(|token| credentials::seal_submodule_token(&state, &scan.id, token))
+            .transpose()?;
+        Scan::set_submodules(&state.db, &scan.id, token_encrypted.as_deref()).await?;
+    }
+
+    if let Some(credential_id) = &parent.credential_id {
+        Scan::set_credential(&state.db, &scan.id, credential_id).await?;
+    }
+
+    if let Some(callback_url) = &parent.callback_url {
+        Scan::set_callback_url(&state.db, &scan.id, callback_url).await?;
+    }
+
+    ScanCustomFieldValue::copy(&state.db, &parent.id, &scan.id).await?;
+
+    let mut warnings = Vec::new();
+    if skip_fossology {
+        Scan::update_fossology_status(&state.db, &scan.id, "skipped", None).await?;
+        warnings.push(skipped_scanner_warning("Fossology", &parent.id));
+    }
+    if skip_semgrep {
+        Scan::update_semgrep_status(&state.db, &scan.id, "skipped", None).await?;
+        warnings.push(skipped_scanner_warning("Semgrep", &parent.id));
+    }
+    if !warnings.is_empty() {
+        Scan::set_warnings(&state.db, &scan.id, &warnings).await?;
+    }
+
+    let detail = format!("re-run of scan {}", parent.id);
+    let _ = record_event(&state, &scan.id, "created", None, Some(&detail)).await;
+    let _ = record_event(&state, &scan.id, "queued", None, None).await;
+    audit::record(&state, &actor, "rerun", "scan", Some(&scan.id), Some(&detail)).await?;
+
+    let scan = Scan::find_by_id(&state.db, &scan.id)
+        .await?
+        .ok_or_else(|| AppError::Internal(format!("Scan {} disappeared", scan.id)))?;
+
+    let scan_id = scan.id.clone();
+    let state_clone = state.clone();
+    tokio::spawn(async move {
+        super::scan_job::execute_scan_job(scan_id, state_clone).await;
+    });
+
+    tracing::info!("Scan {} re-runs scan {}", scan.id, parent.id);
+    Ok((StatusCode::CREATED, Json(ScanResponse::from(scan))))
+}
+
+/// Reject a repository that cannot be reached now, instead of failing the
+/// scan in the background job
+async fn check_remote(
+    state: &AppState,
+    git_url: &str,
+    branch: Option<&str>,
+    auth: Option<&GitAuth>,
+) -> Result<(), AppError> {
+    if state.config.remote_check_timeout_secs == 0 {
+        return Ok(());
+    }
+    let timeout = std::time::Duration::from_secs(state.config.remote_check_timeout_secs);
+    crate::git::check_remote(git_url, branch, auth, timeout)
+        .await
+        .map_err(AppError::Unprocessable)
+}
+
+fn skipped_scanner_warning(scanner: &str, parent_id: &str) -> String {
+    format!(
+        "{} completed in scan {} and was not re-run, its findings are missing from this scan",
+        scanner, parent_id
+    )
+}
+
+/// GET /api/v1/scans - List scans, newest first, one page at a time
+///
+/// The body stays a plain array; totals are returned in `X-Total-Count`,
+/// `X-Page` and `X-Per-Page` headers.
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans",
+    tag = "scans",
+    params(ListScansQueryParams),
+    responses(
+        (status = 200, description = "One page of scans; totals in `X-Total-Count`, `X-Page` and `X-Per-Page`", body = Vec<ScanResponse>),
+        (status = 400, description = "Invalid filter or sort", body = ErrorResponse),
+    )
+)]
 pub async fn list_scans(
     State(state): State<AppState>,
-) -> Result<Json<Vec<ScanResponse>>, AppError> {
-    let scans = Scan::list_all(&state.db, 100).await?;
-
-    let responses: Vec<ScanResponse> = scans
-        .into_iter()
-        .map(|scan| ScanResponse {
-            scan_id: scan.id,
-            status: scan.status,
-            created_at: scan.created_at,
-            git_url: scan.git_url,
-            fossology_status: scan.fossology_status,
-            semgrep_status: scan.semgrep_status,
-            risk_score: scan.risk_score,
-            risk_level: scan.risk_level,
-        })
-        .collect();
+    Query(params): Query<ListScansQueryParams>,
+) -> Result<(HeaderMap, Json<Vec<ScanResponse>>), AppError> {
+    let page = params.page.unwrap_or(1);
+    if page < 1 {
+        return Err(AppError::Validation("page must be at least 1".to_string()));
+    }
+    let per_page = params.per_page.unwrap_or(DEFAULT_PER_PAGE);
+    if !(1..=MAX_PER_PAGE).contains(&per_page) {
+        return Err(AppError::Validation(format!(
+            "per_page must be between 1 and {}",
+            MAX_PER_PAGE
+        )));
+    }
 
-    Ok(Json(responses))
+    if let Some(status) = params.status.as_deref() {
+        if !SCAN_STATUSES.contains(&status) {
+            return Err(AppError::Validation(format!(
+                "Invalid status '{}'. Must be one of {}",
+                status,
+                SCAN_STATUSES.join(", ")
+            )));
+        }
+    }
+
+    let sort = params
+        .sort
+        .as_deref()
+        .map(str::parse::<ScanSort>)
+        .transpose()
+        .map_err(AppError::Validation)?
+        .unwrap_or_default();
+
+    let filter = ScanListFilter {
+        status: params.status,
+        git_url_contains: params.git_url_contains.filter(|f| !f.is_empty()),
+        created_after: params
+            .created_after
+            .as_deref()
+            .map(|v| parse_timestamp("created_after", v))
+            .transpose()?,
+        created_before: params
+            .created_before
+            .as_deref()
+            .map(|v| parse_timestamp("created_before", v))
+            .transpose()?,
+    };
+
+    let total = Scan::count(&state.db, &filter).await?;
+    let scans = Scan::list(&state.db, &filter, sort, per_page, (page - 1) * per_page).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-total-count", HeaderValue::from(total));
+    headers.insert("x-page", HeaderValue::from(page));
+    headers.insert("x-per-page", HeaderValue::from(per_page));
+
+    let responses: Vec<ScanResponse> = scans.into_iter().map(ScanResponse::from).collect();
+
+    Ok((headers, Json(responses)))
+}
+
+/// Convert a `YYYY-MM-DD` or RFC 3339 query value to the stored
+/// `YYYY-MM-DD HH:MM:SS` (UTC) format so it compares as a string
+pub(crate) fn parse_timestamp(param: &str, value: &str) -> Result<String, AppError> {
+    const STORED_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN).format(STORED_FORMAT).to_string());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.naive_utc().format(STORED_FORMAT).to_string())
+        .map_err(|_| {
+            AppError::Validation(format!(
+                "Invalid {} '{}' (expected YYYY-MM-DD or RFC 3339)",
+                param, value
+            ))
+        })
 }
 
 /// GET /api/v1/scans/:id - Get scan details
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}",
+    tag = "scans",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, description = "Scan with summary, risk assessment and warnings", body = Object),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
 pub async fn get_scan(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -83,7 +654,16 @@ pub async fn get_scan(
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
 
-    let summary = Scan::get_summary(&state.db, &id).await.ok();
+    let summary = state.result_store.summarize(&id).await.ok();
+    let screening = ScanScreening::find_by_scan_id(&state.db, &id).await?;
+    let custom_fields = custom_fields::load(&state.db, &id).await?;
+    let submodules = if scan.submodules {
+        Some(ScanSubmodule::find_by_scan_id(&state.db, &id).await?)
+    } else {
+        None
+    };
+    let repository = super::repository::metadata_response(&state.db, &id).await?;
+    let policy_evaluation = PolicyEvaluationRecord::find_by_scan_id(&state.db, &id).await?;
 
     // Parse risk factors if present
     let risk_assessment = if let (Some(score), Some(level), Some(factors_json)) =
@@ -93,7 +673,12 @@ pub async fn get_scan(
             Ok(factors) => Some(RiskAssessment {
                 score: *score,
                 level: level.clone(),
+                remediation: remediation::for_factors(&factors),
                 factors,
+                preliminary: scan.quick,
+                algorithm_version: scan.risk_algorithm_version,
+                config_version: scan.risk_config_version.clone(),
+                assessed_at: scan.risk_assessed_at.clone(),
             }),
             Err(e) => {
                 tracing::error!("Failed to parse risk factors: {}", e);
@@ -116,63 +701,235 @@ pub async fn get_scan(
         "semgrep_status": scan.semgrep_status,
         "fossology_error": scan.fossology_error,
         "semgrep_error": scan.semgrep_error,
+        "quick": scan.quick,
+        "due_diligence": scan.due_diligence,
+        "git_authorship": scan.git_authorship,
+        "approved_at": scan.approved_at,
+        "reviewed_at": scan.reviewed_at,
+        "screening_status": screening.map(|s| s.status),
+        "warnings": scan.warning_list(),
+        "parent_scan_id": scan.parent_scan_id,
+        "scan_path": scan.scan_path,
+        "branch": scan.branch,
+        "credential_id": scan.credential_id,
+        "submodules": submodules,
+        "custom_fields": custom_fields,
+        "repository": repository,
+        "legal_hold_at": scan.legal_hold_at,
+        "legal_hold_reason": scan.legal_hold_reason,
+        "results_purged_at": scan.results_purged_at,
+        "results_archived_at": scan.results_archived_at,
+        "policy": scan.policy,
+        "policy_outcome": policy_evaluation.map(|e| e.outcome),
         "summary": summary,
         "risk_assessment": risk_assessment
     })))
 }
 
+/// POST /api/v1/scans/:id/approve - Approve a completed scan. Refused while an
+/// export control screening of the scan has not cleared.
+#[utoipa::path(
+    post,
+    path = "/api/v1/scans/{id}/approve",
+    tag = "scans",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ApproveScanResponse),
+        (status = 400, description = "Scan is not completed yet", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+        (status = 409, description = "Export control screening has not cleared", body = ErrorResponse),
+    )
+)]
+pub async fn approve_scan(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ApproveScanResponse>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    if scan.status != "completed" {
+        return Err(AppError::Validation(format!(
+            "Scan is not completed yet. Current status: {}",
+            scan.status
+        )));
+    }
+
+    let screening = ScanScreening::find_by_scan_id(&state.db, &id).await?;
+    if let Some(screening) = &screening {
+        if screening.status != "cleared" {
+            return Err(AppError::Conflict(format!(
+                "Export control screening has not cleared (status: {})",
+                screening.status
+            )));
+        }
+    }
+
+    if Scan::approve(&state.db, &id).await? {
+        tracing::info!("Scan {} approved", id);
+    }
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    Ok(Json(ApproveScanResponse {
+        scan_id: scan.id,
+        approved_at: scan.approved_at,
+        screening_status: screening.map(|s| s.status),
+    }))
+}
+
+/// POST /api/v1/scans/:id/risk/recalculate - Rescore a completed scan with
+/// the current scoring algorithm and risk configuration, e.g. after license
+/// weights changed
+#[utoipa::path(
+    post,
+    path = "/api/v1/scans/{id}/risk/recalculate",
+    tag = "scans",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, description = "Previous and new risk score with their versions", body = RiskRecalculation),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+        (status = 409, description = "Scan not completed, or its findings were purged", body = ErrorResponse),
+    )
+)]
+pub async fn recalculate_scan_risk(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<RiskRecalculation>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    Ok(Json(rescoring::recalculate(&state, &scan, false).await?))
+}
+
 /// DELETE /api/v1/scans/:id - Delete a scan
+#[utoipa::path(
+    delete,
+    path = "/api/v1/scans/{id}",
+    tag = "scans",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 204, description = "Scan deleted"),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+        (status = 409, description = "Scan is under legal hold", body = ErrorResponse),
+    )
+)]
 pub async fn delete_scan(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    headers: HeaderMap,
 ) -> Result<StatusCode, AppError> {
     // Check if scan exists
-    let _ = Scan::find_by_id(&state.db, &id)
+    let scan = Scan::find_by_id(&state.db, &id)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+    legal_hold::ensure_deletable(&state.db, &scan).await?;
 
+    // Results are kept with the scan, so an admin can restore it
+    let actor = Actor::from_headers(&state, &headers).await?;
     Scan::delete(&state.db, &id).await?;
+    audit::record(&state, &actor, "delete", "scan", Some(&id), None).await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
-/// DELETE /api/v1/scans - Delete all scans
+/// DELETE /api/v1/scans - Delete all scans except those under legal hold
+#[utoipa::path(
+    delete,
+    path = "/api/v1/scans",
+    tag = "scans",
+    responses(
+        (status = 200, description = "Number of deleted scans", body = Object),
+    )
+)]
 pub async fn delete_all_scans(
     State(state): State<AppState>,
+    headers: HeaderMap,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let deleted_count = Scan::delete_all(&state.db).await?;
+    let actor = Actor::from_headers(&state, &headers).await?;
+    let held_repositories = Project::held_repositories(&state.db).await?;
+    let deleted_count = Scan::delete_all(&state.db, &held_repositories).await?;
+    let held_count = Scan::count(&state.db, &ScanListFilter::default()).await?;
+    let detail = format!("{} scans deleted, {} held", deleted_count, held_count);
+    audit::record(&state, &actor, "delete_all", "scan", None, Some(&detail)).await?;
 
     Ok(Json(serde_json::json!({
-        "deleted": deleted_count
+        "deleted": deleted_count,
+        "held": held_count
     })))
 }
 
 /// GET /api/v1/scans/:id/results - Get scan results
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/results",
+    tag = "results",
+    params(("id" = String, Path, description = "Scan ID"), ScanResultsQueryParams),
+    responses(
+        (status = 200, body = ScanResultsResponse),
+        (status = 400, description = "Invalid filter or cursor", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
 pub async fn get_scan_results(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    Query(params): Query<ScanResultsQueryParams>,
 ) -> Result<Json<ScanResultsResponse>, AppError> {
     // Check if scan exists
     let scan = Scan::find_by_id(&state.db, &id)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
 
-    // Get all results
-    let results = ScanResult::find_by_scan_id(&state.db, &id).await?;
+    let mut query = results_query(params)?;
+
+    // Fetch one extra row to find out whether another page follows
+    let page_size = query.limit;
+    query.limit = page_size.map(|limit| limit + 1);
+    let mut results = state.result_store.query_results(&id, &query).await?;
+
+    let mut next_cursor = None;
+    if let Some(limit) = page_size {
+        if results.len() as i64 > limit {
+            results.truncate(limit as usize);
+            next_cursor = results.last().map(|r| r.id.to_string());
+        }
+    }
+
+    waiver::mark(&state.db, &scan, &mut results).await?;
+    submodules::mark(&state.db, &scan, &mut results).await?;
 
-    // Separate licenses, copyrights, and ECC findings
+    // Separate licenses, copyrights, ECC and malware findings
     let mut licenses = Vec::new();
+    let mut documentation_licenses = Vec::new();
+    let mut data_licenses = Vec::new();
     let mut copyrights = Vec::new();
     let mut ecc_findings = Vec::new();
+    let mut malware_findings = Vec::new();
+    let mut packages = Vec::new();
+    let mut model_licenses = Vec::new();
 
     for result in results {
         if result.result_type == "license" {
-            licenses.push(serde_json::json!({
+            let finding = serde_json::json!({
+                "id": result.id,
                 "file_path": result.file_path,
                 "license": result.license_name,
                 "spdx_id": result.license_spdx_id,
-                "confidence": result.confidence
-            }));
+                "confidence": result.confidence,
+                "provenance": result.merge_provenance,
+                "agreement": result.license_agreement,
+                "waiver_id": result.waiver_id,
+                "submodule": result.submodule,
+                "review_status": result.review_status
+            });
+            match result.license_category.as_deref() {
+                Some("documentation") => documentation_licenses.push(finding),
+                Some("data") => data_licenses.push(finding),
+                _ => licenses.push(finding),
+            }
         } else if result.result_type == "copyright" {
             let holders: Vec<String> = result
                 .copyright_holders
@@ -184,19 +941,62 @@ pub async fn get_scan_results(
                 .unwrap_or_default();
 
             copyrights.push(serde_json::json!({
+                "id": result.id,
                 "file_path": result.file_path,
                 "statement": result.copyright_statement,
                 "holders": holders,
-                "years": years
+                "years": years,
+                "waiver_id": result.waiver_id,
+                "submodule": result.submodule,
+                "review_status": result.review_status
             }));
         } else if result.result_type == "ecc" {
             ecc_findings.push(serde_json::json!({
+                "id": result.id,
                 "file_path": result.file_path,
                 "content": result.raw_data,
                 "risk_severity": result.risk_severity,
                 "source": result.ecc_source,
                 "line_number": result.ecc_line_number,
-                "check_id": result.ecc_check_id
+                "check_id": result.ecc_check_id,
+                "waiver_id": result.waiver_id,
+                "submodule": result.submodule,
+                "review_status": result.review_status
+            }));
+        } else if result.result_type == "malware" {
+            malware_findings.push(serde_json::json!({
+                "id": result.id,
+                "file_path": result.file_path,
+                "signature": result.raw_data,
+                "source": result.ecc_source,
+                "waiver_id": result.waiver_id,
+                "submodule": result.submodule,
+                "review_status": result.review_status
+            }));
+        } else if result.result_type == "package" {
+            packages.push(serde_json::json!({
+                "id": result.id,
+                "file_path": result.file_path,
+                "name": result.package_name,
+                "version": result.package_version,
+                "ecosystem": result.package_ecosystem,
+                "declared_license": result.license_spdx_id,
+                "purl": result.package_purl,
+                "waiver_id": result.waiver_id,
+                "submodule": result.submodule,
+                "review_status": result.review_status
+            }));
+        } else if result.result_type == "model_license" {
+            model_licenses.push(serde_json::json!({
+                "id": result.id,
+                "file_path": result.file_path,
+                "license": result.license_name,
+                "spdx_id": result.license_spdx_id,
+                "format": result.ecc_source,
+                "source": result.raw_data,
+                "waiver_id": result.waiver_id,
+                "submodule": result.submodule,
+                "review_status": result.review_status
             }));
         }
     }
@@ -208,8 +1008,74 @@ pub async fn get_scan_results(
         status: scan.status,
         results: serde_json::json!({
             "licenses": licenses,
+            "documentation_licenses": documentation_licenses,
+            "data_licenses": data_licenses,
             "copyrights": copyrights,
-            "ecc_findings": ecc_findings
+            "ecc_findings": ecc_findings,
+            "malware_findings": malware_findings,
+            "packages": packages,
+            "model_licenses": model_licenses
         }),
+        next_cursor,
     }))
 }
+
+/// Validate result filters. Results are paginated once `limit` or `cursor`
+/// is given; otherwise every matching finding is returned.
+fn results_query(params: ScanResultsQueryParams) -> Result<ResultQuery, AppError> {
+    if let Some(result_type) = params.result_type.as_deref() {
+        if !RESULT_TYPES.contains(&result_type) {
+            return Err(AppError::Validation(format!(
+                "Invalid type '{}'. Must be one of {}",
+                result_type,
+                RESULT_TYPES.join(", ")
+            )));
+        }
+    }
+
+    if let Some(status) = params.review_status.as_deref() {
+        if !review::REVIEW_STATUSES.contains(&status) {
+            return Err(AppError::Validation(format!(
+                "Invalid review_status '{}'. Must be one of {}",
+                status,
+                review::REVIEW_STATUSES.join(", ")
+            )));
+        }
+    }
+
+    let after_id = params
+        .cursor
+        .as_deref()
+        .map(|c| {
+            c.parse::<i64>()
+                .map_err(|_| AppError::Validation(format!("Invalid cursor '{}'", c)))
+        })
+        .transpose()?;
+
+    let limit = match (params.limit, after_id) {
+        (Some(limit), _) if !(1..=MAX_RESULTS_LIMIT).contains(&limit) => {
+            return Err(AppError::Validation(format!(
+                "limit must be between 1 and {}",
+                MAX_RESULTS_LIMIT
+            )))
+        }
+        (Some(limit), _) => Some(limit),
+        (None, Some(_)) => Some(DEFAULT_RESULTS_LIMIT),
+        (None, None) => None,
+    };
+
+    let non_empty = |v: Option<String>| v.filter(|v| !v.is_empty());
+    Ok(ResultQuery {
+        result_type: params.result_type,
+        license: non_empty(params.license),
+        severity: non_empty(params.severity),
+        file_prefix: non_empty(params.file_prefix).map(|p| {
+            p.trim_start_matches("./")
+                .trim_start_matches('/')
+                .to_string()
+        }),
+        review_status: params.review_status,
+        after_id,
+        limit,
+    })
+}
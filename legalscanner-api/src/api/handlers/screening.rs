@@ -0,0 +1,96 @@
+use crate::{
+    api::models::ScreeningDecisionRequest,
+    db::models::{Scan, ScanScreening},
+    error::AppError,
+    screening, AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+/// GET /api/v1/scans/:id/screening - Export control screening status of a scan
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/screening",
+    tag = "screening",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanScreening),
+        (status = 404, description = "Scan or screening not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_screening(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanScreening>, AppError> {
+    ScanScreening::find_by_scan_id(&state.db, &id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} has not been screened", id)))
+}
+
+/// POST /api/v1/scans/:id/screening - (Re)submit a scan to the screening webhook,
+/// e.g. after the webhook was unreachable
+#[utoipa::path(
+    post,
+    path = "/api/v1/scans/{id}/screening",
+    tag = "screening",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanScreening),
+        (status = 400, description = "Scan is not completed yet", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn submit_scan_screening(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanScreening>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    if scan.status != "completed" {
+        return Err(AppError::Validation(format!(
+            "Scan is not completed yet. Current status: {}",
+            scan.status
+        )));
+    }
+
+    Ok(Json(screening::submit(&state, &id).await?))
+}
+
+/// PUT /api/v1/scans/:id/screening - Decision reported back by the screening system
+#[utoipa::path(
+    put,
+    path = "/api/v1/scans/{id}/screening",
+    tag = "screening",
+    params(("id" = String, Path, description = "Scan ID")),
+    request_body = ScreeningDecisionRequest,
+    responses(
+        (status = 200, body = ScanScreening),
+        (status = 400, description = "Status is not `cleared` or `blocked`", body = ErrorResponse),
+        (status = 404, description = "Scan or screening not found", body = ErrorResponse),
+    )
+)]
+pub async fn record_screening_decision(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<ScreeningDecisionRequest>,
+) -> Result<Json<ScanScreening>, AppError> {
+    let status = payload.status.trim().to_lowercase();
+    if status != "cleared" && status != "blocked" {
+        return Err(AppError::Validation(
+            "Screening status must be 'cleared' or 'blocked'".to_string(),
+        ));
+    }
+
+    let screening =
+        ScanScreening::record_decision(&state.db, &id, &status, payload.reference.as_deref())
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Scan {} has not been screened", id)))?;
+
+    tracing::info!("Scan {} screening {} ({:?})", id, status, screening.reference);
+    Ok(Json(screening))
+}
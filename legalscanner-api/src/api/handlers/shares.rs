@@ -0,0 +1,300 @@
+use crate::{
+    api::models::{
+        CreateScanShareRequest, CreateScanShareResponse, RiskFactor, SharedLicenseCount,
+        SharedRiskAssessment, SharedRiskFactor, SharedScanReport,
+    },
+    db::models::{Scan, ScanResult, ScanShare},
+    error::AppError,
+    git::info::repo_name_from_url,
+    local_scan,
+    utils::crypto,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// POST /api/v1/scans/:id/shares - Create a read-only share link for a scan report
+#[utoipa::path(
+    post,
+    path = "/api/v1/scans/{id}/shares",
+    tag = "shares",
+    params(("id" = String, Path, description = "Scan ID")),
+    request_body = Option<CreateScanShareRequest>,
+    responses(
+        (status = 201, body = CreateScanShareResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn create_scan_share(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    payload: Option<Json<CreateScanShareRequest>>,
+) -> Result<(StatusCode, Json<CreateScanShareResponse>), AppError> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+
+    if payload.expires_in_days == Some(0) {
+        return Err(AppError::Validation(
+            "expires_in_days must be at least 1".to_string(),
+        ));
+    }
+
+    let _ = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let token = crypto::generate_share_token();
+    let token_hash = crypto::hash_share_token(&token);
+
+    let share = ScanShare::create(&state.db, &id, token_hash, payload.expires_in_days).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateScanShareResponse {
+            id: share.id,
+            scan_id: share.scan_id,
+            url: format!("/api/v1/shared/{}", token),
+            token,
+            created_at: share.created_at,
+            expires_at: share.expires_at,
+            message: "Anyone with this link can view the scan report. It will not be shown again."
+                .to_string(),
+        }),
+    ))
+}
+
+/// GET /api/v1/scans/:id/shares - List share links for a scan
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/shares",
+    tag = "shares",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = Vec<ScanShare>),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_scan_shares(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<ScanShare>>, AppError> {
+    let shares = ScanShare::find_by_scan_id(&state.db, &id).await?;
+    Ok(Json(shares))
+}
+
+/// DELETE /api/v1/scans/:id/shares/:share_id - Revoke a share link
+#[utoipa::path(
+    delete,
+    path = "/api/v1/scans/{id}/shares/{share_id}",
+    tag = "shares",
+    params(("id" = String, Path, description = "Scan ID"), ("share_id" = String, Path, description = "Share link ID")),
+    responses(
+        (status = 204, description = "Share link revoked"),
+        (status = 404, description = "Share link not found", body = ErrorResponse),
+    )
+)]
+pub async fn revoke_scan_share(
+    State(state): State<AppState>,
+    Path((id, share_id)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    if !ScanShare::revoke(&state.db, &id, &share_id).await? {
+        return Err(AppError::NotFound(format!(
+            "Active share {} not found for scan {}",
+            share_id, id
+        )));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/v1/shared/:token - Public, unauthenticated read-only scan report
+///
+/// Unknown, expired and revoked tokens all return the same 404 so the
+/// endpoint does not reveal which links once existed.
+#[utoipa::path(
+    get,
+    path = "/api/v1/shared/{token}",
+    tag = "shares",
+    params(("token" = String, Path, description = "Share link token")),
+    responses(
+        (status = 200, body = SharedScanReport),
+        (status = 404, description = "Unknown, expired or revoked link", body = ErrorResponse),
+    )
+)]
+pub async fn get_shared_report(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Json<SharedScanReport>, AppError> {
+    let not_found = || AppError::NotFound("Shared report not found".to_string());
+
+    let token_hash = crypto::hash_share_token(&token);
+
+    let share = ScanShare::find_active_by_hash(&state.db, &token_hash)
+        .await?
+        .ok_or_else(not_found)?;
+
+    let scan = Scan::find_by_id(&state.db, &share.scan_id)
+        .await?
+        .ok_or_else(not_found)?;
+
+    let summary = state.result_store.summarize(&scan.id).await.ok();
+    let results = state.result_store.load_results(&scan.id).await?;
+
+    let risk_assessment = match (scan.risk_score, &scan.risk_level, &scan.risk_factors) {
+        (Some(score), Some(level), Some(factors_json)) => {
+            match serde_json::from_str::<Vec<RiskFactor>>(factors_json) {
+                Ok(factors) => Some(SharedRiskAssessment {
+                    score,
+                    level: level.clone(),
+                    factors: factors
+                        .into_iter()
+                        .map(|f| SharedRiskFactor {
+                            category: f.category,
+                            severity: f.severity,
+                            description: f.description,
+                            affected_count: f.affected_count,
+                        })
+                        .collect(),
+                    preliminary: scan.quick,
+                }),
+                Err(e) => {
+                    tracing::error!("Failed to parse risk factors: {}", e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    Ok(Json(SharedScanReport {
+        warnings: scan.warning_list(),
+        repository_url: shared_repository_url(&scan.git_url),
+        status: scan.status,
+        scan_date: scan.created_at,
+        completed_at: scan.completed_at,
+        summary,
+        risk_assessment,
+        licenses: license_counts(&results),
+        ecc_findings_by_severity: ecc_severity_counts(&results),
+        malware_findings: results
+            .iter()
+            .filter(|r| r.result_type == "malware")
+            .count() as i64,
+        expires_at: share.expires_at,
+    }))
+}
+
+/// Repository shown to share link holders. Directories on the server and
+/// local clones are reduced to their name, so the report does not reveal
+/// paths on the server.
+fn shared_repository_url(git_url: &str) -> String {
+    if local_scan::is_local(git_url) || git_url.starts_with("file://") || git_url.starts_with('/') {
+        repo_name_from_url(git_url)
+    } else {
+        git_url.to_string()
+    }
+}
+
+/// Count distinct files per detected license, most common first
+fn license_counts(results: &[ScanResult]) -> Vec<SharedLicenseCount> {
+    let mut files: HashMap<(&str, Option<&str>), HashSet<&str>> = HashMap::new();
+
+    for result in results.iter().filter(|r| r.result_type == "license") {
+        if let Some(name) = result.license_name.as_deref() {
+            files
+                .entry((name, result.license_spdx_id.as_deref()))
+                .or_default()
+                .insert(result.file_path.as_str());
+        }
+    }
+
+    let mut counts: Vec<SharedLicenseCount> = files
+        .into_iter()
+        .map(|((license, spdx_id), files)| SharedLicenseCount {
+            license: license.to_string(),
+            spdx_id: spdx_id.map(str::to_string),
+            file_count: files.len() as i64,
+        })
+        .collect();
+
+    counts.sort_by(|a, b| {
+        b.file_count
+            .cmp(&a.file_count)
+            .then_with(|| a.license.cmp(&b.license))
+    });
+    counts
+}
+
+fn ecc_severity_counts(results: &[ScanResult]) -> BTreeMap<String, i64> {
+    let mut counts = BTreeMap::new();
+    for result in results.iter().filter(|r| r.result_type == "ecc") {
+        let severity = result.risk_severity.as_deref().unwrap_or("unknown");
+        *counts.entry(severity.to_string()).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(
+        result_type: &str,
+        file: &str,
+        license: Option<&str>,
+        severity: Option<&str>,
+    ) -> ScanResult {
+        ScanResult {
+            license_name: license.map(str::to_string),
+            license_spdx_id: license.map(str::to_string),
+            risk_severity: severity.map(str::to_string),
+            ..ScanResult::for_test(result_type, file)
+        }
+    }
+
+    #[test]
+    fn test_license_counts_dedupes_files() {
+        let results = vec![
+            result("license", "a.rs", Some("MIT"), None),
+            result("license", "a.rs", Some("MIT"), None),
+            result("license", "b.rs", Some("MIT"), None),
+            result("license", "c.rs", Some("Apache-2.0"), None),
+            result("copyright", "c.rs", None, None),
+        ];
+
+        let counts = license_counts(&results);
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[0].license, "MIT");
+        assert_eq!(counts[0].file_count, 2);
+        assert_eq!(counts[1].license, "Apache-2.0");
+        assert_eq!(counts[1].file_count, 1);
+    }
+
+    #[test]
+    fn test_shared_repository_url_hides_server_paths() {
+        assert_eq!(
+            shared_repository_url("https://github.com/acme/app.git"),
+            "https://github.com/acme/app.git"
+        );
+        assert_eq!(shared_repository_url("local:///srv/builds/app"), "app");
+        assert_eq!(shared_repository_url("file:///tmp/fixtures/app.git"), "app");
+    }
+
+    #[test]
+    fn test_ecc_severity_counts() {
+        let results = vec![
+            result("ecc", "a.rs", None, Some("high")),
+            result("ecc", "b.rs", None, Some("high")),
+            result("ecc", "c.rs", None, None),
+            result("license", "d.rs", Some("MIT"), None),
+        ];
+
+        let counts = ecc_severity_counts(&results);
+        assert_eq!(counts.get("high"), Some(&2));
+        assert_eq!(counts.get("unknown"), Some(&1));
+        assert_eq!(counts.len(), 2);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{
+    api::models::StatsResponse,
+    db::models::{
+        scan_result::{EccFindingDay, LicenseUsage},
+        Scan,
+    },
+    error::AppError,
+    AppState,
+};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use utoipa::IntoParams;
+
+const DEFAULT_DAYS: i64 = 30;
+const MAX_DAYS: i64 = 365;
+const DEFAULT_TOP_LICENSES: i64 = 10;
+const MAX_TOP_LICENSES: i64 = 100;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQueryParams {
+    /// Days of ECC findings to report, counting today (default 30, max 365)
+    days: Option<i64>,
+    /// Number of most common licenses to report (default 10, max 100)
+    top_licenses: Option<i64>,
+}
+
+/// GET /api/v1/stats - Organization-wide scan statistics for dashboards
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats",
+    tag = "metrics",
+    params(StatsQueryParams),
+    responses(
+        (status = 200, body = StatsResponse),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+    )
+)]
+pub async fn get_stats(
+    State(state): State<AppState>,
+    Query(params): Query<StatsQueryParams>,
+) -> Result<Json<StatsResponse>, AppError> {
+    let days = params.days.unwrap_or(DEFAULT_DAYS);
+    if !(1..=MAX_DAYS).contains(&days) {
+        return Err(AppError::Validation(format!(
+            "days must be between 1 and {}",
+            MAX_DAYS
+        )));
+    }
+    let top_licenses = params.top_licenses.unwrap_or(DEFAULT_TOP_LICENSES);
+    if !(1..=MAX_TOP_LICENSES).contains(&top_licenses) {
+        return Err(AppError::Validation(format!(
+            "top_licenses must be between 1 and {}",
+            MAX_TOP_LICENSES
+        )));
+    }
+    let since = (Utc::now().date_naive() - Duration::days(days - 1))
+        .format("%Y-%m-%d")
+        .to_string();
+
+    Ok(Json(StatsResponse {
+        scans_by_status: Scan::status_counts(&state.db).await?,
+        average_scan_duration_secs: Scan::average_duration_secs(&state.db).await?,
+        top_licenses: most_found_licenses(&state, top_licenses).await?,
+        repositories_by_risk_level: Scan::repository_risk_level_counts(&state.db).await?,
+        ecc_findings_by_day: ecc_findings_by_day(&state, &since).await?,
+        since,
+    }))
+}
+
+/// Licenses found in the most completed full scans, with the number of scans
+/// and files they were found in
+async fn most_found_licenses(state: &AppState, limit: i64) -> Result<Vec<LicenseUsage>, AppError> {
+    let scan_ids: Vec<String> = Scan::completed_days(&state.db, None, true)
+        .await?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+
+    let mut usage: BTreeMap<String, LicenseUsage> = BTreeMap::new();
+    for count in state.result_store.license_counts(&scan_ids).await? {
+        let license = usage
+            .entry(count.license.clone())
+            .or_insert_with(|| LicenseUsage {
+                license: count.license,
+                scan_count: 0,
+                file_count: 0,
+            });
+        license.scan_count += 1;
+        license.file_count += count.file_count;
+    }
+
+    let mut licenses: Vec<LicenseUsage> = usage.into_values().collect();
+    // Most scans first, then most files; the map already ordered them by name
+    licenses.sort_by_key(|l| std::cmp::Reverse((l.scan_count, l.file_count)));
+    licenses.truncate(limit.max(0) as usize);
+    Ok(licenses)
+}
+
+/// ECC findings of completed scans per day of completion and severity, for
+/// scans completed on or after `since` (YYYY-MM-DD)
+async fn ecc_findings_by_day(
+    state: &AppState,
+    since: &str,
+) -> Result<Vec<EccFindingDay>, AppError> {
+    let completed_on: HashMap<String, String> = Scan::completed_days(&state.db, Some(since), false)
+        .await?
+        .into_iter()
+        .filter_map(|(id, day)| Some((id, day?)))
+        .collect();
+    let scan_ids: Vec<String> = completed_on.keys().cloned().collect();
+
+    let mut days: BTreeMap<&str, EccFindingDay> = BTreeMap::new();
+    for count in state.result_store.ecc_counts(&scan_ids).await? {
+        let Some(day) = completed_on.get(&count.scan_id) else {
+            continue;
+        };
+        let findings = days.entry(day).or_insert_with(|| EccFindingDay {
+            day: day.clone(),
+            finding_count: 0,
+            critical: 0,
+            high: 0,
+            medium: 0,
+            low: 0,
+        });
+        findings.finding_count += count.finding_count;
+        match count.risk_severity.as_deref() {
+            Some("critical") => findings.critical += count.finding_count,
+            Some("high") => findings.high += count.finding_count,
+            Some("medium") => findings.medium += count.finding_count,
+            Some("low") => findings.low += count.finding_count,
+            _ => {}
+        }
+    }
+    Ok(days.into_values().collect())
+}
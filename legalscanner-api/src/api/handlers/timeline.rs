@@ -0,0 +1,127 @@
+use crate::{
+    api::models::{ScanTimelineResponse, TimelineEvent},
+    db::models::{Scan, ScanEvent},
+    error::AppError,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::NaiveDateTime;
+
+/// GET /api/v1/scans/:id/timeline - Ordered lifecycle events with durations
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/timeline",
+    tag = "scans",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = ScanTimelineResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_timeline(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScanTimelineResponse>, AppError> {
+    let _ = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let events = ScanEvent::find_by_scan_id(&state.db, &id).await?;
+    let (events, total_duration_ms) = build_timeline(events);
+
+    Ok(Json(ScanTimelineResponse {
+        scan_id: id,
+        events,
+        total_duration_ms,
+    }))
+}
+
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").ok()
+}
+
+/// The event that starts the span closed by `event`, if any
+fn matching_start<'a>(event: &ScanEvent, previous: &'a [ScanEvent]) -> Option<&'a ScanEvent> {
+    let start_type = match event.event_type.as_str() {
+        "clone_finished" => "clone_started",
+        "scanner_finished" | "scanner_failed" => "scanner_started",
+        _ => return None,
+    };
+    previous
+        .iter()
+        .rev()
+        .find(|e| e.event_type == start_type && e.scanner == event.scanner)
+}
+
+fn build_timeline(events: Vec<ScanEvent>) -> (Vec<TimelineEvent>, i64) {
+    let millis = |from: &ScanEvent, to: &ScanEvent| {
+        Some((parse_timestamp(&to.occurred_at)? - parse_timestamp(&from.occurred_at)?).num_milliseconds())
+    };
+
+    let timeline = events
+        .iter()
+        .enumerate()
+        .map(|(idx, event)| {
+            let previous = &events[..idx];
+            let duration_ms = match matching_start(event, previous) {
+                Some(start) => millis(start, event),
+                None => previous.last().and_then(|p| millis(p, event)),
+            };
+            TimelineEvent {
+                event_type: event.event_type.clone(),
+                scanner: event.scanner.clone(),
+                detail: event.detail.clone(),
+                timestamp: event.occurred_at.clone(),
+                duration_ms,
+            }
+        })
+        .collect();
+
+    let total = match (events.first(), events.last()) {
+        (Some(first), Some(last)) => millis(first, last).unwrap_or(0),
+        _ => 0,
+    };
+
+    (timeline, total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: i64, event_type: &str, scanner: Option<&str>, occurred_at: &str) -> ScanEvent {
+        ScanEvent {
+            id,
+            scan_id: "scan-1".to_string(),
+            event_type: event_type.to_string(),
+            scanner: scanner.map(str::to_string),
+            detail: None,
+            occurred_at: occurred_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_build_timeline_durations() {
+        let events = vec![
+            event(1, "created", None, "2025-01-14 10:00:00.000"),
+            event(2, "clone_started", None, "2025-01-14 10:00:00.250"),
+            event(3, "scanner_started", Some("fossology"), "2025-01-14 10:00:02.000"),
+            event(4, "scanner_started", Some("semgrep"), "2025-01-14 10:00:02.010"),
+            event(5, "clone_finished", None, "2025-01-14 10:00:01.750"),
+            event(6, "scanner_finished", Some("semgrep"), "2025-01-14 10:00:05.010"),
+            event(7, "scanner_finished", Some("fossology"), "2025-01-14 10:00:12.000"),
+        ];
+
+        let (timeline, total) = build_timeline(events);
+
+        assert_eq!(timeline[0].duration_ms, None);
+        assert_eq!(timeline[1].duration_ms, Some(250));
+        assert_eq!(timeline[4].duration_ms, Some(1500));
+        assert_eq!(timeline[5].duration_ms, Some(3000));
+        assert_eq!(timeline[6].duration_ms, Some(10000));
+        assert_eq!(total, 12000);
+    }
+}
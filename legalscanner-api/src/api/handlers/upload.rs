@@ -0,0 +1,206 @@
+use crate::{
+    api::models::ScanResponse,
+    audit::{self, Actor},
+    custom_fields,
+    db::models::{CustomField, Scan},
+    error::AppError,
+    progress::record_event,
+    upload::{self, ArchiveFormat},
+    AppState,
+};
+use axum::{
+    extract::{multipart::MultipartError, Multipart, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use serde_json::{Map, Value};
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+
+/// Form fields of an upload other than the archive itself
+#[derive(Default)]
+struct UploadForm {
+    name: Option<String>,
+    quick: bool,
+    scan_path: Option<String>,
+    callback_url: Option<String>,
+    custom_fields: Map<String, Value>,
+}
+
+/// POST /api/v1/scans/upload - Scan a zip or tar.gz archive of source code
+/// instead of cloning a repository. The archive is extracted into the scan
+/// workspace and removed afterwards; a lone top-level directory is stripped.
+#[utoipa::path(
+    post,
+    path = "/api/v1/scans/upload",
+    tag = "scans",
+    request_body(content = UploadScanRequest, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "Scan created", body = ScanResponse),
+        (status = 400, description = "Missing or unsupported archive, or invalid form field", body = ErrorResponse),
+        (status = 413, description = "Archive larger than UPLOAD_MAX_SIZE_MB", body = ErrorResponse),
+    )
+)]
+pub async fn upload_scan(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Result<(StatusCode, Json<ScanResponse>), AppError> {
+    let actor = Actor::from_headers(&state, &headers).await?;
+    let part = upload::archive_path(
+        &state.config.temp_workspace_dir,
+        &format!("{}.part", uuid::Uuid::new_v4()),
+    );
+
+    let result = create_upload_scan(&state, multipart, &part, actor.api_key_id()).await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&part).await;
+    }
+    let scan = result?;
+
+    let _ = record_event(&state, &scan.id, "created", None, Some("uploaded archive")).await;
+    let _ = record_event(&state, &scan.id, "queued", None, None).await;
+    audit::record(&state, &actor, "create", "scan", Some(&scan.id), Some("uploaded archive")).await?;
+
+    // Spawn background task to execute the scan
+    let scan_id = scan.id.clone();
+    let state_clone = state.clone();
+
+    tokio::spawn(async move {
+        super::scan_job::execute_scan_job(scan_id, state_clone).await;
+    });
+
+    Ok((StatusCode::CREATED, Json(ScanResponse::from(scan))))
+}
+
+/// Store the archive at `part`, validate the form and create the scan. The
+/// archive is moved to the scan's upload path once the scan exists.
+async fn create_upload_scan(
+    state: &AppState,
+    mut multipart: Multipart,
+    part: &Path,
+    created_by_key_id: Option<&str>,
+) -> Result<Scan, AppError> {
+    let mut form = UploadForm::default();
+    let mut file_name = None;
+
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        let field_name = field.name().unwrap_or_default().to_string();
+        if field_name == "file" {
+            if file_name.is_some() {
+                return Err(AppError::Validation(
+                    "Only one archive can be uploaded per scan".to_string(),
+                ));
+            }
+            file_name = Some(field.file_name().unwrap_or_default().to_string());
+
+            if let Some(dir) = part.parent() {
+                tokio::fs::create_dir_all(dir).await.map_err(|e| {
+                    AppError::Internal(format!("Failed to create upload dir: {}", e))
+                })?;
+            }
+            let mut file = tokio::fs::File::create(part)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to store upload: {}", e)))?;
+            while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+                file.write_all(&chunk)
+                    .await
+                    .map_err(|e| AppError::Internal(format!("Failed to store upload: {}", e)))?;
+            }
+            file.flush()
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to store upload: {}", e)))?;
+            continue;
+        }
+
+        let text = field.text().await.map_err(multipart_error)?;
+        let text = text.trim();
+        match field_name.as_str() {
+            "name" => form.name = Some(text.to_string()).filter(|n| !n.is_empty()),
+            "quick" => {
+                form.quick = text.parse().map_err(|_| {
+                    AppError::Validation(format!("quick must be true or false, got '{}'", text))
+                })?
+            }
+            "scan_path" => form.scan_path = Some(text.to_string()).filter(|p| !p.is_empty()),
+            "callback_url" => form.callback_url = Some(text.to_string()).filter(|u| !u.is_empty()),
+            "custom_fields" => {
+                form.custom_fields = serde_json::from_str(text).map_err(|e| {
+                    AppError::Validation(format!("custom_fields must be a JSON object: {}", e))
+                })?
+            }
+            other => {
+                return Err(AppError::Validation(format!(
+                    "Unknown form field '{}'",
+                    other
+                )))
+            }
+        }
+    }
+
+    let Some(file_name) = file_name else {
+        return Err(AppError::Validation(
+            "Missing archive, send it in the 'file' field".to_string(),
+        ));
+    };
+
+    let format = ArchiveFormat::detect_file(part)
+        .map_err(|e| AppError::Internal(format!("Failed to read upload: {}", e)))?;
+    if format.is_none() {
+        return Err(AppError::Validation(
+            "Unsupported archive, upload a zip, tar or tar.gz file".to_string(),
+        ));
+    }
+
+    if let Some(callback_url) = &form.callback_url {
+        crate::webhooks::validate_callback_url(callback_url).map_err(AppError::Validation)?;
+    }
+
+    let scan_path = form
+        .scan_path
+        .as_deref()
+        .map(crate::git::workspace::normalize_scan_path)
+        .transpose()
+        .map_err(AppError::Validation)?;
+
+    let fields = CustomField::list(&state.db).await?;
+    let custom_field_values = custom_fields::validate_values(&fields, &form.custom_fields, true)
+        .map_err(AppError::Validation)?;
+
+    let git_url = upload::upload_url(form.name.as_deref(), Some(file_name.as_str()));
+    let mut scan = Scan::create(&state.db, git_url, None, created_by_key_id.map(str::to_string)).await?;
+
+    let archive = upload::archive_path(&state.config.temp_workspace_dir, &scan.id);
+    if let Err(e) = tokio::fs::rename(part, &archive).await {
+        let _ = Scan::delete_permanently(&state.db, &scan.id).await;
+        return Err(AppError::Internal(format!("Failed to store upload: {}", e)));
+    }
+
+    if form.quick {
+        Scan::set_quick(&state.db, &scan.id).await?;
+        scan.quick = true;
+    }
+
+    if let Some(scan_path) = scan_path {
+        Scan::set_scan_path(&state.db, &scan.id, &scan_path).await?;
+        scan.scan_path = Some(scan_path);
+    }
+
+    if let Some(callback_url) = &form.callback_url {
+        Scan::set_callback_url(&state.db, &scan.id, callback_url).await?;
+        scan.callback_url = Some(callback_url.clone());
+    }
+
+    custom_fields::apply(&state.db, &scan.id, &custom_field_values).await?;
+
+    Ok(scan)
+}
+
+/// Requests over the body limit surface as 413, other multipart errors as 400
+fn multipart_error(e: MultipartError) -> AppError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AppError::PayloadTooLarge(e.body_text())
+    } else {
+        AppError::Validation(e.body_text())
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{
+    api::models::{VerifyLicenseRequest, VerifyLicenseResponse},
+    error::AppError,
+    git::{clone_repository_with_depth, workspace::Workspace, GitAuth},
+    progress::ProgressReporter,
+    scanner::declared::{detect_declared_licenses, license_matches},
+    AppState,
+};
+use axum::{extract::State, Json};
+
+/// POST /api/v1/verify - Check a repository's declared license against an expected license
+///
+/// Only root license files and package manifests of a shallow clone are inspected,
+/// so this is fast enough to run as a pre-merge check.
+#[utoipa::path(
+    post,
+    path = "/api/v1/verify",
+    tag = "verify",
+    request_body = VerifyLicenseRequest,
+    responses(
+        (status = 200, body = VerifyLicenseResponse),
+        (status = 400, description = "Invalid repository URL or clone failure", body = ErrorResponse),
+    )
+)]
+pub async fn verify_license(
+    State(state): State<AppState>,
+    Json(payload): Json<VerifyLicenseRequest>,
+) -> Result<Json<VerifyLicenseResponse>, AppError> {
+    crate::git::validate_git_url(&payload.git_url, &state.config.git_url_schemes)
+        .map_err(AppError::Validation)?;
+    let git_url = crate::git::normalize_git_url(&payload.git_url);
+
+    if payload.expected_license.trim().is_empty() {
+        return Err(AppError::Validation(
+            "Expected license cannot be empty".to_string(),
+        ));
+    }
+
+    let workspace = Workspace::new(
+        state.config.temp_workspace_dir.clone(),
+        format!("verify-{}", uuid::Uuid::new_v4()),
+    );
+    let workspace_path = workspace
+        .create()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to create workspace: {}", e)))?;
+
+    let clone_result = clone_repository_with_depth(
+        &git_url,
+        &workspace_path,
+        payload
+            .git_token
+            .clone()
+            .map(|token| GitAuth::for_token(&git_url, token))
+            .as_ref(),
+        Some(1),
+        &ProgressReporter::disabled(),
+        (state.config.clone_timeout_secs > 0)
+            .then(|| std::time::Duration::from_secs(state.config.clone_timeout_secs)),
+    )
+    .await;
+
+    let declared_licenses = match clone_result {
+        Ok(()) => detect_declared_licenses(&workspace_path).await,
+        Err(e) => {
+            workspace.cleanup().await.ok();
+            return Err(AppError::Git(e));
+        }
+    };
+
+    if let Err(e) = workspace.cleanup().await {
+        tracing::warn!("Failed to clean up verify workspace: {}", e);
+    }
+
+    let passed = declared_licenses
+        .iter()
+        .any(|d| license_matches(&payload.expected_license, &d.license));
+
+    let message = if declared_licenses.is_empty() {
+        "No declared license found in root license files or package manifests".to_string()
+    } else if passed {
+        format!("Repository declares {}", payload.expected_license)
+    } else {
+        format!(
+            "Expected {} but repository declares {}",
+            payload.expected_license,
+            declared_licenses
+                .iter()
+                .map(|d| d.license.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+
+    Ok(Json(VerifyLicenseResponse {
+        git_url,
+        expected_license: payload.expected_license,
+        passed,
+        declared_licenses,
+        message,
+    }))
+}
@@ -0,0 +1,182 @@
+use crate::{
+    api::handlers::scans::parse_timestamp,
+    api::models::{CreateWaiverRequest, CreateWaiverResponse, WaiversResponse},
+    db::models::{Scan, Waiver},
+    error::AppError,
+    scan_diff::normalize_repository_url,
+    waiver, AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WaiversQueryParams {
+    /// Only waivers of this scan
+    scan_id: Option<String>,
+    /// Only waivers of this repository, compared regardless of case, a
+    /// trailing slash or `.git`
+    git_url: Option<String>,
+    /// Also list expired waivers
+    #[serde(default)]
+    include_expired: bool,
+}
+
+/// POST /api/v1/waivers - Waive findings of a scan or repository
+#[utoipa::path(
+    post,
+    path = "/api/v1/waivers",
+    tag = "waivers",
+    request_body = CreateWaiverRequest,
+    responses(
+        (status = 201, body = CreateWaiverResponse),
+        (status = 400, description = "Invalid scope, criteria or expiry", body = ErrorResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn create_waiver(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateWaiverRequest>,
+) -> Result<(StatusCode, Json<CreateWaiverResponse>), AppError> {
+    let non_empty = |v: &Option<String>| {
+        v.as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    let justification = payload.justification.trim();
+    if justification.is_empty() {
+        return Err(AppError::Validation(
+            "justification is required".to_string(),
+        ));
+    }
+
+    let scan_id = non_empty(&payload.scan_id);
+    let repository = non_empty(&payload.git_url).map(|url| normalize_repository_url(&url));
+    match (&scan_id, &repository) {
+        (None, None) => {
+            return Err(AppError::Validation(
+                "Either scan_id or git_url is required".to_string(),
+            ))
+        }
+        (Some(_), Some(_)) => {
+            return Err(AppError::Validation(
+                "Give either scan_id or git_url, not both".to_string(),
+            ))
+        }
+        (Some(id), None) => {
+            Scan::find_by_id(&state.db, id)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+        }
+        (None, Some(_)) => {}
+    }
+
+    let file_path = non_empty(&payload.file_path).map(|p| {
+        p.trim_start_matches("./")
+            .trim_start_matches('/')
+            .to_string()
+    });
+    let check_id = non_empty(&payload.check_id);
+    let license = non_empty(&payload.license);
+    if file_path.is_none() && check_id.is_none() && license.is_none() {
+        return Err(AppError::Validation(
+            "At least one of file_path, check_id or license is required".to_string(),
+        ));
+    }
+
+    let expires_at = non_empty(&payload.expires_at)
+        .map(|value| parse_timestamp("expires_at", &value))
+        .transpose()?;
+    if let Some(expires_at) = &expires_at {
+        let now = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        if *expires_at <= now {
+            return Err(AppError::Validation(
+                "expires_at must be in the future".to_string(),
+            ));
+        }
+    }
+
+    let waiver = Waiver::create(
+        &state.db,
+        scan_id.as_deref(),
+        repository.as_deref(),
+        file_path.as_deref(),
+        check_id.as_deref(),
+        license.as_deref(),
+        justification,
+        expires_at.as_deref(),
+    )
+    .await?;
+    let rescored_scans = waiver::rescore(&state, &waiver).await?;
+    tracing::info!(
+        "Created waiver {}, rescored {} scans",
+        waiver.id,
+        rescored_scans
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateWaiverResponse {
+            waiver,
+            rescored_scans,
+        }),
+    ))
+}
+
+/// GET /api/v1/waivers - Unexpired waivers, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/waivers",
+    tag = "waivers",
+    params(WaiversQueryParams),
+    responses((status = 200, body = WaiversResponse))
+)]
+pub async fn list_waivers(
+    State(state): State<AppState>,
+    Query(params): Query<WaiversQueryParams>,
+) -> Result<Json<WaiversResponse>, AppError> {
+    let repository = params.git_url.as_deref().map(normalize_repository_url);
+    let waivers = Waiver::list(
+        &state.db,
+        params.scan_id.as_deref(),
+        repository.as_deref(),
+        params.include_expired,
+    )
+    .await?;
+
+    Ok(Json(WaiversResponse { waivers }))
+}
+
+/// DELETE /api/v1/waivers/:id - Delete a waiver; its findings count towards
+/// the risk score again
+#[utoipa::path(
+    delete,
+    path = "/api/v1/waivers/{id}",
+    tag = "waivers",
+    params(("id" = String, Path, description = "Waiver ID")),
+    responses(
+        (status = 204, description = "Waiver deleted"),
+        (status = 404, description = "Waiver not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_waiver(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let waiver = Waiver::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Waiver {} not found", id)))?;
+
+    Waiver::delete(&state.db, &id).await?;
+    waiver::rescore(&state, &waiver).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
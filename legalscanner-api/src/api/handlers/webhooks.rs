@@ -0,0 +1,39 @@
+use crate::{
+    api::models::WebhookDeliveriesResponse,
+    db::models::{Scan, WebhookDelivery},
+    error::AppError,
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+/// GET /api/v1/scans/:id/webhook-deliveries - Audit log of the scan's
+/// callback delivery attempts
+#[utoipa::path(
+    get,
+    path = "/api/v1/scans/{id}/webhook-deliveries",
+    tag = "scans",
+    params(("id" = String, Path, description = "Scan ID")),
+    responses(
+        (status = 200, body = WebhookDeliveriesResponse),
+        (status = 404, description = "Scan not found", body = ErrorResponse),
+    )
+)]
+pub async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<WebhookDeliveriesResponse>, AppError> {
+    let scan = Scan::find_by_id(&state.db, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", id)))?;
+
+    let deliveries = WebhookDelivery::find_by_scan_id(&state.db, &id).await?;
+
+    Ok(Json(WebhookDeliveriesResponse {
+        scan_id: scan.id,
+        callback_url: scan.callback_url,
+        deliveries,
+    }))
+}
@@ -1,2 +1,6 @@
 // API middleware
 // TODO: Implement API key authentication middleware
+
+/// Path prefixes that must stay reachable without an API key once
+/// authentication is enforced. Share links are authorised by their token.
+pub const PUBLIC_PATH_PREFIXES: &[&str] = &["/health", "/api/v1/shared/"];
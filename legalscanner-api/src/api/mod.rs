@@ -1,4 +1,5 @@
 pub mod handlers;
 pub mod middleware;
 pub mod models;
+pub mod openapi;
 pub mod routes;
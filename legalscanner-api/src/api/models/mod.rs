@@ -1,16 +1,96 @@
+use crate::codeowners::OwnerSummary;
+use crate::db::models::scan::{RepositoryRiskLevelCount, ScanStatusCount, ScanSummary};
+use crate::db::models::scan_result::{EccFindingDay, LicenseUsage};
+use crate::db::models::{
+    AuditLogEntry, CopyrightHolder, CopyrightHolderProject, CustomField, IntegrityEvent, LegalHoldEvent, LicenseCuration,
+    NotificationTemplate, Policy, Scan, ScanFileAuthor, ScanFileSwhid, ScanHistoryFinding, ScanResultComment, TrackedFinding, Waiver,
+    WebhookDelivery,
+};
+use crate::export::component_license::ComponentLicense;
+use crate::remediation::Remediation;
+use crate::scan_diff::ScanDiff;
+use crate::scanner::declared::DeclaredLicense;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// Body of every error response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    /// Short error category, or the message for client errors
+    pub error: String,
+    pub details: String,
+}
 
 // Scan models
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateScanRequest {
+    #[serde(default)]
     pub git_url: String,
     #[serde(default)]
     pub branch: Option<String>,
+    /// Stored in plaintext with the scan; prefer `credential_id`
     #[serde(default)]
     pub git_token: Option<String>,
+    /// Registered credential to clone with, instead of `git_token`
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    /// Semgrep rule files / registry rulesets overriding the server defaults
+    #[serde(default)]
+    pub semgrep_rulesets: Option<Vec<String>>,
+    /// Time-boxed preview: skip Fossology and run only fast analyses
+    #[serde(default)]
+    pub quick: bool,
+    /// Also scan the git history for licensed code removed from HEAD
+    #[serde(default)]
+    pub due_diligence: bool,
+    /// Also attribute files to their commit authors from the git history
+    #[serde(default)]
+    pub git_authorship: bool,
+    /// Notified with a signed POST when the scan completes or fails
+    #[serde(default)]
+    pub callback_url: Option<String>,
+    /// Only check out and scan this subdirectory, e.g. `services/payments`,
+    /// with a sparse checkout. Results and the SBOM are scoped to it. Also
+    /// accepted as `subpath`.
+    #[serde(default, alias = "subpath")]
+    pub scan_path: Option<String>,
+    /// Values of the deployment's custom fields, by field key
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub custom_fields: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Scan this server directory in place instead of cloning `git_url`.
+    /// Admin only, needs `SEMGREP_MODE=local`, and the directory must be
+    /// under `LOCAL_SCAN_BASE_DIRS`.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Also check out git submodules, recursively, and scan their code
+    #[serde(default)]
+    pub submodules: bool,
+    /// Token for cloning submodules hosted elsewhere; `git_token` is used
+    /// when unset. Stored encrypted, so `CREDENTIALS_ENCRYPTION_KEY` must be
+    /// set.
+    #[serde(default)]
+    pub submodule_token: Option<String>,
+}
+
+/// Multipart form of `POST /api/v1/scans/upload`
+#[derive(Debug, ToSchema)]
+pub struct UploadScanRequest {
+    /// zip, tar or tar.gz archive of the source code
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+    /// Shown as the scan's repository, `upload://<name>`. Defaults to the
+    /// archive's file name.
+    pub name: Option<String>,
+    pub quick: Option<bool>,
+    pub scan_path: Option<String>,
+    pub callback_url: Option<String>,
+    /// JSON object of custom field values, by field key
+    pub custom_fields: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ScanResponse {
     pub scan_id: String,
     pub status: String,
@@ -18,30 +98,725 @@ pub struct ScanResponse {
     pub git_url: String,
     pub fossology_status: String,
     pub semgrep_status: String,
+    pub quick: bool,
+    pub due_diligence: bool,
+    pub git_authorship: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub risk_score: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub risk_level: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_scan_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Commit checked out instead of the head of the branch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_sha: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_id: Option<String>,
+}
+
+impl From<Scan> for ScanResponse {
+    fn from(scan: Scan) -> Self {
+        Self {
+            scan_id: scan.id,
+            status: scan.status,
+            created_at: scan.created_at,
+            git_url: scan.git_url,
+            fossology_status: scan.fossology_status,
+            semgrep_status: scan.semgrep_status,
+            quick: scan.quick,
+            due_diligence: scan.due_diligence,
+            git_authorship: scan.git_authorship,
+            risk_score: scan.risk_score,
+            risk_level: scan.risk_level,
+            parent_scan_id: scan.parent_scan_id,
+            scan_path: scan.scan_path,
+            branch: scan.branch,
+            commit_sha: scan.commit_sha,
+            credential_id: scan.credential_id,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RerunScanRequest {
+    /// Only repeat the scanners that failed; the others are skipped
+    #[serde(default)]
+    pub failed_only: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ScanResultsResponse {
     pub scan_id: String,
     pub repository_url: String,
     pub scan_date: String,
     pub status: String,
+    /// Findings grouped by type
+    #[schema(value_type = Object)]
     pub results: serde_json::Value,
+    /// Set when more results follow; pass it back as `cursor`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanTimelineResponse {
+    pub scan_id: String,
+    pub events: Vec<TimelineEvent>,
+    /// Milliseconds between the first and last event
+    pub total_duration_ms: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimelineEvent {
+    pub event_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scanner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub timestamp: String,
+    /// For *_finished/*_failed events the time since the matching start,
+    /// otherwise the time since the previous event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<i64>,
+}
+
+// License summary models
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanLicenseSummaryResponse {
+    pub scan_id: String,
+    /// License of the repository as a whole, concluded from its license
+    /// files, manifests and the licenses of its files
+    pub concluded_license: ComponentLicense,
+    /// Licenses by file count, most common first
+    pub licenses: Vec<LicenseSummary>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LicenseSummary {
+    pub license: String,
+    pub spdx_id: Option<String>,
+    pub file_count: i64,
+    /// A few of the files, relative to the repository root
+    pub example_paths: Vec<String>,
+    /// Points the license adds to the risk score, 0 for unrated licenses
+    pub risk_weight: i32,
+}
+
+// Coverage models
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanCoverageResponse {
+    pub scan_id: String,
+    /// Languages by file count, most common first
+    pub languages: Vec<LanguageCoverage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct LanguageCoverage {
+    pub language: String,
+    /// Files of the language in the scanned checkout
+    pub file_count: i64,
+    pub total_bytes: i64,
+    /// Files with at least one license finding
+    pub licensed_files: i64,
+    /// Share of the files with a license finding, 0 to 100
+    pub coverage_percent: f64,
+    /// Files per detected license, most common first
+    pub licenses: Vec<LicenseFileCount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct LicenseFileCount {
+    pub license: String,
+    pub file_count: i64,
+}
+
+// Curation models
+/// Body of `POST /api/v1/curations/import/fossology`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportFossologyDecisionsRequest {
+    /// Repository the decisions apply to
+    pub git_url: String,
+    /// Fossology upload whose clearing decisions are imported
+    pub upload_id: i32,
+    /// Directory of the repository inside the upload, for uploads not made
+    /// by LegalScanner
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+}
+
+/// Body of `POST /api/v1/curations/export/fossology`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ExportFossologyDecisionsRequest {
+    /// Repository whose curations are exported
+    pub git_url: String,
+    /// Fossology upload the clearing decisions are recorded on
+    pub upload_id: i32,
+    /// Directory of the repository inside the upload, for uploads not made
+    /// by LegalScanner
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LicenseCurationsResponse {
+    pub repository: String,
+    pub curations: Vec<LicenseCuration>,
+}
+
+// Ownership models
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanOwnersResponse {
+    pub scan_id: String,
+    /// CODEOWNERS file the findings were attributed with, `null` when the
+    /// repository has none
+    pub codeowners_path: Option<String>,
+    /// Owners with the most violations first, unowned findings last
+    pub owners: Vec<OwnerSummary>,
+}
+
+// Repository metadata models
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanRepositoryMetadataResponse {
+    pub scan_id: String,
+    /// `github` or `gitlab` when the provider's API was read, `null` when
+    /// only the checkout was (no token, or another host)
+    pub provider: Option<String>,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub default_branch: Option<String>,
+    pub topics: Vec<String>,
+    pub latest_tag: Option<String>,
+    pub contributor_count: Option<i64>,
+    /// Full SHA of the scanned commit
+    pub commit_sha: Option<String>,
+    /// Commit date, RFC 3339 in UTC
+    pub commit_date: Option<String>,
+    /// Name of the commit's author
+    pub commit_author: Option<String>,
+    /// Tag pointing at the scanned commit
+    pub head_tag: Option<String>,
+    /// Size of the working tree's files, `.git` excluded
+    pub size_bytes: Option<i64>,
+    pub file_count: Option<i64>,
+    /// Files per programming language, the most common first
+    pub languages: Vec<LanguageFiles>,
+    pub captured_at: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LanguageFiles {
+    pub language: String,
+    pub file_count: i64,
+    pub total_bytes: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanSwhidsResponse {
+    pub scan_id: String,
+    /// Revision SWHID of the scanned commit, unset for scans of uploads and
+    /// local directories
+    pub revision_swhid: Option<String>,
+    /// Content SWHID of each scanned file, by path
+    pub files: Vec<ScanFileSwhid>,
+}
+
+// History models
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanHistoryResponse {
+    pub scan_id: String,
+    /// False if the scan did not walk the history; `findings` is then empty
+    pub due_diligence: bool,
+    pub findings: Vec<ScanHistoryFinding>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanAuthorsResponse {
+    pub scan_id: String,
+    /// False if the scan did not attribute files to commit authors; `files`
+    /// is then empty
+    pub git_authorship: bool,
+    /// Commit authors of each file, by path
+    pub files: Vec<ScanFileAuthor>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LicenseDisputesResponse {
+    pub scan_id: String,
+    /// Files the license scanners disagree on, by path
+    pub files: Vec<DisputedFile>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DisputedFile {
+    pub file_path: String,
+    /// Every license finding of the file, corroborated ones included
+    pub licenses: Vec<DisputedLicense>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DisputedLicense {
+    pub license: Option<String>,
+    pub spdx_id: Option<String>,
+    pub confidence: Option<f32>,
+    /// Scanner(s) that reported the license
+    pub provenance: Option<String>,
+    /// `corroborated` or `disputed`
+    pub agreement: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResultSampleResponse {
+    pub scan_id: String,
+    /// license, type, severity or none
+    pub stratify: String,
+    /// Findings matching the filters, before sampling
+    pub total: usize,
+    pub strata: Vec<SampleStratum>,
+    pub findings: Vec<SampledFinding>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SampleStratum {
+    pub key: String,
+    pub total: usize,
+    pub sampled: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SampledFinding {
+    pub id: i64,
+    /// Key of the stratum the finding was drawn from
+    pub stratum: String,
+    pub result_type: String,
+    pub file_path: String,
+    pub license: Option<String>,
+    pub spdx_id: Option<String>,
+    pub confidence: Option<f32>,
+    pub copyright: Option<String>,
+    pub severity: Option<String>,
+    pub check_id: Option<String>,
+    pub line_number: Option<i32>,
+    pub package: Option<String>,
+    pub review_status: String,
+    pub waiver_id: Option<String>,
+}
+
+// Admin models
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ErasureRequest {
+    /// Erase every scan of this repository
+    #[serde(default)]
+    pub git_url: Option<String>,
+    /// Erase findings and registry entries mentioning this e-mail address
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+/// Body of `POST /api/v1/admin/purge`. The retention periods default to
+/// `RETENTION_RESULTS_DAYS` and `RETENTION_SCANS_DAYS`, 0 keeps forever.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PurgeRequest {
+    /// Purge the findings of scans finished at least this many days ago
+    #[serde(default)]
+    pub results_older_than_days: Option<u64>,
+    /// Delete scans finished at least this many days ago
+    #[serde(default)]
+    pub scans_older_than_days: Option<u64>,
+    /// Only report what would be removed
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Shrink the database file afterwards; blocks other writes meanwhile
+    #[serde(default)]
+    pub vacuum: bool,
+}
+
+/// Body of `POST /api/v1/admin/archive`. The age defaults to
+/// `RESULT_ARCHIVE_AFTER_DAYS`.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ArchiveRequest {
+    /// Archive the findings of scans finished at least this many days ago
+    #[serde(default)]
+    pub older_than_days: Option<u64>,
+    /// Only report what would be archived
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Body of `POST /api/v1/admin/risk/recalculate`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecalculateRiskRequest {
+    /// Rescore every completed scan, not only those assessed with another
+    /// algorithm or risk configuration version
+    #[serde(default)]
+    pub all: bool,
+    /// Only report which scores would change
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Body of `PUT /api/v1/admin/scans/:id/legal-hold` and
+/// `PUT /api/v1/admin/projects/:id/legal-hold`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LegalHoldRequest {
+    /// Why the data must be kept, e.g. the matter or case reference
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LegalHoldResponse {
+    pub target_type: String,
+    pub target_id: String,
+    /// `None` once the hold is lifted
+    pub legal_hold_at: Option<String>,
+    pub legal_hold_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LegalHoldEventsResponse {
+    pub events: Vec<LegalHoldEvent>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IntegrityEventsResponse {
+    pub events: Vec<IntegrityEvent>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditLogResponse {
+    pub entries: Vec<AuditLogEntry>,
+}
+
+// Project models
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateProjectRequest {
+    /// Defaults to the name of the first repository, as its provider reports
+    /// it when a scan captured that
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Defaults to the first repository's description, when a scan captured one
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Git URLs whose scans belong to the project
+    #[serde(default)]
+    pub repositories: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct UpdateProjectRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Replaces the project's repositories when given
+    #[serde(default)]
+    pub repositories: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectResponse {
+    pub project_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Normalized: lowercase, without a trailing slash or `.git`
+    pub repositories: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Set while the project and its scans are under legal hold
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legal_hold_at: Option<String>,
+    /// License policy of the scans of its repositories
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectLatestScansResponse {
+    pub project_id: String,
+    pub repositories: Vec<RepositoryLatestScan>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RepositoryLatestScan {
+    pub git_url: String,
+    /// Most recently created scan, whatever its status
+    pub latest_scan: Option<ScanResponse>,
+    /// Most recently completed full scan, the one the project SBOM uses
+    pub latest_completed_scan: Option<ScanResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectRiskTrendResponse {
+    pub project_id: String,
+    /// Completed full scans, oldest first
+    pub points: Vec<RiskTrendPoint>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RiskTrendPoint {
+    pub scan_id: String,
+    pub git_url: String,
+    pub completed_at: Option<String>,
+    pub risk_score: Option<i32>,
+    pub risk_level: Option<String>,
+}
+
+// Composite scan models
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateCompositeScanRequest {
+    /// Token used to clone every repository of the project
+    #[serde(default)]
+    pub git_token: Option<String>,
+    /// Registered credential to clone with, instead of `git_token`
+    #[serde(default)]
+    pub credential_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CompositeScanResponse {
+    pub composite_scan_id: String,
+    pub project_id: String,
+    /// Name of the project the product is built from
+    pub product: String,
+    /// `in_progress` while a member scan is pending or running, then `failed`
+    /// if any member failed, else `completed`
+    pub status: String,
+    pub created_at: String,
+    /// One scan per repository
+    pub scans: Vec<ScanResponse>,
+    /// Risk of the findings of all member scans together, once completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk_assessment: Option<RiskAssessment>,
+}
+
+// GitHub organization import models
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportGithubOrgRequest {
+    /// Organization login, e.g. `acme`
+    pub org: String,
+    /// Token that lists the organization's repositories and clones them
+    #[serde(default)]
+    pub git_token: Option<String>,
+    /// Registered github credential to use instead of `git_token`
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    /// Only repositories with at least one of these topics
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// `all` (default), `public`, `private` or `internal`
+    #[serde(default)]
+    pub visibility: Option<String>,
+    #[serde(default)]
+    pub include_archived: bool,
+    #[serde(default)]
+    pub include_forks: bool,
+    /// List the matching repositories without starting scans
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Body of `POST /api/v1/import/repositories`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportRepositoriesRequest {
+    /// github, gitlab, bitbucket or azure_devops
+    pub provider: String,
+    /// GitHub organization, GitLab group path (`acme/platform`, subgroups
+    /// included), Bitbucket workspace or Azure DevOps `organization/project`
+    pub namespace: String,
+    /// Token that lists the repositories and clones them
+    #[serde(default)]
+    pub git_token: Option<String>,
+    /// Registered credential to use instead of `git_token`
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    /// Only repositories with at least one of these topics (GitHub and
+    /// GitLab)
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// `all` (default), `public`, `private` or `internal`
+    #[serde(default)]
+    pub visibility: Option<String>,
+    /// Archived repositories, disabled ones on Azure DevOps
+    #[serde(default)]
+    pub include_archived: bool,
+    #[serde(default)]
+    pub include_forks: bool,
+    /// List the matching repositories without starting scans
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl From<ImportGithubOrgRequest> for ImportRepositoriesRequest {
+    fn from(request: ImportGithubOrgRequest) -> Self {
+        Self {
+            provider: "github".to_string(),
+            namespace: request.org,
+            git_token: request.git_token,
+            credential_id: request.credential_id,
+            topics: request.topics,
+            visibility: request.visibility,
+            include_archived: request.include_archived,
+            include_forks: request.include_forks,
+            dry_run: request.dry_run,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportedRepository {
+    pub name: String,
+    pub git_url: String,
+    pub visibility: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    pub topics: Vec<String>,
+    /// Scan of the repository, absent for a dry run. With
+    /// `SCAN_LOCK_PER_REPOSITORY` this can be a scan that was already running.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan: Option<ScanResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GithubOrgImportResponse {
+    pub org: String,
+    /// Repositories of the organization before filtering
+    pub listed: usize,
+    pub dry_run: bool,
+    /// Repositories that matched the filters
+    pub repositories: Vec<ImportedRepository>,
+}
+
+/// Outcome of a push webhook delivery
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PushHookResponse {
+    /// github or gitlab
+    pub provider: String,
+    /// Repository and branch pushed to, absent for other events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Projects the repository belongs to
+    pub projects: Vec<String>,
+    /// Scan started for the push, or the scan of the branch still running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan: Option<ScanResponse>,
+    /// Why nothing was scanned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignored: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RepositoryImportResponse {
+    pub provider: String,
+    pub namespace: String,
+    /// Repositories of the namespace before filtering
+    pub listed: usize,
+    pub dry_run: bool,
+    /// Repositories that matched the filters
+    pub repositories: Vec<ImportedRepository>,
+}
+
+// Webhook models
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookDeliveriesResponse {
+    pub scan_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>,
+    /// Delivery attempts, oldest first
+    pub deliveries: Vec<WebhookDelivery>,
+}
+
+/// Body of `PUT /api/v1/notification-templates/:event`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpsertNotificationTemplateRequest {
+    /// Defaults to `application/json`
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Payload with `{{variable}}` placeholders
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationTemplatesResponse {
+    pub templates: Vec<NotificationTemplate>,
+    /// Events that can be given a template
+    pub events: Vec<String>,
+    /// Placeholders available to every template
+    pub variables: Vec<String>,
+}
+
+// Diff models
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanDiffResponse {
+    /// Baseline scan
+    pub scan_id: String,
+    /// Scan compared against the baseline
+    pub other_scan_id: String,
+    pub git_url: String,
+    pub risk: RiskScoreDelta,
+    #[serde(flatten)]
+    pub diff: ScanDiff,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RiskScoreDelta {
+    pub score: Option<i32>,
+    pub other_score: Option<i32>,
+    /// `other_score - score`; positive when the other scan is riskier
+    pub delta: Option<i32>,
+    pub level: Option<String>,
+    pub other_level: Option<String>,
+}
+
+// License verification models
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerifyLicenseRequest {
+    pub git_url: String,
+    pub expected_license: String,
+    #[serde(default)]
+    pub git_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VerifyLicenseResponse {
+    pub git_url: String,
+    pub expected_license: String,
+    pub passed: bool,
+    pub declared_licenses: Vec<DeclaredLicense>,
+    pub message: String,
 }
 
 // Risk Assessment models
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RiskAssessment {
     pub score: i32,                  // 0-100
     pub level: String,               // low, medium, high, critical
     pub factors: Vec<RiskFactor>,
+    /// Set for quick scans, where Fossology did not run
+    #[serde(default)]
+    pub preliminary: bool,
+    /// Suggested actions for each factor category
+    #[serde(default)]
+    pub remediation: Vec<Remediation>,
+    /// Version of the scoring algorithm, `None` for assessments stored
+    /// before versions were recorded
+    #[serde(default)]
+    pub algorithm_version: Option<i32>,
+    /// Hash of the license weights and ECC path rules scored with
+    #[serde(default)]
+    pub config_version: Option<String>,
+    #[serde(default)]
+    pub assessed_at: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RiskFactor {
     pub category: String,            // e.g., "copyleft_license", "missing_spdx"
     pub severity: String,            // low, medium, high, critical
@@ -50,13 +825,429 @@ pub struct RiskFactor {
     pub details: Vec<String>,        // file paths or license names
 }
 
+// Share link models
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateScanShareRequest {
+    /// Link lifetime; links without an expiry stay valid until revoked
+    #[serde(default)]
+    pub expires_in_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateScanShareResponse {
+    pub id: String,
+    pub scan_id: String,
+    pub token: String,
+    pub url: String,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+    pub message: String,
+}
+
+/// Read-only compliance report served to unauthenticated share link holders.
+/// Carries aggregate counts only, never per-file results.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharedScanReport {
+    /// Remote URL of the repository, or only the directory name for scans
+    /// of directories on the server
+    pub repository_url: String,
+    pub status: String,
+    pub scan_date: String,
+    pub completed_at: Option<String>,
+    /// Soft limits the repository exceeded; findings may be incomplete
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    pub summary: Option<ScanSummary>,
+    pub risk_assessment: Option<SharedRiskAssessment>,
+    pub licenses: Vec<SharedLicenseCount>,
+    pub ecc_findings_by_severity: BTreeMap<String, i64>,
+    pub malware_findings: i64,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharedRiskAssessment {
+    pub score: i32,
+    pub level: String,
+    pub factors: Vec<SharedRiskFactor>,
+    pub preliminary: bool,
+}
+
+/// A risk factor without its file-level details
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharedRiskFactor {
+    pub category: String,
+    pub severity: String,
+    pub description: String,
+    pub affected_count: i32,
+}
+
+#[derive(Debug, Serialize, PartialEq, ToSchema)]
+pub struct SharedLicenseCount {
+    pub license: String,
+    pub spdx_id: Option<String>,
+    pub file_count: i64,
+}
+
+// Export control screening models
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ScreeningDecisionRequest {
+    /// `cleared` or `blocked`
+    pub status: String,
+    #[serde(default)]
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApproveScanResponse {
+    pub scan_id: String,
+    pub approved_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub screening_status: Option<String>,
+}
+
+// Copyright holder registry models
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CopyrightHolderDetailResponse {
+    #[serde(flatten)]
+    pub holder: CopyrightHolder,
+    pub projects: Vec<CopyrightHolderProject>,
+}
+
+// Waiver models
+/// Body of `POST /api/v1/waivers`. The waiver applies to one scan
+/// (`scan_id`) or to every scan of a repository (`git_url`) and covers the
+/// findings matching all of `file_path`, `check_id` and `license` it sets.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWaiverRequest {
+    #[serde(default)]
+    pub scan_id: Option<String>,
+    #[serde(default)]
+    pub git_url: Option<String>,
+    /// Repository-relative path; a trailing `/` covers a directory
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// ECC rule ID
+    #[serde(default)]
+    pub check_id: Option<String>,
+    /// SPDX ID or license name, compared case-insensitively
+    #[serde(default)]
+    pub license: Option<String>,
+    pub justification: String,
+    /// `YYYY-MM-DD` or RFC 3339; the waiver no longer applies from then on
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateWaiverResponse {
+    #[serde(flatten)]
+    pub waiver: Waiver,
+    /// Completed scans whose risk score was recalculated
+    pub rescored_scans: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WaiversResponse {
+    pub waivers: Vec<Waiver>,
+}
+
+// Review models
+/// Body of `POST /api/v1/scans/:id/results/review`. Without `result_ids`,
+/// every pending finding matching `result_type` and `severity` is reviewed.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReviewResultsRequest {
+    /// pending, approved or rejected
+    pub status: String,
+    /// Finding IDs as listed in the scan results
+    #[serde(default)]
+    pub result_ids: Option<Vec<i64>>,
+    #[serde(default)]
+    pub result_type: Option<String>,
+    #[serde(default)]
+    pub severity: Option<String>,
+    /// Added as a comment to every finding reviewed
+    #[serde(default)]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReviewResultsResponse {
+    /// Findings whose status changed
+    pub updated: u64,
+    pub review: ScanReviewResponse,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanReviewResponse {
+    pub scan_id: String,
+    /// When the scan was marked reviewed
+    pub reviewed_at: Option<String>,
+    pub pending: i64,
+    pub approved: i64,
+    pub rejected: i64,
+    /// Pending, unwaived findings that trip a failing gate rule: copyleft
+    /// licenses, critical or high ECC findings and malware. The scan can be
+    /// marked reviewed when this is 0.
+    pub unresolved_high_severity: i64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateResultCommentRequest {
+    pub body: String,
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResultCommentsResponse {
+    pub result_id: i64,
+    pub comments: Vec<ScanResultComment>,
+}
+
+// Finding lifecycle models
+/// Body of the finding transition endpoints
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct TransitionFindingRequest {
+    /// Why the finding was triaged, resolved or reopened
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrackedFindingsResponse {
+    pub findings: Vec<TrackedFinding>,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct FindingStateCounts {
+    pub open: i64,
+    pub triaged: i64,
+    pub resolved: i64,
+}
+
+impl FindingStateCounts {
+    pub fn add(&mut self, state: &str) {
+        match state {
+            "open" => self.open += 1,
+            "triaged" => self.triaged += 1,
+            "resolved" => self.resolved += 1,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LicenseFindingCounts {
+    pub license: String,
+    #[serde(flatten)]
+    pub counts: FindingStateCounts,
+    /// Opened before `since` and still open or triaged
+    pub unresolved_since: i64,
+}
+
+/// Remediation progress of the findings of a project's repositories
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectFindingsReport {
+    pub project_id: String,
+    /// Start of the reporting period
+    pub since: String,
+    /// Current state of every tracked finding
+    pub totals: FindingStateCounts,
+    /// Findings first opened in the period
+    pub opened_since: i64,
+    /// Findings resolved in the period and not reopened since
+    pub resolved_since: i64,
+    /// Findings opened before the period that are still open or triaged
+    pub unresolved_since: i64,
+    /// License findings by license
+    pub licenses: Vec<LicenseFindingCounts>,
+}
+
+// Custom field models
+/// Body of `PUT /api/v1/custom-fields/:key`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpsertCustomFieldRequest {
+    /// Shown in reports; defaults to the key
+    #[serde(default)]
+    pub label: Option<String>,
+    /// string, number, boolean, date or enum
+    pub field_type: String,
+    /// Allowed values of an enum field
+    #[serde(default)]
+    pub options: Vec<String>,
+    /// Regular expression string values must match
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// New scans must set the field
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CustomFieldResponse {
+    pub key: String,
+    pub label: String,
+    pub field_type: String,
+    pub options: Vec<String>,
+    pub pattern: Option<String>,
+    pub required: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<CustomField> for CustomFieldResponse {
+    fn from(field: CustomField) -> Self {
+        Self {
+            options: field.option_list(),
+            key: field.key,
+            label: field.label,
+            field_type: field.field_type,
+            pattern: field.pattern,
+            required: field.required,
+            created_at: field.created_at,
+            updated_at: field.updated_at,
+        }
+    }
+}
+
+/// Body of `PUT /api/v1/policies/:name`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpsertPolicyRequest {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// SPDX IDs or license names; when not empty, licenses on none of the
+    /// lists fail
+    #[serde(default)]
+    pub allowed_licenses: Vec<String>,
+    /// Licenses that fail the policy
+    #[serde(default)]
+    pub denied_licenses: Vec<String>,
+    /// Licenses that warn until reviewed
+    #[serde(default)]
+    pub review_licenses: Vec<String>,
+    /// Risk scores above this fail
+    #[serde(default)]
+    pub max_risk_score: Option<i32>,
+    /// ECC findings of this severity or higher fail: low, medium, high or
+    /// critical
+    #[serde(default)]
+    pub ecc_fail_severity: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PolicyResponse {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub allowed_licenses: Vec<String>,
+    pub denied_licenses: Vec<String>,
+    pub review_licenses: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_risk_score: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ecc_fail_severity: Option<String>,
+    /// Whether this is the organization's default policy
+    pub is_default: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<Policy> for PolicyResponse {
+    fn from(policy: Policy) -> Self {
+        Self {
+            allowed_licenses: policy.allowed(),
+            denied_licenses: policy.denied(),
+            review_licenses: policy.needs_review(),
+            name: policy.name,
+            description: policy.description,
+            max_risk_score: policy.max_risk_score,
+            ecc_fail_severity: policy.ecc_fail_severity,
+            is_default: policy.is_default,
+            created_at: policy.created_at,
+            updated_at: policy.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PoliciesResponse {
+    pub policies: Vec<PolicyResponse>,
+}
+
+/// Body of `PUT /api/v1/default-policy`, `PUT /api/v1/projects/:id/policy`
+/// and `PUT /api/v1/scans/:id/policy`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AssignPolicyRequest {
+    pub policy: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CustomFieldsResponse {
+    pub fields: Vec<CustomFieldResponse>,
+    /// Supported field types
+    pub types: Vec<String>,
+}
+
+/// Body of `PUT /api/v1/scans/:id/custom-fields`. Fields not listed keep
+/// their value; `null` removes one.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetScanCustomFieldsRequest {
+    #[schema(value_type = Object)]
+    pub values: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanCustomFieldsResponse {
+    pub scan_id: String,
+    #[schema(value_type = Object)]
+    pub values: BTreeMap<String, serde_json::Value>,
+}
+
+// Statistics models
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StatsResponse {
+    pub scans_by_status: Vec<ScanStatusCount>,
+    /// Average seconds from start to completion of completed scans
+    pub average_scan_duration_secs: Option<f64>,
+    /// Licenses found in the most completed full scans
+    pub top_licenses: Vec<LicenseUsage>,
+    /// Risk levels of each repository's latest completed full scan
+    pub repositories_by_risk_level: Vec<RepositoryRiskLevelCount>,
+    /// First day (YYYY-MM-DD) of `ecc_findings_by_day`
+    pub since: String,
+    /// ECC findings of scans completed on each day, days without any omitted
+    pub ecc_findings_by_day: Vec<EccFindingDay>,
+}
+
+// Credential models
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateCredentialRequest {
+    pub name: String,
+    /// github, gitlab, bitbucket, azure_devops or ssh_key
+    pub provider: String,
+    /// HTTPS user name or SSH user. Defaults to `oauth2` for GitLab, `pat`
+    /// for Azure DevOps and the user of the URL for SSH; GitHub tokens are
+    /// sent as the user name when unset.
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Personal access token, or private key for ssh_key. Encrypted at rest
+    /// and never returned.
+    pub secret: String,
+    /// Passphrase of an encrypted SSH private key
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
 // API Key models
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateApiKeyRequest {
     pub name: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct CreateApiKeyResponse {
     pub id: String,
     pub name: String,
@@ -0,0 +1,429 @@
+//! OpenAPI 3 description of the HTTP API, generated from the handler
+//! annotations and the request/response structs in [`crate::api::models`].
+//! Served as `/api/v1/openapi.json` with Swagger UI under `/api/v1/docs`.
+
+use super::handlers;
+use super::models::{
+    ApproveScanResponse, AssignPolicyRequest, CopyrightHolderDetailResponse, CreateApiKeyRequest, CreateApiKeyResponse,
+    CompositeScanResponse, CreateCompositeScanRequest, CreateCredentialRequest, CreateProjectRequest,
+    CreateResultCommentRequest, CreateScanRequest, CreateScanShareRequest,
+    CreateScanShareResponse, CreateWaiverRequest, CreateWaiverResponse, CustomFieldResponse,
+    CustomFieldsResponse, DisputedFile, GithubOrgImportResponse, ImportGithubOrgRequest,
+    ImportRepositoriesRequest, ImportedRepository, PushHookResponse, RepositoryImportResponse, DisputedLicense, ErasureRequest, PurgeRequest, ArchiveRequest, RecalculateRiskRequest, ErrorResponse, LegalHoldEventsResponse, AuditLogResponse, IntegrityEventsResponse, LegalHoldRequest, LegalHoldResponse,
+    ExportFossologyDecisionsRequest, FindingStateCounts, ImportFossologyDecisionsRequest,
+    LicenseCurationsResponse, LicenseFindingCounts, ProjectFindingsReport,
+    LanguageCoverage, LanguageFiles, LicenseDisputesResponse, LicenseFileCount, LicenseSummary, NotificationTemplatesResponse, PoliciesResponse, PolicyResponse,
+    ProjectLatestScansResponse, ProjectResponse, ProjectRiskTrendResponse, RepositoryLatestScan,
+    RerunScanRequest, ResultCommentsResponse, ResultSampleResponse, ReviewResultsRequest,
+    ReviewResultsResponse, RiskAssessment, RiskFactor, RiskScoreDelta, RiskTrendPoint,
+    SampleStratum, SampledFinding, ScanCoverageResponse, ScanCustomFieldsResponse, ScanDiffResponse, ScanHistoryResponse, ScanAuthorsResponse,
+    ScanLicenseSummaryResponse, ScanOwnersResponse, ScanRepositoryMetadataResponse, ScanResponse, ScanSwhidsResponse,
+    ScanResultsResponse, ScanReviewResponse, ScanTimelineResponse, ScreeningDecisionRequest,
+    SetScanCustomFieldsRequest, SharedLicenseCount, SharedRiskAssessment, SharedRiskFactor,
+    SharedScanReport, StatsResponse, TimelineEvent, UpdateProjectRequest, UploadScanRequest,
+    TrackedFindingsResponse, TransitionFindingRequest, UpsertCustomFieldRequest, UpsertPolicyRequest,
+    UpsertNotificationTemplateRequest, VerifyLicenseRequest,
+    VerifyLicenseResponse, WaiversResponse, WebhookDeliveriesResponse,
+};
+use crate::codeowners::{OwnedViolation, OwnerSummary};
+use crate::curation::{CurationExportReport, CurationImportReport};
+use crate::db::models::scan::{RepositoryRiskLevelCount, ScanStatusCount, ScanSummary};
+use crate::db::models::scan_result::{EccFindingDay, LicenseUsage};
+use crate::db::models::{
+    ApiKey, AuditLogEntry, CopyrightHolder, CopyrightHolderProject, Credential, IntegrityEvent, LegalHoldEvent, LicenseCuration, NotificationTemplate,
+    ScanHistoryFinding, ScanFileAuthor, ScanFileSwhid, ScanResultComment, ScanScreening, ScanShare, TrackedFinding, Waiver,
+    WebhookDelivery,
+};
+use crate::erasure::ErasureReport;
+use crate::policy::{
+    EffectiveEccThreshold, EffectiveLicenseRule, EffectivePolicy, EffectiveRiskLimit, Listing,
+    PolicyEvaluation, PolicyLevel, PolicySource, PolicyViolation,
+};
+use crate::retention::PurgeReport;
+use crate::archival::ArchiveReport;
+use crate::rescoring::{RescoreReport, RiskRecalculation, VersionedRiskScore};
+use crate::scan_archive::ArchiveImportReport;
+use crate::integrity::IntegrityReport;
+use crate::telemetry::TelemetryReport;
+use crate::export::anonymized::{AnonymizedAnalytics, LicenseBucket};
+use crate::export::component_license::{ComponentLicense, ConclusionBasis};
+use crate::export::{ResultsExportFormat, SbomFormat, SpdxVersion};
+use crate::gate::{GateOutcome, GateReport, GateViolation};
+use crate::remediation::{Remediation, RemediationAction};
+use crate::scan_diff::{FindingChange, LicenseChange, ScanDiff};
+use crate::scanner::declared::DeclaredLicense;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "LegalScanner API",
+        description = "License, copyright and export control scanning of Git repositories"
+    ),
+    paths(
+        handlers::health::health_check,
+        handlers::metrics::queue_metrics,
+        handlers::analytics::get_anonymized_analytics,
+        handlers::stats::get_stats,
+        handlers::scans::create_scan,
+        handlers::scans::list_scans,
+        handlers::scans::delete_all_scans,
+        handlers::scans::get_scan,
+        handlers::scans::delete_scan,
+        handlers::scans::get_scan_results,
+        handlers::scans::approve_scan,
+        handlers::scans::rerun_scan,
+        handlers::scans::recalculate_scan_risk,
+        handlers::upload::upload_scan,
+        handlers::licenses::get_scan_license_summary,
+        handlers::licenses::get_scan_coverage,
+        handlers::disputes::get_scan_license_disputes,
+        handlers::sample::get_scan_results_sample,
+        handlers::owners::get_scan_owners,
+        handlers::repository::get_scan_repository,
+        handlers::repository::get_scan_swhids,
+        handlers::custom_fields::get_scan_custom_fields,
+        handlers::custom_fields::set_scan_custom_fields,
+        handlers::reviews::get_scan_review,
+        handlers::reviews::mark_scan_reviewed,
+        handlers::reviews::review_scan_results,
+        handlers::reviews::list_result_comments,
+        handlers::reviews::add_result_comment,
+        handlers::export::export_scan_results,
+        handlers::export::export_scan_sarif,
+        handlers::export::export_scan_markdown,
+        handlers::export::export_scan_export_control_report,
+        handlers::export::export_scan_notice,
+        handlers::export::export_scan_viewer,
+        handlers::export::export_scan_archive,
+        handlers::timeline::get_scan_timeline,
+        handlers::events::stream_scan_events,
+        handlers::history::get_scan_history,
+        handlers::history::get_scan_authors,
+        handlers::diff::get_scan_diff,
+        handlers::gate::get_scan_gate,
+        handlers::remediation::list_remediation,
+        handlers::sbom::get_scan_sbom,
+        handlers::screening::get_scan_screening,
+        handlers::screening::submit_scan_screening,
+        handlers::screening::record_screening_decision,
+        handlers::shares::create_scan_share,
+        handlers::shares::list_scan_shares,
+        handlers::shares::revoke_scan_share,
+        handlers::shares::get_shared_report,
+        handlers::webhooks::list_webhook_deliveries,
+        handlers::projects::create_project,
+        handlers::projects::list_projects,
+        handlers::projects::get_project,
+        handlers::projects::update_project,
+        handlers::projects::delete_project,
+        handlers::projects::get_project_latest_scans,
+        handlers::projects::get_project_risk_trend,
+        handlers::projects::get_project_sbom,
+        handlers::findings::get_project_findings_report,
+        handlers::composite_scans::create_composite_scan,
+        handlers::composite_scans::list_composite_scans,
+        handlers::composite_scans::get_composite_scan,
+        handlers::composite_scans::get_composite_scan_sbom,
+        handlers::composite_scans::get_composite_scan_notice,
+        handlers::copyright_holders::search_copyright_holders,
+        handlers::copyright_holders::get_copyright_holder,
+        handlers::curations::import_fossology_decisions,
+        handlers::curations::export_fossology_decisions,
+        handlers::curations::list_license_curations,
+        handlers::curations::delete_license_curation,
+        handlers::waivers::create_waiver,
+        handlers::waivers::list_waivers,
+        handlers::waivers::delete_waiver,
+        handlers::findings::list_findings,
+        handlers::findings::get_finding,
+        handlers::findings::triage_finding,
+        handlers::findings::resolve_finding,
+        handlers::findings::reopen_finding,
+        handlers::notification_templates::list_notification_templates,
+        handlers::notification_templates::upsert_notification_template,
+        handlers::notification_templates::delete_notification_template,
+        handlers::custom_fields::list_custom_fields,
+        handlers::custom_fields::upsert_custom_field,
+        handlers::custom_fields::delete_custom_field,
+        handlers::policies::list_policies,
+        handlers::policies::get_policy,
+        handlers::policies::upsert_policy,
+        handlers::policies::delete_policy,
+        handlers::policies::get_default_policy,
+        handlers::policies::set_default_policy,
+        handlers::policies::remove_default_policy,
+        handlers::policies::assign_project_policy,
+        handlers::policies::remove_project_policy,
+        handlers::policies::assign_scan_policy,
+        handlers::policies::remove_scan_policy,
+        handlers::policies::get_effective_policy,
+        handlers::policies::get_policy_evaluation,
+        handlers::policies::evaluate_scan_policy,
+        handlers::admin::erase_data,
+        handlers::admin::place_scan_legal_hold,
+        handlers::admin::lift_scan_legal_hold,
+        handlers::admin::place_project_legal_hold,
+        handlers::admin::lift_project_legal_hold,
+        handlers::admin::list_legal_hold_events,
+        handlers::admin::verify_integrity,
+        handlers::admin::list_integrity_events,
+        handlers::admin::preview_telemetry,
+        handlers::admin::purge_data,
+        handlers::admin::archive_results,
+        handlers::admin::recalculate_risk,
+        handlers::admin::import_scan_archive,
+        handlers::admin::restore_scan,
+        handlers::admin::list_audit_log,
+        handlers::import::import_github_org,
+        handlers::import::import_repositories,
+        handlers::push_hooks::github_push,
+        handlers::push_hooks::gitlab_push,
+        handlers::verify::verify_license,
+        handlers::credentials::create_credential,
+        handlers::credentials::list_credentials,
+        handlers::credentials::get_credential,
+        handlers::credentials::delete_credential,
+        handlers::api_keys::create_api_key,
+        handlers::api_keys::list_api_keys,
+        handlers::api_keys::delete_api_key,
+    ),
+    components(schemas(
+        ErrorResponse,
+        CreateScanRequest,
+        ScanResponse,
+        RerunScanRequest,
+        UploadScanRequest,
+        ScanResultsResponse,
+        ScanTimelineResponse,
+        TimelineEvent,
+        ScanHistoryResponse,
+        ScanHistoryFinding,
+        ScanAuthorsResponse,
+        ScanFileAuthor,
+        ScanLicenseSummaryResponse,
+        LicenseSummary,
+        ComponentLicense,
+        ConclusionBasis,
+        ScanCoverageResponse,
+        LanguageCoverage,
+        LicenseFileCount,
+        LicenseDisputesResponse,
+        ResultSampleResponse,
+        SampleStratum,
+        SampledFinding,
+        DisputedFile,
+        DisputedLicense,
+        ScanOwnersResponse,
+        ScanRepositoryMetadataResponse,
+        LanguageFiles,
+        ScanSwhidsResponse,
+        ScanFileSwhid,
+        ScanReviewResponse,
+        ReviewResultsRequest,
+        ReviewResultsResponse,
+        CreateResultCommentRequest,
+        ResultCommentsResponse,
+        ScanResultComment,
+        OwnerSummary,
+        OwnedViolation,
+        ImportFossologyDecisionsRequest,
+        CurationImportReport,
+        ExportFossologyDecisionsRequest,
+        CurationExportReport,
+        LicenseCurationsResponse,
+        LicenseCuration,
+        CreateWaiverRequest,
+        CreateWaiverResponse,
+        WaiversResponse,
+        Waiver,
+        TrackedFindingsResponse,
+        TrackedFinding,
+        TransitionFindingRequest,
+        ProjectFindingsReport,
+        FindingStateCounts,
+        LicenseFindingCounts,
+        NotificationTemplatesResponse,
+        NotificationTemplate,
+        UpsertNotificationTemplateRequest,
+        CustomFieldsResponse,
+        CustomFieldResponse,
+        UpsertCustomFieldRequest,
+        ScanCustomFieldsResponse,
+        SetScanCustomFieldsRequest,
+        PoliciesResponse,
+        PolicyResponse,
+        UpsertPolicyRequest,
+        AssignPolicyRequest,
+        PolicyEvaluation,
+        PolicyViolation,
+        EffectivePolicy,
+        EffectiveLicenseRule,
+        EffectiveRiskLimit,
+        EffectiveEccThreshold,
+        PolicySource,
+        PolicyLevel,
+        Listing,
+        ErasureRequest,
+        ErasureReport,
+        PurgeRequest,
+        PurgeReport,
+        ArchiveRequest,
+        ArchiveReport,
+        RecalculateRiskRequest,
+        RescoreReport,
+        RiskRecalculation,
+        VersionedRiskScore,
+        ArchiveImportReport,
+        LegalHoldRequest,
+        LegalHoldResponse,
+        LegalHoldEventsResponse,
+        AuditLogResponse,
+        AuditLogEntry,
+        LegalHoldEvent,
+        IntegrityReport,
+        TelemetryReport,
+        IntegrityEventsResponse,
+        IntegrityEvent,
+        CreateProjectRequest,
+        UpdateProjectRequest,
+        ProjectResponse,
+        ProjectLatestScansResponse,
+        RepositoryLatestScan,
+        ProjectRiskTrendResponse,
+        RiskTrendPoint,
+        CreateCompositeScanRequest,
+        CompositeScanResponse,
+        WebhookDeliveriesResponse,
+        WebhookDelivery,
+        ScanDiffResponse,
+        RiskScoreDelta,
+        ScanDiff,
+        LicenseChange,
+        FindingChange,
+        VerifyLicenseRequest,
+        VerifyLicenseResponse,
+        DeclaredLicense,
+        RiskAssessment,
+        RiskFactor,
+        Remediation,
+        RemediationAction,
+        CreateScanShareRequest,
+        CreateScanShareResponse,
+        ScanShare,
+        SharedScanReport,
+        SharedRiskAssessment,
+        SharedRiskFactor,
+        SharedLicenseCount,
+        ScanSummary,
+        ScreeningDecisionRequest,
+        ScanScreening,
+        ApproveScanResponse,
+        CopyrightHolder,
+        CopyrightHolderProject,
+        CopyrightHolderDetailResponse,
+        ImportGithubOrgRequest,
+        ImportedRepository,
+        GithubOrgImportResponse,
+        ImportRepositoriesRequest,
+        RepositoryImportResponse,
+        PushHookResponse,
+        CreateCredentialRequest,
+        Credential,
+        CreateApiKeyRequest,
+        CreateApiKeyResponse,
+        ApiKey,
+        GateReport,
+        GateViolation,
+        GateOutcome,
+        handlers::gate::GateFormat,
+        AnonymizedAnalytics,
+        LicenseBucket,
+        StatsResponse,
+        ScanStatusCount,
+        LicenseUsage,
+        RepositoryRiskLevelCount,
+        EccFindingDay,
+        SbomFormat,
+        SpdxVersion,
+        ResultsExportFormat,
+    )),
+    modifiers(&AdminTokenScheme)
+)]
+pub struct ApiDoc;
+
+/// Bearer token guarding the admin endpoints, see `ADMIN_API_TOKEN`
+struct AdminTokenScheme;
+
+impl Modify for AdminTokenScheme {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "admin_token",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    match (key.as_str(), value) {
+                        ("$ref", Value::String(r)) => refs.push(r.clone()),
+                        _ => collect_refs(value, refs),
+                    }
+                }
+            }
+            Value::Array(values) => values.iter().for_each(|v| collect_refs(v, refs)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_every_schema_reference_is_registered() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+
+        let mut refs = Vec::new();
+        collect_refs(&spec, &mut refs);
+        assert!(!refs.is_empty());
+        for r in refs {
+            let name = r.trim_start_matches("#/components/schemas/");
+            assert!(schemas.contains_key(name), "unregistered schema {}", r);
+        }
+    }
+
+    #[test]
+    fn test_spec_documents_routes_and_admin_security() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        let scan = &spec["paths"]["/api/v1/scans/{id}/results"]["get"];
+        assert_eq!(scan["parameters"][0]["name"], "id");
+        assert!(scan["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|p| p["name"] == "type" && p["in"] == "query"));
+        assert_eq!(
+            spec["paths"]["/api/v1/scans"]["post"]["requestBody"]["content"]["application/json"]
+                ["schema"]["$ref"],
+            "#/components/schemas/CreateScanRequest"
+        );
+        assert_eq!(
+            spec["paths"]["/api/v1/admin/erasure"]["post"]["security"][0]["admin_token"],
+            serde_json::json!([])
+        );
+        assert_eq!(
+            spec["components"]["securitySchemes"]["admin_token"]["scheme"],
+            "bearer"
+        );
+    }
+}
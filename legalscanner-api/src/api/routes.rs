@@ -1,31 +1,448 @@
 use crate::AppState;
 use axum::{
-    routing::{delete, get, post},
+    extract::DefaultBodyLimit,
+    routing::{delete, get, post, put},
     Router,
 };
+use tower_http::compression::{
+    predicate::{NotForContentType, Predicate, SizeAbove},
+    CompressionLayer, DefaultPredicate,
+};
 use tower_http::cors::CorsLayer;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 
 use super::handlers;
+use super::openapi::ApiDoc;
 
 pub fn create_router(state: AppState) -> Router {
+    // Negotiated via Accept-Encoding; mostly benefits results and SBOM payloads.
+    // Parquet and Excel exports are already compressed.
+    let algorithms = &state.config.response_compression;
+    let compression = CompressionLayer::new()
+        .gzip(algorithms.iter().any(|a| a == "gzip"))
+        .br(algorithms.iter().any(|a| a == "br"))
+        .compress_when(
+            DefaultPredicate::new()
+                .and(SizeAbove::new(state.config.compression_min_size))
+                .and(NotForContentType::const_new("application/vnd.apache.parquet"))
+                .and(NotForContentType::const_new(
+                    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+                )),
+        );
+
+    // Archive uploads are streamed to disk, the limit replaces axum's 2 MB
+    // default for those and scan archive imports only
+    let upload_limit =
+        DefaultBodyLimit::max(state.config.upload_max_size_mb as usize * 1024 * 1024);
+
     Router::new()
         // Health check
         .route("/health", get(handlers::health::health_check))
 
+        // Autoscaling metrics
+        .route("/api/v1/metrics/queue", get(handlers::metrics::queue_metrics))
+
+        // Cross-instance benchmarking (opt-in)
+        .route(
+            "/api/v1/analytics/anonymized",
+            get(handlers::analytics::get_anonymized_analytics),
+        )
+
+        // Organization-wide statistics for dashboards
+        .route("/api/v1/stats", get(handlers::stats::get_stats))
+
+        // Remediation guidance for risk factors
+        .route(
+            "/api/v1/remediation",
+            get(handlers::remediation::list_remediation),
+        )
+
         // Scans
         .route("/api/v1/scans", post(handlers::scans::create_scan))
         .route("/api/v1/scans", get(handlers::scans::list_scans))
         .route("/api/v1/scans", delete(handlers::scans::delete_all_scans))
+        .route(
+            "/api/v1/scans/upload",
+            post(handlers::upload::upload_scan).layer(upload_limit),
+        )
         .route("/api/v1/scans/:id", get(handlers::scans::get_scan))
         .route("/api/v1/scans/:id", delete(handlers::scans::delete_scan))
         .route(
             "/api/v1/scans/:id/results",
             get(handlers::scans::get_scan_results),
         )
+        .route(
+            "/api/v1/scans/:id/licenses",
+            get(handlers::licenses::get_scan_license_summary),
+        )
+        .route(
+            "/api/v1/scans/:id/coverage",
+            get(handlers::licenses::get_scan_coverage),
+        )
+        .route(
+            "/api/v1/scans/:id/results/sample",
+            get(handlers::sample::get_scan_results_sample),
+        )
+        .route(
+            "/api/v1/scans/:id/results/disputed",
+            get(handlers::disputes::get_scan_license_disputes),
+        )
+        .route(
+            "/api/v1/scans/:id/results/export",
+            get(handlers::export::export_scan_results),
+        )
+        .route(
+            "/api/v1/scans/:id/export",
+            get(handlers::export::export_scan_results),
+        )
+        .route(
+            "/api/v1/scans/:id/sarif",
+            get(handlers::export::export_scan_sarif),
+        )
+        .route(
+            "/api/v1/scans/:id/markdown",
+            get(handlers::export::export_scan_markdown),
+        )
+        .route(
+            "/api/v1/scans/:id/export-control/report",
+            get(handlers::export::export_scan_export_control_report),
+        )
+        .route(
+            "/api/v1/scans/:id/notice",
+            get(handlers::export::export_scan_notice),
+        )
+        .route(
+            "/api/v1/scans/:id/viewer",
+            get(handlers::export::export_scan_viewer),
+        )
+        .route(
+            "/api/v1/scans/:id/archive",
+            get(handlers::export::export_scan_archive),
+        )
+        .route(
+            "/api/v1/scans/:id/timeline",
+            get(handlers::timeline::get_scan_timeline),
+        )
+        .route(
+            "/api/v1/scans/:id/events",
+            get(handlers::events::stream_scan_events),
+        )
+        .route(
+            "/api/v1/scans/:id/history",
+            get(handlers::history::get_scan_history),
+        )
+        .route(
+            "/api/v1/scans/:id/authors",
+            get(handlers::history::get_scan_authors),
+        )
+        .route(
+            "/api/v1/scans/:id/diff/:other_id",
+            get(handlers::diff::get_scan_diff),
+        )
+        .route(
+            "/api/v1/scans/:id/gate",
+            get(handlers::gate::get_scan_gate),
+        )
         .route(
             "/api/v1/scans/:id/sbom",
             get(handlers::sbom::get_scan_sbom),
         )
+        .route(
+            "/api/v1/scans/:id/approve",
+            post(handlers::scans::approve_scan),
+        )
+        .route(
+            "/api/v1/scans/:id/rerun",
+            post(handlers::scans::rerun_scan),
+        )
+        .route(
+            "/api/v1/scans/:id/risk/recalculate",
+            post(handlers::scans::recalculate_scan_risk),
+        )
+        .route(
+            "/api/v1/scans/:id/screening",
+            get(handlers::screening::get_scan_screening)
+                .post(handlers::screening::submit_scan_screening)
+                .put(handlers::screening::record_screening_decision),
+        )
+        .route(
+            "/api/v1/scans/:id/owners",
+            get(handlers::owners::get_scan_owners),
+        )
+        .route(
+            "/api/v1/scans/:id/repository",
+            get(handlers::repository::get_scan_repository),
+        )
+        .route(
+            "/api/v1/scans/:id/swhids",
+            get(handlers::repository::get_scan_swhids),
+        )
+        .route(
+            "/api/v1/scans/:id/custom-fields",
+            get(handlers::custom_fields::get_scan_custom_fields)
+                .put(handlers::custom_fields::set_scan_custom_fields),
+        )
+        .route(
+            "/api/v1/scans/:id/policy",
+            put(handlers::policies::assign_scan_policy)
+                .delete(handlers::policies::remove_scan_policy),
+        )
+        .route(
+            "/api/v1/scans/:id/effective-policy",
+            get(handlers::policies::get_effective_policy),
+        )
+        .route(
+            "/api/v1/scans/:id/policy-evaluation",
+            get(handlers::policies::get_policy_evaluation)
+                .post(handlers::policies::evaluate_scan_policy),
+        )
+        .route(
+            "/api/v1/scans/:id/review",
+            get(handlers::reviews::get_scan_review).post(handlers::reviews::mark_scan_reviewed),
+        )
+        .route(
+            "/api/v1/scans/:id/results/review",
+            post(handlers::reviews::review_scan_results),
+        )
+        .route(
+            "/api/v1/scans/:id/results/:result_id/comments",
+            get(handlers::reviews::list_result_comments)
+                .post(handlers::reviews::add_result_comment),
+        )
+        .route(
+            "/api/v1/scans/:id/shares",
+            post(handlers::shares::create_scan_share).get(handlers::shares::list_scan_shares),
+        )
+        .route(
+            "/api/v1/scans/:id/shares/:share_id",
+            delete(handlers::shares::revoke_scan_share),
+        )
+        .route(
+            "/api/v1/scans/:id/webhook-deliveries",
+            get(handlers::webhooks::list_webhook_deliveries),
+        )
+
+        // Projects grouping the scans of one or more repositories
+        .route(
+            "/api/v1/projects",
+            post(handlers::projects::create_project).get(handlers::projects::list_projects),
+        )
+        .route(
+            "/api/v1/projects/:id",
+            get(handlers::projects::get_project)
+                .put(handlers::projects::update_project)
+                .delete(handlers::projects::delete_project),
+        )
+        .route(
+            "/api/v1/projects/:id/scans/latest",
+            get(handlers::projects::get_project_latest_scans),
+        )
+        .route(
+            "/api/v1/projects/:id/risk-trend",
+            get(handlers::projects::get_project_risk_trend),
+        )
+        .route(
+            "/api/v1/projects/:id/sbom",
+            get(handlers::projects::get_project_sbom),
+        )
+        .route(
+            "/api/v1/projects/:id/findings/report",
+            get(handlers::findings::get_project_findings_report),
+        )
+        .route(
+            "/api/v1/projects/:id/policy",
+            put(handlers::policies::assign_project_policy)
+                .delete(handlers::policies::remove_project_policy),
+        )
+        .route(
+            "/api/v1/projects/:id/composite-scans",
+            post(handlers::composite_scans::create_composite_scan)
+                .get(handlers::composite_scans::list_composite_scans),
+        )
+        .route(
+            "/api/v1/composite-scans/:id",
+            get(handlers::composite_scans::get_composite_scan),
+        )
+        .route(
+            "/api/v1/composite-scans/:id/sbom",
+            get(handlers::composite_scans::get_composite_scan_sbom),
+        )
+        .route(
+            "/api/v1/composite-scans/:id/notice",
+            get(handlers::composite_scans::get_composite_scan_notice),
+        )
+
+        // Public read-only share links (no authentication)
+        .route(
+            "/api/v1/shared/:token",
+            get(handlers::shares::get_shared_report),
+        )
+
+        // Cross-scan copyright holder registry
+        .route(
+            "/api/v1/copyright-holders",
+            get(handlers::copyright_holders::search_copyright_holders),
+        )
+        .route(
+            "/api/v1/copyright-holders/:id",
+            get(handlers::copyright_holders::get_copyright_holder),
+        )
+
+        // License curations, e.g. Fossology clearing decisions
+        .route(
+            "/api/v1/curations",
+            get(handlers::curations::list_license_curations),
+        )
+        .route(
+            "/api/v1/curations/import/fossology",
+            post(handlers::curations::import_fossology_decisions),
+        )
+        .route(
+            "/api/v1/curations/export/fossology",
+            post(handlers::curations::export_fossology_decisions),
+        )
+        .route(
+            "/api/v1/curations/:id",
+            delete(handlers::curations::delete_license_curation),
+        )
+
+        // Waivers of accepted findings
+        .route("/api/v1/waivers", post(handlers::waivers::create_waiver))
+        .route("/api/v1/waivers", get(handlers::waivers::list_waivers))
+        .route(
+            "/api/v1/waivers/:id",
+            delete(handlers::waivers::delete_waiver),
+        )
+
+        // Lifecycle of findings across the scans of a repository
+        .route("/api/v1/findings", get(handlers::findings::list_findings))
+        .route("/api/v1/findings/:id", get(handlers::findings::get_finding))
+        .route(
+            "/api/v1/findings/:id/triage",
+            post(handlers::findings::triage_finding),
+        )
+        .route(
+            "/api/v1/findings/:id/resolve",
+            post(handlers::findings::resolve_finding),
+        )
+        .route(
+            "/api/v1/findings/:id/reopen",
+            post(handlers::findings::reopen_finding),
+        )
+
+        // Per-event callback payload templates
+        .route(
+            "/api/v1/notification-templates",
+            get(handlers::notification_templates::list_notification_templates),
+        )
+        .route(
+            "/api/v1/notification-templates/:event",
+            put(handlers::notification_templates::upsert_notification_template)
+                .delete(handlers::notification_templates::delete_notification_template),
+        )
+
+        // Custom scan fields
+        .route(
+            "/api/v1/custom-fields",
+            get(handlers::custom_fields::list_custom_fields),
+        )
+        .route(
+            "/api/v1/custom-fields/:key",
+            put(handlers::custom_fields::upsert_custom_field)
+                .delete(handlers::custom_fields::delete_custom_field),
+        )
+
+        // License policies
+        .route("/api/v1/policies", get(handlers::policies::list_policies))
+        .route(
+            "/api/v1/policies/:name",
+            get(handlers::policies::get_policy)
+                .put(handlers::policies::upsert_policy)
+                .delete(handlers::policies::delete_policy),
+        )
+        .route(
+            "/api/v1/default-policy",
+            get(handlers::policies::get_default_policy)
+                .put(handlers::policies::set_default_policy)
+                .delete(handlers::policies::remove_default_policy),
+        )
+
+        // Data erasure for data subject requests (admin token)
+        .route("/api/v1/admin/erasure", post(handlers::admin::erase_data))
+        // Legal holds blocking deletion (admin token)
+        .route(
+            "/api/v1/admin/scans/:id/legal-hold",
+            put(handlers::admin::place_scan_legal_hold)
+                .delete(handlers::admin::lift_scan_legal_hold),
+        )
+        .route(
+            "/api/v1/admin/projects/:id/legal-hold",
+            put(handlers::admin::place_project_legal_hold)
+                .delete(handlers::admin::lift_project_legal_hold),
+        )
+        .route(
+            "/api/v1/admin/legal-holds/events",
+            get(handlers::admin::list_legal_hold_events),
+        )
+        .route(
+            "/api/v1/admin/integrity/verify",
+            post(handlers::admin::verify_integrity),
+        )
+        .route(
+            "/api/v1/admin/integrity/events",
+            get(handlers::admin::list_integrity_events),
+        )
+        .route("/api/v1/admin/purge", post(handlers::admin::purge_data))
+        .route("/api/v1/admin/archive", post(handlers::admin::archive_results))
+        .route(
+            "/api/v1/admin/risk/recalculate",
+            post(handlers::admin::recalculate_risk),
+        )
+        .route(
+            "/api/v1/admin/scans/import",
+            post(handlers::admin::import_scan_archive).layer(upload_limit),
+        )
+        // Soft-deleted scans and the audit trail (admin token)
+        .route(
+            "/api/v1/admin/scans/:id/restore",
+            post(handlers::admin::restore_scan),
+        )
+        .route("/api/v1/admin/audit-log", get(handlers::admin::list_audit_log))
+        .route(
+            "/api/v1/admin/telemetry",
+            get(handlers::admin::preview_telemetry),
+        )
+
+        // Bulk onboarding of a GitHub organization, GitLab group, Bitbucket
+        // workspace or Azure DevOps project
+        .route(
+            "/api/v1/import/github-org",
+            post(handlers::import::import_github_org),
+        )
+        .route(
+            "/api/v1/import/repositories",
+            post(handlers::import::import_repositories),
+        )
+
+        // Rescans on push, from GitHub and GitLab webhooks
+        .route("/api/v1/hooks/github", post(handlers::push_hooks::github_push))
+        .route("/api/v1/hooks/gitlab", post(handlers::push_hooks::gitlab_push))
+
+        // License verification
+        .route("/api/v1/verify", post(handlers::verify::verify_license))
+
+        // Named git credentials referenced by scans
+        .route(
+            "/api/v1/credentials",
+            post(handlers::credentials::create_credential)
+                .get(handlers::credentials::list_credentials),
+        )
+        .route(
+            "/api/v1/credentials/:id",
+            get(handlers::credentials::get_credential)
+                .delete(handlers::credentials::delete_credential),
+        )
 
         // API Keys
         .route("/api/v1/api-keys", post(handlers::api_keys::create_api_key))
@@ -35,6 +452,12 @@ pub fn create_router(state: AppState) -> Router {
             delete(handlers::api_keys::delete_api_key),
         )
 
+        // OpenAPI specification and Swagger UI
+        .merge(SwaggerUi::new("/api/v1/docs").url("/api/v1/openapi.json", ApiDoc::openapi()))
+
+        // Response compression
+        .layer(compression)
+
         // CORS
         .layer(CorsLayer::permissive())
 
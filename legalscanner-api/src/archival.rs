@@ -0,0 +1,110 @@
+//! Archival of old findings. Once a scan has been finished for
+//! `RESULT_ARCHIVE_AFTER_DAYS`, its findings are written to the archive
+//! configured with `RESULT_ARCHIVE`, one gzip-compressed JSON file per scan
+//! on disk or in an S3 bucket, and removed from the database. The scan keeps
+//! its status, risk assessment and a summary of the findings, and the
+//! findings are still served by the same endpoints: reading them downloads
+//! the archive. Review comments stay in the database; archived findings can
+//! no longer be reviewed.
+//!
+//! Archival runs every `RESULT_ARCHIVE_INTERVAL_HOURS` and on request from
+//! `POST /api/v1/admin/archive`. Statistics across scans (license usage,
+//! trends, composite scans) only count the findings in the database.
+
+use crate::db::models::{result_partition, Scan};
+use crate::error::AppError;
+use crate::storage::archive;
+use crate::AppState;
+use chrono::Utc;
+use serde::Serialize;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+
+/// What an archival run moved, or would move on a dry run
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ArchiveReport {
+    pub dry_run: bool,
+    /// Scans whose findings were archived
+    pub scans_archived: Vec<String>,
+    pub results_archived: u64,
+    /// Compressed size of the archives written, in bytes
+    pub bytes_written: u64,
+    /// Monthly result partitions dropped because nothing was left in them
+    pub partitions_dropped: Vec<String>,
+    pub archived_at: String,
+}
+
+/// Archive the findings of the scans finished more than `older_than_days`
+/// ago. With `dry_run`, only report what would be archived.
+pub async fn archive(
+    state: &AppState,
+    older_than_days: u64,
+    dry_run: bool,
+) -> Result<ArchiveReport, AppError> {
+    let storage = state.result_archive.as_ref().ok_or_else(|| {
+        AppError::Validation("No result archive is configured (RESULT_ARCHIVE)".to_string())
+    })?;
+    let mut report = ArchiveReport {
+        dry_run,
+        ..Default::default()
+    };
+
+    let cutoff = (Utc::now() - chrono::Duration::days(older_than_days as i64))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    for scan in Scan::find_archivable(&state.db, &cutoff).await? {
+        let results = state.result_store.load_results(&scan.id).await?;
+        if results.is_empty() {
+            continue;
+        }
+        report.results_archived += results.len() as u64;
+        if !dry_run {
+            // The summary is taken while the findings are still in the
+            // result store
+            let summary = state.result_store.summarize(&scan.id).await?;
+            let key = archive::archive_key(&scan.id);
+            let data = archive::encode(&scan.id, results)?;
+            report.bytes_written += data.len() as u64;
+            storage.put(&key, data).await?;
+            Scan::mark_results_archived(&state.db, &scan.id, &key, &summary).await?;
+            state.result_store.discard_archived(&scan.id).await?;
+        }
+        report.scans_archived.push(scan.id);
+    }
+
+    if !dry_run && !report.scans_archived.is_empty() {
+        report.partitions_dropped = result_partition::drop_empty(&state.db).await?;
+    }
+
+    report.archived_at = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    tracing::info!(
+        "Result archival{}: findings of {} scans archived, {} results, {} bytes",
+        if dry_run { " (dry run)" } else { "" },
+        report.scans_archived.len(),
+        report.results_archived,
+        report.bytes_written
+    );
+    Ok(report)
+}
+
+/// Run [`archive`] with `RESULT_ARCHIVE_AFTER_DAYS` every
+/// `RESULT_ARCHIVE_INTERVAL_HOURS`, the first time one interval after
+/// startup. `None` without an archive or when periodic archival is disabled.
+pub fn spawn_periodic(state: AppState) -> Option<JoinHandle<()>> {
+    let days = state.config.result_archive_after_days;
+    let hours = state.config.result_archive_interval_hours;
+    if state.result_archive.is_none() || days == 0 || hours == 0 {
+        return None;
+    }
+    Some(tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(hours * 3600));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = archive(&state, days, false).await {
+                tracing::warn!("Result archival failed: {}", e);
+            }
+        }
+    }))
+}
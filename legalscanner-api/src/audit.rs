@@ -0,0 +1,84 @@
+//! Audit trail of who created, deleted, restored and re-ran scans and who
+//! created and deleted API keys. Requests are attributed to the API key in
+//! their `X-API-Key` header, or to the admin if they carry the admin token.
+//! Entries are kept after their target is deleted, and listed by
+//! `GET /admin/audit-log`.
+
+use crate::db::models::{ApiKey, AuditLogEntry};
+use crate::error::AppError;
+use crate::utils::crypto;
+use crate::AppState;
+use axum::http::HeaderMap;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Who made a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Actor {
+    ApiKey(String),
+    Admin,
+    /// No known API key and no admin token
+    Anonymous,
+}
+
+impl Actor {
+    /// The active API key in `X-API-Key`, else the admin if the request
+    /// carries the admin token
+    pub async fn from_headers(state: &AppState, headers: &HeaderMap) -> Result<Actor, AppError> {
+        let key = headers
+            .get(API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|k| !k.is_empty());
+        if let Some(key) = key {
+            let hash = crypto::hash_api_key(key, &state.config.api_key_salt)
+                .map_err(|e| AppError::Internal(format!("Failed to hash API key: {}", e)))?;
+            if let Some(api_key) = ApiKey::find_by_hash(&state.db, &hash).await? {
+                return Ok(Actor::ApiKey(api_key.id));
+            }
+        }
+
+        if crate::api::handlers::admin::authorize_admin(state, headers).is_ok() {
+            return Ok(Actor::Admin);
+        }
+        Ok(Actor::Anonymous)
+    }
+
+    /// ID of the API key, to store as `created_by_key_id`
+    pub fn api_key_id(&self) -> Option<&str> {
+        match self {
+            Actor::ApiKey(id) => Some(id),
+            Actor::Admin | Actor::Anonymous => None,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Actor::ApiKey(_) => "api_key",
+            Actor::Admin => "admin",
+            Actor::Anonymous => "anonymous",
+        }
+    }
+}
+
+/// Record that `actor` did `action` on a scan or API key
+pub async fn record(
+    state: &AppState,
+    actor: &Actor,
+    action: &str,
+    target_type: &str,
+    target_id: Option<&str>,
+    detail: Option<&str>,
+) -> Result<AuditLogEntry, AppError> {
+    let entry = AuditLogEntry::record(
+        &state.db,
+        action,
+        target_type,
+        target_id,
+        actor.kind(),
+        actor.api_key_id(),
+        detail,
+    )
+    .await?;
+    Ok(entry)
+}
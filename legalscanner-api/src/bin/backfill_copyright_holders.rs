@@ -0,0 +1,63 @@
+/// Utility to add scans completed before the copyright holder registry
+/// existed. Safe to re-run: holders and scan links are upserted.
+///
+/// Usage: cargo run --bin backfill_copyright_holders
+use legalscanner_api::config::Config;
+use legalscanner_api::copyright_holders;
+use legalscanner_api::db;
+use legalscanner_api::db::models::scan::{ScanListFilter, ScanSort};
+use legalscanner_api::db::models::Scan;
+use legalscanner_api::storage;
+use tracing::{error, info};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize tracing
+    tracing_subscriber::fmt::init();
+
+    info!("Starting copyright holder registry backfill");
+
+    // Load config
+    let config = Config::from_env()?;
+
+    // Connect to database
+    let pool = db::create_pool(&config.database_url, &db::PoolSettings::from_config(&config)).await?;
+
+    // Run migrations to ensure schema is up to date
+    info!("Running migrations");
+    sqlx::migrate!("./migrations").run(&pool).await?;
+
+    let archive = storage::archive_from_config(&config)?;
+    let store = storage::result_store_from_config(&config, &pool, archive)?;
+
+    // Oldest first, so first-seen dates come out right
+    let filter = ScanListFilter {
+        status: Some("completed".to_string()),
+        ..Default::default()
+    };
+    let scans = Scan::list(&pool, &filter, ScanSort::CompletedAtAsc, i64::MAX, 0).await?;
+
+    info!("Found {} completed scans", scans.len());
+
+    let mut success_count = 0;
+    let mut error_count = 0;
+
+    for scan in scans {
+        match copyright_holders::update_registry(&pool, store.as_ref(), &scan).await {
+            Ok(count) => {
+                info!("  Scan {}: {} holders", scan.id, count);
+                success_count += 1;
+            }
+            Err(e) => {
+                error!("  Scan {}: failed to update registry: {}", scan.id, e);
+                error_count += 1;
+            }
+        }
+    }
+
+    info!("Backfill complete:");
+    info!("  Success: {}", success_count);
+    info!("  Errors: {}", error_count);
+
+    Ok(())
+}
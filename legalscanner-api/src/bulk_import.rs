@@ -0,0 +1,576 @@
+//! Onboarding of a whole GitHub organization, GitLab group, Bitbucket
+//! workspace or Azure DevOps project: its repositories are listed through
+//! the provider's API with the caller's token and filtered by topic,
+//! visibility, fork and archive status before each one is scanned.
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::git::{normalize_git_url, GitAuth, Provider};
+use reqwest::header::{ACCEPT, LINK};
+use reqwest::{Client, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest page size of the GitHub, GitLab and Bitbucket APIs
+const PER_PAGE: usize = 100;
+
+/// Listing stops after this many pages (10,000 repositories)
+const MAX_PAGES: usize = 100;
+
+pub const VISIBILITIES: [&str; 4] = ["all", "public", "private", "internal"];
+
+/// A repository as listed by its provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListedRepository {
+    /// Name, with the group path for GitLab subgroups
+    pub name: String,
+    pub clone_url: String,
+    pub default_branch: Option<String>,
+    /// `public`, `private` or `internal`
+    pub visibility: String,
+    pub topics: Vec<String>,
+    pub archived: bool,
+    pub fork: bool,
+}
+
+/// Which repositories of an organization are imported
+#[derive(Debug, Clone, Default)]
+pub struct RepositoryFilter {
+    /// Any of these topics, every repository when empty
+    pub topics: Vec<String>,
+    /// One of [`VISIBILITIES`] other than `all`
+    pub visibility: Option<String>,
+    pub include_archived: bool,
+    pub include_forks: bool,
+}
+
+impl RepositoryFilter {
+    pub fn matches(&self, repository: &ListedRepository) -> bool {
+        (self.include_archived || !repository.archived)
+            && (self.include_forks || !repository.fork)
+            && self
+                .visibility
+                .as_deref()
+                .is_none_or(|v| repository.visibility == v)
+            && (self.topics.is_empty()
+                || repository.topics.iter().any(|t| {
+                    self.topics
+                        .iter()
+                        .any(|wanted| wanted.eq_ignore_ascii_case(t))
+                }))
+    }
+}
+
+/// Whether `namespace` can name what is imported from `provider`: a GitHub
+/// organization login, a GitLab group path (`acme/platform`), a Bitbucket
+/// workspace or an Azure DevOps `organization/project`
+pub fn validate_namespace(provider: Provider, namespace: &str) -> Result<(), String> {
+    let slug = |s: &str, extra: &[char]| {
+        !s.is_empty()
+            && !s.starts_with('-')
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || extra.contains(&c))
+    };
+    let (valid, what) = match provider {
+        Provider::GitHub => (
+            namespace.len() <= 39 && slug(namespace, &[]),
+            "GitHub organization",
+        ),
+        Provider::GitLab => (
+            namespace
+                .split('/')
+                .all(|group| slug(group, &['_', '.']) && !group.starts_with('.')),
+            "GitLab group",
+        ),
+        Provider::Bitbucket => (
+            namespace.len() <= 62 && slug(namespace, &['_']),
+            "Bitbucket workspace",
+        ),
+        Provider::AzureDevOps => (
+            namespace.split_once('/').is_some_and(|(org, project)| {
+                slug(org, &[])
+                    && !project.trim().is_empty()
+                    && !project.contains('/')
+                    && !project.chars().any(char::is_control)
+            }),
+            "Azure DevOps organization/project",
+        ),
+        Provider::Other => (false, "namespace"),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid {} '{}'", what, namespace))
+    }
+}
+
+/// Every repository of `namespace` the token can see
+pub async fn list_repositories(
+    config: &Config,
+    provider: Provider,
+    namespace: &str,
+    auth: &GitAuth,
+) -> Result<Vec<ListedRepository>, AppError> {
+    let client = Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("legalscanner/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let listing = Listing {
+        client,
+        provider,
+        namespace,
+        auth,
+    };
+
+    let repositories = match provider {
+        Provider::GitHub => listing.github(&config.github_api_url).await?,
+        Provider::GitLab => listing.gitlab(&config.gitlab_api_url).await?,
+        Provider::Bitbucket => listing.bitbucket(&config.bitbucket_api_url).await?,
+        Provider::AzureDevOps => listing.azure_devops(&config.azure_devops_url).await?,
+        Provider::Other => Vec::new(),
+    };
+    Ok(repositories
+        .into_iter()
+        .map(|repository| ListedRepository {
+            clone_url: normalize_git_url(&repository.clone_url),
+            ..repository
+        })
+        .collect())
+}
+
+/// A repository of `GET /orgs/:org/repos`
+#[derive(Debug, Deserialize)]
+struct GithubRepository {
+    name: String,
+    clone_url: String,
+    #[serde(default)]
+    default_branch: Option<String>,
+    /// Older GitHub Enterprise versions only send `private`
+    #[serde(default)]
+    visibility: Option<String>,
+    #[serde(default)]
+    private: bool,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    fork: bool,
+}
+
+/// A project of `GET /groups/:id/projects`
+#[derive(Debug, Deserialize)]
+struct GitlabProject {
+    path_with_namespace: String,
+    http_url_to_repo: String,
+    #[serde(default)]
+    default_branch: Option<String>,
+    #[serde(default)]
+    visibility: Option<String>,
+    /// `tag_list` before GitLab 14.0
+    #[serde(default, alias = "tag_list")]
+    topics: Vec<String>,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    forked_from_project: Option<serde_json::Value>,
+}
+
+/// A page of `GET /repositories/:workspace`
+#[derive(Debug, Deserialize)]
+struct BitbucketPage {
+    values: Vec<BitbucketRepository>,
+    #[serde(default)]
+    next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketRepository {
+    full_name: String,
+    #[serde(default)]
+    is_private: bool,
+    #[serde(default)]
+    mainbranch: Option<BitbucketBranch>,
+    #[serde(default)]
+    parent: Option<serde_json::Value>,
+    links: BitbucketLinks,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketBranch {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketLinks {
+    #[serde(default)]
+    clone: Vec<BitbucketLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketLink {
+    name: String,
+    href: String,
+}
+
+/// `GET /:org/:project/_apis/git/repositories`
+#[derive(Debug, Deserialize)]
+struct AzureRepositories {
+    value: Vec<AzureRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureRepository {
+    name: String,
+    remote_url: String,
+    /// `refs/heads/main`, absent for an empty repository
+    #[serde(default)]
+    default_branch: Option<String>,
+    #[serde(default)]
+    is_disabled: bool,
+    #[serde(default)]
+    is_fork: bool,
+    #[serde(default)]
+    project: Option<AzureProject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AzureProject {
+    #[serde(default)]
+    visibility: Option<String>,
+}
+
+/// The paginated API requests of one listing
+struct Listing<'a> {
+    client: Client,
+    provider: Provider,
+    namespace: &'a str,
+    auth: &'a GitAuth,
+}
+
+impl Listing<'_> {
+    async fn github(&self, api_url: &str) -> Result<Vec<ListedRepository>, AppError> {
+        let first = format!(
+            "{}/orgs/{}/repos?type=all&per_page={}",
+            api_url.trim_end_matches('/'),
+            self.namespace,
+            PER_PAGE
+        );
+        let pages: Vec<Vec<GithubRepository>> = self.link_pages(first).await?;
+        Ok(pages
+            .into_iter()
+            .flatten()
+            .map(|r| ListedRepository {
+                visibility: match r.visibility {
+                    Some(visibility) => visibility,
+                    None if r.private => "private".to_string(),
+                    None => "public".to_string(),
+                },
+                name: r.name,
+                clone_url: r.clone_url,
+                default_branch: r.default_branch,
+                topics: r.topics,
+                archived: r.archived,
+                fork: r.fork,
+            })
+            .collect())
+    }
+
+    async fn gitlab(&self, api_url: &str) -> Result<Vec<ListedRepository>, AppError> {
+        let mut first = self.url(api_url, &["groups", self.namespace, "projects"])?;
+        first
+            .query_pairs_mut()
+            .append_pair("include_subgroups", "true")
+            .append_pair("with_shared", "false")
+            .append_pair("per_page", &PER_PAGE.to_string());
+        let pages: Vec<Vec<GitlabProject>> = self.link_pages(first.to_string()).await?;
+        Ok(pages
+            .into_iter()
+            .flatten()
+            .map(|p| ListedRepository {
+                name: p.path_with_namespace,
+                clone_url: p.http_url_to_repo,
+                default_branch: p.default_branch,
+                visibility: p.visibility.unwrap_or_else(|| "private".to_string()),
+                topics: p.topics,
+                archived: p.archived,
+                fork: p.forked_from_project.is_some(),
+            })
+            .collect())
+    }
+
+    async fn bitbucket(&self, api_url: &str) -> Result<Vec<ListedRepository>, AppError> {
+        let mut first = self.url(api_url, &["repositories", self.namespace])?;
+        first
+            .query_pairs_mut()
+            .append_pair("pagelen", &PER_PAGE.to_string());
+
+        let mut repositories = Vec::new();
+        let mut url = Some(first.to_string());
+        for _ in 0..MAX_PAGES {
+            let Some(page_url) = url.take() else {
+                break;
+            };
+            let page: BitbucketPage = self.json(self.get(&page_url).await?).await?;
+            url = page.next;
+            repositories.extend(page.values.into_iter().filter_map(|r| {
+                let clone_url = r
+                    .links
+                    .clone
+                    .into_iter()
+                    .find(|link| link.name == "https")?
+                    .href;
+                Some(ListedRepository {
+                    name: r.full_name,
+                    clone_url,
+                    default_branch: r.mainbranch.map(|b| b.name),
+                    visibility: if r.is_private { "private" } else { "public" }.to_string(),
+                    topics: Vec::new(),
+                    archived: false,
+                    fork: r.parent.is_some(),
+                })
+            }));
+        }
+        self.warn_truncated(url.is_some());
+        Ok(repositories)
+    }
+
+    async fn azure_devops(&self, api_url: &str) -> Result<Vec<ListedRepository>, AppError> {
+        let (org, project) = self
+            .namespace
+            .split_once('/')
+            .unwrap_or((self.namespace, ""));
+        let mut url = self.url(api_url, &[org, project, "_apis", "git", "repositories"])?;
+        url.query_pairs_mut().append_pair("api-version", "7.0");
+
+        let listing: AzureRepositories = self.json(self.get(url.as_str()).await?).await?;
+        Ok(listing
+            .value
+            .into_iter()
+            .map(|r| ListedRepository {
+                name: r.name,
+                clone_url: r.remote_url,
+                default_branch: r
+                    .default_branch
+                    .map(|b| b.trim_start_matches("refs/heads/").to_string()),
+                visibility: r
+                    .project
+                    .and_then(|p| p.visibility)
+                    .map(|v| v.to_lowercase())
+                    .unwrap_or_else(|| "private".to_string()),
+                topics: Vec::new(),
+                archived: r.is_disabled,
+                fork: r.is_fork,
+            })
+            .collect())
+    }
+
+    /// `base` with `segments` appended, each percent-encoded (a GitLab group
+    /// path is a single segment)
+    fn url(&self, base: &str, segments: &[&str]) -> Result<Url, AppError> {
+        let mut url = Url::parse(base.trim_end_matches('/')).map_err(|e| {
+            AppError::Internal(format!("Invalid {} API URL: {}", self.provider.label(), e))
+        })?;
+        url.path_segments_mut()
+            .map_err(|_| AppError::Internal(format!("Invalid {} API URL", self.provider.label())))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    /// The pages of a listing, following the `Link` header's `rel="next"`
+    async fn link_pages<T: DeserializeOwned>(&self, first: String) -> Result<Vec<T>, AppError> {
+        let mut pages = Vec::new();
+        let mut url = Some(first);
+        for _ in 0..MAX_PAGES {
+            let Some(page_url) = url.take() else {
+                break;
+            };
+            let response = self.get(&page_url).await?;
+            url = response
+                .headers()
+                .get(LINK)
+                .and_then(|v| v.to_str().ok())
+                .and_then(next_page);
+            pages.push(self.json(response).await?);
+        }
+        self.warn_truncated(url.is_some());
+        Ok(pages)
+    }
+
+    async fn get(&self, url: &str) -> Result<Response, AppError> {
+        let response = self
+            .authorize(self.client.get(url))
+            .send()
+            .await
+            .map_err(|e| self.unreachable(e))?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Err(AppError::NotFound(format!(
+                "{} namespace {} not found",
+                self.provider.label(),
+                self.namespace
+            ))),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Err(AppError::Unprocessable(format!(
+                    "{} rejected the token for {} ({})",
+                    self.provider.label(),
+                    self.namespace,
+                    response.status()
+                )))
+            }
+            _ => response.error_for_status().map_err(|e| self.unreachable(e)),
+        }
+    }
+
+    async fn json<T: DeserializeOwned>(&self, response: Response) -> Result<T, AppError> {
+        response.json().await.map_err(|e| self.unreachable(e))
+    }
+
+    /// GitHub and GitLab take the token as a bearer token, Azure DevOps as
+    /// the password of basic authentication. Bitbucket takes access tokens
+    /// as bearer tokens and app passwords with the account's user name.
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        let token = self.auth.api_token().unwrap_or_default();
+        match (self.provider, self.auth) {
+            (Provider::GitHub, _) => request
+                .bearer_auth(token)
+                .header(ACCEPT, "application/vnd.github+json"),
+            (Provider::AzureDevOps, _) => request.basic_auth("", Some(token)),
+            (Provider::Bitbucket, GitAuth::UserPass { username, password })
+                if username != "x-token-auth" =>
+            {
+                request.basic_auth(username, Some(password))
+            }
+            _ => request.bearer_auth(token),
+        }
+    }
+
+    fn unreachable(&self, e: reqwest::Error) -> AppError {
+        AppError::Unprocessable(format!(
+            "Failed to list repositories of {}: {}",
+            self.namespace, e
+        ))
+    }
+
+    fn warn_truncated(&self, truncated: bool) {
+        if truncated {
+            tracing::warn!(
+                "{} has more than {} repositories, the rest are not imported",
+                self.namespace,
+                MAX_PAGES * PER_PAGE
+            );
+        }
+    }
+}
+
+/// URL of the `rel="next"` link of a paginated response's `Link` header
+fn next_page(link: &str) -> Option<String> {
+    let next = link.split(',').find(|l| l.contains("rel=\"next\""))?;
+    let url = next
+        .split(';')
+        .next()?
+        .trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>');
+    Some(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository(
+        visibility: &str,
+        topics: &[&str],
+        archived: bool,
+        fork: bool,
+    ) -> ListedRepository {
+        ListedRepository {
+            name: "app".to_string(),
+            clone_url: "https://github.com/acme/app.git".to_string(),
+            default_branch: Some("main".to_string()),
+            visibility: visibility.to_string(),
+            topics: topics.iter().map(|t| t.to_string()).collect(),
+            archived,
+            fork,
+        }
+    }
+
+    #[test]
+    fn test_filter_repositories() {
+        let all = RepositoryFilter::default();
+        assert!(all.matches(&repository("private", &[], false, false)));
+        assert!(!all.matches(&repository("public", &[], true, false)));
+        assert!(!all.matches(&repository("public", &[], false, true)));
+
+        let filter = RepositoryFilter {
+            topics: vec!["Compliance".to_string()],
+            visibility: Some("internal".to_string()),
+            include_archived: true,
+            include_forks: false,
+        };
+        assert!(filter.matches(&repository(
+            "internal",
+            &["rust", "compliance"],
+            true,
+            false
+        )));
+        assert!(!filter.matches(&repository("internal", &["rust"], false, false)));
+        assert!(!filter.matches(&repository("public", &["compliance"], false, false)));
+    }
+
+    #[test]
+    fn test_next_page() {
+        let link = "<https://api.github.com/organizations/1/repos?per_page=100&page=2>; rel=\"next\", \
+                    <https://api.github.com/organizations/1/repos?per_page=100&page=5>; rel=\"last\"";
+        assert_eq!(
+            next_page(link).as_deref(),
+            Some("https://api.github.com/organizations/1/repos?per_page=100&page=2")
+        );
+        assert_eq!(
+            next_page("<https://api.github.com/x?page=1>; rel=\"prev\""),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validate_namespace() {
+        assert!(validate_namespace(Provider::GitHub, "acme-corp").is_ok());
+        assert!(validate_namespace(Provider::GitHub, "").is_err());
+        assert!(validate_namespace(Provider::GitHub, "acme/app").is_err());
+        assert!(validate_namespace(Provider::GitHub, "-acme").is_err());
+        assert!(validate_namespace(Provider::GitLab, "acme/platform.team").is_ok());
+        assert!(validate_namespace(Provider::GitLab, "acme//platform").is_err());
+        assert!(validate_namespace(Provider::Bitbucket, "acme_corp").is_ok());
+        assert!(validate_namespace(Provider::AzureDevOps, "acme/Web Platform").is_ok());
+        assert!(validate_namespace(Provider::AzureDevOps, "acme").is_err());
+        assert!(validate_namespace(Provider::Other, "acme").is_err());
+    }
+
+    #[test]
+    fn test_api_urls_encode_namespaces() {
+        let auth = GitAuth::Token("secret".to_string());
+        let listing = Listing {
+            client: Client::new(),
+            provider: Provider::GitLab,
+            namespace: "acme/platform",
+            auth: &auth,
+        };
+        let url = listing
+            .url(
+                "https://gitlab.example.com/api/v4/",
+                &["groups", "acme/platform", "projects"],
+            )
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://gitlab.example.com/api/v4/groups/acme%2Fplatform/projects"
+        );
+    }
+}
@@ -0,0 +1,346 @@
+//! Finding ownership from the scanned repository's CODEOWNERS file. The file
+//! is captured when the repository is cloned, so findings can be grouped by
+//! owning team long after the workspace is gone, and callbacks can name the
+//! teams whose paths tripped a failing rule.
+
+use crate::api::handlers::risk::is_copyleft_license;
+use crate::db::models::{Scan, ScanCodeOwners, ScanResult};
+use crate::error::AppError;
+use crate::export::sarif::{relative_uri, repository_path};
+use crate::AppState;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use utoipa::ToSchema;
+
+/// Where GitHub and GitLab look for CODEOWNERS, in order of precedence
+pub const LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Files larger than this are not CODEOWNERS files worth parsing
+const MAX_CODEOWNERS_BYTES: u64 = 3 * 1024 * 1024;
+
+/// Read the first CODEOWNERS file found in a checkout, with its path
+pub async fn read(repo_root: &Path) -> Option<(String, String)> {
+    for location in LOCATIONS {
+        let path = repo_root.join(location);
+        let Ok(metadata) = tokio::fs::metadata(&path).await else {
+            continue;
+        };
+        if !metadata.is_file() || metadata.len() > MAX_CODEOWNERS_BYTES {
+            continue;
+        }
+        if let Ok(content) = tokio::fs::read_to_string(&path).await {
+            return Some((location.to_string(), content));
+        }
+    }
+    None
+}
+
+/// Store the CODEOWNERS file of a checkout with its scan. Best-effort: a
+/// failure only costs the ownership view.
+pub async fn record(state: &AppState, scan_id: &str, repo_root: &Path) {
+    let Some((source_path, content)) = read(repo_root).await else {
+        return;
+    };
+    match ScanCodeOwners::upsert(&state.db, scan_id, &source_path, &content).await {
+        Ok(_) => tracing::info!("Recorded {} for scan {}", source_path, scan_id),
+        Err(e) => tracing::error!("Failed to store CODEOWNERS for scan {}: {}", scan_id, e),
+    }
+}
+
+#[derive(Debug)]
+struct Rule {
+    pattern: Regex,
+    owners: Vec<String>,
+}
+
+/// Parsed CODEOWNERS rules. As on GitHub, the last matching rule wins and a
+/// rule without owners leaves matching paths unowned.
+#[derive(Debug, Default)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Parse CODEOWNERS content. Comments, GitLab `[Section]` headers and
+    /// patterns that cannot be translated are skipped.
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .filter_map(|line| {
+                let line = line.split(" #").next().unwrap_or_default().trim();
+                if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+                    return None;
+                }
+                let mut fields = line.split_whitespace();
+                let pattern = pattern_regex(fields.next()?)?;
+                let owners = fields.map(str::to_string).collect();
+                Some(Rule { pattern, owners })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Owners of a repository-relative path, empty when no rule assigns any
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.pattern.is_match(path))
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Translate a gitignore-style CODEOWNERS pattern into an anchored regex
+fn pattern_regex(pattern: &str) -> Option<Regex> {
+    let directory = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    // Patterns without an inner slash match at any depth
+    let anchored = trimmed.starts_with('/') || trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    if trimmed.is_empty() && !directory {
+        return None;
+    }
+
+    let mut regex = String::from("^");
+    if !anchored {
+        regex.push_str("(?:.*/)?");
+    }
+
+    let segments: Vec<&str> = trimmed.split('/').collect();
+    for (i, segment) in segments.iter().enumerate() {
+        let last = i == segments.len() - 1;
+        match *segment {
+            "**" if last => regex.push_str(".*"),
+            "**" => regex.push_str("(?:.*/)?"),
+            _ => {
+                for c in segment.chars() {
+                    match c {
+                        '*' => regex.push_str("[^/]*"),
+                        '?' => regex.push_str("[^/]"),
+                        c => regex.push_str(&regex::escape(&c.to_string())),
+                    }
+                }
+                if !last {
+                    regex.push('/');
+                }
+            }
+        }
+    }
+
+    // `docs/` and `docs` own everything below; `docs/*` only direct children
+    let last_segment = segments.last().copied().unwrap_or_default();
+    if directory {
+        regex.push_str("/.*");
+    } else if !last_segment.contains(['*', '?']) {
+        regex.push_str("(?:/.*)?");
+    }
+    regex.push('$');
+
+    Regex::new(&regex).ok()
+}
+
+/// A finding that fails the policy gate, attributed to its file's owners
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OwnedViolation {
+    /// Gate rule the finding trips: `copyleft_license`, `ecc_critical_high` or `malware`
+    #[schema(value_type = String)]
+    pub rule: &'static str,
+    pub file_path: String,
+    /// License name, ECC severity and check, or malware signature
+    pub detail: String,
+}
+
+/// Findings in the paths one owner is responsible for
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OwnerSummary {
+    /// Team or user from CODEOWNERS, `null` for findings no rule assigns
+    pub owner: Option<String>,
+    pub file_count: i64,
+    /// Finding counts by result type
+    pub finding_counts: BTreeMap<String, i64>,
+    pub violations: Vec<OwnedViolation>,
+}
+
+/// The gate rule a stored finding trips, if any
+pub(crate) fn violation(result: &ScanResult) -> Option<(&'static str, String)> {
+    match result.result_type.as_str() {
+        "license" => result
+            .license_name
+            .as_deref()
+            .filter(|name| is_copyleft_license(name))
+            .map(|name| ("copyleft_license", name.to_string())),
+        "ecc" => {
+            let severity = result.risk_severity.as_deref().unwrap_or_default();
+            matches!(severity, "critical" | "high").then(|| {
+                let check = result.ecc_check_id.as_deref().unwrap_or("unknown check");
+                ("ecc_critical_high", format!("{} ({})", severity, check))
+            })
+        }
+        "malware" => Some(("malware", result.raw_data.clone().unwrap_or_default())),
+        _ => None,
+    }
+}
+
+/// Group a scan's findings by owner. Files with several owners count for
+/// each of them; owners with the most violations come first and unowned
+/// findings last.
+pub fn summarize(
+    codeowners: &CodeOwners,
+    scan_id: &str,
+    scan_path: Option<&str>,
+    results: &[ScanResult],
+) -> Vec<OwnerSummary> {
+    #[derive(Default)]
+    struct Acc {
+        files: BTreeSet<String>,
+        counts: BTreeMap<String, i64>,
+        violations: Vec<OwnedViolation>,
+    }
+
+    let mut by_owner: BTreeMap<Option<String>, Acc> = BTreeMap::new();
+    for result in results {
+        let owners = codeowners.owners_of(&repository_path(&result.file_path, scan_id, scan_path));
+        let keys: Vec<Option<String>> = if owners.is_empty() {
+            vec![None]
+        } else {
+            owners.iter().cloned().map(Some).collect()
+        };
+
+        let file_path = relative_uri(&result.file_path, scan_id);
+        let violation = violation(result);
+        for key in keys {
+            let acc = by_owner.entry(key).or_default();
+            acc.files.insert(file_path.clone());
+            *acc.counts.entry(result.result_type.clone()).or_default() += 1;
+            if let Some((rule, detail)) = &violation {
+                acc.violations.push(OwnedViolation {
+                    rule,
+                    file_path: file_path.clone(),
+                    detail: detail.clone(),
+                });
+            }
+        }
+    }
+
+    let mut summaries: Vec<OwnerSummary> = by_owner
+        .into_iter()
+        .map(|(owner, acc)| OwnerSummary {
+            owner,
+            file_count: acc.files.len() as i64,
+            finding_counts: acc.counts,
+            violations: acc.violations,
+        })
+        .collect();
+    summaries.sort_by(|a, b| {
+        a.owner
+            .is_none()
+            .cmp(&b.owner.is_none())
+            .then(b.violations.len().cmp(&a.violations.len()))
+            .then(a.owner.cmp(&b.owner))
+    });
+    summaries
+}
+
+/// Findings of a scan grouped by owner, with the CODEOWNERS location.
+/// `None` when the scanned checkout had no CODEOWNERS file.
+pub async fn scan_owners(
+    state: &AppState,
+    scan: &Scan,
+) -> Result<Option<(String, Vec<OwnerSummary>)>, AppError> {
+    let Some(stored) = ScanCodeOwners::find_by_scan_id(&state.db, &scan.id).await? else {
+        return Ok(None);
+    };
+
+    let results = state.result_store.load_results(&scan.id).await?;
+    let summaries = summarize(
+        &CodeOwners::parse(&stored.content),
+        &scan.id,
+        scan.scan_path.as_deref(),
+        &results,
+    );
+    Ok(Some((stored.source_path, summaries)))
+}
+
+/// Owners responsible for at least one violation, for routing notifications
+pub fn violating_owners(summaries: &[OwnerSummary]) -> Vec<String> {
+    summaries
+        .iter()
+        .filter(|s| !s.violations.is_empty())
+        .filter_map(|s| s.owner.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEOWNERS: &str = "\
+# Default owners
+*                       @acme/legal
+*.js                    @acme/frontend
+/services/payments/     @acme/payments @alice
+docs/*                  @acme/docs
+**/vendor/**            @acme/platform
+/services/payments/generated/
+";
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let owners = CodeOwners::parse(CODEOWNERS);
+        assert_eq!(owners.owners_of("README.md"), ["@acme/legal"]);
+        assert_eq!(owners.owners_of("web/app.js"), ["@acme/frontend"]);
+        assert_eq!(
+            owners.owners_of("services/payments/src/lib.rs"),
+            ["@acme/payments", "@alice"]
+        );
+        assert_eq!(owners.owners_of("docs/intro.md"), ["@acme/docs"]);
+        // `docs/*` does not reach into subdirectories
+        assert_eq!(owners.owners_of("docs/guides/setup.md"), ["@acme/legal"]);
+        assert_eq!(owners.owners_of("a/b/vendor/lib/x.c"), ["@acme/platform"]);
+        assert!(owners
+            .owners_of("services/payments/generated/api.rs")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_unanchored_patterns_match_at_any_depth() {
+        let owners = CodeOwners::parse("build/ @acme/ci\nMakefile @acme/ci\n");
+        assert_eq!(owners.owners_of("tools/build/run.sh"), ["@acme/ci"]);
+        assert_eq!(owners.owners_of("sub/Makefile"), ["@acme/ci"]);
+        assert!(owners.owners_of("buildx/run.sh").is_empty());
+        assert!(CodeOwners::parse("").owners_of("src/lib.rs").is_empty());
+    }
+
+    #[test]
+    fn test_summarize_attributes_violations_to_owners() {
+        let owners = CodeOwners::parse("/payments/ @acme/payments\n/web/ @acme/web\n");
+        let mut gpl = ScanResult::for_test("license", "/work/scan-1/payments/src/lib.rs");
+        gpl.license_name = Some("GPL-3.0".to_string());
+        let mut ecc = ScanResult::for_test("ecc", "/work/scan-1/web/crypto.js");
+        ecc.risk_severity = Some("low".to_string());
+        let results = vec![
+            gpl,
+            ecc,
+            ScanResult::for_test("copyright", "/work/scan-1/README.md"),
+        ];
+
+        let summaries = summarize(&owners, "scan-1", None, &results);
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(summaries[0].owner.as_deref(), Some("@acme/payments"));
+        assert_eq!(summaries[0].violations[0].rule, "copyleft_license");
+        assert_eq!(summaries[0].violations[0].file_path, "payments/src/lib.rs");
+        assert_eq!(summaries[1].owner.as_deref(), Some("@acme/web"));
+        assert!(summaries[1].violations.is_empty());
+        assert_eq!(summaries[2].owner, None);
+        assert_eq!(summaries[2].finding_counts["copyright"], 1);
+        assert_eq!(violating_owners(&summaries), ["@acme/payments"]);
+
+        // Paths of scans limited to a subdirectory are relative to it
+        let results = vec![ScanResult::for_test("license", "/work/scan-1/src/lib.rs")];
+        let summaries = summarize(&owners, "scan-1", Some("payments"), &results);
+        assert_eq!(summaries[0].owner.as_deref(), Some("@acme/payments"));
+    }
+}
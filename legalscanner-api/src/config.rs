@@ -1,13 +1,148 @@
+use crate::db::models::result_partition::ResultPartitioning;
+use crate::export::pipeline::POST_PROCESSOR_NAMES;
+use crate::git::DEFAULT_GIT_URL_SCHEMES;
+use crate::export::processors::parse_custom_fields;
+use crate::scanner::fossology::{DEFAULT_MAX_CONCURRENT_JOBS, DEFAULT_UPLOAD_NAME_TEMPLATE};
+use crate::scanner::merge::MergeStrategy;
+use crate::scanner::sandbox;
+use crate::scanner::semgrep::{CheckCategoryMap, SemgrepMode};
+use crate::server::ListenAddr;
+use sqlx::sqlite::SqliteSynchronous;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
+    // Connection pool and SQLite tuning, see db::PoolSettings
+    pub database_max_connections: u32,
+    pub database_busy_timeout_ms: u64,
+    pub database_wal: bool,
+    pub database_synchronous: SqliteSynchronous,
     pub fossology_url: String,
     pub fossology_api_token: String,
+    pub fossology_upload_name_template: String,
+    pub fossology_max_concurrent_jobs: usize,
     pub temp_workspace_dir: PathBuf,
-    pub server_port: u16,
+    /// `LISTEN_ADDR`, else every interface on `SERVER_PORT`
+    pub listen_addr: ListenAddr,
     pub api_key_salt: String,
+    // Backend of the findings: sqlite, postgres or parquet
+    pub result_store: String,
+    pub result_store_postgres_url: Option<String>,
+    // Parquet objects go to the bucket when set, else to the directory
+    pub result_store_dir: PathBuf,
+    pub result_store_s3_bucket: Option<String>,
+    pub result_store_s3_region: String,
+    pub result_store_s3_endpoint: Option<String>,
+    pub result_store_s3_prefix: String,
+    pub result_store_s3_access_key_id: Option<String>,
+    pub result_store_s3_secret_access_key: Option<String>,
+    pub semgrep_rulesets: Vec<String>,
+    pub semgrep_check_categories: CheckCategoryMap,
+    pub semgrep_mode: SemgrepMode,
+    pub semgrep_binary: String,
+    pub semgrep_local_rules_dir: PathBuf,
+    pub clamav_address: Option<String>,
+    pub syft_binary: Option<String>,
+    // Confinement of Semgrep in local mode and Syft, see scanner::sandbox.
+    // Limits of 0 are off.
+    pub scanner_sandbox_user: Option<(u32, u32)>,
+    pub scanner_sandbox_memory_mb: u64,
+    pub scanner_sandbox_cpu_secs: u64,
+    pub scanner_sandbox_max_processes: u64,
+    pub scanner_sandbox_max_file_size_mb: u64,
+    pub scanner_sandbox_cgroup: Option<PathBuf>,
+    pub scanner_sandbox_bwrap: Option<String>,
+    pub scanner_sandbox_network: bool,
+    pub merge_strategy: MergeStrategy,
+    pub quick_scan_timeout_secs: u64,
+    pub screening_webhook_url: Option<String>,
+    pub screening_webhook_token: Option<String>,
+    pub screening_min_severity: String,
+    pub anonymized_analytics_enabled: bool,
+    pub analytics_instance_label: Option<String>,
+    pub soft_limit_max_files: u64,
+    pub soft_limit_max_size_mb: u64,
+    pub due_diligence_max_commits: usize,
+    // Commits the authorship scan walks, newest first, 0 for all
+    pub git_authorship_max_commits: usize,
+    pub worker_name: String,
+    pub response_compression: Vec<String>,
+    pub compression_min_size: u16,
+    // SBOM export post-processors, in the order they run
+    pub export_post_processors: Vec<String>,
+    pub export_custom_fields: Vec<(String, String)>,
+    pub export_signing_key: Option<String>,
+    // Scan completion callbacks
+    pub webhook_secret: Option<String>,
+    pub webhook_max_attempts: u32,
+    pub webhook_retry_delay_secs: u64,
+    pub public_base_url: Option<String>,
+    // Return the running scan of a repository and branch instead of
+    // starting a second one
+    pub scan_lock_per_repository: bool,
+    // Bearer token for admin endpoints, which are disabled without it
+    pub admin_api_token: Option<String>,
+    // Queried for repository metadata of github.com scans that have a token
+    pub github_api_url: String,
+    // Queried to list the repositories of GitLab groups, Bitbucket
+    // workspaces and Azure DevOps projects for bulk import
+    pub gitlab_api_url: String,
+    pub bitbucket_api_url: String,
+    pub azure_devops_url: String,
+    // Archive uploads: request size and total size once extracted
+    pub upload_max_size_mb: u64,
+    pub upload_max_extracted_mb: u64,
+    // Hours between integrity checks of the stored results, 0 disables them
+    pub integrity_check_interval_hours: u64,
+    // Directories whose subdirectories admins may scan in place, by `path`
+    pub local_scan_base_dirs: Vec<PathBuf>,
+    // Schemes a git URL may use, scp-like `git@host:path` is always allowed
+    pub git_url_schemes: Vec<String>,
+    // Time allowed to reach a git remote before a scan is accepted, 0 skips
+    // the check
+    pub remote_check_timeout_secs: u64,
+    // Time a clone may take before its scan fails, 0 waits indefinitely
+    pub clone_timeout_secs: u64,
+    // Encrypts stored git credentials, which cannot be registered without it
+    pub credentials_encryption_key: Option<String>,
+    // Content SWHIDs of the scanned files. Software Heritage is only asked
+    // about them when its API URL is set, they are derived from the code.
+    pub swhid_enabled: bool,
+    pub software_heritage_url: Option<String>,
+    // Needed for the provenance API that names the origins of a file
+    pub software_heritage_token: Option<String>,
+    // Files whose origin is looked up per scan, 0 for none
+    pub software_heritage_max_lookups: usize,
+    // Opt-in anonymous usage reports, nothing is sent without the URL
+    pub telemetry_url: Option<String>,
+    pub telemetry_interval_hours: u64,
+    // Days the findings and the scans themselves are kept once a scan has
+    // finished, 0 keeps them forever
+    pub retention_results_days: u64,
+    pub retention_scans_days: u64,
+    // Hours between retention purges, 0 leaves purging to the admin endpoint
+    pub retention_purge_interval_hours: u64,
+    // Findings of new scans in one table per scan month, or all in one
+    pub result_partitioning: ResultPartitioning,
+    // Where the findings of old scans are archived: none, disk or s3
+    pub result_archive: String,
+    pub result_archive_dir: PathBuf,
+    pub result_archive_s3_bucket: Option<String>,
+    pub result_archive_s3_region: String,
+    // S3-compatible endpoint, AWS when unset
+    pub result_archive_s3_endpoint: Option<String>,
+    pub result_archive_s3_prefix: String,
+    pub result_archive_s3_access_key_id: Option<String>,
+    pub result_archive_s3_secret_access_key: Option<String>,
+    // Days a scan has been finished before its findings are archived, 0
+    // archives nothing
+    pub result_archive_after_days: u64,
+    // Hours between archival runs, 0 leaves archiving to the admin endpoint
+    pub result_archive_interval_hours: u64,
+    // Secret of the GitHub and GitLab push webhooks, which are refused
+    // without it
+    pub push_webhook_secret: Option<String>,
 }
 
 impl Config {
@@ -15,21 +150,364 @@ impl Config {
         // Load .env file if it exists
         dotenvy::dotenv().ok();
 
+        let export_signing_key = std::env::var("EXPORT_SIGNING_KEY")
+            .ok()
+            .filter(|k| !k.is_empty());
+        let export_post_processors = parse_post_processors(
+            &std::env::var("EXPORT_POST_PROCESSORS").unwrap_or_default(),
+            export_signing_key.is_some(),
+        )?;
+
         Ok(Config {
             database_url: std::env::var("DATABASE_URL")
                 .unwrap_or_else(|_| "./data/legalscanner.db".to_string()),
+            database_max_connections: parse_max_connections(
+                &std::env::var("DATABASE_MAX_CONNECTIONS").unwrap_or_else(|_| "10".to_string()),
+            )?,
+            database_busy_timeout_ms: std::env::var("DATABASE_BUSY_TIMEOUT_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()?,
+            database_wal: std::env::var("DATABASE_WAL")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()?,
+            database_synchronous: std::env::var("DATABASE_SYNCHRONOUS")
+                .unwrap_or_else(|_| "normal".to_string())
+                .parse()?,
             fossology_url: std::env::var("FOSSOLOGY_URL")
                 .unwrap_or_else(|_| "http://localhost:8081".to_string()),
             fossology_api_token: std::env::var("FOSSOLOGY_API_TOKEN")
                 .unwrap_or_else(|_| "".to_string()),
+            fossology_upload_name_template: std::env::var("FOSSOLOGY_UPLOAD_NAME_TEMPLATE")
+                .unwrap_or_else(|_| DEFAULT_UPLOAD_NAME_TEMPLATE.to_string()),
+            fossology_max_concurrent_jobs: std::env::var("FOSSOLOGY_MAX_CONCURRENT_JOBS")
+                .unwrap_or_else(|_| DEFAULT_MAX_CONCURRENT_JOBS.to_string())
+                .parse()?,
             temp_workspace_dir: std::env::var("TEMP_WORKSPACE_DIR")
                 .unwrap_or_else(|_| "/tmp/legalscanner".to_string())
                 .into(),
-            server_port: std::env::var("SERVER_PORT")
-                .unwrap_or_else(|_| "8080".to_string())
-                .parse()?,
+            listen_addr: match std::env::var("LISTEN_ADDR") {
+                Ok(addr) => addr.parse()?,
+                Err(_) => ListenAddr::any(
+                    std::env::var("SERVER_PORT")
+                        .unwrap_or_else(|_| "8080".to_string())
+                        .parse()?,
+                ),
+            },
             api_key_salt: std::env::var("API_KEY_SALT")
                 .unwrap_or_else(|_| "default-salt-change-in-production".to_string()),
+            result_store: std::env::var("RESULT_STORE")
+                .unwrap_or_else(|_| "sqlite".to_string()),
+            result_store_postgres_url: std::env::var("RESULT_STORE_POSTGRES_URL")
+                .ok()
+                .filter(|u| !u.is_empty()),
+            result_store_dir: std::env::var("RESULT_STORE_DIR")
+                .unwrap_or_else(|_| "./data/results".to_string())
+                .into(),
+            result_store_s3_bucket: std::env::var("RESULT_STORE_S3_BUCKET")
+                .ok()
+                .filter(|b| !b.is_empty()),
+            result_store_s3_region: std::env::var("RESULT_STORE_S3_REGION")
+                .unwrap_or_else(|_| "us-east-1".to_string()),
+            result_store_s3_endpoint: std::env::var("RESULT_STORE_S3_ENDPOINT")
+                .ok()
+                .filter(|e| !e.is_empty()),
+            result_store_s3_prefix: std::env::var("RESULT_STORE_S3_PREFIX")
+                .unwrap_or_default(),
+            result_store_s3_access_key_id: std::env::var("RESULT_STORE_S3_ACCESS_KEY_ID")
+                .ok()
+                .filter(|k| !k.is_empty()),
+            result_store_s3_secret_access_key: std::env::var("RESULT_STORE_S3_SECRET_ACCESS_KEY")
+                .ok()
+                .filter(|k| !k.is_empty()),
+            semgrep_rulesets: std::env::var("SEMGREP_RULESETS")
+                .map(|v| {
+                    v.split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            semgrep_check_categories: CheckCategoryMap::parse(
+                &std::env::var("SEMGREP_CHECK_CATEGORIES").unwrap_or_default(),
+            )?,
+            semgrep_mode: std::env::var("SEMGREP_MODE")
+                .unwrap_or_else(|_| "docker".to_string())
+                .parse()?,
+            semgrep_binary: std::env::var("SEMGREP_BINARY")
+                .unwrap_or_else(|_| "semgrep".to_string()),
+            semgrep_local_rules_dir: std::env::var("SEMGREP_RULES_DIR")
+                .unwrap_or_else(|_| "./semgrep-rules".to_string())
+                .into(),
+            clamav_address: std::env::var("CLAMAV_ADDRESS")
+                .ok()
+                .filter(|a| !a.is_empty()),
+            syft_binary: std::env::var("SYFT_BINARY")
+                .ok()
+                .filter(|b| !b.is_empty()),
+            scanner_sandbox_user: sandbox::parse_user(
+                &std::env::var("SCANNER_SANDBOX_USER").unwrap_or_default(),
+            )?,
+            scanner_sandbox_memory_mb: std::env::var("SCANNER_SANDBOX_MEMORY_MB")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            scanner_sandbox_cpu_secs: std::env::var("SCANNER_SANDBOX_CPU_SECS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            scanner_sandbox_max_processes: std::env::var("SCANNER_SANDBOX_MAX_PROCESSES")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            scanner_sandbox_max_file_size_mb: std::env::var("SCANNER_SANDBOX_MAX_FILE_SIZE_MB")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            scanner_sandbox_cgroup: std::env::var("SCANNER_SANDBOX_CGROUP")
+                .ok()
+                .filter(|c| !c.is_empty())
+                .map(PathBuf::from),
+            scanner_sandbox_bwrap: std::env::var("SCANNER_SANDBOX_BWRAP")
+                .ok()
+                .filter(|b| !b.is_empty()),
+            scanner_sandbox_network: std::env::var("SCANNER_SANDBOX_NETWORK")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()?,
+            merge_strategy: std::env::var("MERGE_STRATEGY")
+                .unwrap_or_else(|_| "keep-all".to_string())
+                .parse()?,
+            quick_scan_timeout_secs: std::env::var("QUICK_SCAN_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()?,
+            screening_webhook_url: std::env::var("SCREENING_WEBHOOK_URL")
+                .ok()
+                .filter(|u| !u.is_empty()),
+            screening_webhook_token: std::env::var("SCREENING_WEBHOOK_TOKEN")
+                .ok()
+                .filter(|t| !t.is_empty()),
+            screening_min_severity: parse_severity(
+                &std::env::var("SCREENING_MIN_SEVERITY").unwrap_or_else(|_| "critical".to_string()),
+            )?,
+            anonymized_analytics_enabled: std::env::var("ANONYMIZED_ANALYTICS_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()?,
+            analytics_instance_label: std::env::var("ANALYTICS_INSTANCE_LABEL")
+                .ok()
+                .filter(|l| !l.is_empty()),
+            soft_limit_max_files: std::env::var("SOFT_LIMIT_MAX_FILES")
+                .unwrap_or_else(|_| "50000".to_string())
+                .parse()?,
+            soft_limit_max_size_mb: std::env::var("SOFT_LIMIT_MAX_SIZE_MB")
+                .unwrap_or_else(|_| "1024".to_string())
+                .parse()?,
+            due_diligence_max_commits: std::env::var("DUE_DILIGENCE_MAX_COMMITS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()?,
+            git_authorship_max_commits: std::env::var("GIT_AUTHORSHIP_MAX_COMMITS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()?,
+            worker_name: std::env::var("HOSTNAME")
+                .unwrap_or_else(|_| "legalscanner-api".to_string()),
+            response_compression: parse_compression(
+                &std::env::var("RESPONSE_COMPRESSION").unwrap_or_else(|_| "gzip,br".to_string()),
+            )?,
+            compression_min_size: std::env::var("COMPRESSION_MIN_SIZE")
+                .unwrap_or_else(|_| "1024".to_string())
+                .parse()?,
+            export_post_processors,
+            export_custom_fields: parse_custom_fields(
+                &std::env::var("EXPORT_CUSTOM_FIELDS").unwrap_or_default(),
+            )?,
+            export_signing_key,
+            webhook_secret: std::env::var("WEBHOOK_SECRET")
+                .ok()
+                .filter(|s| !s.is_empty()),
+            webhook_max_attempts: std::env::var("WEBHOOK_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()?,
+            webhook_retry_delay_secs: std::env::var("WEBHOOK_RETRY_DELAY_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()?,
+            public_base_url: std::env::var("PUBLIC_BASE_URL")
+                .ok()
+                .map(|u| u.trim_end_matches('/').to_string())
+                .filter(|u| !u.is_empty()),
+            scan_lock_per_repository: std::env::var("SCAN_LOCK_PER_REPOSITORY")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()?,
+            admin_api_token: std::env::var("ADMIN_API_TOKEN")
+                .ok()
+                .filter(|t| !t.is_empty()),
+            github_api_url: std::env::var("GITHUB_API_URL")
+                .unwrap_or_else(|_| "https://api.github.com".to_string()),
+            gitlab_api_url: std::env::var("GITLAB_API_URL")
+                .unwrap_or_else(|_| "https://gitlab.com/api/v4".to_string()),
+            bitbucket_api_url: std::env::var("BITBUCKET_API_URL")
+                .unwrap_or_else(|_| "https://api.bitbucket.org/2.0".to_string()),
+            azure_devops_url: std::env::var("AZURE_DEVOPS_URL")
+                .unwrap_or_else(|_| "https://dev.azure.com".to_string()),
+            upload_max_size_mb: std::env::var("UPLOAD_MAX_SIZE_MB")
+                .unwrap_or_else(|_| "512".to_string())
+                .parse()?,
+            upload_max_extracted_mb: std::env::var("UPLOAD_MAX_EXTRACTED_MB")
+                .unwrap_or_else(|_| "4096".to_string())
+                .parse()?,
+            integrity_check_interval_hours: std::env::var("INTEGRITY_CHECK_INTERVAL_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()?,
+            local_scan_base_dirs: std::env::var("LOCAL_SCAN_BASE_DIRS")
+                .map(|v| {
+                    v.split(',')
+                        .map(|s| s.trim())
+                        .filter(|s| !s.is_empty())
+                        .map(PathBuf::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            git_url_schemes: std::env::var("GIT_URL_SCHEMES")
+                .map(|v| {
+                    v.split(',')
+                        .map(|s| s.trim().to_lowercase())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_else(|_| DEFAULT_GIT_URL_SCHEMES.map(String::from).to_vec()),
+            remote_check_timeout_secs: std::env::var("REMOTE_CHECK_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()?,
+            clone_timeout_secs: std::env::var("CLONE_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()?,
+            credentials_encryption_key: std::env::var("CREDENTIALS_ENCRYPTION_KEY")
+                .ok()
+                .filter(|k| !k.is_empty()),
+            swhid_enabled: std::env::var("SWHID_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()?,
+            software_heritage_url: std::env::var("SOFTWARE_HERITAGE_URL")
+                .ok()
+                .map(|u| u.trim_end_matches('/').to_string())
+                .filter(|u| !u.is_empty()),
+            software_heritage_token: std::env::var("SOFTWARE_HERITAGE_TOKEN")
+                .ok()
+                .filter(|t| !t.is_empty()),
+            software_heritage_max_lookups: std::env::var("SOFTWARE_HERITAGE_MAX_LOOKUPS")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()?,
+            telemetry_url: std::env::var("TELEMETRY_URL")
+                .ok()
+                .filter(|u| !u.is_empty()),
+            telemetry_interval_hours: std::env::var("TELEMETRY_INTERVAL_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()?,
+            retention_results_days: std::env::var("RETENTION_RESULTS_DAYS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            retention_scans_days: std::env::var("RETENTION_SCANS_DAYS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            retention_purge_interval_hours: std::env::var("RETENTION_PURGE_INTERVAL_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()?,
+            result_partitioning: std::env::var("RESULT_PARTITIONING")
+                .unwrap_or_else(|_| "none".to_string())
+                .parse()?,
+            result_archive: std::env::var("RESULT_ARCHIVE")
+                .unwrap_or_else(|_| "none".to_string()),
+            result_archive_dir: std::env::var("RESULT_ARCHIVE_DIR")
+                .unwrap_or_else(|_| "./data/archive".to_string())
+                .into(),
+            result_archive_s3_bucket: std::env::var("RESULT_ARCHIVE_S3_BUCKET")
+                .ok()
+                .filter(|b| !b.is_empty()),
+            result_archive_s3_region: std::env::var("RESULT_ARCHIVE_S3_REGION")
+                .unwrap_or_else(|_| "us-east-1".to_string()),
+            result_archive_s3_endpoint: std::env::var("RESULT_ARCHIVE_S3_ENDPOINT")
+                .ok()
+                .filter(|e| !e.is_empty()),
+            result_archive_s3_prefix: std::env::var("RESULT_ARCHIVE_S3_PREFIX")
+                .unwrap_or_default(),
+            result_archive_s3_access_key_id: std::env::var("RESULT_ARCHIVE_S3_ACCESS_KEY_ID")
+                .ok()
+                .filter(|k| !k.is_empty()),
+            result_archive_s3_secret_access_key: std::env::var(
+                "RESULT_ARCHIVE_S3_SECRET_ACCESS_KEY",
+            )
+            .ok()
+            .filter(|k| !k.is_empty()),
+            result_archive_after_days: std::env::var("RESULT_ARCHIVE_AFTER_DAYS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            result_archive_interval_hours: std::env::var("RESULT_ARCHIVE_INTERVAL_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()?,
+            push_webhook_secret: std::env::var("PUSH_WEBHOOK_SECRET")
+                .ok()
+                .filter(|s| !s.is_empty()),
         })
     }
 }
+
+/// ECC severity threshold, one of `low`, `medium`, `high` or `critical`
+fn parse_severity(value: &str) -> Result<String, String> {
+    let severity = value.trim().to_lowercase();
+    match severity.as_str() {
+        "low" | "medium" | "high" | "critical" => Ok(severity),
+        other => Err(format!(
+            "Invalid SCREENING_MIN_SEVERITY '{}' (expected low, medium, high or critical)",
+            other
+        )),
+    }
+}
+
+/// `DATABASE_MAX_CONNECTIONS`, at least one
+fn parse_max_connections(value: &str) -> Result<u32, String> {
+    match value.trim().parse::<u32>() {
+        Ok(0) | Err(_) => Err(format!(
+            "Invalid DATABASE_MAX_CONNECTIONS '{}' (expected a positive number)",
+            value
+        )),
+        Ok(connections) => Ok(connections),
+    }
+}
+
+/// Parse the comma-separated `EXPORT_POST_PROCESSORS` list; `sign` needs
+/// `EXPORT_SIGNING_KEY`
+fn parse_post_processors(value: &str, has_signing_key: bool) -> Result<Vec<String>, String> {
+    let mut processors = Vec::new();
+    for name in value
+        .split(',')
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+    {
+        if !POST_PROCESSOR_NAMES.contains(&name.as_str()) {
+            return Err(format!(
+                "Unknown EXPORT_POST_PROCESSORS entry '{}' (expected {})",
+                name,
+                POST_PROCESSOR_NAMES.join(", ")
+            ));
+        }
+        if name == "sign" && !has_signing_key {
+            return Err(
+                "EXPORT_POST_PROCESSORS includes sign but EXPORT_SIGNING_KEY is not set"
+                    .to_string(),
+            );
+        }
+        processors.push(name);
+    }
+    Ok(processors)
+}
+
+/// Parse a comma-separated list of response encodings; `none` disables compression
+fn parse_compression(value: &str) -> Result<Vec<String>, String> {
+    let mut algorithms = Vec::new();
+    for algorithm in value.split(',').map(|a| a.trim().to_lowercase()).filter(|a| !a.is_empty()) {
+        match algorithm.as_str() {
+            "none" => return Ok(Vec::new()),
+            "gzip" | "br" => algorithms.push(algorithm),
+            other => {
+                return Err(format!(
+                    "Unsupported RESPONSE_COMPRESSION algorithm '{}' (expected gzip, br or none)",
+                    other
+                ))
+            }
+        }
+    }
+    Ok(algorithms)
+}
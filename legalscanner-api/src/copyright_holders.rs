@@ -0,0 +1,296 @@
+//! Cross-scan copyright holder registry. Holders are keyed by a normalized
+//! name so spelling variants ("Acme, Inc.", "ACME Inc") count as one holder
+//! when legal asks whether anything we ship is copyrighted by X.
+//!
+//! Commit authors from a `git_authorship` scan are recorded as a separate
+//! kind of evidence: the files they committed to are counted apart from the
+//! files whose copyright notices name the holder.
+
+use crate::db::models::{CopyrightHolder, Scan, ScanFileAuthor, ScanResult};
+use crate::storage::{ResultQuery, ResultStore, StorageError};
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashSet};
+
+/// Company forms dropped from the end of a holder name
+const LEGAL_SUFFIXES: &[&str] = &[
+    "inc",
+    "incorporated",
+    "llc",
+    "llp",
+    "ltd",
+    "limited",
+    "gmbh",
+    "corp",
+    "corporation",
+    "co",
+    "company",
+    "ag",
+    "sa",
+    "bv",
+    "plc",
+];
+
+/// Trailing phrases that are not part of the holder's name
+const TRAILING_PHRASES: &[&[&str]] = &[
+    &["all", "rights", "reserved"],
+    &["and", "its", "contributors"],
+    &["and", "other", "contributors"],
+    &["and", "contributors"],
+    &["et", "al"],
+];
+
+/// Registry key for a holder: lowercase words without punctuation, e-mail
+/// addresses, URLs, company forms or "all rights reserved"-style suffixes.
+/// Returns `None` if nothing identifying is left.
+pub fn normalize_holder(holder: &str) -> Option<String> {
+    let mut words: Vec<String> = holder
+        .split_whitespace()
+        .filter(|w| !w.contains('@') && !w.contains("://"))
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    loop {
+        let before = words.len();
+        for phrase in TRAILING_PHRASES {
+            let start = words.len().saturating_sub(phrase.len());
+            if start > 0
+                && words[start..]
+                    .iter()
+                    .zip(phrase.iter())
+                    .all(|(w, p)| w == p)
+            {
+                words.truncate(start);
+            }
+        }
+        if words.len() > 1 && LEGAL_SUFFIXES.contains(&words[words.len() - 1].as_str()) {
+            words.pop();
+        }
+        if words.len() == before {
+            break;
+        }
+    }
+
+    if words.len() > 1 && words[0] == "the" {
+        words.remove(0);
+    }
+
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join(" "))
+    }
+}
+
+/// A holder found in a scan and the number of files attributed to it
+#[derive(Debug, PartialEq)]
+pub struct HolderCount {
+    pub normalized_name: String,
+    pub display_name: String,
+    /// Files whose copyright notices name the holder
+    pub file_count: i64,
+    /// Files the holder authored commits to
+    pub commit_file_count: i64,
+}
+
+/// Group a scan's copyright findings by normalized holder. The first
+/// spelling encountered is kept as the display name.
+pub fn holder_counts(results: &[ScanResult]) -> Vec<HolderCount> {
+    let mut holders: BTreeMap<String, (String, HashSet<&str>)> = BTreeMap::new();
+
+    for result in results.iter().filter(|r| r.result_type == "copyright") {
+        let names: Vec<String> = result
+            .copyright_holders
+            .as_deref()
+            .and_then(|h| serde_json::from_str(h).ok())
+            .unwrap_or_default();
+
+        for name in names {
+            let Some(normalized) = normalize_holder(&name) else {
+                continue;
+            };
+            let display = name.trim().trim_end_matches([',', '.', ';']).to_string();
+            holders
+                .entry(normalized)
+                .or_insert_with(|| (display, HashSet::new()))
+                .1
+                .insert(result.file_path.as_str());
+        }
+    }
+
+    holders
+        .into_iter()
+        .map(|(normalized_name, (display_name, files))| HolderCount {
+            normalized_name,
+            display_name,
+            file_count: files.len() as i64,
+            commit_file_count: 0,
+        })
+        .collect()
+}
+
+/// Add the commit authors of a scan's files to the holder counts, by
+/// normalized author name. Holders only seen as authors keep the author's
+/// spelling.
+pub fn add_commit_authors(holders: &mut Vec<HolderCount>, authors: &[ScanFileAuthor]) {
+    let mut files: BTreeMap<String, (String, HashSet<&str>)> = BTreeMap::new();
+    for author in authors {
+        let Some(normalized) = normalize_holder(&author.author_name) else {
+            continue;
+        };
+        files
+            .entry(normalized)
+            .or_insert_with(|| (author.author_name.trim().to_string(), HashSet::new()))
+            .1
+            .insert(author.file_path.as_str());
+    }
+
+    for (normalized_name, (display_name, files)) in files {
+        let commit_file_count = files.len() as i64;
+        match holders.iter_mut().find(|h| h.normalized_name == normalized_name) {
+            Some(holder) => holder.commit_file_count = commit_file_count,
+            None => holders.push(HolderCount {
+                normalized_name,
+                display_name,
+                file_count: 0,
+                commit_file_count,
+            }),
+        }
+    }
+}
+
+/// Add a scan's copyright holders to the registry. Returns the number of
+/// distinct holders recorded.
+pub async fn update_registry(
+    pool: &SqlitePool,
+    store: &dyn ResultStore,
+    scan: &Scan,
+) -> Result<usize, StorageError> {
+    let query = ResultQuery {
+        result_type: Some("copyright".to_string()),
+        ..Default::default()
+    };
+    let results = store.query_results(&scan.id, &query).await?;
+    let seen_at = scan.completed_at.as_deref().unwrap_or(&scan.created_at);
+
+    let mut holders = holder_counts(&results);
+    if scan.git_authorship {
+        let authors = ScanFileAuthor::find_by_scan_id(pool, &scan.id).await?;
+        add_commit_authors(&mut holders, &authors);
+    }
+    for holder in &holders {
+        CopyrightHolder::record(
+            pool,
+            &holder.normalized_name,
+            &holder.display_name,
+            &scan.id,
+            &scan.git_url,
+            holder.file_count,
+            holder.commit_file_count,
+            seen_at,
+        )
+        .await?;
+    }
+
+    Ok(holders.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copyright(file: &str, holders: &[&str]) -> ScanResult {
+        ScanResult {
+            copyright_holders: Some(serde_json::to_string(holders).unwrap()),
+            ..ScanResult::for_test("copyright", file)
+        }
+    }
+
+    #[test]
+    fn test_normalize_holder() {
+        assert_eq!(normalize_holder("Acme, Inc.").as_deref(), Some("acme"));
+        assert_eq!(normalize_holder("ACME Inc").as_deref(), Some("acme"));
+        assert_eq!(
+            normalize_holder("The Rust Project Developers").as_deref(),
+            Some("rust project developers")
+        );
+        assert_eq!(
+            normalize_holder("Jane Doe <jane@example.com>, All Rights Reserved").as_deref(),
+            Some("jane doe")
+        );
+        assert_eq!(
+            normalize_holder("Google LLC and its contributors").as_deref(),
+            Some("google")
+        );
+        // A bare company form is kept rather than emptied
+        assert_eq!(normalize_holder("Inc.").as_deref(), Some("inc"));
+        assert_eq!(normalize_holder(" , "), None);
+    }
+
+    #[test]
+    fn test_holder_counts_merges_variants() {
+        let results = vec![
+            copyright("a.rs", &["Acme, Inc."]),
+            copyright("b.rs", &["ACME Inc", "Jane Doe"]),
+            copyright("b.rs", &["Acme Corporation"]),
+        ];
+
+        let counts = holder_counts(&results);
+        assert_eq!(
+            counts,
+            vec![
+                HolderCount {
+                    normalized_name: "acme".to_string(),
+                    display_name: "Acme, Inc".to_string(),
+                    file_count: 2,
+                    commit_file_count: 0,
+                },
+                HolderCount {
+                    normalized_name: "jane doe".to_string(),
+                    display_name: "Jane Doe".to_string(),
+                    file_count: 1,
+                    commit_file_count: 0,
+                },
+            ]
+        );
+    }
+
+    fn author(file: &str, name: &str) -> ScanFileAuthor {
+        ScanFileAuthor {
+            id: 1,
+            scan_id: "scan-1".to_string(),
+            file_path: file.to_string(),
+            author_name: name.to_string(),
+            author_email: format!("{}@example.com", name.to_lowercase().replace(' ', ".")),
+            commit_count: 1,
+            first_commit_sha: "abc".to_string(),
+            first_commit_date: "2024-01-01T00:00:00+00:00".to_string(),
+            last_commit_sha: "abc".to_string(),
+            last_commit_date: "2024-01-01T00:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_add_commit_authors() {
+        let mut counts = holder_counts(&[copyright("a.rs", &["Jane Doe"])]);
+        add_commit_authors(
+            &mut counts,
+            &[
+                author("a.rs", "Jane Doe"),
+                author("b.rs", "jane doe"),
+                author("b.rs", "John Roe"),
+            ],
+        );
+
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[0].display_name, "Jane Doe");
+        assert_eq!((counts[0].file_count, counts[0].commit_file_count), (1, 2));
+        assert_eq!(counts[1].display_name, "John Roe");
+        assert_eq!((counts[1].file_count, counts[1].commit_file_count), (0, 1));
+    }
+}
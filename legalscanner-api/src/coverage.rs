@@ -0,0 +1,229 @@
+//! License coverage per programming language. The files of the checkout are
+//! counted per language, by file extension, when the repository is cloned;
+//! the report compares them with the files that have license findings, to
+//! spot e.g. a Python tree without license headers next to licensed JS.
+//! Files of no known language (documentation, data, LICENSE files) are left
+//! out.
+
+use crate::api::models::{LanguageCoverage, LicenseFileCount};
+use crate::db::models::ScanLanguageStat;
+use crate::error::AppError;
+use crate::export::sarif::relative_uri;
+use crate::scanner::walk::collect_files;
+use crate::storage::ResultQuery;
+use crate::AppState;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// Programming languages by lowercase file extension
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("c", "C"),
+    ("h", "C"),
+    ("cc", "C++"),
+    ("cpp", "C++"),
+    ("cxx", "C++"),
+    ("hh", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("cjs", "JavaScript"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("m", "Objective-C"),
+    ("mm", "Objective-C"),
+    ("php", "PHP"),
+    ("pl", "Perl"),
+    ("pm", "Perl"),
+    ("py", "Python"),
+    ("rb", "Ruby"),
+    ("rs", "Rust"),
+    ("scala", "Scala"),
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("swift", "Swift"),
+    ("dart", "Dart"),
+    ("lua", "Lua"),
+    ("r", "R"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("erl", "Erlang"),
+    ("hs", "Haskell"),
+    ("vue", "Vue"),
+    ("sql", "SQL"),
+];
+
+/// Language of a file, by its extension
+pub fn language_of(path: &str) -> Option<&'static str> {
+    let file_name = path.rsplit('/').next()?;
+    let (_, extension) = file_name.rsplit_once('.')?;
+    let extension = extension.to_ascii_lowercase();
+    EXTENSIONS
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, language)| *language)
+}
+
+/// Count the files of a checkout per language and store the counts with the
+/// scan. Best-effort: a failure only costs the coverage report.
+pub async fn record(state: &AppState, scan_id: &str, root: &Path) {
+    let files = match collect_files(root).await {
+        Ok(files) => files,
+        Err(e) => {
+            tracing::warn!("Failed to count files for scan {}: {}", scan_id, e);
+            return;
+        }
+    };
+
+    let mut languages: BTreeMap<&str, (i64, i64)> = BTreeMap::new();
+    for file in &files {
+        let Some(language) = language_of(&file.to_string_lossy()) else {
+            continue;
+        };
+        let size = tokio::fs::metadata(file).await.map(|m| m.len()).unwrap_or(0);
+        let entry = languages.entry(language).or_default();
+        entry.0 += 1;
+        entry.1 += size as i64;
+    }
+
+    let stats: Vec<(String, i64, i64)> = languages
+        .into_iter()
+        .map(|(language, (files, bytes))| (language.to_string(), files, bytes))
+        .collect();
+    match ScanLanguageStat::replace(&state.db, scan_id, &stats).await {
+        Ok(()) => tracing::info!("Recorded {} languages for scan {}", stats.len(), scan_id),
+        Err(e) => tracing::error!(
+            "Failed to store language statistics for scan {}: {}",
+            scan_id,
+            e
+        ),
+    }
+}
+
+/// Coverage of each language recorded for the scan
+pub async fn report(state: &AppState, scan_id: &str) -> Result<Vec<LanguageCoverage>, AppError> {
+    let stats = ScanLanguageStat::find_by_scan_id(&state.db, scan_id).await?;
+    let query = ResultQuery {
+        result_type: Some("license".to_string()),
+        ..Default::default()
+    };
+    let license_files: BTreeSet<(String, String)> = state
+        .result_store
+        .query_results(scan_id, &query)
+        .await?
+        .into_iter()
+        .filter_map(|r| Some((relative_uri(&r.file_path, scan_id), r.license_name?)))
+        .collect();
+    let license_files: Vec<(String, String)> = license_files.into_iter().collect();
+    Ok(coverage(&stats, &license_files))
+}
+
+/// Combine per-language file counts with the `(path, license)` pairs of the
+/// license findings
+fn coverage(stats: &[ScanLanguageStat], license_files: &[(String, String)]) -> Vec<LanguageCoverage> {
+    let mut licensed: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    let mut licenses: HashMap<&str, BTreeMap<&str, i64>> = HashMap::new();
+    for (path, license) in license_files {
+        let Some(language) = language_of(path) else {
+            continue;
+        };
+        licensed.entry(language).or_default().insert(path);
+        *licenses
+            .entry(language)
+            .or_default()
+            .entry(license)
+            .or_default() += 1;
+    }
+
+    stats
+        .iter()
+        .map(|stat| {
+            // A scanner reporting a path the walk did not count (e.g. inside
+            // an archive) must not push coverage past 100%
+            let licensed_files = licensed
+                .get(stat.language.as_str())
+                .map_or(0, |files| files.len() as i64)
+                .min(stat.file_count);
+            let mut counts: Vec<LicenseFileCount> = licenses
+                .get(stat.language.as_str())
+                .into_iter()
+                .flatten()
+                .map(|(license, files)| LicenseFileCount {
+                    license: license.to_string(),
+                    file_count: *files,
+                })
+                .collect();
+            counts.sort_by(|a, b| b.file_count.cmp(&a.file_count).then(a.license.cmp(&b.license)));
+            let coverage_percent = if stat.file_count > 0 {
+                (licensed_files as f64 * 1000.0 / stat.file_count as f64).round() / 10.0
+            } else {
+                0.0
+            };
+
+            LanguageCoverage {
+                language: stat.language.clone(),
+                file_count: stat.file_count,
+                total_bytes: stat.total_bytes,
+                licensed_files,
+                coverage_percent,
+                licenses: counts,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(language: &str, file_count: i64) -> ScanLanguageStat {
+        ScanLanguageStat {
+            scan_id: "scan-1".to_string(),
+            language: language.to_string(),
+            file_count,
+            total_bytes: file_count * 100,
+        }
+    }
+
+    #[test]
+    fn test_language_of() {
+        assert_eq!(language_of("src/lib.rs"), Some("Rust"));
+        assert_eq!(language_of("web/App.TSX"), Some("TypeScript"));
+        assert_eq!(language_of("tools/.hidden/run.py"), Some("Python"));
+        assert_eq!(language_of("LICENSE"), None);
+        assert_eq!(language_of("README.md"), None);
+    }
+
+    #[test]
+    fn test_coverage_per_language() {
+        let stats = [stat("JavaScript", 3), stat("Python", 2)];
+        let files = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(p, l)| (p.to_string(), l.to_string()))
+                .collect()
+        };
+        let license_files = files(&[
+            ("web/a.js", "MIT"),
+            ("web/b.js", "MIT"),
+            ("web/b.js", "Apache-2.0"),
+            ("LICENSE", "MIT"),
+        ]);
+
+        let report = coverage(&stats, &license_files);
+        assert_eq!(report[0].language, "JavaScript");
+        assert_eq!(report[0].licensed_files, 2);
+        assert_eq!(report[0].coverage_percent, 66.7);
+        assert_eq!(report[0].licenses[0].license, "MIT");
+        assert_eq!(report[0].licenses[0].file_count, 2);
+        assert_eq!(report[1].language, "Python");
+        assert_eq!(report[1].licensed_files, 0);
+        assert_eq!(report[1].coverage_percent, 0.0);
+        assert!(report[1].licenses.is_empty());
+    }
+}
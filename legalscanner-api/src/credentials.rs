@@ -0,0 +1,236 @@
+//! Named git credentials (GitHub, GitLab, Bitbucket and Azure DevOps tokens,
+//! SSH keys)
+//! that scans reference by ID instead of carrying a raw token. Secrets are
+//! encrypted with `CREDENTIALS_ENCRYPTION_KEY`, bound to the credential's
+//! ID, and only decrypted to clone.
+
+use crate::api::models::CreateCredentialRequest;
+use crate::db::models::{Credential, Scan};
+use crate::error::AppError;
+use crate::git::GitAuth;
+use crate::utils::crypto::{decrypt_secret, encrypt_secret};
+use crate::AppState;
+use uuid::Uuid;
+
+pub const PROVIDERS: [&str; 5] = ["github", "gitlab", "bitbucket", "azure_devops", "ssh_key"];
+
+/// Validate, encrypt and store a new credential
+pub async fn register(
+    state: &AppState,
+    request: CreateCredentialRequest,
+) -> Result<Credential, AppError> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation(
+            "Credential name cannot be empty".to_string(),
+        ));
+    }
+    let provider = request.provider.trim().to_lowercase();
+    if !PROVIDERS.contains(&provider.as_str()) {
+        return Err(AppError::Validation(format!(
+            "Invalid provider '{}'. Must be one of {}",
+            request.provider,
+            PROVIDERS.join(", ")
+        )));
+    }
+    if request.secret.trim().is_empty() {
+        return Err(AppError::Validation(
+            "Credential secret cannot be empty".to_string(),
+        ));
+    }
+    if provider == "ssh_key" {
+        if !request.secret.contains("PRIVATE KEY-----") {
+            return Err(AppError::Validation(
+                "An ssh_key secret must be a private key in OpenSSH or PEM format".to_string(),
+            ));
+        }
+    } else if request.passphrase.is_some() {
+        return Err(AppError::Validation(
+            "passphrase is only used with ssh_key credentials".to_string(),
+        ));
+    }
+
+    let Some(key) = state.config.credentials_encryption_key.as_deref() else {
+        return Err(AppError::Validation(
+            "Credential storage is not configured, set CREDENTIALS_ENCRYPTION_KEY".to_string(),
+        ));
+    };
+    if Credential::find_by_name(&state.db, name).await?.is_some() {
+        return Err(AppError::Conflict(format!(
+            "A credential named '{}' already exists",
+            name
+        )));
+    }
+
+    // Tokens are pasted with stray whitespace, keys need their final newline
+    let secret = if provider == "ssh_key" {
+        request.secret
+    } else {
+        request.secret.trim().to_string()
+    };
+    let id = Uuid::new_v4().to_string();
+    let secret_encrypted = encrypt_secret(key, &id, &secret).map_err(AppError::Internal)?;
+    let passphrase_encrypted = request
+        .passphrase
+        .filter(|p| !p.is_empty())
+        .map(|p| encrypt_secret(key, &id, &p))
+        .transpose()
+        .map_err(AppError::Internal)?;
+    let username = request
+        .username
+        .as_deref()
+        .map(str::trim)
+        .filter(|u| !u.is_empty());
+
+    let credential = Credential::create(
+        &state.db,
+        &id,
+        name,
+        &provider,
+        username,
+        &secret_encrypted,
+        passphrase_encrypted.as_deref(),
+    )
+    .await?;
+    tracing::info!("Credential {} ({}) registered", credential.name, provider);
+
+    Ok(credential)
+}
+
+/// Decrypted authentication of the credential `id`
+pub async fn resolve(state: &AppState, id: &str) -> Result<GitAuth, AppError> {
+    let credential = Credential::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Credential {} not found", id)))?;
+    let Some(key) = state.config.credentials_encryption_key.as_deref() else {
+        return Err(AppError::Unprocessable(format!(
+            "Credential {} cannot be used, CREDENTIALS_ENCRYPTION_KEY is not set",
+            credential.name
+        )));
+    };
+
+    let decrypt = |encrypted: &str| {
+        decrypt_secret(key, &credential.id, encrypted)
+            .map_err(|e| AppError::Unprocessable(format!("Credential {}: {}", credential.name, e)))
+    };
+    let secret = decrypt(&credential.secret_encrypted)?;
+    let passphrase = credential
+        .passphrase_encrypted
+        .as_deref()
+        .map(decrypt)
+        .transpose()?;
+
+    Credential::update_last_used(&state.db, &credential.id).await?;
+
+    Ok(git_auth(
+        &credential.provider,
+        credential.username.clone(),
+        secret,
+        passphrase,
+    ))
+}
+
+/// Authentication of a scan: its credential, else its raw token, signing in
+/// the way the repository's provider expects
+pub async fn scan_auth(state: &AppState, scan: &Scan) -> Result<Option<GitAuth>, AppError> {
+    match &scan.credential_id {
+        Some(id) => Ok(Some(resolve(state, id).await?)),
+        None => Ok(scan
+            .git_token
+            .clone()
+            .map(|token| GitAuth::for_token(&scan.git_url, token))),
+    }
+}
+
+/// Encrypt the submodule token of scan `scan_id`, bound to its ID
+pub fn seal_submodule_token(
+    state: &AppState,
+    scan_id: &str,
+    token: &str,
+) -> Result<String, AppError> {
+    let Some(key) = state.config.credentials_encryption_key.as_deref() else {
+        return Err(AppError::Validation(
+            "submodule_token is stored encrypted, set CREDENTIALS_ENCRYPTION_KEY".to_string(),
+        ));
+    };
+    encrypt_secret(key, scan_id, token).map_err(AppError::Internal)
+}
+
+/// Decrypted submodule token of a scan, if it has one
+pub fn submodule_token(state: &AppState, scan: &Scan) -> Result<Option<String>, AppError> {
+    let Some(encrypted) = scan.submodule_token_encrypted.as_deref() else {
+        return Ok(None);
+    };
+    let Some(key) = state.config.credentials_encryption_key.as_deref() else {
+        return Err(AppError::Unprocessable(format!(
+            "Submodule token of scan {} cannot be used, CREDENTIALS_ENCRYPTION_KEY is not set",
+            scan.id
+        )));
+    };
+    decrypt_secret(key, &scan.id, encrypted)
+        .map(Some)
+        .map_err(|e| AppError::Unprocessable(format!("Submodule token of scan {}: {}", scan.id, e)))
+}
+
+/// How a credential of `provider` signs in. GitLab, Bitbucket and Azure
+/// DevOps expect the token as the password, with any user name; GitHub also
+/// takes it as the user name.
+fn git_auth(
+    provider: &str,
+    username: Option<String>,
+    secret: String,
+    passphrase: Option<String>,
+) -> GitAuth {
+    let user_pass = |default: &str| GitAuth::UserPass {
+        username: username.clone().unwrap_or_else(|| default.to_string()),
+        password: secret.clone(),
+    };
+    match provider {
+        "gitlab" => user_pass("oauth2"),
+        "bitbucket" => user_pass("x-token-auth"),
+        "azure_devops" => user_pass("pat"),
+        "ssh_key" => GitAuth::SshKey {
+            username,
+            private_key: secret,
+            passphrase,
+        },
+        _ if username.is_some() => user_pass("x-access-token"),
+        _ => GitAuth::Token(secret),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_auth_per_provider() {
+        let token = || "secret".to_string();
+        assert_eq!(
+            git_auth("github", None, token(), None),
+            GitAuth::Token(token())
+        );
+        assert_eq!(
+            git_auth("gitlab", None, token(), None),
+            GitAuth::UserPass {
+                username: "oauth2".to_string(),
+                password: token()
+            }
+        );
+        assert_eq!(
+            git_auth("azure_devops", Some("build".to_string()), token(), None),
+            GitAuth::UserPass {
+                username: "build".to_string(),
+                password: token()
+            }
+        );
+        assert!(matches!(
+            git_auth("ssh_key", None, token(), Some("pw".to_string())),
+            GitAuth::SshKey {
+                username: None,
+                passphrase: Some(_),
+                ..
+            }
+        ));
+    }
+}
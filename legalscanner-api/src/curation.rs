@@ -0,0 +1,372 @@
+//! License curations: reviewed conclusions that replace what the scanners
+//! report for a file. Organizations that clear licenses by hand in
+//! Fossology's UI import those clearing decisions so both tools agree, and
+//! export curations made elsewhere back to Fossology as clearing decisions.
+//! Curations apply to every later scan of the repository before its results
+//! are stored, so risk scores, the gate and exports all see them.
+
+use crate::config::Config;
+use crate::db::models::{LicenseCuration, Scan};
+use crate::error::AppError;
+use crate::export::sarif::repository_path;
+use crate::scan_diff::normalize_repository_url;
+use crate::scanner::fossology::{map_to_spdx, FossologyClient};
+use crate::scanner::{LicenseCategory, LicenseFinding, ScanResult};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+pub const FOSSOLOGY_SOURCE: &str = "fossology";
+
+/// Fossology's conclusion for files cleared as having no relevant license,
+/// e.g. files a reviewer marked irrelevant
+const VOID_LICENSE: &str = "Void";
+
+/// Upload containers Fossology lists as leading path components
+const ARCHIVE_SUFFIXES: [&str; 7] = [
+    ".tar.gz", ".tgz", ".tar.bz2", ".tar.xz", ".tar", ".zip", ".gz",
+];
+
+/// What an import of Fossology clearing decisions stored
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct CurationImportReport {
+    /// Normalized repository URL the curations apply to
+    pub repository: String,
+    pub upload_id: i32,
+    /// Files with concluded licenses
+    pub concluded: u64,
+    /// Files cleared as irrelevant
+    pub irrelevant: u64,
+    /// Decisions on files outside `path_prefix`
+    pub skipped: u64,
+}
+
+/// What an export of curations to a Fossology upload recorded there
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct CurationExportReport {
+    /// Normalized repository URL the curations apply to
+    pub repository: String,
+    pub upload_id: i32,
+    /// Files given an `Identified` decision with the concluded licenses
+    pub concluded: u64,
+    /// Files given an `Irrelevant` decision
+    pub irrelevant: u64,
+    /// Curations imported from this upload, which it already records
+    pub unchanged: u64,
+    /// Curated files the upload does not contain
+    pub unmatched: u64,
+}
+
+/// Repository-relative path of a file in a Fossology upload. Leading archive
+/// containers are dropped, as is the workspace directory of uploads made by
+/// LegalScanner itself. With `path_prefix` (the repository's directory in a
+/// hand-made upload), files outside it yield `None`.
+pub fn fossology_path(path: &str, path_prefix: Option<&str>) -> Option<String> {
+    let mut components = path
+        .split('/')
+        .filter(|c| !c.is_empty())
+        .skip_while(|c| ARCHIVE_SUFFIXES.iter().any(|s| c.ends_with(s)))
+        .peekable();
+    if components
+        .peek()
+        .is_some_and(|c| uuid::Uuid::parse_str(c).is_ok())
+    {
+        components.next();
+    }
+    let path = components.collect::<Vec<_>>().join("/");
+
+    let path = match path_prefix
+        .map(|p| p.trim_matches('/'))
+        .filter(|p| !p.is_empty())
+    {
+        Some(prefix) => path.strip_prefix(prefix)?.strip_prefix('/')?.to_string(),
+        None => path,
+    };
+    (!path.is_empty()).then_some(path)
+}
+
+/// Import the clearing decisions of a Fossology upload as curations of
+/// `git_url`, replacing earlier curations of the same files
+pub async fn import_fossology(
+    pool: &SqlitePool,
+    config: &Config,
+    git_url: &str,
+    upload_id: i32,
+    path_prefix: Option<&str>,
+) -> Result<CurationImportReport, AppError> {
+    let client = FossologyClient::new(
+        config.fossology_url.clone(),
+        config.fossology_api_token.clone(),
+    );
+    let decisions = client
+        .get_clearing_decisions(upload_id)
+        .await
+        .map_err(|e| AppError::Scanner(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Fossology upload {} not found", upload_id)))?;
+
+    let mut report = CurationImportReport {
+        repository: normalize_repository_url(git_url),
+        upload_id,
+        ..Default::default()
+    };
+    let reference = upload_id.to_string();
+
+    for decision in decisions {
+        let Some(file_path) = fossology_path(&decision.file_path, path_prefix) else {
+            report.skipped += 1;
+            continue;
+        };
+        let irrelevant = decision.concluded.iter().all(|l| l == VOID_LICENSE);
+        let concluded: Vec<String> = decision
+            .concluded
+            .into_iter()
+            .filter(|l| l != VOID_LICENSE)
+            .collect();
+
+        LicenseCuration::upsert(
+            pool,
+            &report.repository,
+            &file_path,
+            &concluded,
+            irrelevant,
+            FOSSOLOGY_SOURCE,
+            Some(&reference),
+        )
+        .await?;
+        if irrelevant {
+            report.irrelevant += 1;
+        } else {
+            report.concluded += 1;
+        }
+    }
+
+    tracing::info!(
+        "Imported {} concluded and {} irrelevant files from Fossology upload {}",
+        report.concluded,
+        report.irrelevant,
+        upload_id
+    );
+    Ok(report)
+}
+
+/// Record the curations of `git_url` as clearing decisions on the files of a
+/// Fossology upload, so reviewers working in Fossology see them
+pub async fn export_fossology(
+    pool: &SqlitePool,
+    config: &Config,
+    git_url: &str,
+    upload_id: i32,
+    path_prefix: Option<&str>,
+) -> Result<CurationExportReport, AppError> {
+    let mut report = CurationExportReport {
+        repository: normalize_repository_url(git_url),
+        upload_id,
+        ..Default::default()
+    };
+    let curations = LicenseCuration::find_by_repository(pool, &report.repository).await?;
+
+    let client = FossologyClient::new(
+        config.fossology_url.clone(),
+        config.fossology_api_token.clone(),
+    );
+    let items = client
+        .get_upload_items(upload_id)
+        .await
+        .map_err(|e| AppError::Scanner(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Fossology upload {} not found", upload_id)))?;
+    let items: HashMap<String, i64> = items
+        .into_iter()
+        .filter_map(|item| Some((fossology_path(&item.file_path, path_prefix)?, item.item_id)))
+        .collect();
+    let reference = upload_id.to_string();
+
+    for curation in &curations {
+        if curation.source == FOSSOLOGY_SOURCE
+            && curation.source_reference.as_deref() == Some(reference.as_str())
+        {
+            report.unchanged += 1;
+            continue;
+        }
+        let Some(&item_id) = items.get(&curation.file_path) else {
+            report.unmatched += 1;
+            continue;
+        };
+
+        client
+            .set_clearing_decision(
+                upload_id,
+                item_id,
+                &curation.concluded_license_list(),
+                curation.irrelevant,
+            )
+            .await
+            .map_err(|e| AppError::Scanner(e.to_string()))?;
+        if curation.irrelevant {
+            report.irrelevant += 1;
+        } else {
+            report.concluded += 1;
+        }
+    }
+
+    tracing::info!(
+        "Exported {} concluded and {} irrelevant files to Fossology upload {}",
+        report.concluded,
+        report.irrelevant,
+        upload_id
+    );
+    Ok(report)
+}
+
+/// Replace the license findings of curated files: irrelevant files lose
+/// them, other files get the concluded licenses. Returns how many files were
+/// curated.
+pub async fn apply(
+    pool: &SqlitePool,
+    scan: &Scan,
+    results: &mut [ScanResult],
+) -> Result<usize, sqlx::Error> {
+    let curations =
+        LicenseCuration::find_by_repository(pool, &normalize_repository_url(&scan.git_url)).await?;
+    Ok(apply_curations(
+        &curations,
+        &scan.id,
+        scan.scan_path.as_deref(),
+        results,
+    ))
+}
+
+fn apply_curations(
+    curations: &[LicenseCuration],
+    scan_id: &str,
+    scan_path: Option<&str>,
+    results: &mut [ScanResult],
+) -> usize {
+    if curations.is_empty() {
+        return 0;
+    }
+    let by_path: HashMap<&str, &LicenseCuration> = curations
+        .iter()
+        .map(|c| (c.file_path.as_str(), c))
+        .collect();
+
+    let mut applied = 0;
+    for result in results.iter_mut() {
+        let path = repository_path(&result.file_path, scan_id, scan_path);
+        let Some(curation) = by_path.get(path.as_str()) else {
+            continue;
+        };
+
+        let category = result
+            .licenses
+            .first()
+            .map(|l| l.category)
+            .unwrap_or(LicenseCategory::Code);
+        let provenance = format!("curation:{}", curation.source);
+        result.licenses = curation
+            .concluded_license_list()
+            .into_iter()
+            .map(|name| LicenseFinding {
+                spdx_id: map_to_spdx(&name),
+                name,
+                confidence: 1.0,
+                category,
+                provenance: Some(provenance.clone()),
+                agreement: None,
+                exception: None,
+            })
+            .collect();
+        applied += 1;
+    }
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fossology_path() {
+        let scan_id = "0b6c3c0e-8f1a-4c36-9d7e-3f1f7f6f2a11";
+        assert_eq!(
+            fossology_path(
+                &format!("app@main (c04f4ed).tar.gz/app.tar/{}/src/lib.rs", scan_id),
+                None
+            ),
+            Some("src/lib.rs".to_string())
+        );
+        assert_eq!(
+            fossology_path("release.zip/project-1.0/src/lib.rs", Some("project-1.0/")),
+            Some("src/lib.rs".to_string())
+        );
+        assert_eq!(
+            fossology_path("release.zip/other/src/lib.rs", Some("project-1.0")),
+            None
+        );
+        assert_eq!(fossology_path("release.zip", None), None);
+    }
+
+    fn curation(file_path: &str, concluded: Option<&str>) -> LicenseCuration {
+        LicenseCuration {
+            id: file_path.to_string(),
+            repository: "https://github.com/acme/app".to_string(),
+            file_path: file_path.to_string(),
+            concluded_licenses: concluded.map(|l| format!("[\"{}\"]", l)),
+            irrelevant: concluded.is_none(),
+            source: FOSSOLOGY_SOURCE.to_string(),
+            source_reference: Some("42".to_string()),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn licensed(file_path: &str, name: &str) -> ScanResult {
+        ScanResult {
+            file_path: file_path.to_string(),
+            licenses: vec![LicenseFinding {
+                name: name.to_string(),
+                spdx_id: None,
+                confidence: 0.6,
+                category: LicenseCategory::Documentation,
+                provenance: Some("union:fossology".to_string()),
+                agreement: None,
+                exception: None,
+            }],
+            copyrights: Vec::new(),
+            ecc_findings: Vec::new(),
+            malware_findings: Vec::new(),
+            package_findings: Vec::new(),
+            model_licenses: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_apply_curations() {
+        let mut results = vec![
+            licensed("/work/scan-1/src/lib.rs", "GPL-2.0"),
+            licensed("/work/scan-1/vendor/blob.bin", "Unknown"),
+            licensed("/work/scan-1/README.md", "MIT"),
+        ];
+        let curations = vec![
+            curation("services/api/src/lib.rs", Some("MIT")),
+            curation("services/api/vendor/blob.bin", None),
+        ];
+
+        // Scan limited to services/api
+        let applied = apply_curations(&curations, "scan-1", Some("services/api"), &mut results);
+        assert_eq!(applied, 2);
+        assert_eq!(results[0].licenses[0].name, "MIT");
+        assert_eq!(results[0].licenses[0].spdx_id.as_deref(), Some("MIT"));
+        assert_eq!(
+            results[0].licenses[0].category,
+            LicenseCategory::Documentation
+        );
+        assert_eq!(
+            results[0].licenses[0].provenance.as_deref(),
+            Some("curation:fossology")
+        );
+        assert!(results[1].licenses.is_empty());
+        assert_eq!(results[2].licenses[0].name, "MIT");
+        assert_eq!(results[2].licenses[0].confidence, 0.6);
+    }
+}
@@ -0,0 +1,295 @@
+//! Custom fields: metadata a deployment attaches to its scans, such as an
+//! export classification, product line or contract ID. Fields are defined
+//! through the API with a type and optional validation; values are set when
+//! a scan is created or later, and shown in scan details and reports.
+
+use crate::db::models::{CustomField, ScanCustomFieldValue};
+use crate::error::AppError;
+use regex::Regex;
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+pub const FIELD_TYPES: [&str; 5] = ["string", "number", "boolean", "date", "enum"];
+
+const MAX_KEY_LENGTH: usize = 64;
+
+/// Check a field definition before it is stored
+pub fn validate_definition(
+    key: &str,
+    field_type: &str,
+    options: &[String],
+    pattern: Option<&str>,
+) -> Result<(), String> {
+    let valid_key = !key.is_empty()
+        && key.len() <= MAX_KEY_LENGTH
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid_key {
+        return Err(format!(
+            "Invalid field key '{}' (lowercase letters, digits and underscores, at most {} characters)",
+            key, MAX_KEY_LENGTH
+        ));
+    }
+
+    if !FIELD_TYPES.contains(&field_type) {
+        return Err(format!(
+            "Invalid field type '{}'. Must be one of {}",
+            field_type,
+            FIELD_TYPES.join(", ")
+        ));
+    }
+
+    match (field_type, options.is_empty()) {
+        ("enum", true) => return Err("enum fields need at least one option".to_string()),
+        ("enum", false) if options.iter().any(|o| o.trim().is_empty()) => {
+            return Err("enum options cannot be empty".to_string());
+        }
+        ("enum", false) => {}
+        (_, false) => return Err("options are only allowed on enum fields".to_string()),
+        _ => {}
+    }
+
+    if let Some(pattern) = pattern {
+        if field_type != "string" {
+            return Err("pattern is only allowed on string fields".to_string());
+        }
+        Regex::new(pattern).map_err(|e| format!("Invalid pattern: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Check a value against its field's definition and return the value to
+/// store
+pub fn validate_value(field: &CustomField, value: &Value) -> Result<Value, String> {
+    let invalid = |expected: &str| {
+        Err(format!(
+            "Custom field '{}' expects {}, got {}",
+            field.key, expected, value
+        ))
+    };
+
+    match field.field_type.as_str() {
+        "number" => match value {
+            Value::Number(_) => Ok(value.clone()),
+            _ => invalid("a number"),
+        },
+        "boolean" => match value {
+            Value::Bool(_) => Ok(value.clone()),
+            _ => invalid("true or false"),
+        },
+        "date" => match value.as_str().map(str::trim) {
+            Some(date) if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() => {
+                Ok(Value::String(date.to_string()))
+            }
+            _ => invalid("a date (YYYY-MM-DD)"),
+        },
+        "enum" => {
+            let options = field.option_list();
+            match value.as_str().map(str::trim) {
+                Some(option) if options.iter().any(|o| o == option) => {
+                    Ok(Value::String(option.to_string()))
+                }
+                _ => invalid(&format!("one of {}", options.join(", "))),
+            }
+        }
+        _ => match value.as_str().map(str::trim) {
+            Some(text) if !text.is_empty() => {
+                if let Some(pattern) = &field.pattern {
+                    let matches = Regex::new(pattern)
+                        .map(|re| re.is_match(text))
+                        .unwrap_or(false);
+                    if !matches {
+                        return Err(format!(
+                            "Custom field '{}' must match {}",
+                            field.key, pattern
+                        ));
+                    }
+                }
+                Ok(Value::String(text.to_string()))
+            }
+            _ => invalid("a non-empty string"),
+        },
+    }
+}
+
+/// Validate values sent for a scan. `null` removes a value. A new scan must
+/// get every required field; required fields defined after a scan was
+/// created are not enforced on it, but cannot be removed once set.
+pub fn validate_values(
+    fields: &[CustomField],
+    values: &Map<String, Value>,
+    new_scan: bool,
+) -> Result<Vec<(String, Option<Value>)>, String> {
+    let mut validated = Vec::new();
+    for (key, value) in values {
+        let field = fields
+            .iter()
+            .find(|f| &f.key == key)
+            .ok_or_else(|| format!("Unknown custom field '{}'", key))?;
+
+        if value.is_null() {
+            if field.required {
+                return Err(format!("Custom field '{}' is required", key));
+            }
+            validated.push((key.clone(), None));
+        } else {
+            validated.push((key.clone(), Some(validate_value(field, value)?)));
+        }
+    }
+
+    if new_scan {
+        let missing: Vec<&str> = fields
+            .iter()
+            .filter(|f| f.required)
+            .filter(|f| {
+                !validated
+                    .iter()
+                    .any(|(key, v)| key == &f.key && v.is_some())
+            })
+            .map(|f| f.key.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "Missing required custom fields: {}",
+                missing.join(", ")
+            ));
+        }
+    }
+
+    Ok(validated)
+}
+
+/// Store validated values on a scan
+pub async fn apply(
+    pool: &SqlitePool,
+    scan_id: &str,
+    values: &[(String, Option<Value>)],
+) -> Result<(), AppError> {
+    for (key, value) in values {
+        match value {
+            Some(value) => ScanCustomFieldValue::set(pool, scan_id, key, value).await?,
+            None => ScanCustomFieldValue::delete(pool, scan_id, key).await?,
+        }
+    }
+    Ok(())
+}
+
+/// Values set on a scan, by field key
+pub async fn load(pool: &SqlitePool, scan_id: &str) -> Result<BTreeMap<String, Value>, AppError> {
+    Ok(ScanCustomFieldValue::find_by_scan_id(pool, scan_id)
+        .await?
+        .into_iter()
+        .filter_map(|v| Some((v.field_key, serde_json::from_str(&v.value).ok()?)))
+        .collect())
+}
+
+/// Label and display text of each field set on a scan, in key order, for
+/// reports
+pub async fn report_fields(
+    pool: &SqlitePool,
+    scan_id: &str,
+) -> Result<Vec<(String, String)>, AppError> {
+    let values = load(pool, scan_id).await?;
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let fields = CustomField::list(pool).await?;
+    Ok(fields
+        .iter()
+        .filter_map(|field| {
+            let value = values.get(&field.key)?;
+            Some((field.label.clone(), display(value)))
+        })
+        .collect())
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Bool(true) => "yes".to_string(),
+        Value::Bool(false) => "no".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field(key: &str, field_type: &str, required: bool) -> CustomField {
+        CustomField {
+            key: key.to_string(),
+            label: key.to_string(),
+            field_type: field_type.to_string(),
+            options: (field_type == "enum").then(|| r#"["EAR99","5D002"]"#.to_string()),
+            pattern: None,
+            required,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_definition() {
+        assert!(validate_definition("contract_id", "string", &[], Some("^C-[0-9]+$")).is_ok());
+        assert!(validate_definition("Contract", "string", &[], None).is_err());
+        assert!(validate_definition("eccn", "enum", &[], None).is_err());
+        assert!(validate_definition("eccn", "enum", &["EAR99".to_string()], None).is_ok());
+        assert!(validate_definition("budget", "number", &["1".to_string()], None).is_err());
+        assert!(validate_definition("budget", "number", &[], Some("[0-9]")).is_err());
+        assert!(validate_definition("contract_id", "string", &[], Some("(")).is_err());
+        assert!(validate_definition("unit", "text", &[], None).is_err());
+    }
+
+    #[test]
+    fn test_validate_value() {
+        let mut contract = field("contract_id", "string", false);
+        contract.pattern = Some("^C-[0-9]+$".to_string());
+        assert_eq!(
+            validate_value(&contract, &json!(" C-42 ")).unwrap(),
+            json!("C-42")
+        );
+        assert!(validate_value(&contract, &json!("42")).is_err());
+
+        let eccn = field("eccn", "enum", false);
+        assert!(validate_value(&eccn, &json!("EAR99")).is_ok());
+        assert!(validate_value(&eccn, &json!("3A001")).is_err());
+
+        assert!(validate_value(&field("due", "date", false), &json!("2025-02-30")).is_err());
+        assert!(validate_value(&field("due", "date", false), &json!("2025-02-28")).is_ok());
+        assert!(validate_value(&field("budget", "number", false), &json!("12")).is_err());
+        assert!(validate_value(&field("public", "boolean", false), &json!(true)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_values_enforces_required_fields() {
+        let fields = vec![field("eccn", "enum", true), field("line", "string", false)];
+        let values = |v: Value| v.as_object().unwrap().clone();
+
+        let err = validate_values(&fields, &values(json!({ "line": "Cloud" })), true).unwrap_err();
+        assert_eq!(err, "Missing required custom fields: eccn");
+        // Existing scans may predate the field
+        assert!(validate_values(&fields, &values(json!({ "line": "Cloud" })), false).is_ok());
+        assert!(validate_values(&fields, &values(json!({ "eccn": null })), false).is_err());
+        assert!(validate_values(&fields, &values(json!({ "other": 1 })), false).is_err());
+
+        let validated = validate_values(
+            &fields,
+            &values(json!({ "eccn": "EAR99", "line": null })),
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            validated,
+            vec![
+                ("eccn".to_string(), Some(json!("EAR99"))),
+                ("line".to_string(), None)
+            ]
+        );
+    }
+}
@@ -1,9 +1,52 @@
 pub mod models;
 
-use sqlx::{sqlite::{SqlitePoolOptions, SqliteConnectOptions}, SqlitePool};
+use crate::config::Config;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
+    SqlitePool,
+};
 use std::str::FromStr;
+use std::time::Duration;
 
-pub async fn create_pool(database_url: &str) -> Result<SqlitePool, sqlx::Error> {
+/// Pool size and the SQLite pragmas set on every connection
+#[derive(Debug, Clone, Copy)]
+pub struct PoolSettings {
+    pub max_connections: u32,
+    /// How long a statement waits for a lock held by another connection
+    /// before failing with `database is locked`
+    pub busy_timeout: Duration,
+    /// Write-ahead logging, so readers don't block the writer and vice versa
+    pub wal: bool,
+    pub synchronous: SqliteSynchronous,
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        PoolSettings {
+            max_connections: 10,
+            busy_timeout: Duration::from_secs(5),
+            wal: true,
+            // Durable with WAL, short of a power loss
+            synchronous: SqliteSynchronous::Normal,
+        }
+    }
+}
+
+impl PoolSettings {
+    pub fn from_config(config: &Config) -> Self {
+        PoolSettings {
+            max_connections: config.database_max_connections,
+            busy_timeout: Duration::from_millis(config.database_busy_timeout_ms),
+            wal: config.database_wal,
+            synchronous: config.database_synchronous,
+        }
+    }
+}
+
+pub async fn create_pool(
+    database_url: &str,
+    settings: &PoolSettings,
+) -> Result<SqlitePool, sqlx::Error> {
     // Extract file path from SQLite URL if needed
     let file_path = if database_url.starts_with("sqlite://") {
         database_url.strip_prefix("sqlite://").unwrap()
@@ -16,12 +59,23 @@ pub async fn create_pool(database_url: &str) -> Result<SqlitePool, sqlx::Error>
         std::fs::create_dir_all(parent).ok();
     }
 
+    // The journal mode is stored in the database file, so turning WAL off
+    // switches an existing database back to rollback journaling
+    let journal_mode = if settings.wal {
+        SqliteJournalMode::Wal
+    } else {
+        SqliteJournalMode::Delete
+    };
+
     // Create connect options with create_if_missing enabled
     let connect_options = SqliteConnectOptions::from_str(database_url)?
-        .create_if_missing(true);
+        .create_if_missing(true)
+        .busy_timeout(settings.busy_timeout)
+        .journal_mode(journal_mode)
+        .synchronous(settings.synchronous);
 
     SqlitePoolOptions::new()
-        .max_connections(5)
+        .max_connections(settings.max_connections)
         .connect_with(connect_options)
         .await
 }
@@ -30,5 +84,61 @@ pub async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     sqlx::migrate!("./migrations")
         .run(pool)
         .await
-        .map_err(|e| sqlx::Error::Migrate(Box::new(e)))
+        .map_err(|e| sqlx::Error::Migrate(Box::new(e)))?;
+    // Result partitions are created at runtime, migrations only know
+    // scan_results
+    models::result_partition::refresh(pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::Connection;
+
+    #[tokio::test]
+    async fn test_create_pool_applies_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}", dir.path().join("tuned.db").display());
+        let settings = PoolSettings {
+            max_connections: 3,
+            busy_timeout: Duration::from_millis(1500),
+            ..PoolSettings::default()
+        };
+        let pool = create_pool(&url, &settings).await.unwrap();
+        assert_eq!(pool.options().get_max_connections(), 3);
+        // A detached connection is closed before the pool is reopened;
+        // leaving WAL fails while another connection is open
+        let mut conn = pool.acquire().await.unwrap().detach();
+        pool.close().await;
+
+        let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+        let busy_timeout: i64 = sqlx::query_scalar("PRAGMA busy_timeout")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(busy_timeout, 1500);
+        // 0 OFF, 1 NORMAL, 2 FULL
+        let synchronous: i64 = sqlx::query_scalar("PRAGMA synchronous")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(synchronous, 1);
+        conn.close().await.unwrap();
+
+        // Rollback journaling again once WAL is turned off
+        let settings = PoolSettings {
+            wal: false,
+            ..settings
+        };
+        let pool = create_pool(&url, &settings).await.unwrap();
+        let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(journal_mode, "delete");
+    }
 }
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+/// Audit log entry of a scan or API key being created, deleted, restored
+/// or re-run
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    pub action: String,      // create, delete, delete_all, restore, rerun, import
+    pub target_type: String, // scan, api_key
+    /// `None` for actions on many targets, e.g. `delete_all`
+    pub target_id: Option<String>,
+    pub actor: String, // api_key, admin, anonymous
+    /// API key the request was made with, if `actor` is `api_key`
+    pub api_key_id: Option<String>,
+    pub detail: Option<String>,
+    pub occurred_at: String,
+}
+
+/// Filter for [`AuditLogEntry::list`], unset fields match everything
+#[derive(Debug, Default)]
+pub struct AuditLogFilter<'a> {
+    pub target_id: Option<&'a str>,
+    pub api_key_id: Option<&'a str>,
+    pub action: Option<&'a str>,
+    /// Only entries recorded at or after this time
+    pub since: Option<&'a str>,
+}
+
+impl AuditLogEntry {
+    pub async fn record(
+        pool: &SqlitePool,
+        action: &str,
+        target_type: &str,
+        target_id: Option<&str>,
+        actor: &str,
+        api_key_id: Option<&str>,
+        detail: Option<&str>,
+    ) -> Result<AuditLogEntry, sqlx::Error> {
+        sqlx::query_as::<_, AuditLogEntry>(
+            r#"
+            INSERT INTO audit_log (action, target_type, target_id, actor, api_key_id, detail)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(action)
+        .bind(target_type)
+        .bind(target_id)
+        .bind(actor)
+        .bind(api_key_id)
+        .bind(detail)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Entries matching `filter` in the order they were recorded
+    pub async fn list(
+        pool: &SqlitePool,
+        filter: &AuditLogFilter<'_>,
+    ) -> Result<Vec<AuditLogEntry>, sqlx::Error> {
+        sqlx::query_as::<_, AuditLogEntry>(
+            r#"
+            SELECT * FROM audit_log
+            WHERE (?1 IS NULL OR target_id = ?1)
+              AND (?2 IS NULL OR api_key_id = ?2)
+              AND (?3 IS NULL OR action = ?3)
+              AND (?4 IS NULL OR occurred_at >= ?4)
+            ORDER BY id
+            "#,
+        )
+        .bind(filter.target_id)
+        .bind(filter.api_key_id)
+        .bind(filter.action)
+        .bind(filter.since)
+        .fetch_all(pool)
+        .await
+    }
+}
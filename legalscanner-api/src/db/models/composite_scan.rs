@@ -0,0 +1,83 @@
+use crate::db::models::Scan;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// Scan of every repository of a project, tracked as one scan of the product
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CompositeScan {
+    pub id: String,
+    pub project_id: String,
+    pub created_at: String,
+}
+
+impl CompositeScan {
+    /// Create a composite scan made of already created member scans
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: &str,
+        scan_ids: &[String],
+    ) -> Result<CompositeScan, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        let mut tx = pool.begin().await?;
+
+        let composite = sqlx::query_as::<_, CompositeScan>(
+            "INSERT INTO composite_scans (id, project_id) VALUES (?, ?) RETURNING *",
+        )
+        .bind(&id)
+        .bind(project_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for scan_id in scan_ids {
+            sqlx::query(
+                "INSERT OR IGNORE INTO composite_scan_members (composite_scan_id, scan_id) VALUES (?, ?)",
+            )
+            .bind(&id)
+            .bind(scan_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(composite)
+    }
+
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        id: &str,
+    ) -> Result<Option<CompositeScan>, sqlx::Error> {
+        sqlx::query_as::<_, CompositeScan>("SELECT * FROM composite_scans WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Composite scans of a project, newest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: &str,
+    ) -> Result<Vec<CompositeScan>, sqlx::Error> {
+        sqlx::query_as::<_, CompositeScan>(
+            "SELECT * FROM composite_scans WHERE project_id = ? ORDER BY created_at DESC, rowid DESC",
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Member scans still stored, by repository URL
+    pub async fn scans(pool: &SqlitePool, id: &str) -> Result<Vec<Scan>, sqlx::Error> {
+        sqlx::query_as::<_, Scan>(
+            r#"
+            SELECT s.* FROM scans s
+            JOIN composite_scan_members m ON m.scan_id = s.id
+            WHERE m.composite_scan_id = ? AND s.deleted_at IS NULL
+            ORDER BY s.git_url
+            "#,
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await
+    }
+}
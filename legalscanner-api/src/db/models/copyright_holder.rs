@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool};
+use utoipa::ToSchema;
+
+/// A copyright holder seen in at least one scan
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CopyrightHolder {
+    pub id: i64,
+    pub normalized_name: String,
+    pub display_name: String,
+    pub first_seen_at: String,
+    pub last_seen_at: String,
+    /// Distinct repositories among the scans still on record
+    pub project_count: i64,
+    pub scan_count: i64,
+}
+
+/// A repository in which a holder appeared
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CopyrightHolderProject {
+    pub git_url: String,
+    pub scan_count: i64,
+    pub last_scan_id: String,
+    pub last_seen_at: String,
+    /// Files whose copyright notices name the holder in the latest scan
+    pub file_count: i64,
+    /// Files the holder authored commits to in the latest scan, if it
+    /// attributed files to their commit authors
+    pub commit_file_count: i64,
+}
+
+const HOLDER_COLUMNS: &str = r#"
+    SELECT
+        h.id, h.normalized_name, h.display_name, h.first_seen_at, h.last_seen_at,
+        COUNT(DISTINCT s.git_url) as project_count,
+        COUNT(s.scan_id) as scan_count
+    FROM copyright_holders h
+    LEFT JOIN copyright_holder_scans s ON s.holder_id = h.id
+"#;
+
+impl CopyrightHolder {
+    /// Register a holder's appearance in a scan. Re-recording the same scan
+    /// replaces its file counts; `seen_at` widens the first/last seen range.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record(
+        pool: &SqlitePool,
+        normalized_name: &str,
+        display_name: &str,
+        scan_id: &str,
+        git_url: &str,
+        file_count: i64,
+        commit_file_count: i64,
+        seen_at: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let holder_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO copyright_holders (normalized_name, display_name, first_seen_at, last_seen_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(normalized_name) DO UPDATE SET
+                first_seen_at = min(first_seen_at, excluded.first_seen_at),
+                last_seen_at = max(last_seen_at, excluded.last_seen_at)
+            RETURNING id
+            "#,
+        )
+        .bind(normalized_name)
+        .bind(display_name)
+        .bind(seen_at)
+        .bind(seen_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO copyright_holder_scans
+                (holder_id, scan_id, git_url, file_count, commit_file_count, seen_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(holder_id, scan_id) DO UPDATE SET
+                file_count = excluded.file_count,
+                commit_file_count = excluded.commit_file_count,
+                seen_at = excluded.seen_at
+            "#,
+        )
+        .bind(holder_id)
+        .bind(scan_id)
+        .bind(git_url)
+        .bind(file_count)
+        .bind(commit_file_count)
+        .bind(seen_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// Holders whose normalized name contains `query` (already normalized),
+    /// most widespread first
+    pub async fn search(
+        pool: &SqlitePool,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<CopyrightHolder>, sqlx::Error> {
+        sqlx::query_as::<_, CopyrightHolder>(&format!(
+            r#"
+            {}
+            WHERE instr(h.normalized_name, ?) > 0
+            GROUP BY h.id
+            ORDER BY project_count DESC, h.display_name
+            LIMIT ?
+            "#,
+            HOLDER_COLUMNS
+        ))
+        .bind(query)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<Option<CopyrightHolder>, sqlx::Error> {
+        sqlx::query_as::<_, CopyrightHolder>(&format!(
+            "{} WHERE h.id = ? GROUP BY h.id",
+            HOLDER_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Repositories the holder appeared in, most recently seen first
+    pub async fn projects(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<Vec<CopyrightHolderProject>, sqlx::Error> {
+        sqlx::query_as::<_, CopyrightHolderProject>(
+            r#"
+            SELECT
+                s.git_url,
+                COUNT(*) as scan_count,
+                MAX(s.seen_at) as last_seen_at,
+                (SELECT l.scan_id FROM copyright_holder_scans l
+                 WHERE l.holder_id = s.holder_id AND l.git_url = s.git_url
+                 ORDER BY l.seen_at DESC LIMIT 1) as last_scan_id,
+                (SELECT l.file_count FROM copyright_holder_scans l
+                 WHERE l.holder_id = s.holder_id AND l.git_url = s.git_url
+                 ORDER BY l.seen_at DESC LIMIT 1) as file_count,
+                (SELECT l.commit_file_count FROM copyright_holder_scans l
+                 WHERE l.holder_id = s.holder_id AND l.git_url = s.git_url
+                 ORDER BY l.seen_at DESC LIMIT 1) as commit_file_count
+            FROM copyright_holder_scans s
+            WHERE s.holder_id = ?
+            GROUP BY s.git_url
+            ORDER BY last_seen_at DESC
+            "#,
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Delete holders whose display name contains `text` (lowercase)
+    pub async fn delete_containing(pool: &SqlitePool, text: &str) -> Result<u64, sqlx::Error> {
+        let result =
+            sqlx::query("DELETE FROM copyright_holders WHERE instr(lower(display_name), ?) > 0")
+                .bind(text)
+                .execute(pool)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete holders registered under `normalized_name` only as commit
+    /// authors, without a copyright notice naming them
+    pub async fn delete_commit_author(
+        pool: &SqlitePool,
+        normalized_name: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM copyright_holders
+            WHERE normalized_name = ?
+              AND id NOT IN (SELECT holder_id FROM copyright_holder_scans WHERE file_count > 0)
+            "#,
+        )
+        .bind(normalized_name)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete holders that appeared in the given scans and no other scan
+    /// still on record
+    pub async fn delete_seen_only_in(
+        pool: &SqlitePool,
+        scan_ids: &[String],
+    ) -> Result<u64, sqlx::Error> {
+        if scan_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "DELETE FROM copyright_holders WHERE id IN \
+             (SELECT holder_id FROM copyright_holder_scans WHERE scan_id IN (",
+        );
+        push_ids(&mut query, scan_ids);
+        query.push(
+            ")) AND id NOT IN \
+             (SELECT holder_id FROM copyright_holder_scans WHERE scan_id NOT IN (",
+        );
+        push_ids(&mut query, scan_ids);
+        query.push("))");
+
+        Ok(query.build().execute(pool).await?.rows_affected())
+    }
+}
+
+fn push_ids<'a>(query: &mut QueryBuilder<'a, Sqlite>, ids: &'a [String]) {
+    let mut separated = query.separated(", ");
+    for id in ids {
+        separated.push_bind(id);
+    }
+}
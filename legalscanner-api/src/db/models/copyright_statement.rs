@@ -0,0 +1,78 @@
+use crate::db::models::license::INTERN_BATCH_ROWS;
+use serde::Serialize;
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqliteConnection};
+use std::collections::HashMap;
+
+/// A distinct copyright statement with the holders and years parsed from it
+/// (JSON arrays), referenced by `scan_results.copyright_statement_id`
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CopyrightStatement {
+    pub id: i64,
+    pub statement: String,
+    pub holders: Option<String>,
+    pub years: Option<String>,
+}
+
+/// Statement, holders and years of a copyright finding
+pub type CopyrightKey = (String, Option<String>, Option<String>);
+
+impl CopyrightStatement {
+    /// IDs of the statements, storing the ones not seen before
+    pub async fn intern(
+        conn: &mut SqliteConnection,
+        statements: &[(&str, Option<&str>, Option<&str>)],
+    ) -> Result<HashMap<CopyrightKey, i64>, sqlx::Error> {
+        let mut unique = statements.to_vec();
+        unique.sort_unstable();
+        unique.dedup();
+
+        let mut ids = HashMap::with_capacity(unique.len());
+        for batch in unique.chunks(INTERN_BATCH_ROWS) {
+            let mut sql = QueryBuilder::<Sqlite>::new(
+                "INSERT INTO copyright_statements (statement, holders, years) ",
+            );
+            sql.push_values(batch, |mut values, (statement, holders, years)| {
+                values
+                    .push_bind(*statement)
+                    .push_bind(*holders)
+                    .push_bind(*years);
+            });
+            sql.push(
+                " ON CONFLICT (statement, IFNULL(holders, ''), IFNULL(years, '')) \
+                 DO UPDATE SET statement = excluded.statement \
+                 RETURNING id, statement, holders, years",
+            );
+            let stored = sql
+                .build_query_as::<CopyrightStatement>()
+                .fetch_all(&mut *conn)
+                .await?;
+            for s in stored {
+                ids.insert((s.statement, s.holders, s.years), s.id);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Delete the statements whose text or holders contain `text`
+    /// (lowercase), for data erasure requests. The findings referring to
+    /// them must be deleted first, see [`ScanResult::delete_containing`].
+    ///
+    /// [`ScanResult::delete_containing`]: crate::db::models::ScanResult::delete_containing
+    pub async fn delete_containing(
+        conn: &mut SqliteConnection,
+        text: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM copyright_statements
+            WHERE instr(lower(statement), ?1) > 0
+               OR instr(lower(coalesce(holders, '')), ?1) > 0
+            "#,
+        )
+        .bind(text)
+        .execute(conn)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+/// A named git credential, see [`crate::credentials`]. The secret is only
+/// stored encrypted and never serialized.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Credential {
+    pub id: String,
+    pub name: String,
+    /// github, gitlab, azure_devops or ssh_key
+    pub provider: String,
+    /// HTTPS user name or SSH user, the provider's default when unset
+    pub username: Option<String>,
+    #[serde(skip_serializing)]
+    pub secret_encrypted: String,
+    #[serde(skip_serializing)]
+    pub passphrase_encrypted: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub last_used_at: Option<String>,
+}
+
+impl Credential {
+    /// Store a credential whose secrets were encrypted for `id`
+    pub async fn create(
+        pool: &SqlitePool,
+        id: &str,
+        name: &str,
+        provider: &str,
+        username: Option<&str>,
+        secret_encrypted: &str,
+        passphrase_encrypted: Option<&str>,
+    ) -> Result<Credential, sqlx::Error> {
+        sqlx::query_as::<_, Credential>(
+            r#"
+            INSERT INTO credentials
+            (id, name, provider, username, secret_encrypted, passphrase_encrypted)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(name)
+        .bind(provider)
+        .bind(username)
+        .bind(secret_encrypted)
+        .bind(passphrase_encrypted)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        id: &str,
+    ) -> Result<Option<Credential>, sqlx::Error> {
+        sqlx::query_as::<_, Credential>("SELECT * FROM credentials WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn find_by_name(
+        pool: &SqlitePool,
+        name: &str,
+    ) -> Result<Option<Credential>, sqlx::Error> {
+        sqlx::query_as::<_, Credential>("SELECT * FROM credentials WHERE name = ?")
+            .bind(name)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn list(pool: &SqlitePool) -> Result<Vec<Credential>, sqlx::Error> {
+        sqlx::query_as::<_, Credential>("SELECT * FROM credentials ORDER BY name")
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn update_last_used(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE credentials SET last_used_at = datetime('now') WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Scans referencing the credential keep its ID and fail to clone
+    /// afterwards
+    pub async fn delete(pool: &SqlitePool, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM credentials WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Deployment-defined scan field, see [`crate::custom_fields`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CustomField {
+    pub key: String,
+    pub label: String,
+    pub field_type: String,      // string, number, boolean, date, enum
+    pub options: Option<String>, // JSON array, enum fields only
+    pub pattern: Option<String>,
+    pub required: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl CustomField {
+    /// Create or replace the definition of `key`
+    pub async fn upsert(
+        pool: &SqlitePool,
+        key: &str,
+        label: &str,
+        field_type: &str,
+        options: &[String],
+        pattern: Option<&str>,
+        required: bool,
+    ) -> Result<CustomField, sqlx::Error> {
+        let options = (!options.is_empty())
+            .then(|| serde_json::to_string(options).unwrap_or_else(|_| "[]".to_string()));
+
+        sqlx::query_as::<_, CustomField>(
+            r#"
+            INSERT INTO custom_fields (key, label, field_type, options, pattern, required)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET
+                label = excluded.label,
+                field_type = excluded.field_type,
+                options = excluded.options,
+                pattern = excluded.pattern,
+                required = excluded.required,
+                updated_at = datetime('now')
+            RETURNING *
+            "#,
+        )
+        .bind(key)
+        .bind(label)
+        .bind(field_type)
+        .bind(options)
+        .bind(pattern)
+        .bind(required)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn list(pool: &SqlitePool) -> Result<Vec<CustomField>, sqlx::Error> {
+        sqlx::query_as::<_, CustomField>("SELECT * FROM custom_fields ORDER BY key")
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Returns false if no field had the key. Values set on scans are
+    /// deleted with the definition.
+    pub async fn delete(pool: &SqlitePool, key: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM custom_fields WHERE key = ?")
+            .bind(key)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Allowed values of an enum field
+    pub fn option_list(&self) -> Vec<String> {
+        self.options
+            .as_deref()
+            .and_then(|o| serde_json::from_str(o).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Value of a custom field on one scan
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScanCustomFieldValue {
+    pub scan_id: String,
+    pub field_key: String,
+    pub value: String, // JSON-encoded
+    pub updated_at: String,
+}
+
+impl ScanCustomFieldValue {
+    pub async fn set(
+        pool: &SqlitePool,
+        scan_id: &str,
+        field_key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO scan_custom_field_values (scan_id, field_key, value)
+            VALUES (?, ?, ?)
+            ON CONFLICT(scan_id, field_key) DO UPDATE SET
+                value = excluded.value,
+                updated_at = datetime('now')
+            "#,
+        )
+        .bind(scan_id)
+        .bind(field_key)
+        .bind(value.to_string())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        scan_id: &str,
+        field_key: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM scan_custom_field_values WHERE scan_id = ? AND field_key = ?")
+            .bind(scan_id)
+            .bind(field_key)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Vec<ScanCustomFieldValue>, sqlx::Error> {
+        sqlx::query_as::<_, ScanCustomFieldValue>(
+            "SELECT * FROM scan_custom_field_values WHERE scan_id = ? ORDER BY field_key",
+        )
+        .bind(scan_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Give a rerun the values of the scan it repeats
+    pub async fn copy(
+        pool: &SqlitePool,
+        from_scan_id: &str,
+        to_scan_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO scan_custom_field_values (scan_id, field_key, value)
+            SELECT ?, field_key, value FROM scan_custom_field_values WHERE scan_id = ?
+            "#,
+        )
+        .bind(to_scan_id)
+        .bind(from_scan_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
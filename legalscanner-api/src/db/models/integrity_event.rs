@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+/// Audit log entry of an integrity check, see [`crate::integrity`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct IntegrityEvent {
+    pub id: i64,
+    pub target_type: String, // database, scan
+    pub target_id: Option<String>,
+    pub kind: String, // database_corrupt, checksum_mismatch, unreadable, resealed
+    /// Checksum and finding count sealed with the results
+    pub expected: Option<String>,
+    /// Checksum and finding count found by the check
+    pub actual: Option<String>,
+    pub detail: Option<String>,
+    pub occurred_at: String,
+}
+
+impl IntegrityEvent {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record(
+        pool: &SqlitePool,
+        target_type: &str,
+        target_id: Option<&str>,
+        kind: &str,
+        expected: Option<&str>,
+        actual: Option<&str>,
+        detail: Option<&str>,
+    ) -> Result<IntegrityEvent, sqlx::Error> {
+        sqlx::query_as::<_, IntegrityEvent>(
+            r#"
+            INSERT INTO integrity_events (target_type, target_id, kind, expected, actual, detail)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(target_type)
+        .bind(target_id)
+        .bind(kind)
+        .bind(expected)
+        .bind(actual)
+        .bind(detail)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Events in the order they were recorded, optionally of one scan
+    pub async fn list(
+        pool: &SqlitePool,
+        scan_id: Option<&str>,
+    ) -> Result<Vec<IntegrityEvent>, sqlx::Error> {
+        sqlx::query_as::<_, IntegrityEvent>(
+            "SELECT * FROM integrity_events WHERE ?1 IS NULL OR target_id = ?1 ORDER BY id",
+        )
+        .bind(scan_id)
+        .fetch_all(pool)
+        .await
+    }
+}
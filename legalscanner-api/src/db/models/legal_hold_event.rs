@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+/// Audit log entry of a legal hold placed on or lifted from a scan or project
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct LegalHoldEvent {
+    pub id: i64,
+    pub target_type: String, // scan, project
+    pub target_id: String,
+    pub action: String, // placed, lifted
+    pub reason: Option<String>,
+    pub occurred_at: String,
+}
+
+impl LegalHoldEvent {
+    pub async fn record(
+        pool: &SqlitePool,
+        target_type: &str,
+        target_id: &str,
+        action: &str,
+        reason: Option<&str>,
+    ) -> Result<LegalHoldEvent, sqlx::Error> {
+        sqlx::query_as::<_, LegalHoldEvent>(
+            r#"
+            INSERT INTO legal_hold_events (target_type, target_id, action, reason)
+            VALUES (?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(target_type)
+        .bind(target_id)
+        .bind(action)
+        .bind(reason)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Events in the order they were recorded, optionally of one scan or project
+    pub async fn list(
+        pool: &SqlitePool,
+        target_id: Option<&str>,
+    ) -> Result<Vec<LegalHoldEvent>, sqlx::Error> {
+        sqlx::query_as::<_, LegalHoldEvent>(
+            "SELECT * FROM legal_hold_events WHERE ?1 IS NULL OR target_id = ?1 ORDER BY id",
+        )
+        .bind(target_id)
+        .fetch_all(pool)
+        .await
+    }
+}
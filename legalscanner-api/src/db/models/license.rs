@@ -0,0 +1,51 @@
+use serde::Serialize;
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqliteConnection};
+use std::collections::HashMap;
+
+/// Rows per upsert when storing licenses and copyright statements
+pub const INTERN_BATCH_ROWS: usize = 500;
+
+/// A distinct license found in files, referenced by `scan_results.license_id`
+/// instead of repeating the name on every finding
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct License {
+    pub id: i64,
+    pub name: String,
+    pub spdx_id: Option<String>,
+    /// SPDX exception the license is granted with
+    pub exception: Option<String>,
+}
+
+impl License {
+    /// IDs of the `(name, spdx_id, exception)` triples, storing the ones not
+    /// seen before
+    pub async fn intern(
+        conn: &mut SqliteConnection,
+        licenses: &[(&str, Option<&str>, Option<&str>)],
+    ) -> Result<HashMap<(String, Option<String>, Option<String>), i64>, sqlx::Error> {
+        let mut unique = licenses.to_vec();
+        unique.sort_unstable();
+        unique.dedup();
+
+        let mut ids = HashMap::with_capacity(unique.len());
+        for batch in unique.chunks(INTERN_BATCH_ROWS) {
+            let mut sql = QueryBuilder::<Sqlite>::new("INSERT INTO licenses (name, spdx_id, exception) ");
+            sql.push_values(batch, |mut values, (name, spdx_id, exception)| {
+                values.push_bind(*name).push_bind(*spdx_id).push_bind(*exception);
+            });
+            // A no-op update, so existing rows are returned as well
+            sql.push(
+                " ON CONFLICT (name, IFNULL(spdx_id, ''), IFNULL(exception, '')) \
+                 DO UPDATE SET name = excluded.name RETURNING id, name, spdx_id, exception",
+            );
+            for license in sql
+                .build_query_as::<License>()
+                .fetch_all(&mut *conn)
+                .await?
+            {
+                ids.insert((license.name, license.spdx_id, license.exception), license.id);
+            }
+        }
+        Ok(ids)
+    }
+}
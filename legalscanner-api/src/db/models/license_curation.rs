@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Reviewed license conclusion for one file of a repository, see
+/// [`crate::curation`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct LicenseCuration {
+    pub id: String,
+    pub repository: String, // normalized git URL
+    pub file_path: String,
+    pub concluded_licenses: Option<String>, // JSON array
+    pub irrelevant: bool,
+    pub source: String, // fossology
+    pub source_reference: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl LicenseCuration {
+    /// Create or replace the curation of a file
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upsert(
+        pool: &SqlitePool,
+        repository: &str,
+        file_path: &str,
+        concluded_licenses: &[String],
+        irrelevant: bool,
+        source: &str,
+        source_reference: Option<&str>,
+    ) -> Result<LicenseCuration, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        let concluded_json =
+            (!irrelevant).then(|| serde_json::to_string(concluded_licenses).unwrap_or_default());
+
+        sqlx::query_as::<_, LicenseCuration>(
+            r#"
+            INSERT INTO license_curations
+            (id, repository, file_path, concluded_licenses, irrelevant, source, source_reference)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(repository, file_path) DO UPDATE SET
+                concluded_licenses = excluded.concluded_licenses,
+                irrelevant = excluded.irrelevant,
+                source = excluded.source,
+                source_reference = excluded.source_reference,
+                updated_at = datetime('now')
+            RETURNING *
+            "#,
+        )
+        .bind(&id)
+        .bind(repository)
+        .bind(file_path)
+        .bind(concluded_json)
+        .bind(irrelevant)
+        .bind(source)
+        .bind(source_reference)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_repository(
+        pool: &SqlitePool,
+        repository: &str,
+    ) -> Result<Vec<LicenseCuration>, sqlx::Error> {
+        sqlx::query_as::<_, LicenseCuration>(
+            "SELECT * FROM license_curations WHERE repository = ? ORDER BY file_path",
+        )
+        .bind(repository)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Returns false if no curation has this ID
+    pub async fn delete(pool: &SqlitePool, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM license_curations WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete every curation of a repository, returning how many there were
+    pub async fn delete_by_repository(
+        pool: &SqlitePool,
+        repository: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM license_curations WHERE repository = ?")
+            .bind(repository)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Concluded license names, empty for irrelevant files
+    pub fn concluded_license_list(&self) -> Vec<String> {
+        self.concluded_licenses
+            .as_deref()
+            .and_then(|l| serde_json::from_str(l).ok())
+            .unwrap_or_default()
+    }
+}
@@ -1,7 +1,68 @@
 pub mod api_key;
+pub mod audit_log;
+pub mod composite_scan;
+pub mod copyright_holder;
+pub mod copyright_statement;
+pub mod credential;
+pub mod custom_field;
+pub mod integrity_event;
+pub mod legal_hold_event;
+pub mod license;
+pub mod license_curation;
+pub mod notification_template;
+pub mod policy;
+pub mod project;
+pub mod result_checksum;
+pub mod result_partition;
 pub mod scan;
+pub mod scan_codeowners;
+pub mod scan_event;
+pub mod scan_file_author;
+pub mod scan_file_swhid;
+pub mod scan_history_finding;
+pub mod scan_language_stat;
+pub mod scan_repository_metadata;
 pub mod scan_result;
+pub mod scan_result_comment;
+pub mod scan_screening;
+pub mod scan_share;
+pub mod scan_submodule;
+pub mod telemetry_state;
+pub mod tracked_finding;
+pub mod waiver;
+pub mod webhook_delivery;
+pub mod worker;
 
 pub use api_key::ApiKey;
+pub use audit_log::AuditLogEntry;
+pub use composite_scan::CompositeScan;
+pub use copyright_holder::{CopyrightHolder, CopyrightHolderProject};
+pub use copyright_statement::CopyrightStatement;
+pub use credential::Credential;
+pub use custom_field::{CustomField, ScanCustomFieldValue};
+pub use integrity_event::IntegrityEvent;
+pub use legal_hold_event::LegalHoldEvent;
+pub use license::License;
+pub use license_curation::LicenseCuration;
+pub use notification_template::NotificationTemplate;
+pub use policy::{Policy, PolicyEvaluationRecord};
+pub use project::Project;
+pub use result_checksum::ResultChecksum;
 pub use scan::Scan;
+pub use scan_codeowners::ScanCodeOwners;
+pub use scan_event::ScanEvent;
+pub use scan_file_author::ScanFileAuthor;
+pub use scan_file_swhid::ScanFileSwhid;
+pub use scan_history_finding::ScanHistoryFinding;
+pub use scan_language_stat::ScanLanguageStat;
+pub use scan_repository_metadata::{CommitProvenance, ScanRepositoryMetadata};
 pub use scan_result::ScanResult;
+pub use scan_result_comment::ScanResultComment;
+pub use scan_screening::ScanScreening;
+pub use scan_share::ScanShare;
+pub use scan_submodule::ScanSubmodule;
+pub use telemetry_state::TelemetryState;
+pub use tracked_finding::{TrackedFinding, TrackedFindingFilter};
+pub use waiver::Waiver;
+pub use webhook_delivery::WebhookDelivery;
+pub use worker::Worker;
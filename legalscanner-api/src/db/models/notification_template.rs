@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+/// Payload template for one callback event, see [`crate::webhooks`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct NotificationTemplate {
+    pub event: String, // scan.completed, scan.failed
+    pub content_type: String,
+    pub body: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl NotificationTemplate {
+    /// Create or replace the template of `event`
+    pub async fn upsert(
+        pool: &SqlitePool,
+        event: &str,
+        content_type: &str,
+        body: &str,
+    ) -> Result<NotificationTemplate, sqlx::Error> {
+        sqlx::query_as::<_, NotificationTemplate>(
+            r#"
+            INSERT INTO notification_templates (event, content_type, body)
+            VALUES (?, ?, ?)
+            ON CONFLICT(event) DO UPDATE SET
+                content_type = excluded.content_type,
+                body = excluded.body,
+                updated_at = datetime('now')
+            RETURNING *
+            "#,
+        )
+        .bind(event)
+        .bind(content_type)
+        .bind(body)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_event(
+        pool: &SqlitePool,
+        event: &str,
+    ) -> Result<Option<NotificationTemplate>, sqlx::Error> {
+        sqlx::query_as::<_, NotificationTemplate>(
+            "SELECT * FROM notification_templates WHERE event = ?",
+        )
+        .bind(event)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn list(pool: &SqlitePool) -> Result<Vec<NotificationTemplate>, sqlx::Error> {
+        sqlx::query_as::<_, NotificationTemplate>(
+            "SELECT * FROM notification_templates ORDER BY event",
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Returns false if the event had no template
+    pub async fn delete(pool: &SqlitePool, event: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM notification_templates WHERE event = ?")
+            .bind(event)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Named license policy, see [`crate::policy`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Policy {
+    pub name: String,
+    pub description: Option<String>,
+    pub allowed_licenses: String, // JSON array of SPDX IDs or license names
+    pub denied_licenses: String,  // JSON array
+    pub review_licenses: String,  // JSON array
+    pub max_risk_score: Option<i32>,
+    pub ecc_fail_severity: Option<String>, // low, medium, high, critical
+    pub is_default: bool,                  // the organization's default policy
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Policy {
+    /// Create or replace the policy `name`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upsert(
+        pool: &SqlitePool,
+        name: &str,
+        description: Option<&str>,
+        allowed_licenses: &[String],
+        denied_licenses: &[String],
+        review_licenses: &[String],
+        max_risk_score: Option<i32>,
+        ecc_fail_severity: Option<&str>,
+    ) -> Result<Policy, sqlx::Error> {
+        let json = |list: &[String]| serde_json::to_string(list).unwrap_or_else(|_| "[]".to_string());
+
+        sqlx::query_as::<_, Policy>(
+            r#"
+            INSERT INTO policies (
+                name, description, allowed_licenses, denied_licenses, review_licenses,
+                max_risk_score, ecc_fail_severity
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(name) DO UPDATE SET
+                description = excluded.description,
+                allowed_licenses = excluded.allowed_licenses,
+                denied_licenses = excluded.denied_licenses,
+                review_licenses = excluded.review_licenses,
+                max_risk_score = excluded.max_risk_score,
+                ecc_fail_severity = excluded.ecc_fail_severity,
+                updated_at = datetime('now')
+            RETURNING *
+            "#,
+        )
+        .bind(name)
+        .bind(description)
+        .bind(json(allowed_licenses))
+        .bind(json(denied_licenses))
+        .bind(json(review_licenses))
+        .bind(max_risk_score)
+        .bind(ecc_fail_severity)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_name(pool: &SqlitePool, name: &str) -> Result<Option<Policy>, sqlx::Error> {
+        sqlx::query_as::<_, Policy>("SELECT * FROM policies WHERE name = ?")
+            .bind(name)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// The organization's default policy
+    pub async fn find_default(pool: &SqlitePool) -> Result<Option<Policy>, sqlx::Error> {
+        sqlx::query_as::<_, Policy>("SELECT * FROM policies WHERE is_default = 1")
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Make the policy `name` the organization's default, replacing the
+    /// previous one. Returns `None` if no policy had the name.
+    pub async fn set_default(pool: &SqlitePool, name: &str) -> Result<Option<Policy>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("UPDATE policies SET is_default = 0 WHERE is_default = 1")
+            .execute(&mut *tx)
+            .await?;
+        let policy = sqlx::query_as::<_, Policy>(
+            "UPDATE policies SET is_default = 1 WHERE name = ? RETURNING *",
+        )
+        .bind(name)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if policy.is_some() {
+            tx.commit().await?;
+        }
+
+        Ok(policy)
+    }
+
+    /// Leave the organization without a default policy. Returns false if it
+    /// had none.
+    pub async fn clear_default(pool: &SqlitePool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE policies SET is_default = 0 WHERE is_default = 1")
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list(pool: &SqlitePool) -> Result<Vec<Policy>, sqlx::Error> {
+        sqlx::query_as::<_, Policy>("SELECT * FROM policies ORDER BY name")
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Returns false if no policy had the name. Projects and scans it was
+    /// assigned to are left without a policy.
+    pub async fn delete(pool: &SqlitePool, name: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM policies WHERE name = ?")
+            .bind(name)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub fn allowed(&self) -> Vec<String> {
+        parse_list(&self.allowed_licenses)
+    }
+
+    pub fn denied(&self) -> Vec<String> {
+        parse_list(&self.denied_licenses)
+    }
+
+    pub fn needs_review(&self) -> Vec<String> {
+        parse_list(&self.review_licenses)
+    }
+}
+
+fn parse_list(json: &str) -> Vec<String> {
+    serde_json::from_str(json).unwrap_or_default()
+}
+
+/// Latest evaluation of a scan against its policy
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PolicyEvaluationRecord {
+    pub scan_id: String,
+    pub policy: String,
+    pub outcome: String,    // pass, warn, fail
+    pub violations: String, // JSON array of `policy::PolicyViolation`
+    pub evaluated_at: String,
+}
+
+impl PolicyEvaluationRecord {
+    /// Store the evaluation of a scan, replacing the previous one
+    pub async fn replace(
+        pool: &SqlitePool,
+        scan_id: &str,
+        policy: &str,
+        outcome: &str,
+        violations: &str,
+    ) -> Result<PolicyEvaluationRecord, sqlx::Error> {
+        sqlx::query_as::<_, PolicyEvaluationRecord>(
+            r#"
+            INSERT INTO policy_evaluations (scan_id, policy, outcome, violations)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(scan_id) DO UPDATE SET
+                policy = excluded.policy,
+                outcome = excluded.outcome,
+                violations = excluded.violations,
+                evaluated_at = datetime('now')
+            RETURNING *
+            "#,
+        )
+        .bind(scan_id)
+        .bind(policy)
+        .bind(outcome)
+        .bind(violations)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Option<PolicyEvaluationRecord>, sqlx::Error> {
+        sqlx::query_as::<_, PolicyEvaluationRecord>(
+            "SELECT * FROM policy_evaluations WHERE scan_id = ?",
+        )
+        .bind(scan_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Forget the evaluation of a scan no policy applies to any more
+    pub async fn delete(pool: &SqlitePool, scan_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM policy_evaluations WHERE scan_id = ?")
+            .bind(scan_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
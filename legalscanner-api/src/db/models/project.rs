@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+use uuid::Uuid;
+
+/// Named group of repositories whose scans are tracked together
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    // Set while the project is under legal hold, which also holds every
+    // scan of its repositories
+    pub legal_hold_at: Option<String>,
+    pub legal_hold_reason: Option<String>,
+    // License policy of the project's scans, see [`crate::policy`]
+    pub policy: Option<String>,
+}
+
+impl Project {
+    /// Create a project; `repositories` must already be normalized
+    pub async fn create(
+        pool: &SqlitePool,
+        name: &str,
+        description: Option<&str>,
+        repositories: &[String],
+    ) -> Result<Project, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        let mut tx = pool.begin().await?;
+
+        let project = sqlx::query_as::<_, Project>(
+            "INSERT INTO projects (id, name, description) VALUES (?, ?, ?) RETURNING *",
+        )
+        .bind(&id)
+        .bind(name)
+        .bind(description)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        insert_repositories(&mut tx, &id, repositories).await?;
+
+        tx.commit().await?;
+        Ok(project)
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Project>, sqlx::Error> {
+        sqlx::query_as::<_, Project>("SELECT * FROM projects WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn list(pool: &SqlitePool) -> Result<Vec<Project>, sqlx::Error> {
+        sqlx::query_as::<_, Project>("SELECT * FROM projects ORDER BY name")
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Normalized repository URLs of a project, sorted
+    pub async fn repositories(pool: &SqlitePool, id: &str) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT git_url FROM project_repositories WHERE project_id = ? ORDER BY git_url",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Update name, description and, when given, replace the repositories.
+    /// Returns `None` if the project does not exist.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: &str,
+        name: Option<&str>,
+        description: Option<&str>,
+        repositories: Option<&[String]>,
+    ) -> Result<Option<Project>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let project = sqlx::query_as::<_, Project>(
+            r#"
+            UPDATE projects
+            SET name = COALESCE(?, name),
+                description = COALESCE(?, description),
+                updated_at = datetime('now')
+            WHERE id = ?
+            RETURNING *
+            "#,
+        )
+        .bind(name)
+        .bind(description)
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if project.is_none() {
+            return Ok(None);
+        }
+
+        if let Some(repositories) = repositories {
+            sqlx::query("DELETE FROM project_repositories WHERE project_id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+
+            insert_repositories(&mut tx, id, repositories).await?;
+        }
+
+        tx.commit().await?;
+        Ok(project)
+    }
+
+    /// Delete a project; its scans are kept. Returns false if it did not exist.
+    pub async fn delete(pool: &SqlitePool, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM projects WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Place a legal hold with `Some(reason)`, or lift it with `None`.
+    /// Returns `None` if the project does not exist.
+    pub async fn set_legal_hold(
+        pool: &SqlitePool,
+        id: &str,
+        reason: Option<&str>,
+    ) -> Result<Option<Project>, sqlx::Error> {
+        sqlx::query_as::<_, Project>(
+            r#"
+            UPDATE projects
+            SET legal_hold_at = CASE WHEN ?1 IS NULL THEN NULL ELSE datetime('now') END,
+                legal_hold_reason = ?1
+            WHERE id = ?2
+            RETURNING *
+            "#,
+        )
+        .bind(reason)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Assign a license policy to the project, or remove it with `None`.
+    /// Returns `None` if the project does not exist.
+    pub async fn set_policy(
+        pool: &SqlitePool,
+        id: &str,
+        policy: Option<&str>,
+    ) -> Result<Option<Project>, sqlx::Error> {
+        sqlx::query_as::<_, Project>(
+            r#"
+            UPDATE projects SET policy = ?, updated_at = datetime('now')
+            WHERE id = ?
+            RETURNING *
+            "#,
+        )
+        .bind(policy)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Projects having the normalized repository URL
+    pub async fn find_by_repository(
+        pool: &SqlitePool,
+        git_url: &str,
+    ) -> Result<Vec<Project>, sqlx::Error> {
+        sqlx::query_as::<_, Project>(
+            r#"
+            SELECT p.* FROM projects p
+            JOIN project_repositories r ON r.project_id = p.id
+            WHERE r.git_url = ?
+            ORDER BY p.name
+            "#,
+        )
+        .bind(git_url)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Held projects having the normalized repository URL
+    pub async fn find_held_by_repository(
+        pool: &SqlitePool,
+        git_url: &str,
+    ) -> Result<Vec<Project>, sqlx::Error> {
+        sqlx::query_as::<_, Project>(
+            r#"
+            SELECT p.* FROM projects p
+            JOIN project_repositories r ON r.project_id = p.id
+            WHERE r.git_url = ? AND p.legal_hold_at IS NOT NULL
+            ORDER BY p.name
+            "#,
+        )
+        .bind(git_url)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Normalized repository URLs of every held project
+    pub async fn held_repositories(pool: &SqlitePool) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT DISTINCT r.git_url FROM project_repositories r
+            JOIN projects p ON p.id = r.project_id
+            WHERE p.legal_hold_at IS NOT NULL
+            ORDER BY r.git_url
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Remove a normalized repository URL from every project
+    pub async fn remove_repository(pool: &SqlitePool, git_url: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM project_repositories WHERE git_url = ?")
+            .bind(git_url)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+async fn insert_repositories(
+    conn: &mut SqliteConnection,
+    project_id: &str,
+    repositories: &[String],
+) -> Result<(), sqlx::Error> {
+    for git_url in repositories {
+        sqlx::query("INSERT INTO project_repositories (project_id, git_url) VALUES (?, ?)")
+            .bind(project_id)
+            .bind(git_url)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Checksum of a scan's stored findings, see [`crate::integrity`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ResultChecksum {
+    pub scan_id: String,
+    pub result_count: i64,
+    pub checksum: String,
+    pub sealed_at: String,
+    pub verified_at: Option<String>,
+}
+
+impl ResultChecksum {
+    /// Store the checksum of a scan's findings, replacing an earlier one
+    pub async fn seal(
+        pool: &SqlitePool,
+        scan_id: &str,
+        result_count: i64,
+        checksum: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO scan_result_checksums (scan_id, result_count, checksum)
+            VALUES (?, ?, ?)
+            ON CONFLICT(scan_id) DO UPDATE SET
+                result_count = excluded.result_count,
+                checksum = excluded.checksum,
+                sealed_at = datetime('now'),
+                verified_at = NULL
+            "#,
+        )
+        .bind(scan_id)
+        .bind(result_count)
+        .bind(checksum)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list(pool: &SqlitePool) -> Result<Vec<ResultChecksum>, sqlx::Error> {
+        sqlx::query_as::<_, ResultChecksum>("SELECT * FROM scan_result_checksums ORDER BY scan_id")
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn mark_verified(pool: &SqlitePool, scan_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE scan_result_checksums SET verified_at = datetime('now') WHERE scan_id = ?",
+        )
+        .bind(scan_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Completed scans stored before checksums were recorded
+    pub async fn unsealed_scan_ids(pool: &SqlitePool) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT id FROM scans
+            WHERE status = 'completed'
+              AND id NOT IN (SELECT scan_id FROM scan_result_checksums)
+            ORDER BY id
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,322 @@
+//! Findings partitioned by scan month. With `RESULT_PARTITIONING=monthly`
+//! the findings of a scan go to `scan_results_YYYYMM` for the month the scan
+//! was created in, so the tables recent scans are read from stay small
+//! however many findings older scans have piled up. The table is recorded on
+//! the scan when its findings are first stored and every per-scan query is
+//! routed to it; findings stored before partitioning stay in `scan_results`.
+//!
+//! Findings are read through [`source`], which joins in their license and
+//! copyright statement, and queries across scans read the `all_scan_results`
+//! view over every partition. Finding IDs come from `scan_result_sequence`
+//! and are unique across partitions. A partition is created with the columns
+//! `scan_results` has at the time; columns added to `scan_results` later are
+//! added to the partitions by [`refresh`] once migrations ran.
+
+use sqlx::{Executor, Sqlite, SqliteConnection, SqlitePool};
+use std::str::FromStr;
+
+/// Table holding the findings stored before partitioning, and all findings
+/// without it
+pub const DEFAULT_TABLE: &str = "scan_results";
+
+/// View over the findings of every partition
+pub const ALL_RESULTS_VIEW: &str = "all_scan_results";
+
+/// Columns of a finding as read, with the license and copyright statement
+/// joined in. Findings stored before normalization keep them on the row.
+const RESULT_COLUMNS: &str = "r.id, r.scan_id, r.file_path, r.result_type, \
+    COALESCE(r.license_name, l.name) AS license_name, \
+    COALESCE(r.license_spdx_id, l.spdx_id) AS license_spdx_id, \
+    l.exception AS license_exception, \
+    COALESCE(r.copyright_statement, c.statement) AS copyright_statement, \
+    COALESCE(r.copyright_holders, c.holders) AS copyright_holders, \
+    COALESCE(r.copyright_years, c.years) AS copyright_years, \
+    r.confidence, r.raw_data, r.risk_severity, r.ecc_source, r.ecc_line_number, \
+    r.ecc_check_id, r.license_category, r.package_name, r.package_version, \
+    r.package_ecosystem, r.package_purl, r.merge_provenance, r.license_agreement, \
+    r.review_status, r.reviewed_at, r.license_id, r.copyright_statement_id";
+
+/// How the findings of new scans are split across tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResultPartitioning {
+    /// Every finding in `scan_results`
+    #[default]
+    None,
+    /// One table per month of scan creation
+    Monthly,
+}
+
+impl ResultPartitioning {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResultPartitioning::None => "none",
+            ResultPartitioning::Monthly => "monthly",
+        }
+    }
+}
+
+impl FromStr for ResultPartitioning {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "none" => Ok(ResultPartitioning::None),
+            "monthly" => Ok(ResultPartitioning::Monthly),
+            other => Err(format!(
+                "Invalid RESULT_PARTITIONING '{}'. Must be 'none' or 'monthly'",
+                other
+            )),
+        }
+    }
+}
+
+/// Partition for a scan created at `created_at` (`YYYY-MM-DD ...`)
+pub fn monthly_table(created_at: &str) -> Option<String> {
+    let year = created_at.get(0..4)?;
+    let month = created_at.get(5..7)?;
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    (digits(year) && digits(month) && created_at.get(4..5) == Some("-"))
+        .then(|| format!("{}_{}{}", DEFAULT_TABLE, year, month))
+}
+
+/// Whether `table` is `scan_results` or one of its monthly partitions. Table
+/// names are spliced into SQL, so only these are ever used.
+pub fn is_partition(table: &str) -> bool {
+    table == DEFAULT_TABLE
+        || table
+            .strip_prefix("scan_results_")
+            .is_some_and(|month| month.len() == 6 && month.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Table holding the findings of a scan
+pub async fn table_for<'e, E>(executor: E, scan_id: &str) -> Result<String, sqlx::Error>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let table: Option<String> = sqlx::query_scalar("SELECT results_table FROM scans WHERE id = ?")
+        .bind(scan_id)
+        .fetch_optional(executor)
+        .await?
+        .flatten();
+    Ok(table
+        .filter(|t| is_partition(t))
+        .unwrap_or_else(|| DEFAULT_TABLE.to_string()))
+}
+
+/// Findings of `table` as read, to select from in place of the table
+pub fn source(table: &str) -> String {
+    format!("({})", select(table))
+}
+
+/// [`source`] of the table holding the findings of a scan
+pub async fn source_for<'e, E>(executor: E, scan_id: &str) -> Result<String, sqlx::Error>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    Ok(source(&table_for(executor, scan_id).await?))
+}
+
+fn select(table: &str) -> String {
+    format!(
+        "SELECT {} FROM {} r \
+         LEFT JOIN licenses l ON l.id = r.license_id \
+         LEFT JOIN copyright_statements c ON c.id = r.copyright_statement_id",
+        RESULT_COLUMNS, table
+    )
+}
+
+/// Table the findings of a scan are to be stored in, creating its partition
+/// if needed. A scan keeps the table its first findings went to.
+pub async fn assign(
+    conn: &mut SqliteConnection,
+    scan_id: &str,
+    partitioning: ResultPartitioning,
+) -> Result<String, sqlx::Error> {
+    let (assigned, created_at): (Option<String>, String) =
+        sqlx::query_as("SELECT results_table, created_at FROM scans WHERE id = ?")
+            .bind(scan_id)
+            .fetch_one(&mut *conn)
+            .await?;
+    if let Some(table) = assigned.filter(|t| is_partition(t)) {
+        return Ok(table);
+    }
+
+    let table = match partitioning {
+        ResultPartitioning::None => None,
+        ResultPartitioning::Monthly => monthly_table(&created_at),
+    };
+    let Some(table) = table else {
+        return Ok(DEFAULT_TABLE.to_string());
+    };
+    create(conn, &table).await?;
+    sqlx::query("UPDATE scans SET results_table = ? WHERE id = ?")
+        .bind(&table)
+        .bind(scan_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(table)
+}
+
+/// `scan_results` and its partitions, oldest first
+pub async fn tables<'e, E>(executor: E) -> Result<Vec<String>, sqlx::Error>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let tables: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT name FROM sqlite_master
+        WHERE type = 'table'
+            AND (name = 'scan_results'
+                 OR name GLOB 'scan_results_[0-9][0-9][0-9][0-9][0-9][0-9]')
+        ORDER BY name = 'scan_results' DESC, name
+        "#,
+    )
+    .fetch_all(executor)
+    .await?;
+    Ok(tables.into_iter().filter(|t| is_partition(t)).collect())
+}
+
+/// Bring the partitions up to date after migrations: add the columns
+/// `scan_results` gained since a partition was created, and recreate the
+/// `all_scan_results` view over every partition
+pub async fn refresh(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let wanted = columns(&mut tx, DEFAULT_TABLE).await?;
+    for table in tables(&mut *tx).await? {
+        if table == DEFAULT_TABLE {
+            continue;
+        }
+        let existing = columns(&mut tx, &table).await?;
+        for (name, column_type) in &wanted {
+            if !existing.iter().any(|(n, _)| n == name) {
+                sqlx::query(&format!(
+                    "ALTER TABLE {} ADD COLUMN {} {}",
+                    table, name, column_type
+                ))
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+    }
+    recreate_view(&mut tx).await?;
+    tx.commit().await
+}
+
+/// Names and types of the columns of a table
+async fn columns(
+    conn: &mut SqliteConnection,
+    table: &str,
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    sqlx::query_as("SELECT name, type FROM pragma_table_info(?) ORDER BY cid")
+        .bind(table)
+        .fetch_all(conn)
+        .await
+}
+
+/// Drop the monthly partitions left without findings, e.g. after a retention
+/// purge, pointing their scans back at `scan_results`. Returns the tables
+/// dropped.
+pub async fn drop_empty(pool: &SqlitePool) -> Result<Vec<String>, sqlx::Error> {
+    let mut dropped = Vec::new();
+    let mut tx = pool.begin().await?;
+    for table in tables(&mut *tx).await? {
+        if table == DEFAULT_TABLE {
+            continue;
+        }
+        let empty: bool = sqlx::query_scalar(&format!(
+            "SELECT NOT EXISTS (SELECT 1 FROM {})",
+            table
+        ))
+        .fetch_one(&mut *tx)
+        .await?;
+        if !empty {
+            continue;
+        }
+        sqlx::query("UPDATE scans SET results_table = NULL WHERE results_table = ?")
+            .bind(&table)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(&format!("DROP TABLE {}", table))
+            .execute(&mut *tx)
+            .await?;
+        dropped.push(table);
+    }
+    if !dropped.is_empty() {
+        recreate_view(&mut tx).await?;
+    }
+    tx.commit().await?;
+    Ok(dropped)
+}
+
+/// Create a partition with the columns and constraints of `scan_results`
+async fn create(conn: &mut SqliteConnection, table: &str) -> Result<(), sqlx::Error> {
+    if tables(&mut *conn).await?.iter().any(|t| t == table) {
+        return Ok(());
+    }
+
+    let ddl: String = sqlx::query_scalar(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'scan_results'",
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    let columns = ddl
+        .find('(')
+        .map(|start| &ddl[start..])
+        .ok_or_else(|| sqlx::Error::Protocol("Unexpected scan_results schema".to_string()))?;
+    sqlx::query(&format!("CREATE TABLE {} {}", table, columns))
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(&format!(
+        "CREATE INDEX idx_{0}_scan_id ON {0}(scan_id, review_status)",
+        table
+    ))
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query(&format!(
+        "CREATE INDEX idx_{0}_type ON {0}(scan_id, result_type)",
+        table
+    ))
+    .execute(&mut *conn)
+    .await?;
+
+    tracing::info!("Created result partition {}", table);
+    recreate_view(conn).await
+}
+
+async fn recreate_view(conn: &mut SqliteConnection) -> Result<(), sqlx::Error> {
+    let selects: Vec<String> = tables(&mut *conn)
+        .await?
+        .iter()
+        .map(|table| select(table))
+        .collect();
+    sqlx::query(&format!("DROP VIEW IF EXISTS {}", ALL_RESULTS_VIEW))
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(&format!(
+        "CREATE VIEW {} AS {}",
+        ALL_RESULTS_VIEW,
+        selects.join(" UNION ALL ")
+    ))
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monthly_table() {
+        assert_eq!(
+            monthly_table("2025-03-14 09:26:53").as_deref(),
+            Some("scan_results_202503")
+        );
+        assert_eq!(monthly_table("2025-3-14"), None);
+        assert_eq!(monthly_table(""), None);
+        assert!(is_partition("scan_results"));
+        assert!(is_partition("scan_results_202503"));
+        assert!(!is_partition("scan_results_2025; DROP TABLE scans"));
+        assert!(!is_partition("scans"));
+    }
+}
@@ -1,6 +1,11 @@
+use crate::db::models::{result_partition, ScanResult};
+use crate::scan_diff::normalize_repository_url;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool};
+use std::collections::HashSet;
+use std::str::FromStr;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -29,6 +34,54 @@ pub struct Scan {
     pub risk_score: Option<i32>,
     pub risk_level: Option<String>,
     pub risk_factors: Option<String>, // JSON array
+    // Scoring algorithm and configuration the assessment was made with
+    pub risk_algorithm_version: Option<i32>,
+    pub risk_config_version: Option<String>,
+    pub risk_assessed_at: Option<String>,
+    // Per-scan Semgrep ruleset override
+    pub semgrep_rulesets: Option<String>, // JSON array
+    // Preview scan without Fossology, risk score is preliminary
+    pub quick: bool,
+    pub approved_at: Option<String>,
+    // Set once every finding that trips a failing gate rule was reviewed
+    pub reviewed_at: Option<String>,
+    // Soft limit and partial re-run warnings, results may be incomplete
+    pub warnings: Option<String>, // JSON array
+    // Scan this one re-runs, if started from POST /scans/:id/rerun
+    pub parent_scan_id: Option<String>,
+    // Also scan the git history for code removed from HEAD
+    pub due_diligence: bool,
+    // Notified with a signed POST when the scan completes or fails
+    pub callback_url: Option<String>,
+    // Repository subdirectory the scan is limited to
+    pub scan_path: Option<String>,
+    // Requested branch, `None` for the repository's default branch
+    pub branch: Option<String>,
+    // Commit to check out, e.g. the one a push webhook reported; the head of
+    // the branch when unset
+    pub commit_sha: Option<String>,
+    // Set while the scan is under legal hold and cannot be deleted
+    pub legal_hold_at: Option<String>,
+    pub legal_hold_reason: Option<String>,
+    // Also check out git submodules, recursively
+    pub submodules: bool,
+    // Token for cloning submodules, `git_token` when unset. Encrypted with
+    // `CREDENTIALS_ENCRYPTION_KEY`, bound to the scan's ID.
+    #[serde(skip_serializing)]
+    pub submodule_token_encrypted: Option<String>,
+    // Registered credential to clone with, instead of `git_token`
+    pub credential_id: Option<String>,
+    // Also attribute files to their commit authors from the git history
+    pub git_authorship: bool,
+    // Set once retention purged the findings, the summary is kept
+    pub results_purged_at: Option<String>,
+    // Set once archival moved the findings out of the database, the summary
+    // is kept
+    pub results_archived_at: Option<String>,
+    // Set once the scan was deleted, hidden until restored or purged
+    pub deleted_at: Option<String>,
+    // License policy assigned to the scan, overriding its project's
+    pub policy: Option<String>,
 }
 
 impl Scan {
@@ -55,20 +108,153 @@ impl Scan {
         .await
     }
 
+    /// Create a scan unless one of the same repository and branch is still
+    /// pending or in progress. Returns the new scan and `true`, or the
+    /// running scan and `false`. Check and insert are a single statement, so
+    /// concurrent requests cannot both create a scan.
+    pub async fn create_unless_running(
+        pool: &SqlitePool,
+        git_url: String,
+        branch: Option<String>,
+        git_token: Option<String>,
+        created_by_key_id: Option<String>,
+    ) -> Result<(Scan, bool), sqlx::Error> {
+        let repository = [normalize_repository_url(&git_url)];
+
+        loop {
+            let mut insert = QueryBuilder::<Sqlite>::new(
+                "INSERT INTO scans (id, git_url, branch, git_token, status, created_by_key_id) SELECT ",
+            );
+            let mut values = insert.separated(", ");
+            values.push_bind(Uuid::new_v4().to_string());
+            values.push_bind(&git_url);
+            values.push_bind(&branch);
+            values.push_bind(&git_token);
+            values.push("'pending'");
+            values.push_bind(&created_by_key_id);
+            insert.push(" WHERE NOT EXISTS (SELECT 1 FROM scans WHERE ");
+            push_running_filter(&mut insert, &repository, &branch);
+            insert.push(") RETURNING *");
+
+            if let Some(scan) = insert.build_query_as::<Scan>().fetch_optional(pool).await? {
+                return Ok((scan, true));
+            }
+
+            let mut running = QueryBuilder::<Sqlite>::new("SELECT * FROM scans WHERE ");
+            push_running_filter(&mut running, &repository, &branch);
+            running.push(" ORDER BY created_at, id LIMIT 1");
+
+            // The running scan may have finished in between, then try again
+            if let Some(scan) = running
+                .build_query_as::<Scan>()
+                .fetch_optional(pool)
+                .await?
+            {
+                return Ok((scan, false));
+            }
+        }
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Scan>, sqlx::Error> {
-        sqlx::query_as::<_, Scan>("SELECT * FROM scans WHERE id = ?")
+        sqlx::query_as::<_, Scan>("SELECT * FROM scans WHERE id = ? AND deleted_at IS NULL")
             .bind(id)
             .fetch_optional(pool)
             .await
     }
 
-    pub async fn list_all(pool: &SqlitePool, limit: i64) -> Result<Vec<Scan>, sqlx::Error> {
-        sqlx::query_as::<_, Scan>("SELECT * FROM scans ORDER BY created_at DESC LIMIT ?")
-            .bind(limit)
-            .fetch_all(pool)
+    /// Like [`Scan::find_by_id`], but also finds deleted scans
+    pub async fn find_by_id_including_deleted(
+        pool: &SqlitePool,
+        id: &str,
+    ) -> Result<Option<Scan>, sqlx::Error> {
+        sqlx::query_as::<_, Scan>("SELECT * FROM scans WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
             .await
     }
 
+    /// One page of scans matching `filter`, in `sort` order
+    pub async fn list(
+        pool: &SqlitePool,
+        filter: &ScanListFilter,
+        sort: ScanSort,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Scan>, sqlx::Error> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM scans");
+        filter.push_where(&mut query);
+        query
+            .push(" ORDER BY ")
+            .push(sort.order_by())
+            .push(" LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+
+        query.build_query_as::<Scan>().fetch_all(pool).await
+    }
+
+    /// Scans of any of the given normalized repository URLs, oldest first.
+    /// Stored URLs match regardless of case, a trailing slash or `.git`.
+    pub async fn find_by_repositories(
+        pool: &SqlitePool,
+        repositories: &[String],
+    ) -> Result<Vec<Scan>, sqlx::Error> {
+        Self::select_by_repositories(pool, repositories, false).await
+    }
+
+    /// Like [`Scan::find_by_repositories`], but also finds deleted scans
+    pub async fn find_by_repositories_including_deleted(
+        pool: &SqlitePool,
+        repositories: &[String],
+    ) -> Result<Vec<Scan>, sqlx::Error> {
+        Self::select_by_repositories(pool, repositories, true).await
+    }
+
+    async fn select_by_repositories(
+        pool: &SqlitePool,
+        repositories: &[String],
+        include_deleted: bool,
+    ) -> Result<Vec<Scan>, sqlx::Error> {
+        if repositories.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM scans WHERE ");
+        if !include_deleted {
+            query.push("deleted_at IS NULL AND ");
+        }
+        push_repository_filter(&mut query, repositories);
+        // created_at has second precision, rowid keeps insertion order
+        query.push(" ORDER BY created_at, rowid");
+
+        query.build_query_as::<Scan>().fetch_all(pool).await
+    }
+
+    /// Number of scans matching `filter`, for pagination
+    pub async fn count(pool: &SqlitePool, filter: &ScanListFilter) -> Result<i64, sqlx::Error> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM scans");
+        filter.push_where(&mut query);
+
+        query.build_query_scalar::<i64>().fetch_one(pool).await
+    }
+
+    /// Record what kind of error failed the scan, see
+    /// [`crate::telemetry::error_class`]
+    pub async fn set_error_class(
+        pool: &SqlitePool,
+        id: &str,
+        error_class: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET error_class = ? WHERE id = ?")
+            .bind(error_class)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn update_status(
         pool: &SqlitePool,
         id: &str,
@@ -182,8 +368,10 @@ impl Scan {
             r#"
             UPDATE scans
             SET status = CASE
-                -- If both completed, overall is completed
-                WHEN fossology_status = 'completed' AND semgrep_status = 'completed' THEN 'completed'
+                -- If both completed, overall is completed (quick scans skip Fossology,
+                -- partial re-runs skip the scanners that completed before)
+                WHEN (fossology_status IN ('completed', 'skipped') OR quick)
+                    AND semgrep_status IN ('completed', 'skipped') THEN 'completed'
                 -- If either failed, overall is failed
                 WHEN fossology_status = 'failed' OR semgrep_status = 'failed' THEN 'failed'
                 -- If at least one is in progress, overall is in progress
@@ -192,7 +380,8 @@ impl Scan {
                 ELSE 'pending'
             END,
             completed_at = CASE
-                WHEN fossology_status = 'completed' AND semgrep_status = 'completed'
+                WHEN (fossology_status IN ('completed', 'skipped') OR quick)
+                    AND semgrep_status IN ('completed', 'skipped')
                 THEN datetime('now')
                 ELSE completed_at
             END
@@ -206,26 +395,236 @@ impl Scan {
         Ok(())
     }
 
-    /// Update risk assessment for a scan
+    /// Set the Semgrep rulesets used for this scan instead of the configured defaults
+    pub async fn set_semgrep_rulesets(
+        pool: &SqlitePool,
+        id: &str,
+        rulesets: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let rulesets_json = serde_json::to_string(rulesets).unwrap_or_default();
+
+        sqlx::query("UPDATE scans SET semgrep_rulesets = ? WHERE id = ?")
+            .bind(rulesets_json)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_warnings(
+        pool: &SqlitePool,
+        id: &str,
+        warnings: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let warnings_json = serde_json::to_string(warnings).unwrap_or_default();
+
+        sqlx::query("UPDATE scans SET warnings = ? WHERE id = ?")
+            .bind(warnings_json)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Warnings recorded for this scan
+    pub fn warning_list(&self) -> Vec<String> {
+        self.warnings
+            .as_deref()
+            .and_then(|w| serde_json::from_str(w).ok())
+            .unwrap_or_default()
+    }
+
+    /// Link a scan to the scan it re-runs
+    pub async fn set_parent(
+        pool: &SqlitePool,
+        id: &str,
+        parent_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET parent_scan_id = ? WHERE id = ?")
+            .bind(parent_id)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark a scan as a quick preview scan that skips Fossology
+    pub async fn set_quick(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET quick = 1 WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark a scan as a due-diligence scan that also walks the git history
+    pub async fn set_due_diligence(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET due_diligence = 1 WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Also attribute the scanned files to their commit authors
+    pub async fn set_git_authorship(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET git_authorship = 1 WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Scan a branch other than the repository's default branch
+    pub async fn set_branch(pool: &SqlitePool, id: &str, branch: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET branch = ? WHERE id = ?")
+            .bind(branch)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Check out `commit` instead of the head of the scan's branch
+    pub async fn set_commit(pool: &SqlitePool, id: &str, commit: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET commit_sha = ? WHERE id = ?")
+            .bind(commit)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Check out submodules too, with the encrypted token instead of the
+    /// scan's git token when given
+    pub async fn set_submodules(
+        pool: &SqlitePool,
+        id: &str,
+        token_encrypted: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET submodules = 1, submodule_token_encrypted = ? WHERE id = ?")
+            .bind(token_encrypted)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Clone with a registered credential instead of the scan's git token
+    pub async fn set_credential(
+        pool: &SqlitePool,
+        id: &str,
+        credential_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET credential_id = ? WHERE id = ?")
+            .bind(credential_id)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_scan_path(
+        pool: &SqlitePool,
+        id: &str,
+        scan_path: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET scan_path = ? WHERE id = ?")
+            .bind(scan_path)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Set the URL notified when the scan completes or fails
+    pub async fn set_callback_url(
+        pool: &SqlitePool,
+        id: &str,
+        callback_url: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET callback_url = ? WHERE id = ?")
+            .bind(callback_url)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark a scan as approved. Returns false if it was already approved.
+    pub async fn approve(pool: &SqlitePool, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE scans SET approved_at = datetime('now') WHERE id = ? AND approved_at IS NULL",
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark a scan as reviewed. Returns false if it was already reviewed.
+    pub async fn mark_reviewed(pool: &SqlitePool, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE scans SET reviewed_at = datetime('now') WHERE id = ? AND reviewed_at IS NULL",
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Withdraw the reviewed mark, e.g. after a finding went back to pending
+    pub async fn clear_reviewed(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE scans SET reviewed_at = NULL WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Update risk assessment for a scan, with the versions of the scoring
+    /// algorithm and risk configuration it was made with
     pub async fn update_risk_assessment(
         pool: &SqlitePool,
         id: &str,
         risk_score: i32,
         risk_level: &str,
         risk_factors_json: &str,
+        algorithm_version: i32,
+        config_version: &str,
     ) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
             UPDATE scans
             SET risk_score = ?,
                 risk_level = ?,
-                risk_factors = ?
+                risk_factors = ?,
+                risk_algorithm_version = ?,
+                risk_config_version = ?,
+                risk_assessed_at = datetime('now')
             WHERE id = ?
             "#,
         )
         .bind(risk_score)
         .bind(risk_level)
         .bind(risk_factors_json)
+        .bind(algorithm_version)
+        .bind(config_version)
         .bind(id)
         .execute(pool)
         .await?;
@@ -233,8 +632,10 @@ impl Scan {
         Ok(())
     }
 
+    /// Mark the scan deleted. It and its results are kept, hidden, until
+    /// [`Scan::restore`] or [`Scan::delete_permanently`].
     pub async fn delete(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
-        sqlx::query("DELETE FROM scans WHERE id = ?")
+        sqlx::query("UPDATE scans SET deleted_at = datetime('now') WHERE id = ? AND deleted_at IS NULL")
             .bind(id)
             .execute(pool)
             .await?;
@@ -242,16 +643,474 @@ impl Scan {
         Ok(())
     }
 
-    pub async fn delete_all(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
-        let result = sqlx::query("DELETE FROM scans")
+    /// Remove the scan row for good, its results must be deleted first
+    pub async fn delete_permanently(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM scans WHERE id = ?")
+            .bind(id)
             .execute(pool)
             .await?;
 
-        Ok(result.rows_affected())
+        Ok(())
+    }
+
+    /// Undo [`Scan::delete`]. Returns `None` if there is no deleted scan
+    /// with the ID.
+    pub async fn restore(pool: &SqlitePool, id: &str) -> Result<Option<Scan>, sqlx::Error> {
+        sqlx::query_as::<_, Scan>(
+            "UPDATE scans SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL RETURNING *",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Mark every scan deleted except those under legal hold and those of
+    /// the given held (normalized) repositories
+    pub async fn delete_all(
+        pool: &SqlitePool,
+        held_repositories: &[String],
+    ) -> Result<u64, sqlx::Error> {
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "UPDATE scans SET deleted_at = datetime('now') WHERE deleted_at IS NULL AND legal_hold_at IS NULL",
+        );
+        if !held_repositories.is_empty() {
+            query.push(" AND NOT ");
+            push_repository_filter(&mut query, held_repositories);
+        }
+
+        Ok(query.build().execute(pool).await?.rows_affected())
+    }
+
+    /// Finished scans that completed (or were created, if they never did)
+    /// before `cutoff`, oldest first
+    pub async fn find_finished_before(
+        pool: &SqlitePool,
+        cutoff: &str,
+    ) -> Result<Vec<Scan>, sqlx::Error> {
+        sqlx::query_as::<_, Scan>(
+            r#"
+            SELECT * FROM scans
+            WHERE status IN ('completed', 'failed')
+              AND COALESCE(completed_at, created_at) < ?
+            ORDER BY created_at, rowid
+            "#,
+        )
+        .bind(cutoff)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Finished scans that completed (or were created, if they never did)
+    /// before `cutoff` and whose findings are still in the database, oldest
+    /// first
+    pub async fn find_archivable(
+        pool: &SqlitePool,
+        cutoff: &str,
+    ) -> Result<Vec<Scan>, sqlx::Error> {
+        sqlx::query_as::<_, Scan>(
+            r#"
+            SELECT * FROM scans
+            WHERE status IN ('completed', 'failed')
+              AND COALESCE(completed_at, created_at) < ?
+              AND results_archived_at IS NULL AND results_purged_at IS NULL
+            ORDER BY created_at, rowid
+            "#,
+        )
+        .bind(cutoff)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Completed scans whose findings can be rescored. With `outdated_only`,
+    /// only those without a risk assessment or assessed with other versions
+    /// of the scoring algorithm or risk configuration.
+    pub async fn find_for_rescoring(
+        pool: &SqlitePool,
+        algorithm_version: i32,
+        config_version: &str,
+        outdated_only: bool,
+    ) -> Result<Vec<Scan>, sqlx::Error> {
+        sqlx::query_as::<_, Scan>(
+            r#"
+            SELECT * FROM scans
+            WHERE status = 'completed' AND deleted_at IS NULL AND results_purged_at IS NULL
+              AND (
+                  ? = 0 OR risk_score IS NULL
+                  OR risk_algorithm_version IS NOT ? OR risk_config_version IS NOT ?
+              )
+            ORDER BY completed_at, rowid
+            "#,
+        )
+        .bind(outdated_only)
+        .bind(algorithm_version)
+        .bind(config_version)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Key of the archive holding the findings of the scan, `None` while
+    /// they are in the database
+    pub async fn results_archive_key(
+        pool: &SqlitePool,
+        id: &str,
+    ) -> Result<Option<String>, sqlx::Error> {
+        Ok(
+            sqlx::query_scalar::<_, Option<String>>("SELECT results_archive_key FROM scans WHERE id = ?")
+                .bind(id)
+                .fetch_optional(pool)
+                .await?
+                .flatten(),
+        )
+    }
+
+    /// IDs of the scans whose findings are in the result store, neither
+    /// archived nor purged
+    pub async fn ids_with_stored_results(pool: &SqlitePool) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT id FROM scans WHERE results_archive_key IS NULL AND results_purged_at IS NULL ORDER BY rowid",
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// IDs and archive keys of the scans whose findings are archived
+    pub async fn archived_results(pool: &SqlitePool) -> Result<Vec<(String, String)>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT id, results_archive_key FROM scans WHERE results_archive_key IS NOT NULL ORDER BY rowid",
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record that the findings of the scan were archived under `key`, with
+    /// the summary taken before, and remove them from the database. Their
+    /// review comments stay.
+    pub async fn mark_results_archived(
+        pool: &SqlitePool,
+        id: &str,
+        key: &str,
+        summary: &ScanSummary,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let table = result_partition::table_for(&mut *tx, id).await?;
+        sqlx::query(&format!("DELETE FROM {} WHERE scan_id = ?", table))
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            UPDATE scans
+            SET results_archived_at = datetime('now'), results_archive_key = ?, results_summary = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(key)
+        .bind(serde_json::to_string(summary).unwrap_or_default())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// Forget the archive of the scan once its findings were deleted from it
+    pub async fn clear_results_archive(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE scans SET results_archived_at = NULL, results_archive_key = NULL WHERE id = ?",
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store a scan exported from another instance under its own ID, with
+    /// the summary kept for it if its findings were purged. Clone tokens,
+    /// credentials, API keys and legal holds belong to the other instance and
+    /// are not taken over; the parent scan is kept if it was imported too.
+    pub async fn import(
+        pool: &SqlitePool,
+        scan: &Scan,
+        summary: Option<&ScanSummary>,
+    ) -> Result<Scan, sqlx::Error> {
+        sqlx::query_as::<_, Scan>(
+            r#"
+            INSERT INTO scans (
+                id, git_url, status, error_message, created_at, started_at, completed_at,
+                fossology_status, semgrep_status, fossology_started_at, fossology_completed_at,
+                semgrep_started_at, semgrep_completed_at, fossology_error, semgrep_error,
+                risk_score, risk_level, risk_factors, semgrep_rulesets, quick, approved_at,
+                reviewed_at, warnings, parent_scan_id, due_diligence, callback_url, scan_path,
+                branch, commit_sha, submodules, git_authorship, results_purged_at, results_summary
+            )
+            VALUES (
+                ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                (SELECT id FROM scans WHERE id = ?), ?, ?, ?, ?, ?, ?, ?, ?, ?
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(&scan.id)
+        .bind(&scan.git_url)
+        .bind(&scan.status)
+        .bind(&scan.error_message)
+        .bind(&scan.created_at)
+        .bind(&scan.started_at)
+        .bind(&scan.completed_at)
+        .bind(&scan.fossology_status)
+        .bind(&scan.semgrep_status)
+        .bind(&scan.fossology_started_at)
+        .bind(&scan.fossology_completed_at)
+        .bind(&scan.semgrep_started_at)
+        .bind(&scan.semgrep_completed_at)
+        .bind(&scan.fossology_error)
+        .bind(&scan.semgrep_error)
+        .bind(scan.risk_score)
+        .bind(&scan.risk_level)
+        .bind(&scan.risk_factors)
+        .bind(&scan.semgrep_rulesets)
+        .bind(scan.quick)
+        .bind(&scan.approved_at)
+        .bind(&scan.reviewed_at)
+        .bind(&scan.warnings)
+        .bind(&scan.parent_scan_id)
+        .bind(scan.due_diligence)
+        .bind(&scan.callback_url)
+        .bind(&scan.scan_path)
+        .bind(&scan.branch)
+        .bind(&scan.commit_sha)
+        .bind(scan.submodules)
+        .bind(scan.git_authorship)
+        .bind(&scan.results_purged_at)
+        .bind(summary.map(|s| serde_json::to_string(s).unwrap_or_default()))
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record that retention purged the findings of a scan, keeping their
+    /// `summary`, and drop the per-file details that go with them: history
+    /// findings, commit authors and SWHIDs
+    pub async fn mark_results_purged(
+        pool: &SqlitePool,
+        id: &str,
+        summary: &ScanSummary,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        for table in ["scan_history_findings", "scan_file_authors", "scan_file_swhids"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE scan_id = ?", table))
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query(
+            r#"
+            UPDATE scans
+            SET results_purged_at = datetime('now'), results_summary = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(serde_json::to_string(summary).unwrap_or_default())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// Place a legal hold with `Some(reason)`, or lift it with `None`.
+    /// Returns `None` if the scan does not exist.
+    pub async fn set_legal_hold(
+        pool: &SqlitePool,
+        id: &str,
+        reason: Option<&str>,
+    ) -> Result<Option<Scan>, sqlx::Error> {
+        sqlx::query_as::<_, Scan>(
+            r#"
+            UPDATE scans
+            SET legal_hold_at = CASE WHEN ?1 IS NULL THEN NULL ELSE datetime('now') END,
+                legal_hold_reason = ?1
+            WHERE id = ?2
+            RETURNING *
+            "#,
+        )
+        .bind(reason)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Assign a license policy to the scan, or remove it with `None`.
+    /// Returns `None` if the scan does not exist.
+    pub async fn set_policy(
+        pool: &SqlitePool,
+        id: &str,
+        policy: Option<&str>,
+    ) -> Result<Option<Scan>, sqlx::Error> {
+        sqlx::query_as::<_, Scan>(
+            "UPDATE scans SET policy = ? WHERE id = ? AND deleted_at IS NULL RETURNING *",
+        )
+        .bind(policy)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Queue depth and recent throughput, used by autoscaling metrics
+    pub async fn queue_stats(pool: &SqlitePool, window_minutes: i64) -> Result<QueueStats, sqlx::Error> {
+        sqlx::query_as::<_, QueueStats>(
+            r#"
+            SELECT
+                COUNT(CASE WHEN status = 'pending' THEN 1 END) as pending,
+                COUNT(CASE WHEN status = 'in_progress' THEN 1 END) as in_progress,
+                COUNT(CASE WHEN status = 'completed'
+                    AND completed_at >= datetime('now', '-' || ? || ' minutes') THEN 1 END) as completed_in_window,
+                COUNT(CASE WHEN status = 'failed'
+                    AND completed_at >= datetime('now', '-' || ? || ' minutes') THEN 1 END) as failed_in_window
+            FROM scans
+            "#,
+        )
+        .bind(window_minutes)
+        .bind(window_minutes)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Completed full scans per risk level, optionally limited to scans
+    /// completed on or after `since`. Unscored scans are reported as `unassessed`.
+    pub async fn risk_level_counts(
+        pool: &SqlitePool,
+        since: Option<&str>,
+    ) -> Result<Vec<RiskLevelCount>, sqlx::Error> {
+        sqlx::query_as::<_, RiskLevelCount>(
+            r#"
+            SELECT COALESCE(risk_level, 'unassessed') as risk_level, COUNT(*) as scan_count
+            FROM scans
+            WHERE status = 'completed' AND NOT quick AND deleted_at IS NULL
+                AND (? IS NULL OR completed_at >= ?)
+            GROUP BY 1
+            "#,
+        )
+        .bind(since)
+        .bind(since)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// IDs and completion days (YYYY-MM-DD) of the completed scans, only
+    /// full scans when `full_only`, and only those completed on or after
+    /// `since` when given
+    pub async fn completed_days(
+        pool: &SqlitePool,
+        since: Option<&str>,
+        full_only: bool,
+    ) -> Result<Vec<(String, Option<String>)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT id, date(completed_at)
+            FROM scans
+            WHERE status = 'completed' AND deleted_at IS NULL
+                AND NOT (? AND quick)
+                AND (? IS NULL OR completed_at >= ?)
+            ORDER BY completed_at, id
+            "#,
+        )
+        .bind(full_only)
+        .bind(since)
+        .bind(since)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Number of scans in each status
+    pub async fn status_counts(pool: &SqlitePool) -> Result<Vec<ScanStatusCount>, sqlx::Error> {
+        sqlx::query_as::<_, ScanStatusCount>(
+            "SELECT status, COUNT(*) as scan_count FROM scans WHERE deleted_at IS NULL GROUP BY status ORDER BY status",
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Average seconds from start to completion of completed scans,
+    /// `None` before the first scan completes
+    pub async fn average_duration_secs(pool: &SqlitePool) -> Result<Option<f64>, sqlx::Error> {
+        sqlx::query_scalar::<_, Option<f64>>(
+            r#"
+            SELECT AVG((julianday(completed_at) - julianday(started_at)) * 86400.0)
+            FROM scans
+            WHERE status = 'completed' AND started_at IS NOT NULL AND completed_at IS NOT NULL
+                AND deleted_at IS NULL
+            "#,
+        )
+        .fetch_one(pool)
+        .await
     }
 
+    /// Repositories per risk level of their latest completed full scan.
+    /// URLs differing only in case, a trailing slash or `.git` are one
+    /// repository; unscored scans are reported as `unassessed`.
+    pub async fn repository_risk_level_counts(
+        pool: &SqlitePool,
+    ) -> Result<Vec<RepositoryRiskLevelCount>, sqlx::Error> {
+        sqlx::query_as::<_, RepositoryRiskLevelCount>(
+            r#"
+            WITH normalized AS (
+                SELECT risk_level, completed_at, created_at,
+                    lower(rtrim(git_url, '/')) as url
+                FROM scans
+                WHERE status = 'completed' AND NOT quick AND deleted_at IS NULL
+            ),
+            latest AS (
+                SELECT
+                    risk_level,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY CASE WHEN url LIKE '%.git' THEN substr(url, 1, length(url) - 4) ELSE url END
+                        ORDER BY completed_at DESC, created_at DESC
+                    ) as scan_rank
+                FROM normalized
+            )
+            SELECT COALESCE(risk_level, 'unassessed') as risk_level, COUNT(*) as repository_count
+            FROM latest
+            WHERE scan_rank = 1
+            GROUP BY 1
+            ORDER BY repository_count DESC, risk_level
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The summary taken before the findings of the scan were purged by
+    /// retention or archived, if they were
+    pub async fn kept_summary(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Option<ScanSummary>, sqlx::Error> {
+        let kept: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT results_summary FROM scans
+            WHERE id = ? AND (results_purged_at IS NOT NULL OR results_archived_at IS NOT NULL)
+            "#,
+        )
+        .bind(scan_id)
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+        Ok(kept.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// Summary of the findings of the scan in the `scan_results` table. Use
+    /// [`crate::storage::ResultStore::summarize`] for findings stored
+    /// elsewhere.
     pub async fn get_summary(pool: &SqlitePool, scan_id: &str) -> Result<ScanSummary, sqlx::Error> {
-        let summary = sqlx::query_as::<_, ScanSummary>(
+        if let Some(summary) = Self::kept_summary(pool, scan_id).await? {
+            return Ok(summary);
+        }
+
+        let source = result_partition::source_for(pool, scan_id).await?;
+        let summary = sqlx::query_as::<_, ScanSummary>(&format!(
             r#"
             SELECT
                 COUNT(DISTINCT CASE WHEN result_type = 'license' THEN file_path END) as files_with_licenses,
@@ -259,10 +1118,11 @@ impl Scan {
                 COUNT(DISTINCT CASE WHEN result_type = 'license' THEN license_name END) as unique_licenses,
                 COUNT(DISTINCT CASE WHEN result_type = 'copyright' THEN copyright_statement END) as unique_copyrights,
                 COUNT(DISTINCT file_path) as total_files
-            FROM scan_results
+            FROM {}
             WHERE scan_id = ?
             "#,
-        )
+            source
+        ))
         .bind(scan_id)
         .fetch_one(pool)
         .await?;
@@ -271,7 +1131,32 @@ impl Scan {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+/// `lower(git_url) IN (...)` matching any of the normalized repository URLs
+/// regardless of case, a trailing slash or `.git`
+fn push_repository_filter(query: &mut QueryBuilder<'_, Sqlite>, repositories: &[String]) {
+    query.push("lower(git_url) IN (");
+    let mut candidates = query.separated(", ");
+    for repository in repositories {
+        for suffix in ["", "/", ".git", ".git/"] {
+            candidates.push_bind(format!("{}{}", repository, suffix));
+        }
+    }
+    query.push(")");
+}
+
+/// Pending or in-progress scans of the repository and branch
+fn push_running_filter<'a>(
+    query: &mut QueryBuilder<'a, Sqlite>,
+    repository: &[String],
+    branch: &'a Option<String>,
+) {
+    query.push("deleted_at IS NULL AND status IN ('pending', 'in_progress') AND branch IS ");
+    query.push_bind(branch);
+    query.push(" AND ");
+    push_repository_filter(query, repository);
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct ScanSummary {
     pub total_files: i64,
     pub files_with_licenses: i64,
@@ -279,3 +1164,157 @@ pub struct ScanSummary {
     pub unique_licenses: i64,
     pub unique_copyrights: i64,
 }
+
+impl ScanSummary {
+    /// The summary [`Scan::get_summary`] computes in SQL, from loaded
+    /// findings
+    pub fn from_results(results: &[ScanResult]) -> Self {
+        let count = |values: HashSet<&str>| values.len() as i64;
+        let of_type = |result_type: &'static str| {
+            results.iter().filter(move |r| r.result_type == result_type)
+        };
+        Self {
+            total_files: count(results.iter().map(|r| r.file_path.as_str()).collect()),
+            files_with_licenses: count(of_type("license").map(|r| r.file_path.as_str()).collect()),
+            files_with_copyrights: count(
+                of_type("copyright")
+                    .map(|r| r.file_path.as_str())
+                    .collect(),
+            ),
+            unique_licenses: count(
+                of_type("license")
+                    .filter_map(|r| r.license_name.as_deref())
+                    .collect(),
+            ),
+            unique_copyrights: count(
+                of_type("copyright")
+                    .filter_map(|r| r.copyright_statement.as_deref())
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct QueueStats {
+    pub pending: i64,
+    pub in_progress: i64,
+    pub completed_in_window: i64,
+    pub failed_in_window: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct RiskLevelCount {
+    pub risk_level: String,
+    pub scan_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScanStatusCount {
+    pub status: String,
+    pub scan_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct RepositoryRiskLevelCount {
+    pub risk_level: String,
+    pub repository_count: i64,
+}
+
+/// Criteria for listing scans. Timestamps use the stored
+/// `YYYY-MM-DD HH:MM:SS` format; `created_before` is exclusive.
+#[derive(Debug, Default)]
+pub struct ScanListFilter {
+    pub status: Option<String>,
+    pub git_url_contains: Option<String>,
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+}
+
+impl ScanListFilter {
+    fn push_where<'a>(&'a self, query: &mut QueryBuilder<'a, Sqlite>) {
+        query.push(" WHERE deleted_at IS NULL");
+        if let Some(status) = &self.status {
+            query.push(" AND status = ").push_bind(status);
+        }
+        if let Some(fragment) = &self.git_url_contains {
+            // instr() rather than LIKE so `%` and `_` match literally
+            query
+                .push(" AND instr(lower(git_url), lower(")
+                .push_bind(fragment)
+                .push(")) > 0");
+        }
+        if let Some(after) = &self.created_after {
+            query.push(" AND created_at >= ").push_bind(after);
+        }
+        if let Some(before) = &self.created_before {
+            query.push(" AND created_at < ").push_bind(before);
+        }
+    }
+}
+
+/// Sort order for listing scans, written as a field name with an optional
+/// `-` prefix for descending order (e.g. `-risk_score`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScanSort {
+    CreatedAtAsc,
+    #[default]
+    CreatedAtDesc,
+    CompletedAtAsc,
+    CompletedAtDesc,
+    RiskScoreAsc,
+    RiskScoreDesc,
+    StatusAsc,
+    StatusDesc,
+    GitUrlAsc,
+    GitUrlDesc,
+}
+
+impl ScanSort {
+    /// ORDER BY clause; ties fall back to the newest scan first
+    fn order_by(self) -> &'static str {
+        match self {
+            ScanSort::CreatedAtAsc => "created_at ASC, id",
+            ScanSort::CreatedAtDesc => "created_at DESC, id",
+            ScanSort::CompletedAtAsc => "completed_at IS NULL, completed_at ASC, created_at DESC",
+            ScanSort::CompletedAtDesc => "completed_at IS NULL, completed_at DESC, created_at DESC",
+            ScanSort::RiskScoreAsc => "risk_score IS NULL, risk_score ASC, created_at DESC",
+            ScanSort::RiskScoreDesc => "risk_score IS NULL, risk_score DESC, created_at DESC",
+            ScanSort::StatusAsc => "status ASC, created_at DESC",
+            ScanSort::StatusDesc => "status DESC, created_at DESC",
+            ScanSort::GitUrlAsc => "git_url ASC, created_at DESC",
+            ScanSort::GitUrlDesc => "git_url DESC, created_at DESC",
+        }
+    }
+}
+
+impl FromStr for ScanSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (field, descending) = match s.strip_prefix('-') {
+            Some(field) => (field, true),
+            None => (s, false),
+        };
+        let sort = match (field, descending) {
+            ("created_at", false) => ScanSort::CreatedAtAsc,
+            ("created_at", true) => ScanSort::CreatedAtDesc,
+            ("completed_at", false) => ScanSort::CompletedAtAsc,
+            ("completed_at", true) => ScanSort::CompletedAtDesc,
+            ("risk_score", false) => ScanSort::RiskScoreAsc,
+            ("risk_score", true) => ScanSort::RiskScoreDesc,
+            ("status", false) => ScanSort::StatusAsc,
+            ("status", true) => ScanSort::StatusDesc,
+            ("git_url", false) => ScanSort::GitUrlAsc,
+            ("git_url", true) => ScanSort::GitUrlDesc,
+            _ => {
+                return Err(format!(
+                    "Invalid sort '{}'. Must be one of created_at, completed_at, risk_score, status or git_url, optionally prefixed with '-'",
+                    s
+                ))
+            }
+        };
+        Ok(sort)
+    }
+}
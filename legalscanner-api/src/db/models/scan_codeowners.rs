@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// CODEOWNERS file of a scanned revision, see [`crate::codeowners`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScanCodeOwners {
+    pub scan_id: String,
+    pub source_path: String, // location in the repository
+    pub content: String,
+    pub created_at: String,
+}
+
+impl ScanCodeOwners {
+    pub async fn upsert(
+        pool: &SqlitePool,
+        scan_id: &str,
+        source_path: &str,
+        content: &str,
+    ) -> Result<ScanCodeOwners, sqlx::Error> {
+        sqlx::query_as::<_, ScanCodeOwners>(
+            r#"
+            INSERT INTO scan_codeowners (scan_id, source_path, content)
+            VALUES (?, ?, ?)
+            ON CONFLICT(scan_id) DO UPDATE SET
+                source_path = excluded.source_path,
+                content = excluded.content,
+                created_at = datetime('now')
+            RETURNING *
+            "#,
+        )
+        .bind(scan_id)
+        .bind(source_path)
+        .bind(content)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Option<ScanCodeOwners>, sqlx::Error> {
+        sqlx::query_as::<_, ScanCodeOwners>("SELECT * FROM scan_codeowners WHERE scan_id = ?")
+            .bind(scan_id)
+            .fetch_optional(pool)
+            .await
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScanEvent {
+    pub id: i64,
+    pub scan_id: String,
+    pub event_type: String, // created, queued, clone_started, scanner_finished, ...
+    pub scanner: Option<String>, // Scanner name for scanner_* events
+    pub detail: Option<String>,
+    pub occurred_at: String, // YYYY-MM-DD HH:MM:SS.SSS (UTC)
+}
+
+impl ScanEvent {
+    /// Store an event, returning its ID
+    pub async fn record(
+        pool: &SqlitePool,
+        scan_id: &str,
+        event_type: &str,
+        scanner: Option<&str>,
+        detail: Option<&str>,
+    ) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO scan_events (scan_id, event_type, scanner, detail) VALUES (?, ?, ?, ?)",
+        )
+        .bind(scan_id)
+        .bind(event_type)
+        .bind(scanner)
+        .bind(detail)
+        .execute(pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Store an event exported from another instance, keeping when it
+    /// occurred
+    pub async fn restore(pool: &SqlitePool, event: &ScanEvent) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO scan_events (scan_id, event_type, scanner, detail, occurred_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&event.scan_id)
+        .bind(&event.event_type)
+        .bind(&event.scanner)
+        .bind(&event.detail)
+        .bind(&event.occurred_at)
+        .execute(pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Events for a scan in the order they were recorded
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Vec<ScanEvent>, sqlx::Error> {
+        sqlx::query_as::<_, ScanEvent>("SELECT * FROM scan_events WHERE scan_id = ? ORDER BY id")
+            .bind(scan_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Number of events of each type and scanner recorded in `[from, to)`
+    pub async fn count_between(
+        pool: &SqlitePool,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<(String, Option<String>, i64)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT event_type, scanner, COUNT(*)
+            FROM scan_events
+            WHERE occurred_at >= ? AND occurred_at < ?
+            GROUP BY event_type, scanner
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Scans that failed in `[from, to)` by the class of their error,
+    /// `unknown` for failures recorded before errors were classified
+    pub async fn count_failures_by_class(
+        pool: &SqlitePool,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT COALESCE(s.error_class, 'unknown'), COUNT(*)
+            FROM scan_events e
+            JOIN scans s ON s.id = e.scan_id
+            WHERE e.event_type = 'failed' AND e.occurred_at >= ? AND e.occurred_at < ?
+            GROUP BY 1
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+    }
+}
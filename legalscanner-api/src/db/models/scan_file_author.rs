@@ -0,0 +1,100 @@
+use crate::scanner::authorship::FileAuthor;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+/// Commit author of a file, see [`crate::scanner::authorship`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScanFileAuthor {
+    #[serde(skip_serializing)]
+    pub id: i64,
+    #[serde(skip_serializing)]
+    pub scan_id: String,
+    pub file_path: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub commit_count: i64,
+    pub first_commit_sha: String,
+    pub first_commit_date: String,
+    pub last_commit_sha: String,
+    pub last_commit_date: String,
+}
+
+impl ScanFileAuthor {
+    /// Replace the file authors stored for a scan
+    pub async fn store(
+        pool: &SqlitePool,
+        scan_id: &str,
+        authors: &[FileAuthor],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM scan_file_authors WHERE scan_id = ?")
+            .bind(scan_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for author in authors {
+            sqlx::query(
+                r#"
+                INSERT INTO scan_file_authors
+                    (scan_id, file_path, author_name, author_email, commit_count,
+                     first_commit_sha, first_commit_date, last_commit_sha, last_commit_date)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(scan_id)
+            .bind(&author.file_path)
+            .bind(&author.author_name)
+            .bind(&author.author_email)
+            .bind(author.commit_count)
+            .bind(&author.first_commit_sha)
+            .bind(&author.first_commit_date)
+            .bind(&author.last_commit_sha)
+            .bind(&author.last_commit_date)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// File authors of a scan by path, the most active author of a file first
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Vec<ScanFileAuthor>, sqlx::Error> {
+        sqlx::query_as::<_, ScanFileAuthor>(
+            r#"
+            SELECT * FROM scan_file_authors WHERE scan_id = ?
+            ORDER BY file_path, commit_count DESC, author_name
+            "#,
+        )
+        .bind(scan_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Names recorded for author addresses containing `text` (lowercase)
+    pub async fn names_containing(
+        pool: &SqlitePool,
+        text: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT DISTINCT author_name FROM scan_file_authors WHERE instr(author_email, ?) > 0",
+        )
+        .bind(text)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Delete file authors whose address contains `text` (lowercase)
+    pub async fn delete_containing(pool: &SqlitePool, text: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM scan_file_authors WHERE instr(author_email, ?) > 0")
+            .bind(text)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,67 @@
+use crate::swhid::FileSwhid;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+/// Software Heritage identifier of a scanned file, see [`crate::swhid`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScanFileSwhid {
+    #[serde(skip_serializing)]
+    pub id: i64,
+    #[serde(skip_serializing)]
+    pub scan_id: String,
+    pub file_path: String,
+    pub swhid: String,
+    /// Whether the Software Heritage archive holds the content, unset when
+    /// it was not asked
+    pub known: Option<bool>,
+    /// Upstream repository the archive found the content in
+    pub origin: Option<String>,
+}
+
+impl ScanFileSwhid {
+    /// Replace the SWHIDs stored for a scan
+    pub async fn store(
+        pool: &SqlitePool,
+        scan_id: &str,
+        swhids: &[FileSwhid],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM scan_file_swhids WHERE scan_id = ?")
+            .bind(scan_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for file in swhids {
+            sqlx::query(
+                r#"
+                INSERT INTO scan_file_swhids (scan_id, file_path, swhid, known, origin)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(scan_id)
+            .bind(&file.file_path)
+            .bind(&file.swhid)
+            .bind(file.known)
+            .bind(&file.origin)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// SWHIDs of a scan by path
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Vec<ScanFileSwhid>, sqlx::Error> {
+        sqlx::query_as::<_, ScanFileSwhid>(
+            "SELECT * FROM scan_file_swhids WHERE scan_id = ? ORDER BY file_path",
+        )
+        .bind(scan_id)
+        .fetch_all(pool)
+        .await
+    }
+}
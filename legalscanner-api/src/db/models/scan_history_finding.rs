@@ -0,0 +1,85 @@
+use crate::scanner::history::HistoryFinding;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+/// Finding of a due-diligence history scan, see [`crate::scanner::history`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScanHistoryFinding {
+    #[serde(skip_serializing)]
+    pub id: i64,
+    #[serde(skip_serializing)]
+    pub scan_id: String,
+    pub kind: String, // deleted_file, license_change, removed_third_party
+    pub file_path: String,
+    pub commit_sha: String,
+    pub commit_date: String,
+    pub license: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl ScanHistoryFinding {
+    /// Replace the history findings stored for a scan
+    pub async fn store(
+        pool: &SqlitePool,
+        scan_id: &str,
+        findings: &[HistoryFinding],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM scan_history_findings WHERE scan_id = ?")
+            .bind(scan_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for finding in findings {
+            sqlx::query(
+                r#"
+                INSERT INTO scan_history_findings
+                    (scan_id, kind, file_path, commit_sha, commit_date, license, detail)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(scan_id)
+            .bind(finding.kind.as_str())
+            .bind(&finding.file_path)
+            .bind(&finding.commit_sha)
+            .bind(&finding.commit_date)
+            .bind(&finding.license)
+            .bind(&finding.detail)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// History findings of a scan, newest commit first
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Vec<ScanHistoryFinding>, sqlx::Error> {
+        sqlx::query_as::<_, ScanHistoryFinding>(
+            "SELECT * FROM scan_history_findings WHERE scan_id = ? ORDER BY commit_date DESC, id",
+        )
+        .bind(scan_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Delete history findings whose path or detail contains `text` (lowercase)
+    pub async fn delete_containing(pool: &SqlitePool, text: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM scan_history_findings
+            WHERE instr(lower(file_path), ?1) > 0
+               OR instr(lower(coalesce(detail, '')), ?1) > 0
+            "#,
+        )
+        .bind(text)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
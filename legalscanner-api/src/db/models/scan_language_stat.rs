@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Files of one language in a scanned checkout, see [`crate::coverage`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScanLanguageStat {
+    pub scan_id: String,
+    pub language: String,
+    pub file_count: i64,
+    pub total_bytes: i64,
+}
+
+impl ScanLanguageStat {
+    /// Store the statistics of a scan, replacing any recorded before
+    pub async fn replace(
+        pool: &SqlitePool,
+        scan_id: &str,
+        stats: &[(String, i64, i64)],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM scan_language_stats WHERE scan_id = ?")
+            .bind(scan_id)
+            .execute(&mut *tx)
+            .await?;
+        for (language, file_count, total_bytes) in stats {
+            sqlx::query(
+                r#"
+                INSERT INTO scan_language_stats (scan_id, language, file_count, total_bytes)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(scan_id)
+            .bind(language)
+            .bind(file_count)
+            .bind(total_bytes)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Vec<ScanLanguageStat>, sqlx::Error> {
+        sqlx::query_as::<_, ScanLanguageStat>(
+            "SELECT * FROM scan_language_stats WHERE scan_id = ? ORDER BY file_count DESC, language",
+        )
+        .bind(scan_id)
+        .fetch_all(pool)
+        .await
+    }
+}
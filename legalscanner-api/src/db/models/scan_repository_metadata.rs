@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Repository metadata of a scan, see [`crate::repo_metadata`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScanRepositoryMetadata {
+    pub scan_id: String,
+    pub provider: Option<String>, // github or gitlab
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub default_branch: Option<String>,
+    pub topics: Option<String>, // JSON array
+    pub latest_tag: Option<String>,
+    pub contributor_count: Option<i64>,
+    pub created_at: String,
+    pub commit_sha: Option<String>,
+    pub commit_date: Option<String>, // RFC 3339, UTC
+    pub commit_author: Option<String>,
+    pub head_tag: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub file_count: Option<i64>,
+}
+
+/// The checked-out commit and working tree, read from the checkout
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitProvenance {
+    pub commit_sha: Option<String>,
+    pub commit_date: Option<String>,
+    /// Author name, without the e-mail address
+    pub commit_author: Option<String>,
+    /// Tag pointing at the commit
+    pub head_tag: Option<String>,
+    /// Files of the working tree, `.git` excluded
+    pub size_bytes: Option<i64>,
+    pub file_count: Option<i64>,
+}
+
+impl ScanRepositoryMetadata {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upsert(
+        pool: &SqlitePool,
+        scan_id: &str,
+        provider: Option<&str>,
+        display_name: Option<&str>,
+        description: Option<&str>,
+        default_branch: Option<&str>,
+        topics: &[String],
+        latest_tag: Option<&str>,
+        contributor_count: Option<i64>,
+        commit: &CommitProvenance,
+    ) -> Result<ScanRepositoryMetadata, sqlx::Error> {
+        let topics = serde_json::to_string(topics).unwrap_or_else(|_| "[]".to_string());
+
+        sqlx::query_as::<_, ScanRepositoryMetadata>(
+            r#"
+            INSERT INTO scan_repository_metadata
+            (scan_id, provider, display_name, description, default_branch, topics, latest_tag, contributor_count,
+             commit_sha, commit_date, commit_author, head_tag, size_bytes, file_count)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(scan_id) DO UPDATE SET
+                provider = excluded.provider,
+                display_name = excluded.display_name,
+                description = excluded.description,
+                default_branch = excluded.default_branch,
+                topics = excluded.topics,
+                latest_tag = excluded.latest_tag,
+                contributor_count = excluded.contributor_count,
+                commit_sha = excluded.commit_sha,
+                commit_date = excluded.commit_date,
+                commit_author = excluded.commit_author,
+                head_tag = excluded.head_tag,
+                size_bytes = excluded.size_bytes,
+                file_count = excluded.file_count,
+                created_at = datetime('now')
+            RETURNING *
+            "#,
+        )
+        .bind(scan_id)
+        .bind(provider)
+        .bind(display_name)
+        .bind(description)
+        .bind(default_branch)
+        .bind(topics)
+        .bind(latest_tag)
+        .bind(contributor_count)
+        .bind(&commit.commit_sha)
+        .bind(&commit.commit_date)
+        .bind(&commit.commit_author)
+        .bind(&commit.head_tag)
+        .bind(commit.size_bytes)
+        .bind(commit.file_count)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Option<ScanRepositoryMetadata>, sqlx::Error> {
+        sqlx::query_as::<_, ScanRepositoryMetadata>(
+            "SELECT * FROM scan_repository_metadata WHERE scan_id = ?",
+        )
+        .bind(scan_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Topics as stored, empty if none were captured
+    pub fn topic_list(&self) -> Vec<String> {
+        self.topics
+            .as_deref()
+            .and_then(|t| serde_json::from_str(t).ok())
+            .unwrap_or_default()
+    }
+}
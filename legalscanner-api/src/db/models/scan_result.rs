@@ -1,14 +1,25 @@
+use crate::scanner::{
+    CopyrightFinding, EccFinding, LicenseAgreement, LicenseCategory, LicenseFinding,
+    MalwareFinding, ModelLicenseFinding, PackageFinding,
+};
+use crate::db::models::{result_partition, CopyrightStatement, License};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
+use utoipa::ToSchema;
+
+/// Rows per INSERT statement when storing findings. Each row binds 22
+/// parameters, which keeps a statement well under SQLite's limit of 32766.
+pub const INSERT_BATCH_ROWS: usize = 500;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ScanResult {
     pub id: i64,
     pub scan_id: String,
     pub file_path: String,
-    pub result_type: String, // license, copyright, ecc
+    pub result_type: String, // license, copyright, ecc, malware, package, model_license
     pub license_name: Option<String>,
     pub license_spdx_id: Option<String>,
+    pub license_exception: Option<String>, // SPDX exception ID (license findings only)
     pub copyright_statement: Option<String>,
     pub copyright_holders: Option<String>, // JSON array
     pub copyright_years: Option<String>,    // JSON array
@@ -18,98 +29,415 @@ pub struct ScanResult {
     pub ecc_source: Option<String>, // Source scanner (e.g., 'semgrep', 'scancode')
     pub ecc_line_number: Option<i32>, // Line number where finding was detected
     pub ecc_check_id: Option<String>, // Rule/check ID from scanner
+    pub license_category: Option<String>, // code, documentation, data (license findings only)
+    pub package_name: Option<String>,
+    pub package_version: Option<String>,
+    pub package_ecosystem: Option<String>, // e.g. npm, rust-crate, python
+    pub package_purl: Option<String>,
+    pub merge_provenance: Option<String>, // strategy:scanners (license findings only)
+    pub license_agreement: Option<String>, // corroborated, disputed (license findings only)
+    pub review_status: String, // pending, approved, rejected
+    pub reviewed_at: Option<String>,
+    // Matching waiver, set on read by `waiver::mark` rather than stored
+    #[sqlx(default)]
+    #[serde(default)]
+    pub waiver_id: Option<String>,
+    // Path of the submodule the file came from, set on read by
+    // `submodules::mark`
+    #[sqlx(default)]
+    #[serde(default)]
+    pub submodule: Option<String>,
 }
 
-impl ScanResult {
-    pub async fn create_license(
-        pool: &SqlitePool,
-        scan_id: &str,
-        file_path: &str,
-        license_name: &str,
-        license_spdx_id: Option<&str>,
-        confidence: f32,
-    ) -> Result<ScanResult, sqlx::Error> {
-        sqlx::query_as::<_, ScanResult>(
-            r#"
-            INSERT INTO scan_results
-            (scan_id, file_path, result_type, license_name, license_spdx_id, confidence)
-            VALUES (?, ?, 'license', ?, ?, ?)
-            RETURNING *
-            "#,
-        )
-        .bind(scan_id)
-        .bind(file_path)
-        .bind(license_name)
-        .bind(license_spdx_id)
-        .bind(confidence)
-        .fetch_one(pool)
-        .await
+/// Columns of a `scan_results` row as [`ScanResult::insert_batch`] inserts
+/// them; the scanner finding types map onto them through the constructors
+#[derive(Debug, Clone, Default)]
+pub struct NewScanResult {
+    pub file_path: String,
+    pub result_type: &'static str,
+    pub license_name: Option<String>,
+    pub license_spdx_id: Option<String>,
+    pub license_exception: Option<String>,
+    pub confidence: Option<f32>,
+    pub license_category: Option<&'static str>,
+    pub merge_provenance: Option<String>,
+    pub license_agreement: Option<&'static str>,
+    pub copyright_statement: Option<String>,
+    pub copyright_holders: Option<String>,
+    pub copyright_years: Option<String>,
+    pub raw_data: Option<String>,
+    pub risk_severity: Option<String>,
+    pub ecc_source: Option<String>,
+    pub ecc_line_number: Option<i32>,
+    pub ecc_check_id: Option<String>,
+    pub package_name: Option<String>,
+    pub package_version: Option<String>,
+    pub package_ecosystem: Option<String>,
+    pub package_purl: Option<String>,
+    /// `pending` unless restored with a review decision
+    pub review_status: Option<String>,
+    pub reviewed_at: Option<String>,
+}
+
+impl NewScanResult {
+    /// The rows of every finding a scan produced
+    pub fn rows(results: Vec<crate::scanner::ScanResult>) -> Vec<Self> {
+        let mut rows = Vec::new();
+        for result in results {
+            let file_path = &result.file_path;
+            rows.extend(
+                result
+                    .licenses
+                    .into_iter()
+                    .map(|license| Self::license(file_path, license)),
+            );
+            rows.extend(
+                result
+                    .copyrights
+                    .into_iter()
+                    .map(|copyright| Self::copyright(file_path, copyright)),
+            );
+            rows.extend(
+                result
+                    .ecc_findings
+                    .into_iter()
+                    .map(|finding| Self::ecc(file_path, finding)),
+            );
+            rows.extend(
+                result
+                    .malware_findings
+                    .into_iter()
+                    .map(|malware| Self::malware(file_path, malware)),
+            );
+            rows.extend(
+                result
+                    .package_findings
+                    .into_iter()
+                    .map(|package| Self::package(file_path, package)),
+            );
+            rows.extend(
+                result
+                    .model_licenses
+                    .into_iter()
+                    .map(|finding| Self::model_license(file_path, finding)),
+            );
+        }
+        rows
     }
 
-    pub async fn create_copyright(
-        pool: &SqlitePool,
-        scan_id: &str,
-        file_path: &str,
-        copyright_statement: &str,
-        copyright_holders: &[String],
-        copyright_years: &[String],
-    ) -> Result<ScanResult, sqlx::Error> {
-        let holders_json = serde_json::to_string(copyright_holders).unwrap_or_default();
-        let years_json = serde_json::to_string(copyright_years).unwrap_or_default();
-
-        sqlx::query_as::<_, ScanResult>(
-            r#"
-            INSERT INTO scan_results
-            (scan_id, file_path, result_type, copyright_statement, copyright_holders, copyright_years)
-            VALUES (?, ?, 'copyright', ?, ?, ?)
-            RETURNING *
-            "#,
+    pub fn license(file_path: &str, license: LicenseFinding) -> Self {
+        Self {
+            file_path: file_path.to_string(),
+            result_type: "license",
+            license_name: Some(license.name),
+            license_spdx_id: license.spdx_id,
+            license_exception: license.exception,
+            confidence: Some(license.confidence),
+            license_category: Some(license.category.as_str()),
+            merge_provenance: license.provenance,
+            license_agreement: license.agreement.map(|a| a.as_str()),
+            ..Default::default()
+        }
+    }
+
+    /// Holders and years are stored as JSON arrays
+    pub fn copyright(file_path: &str, copyright: CopyrightFinding) -> Self {
+        Self {
+            file_path: file_path.to_string(),
+            result_type: "copyright",
+            copyright_holders: Some(serde_json::to_string(&copyright.holders).unwrap_or_default()),
+            copyright_years: Some(serde_json::to_string(&copyright.years).unwrap_or_default()),
+            copyright_statement: Some(copyright.statement),
+            ..Default::default()
+        }
+    }
+
+    /// ECC findings keep the matched content in raw_data
+    pub fn ecc(file_path: &str, finding: EccFinding) -> Self {
+        Self {
+            file_path: file_path.to_string(),
+            result_type: "ecc",
+            raw_data: Some(finding.content),
+            risk_severity: Some(finding.risk_severity),
+            ecc_source: finding.source,
+            ecc_line_number: finding.line_number,
+            ecc_check_id: finding.check_id,
+            ..Default::default()
+        }
+    }
+
+    /// Malware findings keep the signature in raw_data and the scanner in ecc_source
+    pub fn malware(file_path: &str, malware: MalwareFinding) -> Self {
+        Self {
+            file_path: file_path.to_string(),
+            result_type: "malware",
+            raw_data: Some(malware.signature),
+            risk_severity: Some("critical".to_string()),
+            ecc_source: Some(malware.source),
+            ..Default::default()
+        }
+    }
+
+    /// Package findings keep the declared license expression in license_spdx_id
+    pub fn package(file_path: &str, package: PackageFinding) -> Self {
+        Self {
+            file_path: file_path.to_string(),
+            result_type: "package",
+            license_spdx_id: package.declared_license,
+            package_name: Some(package.name),
+            package_version: package.version,
+            package_ecosystem: Some(package.ecosystem),
+            package_purl: package.purl,
+            ..Default::default()
+        }
+    }
+
+    /// Model license findings keep the artifact format in ecc_source and the
+    /// model card or license file path in raw_data
+    pub fn model_license(file_path: &str, finding: ModelLicenseFinding) -> Self {
+        Self {
+            file_path: file_path.to_string(),
+            result_type: "model_license",
+            license_name: Some(finding.license),
+            license_spdx_id: finding.spdx_id,
+            raw_data: finding.source,
+            ecc_source: Some(finding.format),
+            ..Default::default()
+        }
+    }
+
+    /// A finding exported from another instance, with its review decision.
+    /// Fails on a type, category or agreement this version does not store.
+    pub fn restored(result: &ScanResult) -> Result<Self, String> {
+        fn known(
+            field: &str,
+            value: Option<&str>,
+            allowed: &[&'static str],
+        ) -> Result<Option<&'static str>, String> {
+            value
+                .map(|value| {
+                    allowed
+                        .iter()
+                        .copied()
+                        .find(|a| *a == value)
+                        .ok_or_else(|| format!("Unknown {} '{}'", field, value))
+                })
+                .transpose()
+        }
+
+        let result_type = known(
+            "result type",
+            Some(&result.result_type),
+            &["license", "copyright", "ecc", "malware", "package", "model_license"],
+        )?
+        .unwrap_or_default();
+        let categories = [
+            LicenseCategory::Code,
+            LicenseCategory::Documentation,
+            LicenseCategory::Data,
+        ]
+        .map(|c| c.as_str());
+        let agreements = [LicenseAgreement::Corroborated, LicenseAgreement::Disputed].map(|a| a.as_str());
+        let review_status = known(
+            "review status",
+            Some(&result.review_status),
+            &["pending", "approved", "rejected"],
+        )?;
+
+        Ok(Self {
+            file_path: result.file_path.clone(),
+            result_type,
+            license_name: result.license_name.clone(),
+            license_spdx_id: result.license_spdx_id.clone(),
+            license_exception: result.license_exception.clone(),
+            confidence: result.confidence,
+            license_category: known(
+                "license category",
+                result.license_category.as_deref(),
+                &categories,
+            )?,
+            merge_provenance: result.merge_provenance.clone(),
+            license_agreement: known(
+                "license agreement",
+                result.license_agreement.as_deref(),
+                &agreements,
+            )?,
+            copyright_statement: result.copyright_statement.clone(),
+            copyright_holders: result.copyright_holders.clone(),
+            copyright_years: result.copyright_years.clone(),
+            raw_data: result.raw_data.clone(),
+            risk_severity: result.risk_severity.clone(),
+            ecc_source: result.ecc_source.clone(),
+            ecc_line_number: result.ecc_line_number,
+            ecc_check_id: result.ecc_check_id.clone(),
+            package_name: result.package_name.clone(),
+            package_version: result.package_version.clone(),
+            package_ecosystem: result.package_ecosystem.clone(),
+            package_purl: result.package_purl.clone(),
+            review_status: review_status.map(str::to_string),
+            reviewed_at: result.reviewed_at.clone(),
+        })
+    }
+
+    /// The finding as read back, for stores that keep whole rows rather
+    /// than the interned columns of `scan_results`
+    pub fn into_stored(self, id: i64, scan_id: &str) -> ScanResult {
+        ScanResult {
+            id,
+            scan_id: scan_id.to_string(),
+            file_path: self.file_path,
+            result_type: self.result_type.to_string(),
+            license_name: self.license_name,
+            license_spdx_id: self.license_spdx_id,
+            license_exception: self.license_exception,
+            copyright_statement: self.copyright_statement,
+            copyright_holders: self.copyright_holders,
+            copyright_years: self.copyright_years,
+            confidence: self.confidence,
+            raw_data: self.raw_data,
+            risk_severity: self.risk_severity,
+            ecc_source: self.ecc_source,
+            ecc_line_number: self.ecc_line_number,
+            ecc_check_id: self.ecc_check_id,
+            license_category: self.license_category.map(str::to_string),
+            package_name: self.package_name,
+            package_version: self.package_version,
+            package_ecosystem: self.package_ecosystem,
+            package_purl: self.package_purl,
+            merge_provenance: self.merge_provenance,
+            license_agreement: self.license_agreement.map(str::to_string),
+            review_status: self.review_status.unwrap_or_else(|| "pending".to_string()),
+            reviewed_at: self.reviewed_at,
+            waiver_id: None,
+            submodule: None,
+        }
+    }
+}
+
+impl ScanResult {
+    /// Reserve `count` consecutive finding IDs, unique across scans and
+    /// partitions. Returns the first of them.
+    pub async fn allocate_ids(conn: &mut SqliteConnection, count: usize) -> Result<i64, sqlx::Error> {
+        let last_id: i64 = sqlx::query_scalar(
+            "UPDATE scan_result_sequence SET last_id = last_id + ? WHERE id = 1 RETURNING last_id",
         )
-        .bind(scan_id)
-        .bind(file_path)
-        .bind(copyright_statement)
-        .bind(holders_json)
-        .bind(years_json)
-        .fetch_one(pool)
-        .await
+        .bind(count as i64)
+        .fetch_one(&mut *conn)
+        .await?;
+        Ok(last_id - count as i64 + 1)
     }
 
-    pub async fn create_ecc(
-        pool: &SqlitePool,
+    /// Insert rows for a scan into its partition with multi-row INSERTs of
+    /// [`INSERT_BATCH_ROWS`] rows each, rather than one statement per
+    /// finding. Run it inside a transaction to store the rows all or none.
+    pub async fn insert_batch(
+        conn: &mut SqliteConnection,
         scan_id: &str,
-        file_path: &str,
-        ecc_content: &str,
-        risk_severity: &str,
-        ecc_source: Option<&str>,
-        ecc_line_number: Option<i32>,
-        ecc_check_id: Option<&str>,
-    ) -> Result<ScanResult, sqlx::Error> {
-        sqlx::query_as::<_, ScanResult>(
-            r#"
-            INSERT INTO scan_results
-            (scan_id, file_path, result_type, raw_data, risk_severity, ecc_source, ecc_line_number, ecc_check_id)
-            VALUES (?, ?, 'ecc', ?, ?, ?, ?, ?)
-            RETURNING *
-            "#,
-        )
-        .bind(scan_id)
-        .bind(file_path)
-        .bind(ecc_content)
-        .bind(risk_severity)
-        .bind(ecc_source)
-        .bind(ecc_line_number)
-        .bind(ecc_check_id)
-        .fetch_one(pool)
-        .await
+        rows: &[NewScanResult],
+    ) -> Result<u64, sqlx::Error> {
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        let table = result_partition::table_for(&mut *conn, scan_id).await?;
+        let licenses: Vec<(&str, Option<&str>, Option<&str>)> = rows
+            .iter()
+            .filter_map(|r| {
+                Some((
+                    r.license_name.as_deref()?,
+                    r.license_spdx_id.as_deref(),
+                    r.license_exception.as_deref(),
+                ))
+            })
+            .collect();
+        let license_ids = License::intern(&mut *conn, &licenses).await?;
+        let statements: Vec<(&str, Option<&str>, Option<&str>)> = rows
+            .iter()
+            .filter_map(|r| {
+                Some((
+                    r.copyright_statement.as_deref()?,
+                    r.copyright_holders.as_deref(),
+                    r.copyright_years.as_deref(),
+                ))
+            })
+            .collect();
+        let statement_ids = CopyrightStatement::intern(&mut *conn, &statements).await?;
+
+        let first_id = Self::allocate_ids(&mut *conn, rows.len()).await?;
+
+        let mut inserted = 0;
+        for (index, batch) in rows.chunks(INSERT_BATCH_ROWS).enumerate() {
+            let batch_first_id = first_id + (index * INSERT_BATCH_ROWS) as i64;
+            let mut sql = QueryBuilder::<Sqlite>::new(format!(
+                "INSERT INTO {} (id, scan_id, file_path, result_type, license_id, \
+                 license_spdx_id, confidence, license_category, merge_provenance, \
+                 license_agreement, copyright_statement_id, raw_data, risk_severity, \
+                 ecc_source, ecc_line_number, ecc_check_id, package_name, package_version, \
+                 package_ecosystem, package_purl, review_status, reviewed_at) ",
+                table
+            ));
+            sql.push_values(batch.iter().zip(batch_first_id..), |mut values, (row, id)| {
+                let license_id = row.license_name.as_ref().and_then(|name| {
+                    license_ids
+                        .get(&(
+                            name.clone(),
+                            row.license_spdx_id.clone(),
+                            row.license_exception.clone(),
+                        ))
+                        .copied()
+                });
+                let statement_id = row.copyright_statement.as_ref().and_then(|statement| {
+                    statement_ids
+                        .get(&(
+                            statement.clone(),
+                            row.copyright_holders.clone(),
+                            row.copyright_years.clone(),
+                        ))
+                        .copied()
+                });
+                // The SPDX ID of a named license is stored with the license;
+                // package findings have an expression without a name
+                let spdx_id = license_id
+                    .is_none()
+                    .then_some(row.license_spdx_id.as_ref())
+                    .flatten();
+                values
+                    .push_bind(id)
+                    .push_bind(scan_id)
+                    .push_bind(&row.file_path)
+                    .push_bind(row.result_type)
+                    .push_bind(license_id)
+                    .push_bind(spdx_id)
+                    .push_bind(row.confidence)
+                    .push_bind(row.license_category)
+                    .push_bind(&row.merge_provenance)
+                    .push_bind(row.license_agreement)
+                    .push_bind(statement_id)
+                    .push_bind(&row.raw_data)
+                    .push_bind(&row.risk_severity)
+                    .push_bind(&row.ecc_source)
+                    .push_bind(row.ecc_line_number)
+                    .push_bind(&row.ecc_check_id)
+                    .push_bind(&row.package_name)
+                    .push_bind(&row.package_version)
+                    .push_bind(&row.package_ecosystem)
+                    .push_bind(&row.package_purl)
+                    .push_bind(row.review_status.as_deref().unwrap_or("pending"))
+                    .push_bind(&row.reviewed_at);
+            });
+            inserted += sql.build().execute(&mut *conn).await?.rows_affected();
+        }
+        Ok(inserted)
     }
 
     pub async fn find_by_scan_id(
         pool: &SqlitePool,
         scan_id: &str,
     ) -> Result<Vec<ScanResult>, sqlx::Error> {
-        sqlx::query_as::<_, ScanResult>(
-            "SELECT * FROM scan_results WHERE scan_id = ? ORDER BY file_path, result_type",
-        )
+        let source = result_partition::source_for(pool, scan_id).await?;
+        sqlx::query_as::<_, ScanResult>(&format!(
+            "SELECT * FROM {} WHERE scan_id = ? ORDER BY file_path, result_type",
+            source
+        ))
         .bind(scan_id)
         .fetch_all(pool)
         .await
@@ -119,13 +447,15 @@ impl ScanResult {
         pool: &SqlitePool,
         scan_id: &str,
     ) -> Result<Vec<ScanResult>, sqlx::Error> {
-        sqlx::query_as::<_, ScanResult>(
+        let source = result_partition::source_for(pool, scan_id).await?;
+        sqlx::query_as::<_, ScanResult>(&format!(
             r#"
-            SELECT * FROM scan_results
+            SELECT * FROM {}
             WHERE scan_id = ? AND result_type = 'license'
             ORDER BY file_path
             "#,
-        )
+            source
+        ))
         .bind(scan_id)
         .fetch_all(pool)
         .await
@@ -135,13 +465,15 @@ impl ScanResult {
         pool: &SqlitePool,
         scan_id: &str,
     ) -> Result<Vec<ScanResult>, sqlx::Error> {
-        sqlx::query_as::<_, ScanResult>(
+        let source = result_partition::source_for(pool, scan_id).await?;
+        sqlx::query_as::<_, ScanResult>(&format!(
             r#"
-            SELECT * FROM scan_results
+            SELECT * FROM {}
             WHERE scan_id = ? AND result_type = 'copyright'
             ORDER BY file_path
             "#,
-        )
+            source
+        ))
         .bind(scan_id)
         .fetch_all(pool)
         .await
@@ -151,15 +483,302 @@ impl ScanResult {
         pool: &SqlitePool,
         scan_id: &str,
     ) -> Result<Vec<ScanResult>, sqlx::Error> {
-        sqlx::query_as::<_, ScanResult>(
+        let source = result_partition::source_for(pool, scan_id).await?;
+        sqlx::query_as::<_, ScanResult>(&format!(
             r#"
-            SELECT * FROM scan_results
+            SELECT * FROM {}
             WHERE scan_id = ? AND result_type = 'ecc'
             ORDER BY risk_severity DESC, file_path
             "#,
-        )
+            source
+        ))
+        .bind(scan_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Files per license of one scan, most common first, with up to
+    /// `example_limit` of the files (alphabetically first) as examples
+    pub async fn license_summary(
+        pool: &SqlitePool,
+        scan_id: &str,
+        example_limit: i64,
+    ) -> Result<Vec<LicenseSummaryRow>, sqlx::Error> {
+        let source = result_partition::source_for(pool, scan_id).await?;
+        sqlx::query_as::<_, LicenseSummaryRow>(&format!(
+            r#"
+            WITH license_files AS (
+                SELECT DISTINCT license_name, license_spdx_id, file_path
+                FROM {}
+                WHERE scan_id = ? AND result_type = 'license' AND license_name IS NOT NULL
+            ),
+            ranked AS (
+                SELECT
+                    license_name,
+                    license_spdx_id,
+                    file_path,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY license_name, license_spdx_id ORDER BY file_path
+                    ) as file_rank
+                FROM license_files
+            )
+            SELECT
+                license_name,
+                license_spdx_id,
+                COUNT(*) as file_count,
+                json_group_array(file_path) FILTER (WHERE file_rank <= ?) as example_paths
+            FROM ranked
+            GROUP BY license_name, license_spdx_id
+            ORDER BY file_count DESC, license_name, license_spdx_id
+            "#,
+            source
+        ))
         .bind(scan_id)
+        .bind(example_limit)
         .fetch_all(pool)
         .await
     }
+
+    /// Files per license (SPDX ID, else name) of each of the scans
+    pub async fn license_counts(
+        pool: &SqlitePool,
+        scan_ids: &[String],
+    ) -> Result<Vec<ScanLicenseCount>, sqlx::Error> {
+        sqlx::query_as::<_, ScanLicenseCount>(
+            r#"
+            SELECT
+                scan_id,
+                COALESCE(license_spdx_id, license_name) as license,
+                COUNT(DISTINCT file_path) as file_count
+            FROM all_scan_results
+            WHERE scan_id IN (SELECT value FROM json_each(?))
+                AND result_type = 'license'
+                AND COALESCE(license_spdx_id, license_name) IS NOT NULL
+            GROUP BY scan_id, license
+            "#,
+        )
+        .bind(serde_json::to_string(scan_ids).unwrap_or_default())
+        .fetch_all(pool)
+        .await
+    }
+
+    /// ECC findings per severity of each of the scans
+    pub async fn ecc_counts(
+        pool: &SqlitePool,
+        scan_ids: &[String],
+    ) -> Result<Vec<ScanSeverityCount>, sqlx::Error> {
+        sqlx::query_as::<_, ScanSeverityCount>(
+            r#"
+            SELECT scan_id, risk_severity, COUNT(*) as finding_count
+            FROM all_scan_results
+            WHERE scan_id IN (SELECT value FROM json_each(?)) AND result_type = 'ecc'
+            GROUP BY scan_id, risk_severity
+            "#,
+        )
+        .bind(serde_json::to_string(scan_ids).unwrap_or_default())
+        .fetch_all(pool)
+        .await
+    }
+
+    /// IDs among `ids` that are findings of the scan
+    pub async fn existing_ids(
+        pool: &SqlitePool,
+        scan_id: &str,
+        ids: &[i64],
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let table = result_partition::table_for(pool, scan_id).await?;
+        let mut query =
+            QueryBuilder::<Sqlite>::new(format!("SELECT id FROM {} WHERE scan_id = ", table));
+        query.push_bind(scan_id).push(" AND id IN (");
+        let mut separated = query.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        query.push(")");
+
+        query.build_query_scalar::<i64>().fetch_all(pool).await
+    }
+
+    /// Set the review status of findings of a scan, recording when they were
+    /// approved or rejected. Returns how many findings changed status.
+    pub async fn set_review_status(
+        pool: &SqlitePool,
+        scan_id: &str,
+        ids: &[i64],
+        status: &str,
+    ) -> Result<u64, sqlx::Error> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let table = result_partition::table_for(pool, scan_id).await?;
+        let mut query =
+            QueryBuilder::<Sqlite>::new(format!("UPDATE {} SET review_status = ", table));
+        query
+            .push_bind(status)
+            .push(", reviewed_at = CASE WHEN ")
+            .push_bind(status)
+            .push(" = 'pending' THEN NULL ELSE datetime('now') END WHERE scan_id = ")
+            .push_bind(scan_id)
+            .push(" AND review_status != ")
+            .push_bind(status)
+            .push(" AND id IN (");
+        let mut separated = query.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        query.push(")");
+
+        Ok(query.build().execute(pool).await?.rows_affected())
+    }
+
+    /// Number of findings of a scan
+    pub async fn count_by_scan_id(pool: &SqlitePool, scan_id: &str) -> Result<i64, sqlx::Error> {
+        let source = result_partition::source_for(pool, scan_id).await?;
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {} WHERE scan_id = ?", source))
+            .bind(scan_id)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Delete the findings of a scan with their review comments
+    pub async fn delete_by_scan_id(pool: &SqlitePool, scan_id: &str) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let table = result_partition::table_for(&mut *tx, scan_id).await?;
+        let result = sqlx::query(&format!("DELETE FROM {} WHERE scan_id = ?", table))
+            .bind(scan_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM scan_result_comments WHERE scan_id = ?")
+            .bind(scan_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete findings of any scan whose copyright statement, holders or raw
+    /// scanner output contain `text` (lowercase), for data erasure requests,
+    /// and the matching copyright statements with them
+    pub async fn delete_containing(pool: &SqlitePool, text: &str) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let mut deleted = 0;
+        for table in result_partition::tables(&mut *tx).await? {
+            deleted += sqlx::query(&format!(
+                r#"
+                DELETE FROM {}
+                WHERE copyright_statement_id IN (
+                        SELECT id FROM copyright_statements
+                        WHERE instr(lower(statement), ?1) > 0
+                           OR instr(lower(coalesce(holders, '')), ?1) > 0
+                    )
+                   OR instr(lower(coalesce(copyright_statement, '')), ?1) > 0
+                   OR instr(lower(coalesce(copyright_holders, '')), ?1) > 0
+                   OR instr(lower(coalesce(raw_data, '')), ?1) > 0
+                "#,
+                table
+            ))
+            .bind(text)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+        if deleted > 0 {
+            sqlx::query(
+                "DELETE FROM scan_result_comments \
+                 WHERE result_id NOT IN (SELECT id FROM all_scan_results) \
+                   AND scan_id NOT IN \
+                       (SELECT id FROM scans WHERE results_archive_key IS NOT NULL)",
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        CopyrightStatement::delete_containing(&mut tx, text).await?;
+        tx.commit().await?;
+
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+impl ScanResult {
+    /// A pending finding with ID 1 of scan `scan-1`, all other columns
+    /// empty. Tests fill in the columns they look at.
+    pub fn for_test(result_type: &str, file_path: &str) -> Self {
+        ScanResult {
+            id: 1,
+            scan_id: "scan-1".to_string(),
+            file_path: file_path.to_string(),
+            result_type: result_type.to_string(),
+            license_name: None,
+            license_spdx_id: None,
+            license_exception: None,
+            copyright_statement: None,
+            copyright_holders: None,
+            copyright_years: None,
+            confidence: None,
+            raw_data: None,
+            risk_severity: None,
+            ecc_source: None,
+            ecc_line_number: None,
+            ecc_check_id: None,
+            license_category: None,
+            package_name: None,
+            package_version: None,
+            package_ecosystem: None,
+            package_purl: None,
+            merge_provenance: None,
+            license_agreement: None,
+            review_status: "pending".to_string(),
+            reviewed_at: None,
+            waiver_id: None,
+            submodule: None,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct LicenseSummaryRow {
+    pub license_name: String,
+    pub license_spdx_id: Option<String>,
+    pub file_count: i64,
+    pub example_paths: String, // JSON array
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct ScanLicenseCount {
+    pub scan_id: String,
+    pub license: String,
+    pub file_count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct ScanSeverityCount {
+    pub scan_id: String,
+    pub risk_severity: Option<String>,
+    pub finding_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct LicenseUsage {
+    /// SPDX ID, or the scanner's name for licenses without one
+    pub license: String,
+    pub scan_count: i64,
+    pub file_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct EccFindingDay {
+    /// YYYY-MM-DD
+    pub day: String,
+    pub finding_count: i64,
+    pub critical: i64,
+    pub high: i64,
+    pub medium: i64,
+    pub low: i64,
 }
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Reviewer comment on a finding, see [`crate::review`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScanResultComment {
+    pub id: String,
+    pub scan_id: String,
+    pub result_id: i64,
+    pub body: String,
+    pub author: Option<String>,
+    pub created_at: String,
+}
+
+impl ScanResultComment {
+    pub async fn create(
+        pool: &SqlitePool,
+        scan_id: &str,
+        result_id: i64,
+        body: &str,
+        author: Option<&str>,
+    ) -> Result<ScanResultComment, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query_as::<_, ScanResultComment>(
+            r#"
+            INSERT INTO scan_result_comments (id, scan_id, result_id, body, author)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(&id)
+        .bind(scan_id)
+        .bind(result_id)
+        .bind(body)
+        .bind(author)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Store a comment exported from another instance on the finding it
+    /// was imported as
+    pub async fn restore(
+        pool: &SqlitePool,
+        comment: &ScanResultComment,
+        result_id: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO scan_result_comments (id, scan_id, result_id, body, author, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&comment.id)
+        .bind(&comment.scan_id)
+        .bind(result_id)
+        .bind(&comment.body)
+        .bind(&comment.author)
+        .bind(&comment.created_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Comments on the findings of a scan, oldest first
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Vec<ScanResultComment>, sqlx::Error> {
+        sqlx::query_as::<_, ScanResultComment>(
+            "SELECT * FROM scan_result_comments WHERE scan_id = ? ORDER BY created_at, rowid",
+        )
+        .bind(scan_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Comments on a finding, oldest first
+    pub async fn find_by_result_id(
+        pool: &SqlitePool,
+        result_id: i64,
+    ) -> Result<Vec<ScanResultComment>, sqlx::Error> {
+        sqlx::query_as::<_, ScanResultComment>(
+            "SELECT * FROM scan_result_comments WHERE result_id = ? ORDER BY created_at, rowid",
+        )
+        .bind(result_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Delete the comments on the findings of a scan
+    pub async fn delete_for_scan(pool: &SqlitePool, scan_id: &str) -> Result<u64, sqlx::Error> {
+        Ok(sqlx::query("DELETE FROM scan_result_comments WHERE scan_id = ?")
+            .bind(scan_id)
+            .execute(pool)
+            .await?
+            .rows_affected())
+    }
+
+    /// Delete the comments on some findings of a scan
+    pub async fn delete_for_results(
+        pool: &SqlitePool,
+        scan_id: &str,
+        result_ids: &[i64],
+    ) -> Result<u64, sqlx::Error> {
+        if result_ids.is_empty() {
+            return Ok(0);
+        }
+        let mut query = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+            "DELETE FROM scan_result_comments WHERE scan_id = ",
+        );
+        query.push_bind(scan_id).push(" AND result_id IN (");
+        let mut separated = query.separated(", ");
+        for id in result_ids {
+            separated.push_bind(id);
+        }
+        query.push(")");
+
+        Ok(query.build().execute(pool).await?.rows_affected())
+    }
+}
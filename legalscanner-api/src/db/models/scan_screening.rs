@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScanScreening {
+    pub id: String,
+    pub scan_id: String,
+    pub status: String, // pending, cleared, blocked, error
+    pub reference: Option<String>, // case ID in the screening system
+    pub response_code: Option<i32>,
+    pub response_body: Option<String>,
+    pub requested_at: String,
+    pub updated_at: String,
+}
+
+impl ScanScreening {
+    /// Record a screening request and the webhook's response, replacing any
+    /// earlier screening of the scan
+    pub async fn upsert(
+        pool: &SqlitePool,
+        scan_id: &str,
+        status: &str,
+        reference: Option<&str>,
+        response_code: Option<i32>,
+        response_body: Option<&str>,
+    ) -> Result<ScanScreening, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query_as::<_, ScanScreening>(
+            r#"
+            INSERT INTO scan_screenings (id, scan_id, status, reference, response_code, response_body)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(scan_id) DO UPDATE SET
+                status = excluded.status,
+                reference = excluded.reference,
+                response_code = excluded.response_code,
+                response_body = excluded.response_body,
+                requested_at = datetime('now'),
+                updated_at = datetime('now')
+            RETURNING *
+            "#,
+        )
+        .bind(&id)
+        .bind(scan_id)
+        .bind(status)
+        .bind(reference)
+        .bind(response_code)
+        .bind(response_body)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Option<ScanScreening>, sqlx::Error> {
+        sqlx::query_as::<_, ScanScreening>("SELECT * FROM scan_screenings WHERE scan_id = ?")
+            .bind(scan_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Apply a decision reported back by the screening system. Returns `None`
+    /// when the scan was never submitted for screening.
+    pub async fn record_decision(
+        pool: &SqlitePool,
+        scan_id: &str,
+        status: &str,
+        reference: Option<&str>,
+    ) -> Result<Option<ScanScreening>, sqlx::Error> {
+        sqlx::query_as::<_, ScanScreening>(
+            r#"
+            UPDATE scan_screenings
+            SET status = ?,
+                reference = COALESCE(?, reference),
+                updated_at = datetime('now')
+            WHERE scan_id = ?
+            RETURNING *
+            "#,
+        )
+        .bind(status)
+        .bind(reference)
+        .bind(scan_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Drop stored webhook responses containing `text` (lowercase)
+    pub async fn redact_containing(pool: &SqlitePool, text: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE scan_screenings SET response_body = NULL \
+             WHERE instr(lower(response_body), ?) > 0",
+        )
+        .bind(text)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
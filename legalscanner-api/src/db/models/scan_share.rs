@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScanShare {
+    pub id: String,
+    pub scan_id: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+impl ScanShare {
+    pub async fn create(
+        pool: &SqlitePool,
+        scan_id: &str,
+        token_hash: String,
+        expires_in_days: Option<u32>,
+    ) -> Result<ScanShare, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        let expires_modifier = expires_in_days.map(|days| format!("+{} days", days));
+
+        sqlx::query_as::<_, ScanShare>(
+            r#"
+            INSERT INTO scan_shares (id, scan_id, token_hash, expires_at)
+            VALUES (?, ?, ?, CASE WHEN ? IS NULL THEN NULL ELSE datetime('now', ?) END)
+            RETURNING *
+            "#,
+        )
+        .bind(&id)
+        .bind(scan_id)
+        .bind(&token_hash)
+        .bind(&expires_modifier)
+        .bind(&expires_modifier)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Find a share that has neither been revoked nor expired
+    pub async fn find_active_by_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<ScanShare>, sqlx::Error> {
+        sqlx::query_as::<_, ScanShare>(
+            r#"
+            SELECT * FROM scan_shares
+            WHERE token_hash = ?
+              AND revoked_at IS NULL
+              AND (expires_at IS NULL OR expires_at > datetime('now'))
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Vec<ScanShare>, sqlx::Error> {
+        sqlx::query_as::<_, ScanShare>(
+            "SELECT * FROM scan_shares WHERE scan_id = ? ORDER BY created_at DESC",
+        )
+        .bind(scan_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Revoke a share link; returns false if no unrevoked share matched
+    pub async fn revoke(pool: &SqlitePool, scan_id: &str, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE scan_shares SET revoked_at = datetime('now')
+            WHERE scan_id = ? AND id = ? AND revoked_at IS NULL
+            "#,
+        )
+        .bind(scan_id)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+/// Git submodule checked out for a scan, see [`crate::submodules`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScanSubmodule {
+    #[serde(skip)]
+    pub scan_id: String,
+    /// Path relative to the repository root
+    pub path: String,
+    pub url: Option<String>,
+    /// Commit the repository pins the submodule to
+    pub commit_sha: Option<String>,
+    /// Set when the submodule could not be checked out, its code is missing
+    /// from the scan
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ScanSubmodule {
+    pub async fn create(
+        pool: &SqlitePool,
+        scan_id: &str,
+        path: &str,
+        url: Option<&str>,
+        commit_sha: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<ScanSubmodule, sqlx::Error> {
+        sqlx::query_as::<_, ScanSubmodule>(
+            r#"
+            INSERT INTO scan_submodules (scan_id, path, url, commit_sha, error)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(scan_id, path) DO UPDATE SET
+                url = excluded.url,
+                commit_sha = excluded.commit_sha,
+                error = excluded.error
+            RETURNING *
+            "#,
+        )
+        .bind(scan_id)
+        .bind(path)
+        .bind(url)
+        .bind(commit_sha)
+        .bind(error)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Submodules of a scan, by path
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Vec<ScanSubmodule>, sqlx::Error> {
+        sqlx::query_as::<_, ScanSubmodule>(
+            "SELECT * FROM scan_submodules WHERE scan_id = ? ORDER BY path",
+        )
+        .bind(scan_id)
+        .fetch_all(pool)
+        .await
+    }
+}
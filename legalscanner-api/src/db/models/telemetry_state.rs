@@ -0,0 +1,48 @@
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// Installation-wide telemetry bookkeeping, see [`crate::telemetry`]
+#[derive(Debug, Clone, FromRow)]
+pub struct TelemetryState {
+    /// Random ID generated on first use, tells reports of different
+    /// installations apart and nothing else
+    pub installation_id: String,
+    /// End of the period last reported, YYYY-MM-DD HH:MM:SS.SSS (UTC)
+    pub last_reported_at: Option<String>,
+}
+
+impl TelemetryState {
+    /// The state of this installation, created on first use
+    pub async fn load(pool: &SqlitePool) -> Result<TelemetryState, sqlx::Error> {
+        sqlx::query("INSERT OR IGNORE INTO telemetry_state (id, installation_id) VALUES (1, ?)")
+            .bind(Uuid::new_v4().to_string())
+            .execute(pool)
+            .await?;
+
+        sqlx::query_as::<_, TelemetryState>(
+            "SELECT installation_id, last_reported_at FROM telemetry_state WHERE id = 1",
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Move the end of the reported period from `previous` to `reported_at`,
+    /// or back when a report could not be sent. False when another worker
+    /// moved it first.
+    pub async fn advance(
+        pool: &SqlitePool,
+        previous: Option<&str>,
+        reported_at: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE telemetry_state SET last_reported_at = ? \
+             WHERE id = 1 AND last_reported_at IS ?",
+        )
+        .bind(reported_at)
+        .bind(previous)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
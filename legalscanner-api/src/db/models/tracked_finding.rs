@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A finding followed across the scans of a repository, see
+/// [`crate::lifecycle`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TrackedFinding {
+    pub id: String,
+    pub repository: String, // normalized git URL
+    pub result_type: String,
+    pub file_path: String,
+    /// License, ECC check, malware signature or package
+    pub value: String,
+    pub severity: Option<String>,
+    /// open, triaged or resolved
+    pub state: String,
+    pub first_scan_id: String,
+    pub last_scan_id: String,
+    pub opened_at: String,
+    pub triaged_at: Option<String>,
+    pub resolved_at: Option<String>,
+    /// Scan that no longer found the finding; unset when resolved by hand
+    pub resolved_scan_id: Option<String>,
+    pub reopened_at: Option<String>,
+    pub note: Option<String>,
+    pub updated_at: String,
+}
+
+/// Filter of [`TrackedFinding::list`]; unset fields match everything
+#[derive(Debug, Default)]
+pub struct TrackedFindingFilter {
+    pub repositories: Option<Vec<String>>,
+    pub state: Option<String>,
+    pub result_type: Option<String>,
+    /// Compared case-insensitively
+    pub value: Option<String>,
+    pub opened_before: Option<String>,
+}
+
+impl TrackedFinding {
+    /// Record that `scan_id` found a finding: opens it the first time and
+    /// reopens it if it was resolved. Returns the finding's state before.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record(
+        pool: &SqlitePool,
+        repository: &str,
+        result_type: &str,
+        file_path: &str,
+        value: &str,
+        severity: Option<&str>,
+        scan_id: &str,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let previous: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT state FROM tracked_findings
+            WHERE repository = ? AND result_type = ? AND file_path = ? AND value = ?
+            "#,
+        )
+        .bind(repository)
+        .bind(result_type)
+        .bind(file_path)
+        .bind(value)
+        .fetch_optional(pool)
+        .await?;
+
+        // SET expressions see the row as it was before the update
+        sqlx::query(
+            r#"
+            INSERT INTO tracked_findings
+            (id, repository, result_type, file_path, value, severity, first_scan_id, last_scan_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(repository, result_type, file_path, value) DO UPDATE SET
+                severity = excluded.severity,
+                last_scan_id = excluded.last_scan_id,
+                state = CASE WHEN state = 'resolved' THEN 'open' ELSE state END,
+                reopened_at = CASE WHEN state = 'resolved' THEN datetime('now') ELSE reopened_at END,
+                triaged_at = CASE WHEN state = 'resolved' THEN NULL ELSE triaged_at END,
+                resolved_at = CASE WHEN state = 'resolved' THEN NULL ELSE resolved_at END,
+                resolved_scan_id = CASE WHEN state = 'resolved' THEN NULL ELSE resolved_scan_id END,
+                updated_at = CASE WHEN state = 'resolved' THEN datetime('now') ELSE updated_at END
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(repository)
+        .bind(result_type)
+        .bind(file_path)
+        .bind(value)
+        .bind(severity)
+        .bind(scan_id)
+        .bind(scan_id)
+        .execute(pool)
+        .await?;
+
+        Ok(previous)
+    }
+
+    /// Resolve the unresolved findings of `repository` under `path_prefix`
+    /// (everything when empty) that `scan_id` did not find. Returns how many
+    /// were resolved.
+    pub async fn resolve_missing(
+        pool: &SqlitePool,
+        repository: &str,
+        path_prefix: &str,
+        scan_id: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE tracked_findings SET
+                state = 'resolved',
+                resolved_at = datetime('now'),
+                resolved_scan_id = ?1,
+                updated_at = datetime('now')
+            WHERE repository = ?2
+                AND state != 'resolved'
+                AND last_scan_id != ?1
+                AND substr(file_path, 1, length(?3)) = ?3
+            "#,
+        )
+        .bind(scan_id)
+        .bind(repository)
+        .bind(path_prefix)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Move a finding to `state`, stamping the matching timestamp. Leaving
+    /// a state clears its timestamp; `opened_at` is never changed.
+    pub async fn transition(
+        pool: &SqlitePool,
+        id: &str,
+        state: &str,
+        note: Option<&str>,
+    ) -> Result<Option<TrackedFinding>, sqlx::Error> {
+        sqlx::query_as::<_, TrackedFinding>(
+            r#"
+            UPDATE tracked_findings SET
+                triaged_at = CASE ?1
+                    WHEN 'triaged' THEN datetime('now')
+                    WHEN 'open' THEN NULL
+                    ELSE triaged_at END,
+                resolved_at = CASE WHEN ?1 = 'resolved' THEN datetime('now') ELSE NULL END,
+                resolved_scan_id = NULL,
+                reopened_at = CASE WHEN ?1 = 'open' THEN datetime('now') ELSE reopened_at END,
+                state = ?1,
+                note = ?2,
+                updated_at = datetime('now')
+            WHERE id = ?3
+            RETURNING *
+            "#,
+        )
+        .bind(state)
+        .bind(note)
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        id: &str,
+    ) -> Result<Option<TrackedFinding>, sqlx::Error> {
+        sqlx::query_as::<_, TrackedFinding>("SELECT * FROM tracked_findings WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Findings matching `filter`, oldest first
+    pub async fn list(
+        pool: &SqlitePool,
+        filter: &TrackedFindingFilter,
+    ) -> Result<Vec<TrackedFinding>, sqlx::Error> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM tracked_findings WHERE 1 = 1");
+        if let Some(repositories) = &filter.repositories {
+            if repositories.is_empty() {
+                return Ok(Vec::new());
+            }
+            query.push(" AND repository IN (");
+            let mut separated = query.separated(", ");
+            for repository in repositories {
+                separated.push_bind(repository);
+            }
+            separated.push_unseparated(")");
+        }
+        if let Some(state) = &filter.state {
+            query.push(" AND state = ").push_bind(state);
+        }
+        if let Some(result_type) = &filter.result_type {
+            query.push(" AND result_type = ").push_bind(result_type);
+        }
+        if let Some(value) = &filter.value {
+            query
+                .push(" AND lower(value) = lower(")
+                .push_bind(value)
+                .push(")");
+        }
+        if let Some(opened_before) = &filter.opened_before {
+            query.push(" AND opened_at < ").push_bind(opened_before);
+        }
+        query.push(" ORDER BY opened_at, repository, file_path, rowid");
+
+        query
+            .build_query_as::<TrackedFinding>()
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn delete_by_repository(
+        pool: &SqlitePool,
+        repository: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM tracked_findings WHERE repository = ?")
+            .bind(repository)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete findings whose path or value contains `text` (lowercase)
+    pub async fn delete_containing(pool: &SqlitePool, text: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM tracked_findings
+            WHERE instr(lower(file_path), ?1) > 0
+               OR instr(lower(value), ?1) > 0
+               OR instr(lower(coalesce(note, '')), ?1) > 0
+            "#,
+        )
+        .bind(text)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Accepted findings of a scan or repository, see [`crate::waiver`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Waiver {
+    pub id: String,
+    pub scan_id: Option<String>,
+    pub repository: Option<String>, // normalized git URL
+    pub file_path: Option<String>,
+    pub check_id: Option<String>,
+    pub license: Option<String>,
+    pub justification: String,
+    pub expires_at: Option<String>,
+    pub created_at: String,
+}
+
+impl Waiver {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        pool: &SqlitePool,
+        scan_id: Option<&str>,
+        repository: Option<&str>,
+        file_path: Option<&str>,
+        check_id: Option<&str>,
+        license: Option<&str>,
+        justification: &str,
+        expires_at: Option<&str>,
+    ) -> Result<Waiver, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query_as::<_, Waiver>(
+            r#"
+            INSERT INTO waivers
+            (id, scan_id, repository, file_path, check_id, license, justification, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(&id)
+        .bind(scan_id)
+        .bind(repository)
+        .bind(file_path)
+        .bind(check_id)
+        .bind(license)
+        .bind(justification)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Waiver>, sqlx::Error> {
+        sqlx::query_as::<_, Waiver>("SELECT * FROM waivers WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Waivers, newest first, optionally only those of one scan or
+    /// repository and only those that have not expired
+    pub async fn list(
+        pool: &SqlitePool,
+        scan_id: Option<&str>,
+        repository: Option<&str>,
+        include_expired: bool,
+    ) -> Result<Vec<Waiver>, sqlx::Error> {
+        sqlx::query_as::<_, Waiver>(
+            r#"
+            SELECT * FROM waivers
+            WHERE (? IS NULL OR scan_id = ?)
+                AND (? IS NULL OR repository = ?)
+                AND (? OR expires_at IS NULL OR expires_at > datetime('now'))
+            ORDER BY created_at DESC, id
+            "#,
+        )
+        .bind(scan_id)
+        .bind(scan_id)
+        .bind(repository)
+        .bind(repository)
+        .bind(include_expired)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Unexpired waivers of a scan or of its repository
+    pub async fn find_active(
+        pool: &SqlitePool,
+        scan_id: &str,
+        repository: &str,
+    ) -> Result<Vec<Waiver>, sqlx::Error> {
+        sqlx::query_as::<_, Waiver>(
+            r#"
+            SELECT * FROM waivers
+            WHERE (scan_id = ? OR repository = ?)
+                AND (expires_at IS NULL OR expires_at > datetime('now'))
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(scan_id)
+        .bind(repository)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Returns false if no waiver has this ID
+    pub async fn delete(pool: &SqlitePool, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM waivers WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete every waiver of a repository, returning how many there were
+    pub async fn delete_by_repository(
+        pool: &SqlitePool,
+        repository: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM waivers WHERE repository = ?")
+            .bind(repository)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
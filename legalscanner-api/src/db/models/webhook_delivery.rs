@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use utoipa::ToSchema;
+
+/// One attempt to deliver a scan callback, see [`crate::webhooks`]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    #[serde(skip_serializing)]
+    pub scan_id: String,
+    pub url: String,
+    pub event: String, // scan.completed, scan.failed
+    pub attempt: i64,
+    pub status: String, // delivered, failed
+    pub response_code: Option<i32>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+}
+
+impl WebhookDelivery {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record(
+        pool: &SqlitePool,
+        scan_id: &str,
+        url: &str,
+        event: &str,
+        attempt: u32,
+        status: &str,
+        response_code: Option<i32>,
+        response_body: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries
+                (scan_id, url, event, attempt, status, response_code, response_body, error)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(scan_id)
+        .bind(url)
+        .bind(event)
+        .bind(attempt as i64)
+        .bind(status)
+        .bind(response_code)
+        .bind(response_body)
+        .bind(error)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Delivery attempts for a scan, oldest first
+    pub async fn find_by_scan_id(
+        pool: &SqlitePool,
+        scan_id: &str,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        sqlx::query_as::<_, WebhookDelivery>(
+            "SELECT * FROM webhook_deliveries WHERE scan_id = ? ORDER BY id",
+        )
+        .bind(scan_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Drop stored receiver responses containing `text` (lowercase)
+    pub async fn redact_containing(pool: &SqlitePool, text: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE webhook_deliveries SET response_body = NULL \
+             WHERE instr(lower(response_body), ?) > 0",
+        )
+        .bind(text)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Worker {
+    pub id: String,
+    pub hostname: String,
+    pub started_at: String,
+    pub last_heartbeat_at: String,
+    pub status: String, // active, draining, stopped
+}
+
+impl Worker {
+    /// Register this process as an active worker
+    pub async fn register(pool: &SqlitePool, hostname: String) -> Result<Worker, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query_as::<_, Worker>(
+            r#"
+            INSERT INTO workers (id, hostname, status)
+            VALUES (?, ?, 'active')
+            RETURNING *
+            "#,
+        )
+        .bind(&id)
+        .bind(&hostname)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn heartbeat(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE workers SET last_heartbeat_at = datetime('now') WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_status(pool: &SqlitePool, id: &str, status: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE workers SET status = ?, last_heartbeat_at = datetime('now') WHERE id = ?")
+            .bind(status)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Count workers that are active and have sent a heartbeat recently
+    pub async fn count_active(pool: &SqlitePool, stale_after_secs: i64) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM workers
+            WHERE status = 'active'
+              AND last_heartbeat_at >= datetime('now', '-' || ? || ' seconds')
+            "#,
+        )
+        .bind(stale_after_secs)
+        .fetch_one(pool)
+        .await
+    }
+}
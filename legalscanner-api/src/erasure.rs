@@ -0,0 +1,163 @@
+//! Hard deletion of personal data for data subject requests. Erasing a
+//! repository deletes its scans with everything stored for them; erasing an
+//! e-mail address deletes the findings, commit authors, registry entries and
+//! stored webhook responses mentioning it. Exports are generated from stored findings on
+//! request, so they no longer contain erased data either. Nothing is kept
+//! that would allow undoing an erasure.
+
+use crate::db::models::{
+    CopyrightHolder, LicenseCuration, Project, Scan, ScanFileAuthor, ScanHistoryFinding,
+    ScanScreening, TrackedFinding, Waiver, WebhookDelivery,
+};
+use crate::copyright_holders::normalize_holder;
+use crate::error::AppError;
+use crate::git::workspace::Workspace;
+use crate::integrity;
+use crate::legal_hold;
+use crate::scan_diff::normalize_repository_url;
+use crate::AppState;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// What an erasure removed
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ErasureReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// IDs of the deleted scans, with their events, shares, screenings,
+    /// history findings and webhook deliveries
+    pub scans_deleted: Vec<String>,
+    pub results_deleted: u64,
+    pub copyright_holders_deleted: u64,
+    pub history_findings_deleted: u64,
+    /// Commit authorship records of the address
+    pub file_authors_deleted: u64,
+    /// Screening and webhook responses whose stored body was dropped
+    pub responses_redacted: u64,
+    pub project_repositories_removed: u64,
+    pub curations_deleted: u64,
+    pub waivers_deleted: u64,
+    /// Findings tracked across the scans of the repository
+    pub tracked_findings_deleted: u64,
+    pub workspaces_removed: u64,
+    /// Scans whose result checksums were sealed again, recorded in the
+    /// integrity audit log
+    pub checksums_resealed: usize,
+    pub erased_at: String,
+}
+
+/// Erase everything stored about a repository and/or an e-mail address
+pub async fn erase(
+    state: &AppState,
+    git_url: Option<&str>,
+    email: Option<&str>,
+) -> Result<ErasureReport, AppError> {
+    let mut report = ErasureReport {
+        git_url: git_url.map(str::to_string),
+        email: email.map(str::to_string),
+        ..Default::default()
+    };
+
+    if let Some(git_url) = git_url {
+        erase_repository(state, &normalize_repository_url(git_url), &mut report).await?;
+    }
+    if let Some(email) = email {
+        erase_email(state, &email.trim().to_lowercase(), &mut report).await?;
+    }
+
+    report.erased_at = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    tracing::info!(
+        "Erased {} scans, {} results and {} copyright holders",
+        report.scans_deleted.len(),
+        report.results_deleted,
+        report.copyright_holders_deleted
+    );
+    Ok(report)
+}
+
+async fn erase_repository(
+    state: &AppState,
+    repository: &str,
+    report: &mut ErasureReport,
+) -> Result<(), AppError> {
+    let scans = Scan::find_by_repositories_including_deleted(&state.db, &[repository.to_string()]).await?;
+
+    // A running job would write its results back after the erasure
+    if let Some(running) = scans
+        .iter()
+        .find(|s| s.status == "pending" || s.status == "in_progress")
+    {
+        return Err(AppError::Conflict(format!(
+            "Scan {} of the repository is still {}, erase it once it has finished",
+            running.id, running.status
+        )));
+    }
+
+    // Held scans must be kept until the hold is lifted
+    for scan in &scans {
+        legal_hold::ensure_deletable(&state.db, scan).await?;
+    }
+
+    let scan_ids: Vec<String> = scans.into_iter().map(|s| s.id).collect();
+
+    // Holders only this repository's scans mention; the registry otherwise
+    // keeps holders after their scans are deleted
+    report.copyright_holders_deleted +=
+        CopyrightHolder::delete_seen_only_in(&state.db, &scan_ids).await?;
+
+    for scan_id in &scan_ids {
+        report.results_deleted += state.result_store.count_results(scan_id).await?;
+        state.result_store.delete_results(scan_id).await?;
+
+        let workspace = Workspace::new(state.config.temp_workspace_dir.clone(), scan_id.clone());
+        if workspace.exists().await {
+            workspace
+                .cleanup()
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to remove workspace: {}", e)))?;
+            report.workspaces_removed += 1;
+        }
+
+        Scan::delete_permanently(&state.db, scan_id).await?;
+    }
+    report.scans_deleted = scan_ids;
+
+    report.project_repositories_removed +=
+        Project::remove_repository(&state.db, repository).await?;
+    report.curations_deleted +=
+        LicenseCuration::delete_by_repository(&state.db, repository).await?;
+    report.waivers_deleted += Waiver::delete_by_repository(&state.db, repository).await?;
+    report.tracked_findings_deleted +=
+        TrackedFinding::delete_by_repository(&state.db, repository).await?;
+    Ok(())
+}
+
+async fn erase_email(
+    state: &AppState,
+    email: &str,
+    report: &mut ErasureReport,
+) -> Result<(), AppError> {
+    report.results_deleted += state.result_store.delete_results_containing(email).await?;
+    report.copyright_holders_deleted +=
+        CopyrightHolder::delete_containing(&state.db, email).await?;
+    report.history_findings_deleted +=
+        ScanHistoryFinding::delete_containing(&state.db, email).await?;
+
+    // Holders registered from the address's commits alone go with them
+    for name in ScanFileAuthor::names_containing(&state.db, email).await? {
+        if let Some(normalized) = normalize_holder(&name) {
+            report.copyright_holders_deleted +=
+                CopyrightHolder::delete_commit_author(&state.db, &normalized).await?;
+        }
+    }
+    report.file_authors_deleted += ScanFileAuthor::delete_containing(&state.db, email).await?;
+    report.tracked_findings_deleted += TrackedFinding::delete_containing(&state.db, email).await?;
+    report.responses_redacted += ScanScreening::redact_containing(&state.db, email).await?
+        + WebhookDelivery::redact_containing(&state.db, email).await?;
+    if report.results_deleted > 0 {
+        report.checksums_resealed += integrity::reseal(state, "erasure of an e-mail address").await?;
+    }
+    Ok(())
+}
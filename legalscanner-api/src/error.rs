@@ -1,9 +1,9 @@
+use crate::api::models::ErrorResponse;
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
 
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
@@ -19,6 +19,9 @@ pub enum AppError {
     #[error("Docker error: {0}")]
     Docker(String),
 
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+
     #[error("Authentication failed")]
     Unauthorized,
 
@@ -28,6 +31,15 @@ pub enum AppError {
     #[error("Invalid input: {0}")]
     Validation(String),
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Unprocessable: {0}")]
+    Unprocessable(String),
+
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
     #[error("Internal server error: {0}")]
     Internal(String),
 }
@@ -51,19 +63,26 @@ impl IntoResponse for AppError {
                 tracing::error!("Docker error: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Docker error")
             }
+            AppError::Storage(ref e) => {
+                tracing::error!("Storage error: {}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Storage error")
+            }
             AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
             AppError::NotFound(ref msg) => (StatusCode::NOT_FOUND, msg.as_str()),
             AppError::Validation(ref msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
+            AppError::Conflict(ref msg) => (StatusCode::CONFLICT, msg.as_str()),
+            AppError::Unprocessable(ref msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg.as_str()),
+            AppError::PayloadTooLarge(ref msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg.as_str()),
             AppError::Internal(ref msg) => {
                 tracing::error!("Internal error: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
             }
         };
 
-        let body = Json(json!({
-            "error": error_message,
-            "details": self.to_string(),
-        }));
+        let body = Json(ErrorResponse {
+            error: error_message.to_string(),
+            details: self.to_string(),
+        });
 
         (status, body).into_response()
     }
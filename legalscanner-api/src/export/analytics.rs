@@ -0,0 +1,222 @@
+use crate::db::models::scan_result::ScanResult;
+use crate::error::AppError;
+use arrow::array::{ArrayRef, Float32Array, Int32Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use futures_util::Stream;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::io::{self, Write};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Number of findings written per Parquet row group
+const ROW_GROUP_SIZE: usize = 10_000;
+
+/// Size of the chunks a streamed Parquet file is sent in
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Arrow schema for exported findings (one row per finding)
+pub fn findings_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("scan_id", DataType::Utf8, false),
+        Field::new("file_path", DataType::Utf8, false),
+        Field::new("result_type", DataType::Utf8, false),
+        Field::new("license_name", DataType::Utf8, true),
+        Field::new("license_spdx_id", DataType::Utf8, true),
+        Field::new("confidence", DataType::Float32, true),
+        Field::new("copyright_statement", DataType::Utf8, true),
+        Field::new("copyright_holders", DataType::Utf8, true),
+        Field::new("copyright_years", DataType::Utf8, true),
+        Field::new("risk_severity", DataType::Utf8, true),
+        Field::new("ecc_source", DataType::Utf8, true),
+        Field::new("ecc_line_number", DataType::Int32, true),
+        Field::new("ecc_check_id", DataType::Utf8, true),
+        Field::new("license_category", DataType::Utf8, true),
+        Field::new("package_name", DataType::Utf8, true),
+        Field::new("package_version", DataType::Utf8, true),
+        Field::new("package_ecosystem", DataType::Utf8, true),
+        Field::new("package_purl", DataType::Utf8, true),
+        Field::new("merge_provenance", DataType::Utf8, true),
+        Field::new("license_agreement", DataType::Utf8, true),
+        Field::new("waiver_id", DataType::Utf8, true),
+        Field::new("raw_data", DataType::Utf8, true),
+    ])
+}
+
+/// Serialize scan findings to a Snappy-compressed Parquet file
+pub fn write_findings_parquet(results: &[ScanResult]) -> Result<Vec<u8>, AppError> {
+    let mut buffer = Vec::new();
+    write_findings(results, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Serialize scan findings to a Snappy-compressed Parquet file sent as a
+/// stream of chunks. The file is encoded on a blocking thread, so only the
+/// row group being encoded is held in memory rather than the whole file. If
+/// encoding fails, the stream ends with the error.
+pub fn stream_findings_parquet(results: Vec<ScanResult>) -> impl Stream<Item = io::Result<Bytes>> {
+    let (sender, receiver) = mpsc::channel(4);
+    tokio::task::spawn_blocking(move || {
+        let mut writer = ChunkWriter {
+            buffer: Vec::with_capacity(STREAM_CHUNK_SIZE),
+            sender: sender.clone(),
+        };
+        let written = write_findings(&results, &mut writer).and_then(|()| {
+            writer
+                .flush()
+                .map_err(|e| AppError::Internal(format!("Failed to send Parquet file: {}", e)))
+        });
+        if let Err(e) = written {
+            tracing::warn!("Parquet export ended early: {}", e);
+            // Fails as well if the client went away
+            let _ = sender.blocking_send(Err(io::Error::other(e.to_string())));
+        }
+    });
+
+    futures_util::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    })
+}
+
+fn write_findings<W: Write + Send>(results: &[ScanResult], output: &mut W) -> Result<(), AppError> {
+    let schema = Arc::new(findings_schema());
+
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_max_row_group_size(ROW_GROUP_SIZE)
+        .build();
+
+    let mut writer = ArrowWriter::try_new(output, schema.clone(), Some(props))
+        .map_err(|e| AppError::Internal(format!("Failed to create Parquet writer: {}", e)))?;
+
+    for chunk in results.chunks(ROW_GROUP_SIZE) {
+        let batch = build_record_batch(schema.clone(), chunk)?;
+        writer
+            .write(&batch)
+            .map_err(|e| AppError::Internal(format!("Failed to write Parquet batch: {}", e)))?;
+    }
+
+    writer
+        .close()
+        .map_err(|e| AppError::Internal(format!("Failed to finalize Parquet file: {}", e)))?;
+
+    Ok(())
+}
+
+/// Sends what is written to it over a channel, in chunks of
+/// `STREAM_CHUNK_SIZE` bytes
+struct ChunkWriter {
+    buffer: Vec<u8>,
+    sender: mpsc::Sender<io::Result<Bytes>>,
+}
+
+impl ChunkWriter {
+    fn send(&mut self) -> io::Result<()> {
+        let chunk = std::mem::replace(&mut self.buffer, Vec::with_capacity(STREAM_CHUNK_SIZE));
+        self.sender
+            .blocking_send(Ok(Bytes::from(chunk)))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Download was cancelled"))
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(data);
+        if self.buffer.len() >= STREAM_CHUNK_SIZE {
+            self.send()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.send()
+    }
+}
+
+fn build_record_batch(schema: Arc<Schema>, results: &[ScanResult]) -> Result<RecordBatch, AppError> {
+    fn strings<'a>(
+        results: &'a [ScanResult],
+        f: impl Fn(&'a ScanResult) -> Option<&'a str>,
+    ) -> ArrayRef {
+        Arc::new(StringArray::from(results.iter().map(f).collect::<Vec<_>>()))
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Int64Array::from(results.iter().map(|r| r.id).collect::<Vec<_>>())),
+        strings(results, |r| Some(r.scan_id.as_str())),
+        strings(results, |r| Some(r.file_path.as_str())),
+        strings(results, |r| Some(r.result_type.as_str())),
+        strings(results, |r| r.license_name.as_deref()),
+        strings(results, |r| r.license_spdx_id.as_deref()),
+        Arc::new(Float32Array::from(
+            results.iter().map(|r| r.confidence).collect::<Vec<_>>(),
+        )),
+        strings(results, |r| r.copyright_statement.as_deref()),
+        strings(results, |r| r.copyright_holders.as_deref()),
+        strings(results, |r| r.copyright_years.as_deref()),
+        strings(results, |r| r.risk_severity.as_deref()),
+        strings(results, |r| r.ecc_source.as_deref()),
+        Arc::new(Int32Array::from(
+            results.iter().map(|r| r.ecc_line_number).collect::<Vec<_>>(),
+        )),
+        strings(results, |r| r.ecc_check_id.as_deref()),
+        strings(results, |r| r.license_category.as_deref()),
+        strings(results, |r| r.package_name.as_deref()),
+        strings(results, |r| r.package_version.as_deref()),
+        strings(results, |r| r.package_ecosystem.as_deref()),
+        strings(results, |r| r.package_purl.as_deref()),
+        strings(results, |r| r.merge_provenance.as_deref()),
+        strings(results, |r| r.license_agreement.as_deref()),
+        strings(results, |r| r.waiver_id.as_deref()),
+        strings(results, |r| r.raw_data.as_deref()),
+    ];
+
+    RecordBatch::try_new(schema, columns)
+        .map_err(|e| AppError::Internal(format!("Failed to build Arrow record batch: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn license_result(id: i64) -> ScanResult {
+        ScanResult {
+            id,
+            license_name: Some("MIT".to_string()),
+            license_spdx_id: Some("MIT".to_string()),
+            confidence: Some(1.0),
+            license_category: Some("code".to_string()),
+            ..ScanResult::for_test("license", &format!("src/file_{}.rs", id))
+        }
+    }
+
+    #[test]
+    fn test_write_findings_parquet() {
+        let results = vec![license_result(1), license_result(2)];
+        let bytes = write_findings_parquet(&results).unwrap();
+
+        // Parquet files start and end with the "PAR1" magic bytes
+        assert_eq!(&bytes[..4], b"PAR1");
+        assert_eq!(&bytes[bytes.len() - 4..], b"PAR1");
+    }
+
+    #[tokio::test]
+    async fn test_stream_findings_parquet() {
+        use futures_util::TryStreamExt;
+
+        // Enough findings for several row groups and chunks
+        let results: Vec<_> = (1..=ROW_GROUP_SIZE as i64 * 2 + 1).map(license_result).collect();
+        let expected = write_findings_parquet(&results).unwrap();
+
+        let chunks: Vec<Bytes> = stream_findings_parquet(results).try_collect().await.unwrap();
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.concat(), expected);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::db::models::scan::RiskLevelCount;
+use crate::db::models::scan_result::ScanLicenseCount;
+use crate::export::license_expression;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use utoipa::ToSchema;
+
+/// Bumped whenever the report layout changes, so aggregators can tell
+/// exports from different releases apart
+const SCHEMA_VERSION: u32 = 1;
+
+/// Bucket for licenses outside the SPDX license list. Their names can
+/// identify an organisation or a product, so they are never exported.
+pub const OTHER_LICENSE: &str = "LicenseRef-other";
+
+/// Instance-level license histogram and risk distribution. Contains counts
+/// only (no scan IDs, repository URLs or file paths), so reports from
+/// several instances can be summed field by field.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnonymizedAnalytics {
+    pub schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    pub generated_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    pub scans_completed: i64,
+    pub risk_levels: BTreeMap<String, i64>,
+    pub licenses: Vec<LicenseBucket>,
+}
+
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct LicenseBucket {
+    pub license: String,
+    /// Completed scans with at least one file under this license
+    pub scan_count: i64,
+    pub file_count: i64,
+}
+
+pub fn build_report(
+    instance: Option<String>,
+    since: Option<String>,
+    risk_levels: Vec<RiskLevelCount>,
+    licenses: Vec<ScanLicenseCount>,
+) -> AnonymizedAnalytics {
+    let risk_levels: BTreeMap<String, i64> = risk_levels
+        .into_iter()
+        .map(|r| (r.risk_level, r.scan_count))
+        .collect();
+
+    AnonymizedAnalytics {
+        schema_version: SCHEMA_VERSION,
+        instance,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        since,
+        scans_completed: risk_levels.values().sum(),
+        risk_levels,
+        licenses: license_histogram(licenses),
+    }
+}
+
+/// Collapse per-scan license counts into one bucket per SPDX license
+fn license_histogram(counts: Vec<ScanLicenseCount>) -> Vec<LicenseBucket> {
+    let mut buckets: BTreeMap<String, (HashSet<String>, i64)> = BTreeMap::new();
+    for count in counts {
+        let Some(license) = bucket_license(&count.license) else {
+            continue;
+        };
+        let (scans, files) = buckets.entry(license).or_default();
+        scans.insert(count.scan_id);
+        *files += count.file_count;
+    }
+
+    let mut histogram: Vec<LicenseBucket> = buckets
+        .into_iter()
+        .map(|(license, (scans, file_count))| LicenseBucket {
+            license,
+            scan_count: scans.len() as i64,
+            file_count,
+        })
+        .collect();
+    histogram.sort_by(|a, b| {
+        b.scan_count
+            .cmp(&a.scan_count)
+            .then(a.license.cmp(&b.license))
+    });
+    histogram
+}
+
+/// SPDX expression for a scanner license name, or [`OTHER_LICENSE`] if it
+/// references anything outside the SPDX license list
+fn bucket_license(name: &str) -> Option<String> {
+    let expression = license_expression::normalize(name)?;
+    if expression.contains("LicenseRef-") {
+        Some(OTHER_LICENSE.to_string())
+    } else {
+        Some(expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(scan_id: &str, license: &str, file_count: i64) -> ScanLicenseCount {
+        ScanLicenseCount {
+            scan_id: scan_id.to_string(),
+            license: license.to_string(),
+            file_count,
+        }
+    }
+
+    #[test]
+    fn test_license_histogram_merges_and_hides_custom_licenses() {
+        let histogram = license_histogram(vec![
+            count("scan-1", "MIT", 3),
+            count("scan-1", "MIT License", 1),
+            count("scan-2", "MIT", 2),
+            count("scan-2", "Acme Corp Internal License", 5),
+            count("scan-3", "No_license_found", 9),
+        ]);
+
+        assert_eq!(
+            histogram,
+            vec![
+                LicenseBucket {
+                    license: "MIT".to_string(),
+                    scan_count: 2,
+                    file_count: 6,
+                },
+                LicenseBucket {
+                    license: OTHER_LICENSE.to_string(),
+                    scan_count: 1,
+                    file_count: 5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_report_totals_risk_levels() {
+        let report = build_report(
+            Some("payments".to_string()),
+            None,
+            vec![
+                RiskLevelCount {
+                    risk_level: "low".to_string(),
+                    scan_count: 4,
+                },
+                RiskLevelCount {
+                    risk_level: "high".to_string(),
+                    scan_count: 1,
+                },
+            ],
+            Vec::new(),
+        );
+
+        assert_eq!(report.scans_completed, 5);
+        assert_eq!(report.risk_levels.get("high"), Some(&1));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json.get("since").is_none());
+        assert_eq!(json["instance"], "payments");
+    }
+}
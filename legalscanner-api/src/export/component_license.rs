@@ -0,0 +1,303 @@
+//! Component-level concluded license. The files of a repository often show
+//! several licenses: vendored code, test fixtures, a stray GPL header. The
+//! license of the component as a whole is concluded by vote, in order of
+//! precedence:
+//!
+//! 1. the license files at the root (`LICENSE`, `COPYING`, ...), refined by
+//!    a package manifest whose expression covers them (`MIT OR Apache-2.0`
+//!    over `LICENSE-MIT` and `LICENSE-APACHE`),
+//! 2. the license package manifests at the root declare, and
+//! 3. the license most files carry.
+//!
+//! The rationale names the evidence and the files that disagree, so a
+//! reviewer can tell why a license was concluded. Exports use the
+//! conclusion for the package-level license fields.
+
+use crate::db::models::scan_result::ScanResult;
+use crate::export::license_expression;
+use crate::export::sarif::relative_uri;
+use crate::scanner::declared::license_matches;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// File name prefixes of license files, compared in upper case
+const LICENSE_FILE_PREFIXES: &[&str] = &["LICENSE", "LICENCE", "COPYING", "UNLICENSE"];
+
+/// Package manifests that declare the license of the component
+const MANIFEST_FILE_NAMES: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.cfg",
+    "setup.py",
+    "pom.xml",
+    "composer.json",
+];
+
+/// Licenses listed by name in the rationale before the rest are counted
+const MAX_DISSENTING_LICENSES: usize = 5;
+
+/// Evidence a component license was concluded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConclusionBasis {
+    LicenseFile,
+    Manifest,
+    Majority,
+    /// No license findings to conclude from
+    None,
+}
+
+/// License concluded for a component, with the reasons
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ComponentLicense {
+    /// SPDX expression, `NOASSERTION` without license findings
+    pub concluded: String,
+    /// What the license files or manifests declare, `NOASSERTION` if none do
+    pub declared: String,
+    pub basis: ConclusionBasis,
+    pub rationale: String,
+}
+
+/// Conclude the license of the component a scan covers from its license
+/// findings. Paths are taken relative to the scanned directory, so a scan of
+/// a subdirectory concludes the license of that subdirectory.
+pub fn conclude(scan_id: &str, results: &[ScanResult]) -> ComponentLicense {
+    // License expression of each file, all of its findings together
+    let mut findings: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for result in results.iter().filter(|r| r.result_type == "license") {
+        if let Some(expression) = license_expression::of_result(result) {
+            findings
+                .entry(relative_uri(&result.file_path, scan_id))
+                .or_default()
+                .push(expression);
+        }
+    }
+    let files: BTreeMap<String, String> = findings
+        .into_iter()
+        .map(|(path, expressions)| (path, license_expression::conjunction(expressions)))
+        .collect();
+
+    let mut license_files = Vec::new();
+    let mut manifests = Vec::new();
+    let mut votes: BTreeMap<&str, usize> = BTreeMap::new();
+    for (path, expression) in &files {
+        if is_license_file(path) {
+            license_files.push((path.as_str(), expression.as_str()));
+        } else if is_manifest(path) {
+            manifests.push((path.as_str(), expression.as_str()));
+        } else {
+            *votes.entry(expression.as_str()).or_default() += 1;
+        }
+    }
+
+    let license_file_expression = declared_expression(&license_files);
+    let manifest_expression = declared_expression(&manifests);
+    let mut rationale = Vec::new();
+    if let Some(expression) = &license_file_expression {
+        rationale.push(declaration(&license_files, "states", "state", expression));
+    }
+    if let Some(expression) = &manifest_expression {
+        rationale.push(declaration(&manifests, "declares", "declare", expression));
+    }
+
+    let (declared, basis) = match (license_file_expression, manifest_expression) {
+        (Some(license_file), Some(manifest)) if covers(&manifest, &license_file) => {
+            rationale.push("The manifest declaration covers the license files.".to_string());
+            (Some(manifest), ConclusionBasis::LicenseFile)
+        }
+        (Some(license_file), Some(_)) => {
+            rationale.push(
+                "The manifest declaration disagrees with the license files, which take \
+                 precedence."
+                    .to_string(),
+            );
+            (Some(license_file), ConclusionBasis::LicenseFile)
+        }
+        (Some(license_file), None) => (Some(license_file), ConclusionBasis::LicenseFile),
+        (None, Some(manifest)) => (Some(manifest), ConclusionBasis::Manifest),
+        (None, None) => (None, ConclusionBasis::Majority),
+    };
+
+    let voters: usize = votes.values().sum();
+    let concluded = match declared.clone() {
+        Some(declared) => declared,
+        None => {
+            let Some(&most) = votes.values().max() else {
+                return ComponentLicense {
+                    concluded: "NOASSERTION".to_string(),
+                    declared: "NOASSERTION".to_string(),
+                    basis: ConclusionBasis::None,
+                    rationale: "No license findings.".to_string(),
+                };
+            };
+            // A tie concludes every license sharing the most files
+            let winners: Vec<String> = votes
+                .iter()
+                .filter(|(_, count)| **count == most)
+                .map(|(expression, _)| expression.to_string())
+                .collect();
+            let concluded = license_expression::conjunction(winners.iter().cloned());
+            rationale.push(format!(
+                "No license file or manifest declares a license; {} {} the most common, in \
+                 {} of {} files with license findings.",
+                winners.join(" and "),
+                if winners.len() == 1 { "is" } else { "are" },
+                most * winners.len(),
+                voters
+            ));
+            concluded
+        }
+    };
+
+    let mut dissent: Vec<(&str, usize)> = votes
+        .iter()
+        .filter(|(expression, _)| !covers(&concluded, expression))
+        .map(|(expression, count)| (*expression, *count))
+        .collect();
+    if !dissent.is_empty() {
+        dissent.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let dissenting: usize = dissent.iter().map(|(_, count)| count).sum();
+        let mut listed: Vec<String> = dissent
+            .iter()
+            .take(MAX_DISSENTING_LICENSES)
+            .map(|(expression, count)| format!("{} ({})", expression, count))
+            .collect();
+        if dissent.len() > MAX_DISSENTING_LICENSES {
+            listed.push(format!("{} more", dissent.len() - MAX_DISSENTING_LICENSES));
+        }
+        rationale.push(format!(
+            "{} of {} files with license findings carry other licenses: {}.",
+            dissenting,
+            voters,
+            listed.join(", ")
+        ));
+    } else if basis != ConclusionBasis::Majority && voters > 0 {
+        rationale.push("No file with license findings disagrees.".to_string());
+    }
+
+    ComponentLicense {
+        concluded,
+        declared: declared.unwrap_or_else(|| "NOASSERTION".to_string()),
+        basis,
+        rationale: rationale.join(" "),
+    }
+}
+
+fn is_license_file(path: &str) -> bool {
+    let name = path.to_uppercase();
+    !path.contains('/') && LICENSE_FILE_PREFIXES.iter().any(|p| name.starts_with(p))
+}
+
+fn is_manifest(path: &str) -> bool {
+    !path.contains('/') && MANIFEST_FILE_NAMES.contains(&path)
+}
+
+/// Every license the files declare, `None` if there are none
+fn declared_expression(files: &[(&str, &str)]) -> Option<String> {
+    (!files.is_empty())
+        .then(|| license_expression::conjunction(files.iter().map(|(_, e)| e.to_string())))
+}
+
+/// Whether every license of `covered` satisfies `expression`
+fn covers(expression: &str, covered: &str) -> bool {
+    license_expression::license_ids(covered)
+        .iter()
+        .all(|id| license_matches(id, expression))
+}
+
+/// "LICENSE states MIT." and the like
+fn declaration(files: &[(&str, &str)], singular: &str, plural: &str, expression: &str) -> String {
+    let names: Vec<&str> = files.iter().map(|(path, _)| *path).collect();
+    format!(
+        "{} {} {}.",
+        names.join(", "),
+        if names.len() == 1 { singular } else { plural },
+        expression
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(file: &str, license: &str) -> ScanResult {
+        ScanResult {
+            license_name: Some(license.to_string()),
+            license_spdx_id: Some(license.to_string()),
+            license_category: Some("code".to_string()),
+            ..ScanResult::for_test("license", &format!("/tmp/workspace/scan-1/{}", file))
+        }
+    }
+
+    #[test]
+    fn test_majority_without_declaration() {
+        let results = vec![
+            result("src/a.rs", "MIT"),
+            result("src/b.rs", "MIT"),
+            result("vendor/c.c", "GPL-2.0-only"),
+        ];
+        let license = conclude("scan-1", &results);
+        assert_eq!(license.concluded, "MIT");
+        assert_eq!(license.declared, "NOASSERTION");
+        assert_eq!(license.basis, ConclusionBasis::Majority);
+        assert_eq!(
+            license.rationale,
+            "No license file or manifest declares a license; MIT is the most common, in 2 of 3 \
+             files with license findings. 1 of 3 files with license findings carry other \
+             licenses: GPL-2.0-only (1)."
+        );
+    }
+
+    #[test]
+    fn test_license_file_takes_precedence() {
+        let results = vec![
+            result("LICENSE", "Apache-2.0"),
+            result("package.json", "MIT"),
+            result("src/a.js", "MIT"),
+            result("src/b.js", "MIT"),
+        ];
+        let license = conclude("scan-1", &results);
+        assert_eq!(license.concluded, "Apache-2.0");
+        assert_eq!(license.declared, "Apache-2.0");
+        assert_eq!(license.basis, ConclusionBasis::LicenseFile);
+        assert!(license.rationale.starts_with(
+            "LICENSE states Apache-2.0. package.json declares MIT. The manifest declaration \
+             disagrees"
+        ));
+        assert!(license
+            .rationale
+            .ends_with("2 of 2 files with license findings carry other licenses: MIT (2)."));
+    }
+
+    #[test]
+    fn test_manifest_expression_covers_license_files() {
+        let results = vec![
+            result("LICENSE-APACHE", "Apache-2.0"),
+            result("LICENSE-MIT", "MIT"),
+            result("Cargo.toml", "MIT OR Apache-2.0"),
+            result("src/lib.rs", "MIT"),
+        ];
+        let license = conclude("scan-1", &results);
+        assert_eq!(license.concluded, "MIT OR Apache-2.0");
+        assert_eq!(license.basis, ConclusionBasis::LicenseFile);
+        assert_eq!(
+            license.rationale,
+            "LICENSE-APACHE, LICENSE-MIT state Apache-2.0 AND MIT. Cargo.toml declares MIT OR \
+             Apache-2.0. The manifest declaration covers the license files. No file with \
+             license findings disagrees."
+        );
+
+        let manifest_only = conclude("scan-1", &results[2..]);
+        assert_eq!(manifest_only.basis, ConclusionBasis::Manifest);
+        assert_eq!(manifest_only.declared, "MIT OR Apache-2.0");
+    }
+
+    #[test]
+    fn test_no_license_findings() {
+        let license = conclude("scan-1", &[result("src/a.rs", "No_license_found")]);
+        assert_eq!(license.concluded, "NOASSERTION");
+        assert_eq!(license.basis, ConclusionBasis::None);
+    }
+}
@@ -0,0 +1,469 @@
+use crate::db::models::scan::Scan;
+use crate::db::models::scan_result::ScanResult;
+use crate::db::models::ScanScreening;
+use crate::export::sarif::repository_path;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+type Groups<'a> = BTreeMap<&'static str, (&'static Functionality, Vec<&'a ScanResult>)>;
+
+/// Rule-derived description of what a group of ECC findings does, in the
+/// wording trade-compliance filings ask for
+struct Functionality {
+    title: &'static str,
+    description: &'static str,
+    /// Whether the functionality is encryption in the Category 5 Part 2 sense,
+    /// as opposed to hashing or a bare library dependency
+    encryption: bool,
+}
+
+const SYMMETRIC: Functionality = Functionality {
+    title: "Symmetric encryption",
+    description: "Encrypts or decrypts data with symmetric ciphers such as AES-256 or ChaCha20",
+    encryption: true,
+};
+const ASYMMETRIC: Functionality = Functionality {
+    title: "Public key cryptography",
+    description:
+        "Uses RSA or elliptic-curve algorithms for key exchange, encryption or digital signatures",
+    encryption: true,
+};
+const KEY_GENERATION: Functionality = Functionality {
+    title: "Key generation",
+    description:
+        "Generates cryptographic keys; key sizes determine whether controlled strength is reached",
+    encryption: true,
+};
+const CONTROLLED: Functionality = Functionality {
+    title: "Legacy or controlled algorithms",
+    description: "Implements or calls algorithms such as DES, 3DES, RC4 or Blowfish",
+    encryption: true,
+};
+const HASHING: Functionality = Functionality {
+    title: "Cryptographic hashing",
+    description:
+        "Computes cryptographic hashes (SHA-2, SHA-3, BLAKE2) for integrity or authentication",
+    encryption: false,
+};
+const LIBRARY: Functionality = Functionality {
+    title: "Cryptographic library usage",
+    description: "Depends on a third-party cryptographic library; the functionality used must be confirmed by review",
+    encryption: false,
+};
+const OTHER: Functionality = Functionality {
+    title: "Other export-control findings",
+    description: "Matched by rules outside the built-in export control set",
+    encryption: false,
+};
+
+/// Functionality described by an ECC rule. Semgrep prefixes rule IDs with the
+/// path of the rules file, so only the last segment is matched.
+fn functionality(check_id: Option<&str>) -> &'static Functionality {
+    let rule = check_id
+        .map(|id| id.rsplit('.').next().unwrap_or(id))
+        .unwrap_or_default();
+    match rule {
+        "ecc-strong-symmetric-encryption" | "ecc-aes-implementation" => &SYMMETRIC,
+        "ecc-strong-rsa-keys" | "ecc-rsa-implementation" | "ecc-elliptic-curve-crypto" => {
+            &ASYMMETRIC
+        }
+        "ecc-key-generation" => &KEY_GENERATION,
+        "ecc-controlled-algorithms" => &CONTROLLED,
+        "ecc-cryptographic-hash-functions" => &HASHING,
+        rule if rule.starts_with("ecc-") && rule.ends_with("-crypto-libraries") => &LIBRARY,
+        _ => &OTHER,
+    }
+}
+
+/// Render the export control report filed with trade-compliance: what the
+/// cryptography in the scanned code does, where it is, why the proposed
+/// classification follows and blocks for the reviewers to sign
+pub fn build_export_control_report(
+    scan: &Scan,
+    results: &[ScanResult],
+    screening: Option<&ScanScreening>,
+    custom_fields: &[(String, String)],
+    generated_at: &str,
+) -> String {
+    let repo_name = scan
+        .git_url
+        .trim_end_matches(".git")
+        .rsplit('/')
+        .next()
+        .unwrap_or("repository");
+
+    let groups = group(results);
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# Export control report: {}\n", cell(repo_name));
+    let _ = writeln!(out, "| | |");
+    let _ = writeln!(out, "|---|---|");
+    let _ = writeln!(out, "| Repository | {} |", cell(&scan.git_url));
+    if let Some(branch) = &scan.branch {
+        let _ = writeln!(out, "| Branch | {} |", cell(branch));
+    }
+    if let Some(path) = &scan.scan_path {
+        let _ = writeln!(out, "| Path | {} |", cell(path));
+    }
+    let _ = writeln!(out, "| Scan | {} |", scan.id);
+    let _ = writeln!(
+        out,
+        "| Scanned | {} |",
+        scan.completed_at.as_deref().unwrap_or(&scan.created_at)
+    );
+    let _ = writeln!(out, "| Report generated | {} |", generated_at);
+    match screening {
+        Some(screening) => {
+            let _ = writeln!(
+                out,
+                "| Screening | {}{} |",
+                screening.status,
+                screening
+                    .reference
+                    .as_deref()
+                    .map(|r| format!(" (reference {})", cell(r)))
+                    .unwrap_or_default()
+            );
+        }
+        None => {
+            let _ = writeln!(out, "| Screening | not submitted |");
+        }
+    }
+    for (label, value) in custom_fields {
+        let _ = writeln!(out, "| {} | {} |", cell(label), cell(value));
+    }
+    out.push('\n');
+
+    write_functionality(&mut out, &groups);
+    write_source_references(&mut out, &groups, scan);
+    write_rationale(&mut out, &groups, screening);
+    write_sign_off(&mut out);
+
+    out
+}
+
+/// ECC findings by the functionality their rule describes, keyed by title so
+/// sections come out in a stable order
+fn group(results: &[ScanResult]) -> Groups<'_> {
+    let mut groups = Groups::new();
+    for result in results.iter().filter(|r| r.result_type == "ecc") {
+        let functionality = functionality(result.ecc_check_id.as_deref());
+        groups
+            .entry(functionality.title)
+            .or_insert_with(|| (functionality, Vec::new()))
+            .1
+            .push(result);
+    }
+    groups
+}
+
+/// Section 1: one row per kind of cryptographic functionality found
+fn write_functionality(out: &mut String, groups: &Groups) {
+    let _ = writeln!(out, "## 1. Cryptographic functionality\n");
+    if groups.is_empty() {
+        let _ = writeln!(out, "No cryptographic functionality was detected.\n");
+        return;
+    }
+    let _ = writeln!(out, "| Functionality | Description | Findings | Files |");
+    let _ = writeln!(out, "|---|---|---|---|");
+    for (functionality, findings) in groups.values() {
+        let files: BTreeSet<&str> = findings.iter().map(|r| r.file_path.as_str()).collect();
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} |",
+            functionality.title,
+            functionality.description,
+            findings.len(),
+            files.len()
+        );
+    }
+    out.push('\n');
+}
+
+/// Section 2: every finding with its location, grouped like section 1
+fn write_source_references(out: &mut String, groups: &Groups, scan: &Scan) {
+    let _ = writeln!(out, "## 2. Source references\n");
+    if groups.is_empty() {
+        let _ = writeln!(out, "None.\n");
+        return;
+    }
+    for (functionality, findings) in groups.values() {
+        let _ = writeln!(out, "### {}\n", functionality.title);
+        let _ = writeln!(
+            out,
+            "| Location | Rule | Severity | Matched code | Review |"
+        );
+        let _ = writeln!(out, "|---|---|---|---|---|");
+        let mut findings = findings.clone();
+        findings.sort_by(|a, b| {
+            (&a.file_path, a.ecc_line_number).cmp(&(&b.file_path, b.ecc_line_number))
+        });
+        for finding in findings {
+            let path = repository_path(&finding.file_path, &scan.id, scan.scan_path.as_deref());
+            let location = match finding.ecc_line_number {
+                Some(line) => format!("{}:{}", path, line),
+                None => path,
+            };
+            let rule = finding
+                .ecc_check_id
+                .as_deref()
+                .map(|id| id.rsplit('.').next().unwrap_or(id))
+                .unwrap_or("-");
+            let matched = finding
+                .raw_data
+                .as_deref()
+                .and_then(|content| content.split_once("Matched code: "))
+                .map(|(_, code)| code.trim().to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let review = match &finding.waiver_id {
+                Some(waiver) => format!("waived ({})", waiver),
+                None => finding.review_status.clone(),
+            };
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                cell(&location),
+                cell(rule),
+                finding.risk_severity.as_deref().unwrap_or("-"),
+                cell(&matched),
+                review
+            );
+        }
+        out.push('\n');
+    }
+}
+
+/// Section 3: proposed classification and the facts it rests on. The
+/// reviewers make the determination in section 4.
+fn write_rationale(out: &mut String, groups: &Groups, screening: Option<&ScanScreening>) {
+    let _ = writeln!(out, "## 3. Classification rationale\n");
+
+    let encryption: Vec<&str> = groups
+        .values()
+        .filter(|(f, _)| f.encryption)
+        .map(|(f, _)| f.title)
+        .collect();
+    let proposed = if !encryption.is_empty() {
+        "5D002"
+    } else if groups.is_empty() {
+        "none (no cryptographic functionality detected)"
+    } else {
+        "to be determined by review"
+    };
+    let _ = writeln!(out, "Proposed classification: **{}**\n", proposed);
+
+    if !encryption.is_empty() {
+        let _ = writeln!(
+            out,
+            "- The code implements or calls encryption functionality ({}). Software whose \
+             primary function or design includes such functionality is described by ECCN 5D002 \
+             unless an exclusion applies; the rules that matched flag algorithms and key sizes \
+             above the controlled thresholds.",
+            encryption.join(", ").to_lowercase()
+        );
+    } else if !groups.is_empty() {
+        let _ = writeln!(
+            out,
+            "- Only hashing or library usage was detected. These alone do not establish \
+             encryption functionality; review whether the libraries are used for \
+             confidentiality."
+        );
+    } else {
+        let _ = writeln!(out, "- No export-control rule matched the scanned code.");
+    }
+    let high: usize = groups
+        .values()
+        .flat_map(|(_, findings)| findings)
+        .filter(|r| matches!(r.risk_severity.as_deref(), Some("critical" | "high")))
+        .count();
+    if high > 0 {
+        let _ = writeln!(
+            out,
+            "- {} finding(s) are of high or critical severity.",
+            high
+        );
+    }
+    let waived = groups
+        .values()
+        .flat_map(|(_, findings)| findings)
+        .filter(|r| r.waiver_id.is_some())
+        .count();
+    if waived > 0 {
+        let _ = writeln!(
+            out,
+            "- {} finding(s) are covered by waivers and remain listed for reference.",
+            waived
+        );
+    }
+    match screening {
+        Some(screening) => {
+            let _ = writeln!(
+                out,
+                "- Screening status is `{}` as of {}.",
+                screening.status, screening.updated_at
+            );
+        }
+        None => {
+            let _ = writeln!(out, "- The scan was not submitted for screening.");
+        }
+    }
+    let _ = writeln!(
+        out,
+        "- Exclusions (mass market, open source publication, authentication-only use) are \
+         not assessed automatically and must be recorded by the reviewers.\n"
+    );
+}
+
+/// Section 4: blank blocks for the engineering and compliance sign-offs
+fn write_sign_off(out: &mut String) {
+    let _ = writeln!(out, "## 4. Reviewer sign-off\n");
+    for role in ["Engineering reviewer", "Trade compliance reviewer"] {
+        let _ = writeln!(out, "### {}\n", role);
+        let _ = writeln!(
+            out,
+            "Determination: [ ] 5D002  [ ] 5D992  [ ] EAR99  [ ] Other: ____________\n"
+        );
+        let _ = writeln!(
+            out,
+            "Comments: ______________________________________________\n"
+        );
+        let _ = writeln!(
+            out,
+            "Name: ______________________  Title: ______________________\n"
+        );
+        let _ = writeln!(
+            out,
+            "Signature: _________________  Date: _______________________\n"
+        );
+    }
+}
+
+fn cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan() -> Scan {
+        Scan {
+            id: "scan-1".to_string(),
+            git_url: "https://github.com/acme/app".to_string(),
+            status: "completed".to_string(),
+            error_message: None,
+            created_at: "2025-01-14 10:00:00".to_string(),
+            started_at: None,
+            completed_at: None,
+            created_by_key_id: None,
+            git_token: None,
+            fossology_status: "completed".to_string(),
+            semgrep_status: "completed".to_string(),
+            fossology_started_at: None,
+            fossology_completed_at: None,
+            semgrep_started_at: None,
+            semgrep_completed_at: None,
+            fossology_error: None,
+            semgrep_error: None,
+            risk_score: None,
+            risk_level: None,
+            risk_factors: None,
+            risk_algorithm_version: None,
+            risk_config_version: None,
+            risk_assessed_at: None,
+            semgrep_rulesets: None,
+            quick: false,
+            approved_at: None,
+            reviewed_at: None,
+            warnings: None,
+            parent_scan_id: None,
+            due_diligence: false,
+            callback_url: None,
+            scan_path: None,
+            branch: None,
+            commit_sha: None,
+            legal_hold_at: None,
+            legal_hold_reason: None,
+            submodules: false,
+            submodule_token_encrypted: None,
+            credential_id: None,
+            git_authorship: false,
+            results_purged_at: None,
+            results_archived_at: None,
+            deleted_at: None,
+            policy: None,
+        }
+    }
+
+    fn ecc(file: &str, line: i32, check_id: &str, severity: &str) -> ScanResult {
+        ScanResult {
+            raw_data: Some("Crypto detected.\n\nMatched code: `Aes256::new(key)`".to_string()),
+            risk_severity: Some(severity.to_string()),
+            ecc_source: Some("semgrep".to_string()),
+            ecc_line_number: Some(line),
+            ecc_check_id: Some(check_id.to_string()),
+            ..ScanResult::for_test("ecc", file)
+        }
+    }
+
+    #[test]
+    fn test_functionality_strips_rules_file_prefix() {
+        assert_eq!(
+            functionality(Some("semgrep-rules.ecc-aes-implementation")).title,
+            SYMMETRIC.title
+        );
+        assert_eq!(
+            functionality(Some("ecc-python-crypto-libraries")).title,
+            LIBRARY.title
+        );
+        assert_eq!(functionality(None).title, OTHER.title);
+    }
+
+    #[test]
+    fn test_rationale_proposes_5d002_for_encryption() {
+        let results = vec![
+            ecc("src/a.rs", 3, "ecc-strong-rsa-keys", "high"),
+            ecc("src/b.rs", 9, "ecc-cryptographic-hash-functions", "low"),
+        ];
+        let mut out = String::new();
+        write_rationale(&mut out, &group(&results), None);
+
+        assert!(out.contains("Proposed classification: **5D002**"));
+        assert!(out.contains("(public key cryptography)"));
+        assert!(out.contains("1 finding(s) are of high or critical severity."));
+        assert!(out.contains("not submitted for screening"));
+    }
+
+    #[test]
+    fn test_rationale_defers_hashing_only() {
+        let results = vec![ecc(
+            "src/b.rs",
+            9,
+            "ecc-cryptographic-hash-functions",
+            "low",
+        )];
+        let mut out = String::new();
+        write_rationale(&mut out, &group(&results), None);
+
+        assert!(out.contains("**to be determined by review**"));
+    }
+
+    #[test]
+    fn test_report_lists_source_references_and_sign_off() {
+        let results = vec![ecc(
+            "/tmp/legalscanner/scan-1/src/a.rs",
+            12,
+            "semgrep-rules.ecc-aes-implementation",
+            "high",
+        )];
+        let out = build_export_control_report(&scan(), &results, None, &[], "2025-01-15 09:00:00");
+
+        assert!(out.contains("| Symmetric encryption | Encrypts or decrypts"));
+        assert!(out.contains("### Symmetric encryption"));
+        assert!(out.contains(
+            "| src/a.rs:12 | ecc-aes-implementation | high | `Aes256::new(key)` | pending |"
+        ));
+        assert!(out.contains("| Screening | not submitted |"));
+        assert_eq!(out.matches("Signature: ").count(), 2);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::db::models::ScanResult;
+use spdx::expression::{ExprNode, Operator};
+use spdx::{Expression, LicenseItem, LicenseReq};
+use std::collections::BTreeSet;
+
+/// Scanner values meaning "no license found" rather than a license
+const PLACEHOLDERS: &[&str] = &["No_license_found", "NOASSERTION", "NONE"];
+
+/// Turn a scanner license name into a valid SPDX license expression: a
+/// license list ID, an already valid compound expression, or a `LicenseRef-`
+/// for anything the SPDX license list does not know. Returns `None` for
+/// empty values and "nothing found" placeholders.
+pub fn normalize(name: &str) -> Option<String> {
+    let name = name.trim();
+    if name.is_empty() || PLACEHOLDERS.iter().any(|p| p.eq_ignore_ascii_case(name)) {
+        return None;
+    }
+
+    if let Some(id) = spdx::license_id(name) {
+        return Some(id.name.to_string());
+    }
+
+    // Semgrep metadata and package manifests may already carry expressions
+    if Expression::parse(name).is_ok() {
+        return Some(name.to_string());
+    }
+
+    // Fossology reports full names such as "Apache License 2.0"
+    if let Some((id, _, _)) = spdx::identifiers::LICENSES
+        .iter()
+        .find(|(_, full_name, _)| full_name.eq_ignore_ascii_case(name))
+    {
+        return Some(id.to_string());
+    }
+
+    if let Some((id, consumed)) = spdx::imprecise_license_id(name) {
+        if consumed == name.len() {
+            return Some(id.name.to_string());
+        }
+    }
+
+    // Expressions with lowercase operators, `/` for `OR` or deprecated IDs,
+    // e.g. "GPL-2.0 or MIT"
+    if is_compound(name) {
+        if let Ok(Some(canonical)) = Expression::canonicalize(name) {
+            if Expression::parse(&canonical).is_ok() {
+                return Some(canonical);
+            }
+        }
+    }
+
+    Some(license_ref(name))
+}
+
+/// Whether a license value is an expression combining licenses with `AND`,
+/// `OR` or `/`, or a license with a `WITH` exception
+pub fn is_compound(name: &str) -> bool {
+    name.contains('/')
+        || name
+            .split_whitespace()
+            .any(|word| ["and", "or", "with"].iter().any(|op| op.eq_ignore_ascii_case(word)))
+}
+
+/// A license in an expression, with its exception
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LicenseTerm {
+    pub license: String,
+    pub exception: Option<String>,
+}
+
+impl LicenseTerm {
+    fn from_req(req: &LicenseReq) -> Self {
+        let license = match &req.license {
+            // The parser folds `-only` and `-or-later` of GNU licenses into
+            // the base ID
+            LicenseItem::Spdx { id, or_later }
+                if id.is_gnu() && !id.name.ends_with("-only") && !id.name.ends_with("-or-later") =>
+            {
+                format!("{}-{}", id.name, if *or_later { "or-later" } else { "only" })
+            }
+            LicenseItem::Spdx { id, or_later } => {
+                format!("{}{}", id.name, if *or_later { "+" } else { "" })
+            }
+            LicenseItem::Other { lic_ref, .. } => format!("LicenseRef-{}", lic_ref),
+        };
+        LicenseTerm {
+            license,
+            exception: req.exception.map(|e| e.name.to_string()),
+        }
+    }
+}
+
+/// The licenses whose terms apply under an expression when each `OR` is
+/// resolved to the alternative with the lowest total `cost`, the first on a
+/// tie; both sides of an `AND` apply. Lowercase operators and `/` are
+/// accepted. `None` if the expression does not parse.
+pub fn resolve<F>(expression: &str, cost: F) -> Option<Vec<LicenseTerm>>
+where
+    F: Fn(&LicenseTerm) -> i32,
+{
+    // Canonical first, so deprecated IDs such as `GPL-2.0` become `GPL-2.0-only`
+    let canonical = Expression::canonicalize(expression)
+        .ok()?
+        .unwrap_or_else(|| expression.to_string());
+    let parsed = Expression::parse(&canonical).ok()?;
+
+    // The expression is stored in postfix order
+    let mut stack: Vec<(Vec<LicenseTerm>, i32)> = Vec::new();
+    for node in parsed.iter() {
+        match node {
+            ExprNode::Req(req) => {
+                let term = LicenseTerm::from_req(&req.req);
+                let term_cost = cost(&term);
+                stack.push((vec![term], term_cost));
+            }
+            ExprNode::Op(op) => {
+                let right = stack.pop()?;
+                let left = stack.pop()?;
+                stack.push(match op {
+                    Operator::Or if right.1 < left.1 => right,
+                    Operator::Or => left,
+                    Operator::And => {
+                        let (mut terms, mut total) = left;
+                        for term in right.0 {
+                            if !terms.contains(&term) {
+                                total += cost(&term);
+                                terms.push(term);
+                            }
+                        }
+                        (terms, total)
+                    }
+                });
+            }
+        }
+    }
+
+    let (terms, _) = stack.pop()?;
+    stack.is_empty().then_some(terms)
+}
+
+/// Valid SPDX expression for a finding's license, preferring the SPDX ID,
+/// with its exception if it has one
+pub fn of_result(result: &ScanResult) -> Option<String> {
+    let expression = result
+        .license_spdx_id
+        .as_deref()
+        .or(result.license_name.as_deref())
+        .and_then(normalize)?;
+    Some(with_exception(expression, result.license_exception.as_deref()))
+}
+
+/// `<license> WITH <exception>`. Only a single license can carry an
+/// exception, so compound expressions are returned as they are.
+pub fn with_exception(license: String, exception: Option<&str>) -> String {
+    match exception {
+        Some(exception) if !license.contains(' ') => format!("{} WITH {}", license, exception),
+        _ => license,
+    }
+}
+
+/// `LicenseRef-` identifier for a license name outside the SPDX license list
+pub fn license_ref(name: &str) -> String {
+    if name.starts_with("LicenseRef-") {
+        return name.to_string();
+    }
+    let sanitized: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' { c } else { '-' })
+        .collect();
+    format!("LicenseRef-{}", sanitized)
+}
+
+/// Combine normalized expressions that all apply to the same content.
+/// Duplicates are dropped, compound members are parenthesized and an empty
+/// input yields `NOASSERTION`.
+pub fn conjunction<I>(expressions: I) -> String
+where
+    I: IntoIterator<Item = String>,
+{
+    let expressions: BTreeSet<String> = expressions.into_iter().collect();
+    match expressions.len() {
+        0 => "NOASSERTION".to_string(),
+        1 => expressions.into_iter().next().unwrap_or_default(),
+        _ => expressions
+            .into_iter()
+            .map(|e| if e.contains(' ') { format!("({})", e) } else { e })
+            .collect::<Vec<_>>()
+            .join(" AND "),
+    }
+}
+
+/// Individual license identifiers referenced by an expression, as used for
+/// `licenseInfoInFile` which does not accept compound expressions
+pub fn license_ids(expression: &str) -> Vec<String> {
+    let Ok(parsed) = Expression::parse(expression) else {
+        return vec![expression.to_string()];
+    };
+
+    let mut ids = Vec::new();
+    for node in parsed.iter() {
+        if let ExprNode::Req(req) = node {
+            let id = match &req.req.license {
+                LicenseItem::Spdx { id, or_later } => {
+                    format!("{}{}", id.name, if *or_later { "+" } else { "" })
+                }
+                LicenseItem::Other { lic_ref, .. } => format!("LicenseRef-{}", lic_ref),
+            };
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("MIT").as_deref(), Some("MIT"));
+        assert_eq!(normalize("Apache License 2.0").as_deref(), Some("Apache-2.0"));
+        assert_eq!(
+            normalize("MIT OR Apache-2.0").as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+        assert_eq!(
+            normalize("Acme Proprietary v1").as_deref(),
+            Some("LicenseRef-Acme-Proprietary-v1")
+        );
+        assert_eq!(normalize("No_license_found"), None);
+        assert_eq!(normalize("  "), None);
+    }
+
+    #[test]
+    fn test_conjunction() {
+        assert_eq!(conjunction(Vec::new()), "NOASSERTION");
+        assert_eq!(conjunction(vec!["MIT".to_string(), "MIT".to_string()]), "MIT");
+        assert_eq!(
+            conjunction(vec![
+                "MIT OR Apache-2.0".to_string(),
+                "BSD-3-Clause".to_string()
+            ]),
+            "BSD-3-Clause AND (MIT OR Apache-2.0)"
+        );
+        assert!(Expression::parse(&conjunction(vec![
+            "GPL-2.0-only".to_string(),
+            "LicenseRef-Acme".to_string(),
+            "MIT OR Apache-2.0".to_string(),
+        ]))
+        .is_ok());
+    }
+
+    #[test]
+    fn test_with_exception() {
+        let expression = with_exception(
+            "GPL-2.0-only".to_string(),
+            Some("Classpath-exception-2.0"),
+        );
+        assert_eq!(expression, "GPL-2.0-only WITH Classpath-exception-2.0");
+        assert!(Expression::parse(&expression).is_ok());
+        assert_eq!(
+            with_exception("MIT OR GPL-2.0-only".to_string(), Some("Classpath-exception-2.0")),
+            "MIT OR GPL-2.0-only"
+        );
+        assert_eq!(
+            conjunction(vec![expression, "MIT".to_string()]),
+            "(GPL-2.0-only WITH Classpath-exception-2.0) AND MIT"
+        );
+    }
+
+    #[test]
+    fn test_normalize_lax_expressions() {
+        assert_eq!(
+            normalize("GPL-2.0-only or MIT").as_deref(),
+            Some("GPL-2.0-only OR MIT")
+        );
+        assert_eq!(normalize("MIT/Apache-2.0").as_deref(), Some("MIT OR Apache-2.0"));
+        assert_eq!(
+            normalize("Apache-2.0 with LLVM-exception").as_deref(),
+            Some("Apache-2.0 WITH LLVM-exception")
+        );
+        assert!(!is_compound("GPL-2.0-or-later"));
+        assert!(!is_compound("Apache License 2.0"));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let cost = |term: &LicenseTerm| match (term.license.as_str(), &term.exception) {
+            (_, Some(_)) => 1,
+            ("GPL-2.0-only" | "GPL-3.0-only", None) => 10,
+            _ => 0,
+        };
+        let licenses = |expression: &str| -> Vec<String> {
+            resolve(expression, cost)
+                .unwrap()
+                .into_iter()
+                .map(|t| match t.exception {
+                    Some(exception) => format!("{} WITH {}", t.license, exception),
+                    None => t.license,
+                })
+                .collect()
+        };
+
+        assert_eq!(licenses("GPL-2.0-only OR MIT"), vec!["MIT"]);
+        assert_eq!(licenses("MIT or GPL-2.0-only"), vec!["MIT"]);
+        assert_eq!(licenses("MIT OR Apache-2.0"), vec!["MIT"]);
+        assert_eq!(licenses("GPL-2.0 OR MIT"), vec!["MIT"]);
+        assert_eq!(licenses("GPL-2.0 AND MIT"), vec!["GPL-2.0-only", "MIT"]);
+        assert_eq!(
+            licenses("GPL-3.0-only AND (GPL-2.0-only OR BSD-3-Clause)"),
+            vec!["GPL-3.0-only", "BSD-3-Clause"]
+        );
+        assert_eq!(
+            licenses("GPL-2.0-only WITH Classpath-exception-2.0 OR GPL-3.0-only"),
+            vec!["GPL-2.0-only WITH Classpath-exception-2.0"]
+        );
+        assert_eq!(
+            licenses("Apache-2.0 WITH LLVM-exception"),
+            vec!["Apache-2.0 WITH LLVM-exception"]
+        );
+        assert_eq!(resolve("Acme Proprietary v1", cost), None);
+    }
+
+    #[test]
+    fn test_license_ids() {
+        assert_eq!(
+            license_ids("(MIT OR Apache-2.0) AND LicenseRef-Acme"),
+            vec!["MIT", "Apache-2.0", "LicenseRef-Acme"]
+        );
+    }
+}
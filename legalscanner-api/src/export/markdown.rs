@@ -0,0 +1,498 @@
+use crate::api::models::{RiskAssessment, RiskFactor};
+use crate::db::models::scan::Scan;
+use crate::db::models::scan_result::ScanResult;
+use crate::db::models::{ScanHistoryFinding, ScanRepositoryMetadata};
+use crate::export::component_license;
+use crate::export::license_expression::with_exception;
+use crate::export::sarif::relative_uri;
+use crate::remediation::Remediation;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+const MAX_RISK_FACTORS: usize = 5;
+const MAX_DIRECTORIES: usize = 20;
+const MAX_HISTORY_FINDINGS: usize = 50;
+
+/// Render a Markdown summary of a scan for pasting into PRs, wikis and
+/// release checklists
+pub fn build_markdown_summary(
+    scan: &Scan,
+    results: &[ScanResult],
+    risk: Option<&RiskAssessment>,
+    history: &[ScanHistoryFinding],
+    repository: Option<&ScanRepositoryMetadata>,
+    custom_fields: &[(String, String)],
+) -> String {
+    let repo_name = scan
+        .git_url
+        .trim_end_matches(".git")
+        .rsplit('/')
+        .next()
+        .unwrap_or("repository");
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# License scan: {}\n", cell(repo_name));
+    let _ = writeln!(out, "| | |");
+    let _ = writeln!(out, "|---|---|");
+    let _ = writeln!(out, "| Repository | {} |", cell(&scan.git_url));
+    if let Some(repository) = repository {
+        write_repository_rows(&mut out, repository);
+    }
+    for (label, value) in custom_fields {
+        let _ = writeln!(out, "| {} | {} |", cell(label), cell(value));
+    }
+    let _ = writeln!(out, "| Status | {} |", scan.status);
+    let _ = writeln!(
+        out,
+        "| Scanned | {} |",
+        scan.completed_at.as_deref().unwrap_or(&scan.created_at)
+    );
+    if let Some(risk) = risk {
+        let _ = writeln!(
+            out,
+            "| Risk | **{}** ({}/100){} |",
+            risk.level,
+            risk.score,
+            if risk.preliminary { ", preliminary (quick scan)" } else { "" }
+        );
+    }
+    out.push('\n');
+    write_warnings(&mut out, &scan.warning_list());
+
+    write_license_table(&mut out, results, &scan.id);
+    if let Some(risk) = risk {
+        write_risk_factors(&mut out, &risk.factors);
+        write_remediation(&mut out, &risk.remediation);
+    }
+    write_directory_table(&mut out, results, &scan.id);
+    if scan.due_diligence {
+        write_history_table(&mut out, history);
+    }
+
+    out
+}
+
+/// Captured repository metadata as rows of the header table
+fn write_repository_rows(out: &mut String, repository: &ScanRepositoryMetadata) {
+    if let Some(description) = &repository.description {
+        let _ = writeln!(out, "| Description | {} |", cell(description));
+    }
+    if let Some(branch) = &repository.default_branch {
+        let _ = writeln!(out, "| Default branch | {} |", cell(branch));
+    }
+    if let Some(sha) = &repository.commit_sha {
+        let commit = match &repository.head_tag {
+            Some(tag) => format!("{} ({})", sha, tag),
+            None => sha.clone(),
+        };
+        let _ = writeln!(out, "| Commit | {} |", cell(&commit));
+    }
+    if let Some(date) = &repository.commit_date {
+        let _ = writeln!(out, "| Commit date | {} |", date);
+    }
+    if let Some(author) = &repository.commit_author {
+        let _ = writeln!(out, "| Commit author | {} |", cell(author));
+    }
+    if let Some(tag) = &repository.latest_tag {
+        let _ = writeln!(out, "| Latest tag | {} |", cell(tag));
+    }
+    let topics = repository.topic_list();
+    if !topics.is_empty() {
+        let _ = writeln!(out, "| Topics | {} |", cell(&topics.join(", ")));
+    }
+    if let Some(count) = repository.contributor_count {
+        let _ = writeln!(out, "| Contributors | {} |", count);
+    }
+}
+
+/// Soft limit warnings as a banner under the header, since they qualify
+/// everything that follows
+fn write_warnings(out: &mut String, warnings: &[String]) {
+    for warning in warnings {
+        let _ = writeln!(out, "> **Warning:** {}", warning);
+    }
+    if !warnings.is_empty() {
+        out.push('\n');
+    }
+}
+
+fn write_license_table(out: &mut String, results: &[ScanResult], scan_id: &str) {
+    // license -> (category, files)
+    let mut licenses: HashMap<String, (&str, BTreeSet<&str>)> = HashMap::new();
+    for result in results.iter().filter(|r| r.result_type == "license") {
+        let Some(license) = result
+            .license_spdx_id
+            .as_deref()
+            .or(result.license_name.as_deref())
+        else {
+            continue;
+        };
+        let license = with_exception(license.to_string(), result.license_exception.as_deref());
+        let entry = licenses.entry(license).or_insert_with(|| {
+            (
+                result.license_category.as_deref().unwrap_or("code"),
+                BTreeSet::new(),
+            )
+        });
+        entry.1.insert(result.file_path.as_str());
+    }
+
+    let _ = writeln!(out, "## Licenses\n");
+    if licenses.is_empty() {
+        let _ = writeln!(out, "No licenses detected.\n");
+        return;
+    }
+
+    let concluded = component_license::conclude(scan_id, results);
+    let _ = writeln!(
+        out,
+        "**Concluded license:** {} ({})\n",
+        concluded.concluded, concluded.rationale
+    );
+
+    let total_files: usize = results
+        .iter()
+        .filter(|r| r.result_type == "license")
+        .map(|r| r.file_path.as_str())
+        .collect::<BTreeSet<_>>()
+        .len();
+
+    let mut rows: Vec<(String, &str, usize)> = licenses
+        .into_iter()
+        .map(|(license, (category, files))| (license, category, files.len()))
+        .collect();
+    rows.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+
+    let _ = writeln!(out, "| License | Category | Files | Share |");
+    let _ = writeln!(out, "|---|---|--:|--:|");
+    for (license, category, files) in rows {
+        let share = files as f64 * 100.0 / total_files.max(1) as f64;
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {:.1}% |",
+            cell(&license),
+            category,
+            files,
+            share
+        );
+    }
+    out.push('\n');
+}
+
+fn write_risk_factors(out: &mut String, factors: &[RiskFactor]) {
+    let _ = writeln!(out, "## Top risk factors\n");
+    if factors.is_empty() {
+        let _ = writeln!(out, "No risk factors identified.\n");
+        return;
+    }
+
+    let mut factors: Vec<&RiskFactor> = factors.iter().collect();
+    factors.sort_by(|a, b| {
+        severity_rank(&b.severity)
+            .cmp(&severity_rank(&a.severity))
+            .then_with(|| b.affected_count.cmp(&a.affected_count))
+    });
+
+    let _ = writeln!(out, "| Severity | Factor | Affected | Details |");
+    let _ = writeln!(out, "|---|---|--:|---|");
+    for factor in factors.iter().take(MAX_RISK_FACTORS) {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} |",
+            factor.severity,
+            cell(&factor.description),
+            factor.affected_count,
+            cell(
+                &factor
+                    .details
+                    .iter()
+                    .take(3)
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        );
+    }
+    if factors.len() > MAX_RISK_FACTORS {
+        let _ = writeln!(
+            out,
+            "\n_{} more risk factors omitted._",
+            factors.len() - MAX_RISK_FACTORS
+        );
+    }
+    out.push('\n');
+}
+
+fn write_remediation(out: &mut String, remediation: &[Remediation]) {
+    if remediation.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "## Next steps\n");
+    for entry in remediation {
+        let _ = writeln!(out, "**{}** (`{}`)\n", entry.summary, entry.category);
+        for action in &entry.actions {
+            match &action.endpoint {
+                Some(endpoint) => {
+                    let _ = writeln!(out, "- {} (`{}`)", action.description, endpoint);
+                }
+                None => {
+                    let _ = writeln!(out, "- {}", action.description);
+                }
+            }
+        }
+        out.push('\n');
+    }
+}
+
+#[derive(Default)]
+struct DirectorySummary<'a> {
+    files: BTreeSet<&'a str>,
+    licenses: BTreeSet<&'a str>,
+    copyrights: usize,
+    ecc_findings: usize,
+    malware_findings: usize,
+}
+
+fn write_directory_table(out: &mut String, results: &[ScanResult], scan_id: &str) {
+    let mut directories: HashMap<String, DirectorySummary> = HashMap::new();
+    for result in results {
+        let summary = directories
+            .entry(top_level_directory(&relative_uri(
+                &result.file_path,
+                scan_id,
+            )))
+            .or_default();
+        summary.files.insert(result.file_path.as_str());
+        match result.result_type.as_str() {
+            "license" => {
+                if let Some(license) = result
+                    .license_spdx_id
+                    .as_deref()
+                    .or(result.license_name.as_deref())
+                {
+                    summary.licenses.insert(license);
+                }
+            }
+            "copyright" => summary.copyrights += 1,
+            "ecc" => summary.ecc_findings += 1,
+            "malware" => summary.malware_findings += 1,
+            _ => {}
+        }
+    }
+
+    let _ = writeln!(out, "## By directory\n");
+    if directories.is_empty() {
+        let _ = writeln!(out, "No findings.\n");
+        return;
+    }
+
+    let mut rows: Vec<(String, DirectorySummary)> = directories.into_iter().collect();
+    rows.sort_by(|a, b| {
+        b.1.files
+            .len()
+            .cmp(&a.1.files.len())
+            .then_with(|| a.0.cmp(&b.0))
+    });
+
+    let _ = writeln!(
+        out,
+        "| Directory | Files | Licenses | Copyrights | ECC | Malware |"
+    );
+    let _ = writeln!(out, "|---|--:|---|--:|--:|--:|");
+    for (directory, summary) in rows.iter().take(MAX_DIRECTORIES) {
+        let _ = writeln!(
+            out,
+            "| `{}` | {} | {} | {} | {} | {} |",
+            directory.replace('`', "'"),
+            summary.files.len(),
+            cell(
+                &summary
+                    .licenses
+                    .iter()
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            summary.copyrights,
+            summary.ecc_findings,
+            summary.malware_findings
+        );
+    }
+    if rows.len() > MAX_DIRECTORIES {
+        let _ = writeln!(
+            out,
+            "\n_{} more directories omitted._",
+            rows.len() - MAX_DIRECTORIES
+        );
+    }
+    out.push('\n');
+}
+
+/// Due-diligence findings: licensed code that left the tree but is still in
+/// the git history
+fn write_history_table(out: &mut String, findings: &[ScanHistoryFinding]) {
+    let _ = writeln!(out, "## History\n");
+    if findings.is_empty() {
+        let _ = writeln!(
+            out,
+            "No licensed code was removed in the scanned history.\n"
+        );
+        return;
+    }
+
+    let _ = writeln!(out, "| Finding | Path | License | Commit | Date | Detail |");
+    let _ = writeln!(out, "|---|---|---|---|---|---|");
+    for finding in findings.iter().take(MAX_HISTORY_FINDINGS) {
+        let _ = writeln!(
+            out,
+            "| {} | `{}` | {} | `{}` | {} | {} |",
+            finding.kind.replace('_', " "),
+            finding.file_path.replace('`', "'"),
+            cell(finding.license.as_deref().unwrap_or("")),
+            &finding.commit_sha[..finding.commit_sha.len().min(7)],
+            finding
+                .commit_date
+                .get(..10)
+                .unwrap_or(&finding.commit_date),
+            cell(finding.detail.as_deref().unwrap_or(""))
+        );
+    }
+    if findings.len() > MAX_HISTORY_FINDINGS {
+        let _ = writeln!(
+            out,
+            "\n_{} more findings omitted, see `/api/v1/scans/:id/history`._",
+            findings.len() - MAX_HISTORY_FINDINGS
+        );
+    }
+    out.push('\n');
+}
+
+/// First path component, or `/` for files at the repository root
+fn top_level_directory(path: &str) -> String {
+    match path.split_once('/') {
+        Some((directory, _)) => format!("{}/", directory),
+        None => "/".to_string(),
+    }
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "critical" => 4,
+        "high" => 3,
+        "medium" => 2,
+        "low" => 1,
+        _ => 0,
+    }
+}
+
+/// Keep table cells on one line and escape column separators
+fn cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(result_type: &str, file: &str, license: Option<&str>) -> ScanResult {
+        ScanResult {
+            license_name: license.map(str::to_string),
+            license_spdx_id: license.map(str::to_string),
+            license_category: license.map(|_| "code".to_string()),
+            ..ScanResult::for_test(result_type, file)
+        }
+    }
+
+    #[test]
+    fn test_license_table() {
+        let results = vec![
+            result("license", "src/a.rs", Some("MIT")),
+            result("license", "src/b.rs", Some("MIT")),
+            result("license", "vendor/c.c", Some("GPL-2.0-only")),
+            result("copyright", "src/a.rs", None),
+        ];
+        let mut out = String::new();
+        write_license_table(&mut out, &results, "scan-1");
+
+        assert!(out.contains("**Concluded license:** MIT (No license file or manifest declares"));
+        assert!(out.contains("| MIT | code | 2 | 66.7% |"));
+        assert!(out.contains("| GPL-2.0-only | code | 1 | 33.3% |"));
+        assert!(out.find("MIT").unwrap() < out.find("GPL-2.0-only").unwrap());
+    }
+
+    #[test]
+    fn test_directory_table_strips_workspace() {
+        let results = vec![
+            result("license", "/tmp/legalscanner/scan-1/src/a.rs", Some("MIT")),
+            result("ecc", "/tmp/legalscanner/scan-1/src/aes.rs", None),
+            result("license", "README.md", Some("CC-BY-4.0")),
+        ];
+        let mut out = String::new();
+        write_directory_table(&mut out, &results, "scan-1");
+
+        assert!(out.contains("| `src/` | 2 | MIT | 0 | 1 | 0 |"));
+        assert!(out.contains("| `/` | 1 | CC-BY-4.0 | 0 | 0 | 0 |"));
+    }
+
+    #[test]
+    fn test_history_table() {
+        let finding = ScanHistoryFinding {
+            id: 1,
+            scan_id: "scan-1".to_string(),
+            kind: "license_change".to_string(),
+            file_path: "LICENSE".to_string(),
+            commit_sha: "0123456789abcdef".to_string(),
+            commit_date: "2021-03-04T05:06:07+00:00".to_string(),
+            license: Some("GPL-2.0-only".to_string()),
+            detail: Some("GPL-2.0-only replaced by MIT".to_string()),
+        };
+        let mut out = String::new();
+        write_history_table(&mut out, &[finding]);
+
+        assert!(out.contains(
+            "| license change | `LICENSE` | GPL-2.0-only | `0123456` | 2021-03-04 | GPL-2.0-only replaced by MIT |"
+        ));
+    }
+
+    #[test]
+    fn test_warnings_banner() {
+        let mut out = String::new();
+        write_warnings(&mut out, &[]);
+        assert!(out.is_empty());
+
+        write_warnings(&mut out, &["Repository contains 60000 files".to_string()]);
+        assert_eq!(out, "> **Warning:** Repository contains 60000 files\n\n");
+    }
+
+    #[test]
+    fn test_risk_factors_sorted_by_severity() {
+        let factor = |severity: &str, description: &str| RiskFactor {
+            category: "x".to_string(),
+            severity: severity.to_string(),
+            description: description.to_string(),
+            affected_count: 1,
+            details: vec!["a|b".to_string()],
+        };
+        let mut out = String::new();
+        write_risk_factors(
+            &mut out,
+            &[factor("low", "minor"), factor("critical", "major")],
+        );
+
+        assert!(out.find("major").unwrap() < out.find("minor").unwrap());
+        assert!(out.contains("a\\|b"));
+    }
+
+    #[test]
+    fn test_remediation_lists_actions() {
+        let mut out = String::new();
+        write_remediation(&mut out, &[]);
+        assert!(out.is_empty());
+
+        let remediation = crate::remediation::for_category("unknown_license").unwrap();
+        write_remediation(&mut out, &[remediation]);
+        assert!(out.starts_with("## Next steps"));
+        assert!(out.contains("- Add a LICENSE file"));
+        assert!(out.contains("(`POST /api/v1/curations`)"));
+    }
+}
@@ -1,13 +1,30 @@
+pub mod analytics;
+pub mod anonymized;
+pub mod component_license;
+pub mod export_control;
+pub mod license_expression;
+pub mod markdown;
+pub mod notice;
+pub mod pipeline;
+pub mod processors;
+pub mod sarif;
 pub mod spdx;
+pub mod spreadsheet;
+pub mod spdx3;
+pub mod viewer;
 
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// SBOM export format
-#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SbomFormat {
     Json,
     Yaml,
+    #[serde(rename = "tag-value", alias = "tagvalue", alias = "spdx")]
+    TagValue,
+    Rdf,
 }
 
 impl SbomFormat {
@@ -15,6 +32,8 @@ impl SbomFormat {
         match self {
             SbomFormat::Json => "application/json",
             SbomFormat::Yaml => "application/x-yaml",
+            SbomFormat::TagValue => "text/spdx",
+            SbomFormat::Rdf => "application/rdf+xml",
         }
     }
 
@@ -22,6 +41,8 @@ impl SbomFormat {
         match self {
             SbomFormat::Json => "json",
             SbomFormat::Yaml => "yaml",
+            SbomFormat::TagValue => "spdx",
+            SbomFormat::Rdf => "rdf.xml",
         }
     }
 }
@@ -31,3 +52,43 @@ impl Default for SbomFormat {
         SbomFormat::Json
     }
 }
+
+/// SPDX specification version for SBOM exports
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+pub enum SpdxVersion {
+    #[default]
+    #[serde(rename = "2.3")]
+    V2_3,
+    #[serde(rename = "3.0")]
+    V3_0,
+}
+
+/// Export format for raw scan findings (analytics and spreadsheets)
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultsExportFormat {
+    #[default]
+    Parquet,
+    Csv,
+    Xlsx,
+}
+
+impl ResultsExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ResultsExportFormat::Parquet => "application/vnd.apache.parquet",
+            ResultsExportFormat::Csv => "text/csv; charset=utf-8",
+            ResultsExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            ResultsExportFormat::Parquet => "parquet",
+            ResultsExportFormat::Csv => "csv",
+            ResultsExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
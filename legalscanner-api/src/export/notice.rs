@@ -0,0 +1,314 @@
+use crate::db::models::scan::Scan;
+use crate::db::models::scan_result::ScanResult;
+use crate::export::license_expression::with_exception;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+const RULE: &str =
+    "================================================================================";
+
+/// Heading for copyright holders found in files without a detected license
+const UNLICENSED_GROUP: &str = "Unknown license";
+
+/// Attribution entry for one license in a NOTICE file
+#[derive(Debug, PartialEq)]
+pub struct LicenseAttribution {
+    pub license: String,
+    /// Holders deduplicated case-insensitively, sorted for stable output
+    pub holders: Vec<String>,
+    /// `name version` of third-party packages declaring this license
+    pub packages: Vec<String>,
+    pub file_count: usize,
+}
+
+/// Assemble a plain-text third-party attribution (NOTICE) document
+pub fn build_notice(scan: &Scan, results: &[ScanResult]) -> String {
+    let repo_name = scan
+        .git_url
+        .trim_end_matches(".git")
+        .rsplit('/')
+        .next()
+        .unwrap_or("repository");
+
+    let mut out = String::new();
+    out.push_str("THIRD-PARTY SOFTWARE NOTICES AND INFORMATION\n\n");
+    out.push_str(&format!(
+        "{} incorporates material from the projects listed below.\n",
+        repo_name
+    ));
+    out.push_str(&format!(
+        "Generated by LegalScanner from scan {} of {} ({}).\n",
+        scan.id,
+        scan.git_url,
+        scan.completed_at.as_deref().unwrap_or(&scan.created_at)
+    ));
+
+    write_attributions(&mut out, results);
+    out
+}
+
+/// Assemble a single NOTICE document for a product built from several
+/// repositories, from the member scans of a composite scan
+pub fn build_product_notice(
+    product: &str,
+    composite_scan_id: &str,
+    scans: &[(Scan, Vec<ScanResult>)],
+) -> String {
+    let mut out = String::new();
+    out.push_str("THIRD-PARTY SOFTWARE NOTICES AND INFORMATION\n\n");
+    out.push_str(&format!(
+        "{} incorporates material from the projects listed below.\n",
+        product
+    ));
+    out.push_str(&format!(
+        "Generated by LegalScanner from composite scan {} of:\n",
+        composite_scan_id
+    ));
+    for (scan, _) in scans {
+        out.push_str(&format!(
+            "  {} (scan {}, {})\n",
+            scan.git_url,
+            scan.id,
+            scan.completed_at.as_deref().unwrap_or(&scan.created_at)
+        ));
+    }
+
+    let results: Vec<ScanResult> = scans
+        .iter()
+        .flat_map(|(_, results)| results.iter().cloned())
+        .collect();
+    write_attributions(&mut out, &results);
+    out
+}
+
+fn write_attributions(out: &mut String, results: &[ScanResult]) {
+    for attribution in group_attributions(results) {
+        out.push('\n');
+        out.push_str(RULE);
+        out.push('\n');
+        out.push_str(&attribution.license);
+        out.push('\n');
+        out.push_str(RULE);
+        out.push('\n');
+
+        let (license, exception) = match attribution.license.split_once(" WITH ") {
+            Some((license, exception)) => (license, Some(exception)),
+            None => (attribution.license.as_str(), None),
+        };
+        if is_spdx_id(license) {
+            out.push_str(&format!(
+                "License text: https://spdx.org/licenses/{}.html\n",
+                license
+            ));
+        }
+        if let Some(exception) = exception {
+            out.push_str(&format!(
+                "Exception text: https://spdx.org/licenses/{}.html\n",
+                exception
+            ));
+        }
+
+        if !attribution.holders.is_empty() {
+            out.push_str("\nCopyright holders:\n");
+            for holder in &attribution.holders {
+                out.push_str(&format!("  {}\n", holder));
+            }
+        }
+
+        if !attribution.packages.is_empty() {
+            out.push_str("\nPackages:\n");
+            for package in &attribution.packages {
+                out.push_str(&format!("  {}\n", package));
+            }
+        }
+
+        if attribution.file_count > 0 {
+            out.push_str(&format!("\nFiles: {}\n", attribution.file_count));
+        }
+    }
+}
+
+/// Group copyright holders and packages by the license that covers them.
+/// Copyright statements are attributed to every license found in the same file.
+pub fn group_attributions(results: &[ScanResult]) -> Vec<LicenseAttribution> {
+    let mut licenses_by_file: HashMap<&str, BTreeSet<String>> = HashMap::new();
+    for result in results.iter().filter(|r| r.result_type == "license") {
+        if let Some(license) = license_key(result) {
+            licenses_by_file
+                .entry(result.file_path.as_str())
+                .or_default()
+                .insert(license);
+        }
+    }
+
+    #[derive(Default)]
+    struct Group {
+        // lowercase holder -> first spelling seen
+        holders: BTreeMap<String, String>,
+        packages: BTreeSet<String>,
+        files: BTreeSet<String>,
+    }
+
+    let mut groups: BTreeMap<String, Group> = BTreeMap::new();
+
+    for (file, licenses) in &licenses_by_file {
+        for license in licenses {
+            groups
+                .entry(license.clone())
+                .or_default()
+                .files
+                .insert(file.to_string());
+        }
+    }
+
+    let unlicensed = BTreeSet::from([UNLICENSED_GROUP.to_string()]);
+    for result in results.iter().filter(|r| r.result_type == "copyright") {
+        let licenses = licenses_by_file
+            .get(result.file_path.as_str())
+            .unwrap_or(&unlicensed);
+
+        for holder in copyright_holders(result) {
+            for license in licenses {
+                groups
+                    .entry(license.clone())
+                    .or_default()
+                    .holders
+                    .entry(holder.to_lowercase())
+                    .or_insert_with(|| holder.clone());
+            }
+        }
+    }
+
+    for result in results.iter().filter(|r| r.result_type == "package") {
+        let Some(name) = result.package_name.as_deref() else {
+            continue;
+        };
+        let license = license_key(result).unwrap_or_else(|| UNLICENSED_GROUP.to_string());
+        let package = match result.package_version.as_deref() {
+            Some(version) => format!("{} {}", name, version),
+            None => name.to_string(),
+        };
+        groups.entry(license).or_default().packages.insert(package);
+    }
+
+    let mut attributions: Vec<LicenseAttribution> = groups
+        .into_iter()
+        .map(|(license, group)| LicenseAttribution {
+            license,
+            holders: group.holders.into_values().collect(),
+            packages: group.packages.into_iter().collect(),
+            file_count: group.files.len(),
+        })
+        .collect();
+
+    // Keep the catch-all group at the end
+    attributions.sort_by_key(|a| a.license == UNLICENSED_GROUP);
+    attributions
+}
+
+/// Prefer the SPDX identifier, skipping scanner placeholders for "nothing
+/// found", with the license exception if there is one
+fn license_key(result: &ScanResult) -> Option<String> {
+    result
+        .license_spdx_id
+        .as_deref()
+        .or(result.license_name.as_deref())
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && *l != "No_license_found" && *l != "NOASSERTION")
+        .map(|l| with_exception(l.to_string(), result.license_exception.as_deref()))
+}
+
+/// Holders from the parsed JSON array, falling back to the raw statement
+fn copyright_holders(result: &ScanResult) -> Vec<String> {
+    let holders: Vec<String> = result
+        .copyright_holders
+        .as_deref()
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default();
+
+    let holders: Vec<String> = holders
+        .into_iter()
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .collect();
+
+    if !holders.is_empty() {
+        return holders;
+    }
+
+    result
+        .copyright_statement
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| vec![s.to_string()])
+        .unwrap_or_default()
+}
+
+fn is_spdx_id(license: &str) -> bool {
+    spdx::license_id(license).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn license(file: &str, spdx_id: &str) -> ScanResult {
+        ScanResult {
+            license_name: Some(spdx_id.to_string()),
+            license_spdx_id: Some(spdx_id.to_string()),
+            ..ScanResult::for_test("license", file)
+        }
+    }
+
+    fn copyright(file: &str, holders: &[&str]) -> ScanResult {
+        ScanResult {
+            copyright_statement: Some(format!("Copyright (c) {}", holders.join(", "))),
+            copyright_holders: Some(serde_json::to_string(holders).unwrap()),
+            ..ScanResult::for_test("copyright", file)
+        }
+    }
+
+    #[test]
+    fn test_group_attributions_dedupes_holders() {
+        let results = vec![
+            license("a.rs", "MIT"),
+            license("b.rs", "MIT"),
+            license("c.rs", "Apache-2.0"),
+            copyright("a.rs", &["Acme Inc."]),
+            copyright("b.rs", &["ACME Inc.", "Jane Doe"]),
+            copyright("c.rs", &["Apache Software Foundation"]),
+            copyright("d.rs", &["Someone Else"]),
+        ];
+
+        let groups = group_attributions(&results);
+        let licenses: Vec<&str> = groups.iter().map(|g| g.license.as_str()).collect();
+        assert_eq!(licenses, vec!["Apache-2.0", "MIT", UNLICENSED_GROUP]);
+
+        assert_eq!(groups[1].holders, vec!["Acme Inc.", "Jane Doe"]);
+        assert_eq!(groups[1].file_count, 2);
+        assert_eq!(groups[2].holders, vec!["Someone Else"]);
+    }
+
+    #[test]
+    fn test_group_attributions_includes_packages() {
+        let package = ScanResult {
+            license_spdx_id: Some("MIT".to_string()),
+            package_name: Some("serde".to_string()),
+            package_version: Some("1.0.0".to_string()),
+            ..ScanResult::for_test("package", "Cargo.lock")
+        };
+
+        let groups = group_attributions(&[package, license("x.rs", "No_license_found")]);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].license, "MIT");
+        assert_eq!(groups[0].packages, vec!["serde 1.0.0"]);
+        assert_eq!(groups[0].file_count, 0);
+    }
+
+    #[test]
+    fn test_is_spdx_id() {
+        assert!(is_spdx_id("Apache-2.0"));
+        assert!(!is_spdx_id("LicenseRef-scancode-public-domain"));
+        assert!(!is_spdx_id("See-file LICENSE"));
+    }
+}
@@ -0,0 +1,365 @@
+//! SBOM export pipeline: collect → normalize → serialize. Every format is
+//! serialized from the same normalized SPDX 2.3 document, and registered
+//! post-processors can adjust that document (redaction, custom fields) or
+//! the serialized output (signing) without touching the format code.
+
+use crate::config::Config;
+use crate::db::models::scan::Scan;
+use crate::db::models::scan_result::ScanResult;
+use crate::error::AppError;
+use crate::export::processors::{CustomFields, HmacSigner, RedactEmails};
+use crate::export::{spdx, spdx3, SbomFormat, SpdxVersion};
+use std::sync::Arc;
+
+/// Post-processor names accepted in `EXPORT_POST_PROCESSORS`
+pub const POST_PROCESSOR_NAMES: &[&str] = &["redact-emails", "custom-fields", "sign"];
+
+/// A serialized export, ready to be sent
+#[derive(Debug)]
+pub struct ExportOutput {
+    pub content: Vec<u8>,
+    pub content_type: &'static str,
+    pub file_name: String,
+    /// Extra response headers, e.g. a signature
+    pub headers: Vec<(String, String)>,
+}
+
+/// Hook into the export pipeline. Both stages default to doing nothing.
+pub trait PostProcessor: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Adjust the normalized document before it is serialized
+    fn process_document(
+        &self,
+        _scan: &Scan,
+        _doc: &mut spdx::SpdxDocument,
+    ) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Adjust the serialized output
+    fn process_output(&self, _output: &mut ExportOutput) -> Result<(), AppError> {
+        Ok(())
+    }
+}
+
+/// Post-processors run in registration order
+#[derive(Clone, Default)]
+pub struct ExportPipeline {
+    processors: Vec<Arc<dyn PostProcessor>>,
+}
+
+impl ExportPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pipeline with the post-processors listed in `EXPORT_POST_PROCESSORS`
+    pub fn from_config(config: &Config) -> Self {
+        let mut pipeline = Self::new();
+        for name in &config.export_post_processors {
+            pipeline = match name.as_str() {
+                "redact-emails" => pipeline.with_processor(RedactEmails),
+                "custom-fields" => {
+                    pipeline.with_processor(CustomFields::new(config.export_custom_fields.clone()))
+                }
+                "sign" => match &config.export_signing_key {
+                    Some(key) => pipeline.with_processor(HmacSigner::new(key)),
+                    None => pipeline,
+                },
+                other => {
+                    tracing::warn!("Ignoring unknown export post-processor '{}'", other);
+                    pipeline
+                }
+            };
+        }
+        pipeline
+    }
+
+    pub fn with_processor(mut self, processor: impl PostProcessor + 'static) -> Self {
+        self.processors.push(Arc::new(processor));
+        self
+    }
+
+    /// Build the SPDX 2.3 document all formats derive from and apply the
+    /// document post-processors
+    pub fn normalize(
+        &self,
+        scan: &Scan,
+        results: &[ScanResult],
+    ) -> Result<spdx::SpdxDocument, AppError> {
+        let mut doc = spdx::build_spdx_document(scan, results)?;
+        for processor in &self.processors {
+            processor.process_document(scan, &mut doc)?;
+        }
+        Ok(doc)
+    }
+
+    /// Serialize a normalized document and apply the output post-processors
+    pub fn serialize(
+        &self,
+        scan: &Scan,
+        doc: &spdx::SpdxDocument,
+        format: SbomFormat,
+        spdx_version: SpdxVersion,
+    ) -> Result<ExportOutput, AppError> {
+        let repo_name = scan
+            .git_url
+            .trim_end_matches(".git")
+            .rsplit('/')
+            .next()
+            .unwrap_or("repository");
+        self.serialize_named(repo_name, doc, format, spdx_version)
+    }
+
+    fn serialize_named(
+        &self,
+        base_name: &str,
+        doc: &spdx::SpdxDocument,
+        format: SbomFormat,
+        spdx_version: SpdxVersion,
+    ) -> Result<ExportOutput, AppError> {
+        let mut output = serialize_sbom(base_name, doc, format, spdx_version)?;
+        for processor in &self.processors {
+            processor.process_output(&mut output)?;
+        }
+        Ok(output)
+    }
+
+    /// Run the normalize and serialize stages over collected results
+    pub fn export_sbom(
+        &self,
+        scan: &Scan,
+        results: &[ScanResult],
+        format: SbomFormat,
+        spdx_version: SpdxVersion,
+    ) -> Result<ExportOutput, AppError> {
+        let doc = self.normalize(scan, results)?;
+        self.serialize(scan, &doc, format, spdx_version)
+    }
+
+    /// Export one SBOM covering the scans of several repositories, e.g. the
+    /// latest scan of each repository of a project
+    pub fn export_aggregate_sbom(
+        &self,
+        name: &str,
+        namespace: String,
+        scans: &[(Scan, Vec<ScanResult>)],
+        format: SbomFormat,
+        spdx_version: SpdxVersion,
+    ) -> Result<ExportOutput, AppError> {
+        let documents = scans
+            .iter()
+            .map(|(scan, results)| self.normalize(scan, results))
+            .collect::<Result<Vec<_>, _>>()?;
+        let created = scans
+            .iter()
+            .filter_map(|(scan, _)| scan.completed_at.clone())
+            .max()
+            .unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string());
+
+        let doc = spdx::aggregate_documents(name, namespace, created, documents);
+        self.serialize_named(&file_name_stem(name), &doc, format, spdx_version)
+    }
+}
+
+/// Lowercase name with everything but letters, digits, `-` and `_` replaced
+fn file_name_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn serialize_sbom(
+    repo_name: &str,
+    doc: &spdx::SpdxDocument,
+    format: SbomFormat,
+    spdx_version: SpdxVersion,
+) -> Result<ExportOutput, AppError> {
+    // SPDX 3.0 is produced as JSON-LD, converted from the 2.3 document
+    if spdx_version == SpdxVersion::V3_0 {
+        if !matches!(format, SbomFormat::Json) {
+            return Err(AppError::Validation(
+                "SPDX 3.0 documents are only available as JSON-LD (format=json)".to_string(),
+            ));
+        }
+        let document = spdx3::build_spdx3_document(doc);
+        let json = serde_json::to_string_pretty(&document).map_err(|e| {
+            AppError::Internal(format!("Failed to serialize SPDX 3.0 document: {}", e))
+        })?;
+        return Ok(ExportOutput {
+            content: json.into_bytes(),
+            content_type: "application/ld+json",
+            file_name: format!("{}-sbom.spdx3.jsonld", repo_name),
+            headers: Vec::new(),
+        });
+    }
+
+    let content = match format {
+        SbomFormat::Json => serde_json::to_string_pretty(doc)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize SPDX to JSON: {}", e)))?,
+        SbomFormat::Yaml => serde_yaml::to_string(doc)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize SPDX to YAML: {}", e)))?,
+        SbomFormat::TagValue => spdx::to_tag_value(doc),
+        SbomFormat::Rdf => spdx::to_rdf_xml(doc),
+    };
+
+    // Tag-value documents use the bare `.spdx` extension
+    let file_name = match format {
+        SbomFormat::TagValue => format!("{}-sbom.{}", repo_name, format.file_extension()),
+        _ => format!("{}-sbom.spdx.{}", repo_name, format.file_extension()),
+    };
+
+    Ok(ExportOutput {
+        content: content.into_bytes(),
+        content_type: format.content_type(),
+        file_name,
+        headers: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Rename;
+
+    impl PostProcessor for Rename {
+        fn name(&self) -> &str {
+            "rename"
+        }
+
+        fn process_document(
+            &self,
+            _scan: &Scan,
+            doc: &mut spdx::SpdxDocument,
+        ) -> Result<(), AppError> {
+            doc.name = "Renamed".to_string();
+            Ok(())
+        }
+
+        fn process_output(&self, output: &mut ExportOutput) -> Result<(), AppError> {
+            output
+                .headers
+                .push(("X-Processed".to_string(), self.name().to_string()));
+            Ok(())
+        }
+    }
+
+    fn scan() -> Scan {
+        Scan {
+            id: "scan-1".to_string(),
+            git_url: "https://github.com/org/repo.git".to_string(),
+            status: "completed".to_string(),
+            error_message: None,
+            created_at: "2025-01-14 10:00:00".to_string(),
+            started_at: None,
+            completed_at: None,
+            created_by_key_id: None,
+            git_token: None,
+            fossology_status: "completed".to_string(),
+            semgrep_status: "completed".to_string(),
+            fossology_started_at: None,
+            fossology_completed_at: None,
+            semgrep_started_at: None,
+            semgrep_completed_at: None,
+            fossology_error: None,
+            semgrep_error: None,
+            risk_score: None,
+            risk_level: None,
+            risk_factors: None,
+            risk_algorithm_version: None,
+            risk_config_version: None,
+            risk_assessed_at: None,
+            semgrep_rulesets: None,
+            quick: false,
+            approved_at: None,
+            reviewed_at: None,
+            warnings: None,
+            parent_scan_id: None,
+            due_diligence: false,
+            callback_url: None,
+            scan_path: None,
+            branch: None,
+            commit_sha: None,
+            legal_hold_at: None,
+            legal_hold_reason: None,
+            submodules: false,
+            submodule_token_encrypted: None,
+            credential_id: None,
+            git_authorship: false,
+            results_purged_at: None,
+            results_archived_at: None,
+            deleted_at: None,
+            policy: None,
+        }
+    }
+
+    #[test]
+    fn test_processors_apply_to_every_format() {
+        let pipeline = ExportPipeline::new().with_processor(Rename);
+
+        for format in [
+            SbomFormat::Json,
+            SbomFormat::Yaml,
+            SbomFormat::TagValue,
+            SbomFormat::Rdf,
+        ] {
+            let output = pipeline
+                .export_sbom(&scan(), &[], format, SpdxVersion::V2_3)
+                .unwrap();
+            assert!(String::from_utf8_lossy(&output.content).contains("Renamed"));
+            assert_eq!(
+                output.headers,
+                vec![("X-Processed".to_string(), "rename".to_string())]
+            );
+        }
+    }
+
+    #[test]
+    fn test_file_names() {
+        let pipeline = ExportPipeline::new();
+        let json = pipeline
+            .export_sbom(&scan(), &[], SbomFormat::Json, SpdxVersion::V2_3)
+            .unwrap();
+        assert_eq!(json.file_name, "repo-sbom.spdx.json");
+        let tag_value = pipeline
+            .export_sbom(&scan(), &[], SbomFormat::TagValue, SpdxVersion::V2_3)
+            .unwrap();
+        assert_eq!(tag_value.file_name, "repo-sbom.spdx");
+        let spdx3 = pipeline
+            .export_sbom(&scan(), &[], SbomFormat::Json, SpdxVersion::V3_0)
+            .unwrap();
+        assert_eq!(spdx3.file_name, "repo-sbom.spdx3.jsonld");
+        assert!(pipeline
+            .export_sbom(&scan(), &[], SbomFormat::Yaml, SpdxVersion::V3_0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_aggregate_export() {
+        let pipeline = ExportPipeline::new().with_processor(Rename);
+        let scans = vec![(scan(), Vec::new()), (scan(), Vec::new())];
+        let output = pipeline
+            .export_aggregate_sbom(
+                "Checkout Service",
+                "https://legalscanner.io/spdx/project/p-1".to_string(),
+                &scans,
+                SbomFormat::Json,
+                SpdxVersion::V2_3,
+            )
+            .unwrap();
+
+        assert_eq!(output.file_name, "checkout-service-sbom.spdx.json");
+        let doc: spdx::SpdxDocument = serde_json::from_slice(&output.content).unwrap();
+        assert_eq!(doc.packages.len(), 3);
+        assert_eq!(doc.packages[0].name, "Checkout Service");
+        assert_eq!(output.headers.len(), 1);
+    }
+}
@@ -0,0 +1,233 @@
+//! Built-in export post-processors, enabled through `EXPORT_POST_PROCESSORS`
+
+use crate::db::models::scan::Scan;
+use crate::error::AppError;
+use crate::db::models::ScanFileSwhid;
+use crate::export::pipeline::{ExportOutput, PostProcessor};
+use crate::export::sarif::relative_uri;
+use crate::export::spdx::{ExternalRef, SpdxDocument};
+use hmac::{Hmac, Mac};
+use regex::Regex;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Response header carrying the HMAC of a signed export
+pub const SIGNATURE_HEADER: &str = "X-LegalScanner-Signature";
+
+const REDACTED: &str = "[redacted]";
+
+/// Replaces e-mail addresses in copyright texts, for customers that must
+/// not pass on contributors' personal data
+pub struct RedactEmails;
+
+impl RedactEmails {
+    fn redact(text: &mut String) {
+        static RE: OnceLock<Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| Regex::new(r"[\w.+-]+@[\w-]+(\.[\w-]+)+").unwrap());
+        if re.is_match(text) {
+            *text = re.replace_all(text, REDACTED).into_owned();
+        }
+    }
+}
+
+impl PostProcessor for RedactEmails {
+    fn name(&self) -> &str {
+        "redact-emails"
+    }
+
+    fn process_document(&self, _scan: &Scan, doc: &mut SpdxDocument) -> Result<(), AppError> {
+        for package in &mut doc.packages {
+            Self::redact(&mut package.copyright_text);
+        }
+        for file in &mut doc.files {
+            Self::redact(&mut file.copyright_text);
+        }
+        Ok(())
+    }
+}
+
+/// Adds fixed `key: value` lines (contract numbers, business unit) to the
+/// document's creator comment
+pub struct CustomFields {
+    fields: Vec<(String, String)>,
+}
+
+impl CustomFields {
+    pub fn new(fields: Vec<(String, String)>) -> Self {
+        Self { fields }
+    }
+}
+
+impl PostProcessor for CustomFields {
+    fn name(&self) -> &str {
+        "custom-fields"
+    }
+
+    fn process_document(&self, _scan: &Scan, doc: &mut SpdxDocument) -> Result<(), AppError> {
+        if self.fields.is_empty() {
+            return Ok(());
+        }
+
+        let mut lines: Vec<String> = doc.creation_info.comment.iter().cloned().collect();
+        lines.extend(
+            self.fields
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, value)),
+        );
+        doc.creation_info.comment = Some(lines.join("\n"));
+        Ok(())
+    }
+}
+
+/// Adds Software Heritage identifiers: the revision SWHID of the scanned
+/// commit to the repository package, and each file's content SWHID and
+/// upstream origin to the file comment
+pub struct SoftwareHeritageIds {
+    revision: Option<String>,
+    files: HashMap<String, ScanFileSwhid>,
+}
+
+impl SoftwareHeritageIds {
+    pub fn new(revision: Option<String>, files: Vec<ScanFileSwhid>) -> Self {
+        Self {
+            revision,
+            files: files.into_iter().map(|f| (f.file_path.clone(), f)).collect(),
+        }
+    }
+
+    /// SWHID recorded for a file of the document. Local directory scans
+    /// keep absolute paths, matched by their trailing components.
+    fn file(&self, scan_id: &str, file_name: &str) -> Option<&ScanFileSwhid> {
+        let path = relative_uri(file_name, scan_id);
+        self.files.get(&path).or_else(|| {
+            path.match_indices('/')
+                .find_map(|(idx, _)| self.files.get(&path[idx + 1..]))
+        })
+    }
+}
+
+impl PostProcessor for SoftwareHeritageIds {
+    fn name(&self) -> &str {
+        "software-heritage"
+    }
+
+    fn process_document(&self, scan: &Scan, doc: &mut SpdxDocument) -> Result<(), AppError> {
+        if let (Some(revision), Some(package)) = (&self.revision, doc.packages.first_mut()) {
+            package.external_refs.push(ExternalRef {
+                reference_category: "PERSISTENT-ID".to_string(),
+                reference_type: "swh".to_string(),
+                reference_locator: revision.clone(),
+            });
+        }
+
+        for file in &mut doc.files {
+            let Some(swhid) = self.file(&scan.id, &file.file_name) else {
+                continue;
+            };
+            let mut notes: Vec<String> = file.comment.iter().cloned().collect();
+            notes.push(format!("SWHID: {}", swhid.swhid));
+            if let Some(origin) = &swhid.origin {
+                notes.push(format!("Upstream origin: {}", origin));
+            }
+            file.comment = Some(notes.join("; "));
+        }
+        Ok(())
+    }
+}
+
+/// Signs the serialized export with HMAC-SHA256, so recipients holding the
+/// shared key can check the document was not altered
+pub struct HmacSigner {
+    key: Vec<u8>,
+}
+
+impl HmacSigner {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.as_bytes().to_vec(),
+        }
+    }
+
+    pub fn sign(&self, content: &[u8]) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(content);
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+impl PostProcessor for HmacSigner {
+    fn name(&self) -> &str {
+        "sign"
+    }
+
+    fn process_output(&self, output: &mut ExportOutput) -> Result<(), AppError> {
+        let signature = format!("sha256={}", self.sign(&output.content));
+        output
+            .headers
+            .push((SIGNATURE_HEADER.to_string(), signature));
+        Ok(())
+    }
+}
+
+/// Parse `EXPORT_CUSTOM_FIELDS`: `key=value` pairs separated by `;`
+pub fn parse_custom_fields(value: &str) -> Result<Vec<(String, String)>, String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(format!(
+                "Invalid EXPORT_CUSTOM_FIELDS entry '{}' (expected key=value)",
+                pair
+            )),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_emails() {
+        let mut text = "Copyright 2024 Jane Doe <jane.doe@example.com>".to_string();
+        RedactEmails::redact(&mut text);
+        assert_eq!(text, "Copyright 2024 Jane Doe <[redacted]>");
+
+        let mut text = "NOASSERTION".to_string();
+        RedactEmails::redact(&mut text);
+        assert_eq!(text, "NOASSERTION");
+    }
+
+    #[test]
+    fn test_hmac_signature() {
+        // RFC 4231 test case 2
+        let signer = HmacSigner::new("Jefe");
+        assert_eq!(
+            signer.sign(b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_parse_custom_fields() {
+        assert_eq!(
+            parse_custom_fields("contract=ACME-42; business unit = Payments").unwrap(),
+            vec![
+                ("contract".to_string(), "ACME-42".to_string()),
+                ("business unit".to_string(), "Payments".to_string()),
+            ]
+        );
+        assert!(parse_custom_fields("").unwrap().is_empty());
+        assert!(parse_custom_fields("no-separator").is_err());
+    }
+}
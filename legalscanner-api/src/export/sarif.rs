@@ -0,0 +1,284 @@
+use crate::db::models::scan::Scan;
+use crate::db::models::scan_result::ScanResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+/// SARIF 2.1.0 log
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub version: String,
+    pub runs: Vec<Run>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Run {
+    pub tool: Tool,
+    pub results: Vec<SarifResult>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub version_control_provenance: Vec<VersionControlDetails>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Tool {
+    pub driver: ToolComponent,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolComponent {
+    pub name: String,
+    pub version: String,
+    pub information_uri: String,
+    pub rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportingDescriptor {
+    pub id: String,
+    pub name: String,
+    pub short_description: Message,
+    pub default_configuration: ReportingConfiguration,
+    pub properties: RuleProperties,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReportingConfiguration {
+    pub level: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RuleProperties {
+    pub tags: Vec<String>,
+    /// Read by GitHub Code Scanning to rank security alerts (0.0 - 10.0)
+    #[serde(rename = "security-severity")]
+    pub security_severity: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Message {
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub rule_index: usize,
+    pub level: String,
+    pub message: Message,
+    pub locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub suppressions: Vec<Suppression>,
+}
+
+/// Marks a waived finding; `guid` is the waiver's ID
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Suppression {
+    pub kind: String,
+    pub status: String,
+    pub guid: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+    pub artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<Region>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactLocation {
+    pub uri: String,
+    pub uri_base_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub start_line: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionControlDetails {
+    pub repository_uri: String,
+}
+
+/// Build a SARIF report from ECC and malware findings.
+/// License and copyright findings are not code-scanning alerts and are left out.
+/// Waived findings carry an accepted external suppression.
+pub fn build_sarif_report(scan: &Scan, results: &[ScanResult]) -> SarifLog {
+    let mut rules: Vec<ReportingDescriptor> = Vec::new();
+    let mut rule_indexes: HashMap<String, usize> = HashMap::new();
+    let mut sarif_results = Vec::new();
+
+    for result in results {
+        let (rule_id, tag, severity, text) = match result.result_type.as_str() {
+            "ecc" => {
+                let severity = result.risk_severity.clone().unwrap_or_else(|| "medium".to_string());
+                let rule_id = result
+                    .ecc_check_id
+                    .clone()
+                    .unwrap_or_else(|| format!("ecc/{}", severity));
+                let text = result
+                    .raw_data
+                    .clone()
+                    .unwrap_or_else(|| "Export control relevant code".to_string());
+                (rule_id, "export-control", severity, text)
+            }
+            "malware" => {
+                let signature = result.raw_data.clone().unwrap_or_else(|| "unknown".to_string());
+                (
+                    format!("malware/{}", signature),
+                    "malware",
+                    "critical".to_string(),
+                    format!("Malware signature detected: {}", signature),
+                )
+            }
+            _ => continue,
+        };
+
+        let rule_index = *rule_indexes.entry(rule_id.clone()).or_insert_with(|| {
+            rules.push(ReportingDescriptor {
+                id: rule_id.clone(),
+                name: rule_id.clone(),
+                short_description: Message {
+                    text: match tag {
+                        "malware" => "Malware signature".to_string(),
+                        _ => format!("Export control finding ({} severity)", severity),
+                    },
+                },
+                default_configuration: ReportingConfiguration {
+                    level: sarif_level(&severity).to_string(),
+                },
+                properties: RuleProperties {
+                    tags: vec!["security".to_string(), tag.to_string()],
+                    security_severity: security_severity(&severity).to_string(),
+                },
+            });
+            rules.len() - 1
+        });
+
+        sarif_results.push(SarifResult {
+            rule_id,
+            rule_index,
+            level: sarif_level(&severity).to_string(),
+            message: Message { text },
+            locations: vec![Location {
+                physical_location: PhysicalLocation {
+                    artifact_location: ArtifactLocation {
+                        uri: relative_uri(&result.file_path, &scan.id),
+                        uri_base_id: "%SRCROOT%".to_string(),
+                    },
+                    region: result
+                        .ecc_line_number
+                        .filter(|line| *line > 0)
+                        .map(|start_line| Region { start_line }),
+                },
+            }],
+            suppressions: result
+                .waiver_id
+                .iter()
+                .map(|waiver_id| Suppression {
+                    kind: "external".to_string(),
+                    status: "accepted".to_string(),
+                    guid: waiver_id.clone(),
+                })
+                .collect(),
+        });
+    }
+
+    SarifLog {
+        schema: SARIF_SCHEMA.to_string(),
+        version: SARIF_VERSION.to_string(),
+        runs: vec![Run {
+            tool: Tool {
+                driver: ToolComponent {
+                    name: "LegalScanner".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                    information_uri: "https://legalscanner.io".to_string(),
+                    rules,
+                },
+            },
+            results: sarif_results,
+            version_control_provenance: vec![VersionControlDetails {
+                repository_uri: scan.git_url.clone(),
+            }],
+        }],
+    }
+}
+
+fn sarif_level(severity: &str) -> &'static str {
+    match severity {
+        "critical" | "high" => "error",
+        "medium" => "warning",
+        _ => "note",
+    }
+}
+
+fn security_severity(severity: &str) -> &'static str {
+    match severity {
+        "critical" => "9.0",
+        "high" => "7.0",
+        "medium" => "5.0",
+        _ => "3.0",
+    }
+}
+
+/// Scanner paths may be absolute inside the scan workspace (`.../<scan_id>/src/x.rs`);
+/// SARIF locations must be relative to the repository root
+pub(crate) fn relative_uri(file_path: &str, scan_id: &str) -> String {
+    let marker = format!("/{}/", scan_id);
+    match file_path.find(&marker) {
+        Some(idx) => file_path[idx + marker.len()..].to_string(),
+        None => file_path.trim_start_matches("./").trim_start_matches('/').to_string(),
+    }
+}
+
+/// Path relative to the repository root. Scans limited to a subdirectory
+/// store paths relative to that subdirectory.
+pub(crate) fn repository_path(file_path: &str, scan_id: &str, scan_path: Option<&str>) -> String {
+    let path = relative_uri(file_path, scan_id);
+    match scan_path
+        .map(|p| p.trim_matches('/'))
+        .filter(|p| !p.is_empty())
+    {
+        Some(prefix) => format!("{}/{}", prefix, path),
+        None => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_uri() {
+        assert_eq!(relative_uri("/scans/scans/abc-123/src/aes.rs", "abc-123"), "src/aes.rs");
+        assert_eq!(relative_uri("src/aes.rs", "abc-123"), "src/aes.rs");
+        assert_eq!(relative_uri("./src/aes.rs", "abc-123"), "src/aes.rs");
+    }
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(sarif_level("critical"), "error");
+        assert_eq!(sarif_level("medium"), "warning");
+        assert_eq!(sarif_level("low"), "note");
+        assert_eq!(security_severity("high"), "7.0");
+    }
+}
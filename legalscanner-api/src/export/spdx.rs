@@ -1,8 +1,9 @@
 use crate::db::models::scan::Scan;
 use crate::db::models::scan_result::ScanResult;
 use crate::error::AppError;
+use crate::export::{component_license, license_expression};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
 
 /// SPDX 2.3 Document
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,6 +19,8 @@ pub struct SpdxDocument {
     pub packages: Vec<Package>,
     pub files: Vec<File>,
     pub relationships: Vec<Relationship>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub has_extracted_licensing_infos: Vec<ExtractedLicensingInfo>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,6 +30,8 @@ pub struct CreationInfo {
     pub creators: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub license_list_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,13 +40,28 @@ pub struct Package {
     #[serde(rename = "SPDXID")]
     pub spdxid: String,
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_info: Option<String>,
     pub download_location: String,
     pub files_analyzed: bool,
     pub license_concluded: String,
     pub license_declared: String,
+    /// Why the license was concluded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_comments: Option<String>,
     pub copyright_text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_refs: Vec<ExternalRef>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalRef {
+    pub reference_category: String,
+    pub reference_type: String,
+    pub reference_locator: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -57,6 +77,15 @@ pub struct File {
     pub comment: Option<String>,
 }
 
+/// Declares a `LicenseRef-` used for a license outside the SPDX license list
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedLicensingInfo {
+    pub license_id: String,
+    pub extracted_text: String,
+    pub name: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Relationship {
@@ -69,18 +98,27 @@ pub struct Relationship {
 
 /// Build an SPDX 2.3 document from scan data
 pub fn build_spdx_document(scan: &Scan, results: &[ScanResult]) -> Result<SpdxDocument, AppError> {
-    let repo_name = extract_repo_name(&scan.git_url);
+    // A scan of a subdirectory describes that subdirectory only
+    let repo_name = match &scan.scan_path {
+        Some(scan_path) => format!("{}/{}", extract_repo_name(&scan.git_url), scan_path),
+        None => extract_repo_name(&scan.git_url),
+    };
     let namespace = format!("https://legalscanner.io/spdx/{}", scan.id);
 
     let creation_info = CreationInfo {
-        created: scan.completed_at.clone().unwrap_or_else(|| scan.created_at.clone()),
+        created: spdx_timestamp(scan.completed_at.as_deref().unwrap_or(&scan.created_at)),
         creators: vec!["Tool: LegalScanner-1.0".to_string()],
         license_list_version: Some("3.22".to_string()),
+        comment: None,
     };
 
     let package = build_package(scan, &repo_name, results);
+    let dependencies = build_dependency_packages(results);
     let files = build_files(results);
-    let relationships = build_relationships(&files);
+    let relationships = build_relationships(&files, &dependencies);
+
+    let mut packages = vec![package];
+    packages.extend(dependencies);
 
     Ok(SpdxDocument {
         spdx_version: "SPDX-2.3".to_string(),
@@ -89,12 +127,20 @@ pub fn build_spdx_document(scan: &Scan, results: &[ScanResult]) -> Result<SpdxDo
         name: format!("Legal Scanner Report - {}", repo_name),
         document_namespace: namespace,
         creation_info,
-        packages: vec![package],
+        packages,
         files,
         relationships,
+        has_extracted_licensing_infos: build_extracted_licensing_infos(results),
     })
 }
 
+/// SPDX requires `YYYY-MM-DDThh:mm:ssZ`; SQLite stores `YYYY-MM-DD hh:mm:ss` in UTC
+fn spdx_timestamp(value: &str) -> String {
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .map(|t| t.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_else(|_| value.to_string())
+}
+
 fn extract_repo_name(git_url: &str) -> String {
     git_url
         .trim_end_matches(".git")
@@ -105,49 +151,100 @@ fn extract_repo_name(git_url: &str) -> String {
 }
 
 fn build_package(scan: &Scan, repo_name: &str, results: &[ScanResult]) -> Package {
-    let concluded_license = determine_concluded_license(results);
+    let license = component_license::conclude(&scan.id, results);
     let copyright_summary = extract_copyright_summary(results);
 
     let summary = format!(
         "Repository scanned for legal compliance. \
-         Found {} license findings, {} copyright statements, and {} security findings.",
+         Found {} license findings, {} copyright statements, {} security findings, \
+         and {} packages.",
         results.iter().filter(|r| r.result_type == "license").count(),
         results.iter().filter(|r| r.result_type == "copyright").count(),
-        results.iter().filter(|r| r.result_type == "ecc").count()
+        results.iter().filter(|r| r.result_type == "ecc").count(),
+        results.iter().filter(|r| r.result_type == "package").count()
     );
 
     Package {
         spdxid: "SPDXRef-Package".to_string(),
         name: repo_name.to_string(),
+        version_info: None,
         download_location: scan.git_url.clone(),
         files_analyzed: true,
-        license_concluded: concluded_license.clone(),
-        license_declared: concluded_license,
+        license_concluded: license.concluded,
+        license_declared: license.declared,
+        license_comments: Some(license.rationale),
         copyright_text: copyright_summary,
         summary: Some(summary),
+        external_refs: Vec::new(),
     }
 }
 
-fn determine_concluded_license(results: &[ScanResult]) -> String {
-    let licenses: Vec<String> = results
+/// One SPDX package per inventoried dependency (from Syft package findings)
+fn build_dependency_packages(results: &[ScanResult]) -> Vec<Package> {
+    results
         .iter()
-        .filter(|r| r.result_type == "license")
-        .filter_map(|r| {
-            r.license_spdx_id
-                .clone()
-                .or_else(|| r.license_name.clone())
+        .filter(|r| r.result_type == "package")
+        .enumerate()
+        .map(|(idx, r)| Package {
+            spdxid: format!("SPDXRef-Package-{}", idx + 1),
+            name: r.package_name.clone().unwrap_or_else(|| "unknown".to_string()),
+            version_info: r.package_version.clone(),
+            download_location: "NOASSERTION".to_string(),
+            files_analyzed: false,
+            license_concluded: "NOASSERTION".to_string(),
+            license_declared: license_expression::of_result(r)
+                .unwrap_or_else(|| "NOASSERTION".to_string()),
+            license_comments: None,
+            copyright_text: "NOASSERTION".to_string(),
+            summary: Some(format!(
+                "{} package declared in {}",
+                r.package_ecosystem.as_deref().unwrap_or("unknown"),
+                r.file_path
+            )),
+            external_refs: r
+                .package_purl
+                .iter()
+                .map(|purl| ExternalRef {
+                    reference_category: "PACKAGE-MANAGER".to_string(),
+                    reference_type: "purl".to_string(),
+                    reference_locator: purl.clone(),
+                })
+                .collect(),
         })
-        .collect::<HashSet<_>>()
-        .into_iter()
-        .collect();
+        .collect()
+}
 
-    if licenses.is_empty() {
-        "NOASSERTION".to_string()
-    } else if licenses.len() == 1 {
-        licenses[0].clone()
-    } else {
-        format!("({})", licenses.join(" AND "))
+/// Declare every `LicenseRef-` the document uses, named after the scanner's
+/// original license name
+fn build_extracted_licensing_infos(results: &[ScanResult]) -> Vec<ExtractedLicensingInfo> {
+    let mut license_refs: BTreeMap<String, String> = BTreeMap::new();
+    for result in results {
+        let Some(expression) = license_expression::of_result(result) else {
+            continue;
+        };
+        let name = result
+            .license_name
+            .as_deref()
+            .or(result.license_spdx_id.as_deref())
+            .unwrap_or_default();
+        for id in license_expression::license_ids(&expression) {
+            if id.starts_with("LicenseRef-") {
+                license_refs.entry(id).or_insert_with(|| name.to_string());
+            }
+        }
     }
+
+    license_refs
+        .into_iter()
+        .map(|(license_id, name)| ExtractedLicensingInfo {
+            extracted_text: format!(
+                "License text not captured. Reported by the scanner as \"{}\".",
+                name
+            ),
+            license_id,
+            name,
+        })
+        .collect()
 }
 
 fn extract_copyright_summary(results: &[ScanResult]) -> String {
@@ -166,7 +263,8 @@ fn extract_copyright_summary(results: &[ScanResult]) -> String {
 
 fn build_files(results: &[ScanResult]) -> Vec<File> {
     let mut files_map: HashMap<String, Vec<&ScanResult>> = HashMap::new();
-    for result in results {
+    // Package findings become SPDX packages, not file entries
+    for result in results.iter().filter(|r| r.result_type != "package") {
         files_map
             .entry(result.file_path.clone())
             .or_insert_with(Vec::new)
@@ -185,23 +283,20 @@ fn build_files(results: &[ScanResult]) -> Vec<File> {
 fn build_file(file_path: &str, results: Vec<&ScanResult>, index: usize) -> File {
     let spdx_id = format!("SPDXRef-File-{}", index);
 
-    let licenses: Vec<String> = results
+    let expressions: Vec<String> = results
         .iter()
-        .filter(|r| r.result_type == "license")
-        .filter_map(|r| {
-            r.license_spdx_id
-                .clone()
-                .or_else(|| r.license_name.clone())
-        })
+        .filter(|r| r.result_type == "license" || r.result_type == "model_license")
+        .filter_map(|r| license_expression::of_result(r))
         .collect();
 
-    let license_concluded = if licenses.is_empty() {
-        "NOASSERTION".to_string()
-    } else if licenses.len() == 1 {
-        licenses[0].clone()
-    } else {
-        format!("({})", licenses.join(" AND "))
-    };
+    let mut licenses: Vec<String> = Vec::new();
+    for id in expressions.iter().flat_map(|e| license_expression::license_ids(e)) {
+        if !licenses.contains(&id) {
+            licenses.push(id);
+        }
+    }
+
+    let license_concluded = license_expression::conjunction(expressions);
 
     let copyright_text = results
         .iter()
@@ -250,13 +345,641 @@ fn build_file(file_path: &str, results: Vec<&ScanResult>, index: usize) -> File
     }
 }
 
-fn build_relationships(files: &[File]) -> Vec<Relationship> {
-    files
+fn build_relationships(files: &[File], dependencies: &[Package]) -> Vec<Relationship> {
+    let describes = std::iter::once(Relationship {
+        spdx_element_id: "SPDXRef-DOCUMENT".to_string(),
+        relationship_type: "DESCRIBES".to_string(),
+        related_spdx_element: "SPDXRef-Package".to_string(),
+    });
+
+    let contains = files.iter().map(|file| Relationship {
+        spdx_element_id: "SPDXRef-Package".to_string(),
+        relationship_type: "CONTAINS".to_string(),
+        related_spdx_element: file.spdxid.clone(),
+    });
+
+    let depends_on = dependencies.iter().map(|package| Relationship {
+        spdx_element_id: "SPDXRef-Package".to_string(),
+        relationship_type: "DEPENDS_ON".to_string(),
+        related_spdx_element: package.spdxid.clone(),
+    });
+
+    describes.chain(contains).chain(depends_on).collect()
+}
+
+/// Combine the documents of several repositories into one product SBOM. A
+/// top-level package named after the product contains each repository's
+/// package; element IDs get a `R<n>-` prefix and file names the repository
+/// name, so that elements of different repositories stay distinct.
+pub fn aggregate_documents(
+    name: &str,
+    namespace: String,
+    created: String,
+    documents: Vec<SpdxDocument>,
+) -> SpdxDocument {
+    let mut packages = vec![Package {
+        spdxid: "SPDXRef-Package".to_string(),
+        name: name.to_string(),
+        version_info: None,
+        download_location: "NOASSERTION".to_string(),
+        files_analyzed: false,
+        license_concluded: "NOASSERTION".to_string(),
+        license_declared: "NOASSERTION".to_string(),
+        license_comments: None,
+        copyright_text: "NOASSERTION".to_string(),
+        summary: Some(format!("{} repositories", documents.len())),
+        external_refs: Vec::new(),
+    }];
+    let mut files = Vec::new();
+    let mut relationships = vec![Relationship {
+        spdx_element_id: "SPDXRef-DOCUMENT".to_string(),
+        relationship_type: "DESCRIBES".to_string(),
+        related_spdx_element: "SPDXRef-Package".to_string(),
+    }];
+    let mut extracted: BTreeMap<String, ExtractedLicensingInfo> = BTreeMap::new();
+    let comment = documents
         .iter()
-        .map(|file| Relationship {
+        .find_map(|doc| doc.creation_info.comment.clone());
+
+    for (idx, doc) in documents.into_iter().enumerate() {
+        let prefix = |id: &str| id.replacen("SPDXRef-", &format!("SPDXRef-R{}-", idx + 1), 1);
+        let repo_name = doc
+            .packages
+            .iter()
+            .find(|p| p.spdxid == "SPDXRef-Package")
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| format!("repository-{}", idx + 1));
+
+        relationships.push(Relationship {
             spdx_element_id: "SPDXRef-Package".to_string(),
             relationship_type: "CONTAINS".to_string(),
-            related_spdx_element: file.spdxid.clone(),
-        })
-        .collect()
+            related_spdx_element: prefix("SPDXRef-Package"),
+        });
+        relationships.extend(
+            doc.relationships
+                .into_iter()
+                .filter(|r| r.relationship_type != "DESCRIBES")
+                .map(|r| Relationship {
+                    spdx_element_id: prefix(&r.spdx_element_id),
+                    relationship_type: r.relationship_type,
+                    related_spdx_element: prefix(&r.related_spdx_element),
+                }),
+        );
+        packages.extend(doc.packages.into_iter().map(|package| Package {
+            spdxid: prefix(&package.spdxid),
+            ..package
+        }));
+        files.extend(doc.files.into_iter().map(|file| File {
+            spdxid: prefix(&file.spdxid),
+            file_name: format!("{}/{}", repo_name, file.file_name),
+            ..file
+        }));
+        for info in doc.has_extracted_licensing_infos {
+            extracted.entry(info.license_id.clone()).or_insert(info);
+        }
+    }
+
+    SpdxDocument {
+        spdx_version: "SPDX-2.3".to_string(),
+        data_license: "CC0-1.0".to_string(),
+        spdxid: "SPDXRef-DOCUMENT".to_string(),
+        name: format!("Legal Scanner Report - {}", name),
+        document_namespace: namespace,
+        creation_info: CreationInfo {
+            created: spdx_timestamp(&created),
+            creators: vec!["Tool: LegalScanner-1.0".to_string()],
+            license_list_version: Some("3.22".to_string()),
+            comment,
+        },
+        packages,
+        files,
+        relationships,
+        has_extracted_licensing_infos: extracted.into_values().collect(),
+    }
+}
+
+/// Serialize an SPDX document in the tag-value (`.spdx`) format
+pub fn to_tag_value(doc: &SpdxDocument) -> String {
+    fn line(out: &mut String, tag: &str, value: &str) {
+        out.push_str(tag);
+        out.push_str(": ");
+        out.push_str(value);
+        out.push('\n');
+    }
+
+    let mut out = String::new();
+
+    line(&mut out, "SPDXVersion", &doc.spdx_version);
+    line(&mut out, "DataLicense", &doc.data_license);
+    line(&mut out, "SPDXID", &doc.spdxid);
+    line(&mut out, "DocumentName", &doc.name);
+    line(&mut out, "DocumentNamespace", &doc.document_namespace);
+    for creator in &doc.creation_info.creators {
+        line(&mut out, "Creator", creator);
+    }
+    line(&mut out, "Created", &doc.creation_info.created);
+    if let Some(version) = &doc.creation_info.license_list_version {
+        line(&mut out, "LicenseListVersion", version);
+    }
+    if let Some(comment) = &doc.creation_info.comment {
+        line(&mut out, "CreatorComment", &tag_text(comment));
+    }
+
+    for package in &doc.packages {
+        out.push('\n');
+        line(&mut out, "PackageName", &package.name);
+        line(&mut out, "SPDXID", &package.spdxid);
+        if let Some(version) = &package.version_info {
+            line(&mut out, "PackageVersion", version);
+        }
+        line(&mut out, "PackageDownloadLocation", &package.download_location);
+        line(&mut out, "FilesAnalyzed", if package.files_analyzed { "true" } else { "false" });
+        line(&mut out, "PackageLicenseConcluded", &package.license_concluded);
+        line(&mut out, "PackageLicenseDeclared", &package.license_declared);
+        if let Some(comments) = &package.license_comments {
+            line(&mut out, "PackageLicenseComments", &tag_text(comments));
+        }
+        line(&mut out, "PackageCopyrightText", &tag_text(&package.copyright_text));
+        if let Some(summary) = &package.summary {
+            line(&mut out, "PackageSummary", &tag_text(summary));
+        }
+        for external_ref in &package.external_refs {
+            line(
+                &mut out,
+                "ExternalRef",
+                &format!(
+                    "{} {} {}",
+                    external_ref.reference_category,
+                    external_ref.reference_type,
+                    external_ref.reference_locator
+                ),
+            );
+        }
+    }
+
+    for file in &doc.files {
+        out.push('\n');
+        line(&mut out, "FileName", &format!("./{}", file.file_name.trim_start_matches("./")));
+        line(&mut out, "SPDXID", &file.spdxid);
+        line(&mut out, "LicenseConcluded", &file.license_concluded);
+        for license in &file.license_info_in_files {
+            line(&mut out, "LicenseInfoInFile", license);
+        }
+        line(&mut out, "FileCopyrightText", &tag_text(&file.copyright_text));
+        if let Some(comment) = &file.comment {
+            line(&mut out, "FileComment", &tag_text(comment));
+        }
+    }
+
+    for info in &doc.has_extracted_licensing_infos {
+        out.push('\n');
+        line(&mut out, "LicenseID", &info.license_id);
+        line(&mut out, "ExtractedText", &tag_text(&info.extracted_text));
+        line(&mut out, "LicenseName", &info.name);
+    }
+
+    out.push('\n');
+    for relationship in &doc.relationships {
+        line(
+            &mut out,
+            "Relationship",
+            &format!(
+                "{} {} {}",
+                relationship.spdx_element_id,
+                relationship.relationship_type,
+                relationship.related_spdx_element
+            ),
+        );
+    }
+
+    out
+}
+
+/// Multi-line and free-form values are wrapped in <text> tags; NOASSERTION stays bare
+fn tag_text(value: &str) -> String {
+    if value == "NOASSERTION" || value == "NONE" {
+        value.to_string()
+    } else {
+        format!("<text>{}</text>", value)
+    }
+}
+
+const SPDX_TERMS: &str = "http://spdx.org/rdf/terms#";
+const SPDX_LICENSES: &str = "http://spdx.org/licenses/";
+
+/// Serialize an SPDX document as RDF/XML
+pub fn to_rdf_xml(doc: &SpdxDocument) -> String {
+    let ns = &doc.document_namespace;
+    let mut out = String::new();
+
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(
+        "<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\" \
+         xmlns:rdfs=\"http://www.w3.org/2000/01/rdf-schema#\" \
+         xmlns:spdx=\"http://spdx.org/rdf/terms#\">\n",
+    );
+
+    out.push_str(&format!("  <spdx:SpdxDocument rdf:about=\"{}#{}\">\n", xml_escape(ns), doc.spdxid));
+    out.push_str(&format!("    <spdx:specVersion>{}</spdx:specVersion>\n", doc.spdx_version));
+    out.push_str(&format!(
+        "    <spdx:dataLicense rdf:resource=\"{}{}\"/>\n",
+        SPDX_LICENSES, doc.data_license
+    ));
+    out.push_str(&format!("    <spdx:name>{}</spdx:name>\n", xml_escape(&doc.name)));
+    out.push_str("    <spdx:creationInfo>\n      <spdx:CreationInfo>\n");
+    out.push_str(&format!("        <spdx:created>{}</spdx:created>\n", doc.creation_info.created));
+    for creator in &doc.creation_info.creators {
+        out.push_str(&format!("        <spdx:creator>{}</spdx:creator>\n", xml_escape(creator)));
+    }
+    if let Some(version) = &doc.creation_info.license_list_version {
+        out.push_str(&format!("        <spdx:licenseListVersion>{}</spdx:licenseListVersion>\n", version));
+    }
+    if let Some(comment) = &doc.creation_info.comment {
+        out.push_str(&format!("        <rdfs:comment>{}</rdfs:comment>\n", xml_escape(comment)));
+    }
+    out.push_str("      </spdx:CreationInfo>\n    </spdx:creationInfo>\n");
+    push_rdf_relationships(&mut out, doc, &doc.spdxid);
+    out.push_str("  </spdx:SpdxDocument>\n");
+
+    for package in &doc.packages {
+        out.push_str(&format!("  <spdx:Package rdf:about=\"{}#{}\">\n", xml_escape(ns), package.spdxid));
+        out.push_str(&format!("    <spdx:name>{}</spdx:name>\n", xml_escape(&package.name)));
+        if let Some(version) = &package.version_info {
+            out.push_str(&format!("    <spdx:versionInfo>{}</spdx:versionInfo>\n", xml_escape(version)));
+        }
+        out.push_str(&format!(
+            "    <spdx:downloadLocation>{}</spdx:downloadLocation>\n",
+            xml_escape(&package.download_location)
+        ));
+        out.push_str(&format!("    <spdx:filesAnalyzed>{}</spdx:filesAnalyzed>\n", package.files_analyzed));
+        out.push_str(&rdf_license("licenseConcluded", &package.license_concluded, ns));
+        out.push_str(&rdf_license("licenseDeclared", &package.license_declared, ns));
+        if let Some(comments) = &package.license_comments {
+            out.push_str(&format!("    <spdx:licenseComments>{}</spdx:licenseComments>\n", xml_escape(comments)));
+        }
+        out.push_str(&format!(
+            "    <spdx:copyrightText>{}</spdx:copyrightText>\n",
+            xml_escape(&package.copyright_text)
+        ));
+        if let Some(summary) = &package.summary {
+            out.push_str(&format!("    <spdx:summary>{}</spdx:summary>\n", xml_escape(summary)));
+        }
+        for external_ref in &package.external_refs {
+            out.push_str(&format!(
+                "    <spdx:externalRef>\n      <spdx:ExternalRef>\n        \
+                 <spdx:referenceCategory rdf:resource=\"{}referenceCategory_{}\"/>\n        \
+                 <spdx:referenceType rdf:resource=\"http://spdx.org/rdf/references/{}\"/>\n        \
+                 <spdx:referenceLocator>{}</spdx:referenceLocator>\n      \
+                 </spdx:ExternalRef>\n    </spdx:externalRef>\n",
+                SPDX_TERMS,
+                rdf_term(&external_ref.reference_category.replace('-', "_")),
+                external_ref.reference_type,
+                xml_escape(&external_ref.reference_locator)
+            ));
+        }
+        push_rdf_relationships(&mut out, doc, &package.spdxid);
+        out.push_str("  </spdx:Package>\n");
+    }
+
+    for file in &doc.files {
+        out.push_str(&format!("  <spdx:File rdf:about=\"{}#{}\">\n", xml_escape(ns), file.spdxid));
+        out.push_str(&format!(
+            "    <spdx:fileName>./{}</spdx:fileName>\n",
+            xml_escape(file.file_name.trim_start_matches("./"))
+        ));
+        out.push_str(&rdf_license("licenseConcluded", &file.license_concluded, ns));
+        for license in &file.license_info_in_files {
+            out.push_str(&rdf_license("licenseInfoInFile", license, ns));
+        }
+        out.push_str(&format!(
+            "    <spdx:copyrightText>{}</spdx:copyrightText>\n",
+            xml_escape(&file.copyright_text)
+        ));
+        if let Some(comment) = &file.comment {
+            out.push_str(&format!("    <rdfs:comment>{}</rdfs:comment>\n", xml_escape(comment)));
+        }
+        out.push_str("  </spdx:File>\n");
+    }
+
+    for info in &doc.has_extracted_licensing_infos {
+        out.push_str(&format!(
+            "  <spdx:ExtractedLicensingInfo rdf:about=\"{}#{}\">\n    \
+             <spdx:licenseId>{}</spdx:licenseId>\n    \
+             <spdx:extractedText>{}</spdx:extractedText>\n    \
+             <spdx:name>{}</spdx:name>\n  \
+             </spdx:ExtractedLicensingInfo>\n",
+            xml_escape(ns),
+            xml_escape(&info.license_id),
+            xml_escape(&info.license_id),
+            xml_escape(&info.extracted_text),
+            xml_escape(&info.name)
+        ));
+    }
+
+    out.push_str("</rdf:RDF>\n");
+    out
+}
+
+/// Relationships are nested inside the element they originate from
+fn push_rdf_relationships(out: &mut String, doc: &SpdxDocument, element_id: &str) {
+    let ns = xml_escape(&doc.document_namespace);
+    for relationship in doc.relationships.iter().filter(|r| r.spdx_element_id == element_id) {
+        out.push_str(&format!(
+            "    <spdx:relationship>\n      <spdx:Relationship>\n        \
+             <spdx:relationshipType rdf:resource=\"{}relationshipType_{}\"/>\n        \
+             <spdx:relatedSpdxElement rdf:resource=\"{}#{}\"/>\n      \
+             </spdx:Relationship>\n    </spdx:relationship>\n",
+            SPDX_TERMS,
+            rdf_term(&relationship.relationship_type),
+            ns,
+            relationship.related_spdx_element
+        ));
+    }
+}
+
+/// Enum values to RDF term suffixes, e.g. `DEPENDS_ON` -> `dependsOn`
+pub(crate) fn rdf_term(value: &str) -> String {
+    let mut term = String::new();
+    for (idx, part) in value.to_lowercase().split('_').enumerate() {
+        if idx == 0 {
+            term.push_str(part);
+        } else {
+            let mut chars = part.chars();
+            if let Some(first) = chars.next() {
+                term.extend(first.to_uppercase());
+                term.push_str(chars.as_str());
+            }
+        }
+    }
+    term
+}
+
+/// Render a license expression as an RDF property. `AND` / `OR` expressions
+/// become (possibly nested) conjunctive/disjunctive license sets.
+fn rdf_license(property: &str, expression: &str, ns: &str) -> String {
+    match split_expression(expression) {
+        None => format!(
+            "    <spdx:{} rdf:resource=\"{}\"/>\n",
+            property,
+            license_uri(strip_parens(expression), ns)
+        ),
+        Some((set, members)) => {
+            let mut out = format!("    <spdx:{}>\n", property);
+            push_rdf_license_set(&mut out, set, &members, ns, 6);
+            out.push_str(&format!("    </spdx:{}>\n", property));
+            out
+        }
+    }
+}
+
+fn push_rdf_license_set(out: &mut String, set: &str, members: &[&str], ns: &str, indent: usize) {
+    let pad = " ".repeat(indent);
+    out.push_str(&format!("{}<spdx:{}>\n", pad, set));
+    for member in members {
+        match split_expression(member) {
+            None => out.push_str(&format!(
+                "{}  <spdx:member rdf:resource=\"{}\"/>\n",
+                pad,
+                license_uri(strip_parens(member), ns)
+            )),
+            Some((inner, inner_members)) => {
+                out.push_str(&format!("{}  <spdx:member>\n", pad));
+                push_rdf_license_set(out, inner, &inner_members, ns, indent + 4);
+                out.push_str(&format!("{}  </spdx:member>\n", pad));
+            }
+        }
+    }
+    out.push_str(&format!("{}</spdx:{}>\n", pad, set));
+}
+
+/// Split an expression at its top-level operator. `AND` binds tighter than
+/// `OR`, so a top-level `OR` takes precedence. `None` for a single license.
+fn split_expression(expression: &str) -> Option<(&'static str, Vec<&str>)> {
+    let expression = strip_parens(expression);
+    for (operator, set) in [(" OR ", "DisjunctiveLicenseSet"), (" AND ", "ConjunctiveLicenseSet")] {
+        let mut members = Vec::new();
+        let mut depth = 0usize;
+        let mut start = 0;
+        for (idx, c) in expression.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                _ if depth == 0 && expression[idx..].starts_with(operator) && idx >= start => {
+                    members.push(expression[start..idx].trim());
+                    start = idx + operator.len();
+                }
+                _ => {}
+            }
+        }
+        if !members.is_empty() {
+            members.push(expression[start..].trim());
+            return Some((set, members));
+        }
+    }
+    None
+}
+
+/// Remove parentheses that enclose the whole expression
+fn strip_parens(expression: &str) -> &str {
+    let mut expression = expression.trim();
+    while expression.starts_with('(') && expression.ends_with(')') {
+        let inner = &expression[1..expression.len() - 1];
+        // `(A) AND (B)` starts and ends with parentheses that do not match
+        let mut depth = 0i32;
+        let balanced = inner.chars().all(|c| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            depth >= 0
+        });
+        if !balanced {
+            break;
+        }
+        expression = inner.trim();
+    }
+    expression
+}
+
+fn license_uri(license: &str, ns: &str) -> String {
+    match license {
+        "NOASSERTION" => format!("{}noassertion", SPDX_TERMS),
+        "NONE" => format!("{}none", SPDX_TERMS),
+        id if id.starts_with("LicenseRef-") => format!("{}#{}", xml_escape(ns), xml_escape(id)),
+        id if id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '+')) => {
+            format!("{}{}", SPDX_LICENSES, id)
+        }
+        // Scanner-specific names that are not SPDX identifiers
+        other => format!(
+            "{}#{}",
+            xml_escape(ns),
+            xml_escape(&license_expression::license_ref(other))
+        ),
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_document() -> SpdxDocument {
+        let files = vec![File {
+            spdxid: "SPDXRef-File-1".to_string(),
+            file_name: "src/main.rs".to_string(),
+            license_concluded: "(MIT AND Apache-2.0)".to_string(),
+            license_info_in_files: vec!["MIT".to_string(), "Apache-2.0".to_string()],
+            copyright_text: "Copyright (c) 2024 Acme <dev@acme.io>".to_string(),
+            comment: None,
+        }];
+        let relationships = build_relationships(&files, &[]);
+
+        SpdxDocument {
+            spdx_version: "SPDX-2.3".to_string(),
+            data_license: "CC0-1.0".to_string(),
+            spdxid: "SPDXRef-DOCUMENT".to_string(),
+            name: "Legal Scanner Report - repo".to_string(),
+            document_namespace: "https://legalscanner.io/spdx/scan-1".to_string(),
+            creation_info: CreationInfo {
+                created: spdx_timestamp("2025-01-14 10:00:00"),
+                creators: vec!["Tool: LegalScanner-1.0".to_string()],
+                license_list_version: Some("3.22".to_string()),
+                comment: None,
+            },
+            packages: vec![Package {
+                spdxid: "SPDXRef-Package".to_string(),
+                name: "repo".to_string(),
+                version_info: None,
+                download_location: "https://github.com/acme/repo.git".to_string(),
+                files_analyzed: true,
+                license_concluded: "NOASSERTION".to_string(),
+                license_declared: "NOASSERTION".to_string(),
+                license_comments: None,
+                copyright_text: "NOASSERTION".to_string(),
+                summary: None,
+                external_refs: Vec::new(),
+            }],
+            files,
+            relationships,
+            has_extracted_licensing_infos: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_to_tag_value() {
+        let output = to_tag_value(&sample_document());
+
+        assert!(output.starts_with("SPDXVersion: SPDX-2.3\n"));
+        assert!(output.contains("Created: 2025-01-14T10:00:00Z\n"));
+        assert!(output.contains("\nPackageName: repo\n"));
+        assert!(output.contains("PackageCopyrightText: NOASSERTION\n"));
+        assert!(output.contains("FileName: ./src/main.rs\n"));
+        assert!(output.contains("LicenseInfoInFile: Apache-2.0\n"));
+        assert!(output.contains("FileCopyrightText: <text>Copyright (c) 2024 Acme <dev@acme.io></text>\n"));
+        assert!(output.contains("Relationship: SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package\n"));
+        assert!(output.contains("Relationship: SPDXRef-Package CONTAINS SPDXRef-File-1\n"));
+    }
+
+    #[test]
+    fn test_aggregate_documents_keeps_ids_distinct() {
+        let doc = aggregate_documents(
+            "checkout",
+            "https://legalscanner.io/spdx/project/p-1".to_string(),
+            "2025-01-15 08:00:00".to_string(),
+            vec![sample_document(), sample_document()],
+        );
+
+        let ids: std::collections::HashSet<&str> = doc
+            .packages
+            .iter()
+            .map(|p| p.spdxid.as_str())
+            .chain(doc.files.iter().map(|f| f.spdxid.as_str()))
+            .collect();
+        assert_eq!(ids.len(), 5);
+        assert_eq!(doc.files[0].spdxid, "SPDXRef-R1-File-1");
+        assert_eq!(doc.files[1].file_name, "repo/src/main.rs");
+        assert_eq!(doc.creation_info.created, "2025-01-15T08:00:00Z");
+
+        let describes: Vec<&Relationship> = doc
+            .relationships
+            .iter()
+            .filter(|r| r.relationship_type == "DESCRIBES")
+            .collect();
+        assert_eq!(describes.len(), 1);
+        let has = |from: &str, to: &str| {
+            doc.relationships
+                .iter()
+                .any(|r| r.spdx_element_id == from && r.related_spdx_element == to)
+        };
+        assert!(has("SPDXRef-Package", "SPDXRef-R2-Package"));
+        assert!(has("SPDXRef-R2-Package", "SPDXRef-R2-File-1"));
+    }
+
+    #[test]
+    fn test_to_rdf_xml() {
+        let output = to_rdf_xml(&sample_document());
+
+        assert!(output.contains("<spdx:SpdxDocument rdf:about=\"https://legalscanner.io/spdx/scan-1#SPDXRef-DOCUMENT\">"));
+        assert!(output.contains("<spdx:ConjunctiveLicenseSet>"));
+        assert!(output.contains("<spdx:member rdf:resource=\"http://spdx.org/licenses/MIT\"/>"));
+        assert!(output.contains("rdf:resource=\"http://spdx.org/rdf/terms#noassertion\""));
+        assert!(output.contains("Acme &lt;dev@acme.io&gt;"));
+        assert!(output.contains("relationshipType_describes"));
+        assert!(output.trim_end().ends_with("</rdf:RDF>"));
+    }
+
+    #[test]
+    fn test_nested_rdf_license_sets() {
+        let output = rdf_license(
+            "licenseConcluded",
+            "BSD-3-Clause AND (MIT OR Apache-2.0)",
+            "https://x/spdx/1",
+        );
+
+        assert!(output.starts_with("    <spdx:licenseConcluded>\n      <spdx:ConjunctiveLicenseSet>\n"));
+        assert!(output.contains("        <spdx:member>\n          <spdx:DisjunctiveLicenseSet>\n"));
+        assert!(output.contains("<spdx:member rdf:resource=\"http://spdx.org/licenses/Apache-2.0\"/>"));
+        assert_eq!(split_expression("(MIT)"), None);
+    }
+
+    #[test]
+    fn test_scanner_names_become_valid_expressions() {
+        let result = |file: &str, name: &str| ScanResult {
+            license_name: Some(name.to_string()),
+            license_category: Some("code".to_string()),
+            ..ScanResult::for_test("license", file)
+        };
+        let results = vec![
+            result("a.rs", "Apache License 2.0"),
+            result("a.rs", "Acme Internal"),
+            result("b.rs", "No_license_found"),
+        ];
+
+        assert_eq!(
+            component_license::conclude("scan-1", &results).concluded,
+            "Apache-2.0 AND LicenseRef-Acme-Internal"
+        );
+        let infos = build_extracted_licensing_infos(&results);
+        assert_eq!(infos.len(), 1);
+        assert_eq!(infos[0].license_id, "LicenseRef-Acme-Internal");
+        assert_eq!(infos[0].name, "Acme Internal");
+    }
+
+    #[test]
+    fn test_license_uri_for_scanner_names() {
+        assert_eq!(
+            license_uri("No_license_found", "https://x/spdx/1"),
+            "https://x/spdx/1#LicenseRef-No-license-found"
+        );
+        assert_eq!(rdf_term("DEPENDS_ON"), "dependsOn");
+        assert_eq!(rdf_term("PACKAGE_MANAGER"), "packageManager");
+    }
 }
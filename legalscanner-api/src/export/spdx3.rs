@@ -0,0 +1,360 @@
+use crate::export::spdx::{rdf_term, SpdxDocument};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const SPDX3_CONTEXT: &str = "https://spdx.org/rdf/3.0.1/spdx-context.jsonld";
+const SPDX3_VERSION: &str = "3.0.1";
+const CREATION_INFO_ID: &str = "_:creationinfo";
+
+/// SPDX 3.0 JSON-LD document: a context plus a flat graph of elements
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Spdx3Document {
+    #[serde(rename = "@context")]
+    pub context: String,
+    #[serde(rename = "@graph")]
+    pub graph: Vec<Spdx3Element>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Spdx3Element {
+    CreationInfo {
+        #[serde(rename = "@id")]
+        id: String,
+        #[serde(rename = "specVersion")]
+        spec_version: String,
+        created: String,
+        #[serde(rename = "createdBy")]
+        created_by: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<String>,
+    },
+    Tool {
+        #[serde(rename = "spdxId")]
+        spdx_id: String,
+        #[serde(rename = "creationInfo")]
+        creation_info: String,
+        name: String,
+    },
+    SpdxDocument {
+        #[serde(rename = "spdxId")]
+        spdx_id: String,
+        #[serde(rename = "creationInfo")]
+        creation_info: String,
+        name: String,
+        #[serde(rename = "dataLicense")]
+        data_license: String,
+        #[serde(rename = "profileConformance")]
+        profile_conformance: Vec<String>,
+        #[serde(rename = "rootElement")]
+        root_element: Vec<String>,
+        element: Vec<String>,
+    },
+    #[serde(rename = "software_Sbom")]
+    Sbom {
+        #[serde(rename = "spdxId")]
+        spdx_id: String,
+        #[serde(rename = "creationInfo")]
+        creation_info: String,
+        #[serde(rename = "software_sbomType")]
+        sbom_type: Vec<String>,
+        #[serde(rename = "rootElement")]
+        root_element: Vec<String>,
+        element: Vec<String>,
+    },
+    #[serde(rename = "software_Package")]
+    Package {
+        #[serde(rename = "spdxId")]
+        spdx_id: String,
+        #[serde(rename = "creationInfo")]
+        creation_info: String,
+        name: String,
+        #[serde(rename = "software_packageVersion", skip_serializing_if = "Option::is_none")]
+        version: Option<String>,
+        #[serde(rename = "software_downloadLocation", skip_serializing_if = "Option::is_none")]
+        download_location: Option<String>,
+        #[serde(rename = "software_packageUrl", skip_serializing_if = "Option::is_none")]
+        package_url: Option<String>,
+        #[serde(rename = "software_copyrightText", skip_serializing_if = "Option::is_none")]
+        copyright_text: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        summary: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        comment: Option<String>,
+        #[serde(rename = "externalIdentifier", default, skip_serializing_if = "Vec::is_empty")]
+        external_identifier: Vec<ExternalIdentifier>,
+    },
+    #[serde(rename = "software_File")]
+    File {
+        #[serde(rename = "spdxId")]
+        spdx_id: String,
+        #[serde(rename = "creationInfo")]
+        creation_info: String,
+        name: String,
+        #[serde(rename = "software_copyrightText", skip_serializing_if = "Option::is_none")]
+        copyright_text: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        comment: Option<String>,
+    },
+    #[serde(rename = "simplelicensing_LicenseExpression")]
+    LicenseExpression {
+        #[serde(rename = "spdxId")]
+        spdx_id: String,
+        #[serde(rename = "creationInfo")]
+        creation_info: String,
+        #[serde(rename = "simplelicensing_licenseExpression")]
+        license_expression: String,
+    },
+    Relationship {
+        #[serde(rename = "spdxId")]
+        spdx_id: String,
+        #[serde(rename = "creationInfo")]
+        creation_info: String,
+        from: String,
+        #[serde(rename = "relationshipType")]
+        relationship_type: String,
+        to: Vec<String>,
+    },
+}
+
+/// Identifier of an element in another scheme, e.g. a Software Heritage ID
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExternalIdentifier {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(rename = "externalIdentifierType")]
+    pub identifier_type: String,
+    pub identifier: String,
+}
+
+/// Convert an SPDX 2.3 document to the SPDX 3.0 element model.
+/// 2.3 license fields become license expression elements linked through
+/// `hasConcludedLicense` / `hasDeclaredLicense` relationships.
+pub fn build_spdx3_document(doc: &SpdxDocument) -> Spdx3Document {
+    let ns = doc.document_namespace.as_str();
+    let id = |local: &str| format!("{}#{}", ns, local);
+    let creation_info = CREATION_INFO_ID.to_string();
+
+    let mut graph = Vec::new();
+    let mut elements = Vec::new();
+    let mut relationships = Vec::new();
+    let mut license_ids: HashMap<String, String> = HashMap::new();
+
+    let tool_id = id("SPDXRef-Tool-LegalScanner");
+    graph.push(Spdx3Element::CreationInfo {
+        id: creation_info.clone(),
+        spec_version: SPDX3_VERSION.to_string(),
+        created: doc.creation_info.created.clone(),
+        created_by: vec![tool_id.clone()],
+        comment: doc.creation_info.comment.clone(),
+    });
+    graph.push(Spdx3Element::Tool {
+        spdx_id: tool_id,
+        creation_info: creation_info.clone(),
+        name: doc
+            .creation_info
+            .creators
+            .first()
+            .map(|c| c.trim_start_matches("Tool: ").to_string())
+            .unwrap_or_else(|| "LegalScanner".to_string()),
+    });
+
+    // Link an element to a license expression element, creating it once per expression
+    let mut link_license = |graph: &mut Vec<Spdx3Element>,
+                            relationships: &mut Vec<(String, String, String)>,
+                            from: &str,
+                            relationship_type: &str,
+                            expression: &str| {
+        if expression == "NOASSERTION" || expression.is_empty() {
+            return;
+        }
+        let next = license_ids.len() + 1;
+        let license_id = license_ids
+            .entry(expression.to_string())
+            .or_insert_with(|| {
+                let license_id = id(&format!("SPDXRef-License-{}", next));
+                graph.push(Spdx3Element::LicenseExpression {
+                    spdx_id: license_id.clone(),
+                    creation_info: CREATION_INFO_ID.to_string(),
+                    license_expression: expression.to_string(),
+                });
+                license_id
+            })
+            .clone();
+        relationships.push((from.to_string(), relationship_type.to_string(), license_id));
+    };
+
+    for package in &doc.packages {
+        let package_id = id(&package.spdxid);
+        graph.push(Spdx3Element::Package {
+            spdx_id: package_id.clone(),
+            creation_info: creation_info.clone(),
+            name: package.name.clone(),
+            version: package.version_info.clone(),
+            download_location: Some(package.download_location.clone())
+                .filter(|l| l != "NOASSERTION"),
+            package_url: package
+                .external_refs
+                .iter()
+                .find(|r| r.reference_type == "purl")
+                .map(|r| r.reference_locator.clone()),
+            copyright_text: Some(package.copyright_text.clone()).filter(|c| c != "NOASSERTION"),
+            summary: package.summary.clone(),
+            comment: package.license_comments.clone(),
+            external_identifier: package
+                .external_refs
+                .iter()
+                .filter(|r| r.reference_type == "swh")
+                .map(|r| ExternalIdentifier {
+                    kind: "ExternalIdentifier".to_string(),
+                    identifier_type: "swhid".to_string(),
+                    identifier: r.reference_locator.clone(),
+                })
+                .collect(),
+        });
+        link_license(&mut graph, &mut relationships, &package_id, "hasConcludedLicense", &package.license_concluded);
+        link_license(&mut graph, &mut relationships, &package_id, "hasDeclaredLicense", &package.license_declared);
+        elements.push(package_id);
+    }
+
+    for file in &doc.files {
+        let file_id = id(&file.spdxid);
+        graph.push(Spdx3Element::File {
+            spdx_id: file_id.clone(),
+            creation_info: creation_info.clone(),
+            name: file.file_name.clone(),
+            copyright_text: Some(file.copyright_text.clone()).filter(|c| c != "NOASSERTION"),
+            comment: file.comment.clone(),
+        });
+        link_license(&mut graph, &mut relationships, &file_id, "hasConcludedLicense", &file.license_concluded);
+        elements.push(file_id);
+    }
+
+    // Structural relationships; DESCRIBES is expressed through rootElement in 3.0
+    for relationship in doc.relationships.iter().filter(|r| r.relationship_type != "DESCRIBES") {
+        relationships.push((
+            id(&relationship.spdx_element_id),
+            rdf_term(&relationship.relationship_type),
+            id(&relationship.related_spdx_element),
+        ));
+    }
+
+    for (idx, (from, relationship_type, to)) in relationships.into_iter().enumerate() {
+        let relationship_id = id(&format!("SPDXRef-Relationship-{}", idx + 1));
+        graph.push(Spdx3Element::Relationship {
+            spdx_id: relationship_id.clone(),
+            creation_info: creation_info.clone(),
+            from,
+            relationship_type,
+            to: vec![to],
+        });
+        elements.push(relationship_id);
+    }
+    elements.extend(license_ids.into_values());
+
+    let root_package = id("SPDXRef-Package");
+    let sbom_id = id("SPDXRef-Sbom");
+    graph.push(Spdx3Element::Sbom {
+        spdx_id: sbom_id.clone(),
+        creation_info: creation_info.clone(),
+        sbom_type: vec!["analyzed".to_string()],
+        root_element: vec![root_package],
+        element: elements.clone(),
+    });
+
+    let mut document_elements = vec![sbom_id.clone()];
+    document_elements.extend(elements);
+    graph.push(Spdx3Element::SpdxDocument {
+        spdx_id: id(&doc.spdxid),
+        creation_info,
+        name: doc.name.clone(),
+        data_license: format!("https://spdx.org/licenses/{}", doc.data_license),
+        profile_conformance: vec![
+            "core".to_string(),
+            "software".to_string(),
+            "simpleLicensing".to_string(),
+        ],
+        root_element: vec![sbom_id],
+        element: document_elements,
+    });
+
+    Spdx3Document {
+        context: SPDX3_CONTEXT.to_string(),
+        graph,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::spdx::{CreationInfo, File, Package, Relationship};
+
+    #[test]
+    fn test_build_spdx3_document() {
+        let doc = SpdxDocument {
+            spdx_version: "SPDX-2.3".to_string(),
+            data_license: "CC0-1.0".to_string(),
+            spdxid: "SPDXRef-DOCUMENT".to_string(),
+            name: "Legal Scanner Report - repo".to_string(),
+            document_namespace: "https://legalscanner.io/spdx/scan-1".to_string(),
+            creation_info: CreationInfo {
+                created: "2025-01-15T10:00:00Z".to_string(),
+                creators: vec!["Tool: LegalScanner-1.0".to_string()],
+                license_list_version: Some("3.22".to_string()),
+                comment: None,
+            },
+            packages: vec![Package {
+                spdxid: "SPDXRef-Package".to_string(),
+                name: "repo".to_string(),
+                version_info: None,
+                download_location: "https://github.com/acme/repo.git".to_string(),
+                files_analyzed: true,
+                license_concluded: "MIT".to_string(),
+                license_declared: "MIT".to_string(),
+                license_comments: None,
+                copyright_text: "NOASSERTION".to_string(),
+                summary: None,
+                external_refs: Vec::new(),
+            }],
+            files: vec![File {
+                spdxid: "SPDXRef-File-1".to_string(),
+                file_name: "src/lib.rs".to_string(),
+                license_concluded: "MIT".to_string(),
+                license_info_in_files: vec!["MIT".to_string()],
+                copyright_text: "NOASSERTION".to_string(),
+                comment: None,
+            }],
+            relationships: vec![
+                Relationship {
+                    spdx_element_id: "SPDXRef-DOCUMENT".to_string(),
+                    relationship_type: "DESCRIBES".to_string(),
+                    related_spdx_element: "SPDXRef-Package".to_string(),
+                },
+                Relationship {
+                    spdx_element_id: "SPDXRef-Package".to_string(),
+                    relationship_type: "CONTAINS".to_string(),
+                    related_spdx_element: "SPDXRef-File-1".to_string(),
+                },
+            ],
+            has_extracted_licensing_infos: Vec::new(),
+        };
+
+        let spdx3 = build_spdx3_document(&doc);
+        let json = serde_json::to_value(&spdx3).unwrap();
+
+        assert_eq!(json["@context"], SPDX3_CONTEXT);
+        let graph = json["@graph"].as_array().unwrap();
+        let of_type = |t: &str| graph.iter().filter(|e| e["type"] == t).count();
+
+        assert_eq!(of_type("CreationInfo"), 1);
+        assert_eq!(of_type("software_Package"), 1);
+        assert_eq!(of_type("software_File"), 1);
+        // The same expression is shared by the package and the file
+        assert_eq!(of_type("simplelicensing_LicenseExpression"), 1);
+        // concluded + declared on the package, concluded on the file, contains
+        assert_eq!(of_type("Relationship"), 4);
+        assert!(graph
+            .iter()
+            .any(|e| e["type"] == "Relationship" && e["relationshipType"] == "contains"));
+    }
+}
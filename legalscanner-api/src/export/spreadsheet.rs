@@ -0,0 +1,190 @@
+use crate::db::models::scan_result::ScanResult;
+use crate::error::AppError;
+use rust_xlsxwriter::{Format, Workbook};
+
+/// Spreadsheet columns, one row per finding
+pub const COLUMNS: [&str; 9] = [
+    "File",
+    "Type",
+    "License",
+    "SPDX ID",
+    "Confidence",
+    "Severity",
+    "Line",
+    "Detail",
+    "Waiver",
+];
+
+/// A finding flattened to spreadsheet cells
+struct Row<'a> {
+    file_path: &'a str,
+    result_type: &'a str,
+    license: Option<&'a str>,
+    spdx_id: Option<&'a str>,
+    confidence: Option<f32>,
+    severity: Option<&'a str>,
+    line: Option<i32>,
+    detail: Option<String>,
+    waiver_id: Option<&'a str>,
+}
+
+impl<'a> Row<'a> {
+    fn from_result(result: &'a ScanResult) -> Self {
+        let detail = match result.result_type.as_str() {
+            "copyright" => result.copyright_statement.clone(),
+            "package" => result.package_name.as_ref().map(|name| match &result.package_version {
+                Some(version) => format!("{}@{}", name, version),
+                None => name.clone(),
+            }),
+            "license" => result.license_category.clone(),
+            _ => result.raw_data.clone(),
+        };
+
+        Row {
+            file_path: &result.file_path,
+            result_type: &result.result_type,
+            license: result.license_name.as_deref(),
+            spdx_id: result.license_spdx_id.as_deref(),
+            confidence: result.confidence,
+            severity: result.risk_severity.as_deref(),
+            line: result.ecc_line_number,
+            detail,
+            waiver_id: result.waiver_id.as_deref(),
+        }
+    }
+}
+
+/// Serialize findings to CSV
+pub fn write_findings_csv(results: &[ScanResult]) -> Result<Vec<u8>, AppError> {
+    let csv_error = |e: csv::Error| AppError::Internal(format!("Failed to write CSV: {}", e));
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(COLUMNS).map_err(csv_error)?;
+
+    for result in results {
+        let row = Row::from_result(result);
+        writer
+            .write_record([
+                csv_cell(row.file_path),
+                csv_cell(row.result_type),
+                csv_cell(row.license.unwrap_or_default()),
+                csv_cell(row.spdx_id.unwrap_or_default()),
+                row.confidence.map(|c| c.to_string()).unwrap_or_default(),
+                csv_cell(row.severity.unwrap_or_default()),
+                row.line.map(|l| l.to_string()).unwrap_or_default(),
+                csv_cell(row.detail.as_deref().unwrap_or_default()),
+                csv_cell(row.waiver_id.unwrap_or_default()),
+            ])
+            .map_err(csv_error)?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| AppError::Internal(format!("Failed to finalize CSV: {}", e)))
+}
+
+/// Values starting with formula characters are prefixed with `'` so
+/// spreadsheet applications do not evaluate them (CSV injection)
+fn csv_cell(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Serialize findings to an Excel workbook with a frozen, filterable header row
+pub fn write_findings_xlsx(results: &[ScanResult]) -> Result<Vec<u8>, AppError> {
+    let xlsx_error = |e: rust_xlsxwriter::XlsxError| {
+        AppError::Internal(format!("Failed to write Excel workbook: {}", e))
+    };
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name("Findings").map_err(xlsx_error)?;
+
+    let header = Format::new().set_bold();
+    for (col, title) in COLUMNS.iter().enumerate() {
+        worksheet
+            .write_string_with_format(0, col as u16, *title, &header)
+            .map_err(xlsx_error)?;
+    }
+
+    for (idx, result) in results.iter().enumerate() {
+        let row_number = idx as u32 + 1;
+        let row = Row::from_result(result);
+
+        worksheet.write_string(row_number, 0, row.file_path).map_err(xlsx_error)?;
+        worksheet.write_string(row_number, 1, row.result_type).map_err(xlsx_error)?;
+        if let Some(license) = row.license {
+            worksheet.write_string(row_number, 2, license).map_err(xlsx_error)?;
+        }
+        if let Some(spdx_id) = row.spdx_id {
+            worksheet.write_string(row_number, 3, spdx_id).map_err(xlsx_error)?;
+        }
+        if let Some(confidence) = row.confidence {
+            worksheet.write_number(row_number, 4, confidence as f64).map_err(xlsx_error)?;
+        }
+        if let Some(severity) = row.severity {
+            worksheet.write_string(row_number, 5, severity).map_err(xlsx_error)?;
+        }
+        if let Some(line) = row.line {
+            worksheet.write_number(row_number, 6, line as f64).map_err(xlsx_error)?;
+        }
+        if let Some(detail) = &row.detail {
+            worksheet.write_string(row_number, 7, detail).map_err(xlsx_error)?;
+        }
+        if let Some(waiver_id) = row.waiver_id {
+            worksheet.write_string(row_number, 8, waiver_id).map_err(xlsx_error)?;
+        }
+    }
+
+    worksheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+    worksheet
+        .autofilter(0, 0, results.len() as u32, COLUMNS.len() as u16 - 1)
+        .map_err(xlsx_error)?;
+    worksheet.set_column_width(0, 60).map_err(xlsx_error)?;
+    worksheet.set_column_width(7, 60).map_err(xlsx_error)?;
+
+    workbook.save_to_buffer().map_err(xlsx_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(result_type: &str, license: Option<&str>, raw_data: Option<&str>) -> ScanResult {
+        ScanResult {
+            license_name: license.map(str::to_string),
+            license_spdx_id: license.map(str::to_string),
+            confidence: license.map(|_| 0.95),
+            raw_data: raw_data.map(str::to_string),
+            license_category: license.map(|_| "code".to_string()),
+            ..ScanResult::for_test(result_type, "src/lib.rs")
+        }
+    }
+
+    #[test]
+    fn test_write_findings_csv() {
+        let results = vec![
+            result("license", Some("MIT"), None),
+            result("ecc", None, Some("=HYPERLINK(\"x\")")),
+        ];
+        let csv = String::from_utf8(write_findings_csv(&results).unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "File,Type,License,SPDX ID,Confidence,Severity,Line,Detail,Waiver"
+        );
+        assert_eq!(lines[1], "src/lib.rs,license,MIT,MIT,0.95,,,code,");
+        assert!(lines[2].ends_with("\"'=HYPERLINK(\"\"x\"\")\","));
+    }
+
+    #[test]
+    fn test_write_findings_xlsx() {
+        let bytes = write_findings_xlsx(&[result("license", Some("MIT"), None)]).unwrap();
+        // XLSX files are ZIP archives
+        assert_eq!(&bytes[..2], b"PK");
+    }
+}
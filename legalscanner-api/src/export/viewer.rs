@@ -0,0 +1,184 @@
+use crate::api::models::RiskAssessment;
+use crate::db::models::scan::Scan;
+use crate::db::models::scan_result::ScanResult;
+use crate::export::sarif::relative_uri;
+use serde::Serialize;
+
+/// Self-contained page that renders the embedded report, with no external
+/// scripts, styles or fonts so it opens on machines without network access
+const TEMPLATE: &str = include_str!("viewer.html");
+
+/// Replaced in [`TEMPLATE`] by the report as JSON
+const REPORT_PLACEHOLDER: &str = "__LEGALSCANNER_REPORT__";
+
+#[derive(Debug, Serialize)]
+struct ViewerReport<'a> {
+    generated_at: String,
+    scan: ViewerScan<'a>,
+    risk: Option<&'a RiskAssessment>,
+    /// Label and value of each custom field set on the scan
+    custom_fields: &'a [(String, String)],
+    findings: Vec<ViewerFinding<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ViewerScan<'a> {
+    id: &'a str,
+    git_url: &'a str,
+    branch: Option<&'a str>,
+    scan_path: Option<&'a str>,
+    created_at: &'a str,
+    completed_at: Option<&'a str>,
+    approved_at: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct ViewerFinding<'a> {
+    file_path: String,
+    result_type: &'a str,
+    license: Option<&'a str>,
+    spdx_id: Option<&'a str>,
+    severity: Option<&'a str>,
+    check_id: Option<&'a str>,
+    line: Option<i32>,
+    detail: Option<&'a str>,
+    waiver_id: Option<&'a str>,
+}
+
+impl<'a> ViewerFinding<'a> {
+    fn from_result(scan_id: &str, result: &'a ScanResult) -> Self {
+        let detail = match result.result_type.as_str() {
+            "copyright" => result.copyright_statement.as_deref(),
+            "package" => result.package_purl.as_deref(),
+            "license" => result.license_category.as_deref(),
+            _ => result.raw_data.as_deref(),
+        };
+
+        ViewerFinding {
+            file_path: relative_uri(&result.file_path, scan_id),
+            result_type: &result.result_type,
+            license: result.license_name.as_deref(),
+            spdx_id: result.license_spdx_id.as_deref(),
+            severity: result.risk_severity.as_deref(),
+            check_id: result.ecc_check_id.as_deref(),
+            line: result.ecc_line_number,
+            detail,
+            waiver_id: result.waiver_id.as_deref(),
+        }
+    }
+}
+
+/// Render a single HTML file holding the scan report and a small viewer
+/// (summary, license breakdown and a filterable findings table) for offline
+/// review, e.g. on air-gapped legal workstations
+pub fn build_viewer(
+    scan: &Scan,
+    results: &[ScanResult],
+    risk: Option<&RiskAssessment>,
+    custom_fields: &[(String, String)],
+) -> String {
+    let report = ViewerReport {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        scan: ViewerScan {
+            id: &scan.id,
+            git_url: &scan.git_url,
+            branch: scan.branch.as_deref(),
+            scan_path: scan.scan_path.as_deref(),
+            created_at: &scan.created_at,
+            completed_at: scan.completed_at.as_deref(),
+            approved_at: scan.approved_at.as_deref(),
+        },
+        risk,
+        custom_fields,
+        findings: results
+            .iter()
+            .map(|r| ViewerFinding::from_result(&scan.id, r))
+            .collect(),
+    };
+
+    TEMPLATE.replace(REPORT_PLACEHOLDER, &embeddable_json(&report))
+}
+
+/// JSON safe to place inside a `<script>` element: `<` only occurs inside
+/// strings, where `\u003c` keeps e.g. `</script>` in a finding from ending
+/// the element
+fn embeddable_json(report: &ViewerReport) -> String {
+    serde_json::to_string(report)
+        .unwrap_or_else(|_| "{}".to_string())
+        .replace('<', "\\u003c")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan() -> Scan {
+        Scan {
+            id: "scan-1".to_string(),
+            git_url: "https://github.com/acme/app".to_string(),
+            status: "completed".to_string(),
+            error_message: None,
+            created_at: "2025-01-14 10:00:00".to_string(),
+            started_at: None,
+            completed_at: None,
+            created_by_key_id: None,
+            git_token: None,
+            fossology_status: "completed".to_string(),
+            semgrep_status: "completed".to_string(),
+            fossology_started_at: None,
+            fossology_completed_at: None,
+            semgrep_started_at: None,
+            semgrep_completed_at: None,
+            fossology_error: None,
+            semgrep_error: None,
+            risk_score: None,
+            risk_level: None,
+            risk_factors: None,
+            risk_algorithm_version: None,
+            risk_config_version: None,
+            risk_assessed_at: None,
+            semgrep_rulesets: None,
+            quick: false,
+            approved_at: None,
+            reviewed_at: None,
+            warnings: None,
+            parent_scan_id: None,
+            due_diligence: false,
+            callback_url: None,
+            scan_path: None,
+            branch: None,
+            commit_sha: None,
+            legal_hold_at: None,
+            legal_hold_reason: None,
+            submodules: false,
+            submodule_token_encrypted: None,
+            credential_id: None,
+            git_authorship: false,
+            results_purged_at: None,
+            results_archived_at: None,
+            deleted_at: None,
+            policy: None,
+        }
+    }
+
+    #[test]
+    fn test_build_viewer_embeds_report() {
+        let copyright = ScanResult {
+            copyright_statement: Some("(c) Acme </script><script>alert(1)</script>".to_string()),
+            ..ScanResult::for_test("copyright", "/work/scan-1/src/lib.rs")
+        };
+        let html = build_viewer(
+            &scan(),
+            &[copyright],
+            None,
+            &[("Contract".to_string(), "C-42".to_string())],
+        );
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains(REPORT_PLACEHOLDER));
+        assert!(html.contains("\"file_path\":\"src/lib.rs\""));
+        assert!(html.contains("(c) Acme \\u003c/script>\\u003cscript>"));
+        assert!(!html.contains("alert(1)</script>"));
+        assert!(html.contains("\"custom_fields\":[[\"Contract\",\"C-42\"]]"));
+    }
+}
@@ -0,0 +1,426 @@
+//! Policy gate for CI pipelines. Turns a finished scan into a pass/warn/fail
+//! verdict that a pipeline can block a merge on. Rule IDs, outcomes and the
+//! plaintext layout are part of the API contract: new rules may be added,
+//! existing ones are not renamed or made stricter without a version bump.
+
+use crate::api::models::RiskFactor;
+use crate::db::models::{Scan, ScanScreening};
+use crate::policy::PolicyEvaluation;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use utoipa::ToSchema;
+
+/// Bumped when the meaning of an existing rule or field changes
+pub const GATE_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GateOutcome {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl GateOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GateOutcome::Pass => "pass",
+            GateOutcome::Warn => "warn",
+            GateOutcome::Fail => "fail",
+        }
+    }
+}
+
+/// A rule the scan violated
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GateViolation {
+    /// Stable rule ID, e.g. `copyleft_license`
+    #[schema(value_type = String)]
+    pub rule: &'static str,
+    pub outcome: GateOutcome,
+    /// Whether approving the scan waives this violation
+    pub waivable: bool,
+    /// Set once the scan is approved and the violation is waivable
+    pub waived: bool,
+    pub message: String,
+    pub details: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GateReport {
+    pub schema_version: u32,
+    pub scan_id: String,
+    pub verdict: GateOutcome,
+    /// True when the verdict is `fail` and approving the scan would lift it
+    pub waivable: bool,
+    pub approved: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk_score: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk_level: Option<String>,
+    pub violations: Vec<GateViolation>,
+}
+
+/// Evaluate the gate rules for a completed or failed scan. Violations of the
+/// scan's license policy are added under the `license_policy` rule.
+pub fn evaluate(
+    scan: &Scan,
+    screening: Option<&ScanScreening>,
+    policy: Option<&PolicyEvaluation>,
+) -> GateReport {
+    let approved = scan.approved_at.is_some();
+    let mut violations = Vec::new();
+
+    if scan.status == "failed" {
+        violations.push(violation(
+            "scan_failed",
+            GateOutcome::Fail,
+            false,
+            format!(
+                "Scan failed: {}",
+                scan.error_message.as_deref().unwrap_or("unknown error")
+            ),
+            Vec::new(),
+        ));
+    }
+
+    let factors: Vec<RiskFactor> = scan
+        .risk_factors
+        .as_deref()
+        .and_then(|f| serde_json::from_str(f).ok())
+        .unwrap_or_default();
+
+    for factor in &factors {
+        let rule = match factor.category.as_str() {
+            "malware" => Some(("malware", GateOutcome::Fail, false)),
+            "copyleft_license" => Some(("copyleft_license", GateOutcome::Fail, true)),
+            "copyleft_license_exception" => {
+                Some(("copyleft_license_exception", GateOutcome::Warn, true))
+            }
+            "ecc_critical_high" => ecc_rule(screening),
+            "model_license" => Some(("model_license", GateOutcome::Warn, true)),
+            "unknown_license" => Some(("unknown_license", GateOutcome::Warn, true)),
+            "missing_spdx_id" => Some(("missing_spdx_id", GateOutcome::Warn, true)),
+            _ => None,
+        };
+        if let Some((rule, outcome, waivable)) = rule {
+            violations.push(violation(
+                rule,
+                outcome,
+                waivable,
+                factor.description.clone(),
+                factor.details.clone(),
+            ));
+        }
+    }
+
+    // Approving a scan does not lift organization rules, only the licenses
+    // the policy wants reviewed
+    if let Some(policy) = policy {
+        for policy_violation in &policy.violations {
+            violations.push(violation(
+                "license_policy",
+                policy_violation.outcome,
+                policy_violation.outcome != GateOutcome::Fail,
+                format!("Policy {}: {}", policy.policy, policy_violation.message),
+                policy_violation.files.clone(),
+            ));
+        }
+    }
+
+    let warnings = scan.warning_list();
+    if !warnings.is_empty() {
+        violations.push(violation(
+            "incomplete_scan",
+            GateOutcome::Warn,
+            true,
+            "Results may be incomplete".to_string(),
+            warnings,
+        ));
+    }
+
+    if scan.quick {
+        violations.push(violation(
+            "preliminary_scan",
+            GateOutcome::Warn,
+            true,
+            "Quick scan without Fossology, license findings are preliminary".to_string(),
+            Vec::new(),
+        ));
+    }
+
+    if approved {
+        for violation in violations.iter_mut().filter(|v| v.waivable) {
+            violation.waived = true;
+        }
+    }
+
+    let verdict = violations
+        .iter()
+        .filter(|v| !v.waived)
+        .map(|v| v.outcome)
+        .max()
+        .unwrap_or(GateOutcome::Pass);
+
+    let failures: Vec<&GateViolation> = violations
+        .iter()
+        .filter(|v| v.outcome == GateOutcome::Fail && !v.waived)
+        .collect();
+    let waivable = verdict == GateOutcome::Fail && failures.iter().all(|v| v.waivable);
+
+    GateReport {
+        schema_version: GATE_SCHEMA_VERSION,
+        scan_id: scan.id.clone(),
+        verdict,
+        waivable,
+        approved,
+        risk_score: scan.risk_score,
+        risk_level: scan.risk_level.clone(),
+        violations,
+    }
+}
+
+/// Critical/high ECC findings fail until export control screening clears
+/// them. Without a screening system they only warn.
+fn ecc_rule(screening: Option<&ScanScreening>) -> Option<(&'static str, GateOutcome, bool)> {
+    match screening.map(|s| s.status.as_str()) {
+        Some("cleared") => None,
+        Some(_) => Some(("ecc_critical_high", GateOutcome::Fail, false)),
+        None => Some(("ecc_critical_high", GateOutcome::Warn, true)),
+    }
+}
+
+fn violation(
+    rule: &'static str,
+    outcome: GateOutcome,
+    waivable: bool,
+    message: String,
+    details: Vec<String>,
+) -> GateViolation {
+    GateViolation {
+        rule,
+        outcome,
+        waivable,
+        waived: false,
+        message,
+        details,
+    }
+}
+
+/// Plaintext rendering. The first line is the verdict in upper case followed
+/// by the scan ID, then one line per violation:
+/// `<outcome> <rule> [waivable|waived|blocking] <message>`.
+pub fn render_text(report: &GateReport) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{} {}",
+        report.verdict.as_str().to_uppercase(),
+        report.scan_id
+    );
+
+    for violation in &report.violations {
+        let flag = if violation.waived {
+            "waived"
+        } else if violation.waivable {
+            "waivable"
+        } else {
+            "blocking"
+        };
+        let _ = writeln!(
+            out,
+            "{} {} {} {}",
+            violation.outcome.as_str(),
+            violation.rule,
+            flag,
+            violation.message
+        );
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::PolicyViolation;
+
+    fn factor(category: &str) -> RiskFactor {
+        RiskFactor {
+            category: category.to_string(),
+            severity: "high".to_string(),
+            description: format!("{} found", category),
+            affected_count: 1,
+            details: vec!["GPL-3.0 (1 files)".to_string()],
+        }
+    }
+
+    fn scan(factors: &[RiskFactor]) -> Scan {
+        Scan {
+            id: "scan-1".to_string(),
+            git_url: "https://github.com/org/repo".to_string(),
+            status: "completed".to_string(),
+            error_message: None,
+            created_at: "2025-01-01 00:00:00".to_string(),
+            started_at: None,
+            completed_at: None,
+            created_by_key_id: None,
+            git_token: None,
+            fossology_status: "completed".to_string(),
+            semgrep_status: "completed".to_string(),
+            fossology_started_at: None,
+            fossology_completed_at: None,
+            semgrep_started_at: None,
+            semgrep_completed_at: None,
+            fossology_error: None,
+            semgrep_error: None,
+            risk_score: Some(40),
+            risk_level: Some("medium".to_string()),
+            risk_factors: Some(serde_json::to_string(factors).unwrap()),
+            risk_algorithm_version: None,
+            risk_config_version: None,
+            risk_assessed_at: None,
+            semgrep_rulesets: None,
+            quick: false,
+            approved_at: None,
+            reviewed_at: None,
+            warnings: None,
+            parent_scan_id: None,
+            due_diligence: false,
+            callback_url: None,
+            scan_path: None,
+            branch: None,
+            commit_sha: None,
+            legal_hold_at: None,
+            legal_hold_reason: None,
+            submodules: false,
+            submodule_token_encrypted: None,
+            credential_id: None,
+            git_authorship: false,
+            results_purged_at: None,
+            results_archived_at: None,
+            deleted_at: None,
+            policy: None,
+        }
+    }
+
+    fn screening(status: &str) -> ScanScreening {
+        ScanScreening {
+            id: "screening-1".to_string(),
+            scan_id: "scan-1".to_string(),
+            status: status.to_string(),
+            reference: None,
+            response_code: None,
+            response_body: None,
+            requested_at: "2025-01-01 00:00:00".to_string(),
+            updated_at: "2025-01-01 00:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_clean_scan_passes() {
+        let report = evaluate(&scan(&[factor("license_diversity")]), None, None);
+        assert_eq!(report.verdict, GateOutcome::Pass);
+        assert!(report.violations.is_empty());
+        assert!(!report.waivable);
+        assert_eq!(render_text(&report), "PASS scan-1\n");
+    }
+
+    #[test]
+    fn test_copyleft_fails_until_approved() {
+        let mut scan = scan(&[factor("copyleft_license"), factor("unknown_license")]);
+        let report = evaluate(&scan, None, None);
+        assert_eq!(report.verdict, GateOutcome::Fail);
+        assert!(report.waivable);
+        assert_eq!(
+            render_text(&report).lines().nth(1),
+            Some("fail copyleft_license waivable copyleft_license found")
+        );
+
+        scan.approved_at = Some("2025-01-01 00:00:00".to_string());
+        let report = evaluate(&scan, None, None);
+        assert_eq!(report.verdict, GateOutcome::Pass);
+        assert!(report.violations.iter().all(|v| v.waived));
+    }
+
+    #[test]
+    fn test_copyleft_with_exception_warns() {
+        let report = evaluate(&scan(&[factor("copyleft_license_exception")]), None, None);
+        assert_eq!(report.verdict, GateOutcome::Warn);
+        assert!(report.violations[0].waivable);
+    }
+
+    #[test]
+    fn test_malware_is_not_waivable() {
+        let mut scan = scan(&[factor("malware"), factor("copyleft_license")]);
+        scan.approved_at = Some("2025-01-01 00:00:00".to_string());
+        let report = evaluate(&scan, None, None);
+        assert_eq!(report.verdict, GateOutcome::Fail);
+        assert!(!report.waivable);
+    }
+
+    #[test]
+    fn test_ecc_follows_screening() {
+        let scan = scan(&[factor("ecc_critical_high")]);
+        assert_eq!(evaluate(&scan, None, None).verdict, GateOutcome::Warn);
+        assert_eq!(
+            evaluate(&scan, Some(&screening("pending")), None).verdict,
+            GateOutcome::Fail
+        );
+        assert_eq!(
+            evaluate(&scan, Some(&screening("cleared")), None).verdict,
+            GateOutcome::Pass
+        );
+    }
+
+    #[test]
+    fn test_failed_and_quick_scans() {
+        let mut failed = scan(&[]);
+        failed.status = "failed".to_string();
+        failed.risk_factors = None;
+        let report = evaluate(&failed, None, None);
+        assert_eq!(report.verdict, GateOutcome::Fail);
+        assert_eq!(report.violations[0].rule, "scan_failed");
+
+        let mut quick = scan(&[]);
+        quick.quick = true;
+        let report = evaluate(&quick, None, None);
+        assert_eq!(report.verdict, GateOutcome::Warn);
+        assert_eq!(report.violations[0].rule, "preliminary_scan");
+    }
+
+    #[test]
+    fn test_policy_failures_are_not_waivable() {
+        let policy_violation = |rule: &str, outcome| PolicyViolation {
+            rule: rule.to_string(),
+            outcome,
+            license: Some("GPL-3.0-only".to_string()),
+            message: format!("{} GPL-3.0-only", rule),
+            files: vec!["vendor/gpl.c".to_string()],
+        };
+        let mut evaluation = PolicyEvaluation {
+            scan_id: "scan-1".to_string(),
+            policy: "distribution".to_string(),
+            outcome: GateOutcome::Fail,
+            passed: false,
+            violations: vec![
+                policy_violation("denied_license", GateOutcome::Fail),
+                policy_violation("review_license", GateOutcome::Warn),
+            ],
+            evaluated_at: "2025-01-01 00:00:00".to_string(),
+        };
+        let mut scan = scan(&[]);
+        scan.approved_at = Some("2025-01-01 00:00:00".to_string());
+
+        let report = evaluate(&scan, None, Some(&evaluation));
+        assert_eq!(report.verdict, GateOutcome::Fail);
+        assert!(!report.waivable);
+        assert_eq!(report.violations[0].rule, "license_policy");
+        assert_eq!(report.violations[0].details, vec!["vendor/gpl.c"]);
+        assert!(report.violations[1].waived);
+
+        evaluation.violations.remove(0);
+        let report = evaluate(&scan, None, Some(&evaluation));
+        assert_eq!(report.verdict, GateOutcome::Pass);
+    }
+}
@@ -0,0 +1,106 @@
+use super::provider::Provider;
+use git2::{Cred, CredentialType, RemoteCallbacks};
+use std::fmt;
+
+/// How to authenticate to a git remote
+#[derive(Clone, PartialEq)]
+pub enum GitAuth {
+    /// Token sent as the HTTPS user name, which GitHub accepts for personal
+    /// access tokens
+    Token(String),
+    /// HTTPS user name and password or token
+    UserPass { username: String, password: String },
+    /// SSH private key; the user comes from the URL (`git@…`) when unset
+    SshKey {
+        username: Option<String>,
+        private_key: String,
+        passphrase: Option<String>,
+    },
+}
+
+// Secrets must not end up in logs
+impl fmt::Debug for GitAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitAuth::Token(_) => f.write_str("Token(..)"),
+            GitAuth::UserPass { username, .. } => write!(f, "UserPass({}, ..)", username),
+            GitAuth::SshKey { username, .. } => write!(f, "SshKey({:?}, ..)", username),
+        }
+    }
+}
+
+impl GitAuth {
+    /// A raw token, signing in the way the provider hosting `url` expects
+    pub fn for_token(url: &str, token: String) -> GitAuth {
+        Provider::detect(url).token_auth(token)
+    }
+
+    /// `auth`, else a token from the `GIT_TOKEN` environment variable
+    pub fn or_env(auth: Option<&GitAuth>) -> Option<GitAuth> {
+        auth.cloned()
+            .or_else(|| std::env::var("GIT_TOKEN").ok().map(GitAuth::Token))
+    }
+
+    /// Token for the hosting provider's REST API, if this is one
+    pub fn api_token(&self) -> Option<&str> {
+        match self {
+            GitAuth::Token(token) => Some(token),
+            GitAuth::UserPass { password, .. } => Some(password),
+            GitAuth::SshKey { .. } => None,
+        }
+    }
+
+    /// Callbacks answering the remote's credential requests
+    pub fn callbacks(&self) -> RemoteCallbacks<'static> {
+        let auth = self.clone();
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(move |_url, username_from_url, allowed_types| {
+            tracing::debug!("Git credentials callback invoked");
+            auth.credentials(username_from_url, allowed_types)
+        });
+        callbacks
+    }
+
+    fn credentials(
+        &self,
+        username_from_url: Option<&str>,
+        allowed_types: CredentialType,
+    ) -> Result<Cred, git2::Error> {
+        match self {
+            GitAuth::Token(token) => Cred::userpass_plaintext(token, ""),
+            GitAuth::UserPass { username, password } => {
+                Cred::userpass_plaintext(username, password)
+            }
+            GitAuth::SshKey {
+                username,
+                private_key,
+                passphrase,
+            } => {
+                let username = username.as_deref().or(username_from_url).unwrap_or("git");
+                // SSH first asks for the user when the URL has none
+                if allowed_types.contains(CredentialType::USERNAME) {
+                    return Cred::username(username);
+                }
+                Cred::ssh_key_from_memory(username, None, private_key, passphrase.as_deref())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_hides_secrets() {
+        let auth = GitAuth::UserPass {
+            username: "oauth2".to_string(),
+            password: "glpat-secret".to_string(),
+        };
+        assert_eq!(format!("{:?}", auth), "UserPass(oauth2, ..)");
+        assert_eq!(
+            format!("{:?}", GitAuth::Token("ghp_secret".to_string())),
+            "Token(..)"
+        );
+    }
+}
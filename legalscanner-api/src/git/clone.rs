@@ -1,89 +1,381 @@
-use git2::{Repository, RemoteCallbacks, FetchOptions, build::RepoBuilder};
+use super::auth::GitAuth;
+use crate::progress::ProgressReporter;
+use git2::{FetchOptions, ObjectType, Progress, Repository, build::{CheckoutBuilder, RepoBuilder}};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Progress messages are sent at most this often, receiving a pack calls
+/// back for every few kilobytes
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Branch and commit a clone checks out, the head of the default branch
+/// when neither is set
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Revision<'a> {
+    pub branch: Option<&'a str>,
+    /// Checked out on a detached HEAD, e.g. the commit of a push even if
+    /// the branch moved on since
+    pub commit: Option<&'a str>,
+}
+
+/// What `clone_with_options` fetches and checks out
+#[derive(Default)]
+struct CloneOptions {
+    depth: Option<i32>,
+    sparse_paths: Vec<String>,
+    branch: Option<String>,
+    commit: Option<String>,
+}
+
+impl CloneOptions {
+    fn at(revision: Revision<'_>) -> Self {
+        CloneOptions {
+            branch: revision.branch.map(str::to_string),
+            commit: revision.commit.map(str::to_string),
+            ..Default::default()
+        }
+    }
+}
 
 /// Clone a Git repository to a destination path
 /// Supports both public and private repositories
-/// Accepts optional token parameter, falls back to GIT_TOKEN environment variable
-pub async fn clone_repository(url: &str, destination: &Path, token: Option<&str>) -> Result<(), git2::Error> {
-    // Validate URL first
-    validate_git_url(url).map_err(|e| git2::Error::from_str(&e))?;
+/// Accepts optional credentials, falls back to GIT_TOKEN environment variable
+/// Transfer progress goes to `progress`; a clone still running after
+/// `timeout` is abandoned
+pub async fn clone_repository(
+    url: &str,
+    destination: &Path,
+    auth: Option<&GitAuth>,
+    revision: Revision<'_>,
+    progress: &ProgressReporter,
+    timeout: Option<Duration>,
+) -> Result<(), git2::Error> {
+    let options = CloneOptions::at(revision);
+    clone_with_options(url, destination, auth, options, progress, timeout).await
+}
+
+/// Clone a Git repository, optionally truncating history to `depth` commits
+/// A shallow clone (`Some(1)`) is enough when only the current tree is inspected
+pub async fn clone_repository_with_depth(
+    url: &str,
+    destination: &Path,
+    auth: Option<&GitAuth>,
+    depth: Option<i32>,
+    progress: &ProgressReporter,
+    timeout: Option<Duration>,
+) -> Result<(), git2::Error> {
+    let options = CloneOptions {
+        depth,
+        ..Default::default()
+    };
+    clone_with_options(url, destination, auth, options, progress, timeout).await
+}
 
+/// Clone a Git repository but only check out `paths` (directories or files,
+/// relative to the root), e.g. one service of a monorepo. The history is
+/// still fetched, the rest of the tree is never written to disk.
+pub async fn clone_repository_sparse(
+    url: &str,
+    destination: &Path,
+    auth: Option<&GitAuth>,
+    revision: Revision<'_>,
+    paths: &[&str],
+    progress: &ProgressReporter,
+    timeout: Option<Duration>,
+) -> Result<(), git2::Error> {
+    let options = CloneOptions {
+        sparse_paths: paths.iter().map(|p| p.to_string()).collect(),
+        ..CloneOptions::at(revision)
+    };
+    clone_with_options(url, destination, auth, options, progress, timeout).await
+}
+
+async fn clone_with_options(
+    url: &str,
+    destination: &Path,
+    auth: Option<&GitAuth>,
+    options: CloneOptions,
+    progress: &ProgressReporter,
+    timeout: Option<Duration>,
+) -> Result<(), git2::Error> {
     // Use tokio::task::spawn_blocking for blocking git2 operations
     let url = url.to_string();
     let destination = destination.to_path_buf();
-    let token = token.map(|t| t.to_string());
+    let progress = progress.clone();
+
+    // Use provided credentials or fall back to environment variable
+    let auth = GitAuth::or_env(auth);
 
-    tokio::task::spawn_blocking(move || {
+    // Set on timeout, the transfer aborts at its next callback
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let cancel = cancelled.clone();
+
+    let clone = tokio::task::spawn_blocking(move || {
         tracing::info!("Cloning repository {} to {:?}", url, destination);
 
-        // Use provided token or fall back to environment variable
-        let git_token = token.or_else(|| std::env::var("GIT_TOKEN").ok());
-
-        if let Some(token) = git_token {
-            tracing::info!("Using authentication token for git clone");
-
-            // Setup authentication callbacks
-            // For GitHub PATs, use the token as username with empty password
-            // This is the correct authentication method for HTTPS GitHub clones with PAT
-            let mut callbacks = RemoteCallbacks::new();
-            callbacks.credentials(move |_url, _username_from_url, _allowed_types| {
-                tracing::debug!("Git credentials callback invoked");
-                git2::Cred::userpass_plaintext(&token, "")
-            });
-
-            // Setup fetch options with callbacks
-            let mut fetch_options = FetchOptions::new();
-            fetch_options.remote_callbacks(callbacks);
-
-            // Clone with authentication
-            let mut builder = RepoBuilder::new();
-            builder.fetch_options(fetch_options);
-            builder.clone(&url, &destination)?;
+        let mut callbacks = if let Some(auth) = auth {
+            tracing::info!("Using authentication for git clone");
+
+            // GitHub PATs are sent as the username with an empty password;
+            // credential profiles pick the scheme of their provider
+            auth.callbacks()
         } else {
             tracing::info!("No GIT_TOKEN found, attempting public clone");
-            // For public repositories, use simple clone
-            Repository::clone(&url, &destination)?;
+            git2::RemoteCallbacks::new()
+        };
+        let mut last_report: Option<Instant> = None;
+        callbacks.transfer_progress(move |stats| {
+            if cancelled.load(Ordering::Relaxed) {
+                return false;
+            }
+            if last_report.is_none_or(|at| at.elapsed() >= PROGRESS_INTERVAL) {
+                last_report = Some(Instant::now());
+                let (message, percent) = describe_progress(&stats);
+                progress.percent(&message, percent);
+            }
+            true
+        });
+
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+        if let Some(depth) = options.depth {
+            tracing::info!("Shallow clone (depth {})", depth);
+            fetch_options.depth(depth);
+        }
+
+        let mut builder = RepoBuilder::new();
+        builder.fetch_options(fetch_options);
+        if let Some(branch) = &options.branch {
+            tracing::info!("Checking out branch {}", branch);
+            builder.branch(branch);
+        }
+        if !options.sparse_paths.is_empty() {
+            tracing::info!("Sparse checkout of {}", options.sparse_paths.join(", "));
+            builder.with_checkout(sparse_checkout(&options.sparse_paths));
+        }
+        let repo = builder.clone(&url, &destination)?;
+        if let Some(commit) = &options.commit {
+            check_out_commit(&repo, commit, &options.sparse_paths)?;
         }
 
         tracing::info!("Repository cloned successfully");
         Ok(())
-    })
-    .await
-    .map_err(|e| git2::Error::from_str(&e.to_string()))?
-}
+    });
 
-/// Validate a Git URL format
-pub fn validate_git_url(url: &str) -> Result<(), String> {
-    if url.is_empty() {
-        return Err("Git URL cannot be empty".to_string());
+    let Some(timeout) = timeout else {
+        return clone
+            .await
+            .map_err(|e| git2::Error::from_str(&e.to_string()))?;
+    };
+    match tokio::time::timeout(timeout, clone).await {
+        Ok(result) => result.map_err(|e| git2::Error::from_str(&e.to_string()))?,
+        Err(_) => {
+            // A stalled connection makes no callbacks, its thread is left to
+            // the OS's socket timeout
+            cancel.store(true, Ordering::Relaxed);
+            Err(git2::Error::from_str(&format!(
+                "Clone did not finish within {} seconds",
+                timeout.as_secs()
+            )))
+        }
     }
+}
 
-    // Check for common Git URL patterns
-    let valid_prefixes = ["http://", "https://", "git://", "ssh://", "git@"];
+/// Checkout of `paths` only, or of the whole tree without any
+fn sparse_checkout(paths: &[String]) -> CheckoutBuilder<'static> {
+    let mut checkout = CheckoutBuilder::new();
+    for path in paths {
+        checkout.path(path);
+    }
+    checkout
+}
 
-    let is_valid = valid_prefixes.iter().any(|prefix| url.starts_with(prefix));
+/// Detach HEAD at `commit` of the cloned branch
+fn check_out_commit(
+    repo: &Repository,
+    commit: &str,
+    sparse_paths: &[String],
+) -> Result<(), git2::Error> {
+    tracing::info!("Checking out commit {}", commit);
+    let object = repo
+        .revparse_single(commit)
+        .and_then(|o| o.peel(ObjectType::Commit))
+        .map_err(|_| {
+            git2::Error::from_str(&format!("Commit {} not found in the repository", commit))
+        })?;
+    let mut checkout = sparse_checkout(sparse_paths);
+    checkout.force();
+    repo.checkout_tree(&object, Some(&mut checkout))?;
+    repo.set_head_detached(object.id())
+}
 
-    if !is_valid {
-        return Err(format!(
-            "Invalid Git URL format. Must start with one of: {}",
-            valid_prefixes.join(", ")
-        ));
+/// Progress message and percentage: objects are received, then deltas
+/// resolved
+fn describe_progress(stats: &Progress<'_>) -> (String, u8) {
+    let mib = stats.received_bytes() as f64 / (1024.0 * 1024.0);
+    if stats.total_deltas() > 0 && stats.received_objects() == stats.total_objects() {
+        let message = format!(
+            "Resolving deltas: {}/{}",
+            stats.indexed_deltas(),
+            stats.total_deltas()
+        );
+        (message, percent(stats.indexed_deltas(), stats.total_deltas()))
+    } else {
+        let message = format!(
+            "Receiving objects: {}/{} ({:.1} MiB)",
+            stats.received_objects(),
+            stats.total_objects(),
+            mib
+        );
+        (message, percent(stats.received_objects(), stats.total_objects()))
     }
+}
 
-    Ok(())
+fn percent(done: usize, total: usize) -> u8 {
+    if total == 0 {
+        return 0;
+    }
+    (done * 100 / total).min(100) as u8
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_sparse_clone_checks_out_only_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = crate::testing::init_fixture_repo(
+            &dir.path().join("fixture"),
+            &[
+                ("LICENSE", "MIT\n"),
+                ("services/payments/main.rs", "fn main() {}\n"),
+                ("services/ledger/main.rs", "fn main() {}\n"),
+            ],
+        );
+
+        let destination = dir.path().join("clone");
+        clone_repository_sparse(
+            &url,
+            &destination,
+            None,
+            Revision::default(),
+            &["services/payments"],
+            &ProgressReporter::disabled(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(destination.join("services/payments/main.rs").is_file());
+        assert!(!destination.join("services/ledger").exists());
+        assert!(!destination.join("LICENSE").exists());
+        assert!(destination.join(".git").is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_clone_checks_out_branch_and_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = dir.path().join("fixture");
+        let url = crate::testing::init_fixture_repo(&fixture, &[("LICENSE", "MIT\n")]);
+        let repo = Repository::open(&fixture).unwrap();
+        let default_branch = repo.head().unwrap().name().unwrap().to_string();
+        let initial = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &initial, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        crate::testing::commit_fixture_changes(&fixture, &[("src/a.rs", "")], &[], "Add a");
+        let pushed = repo.head().unwrap().target().unwrap().to_string();
+        crate::testing::commit_fixture_changes(&fixture, &[("src/b.rs", "")], &[], "Add b");
+        repo.set_head(&default_branch).unwrap();
+
+        let clone = |name: &str, revision| {
+            let destination = dir.path().join(name);
+            let url = url.clone();
+            async move {
+                let progress = ProgressReporter::disabled();
+                clone_repository(&url, &destination, None, revision, &progress, None)
+                    .await
+                    .unwrap();
+                destination
+            }
+        };
+
+        let default = clone("default", Revision::default()).await;
+        assert!(!default.join("src").exists());
+
+        let revision = Revision {
+            branch: Some("feature"),
+            commit: None,
+        };
+        let branch = clone("branch", revision).await;
+        assert!(branch.join("src/b.rs").is_file());
+
+        let revision = Revision {
+            branch: Some("feature"),
+            commit: Some(pushed.as_str()),
+        };
+        let commit = clone("commit", revision).await;
+        assert!(commit.join("src/a.rs").is_file());
+        assert!(!commit.join("src/b.rs").exists());
+        let checkout = Repository::open(&commit).unwrap();
+        assert!(checkout.head_detached().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_clone_reports_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = crate::testing::init_fixture_repo(
+            &dir.path().join("fixture"),
+            &[("LICENSE", "MIT\n"), ("src/main.rs", "fn main() {}\n")],
+        );
+        let hub = crate::progress::ProgressHub::new();
+        let mut updates = hub.subscribe();
+
+        let destination = dir.path().join("clone");
+        clone_repository(
+            &url,
+            &destination,
+            None,
+            Revision::default(),
+            &hub.reporter("scan-1", "git"),
+            Some(Duration::from_secs(60)),
+        )
+        .await
+        .unwrap();
+
+        let update = updates.try_recv().unwrap();
+        assert_eq!(update.scanner.as_deref(), Some("git"));
+        assert!(update.message.unwrap().starts_with("Receiving objects"));
+        assert!(destination.join("LICENSE").is_file());
+    }
+
+    #[tokio::test]
+    async fn test_clone_times_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = crate::testing::init_fixture_repo(
+            &dir.path().join("fixture"),
+            &[("LICENSE", "MIT\n")],
+        );
+
+        let error = clone_repository(
+            &url,
+            &dir.path().join("clone"),
+            None,
+            Revision::default(),
+            &ProgressReporter::disabled(),
+            Some(Duration::ZERO),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.message(), "Clone did not finish within 0 seconds");
+    }
+
     #[test]
-    fn test_validate_git_url() {
-        assert!(validate_git_url("https://github.com/user/repo.git").is_ok());
-        assert!(validate_git_url("http://github.com/user/repo.git").is_ok());
-        assert!(validate_git_url("git://github.com/user/repo.git").is_ok());
-        assert!(validate_git_url("git@github.com:user/repo.git").is_ok());
-        assert!(validate_git_url("").is_err());
-        assert!(validate_git_url("not-a-git-url").is_err());
+    fn test_progress_percent() {
+        assert_eq!(percent(0, 0), 0);
+        assert_eq!(percent(50, 200), 25);
+        assert_eq!(percent(200, 200), 100);
     }
 }
@@ -0,0 +1,84 @@
+use git2::Repository;
+use std::path::Path;
+
+/// Identifying metadata of a cloned repository
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoInfo {
+    /// Repository name taken from the `origin` remote URL
+    pub name: Option<String>,
+    /// Checked-out branch, `None` for a detached HEAD
+    pub branch: Option<String>,
+    /// Full SHA of the HEAD commit
+    pub commit_sha: Option<String>,
+}
+
+impl RepoInfo {
+    /// Abbreviated commit SHA (7 characters, as `git log --oneline` shows)
+    pub fn short_sha(&self) -> Option<&str> {
+        self.commit_sha.as_deref().map(|sha| &sha[..sha.len().min(7)])
+    }
+}
+
+/// Read repository metadata from a checkout. Missing pieces are left as `None`.
+pub async fn read_repo_info(path: &Path) -> RepoInfo {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let Ok(repo) = Repository::open(&path) else {
+            return RepoInfo::default();
+        };
+
+        let head = repo.head().ok();
+        let branch = head
+            .as_ref()
+            .filter(|h| h.is_branch())
+            .and_then(|h| h.shorthand().map(str::to_string));
+        let commit_sha = head
+            .as_ref()
+            .and_then(|h| h.peel_to_commit().ok())
+            .map(|c| c.id().to_string());
+        let name = repo
+            .find_remote("origin")
+            .ok()
+            .and_then(|r| r.url().map(repo_name_from_url));
+
+        RepoInfo {
+            name,
+            branch,
+            commit_sha,
+        }
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// `https://github.com/user/repo.git` -> `repo`
+pub fn repo_name_from_url(url: &str) -> String {
+    url.trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or(url)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_name_from_url() {
+        assert_eq!(repo_name_from_url("https://github.com/user/repo.git"), "repo");
+        assert_eq!(repo_name_from_url("git@github.com:user/repo.git"), "repo");
+        assert_eq!(repo_name_from_url("https://gitlab.com/group/sub/project/"), "project");
+    }
+
+    #[test]
+    fn test_short_sha() {
+        let info = RepoInfo {
+            commit_sha: Some("0123456789abcdef".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(info.short_sha(), Some("0123456"));
+        assert_eq!(RepoInfo::default().short_sha(), None);
+    }
+}
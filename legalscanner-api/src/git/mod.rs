@@ -1,5 +1,15 @@
+pub mod auth;
 pub mod clone;
+pub mod info;
+pub mod provider;
+pub mod remote;
+pub mod submodules;
 pub mod workspace;
 
-pub use clone::{clone_repository, validate_git_url};
+pub use auth::GitAuth;
+pub use clone::{clone_repository, clone_repository_sparse, clone_repository_with_depth, Revision};
+pub use info::{read_repo_info, RepoInfo};
+pub use provider::{normalize_git_url, validate_git_url, Provider, DEFAULT_GIT_URL_SCHEMES};
+pub use remote::check_remote;
+pub use submodules::{update_submodules, SubmoduleCheckout};
 pub use workspace::Workspace;
@@ -0,0 +1,136 @@
+use super::auth::GitAuth;
+use git2::{Direction, Remote};
+use std::time::Duration;
+
+/// Check that a remote can be reached before a scan of it is queued: its
+/// host resolves and, except over SSH, `ls-remote` answers and lists
+/// `branch` when one is requested. Returns why the remote is unusable.
+/// SSH remotes are only resolved.
+pub async fn check_remote(
+    url: &str,
+    branch: Option<&str>,
+    auth: Option<&GitAuth>,
+    timeout: Duration,
+) -> Result<(), String> {
+    if let Some(host) = remote_host(url) {
+        let lookup = tokio::net::lookup_host((host.as_str(), 0));
+        match tokio::time::timeout(timeout, lookup).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => return Err(format!("Could not resolve host {}: {}", host, e)),
+            Err(_) => return Err(format!("Resolving host {} timed out", host)),
+        }
+    }
+
+    if url.starts_with("ssh://") || url.starts_with("git@") {
+        return Ok(());
+    }
+
+    let url = url.to_string();
+    let branch = branch.map(str::to_string);
+    let auth = GitAuth::or_env(auth);
+    let list = tokio::task::spawn_blocking(move || list_heads(&url, auth.as_ref()));
+
+    // A hung connection keeps its blocking thread until the OS gives up, the
+    // request does not wait for it
+    let heads = match tokio::time::timeout(timeout, list).await {
+        Ok(Ok(result)) => {
+            result.map_err(|e| format!("Repository is not reachable: {}", e.message()))?
+        }
+        Ok(Err(e)) => return Err(e.to_string()),
+        Err(_) => {
+            return Err(format!(
+                "Repository did not answer within {} seconds",
+                timeout.as_secs()
+            ))
+        }
+    };
+
+    if let Some(branch) = branch {
+        let reference = format!("refs/heads/{}", branch);
+        if !heads.contains(&reference) {
+            return Err(format!("Branch {} does not exist", branch));
+        }
+    }
+    Ok(())
+}
+
+/// Reference names advertised by the remote
+fn list_heads(url: &str, auth: Option<&GitAuth>) -> Result<Vec<String>, git2::Error> {
+    let mut remote = Remote::create_detached(url)?;
+    let callbacks = auth.map(GitAuth::callbacks).unwrap_or_default();
+    let connection = remote.connect_auth(Direction::Fetch, Some(callbacks), None)?;
+    let heads = connection
+        .list()?
+        .iter()
+        .map(|head| head.name().to_string())
+        .collect();
+    Ok(heads)
+}
+
+/// Host name of a network remote, `None` for local (`file://`) remotes
+fn remote_host(url: &str) -> Option<String> {
+    let rest = if let Some(rest) = url.strip_prefix("git@") {
+        // scp-like syntax: git@host:owner/repo
+        return rest.split(':').next().map(str::to_string);
+    } else {
+        ["https://", "http://", "git://", "ssh://"]
+            .iter()
+            .find_map(|scheme| url.strip_prefix(scheme))?
+    };
+
+    let authority = rest.split('/').next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = if host.starts_with('[') {
+        // IPv6 literal
+        host.split(']').next()?.trim_start_matches('[')
+    } else {
+        host.split(':').next()?
+    };
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_host() {
+        assert_eq!(
+            remote_host("https://github.com/acme/app.git").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(
+            remote_host("https://user:pw@git.example.com:8443/app").as_deref(),
+            Some("git.example.com")
+        );
+        assert_eq!(
+            remote_host("git@gitlab.com:acme/app.git").as_deref(),
+            Some("gitlab.com")
+        );
+        assert_eq!(remote_host("ssh://[::1]:22/app").as_deref(), Some("::1"));
+        assert_eq!(remote_host("file:///srv/git/app"), None);
+    }
+
+    #[tokio::test]
+    async fn test_check_remote_reports_missing_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = crate::testing::init_fixture_repo(dir.path(), &[("README", "hi\n")]);
+        let timeout = Duration::from_secs(5);
+
+        assert!(check_remote(&url, None, None, timeout).await.is_ok());
+        let error = check_remote(&url, Some("nope"), None, timeout)
+            .await
+            .unwrap_err();
+        assert_eq!(error, "Branch nope does not exist");
+
+        let missing = format!("file://{}/missing", dir.path().display());
+        let error = check_remote(&missing, None, None, timeout)
+            .await
+            .unwrap_err();
+        assert!(
+            error.starts_with("Repository is not reachable"),
+            "{}",
+            error
+        );
+    }
+}
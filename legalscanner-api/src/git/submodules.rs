@@ -0,0 +1,91 @@
+use super::auth::GitAuth;
+use git2::{FetchOptions, Repository, SubmoduleUpdateOptions};
+use std::path::{Path, PathBuf};
+
+/// Nesting depth at which submodules of submodules are no longer followed
+const MAX_DEPTH: usize = 8;
+
+/// Outcome of checking out one submodule
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmoduleCheckout {
+    /// Path relative to the repository root, `/`-separated
+    pub path: String,
+    pub url: Option<String>,
+    /// Commit the superproject pins the submodule to
+    pub commit_sha: Option<String>,
+    /// Why the submodule could not be checked out
+    pub error: Option<String>,
+}
+
+/// Initialize and check out every submodule of the clone at `repo_path`,
+/// recursively. A submodule that fails is reported with its error and the
+/// others are still checked out; only failing to read the superproject's
+/// submodules is an error.
+/// Uses `auth`, else the `GIT_TOKEN` environment variable, to fetch.
+pub async fn update_submodules(
+    repo_path: &Path,
+    auth: Option<&GitAuth>,
+) -> Result<Vec<SubmoduleCheckout>, git2::Error> {
+    let repo_path = repo_path.to_path_buf();
+    let auth = GitAuth::or_env(auth);
+
+    tokio::task::spawn_blocking(move || {
+        let repo = Repository::open(&repo_path)?;
+        let mut checkouts = Vec::new();
+        update_recursive(&repo, &PathBuf::new(), auth.as_ref(), 0, &mut checkouts)?;
+        Ok(checkouts)
+    })
+    .await
+    .map_err(|e| git2::Error::from_str(&e.to_string()))?
+}
+
+fn update_recursive(
+    repo: &Repository,
+    prefix: &Path,
+    auth: Option<&GitAuth>,
+    depth: usize,
+    checkouts: &mut Vec<SubmoduleCheckout>,
+) -> Result<(), git2::Error> {
+    for mut submodule in repo.submodules()? {
+        let path = prefix.join(submodule.path());
+        let mut checkout = SubmoduleCheckout {
+            path: path.to_string_lossy().replace('\\', "/"),
+            url: submodule.url().map(str::to_string),
+            commit_sha: submodule.index_id().map(|oid| oid.to_string()),
+            error: None,
+        };
+
+        tracing::info!("Checking out submodule {}", checkout.path);
+        let mut options = SubmoduleUpdateOptions::new();
+        options.fetch(fetch_options(auth));
+        if let Err(e) = submodule.update(true, Some(&mut options)) {
+            tracing::warn!("Failed to check out submodule {}: {}", checkout.path, e);
+            checkout.error = Some(e.message().to_string());
+            checkouts.push(checkout);
+            continue;
+        }
+        checkouts.push(checkout);
+
+        if depth + 1 >= MAX_DEPTH {
+            tracing::warn!(
+                "Not following submodules nested deeper than {}",
+                path.display()
+            );
+            continue;
+        }
+        match submodule.open() {
+            Ok(nested) => update_recursive(&nested, &path, auth, depth + 1, checkouts)?,
+            Err(e) => tracing::warn!("Failed to open submodule {}: {}", path.display(), e),
+        }
+    }
+    Ok(())
+}
+
+fn fetch_options(auth: Option<&GitAuth>) -> FetchOptions<'static> {
+    let mut fetch_options = FetchOptions::new();
+    if let Some(auth) = auth {
+        // Same scheme as the main clone
+        fetch_options.remote_callbacks(auth.callbacks());
+    }
+    fetch_options
+}
@@ -30,6 +30,48 @@ impl Workspace {
         self.path().exists()
     }
 
+    /// Narrow the checkout down to the `scan_path` subtree, so that scanners,
+    /// archives and soft limits only see that directory and report paths
+    /// relative to it. `.git` is kept for repository metadata.
+    pub async fn narrow_to(&self, scan_path: &str) -> Result<(), std::io::Error> {
+        let workspace_path = self.path();
+        let full_path = self.base_dir.join(format!("{}.full", self.scan_id));
+        fs::rename(&workspace_path, &full_path).await?;
+
+        let subtree = full_path.join(scan_path);
+        // Symlinks could point outside the checkout
+        let inside_checkout = match (
+            fs::canonicalize(&subtree).await,
+            fs::canonicalize(&full_path).await,
+        ) {
+            (Ok(resolved), Ok(root)) => resolved.starts_with(&root) && resolved != root,
+            _ => false,
+        };
+        let is_dir = fs::symlink_metadata(&subtree)
+            .await
+            .is_ok_and(|m| m.is_dir());
+        if !inside_checkout || !is_dir {
+            fs::remove_dir_all(&full_path).await?;
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!(
+                    "scan_path '{}' is not a directory in the repository",
+                    scan_path
+                ),
+            ));
+        }
+
+        fs::rename(&subtree, &workspace_path).await?;
+        let git_dir = workspace_path.join(".git");
+        if !git_dir.exists() {
+            let _ = fs::rename(full_path.join(".git"), &git_dir).await;
+        }
+        fs::remove_dir_all(&full_path).await?;
+
+        tracing::debug!("Narrowed workspace {:?} to {}", workspace_path, scan_path);
+        Ok(())
+    }
+
     /// Clean up the workspace
     pub async fn cleanup(&self) -> Result<(), std::io::Error> {
         let workspace_path = self.path();
@@ -42,6 +84,31 @@ impl Workspace {
     }
 }
 
+/// Normalize a `scan_path` to a relative path without `.`/`..` components,
+/// e.g. `/services/payments/` -> `services/payments`
+pub fn normalize_scan_path(scan_path: &str) -> Result<String, String> {
+    let components: Vec<&str> = scan_path
+        .trim()
+        .split('/')
+        .filter(|c| !c.is_empty())
+        .collect();
+
+    if components.is_empty() {
+        return Err("scan_path cannot be empty".to_string());
+    }
+    if components
+        .iter()
+        .any(|c| *c == "." || *c == ".." || *c == ".git" || c.contains('\\'))
+    {
+        return Err(format!(
+            "Invalid scan_path '{}': use a relative path such as services/payments",
+            scan_path
+        ));
+    }
+
+    Ok(components.join("/"))
+}
+
 /// Ensure base workspace directory exists
 pub async fn ensure_base_dir(base_dir: &Path) -> Result<(), std::io::Error> {
     if !base_dir.exists() {
@@ -72,4 +139,36 @@ mod tests {
         workspace.cleanup().await.unwrap();
         assert!(!path.exists());
     }
+
+    #[tokio::test]
+    async fn test_narrow_to_subtree() {
+        let temp_dir = tempdir().unwrap();
+        let workspace = Workspace::new(temp_dir.path().to_path_buf(), "scan-1".to_string());
+        let path = workspace.create().await.unwrap();
+        std::fs::create_dir_all(path.join(".git")).unwrap();
+        std::fs::create_dir_all(path.join("services/payments/src")).unwrap();
+        std::fs::write(path.join("services/payments/src/lib.rs"), "").unwrap();
+        std::fs::write(path.join("README.md"), "").unwrap();
+
+        workspace.narrow_to("services/payments").await.unwrap();
+
+        assert!(path.join("src/lib.rs").exists());
+        assert!(path.join(".git").exists());
+        assert!(!path.join("README.md").exists());
+        assert!(!temp_dir.path().join("scan-1.full").exists());
+
+        let err = workspace.narrow_to("missing").await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_normalize_scan_path() {
+        assert_eq!(
+            normalize_scan_path("/services/payments/").unwrap(),
+            "services/payments"
+        );
+        assert!(normalize_scan_path("").is_err());
+        assert!(normalize_scan_path("../etc").is_err());
+        assert!(normalize_scan_path("services/./payments").is_err());
+    }
 }
@@ -0,0 +1,267 @@
+//! Integrity verification of stored results. The findings of a scan are
+//! sealed with a SHA-256 checksum when they are stored; the integrity job
+//! recomputes the checksums and runs SQLite's `quick_check` over the
+//! database file, so silent corruption of stored results is noticed before
+//! a report or export is built from them. Discrepancies are recorded in the
+//! `integrity_events` audit log. Exports are rendered from the stored
+//! results on request, so verifying the results covers them too.
+//!
+//! Review decisions are not part of the checksum: they change legitimately
+//! after the scan.
+
+use crate::db::models::{IntegrityEvent, ResultChecksum, ScanResult};
+use crate::error::AppError;
+use crate::AppState;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+
+/// `quick_check` lines kept in the audit log
+const MAX_DATABASE_ERRORS: usize = 20;
+
+/// Checks and re-seals never overlap, a re-seal mid-check would be reported
+/// as a mismatch
+static RUNNING: Mutex<()> = Mutex::const_new(());
+
+/// Outcome of one integrity check
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct IntegrityReport {
+    /// Whether SQLite's `quick_check` found the database file intact
+    pub database_ok: bool,
+    /// Scans whose findings still match their checksum
+    pub scans_verified: usize,
+    /// Completed scans stored before checksums were recorded, sealed now
+    pub scans_sealed: usize,
+    /// Discrepancies found, also recorded in the audit log
+    pub discrepancies: Vec<IntegrityEvent>,
+}
+
+/// Checksum over the findings of a scan, in ID order
+pub fn checksum(results: &[ScanResult]) -> String {
+    let mut results: Vec<&ScanResult> = results.iter().collect();
+    results.sort_by_key(|r| r.id);
+
+    let mut hasher = Sha256::new();
+    for r in results {
+        let mut fields = serde_json::json!([
+            r.id,
+            r.scan_id,
+            r.file_path,
+            r.result_type,
+            r.license_name,
+            r.license_spdx_id,
+            r.copyright_statement,
+            r.copyright_holders,
+            r.copyright_years,
+            r.confidence,
+            r.raw_data,
+            r.risk_severity,
+            r.ecc_source,
+            r.ecc_line_number,
+            r.ecc_check_id,
+            r.license_category,
+            r.package_name,
+            r.package_version,
+            r.package_ecosystem,
+            r.package_purl,
+            r.merge_provenance,
+            r.license_agreement,
+        ]);
+        // Only when set, so findings sealed before exceptions were recorded
+        // keep their checksum
+        if let (Some(exception), Some(fields)) = (&r.license_exception, fields.as_array_mut()) {
+            fields.push(exception.clone().into());
+        }
+        hasher.update(fields.to_string().as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Seal the stored findings of a scan with their checksum
+pub async fn seal(state: &AppState, scan_id: &str) -> Result<(), AppError> {
+    let results = state.result_store.load_results(scan_id).await?;
+    ResultChecksum::seal(
+        &state.db,
+        scan_id,
+        results.len() as i64,
+        &checksum(&results),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Check the database file and recompute the checksum of every sealed scan
+pub async fn verify(state: &AppState) -> Result<IntegrityReport, AppError> {
+    let _running = RUNNING.lock().await;
+    let mut report = IntegrityReport::default();
+
+    let problems: Vec<String> = sqlx::query_scalar("PRAGMA quick_check")
+        .fetch_all(&state.db)
+        .await?;
+    report.database_ok = problems.len() == 1 && problems[0] == "ok";
+    if !report.database_ok {
+        let detail = problems
+            .iter()
+            .take(MAX_DATABASE_ERRORS)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let event = IntegrityEvent::record(
+            &state.db,
+            "database",
+            None,
+            "database_corrupt",
+            None,
+            None,
+            Some(&detail),
+        )
+        .await?;
+        report.discrepancies.push(event);
+    }
+
+    for sealed in ResultChecksum::list(&state.db).await? {
+        let expected = describe(sealed.result_count, &sealed.checksum);
+        let event = match state.result_store.load_results(&sealed.scan_id).await {
+            Ok(results) => {
+                let actual = describe(results.len() as i64, &checksum(&results));
+                if actual == expected {
+                    ResultChecksum::mark_verified(&state.db, &sealed.scan_id).await?;
+                    report.scans_verified += 1;
+                    continue;
+                }
+                IntegrityEvent::record(
+                    &state.db,
+                    "scan",
+                    Some(&sealed.scan_id),
+                    "checksum_mismatch",
+                    Some(&expected),
+                    Some(&actual),
+                    None,
+                )
+                .await?
+            }
+            Err(e) => {
+                IntegrityEvent::record(
+                    &state.db,
+                    "scan",
+                    Some(&sealed.scan_id),
+                    "unreadable",
+                    Some(&expected),
+                    None,
+                    Some(&e.to_string()),
+                )
+                .await?
+            }
+        };
+        tracing::error!(
+            "Integrity check: stored results of scan {} are {}",
+            sealed.scan_id,
+            event.kind
+        );
+        report.discrepancies.push(event);
+    }
+
+    for scan_id in ResultChecksum::unsealed_scan_ids(&state.db).await? {
+        seal(state, &scan_id).await?;
+        report.scans_sealed += 1;
+    }
+
+    tracing::info!(
+        "Integrity check: database {}, {} scans verified, {} sealed, {} discrepancies",
+        if report.database_ok { "ok" } else { "corrupt" },
+        report.scans_verified,
+        report.scans_sealed,
+        report.discrepancies.len()
+    );
+    Ok(report)
+}
+
+/// Seal every scan again after findings were removed on purpose, recording
+/// each changed checksum in the audit log with `reason`
+pub async fn reseal(state: &AppState, reason: &str) -> Result<usize, AppError> {
+    let _running = RUNNING.lock().await;
+    let mut resealed = 0;
+    for sealed in ResultChecksum::list(&state.db).await? {
+        let results = state.result_store.load_results(&sealed.scan_id).await?;
+        let actual = checksum(&results);
+        if actual == sealed.checksum && results.len() as i64 == sealed.result_count {
+            continue;
+        }
+        ResultChecksum::seal(&state.db, &sealed.scan_id, results.len() as i64, &actual).await?;
+        IntegrityEvent::record(
+            &state.db,
+            "scan",
+            Some(&sealed.scan_id),
+            "resealed",
+            Some(&describe(sealed.result_count, &sealed.checksum)),
+            Some(&describe(results.len() as i64, &actual)),
+            Some(reason),
+        )
+        .await?;
+        resealed += 1;
+    }
+    Ok(resealed)
+}
+
+/// Run [`verify`] every `INTEGRITY_CHECK_INTERVAL_HOURS`, the first time one
+/// interval after startup. `None` when the periodic check is disabled.
+pub fn spawn_periodic(state: AppState) -> Option<JoinHandle<()>> {
+    let hours = state.config.integrity_check_interval_hours;
+    if hours == 0 {
+        return None;
+    }
+    Some(tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(hours * 3600));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = verify(&state).await {
+                tracing::warn!("Integrity check failed: {}", e);
+            }
+        }
+    }))
+}
+
+fn describe(result_count: i64, checksum: &str) -> String {
+    format!("{} findings, sha256:{}", result_count, checksum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: i64, license: &str) -> ScanResult {
+        ScanResult {
+            id,
+            license_name: Some(license.to_string()),
+            license_spdx_id: Some(license.to_string()),
+            confidence: Some(0.9),
+            license_category: Some("code".to_string()),
+            ..ScanResult::for_test("license", "src/lib.rs")
+        }
+    }
+
+    #[test]
+    fn test_checksum_ignores_order_and_reviews() {
+        let results = vec![result(1, "MIT"), result(2, "Apache-2.0")];
+        let sealed = checksum(&results);
+        assert_eq!(sealed.len(), 64);
+
+        let mut reviewed = vec![result(2, "Apache-2.0"), result(1, "MIT")];
+        reviewed[0].review_status = "approved".to_string();
+        reviewed[0].reviewed_at = Some("2025-02-11 10:00:00".to_string());
+        assert_eq!(checksum(&reviewed), sealed);
+
+        let altered = vec![result(1, "MIT"), result(2, "GPL-3.0-only")];
+        assert_ne!(checksum(&altered), sealed);
+        assert_ne!(checksum(&results[..1]), sealed);
+    }
+}
@@ -0,0 +1,80 @@
+//! Legal holds keep scans from being deleted while litigation or an audit
+//! needs them. A hold is placed on a single scan, or on a project, which
+//! holds every scan of its repositories including later ones. Held scans are
+//! refused by `DELETE /scans/:id`, left in place by `DELETE /scans` and
+//! retention purges, and block repository erasure. Only admins place and lift holds, and every
+//! change is recorded in the `legal_hold_events` audit log.
+
+use crate::db::models::{LegalHoldEvent, Project, Scan};
+use crate::error::AppError;
+use crate::scan_diff::normalize_repository_url;
+use sqlx::SqlitePool;
+
+/// Why `scan` is held, or `None` if it may be deleted
+pub async fn hold_on(pool: &SqlitePool, scan: &Scan) -> Result<Option<String>, sqlx::Error> {
+    if scan.legal_hold_at.is_some() {
+        return Ok(Some(format!("Scan {} is under legal hold", scan.id)));
+    }
+
+    let repository = normalize_repository_url(&scan.git_url);
+    let projects = Project::find_held_by_repository(pool, &repository).await?;
+    Ok(projects.first().map(|project| {
+        format!(
+            "Scan {} belongs to project '{}', which is under legal hold",
+            scan.id, project.name
+        )
+    }))
+}
+
+/// Whether `scan` is held, given the repositories of held projects from
+/// [`Project::held_repositories`]; checks many scans with one query
+pub fn is_held(scan: &Scan, held_repositories: &[String]) -> bool {
+    scan.legal_hold_at.is_some()
+        || held_repositories.contains(&normalize_repository_url(&scan.git_url))
+}
+
+/// Refuse with 409 Conflict if `scan` is held
+pub async fn ensure_deletable(pool: &SqlitePool, scan: &Scan) -> Result<(), AppError> {
+    match hold_on(pool, scan).await? {
+        Some(reason) => Err(AppError::Conflict(reason)),
+        None => Ok(()),
+    }
+}
+
+/// Place (`Some(reason)`) or lift (`None`) the hold on a scan and record it
+pub async fn set_scan_hold(
+    pool: &SqlitePool,
+    scan_id: &str,
+    reason: Option<&str>,
+) -> Result<Scan, AppError> {
+    let scan = Scan::set_legal_hold(pool, scan_id, reason)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", scan_id)))?;
+    record(pool, "scan", scan_id, reason).await?;
+    Ok(scan)
+}
+
+/// Place (`Some(reason)`) or lift (`None`) the hold on a project and record it
+pub async fn set_project_hold(
+    pool: &SqlitePool,
+    project_id: &str,
+    reason: Option<&str>,
+) -> Result<Project, AppError> {
+    let project = Project::set_legal_hold(pool, project_id, reason)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Project {} not found", project_id)))?;
+    record(pool, "project", project_id, reason).await?;
+    Ok(project)
+}
+
+async fn record(
+    pool: &SqlitePool,
+    target_type: &str,
+    target_id: &str,
+    reason: Option<&str>,
+) -> Result<(), AppError> {
+    let action = if reason.is_some() { "placed" } else { "lifted" };
+    LegalHoldEvent::record(pool, target_type, target_id, action, reason).await?;
+    tracing::info!("Legal hold {} on {} {}", action, target_type, target_id);
+    Ok(())
+}
@@ -2,15 +2,55 @@
 // This allows binaries to import modules from the main crate
 
 pub mod api;
+pub mod archival;
+pub mod audit;
+pub mod bulk_import;
+pub mod codeowners;
 pub mod config;
+pub mod copyright_holders;
+pub mod coverage;
+pub mod credentials;
+pub mod curation;
+pub mod custom_fields;
 pub mod db;
+pub mod erasure;
 pub mod error;
 pub mod export;
+pub mod gate;
 pub mod git;
+pub mod integrity;
+pub mod legal_hold;
+pub mod lifecycle;
+pub mod local_scan;
+pub mod orchestrator;
+pub mod policy;
+pub mod progress;
+pub mod push_hooks;
+pub mod remediation;
+pub mod rescoring;
+pub mod repo_metadata;
+pub mod retention;
+pub mod review;
+pub mod sampling;
+pub mod scan_archive;
+pub mod scan_diff;
 pub mod scanner;
+pub mod screening;
+pub mod self_test;
+pub mod server;
+pub mod storage;
+pub mod submodules;
+pub mod swhid;
+pub mod telemetry;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+pub mod upload;
 pub mod utils;
+pub mod waiver;
+pub mod webhooks;
 
 pub use error::AppError;
+pub use orchestrator::{ScanOrchestrator, ScanOutcome};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -19,4 +59,13 @@ pub struct AppState {
     pub config: Arc<config::Config>,
     pub fossology_scanner: Arc<dyn scanner::Scanner>,
     pub semgrep_scanner: Arc<dyn scanner::Scanner>,
+    pub malware_scanner: Option<Arc<dyn scanner::Scanner>>,
+    pub documentation_scanner: Arc<dyn scanner::Scanner>,
+    pub package_scanner: Option<Arc<dyn scanner::Scanner>>,
+    pub model_scanner: Arc<dyn scanner::Scanner>,
+    pub result_store: Arc<dyn storage::ResultStore>,
+    /// Where the findings of old scans are archived, see [`archival`]
+    pub result_archive: Option<Arc<dyn storage::ArchiveStorage>>,
+    /// Live progress for `GET /api/v1/scans/:id/events`
+    pub progress: progress::ProgressHub,
 }
@@ -0,0 +1,222 @@
+//! Finding lifecycle across the scans of a repository, to track remediation
+//! rather than detection. A finding is opened when a scan first reports it,
+//! can be triaged by a reviewer, and is resolved by hand or automatically
+//! once a later full scan of the repository no longer finds it. A resolved
+//! finding that shows up again is reopened. Copyright statements are
+//! attributions, not something to remediate, and are not tracked.
+
+use crate::api::models::{FindingStateCounts, LicenseFindingCounts, ProjectFindingsReport};
+use crate::db::models::{Scan, ScanResult, TrackedFinding};
+use crate::error::AppError;
+use crate::export::sarif::repository_path;
+use crate::scan_diff::{finding_change, normalize_repository_url, FindingChange};
+use crate::AppState;
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub const STATES: [&str; 3] = ["open", "triaged", "resolved"];
+
+/// Findings recorded for a scan
+#[derive(Debug, Default, PartialEq)]
+pub struct SyncReport {
+    pub opened: usize,
+    pub reopened: usize,
+    pub resolved: u64,
+}
+
+/// Whether a reviewer may move a finding from `from` to `to`. Resolved
+/// findings can only be reopened.
+pub fn can_transition(from: &str, to: &str) -> bool {
+    matches!(
+        (from, to),
+        ("open", "triaged")
+            | ("open", "resolved")
+            | ("triaged", "open")
+            | ("triaged", "resolved")
+            | ("resolved", "open")
+    )
+}
+
+/// Record the findings of a completed scan, see [`sync`]
+pub async fn record(state: &AppState, scan_id: &str) -> Result<SyncReport, AppError> {
+    let scan = Scan::find_by_id(&state.db, scan_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", scan_id)))?;
+    let results = state.result_store.load_results(scan_id).await?;
+    Ok(sync(&state.db, &scan, &results).await?)
+}
+
+/// Record the findings of a completed scan. Findings the scan did not
+/// report are resolved only when it covered everything they could be in:
+/// quick scans and re-runs that skipped a scanner only open findings, and a
+/// scan limited to a `scan_path` only resolves findings under it.
+pub async fn sync(
+    pool: &SqlitePool,
+    scan: &Scan,
+    results: &[ScanResult],
+) -> Result<SyncReport, sqlx::Error> {
+    let repository = normalize_repository_url(&scan.git_url);
+    let mut report = SyncReport::default();
+
+    for finding in tracked_findings(scan, results) {
+        let previous = TrackedFinding::record(
+            pool,
+            &repository,
+            &finding.result_type,
+            &finding.file_path,
+            &finding.value,
+            finding.severity.as_deref(),
+            &scan.id,
+        )
+        .await?;
+        match previous.as_deref() {
+            None => report.opened += 1,
+            Some("resolved") => report.reopened += 1,
+            Some(_) => {}
+        }
+    }
+
+    let complete =
+        !scan.quick && scan.fossology_status != "skipped" && scan.semgrep_status != "skipped";
+    if complete {
+        let prefix = scan
+            .scan_path
+            .as_deref()
+            .map(|p| format!("{}/", p.trim_matches('/')))
+            .unwrap_or_default();
+        report.resolved =
+            TrackedFinding::resolve_missing(pool, &repository, &prefix, &scan.id).await?;
+    }
+
+    Ok(report)
+}
+
+/// Distinct findings of a scan, with repository-relative paths
+fn tracked_findings(scan: &Scan, results: &[ScanResult]) -> BTreeSet<FindingChange> {
+    results
+        .iter()
+        .filter(|r| r.result_type != "copyright")
+        .filter_map(|result| finding_change(&scan.id, result))
+        .map(|finding| FindingChange {
+            file_path: repository_path(&finding.file_path, &scan.id, scan.scan_path.as_deref()),
+            ..finding
+        })
+        .collect()
+}
+
+/// Remediation progress of a project's findings. `since` (stored timestamp
+/// format) splits findings opened and resolved in the period from those
+/// that were already open before it and still are.
+pub fn report(project_id: &str, since: &str, findings: &[TrackedFinding]) -> ProjectFindingsReport {
+    let unresolved_since =
+        |f: &TrackedFinding| f.state != "resolved" && f.opened_at.as_str() < since;
+
+    let mut licenses: BTreeMap<&str, LicenseFindingCounts> = BTreeMap::new();
+    for finding in findings
+        .iter()
+        .filter(|f| f.result_type == "license" || f.result_type == "model_license")
+    {
+        let counts = licenses
+            .entry(&finding.value)
+            .or_insert_with(|| LicenseFindingCounts {
+                license: finding.value.clone(),
+                counts: FindingStateCounts::default(),
+                unresolved_since: 0,
+            });
+        counts.counts.add(&finding.state);
+        if unresolved_since(finding) {
+            counts.unresolved_since += 1;
+        }
+    }
+
+    let mut totals = FindingStateCounts::default();
+    for finding in findings {
+        totals.add(&finding.state);
+    }
+
+    ProjectFindingsReport {
+        project_id: project_id.to_string(),
+        since: since.to_string(),
+        totals,
+        opened_since: findings
+            .iter()
+            .filter(|f| f.opened_at.as_str() >= since)
+            .count() as i64,
+        resolved_since: findings
+            .iter()
+            .filter(|f| f.resolved_at.as_deref().is_some_and(|r| r >= since))
+            .count() as i64,
+        unresolved_since: findings.iter().filter(|f| unresolved_since(f)).count() as i64,
+        licenses: licenses.into_values().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(
+        value: &str,
+        state: &str,
+        opened_at: &str,
+        resolved_at: Option<&str>,
+    ) -> TrackedFinding {
+        TrackedFinding {
+            id: format!("{}-{}", value, opened_at),
+            repository: "https://example.com/app".to_string(),
+            result_type: "license".to_string(),
+            file_path: "src/lib.rs".to_string(),
+            value: value.to_string(),
+            severity: None,
+            state: state.to_string(),
+            first_scan_id: "scan-1".to_string(),
+            last_scan_id: "scan-1".to_string(),
+            opened_at: opened_at.to_string(),
+            triaged_at: None,
+            resolved_at: resolved_at.map(str::to_string),
+            resolved_scan_id: None,
+            reopened_at: None,
+            note: None,
+            updated_at: opened_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_can_transition() {
+        assert!(can_transition("open", "triaged"));
+        assert!(can_transition("triaged", "resolved"));
+        assert!(can_transition("resolved", "open"));
+        assert!(!can_transition("resolved", "triaged"));
+        assert!(!can_transition("open", "open"));
+    }
+
+    #[test]
+    fn test_report_counts_findings_open_since() {
+        let since = "2025-01-01 00:00:00";
+        let findings = vec![
+            finding("GPL-3.0-only", "open", "2024-10-01 00:00:00", None),
+            finding("GPL-3.0-only", "triaged", "2024-11-01 00:00:00", None),
+            finding(
+                "GPL-3.0-only",
+                "resolved",
+                "2024-11-01 00:00:00",
+                Some("2025-02-01 00:00:00"),
+            ),
+            finding("MIT", "open", "2025-01-15 00:00:00", None),
+        ];
+
+        let report = report("p-1", since, &findings);
+        assert_eq!(report.totals.open, 2);
+        assert_eq!(report.totals.triaged, 1);
+        assert_eq!(report.totals.resolved, 1);
+        assert_eq!(report.opened_since, 1);
+        assert_eq!(report.resolved_since, 1);
+        assert_eq!(report.unresolved_since, 2);
+
+        assert_eq!(report.licenses[0].license, "GPL-3.0-only");
+        assert_eq!(report.licenses[0].unresolved_since, 2);
+        assert_eq!(report.licenses[0].counts.resolved, 1);
+        assert_eq!(report.licenses[1].license, "MIT");
+        assert_eq!(report.licenses[1].unresolved_since, 0);
+    }
+}
@@ -0,0 +1,99 @@
+//! Scans of a directory on the server, for deployments next to build
+//! servers that already have the sources checked out. The directory is
+//! scanned in place, without cloning, and must lie under one of the
+//! `LOCAL_SCAN_BASE_DIRS`. Such scans are recorded as `local://<path>`.
+
+use crate::scanner::semgrep::SemgrepMode;
+use std::path::{Path, PathBuf};
+
+pub const LOCAL_SCHEME: &str = "local://";
+
+pub fn is_local(git_url: &str) -> bool {
+    git_url.starts_with(LOCAL_SCHEME)
+}
+
+/// Repository URL recorded for a scan of `path`
+pub fn local_url(path: &Path) -> String {
+    format!("{}{}", LOCAL_SCHEME, path.display())
+}
+
+/// The Semgrep container only mounts the scan workspaces, so it cannot read
+/// directories elsewhere on the server
+pub fn check_semgrep_mode(mode: SemgrepMode) -> Result<(), String> {
+    match mode {
+        SemgrepMode::Local => Ok(()),
+        SemgrepMode::Docker => Err(
+            "Local path scans need SEMGREP_MODE=local, the Semgrep container cannot read them"
+                .to_string(),
+        ),
+    }
+}
+
+/// Resolve a requested directory and check it against the allow-list.
+/// Symlinks are resolved first, so a link under a base directory cannot
+/// point the scan elsewhere.
+pub fn resolve(base_dirs: &[PathBuf], path: &str) -> Result<PathBuf, String> {
+    if base_dirs.is_empty() {
+        return Err("Local path scans are disabled, set LOCAL_SCAN_BASE_DIRS".to_string());
+    }
+
+    let requested = Path::new(path.strip_prefix(LOCAL_SCHEME).unwrap_or(path));
+    if !requested.is_absolute() {
+        return Err(format!("Path '{}' must be absolute", path));
+    }
+
+    let resolved = requested
+        .canonicalize()
+        .map_err(|e| format!("Cannot access '{}': {}", path, e))?;
+    if !resolved.is_dir() {
+        return Err(format!("'{}' is not a directory", path));
+    }
+
+    let allowed = base_dirs
+        .iter()
+        .filter_map(|base| base.canonicalize().ok())
+        .any(|base| resolved.starts_with(&base));
+    if !allowed {
+        return Err(format!("'{}' is not under an allowed base directory", path));
+    }
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_checks_allow_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("builds");
+        std::fs::create_dir_all(base.join("app")).unwrap();
+        std::fs::create_dir_all(dir.path().join("secrets")).unwrap();
+        let bases = vec![base.clone()];
+
+        let app = base.join("app");
+        let resolved = resolve(&bases, app.to_str().unwrap()).unwrap();
+        assert_eq!(resolved, app.canonicalize().unwrap());
+        assert_eq!(resolve(&bases, &local_url(&resolved)).unwrap(), resolved);
+
+        let escape = base.join("app/../../secrets");
+        assert!(resolve(&bases, escape.to_str().unwrap()).is_err());
+        assert!(resolve(&bases, "builds/app").is_err());
+        assert!(resolve(&bases, base.join("missing").to_str().unwrap()).is_err());
+        assert!(resolve(&[], app.to_str().unwrap()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_follows_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("builds");
+        std::fs::create_dir_all(&base).unwrap();
+        std::fs::create_dir_all(dir.path().join("secrets")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("secrets"), base.join("link")).unwrap();
+
+        let link = base.join("link");
+        assert!(resolve(&[base], link.to_str().unwrap()).is_err());
+    }
+}
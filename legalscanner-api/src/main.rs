@@ -1,13 +1,24 @@
-use legalscanner_api::AppState;
 use legalscanner_api::config::Config;
-use legalscanner_api::scanner::fossology::FossologyScanner;
-use legalscanner_api::scanner::semgrep::SemgrepScanner;
-use legalscanner_api::{api, db, git};
-use std::sync::Arc;
+use legalscanner_api::db::models::Worker;
+use legalscanner_api::integrity;
+use legalscanner_api::orchestrator::build_app_state;
+use legalscanner_api::api;
+use legalscanner_api::archival;
+use legalscanner_api::retention;
+use legalscanner_api::self_test;
+use legalscanner_api::server;
+use legalscanner_api::telemetry;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Deployment health gate: check dependencies, print a table and exit
+    if std::env::args().skip(1).any(|arg| arg == "--self-test") {
+        let results = self_test::run().await;
+        print!("{}", self_test::render_table(&results));
+        std::process::exit(if self_test::passed(&results) { 0 } else { 1 });
+    }
+
     // Initialize tracing
     tracing_subscriber::registry()
         .with(
@@ -21,46 +32,97 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = Config::from_env()?;
     tracing::info!("Configuration loaded successfully");
 
-    // Initialize database
-    let db_pool = db::create_pool(&config.database_url).await?;
-    tracing::info!("Database connection pool created");
-
-    // Run migrations
-    db::run_migrations(&db_pool).await?;
-    tracing::info!("Database migrations completed");
-
-    // Initialize Fossology scanner
-    let fossology_scanner = FossologyScanner::new(
-        config.fossology_url.clone(),
-        config.fossology_api_token.clone(),
-    );
-    tracing::info!("Fossology scanner initialized");
-
-    // Initialize Semgrep scanner
-    let semgrep_scanner = SemgrepScanner::new();
-    tracing::info!("Semgrep scanner initialized");
-
-    // Ensure workspace directory exists
-    git::workspace::ensure_base_dir(&config.temp_workspace_dir).await?;
-    tracing::info!("Workspace directory ready");
-
-    // Build app state
-    let app_state = AppState {
-        db: db_pool,
-        config: Arc::new(config.clone()),
-        fossology_scanner: Arc::new(fossology_scanner),
-        semgrep_scanner: Arc::new(semgrep_scanner),
-    };
+    // Database, scanners and result store
+    let app_state = build_app_state(config.clone()).await?;
+
+    // Register this process as a worker for autoscaling metrics
+    let worker = Worker::register(&app_state.db, config.worker_name.clone()).await?;
+    tracing::info!("Registered worker {} ({})", worker.id, worker.hostname);
+
+    let heartbeat_pool = app_state.db.clone();
+    let heartbeat_worker_id = worker.id.clone();
+    let heartbeat = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            if let Err(e) = Worker::heartbeat(&heartbeat_pool, &heartbeat_worker_id).await {
+                tracing::warn!("Worker heartbeat failed: {}", e);
+            }
+        }
+    });
+
+    // Recompute the checksums of stored results
+    let integrity_check = integrity::spawn_periodic(app_state.clone());
+
+    // Purge findings and scans past their retention period
+    let retention_purge = retention::spawn_periodic(app_state.clone());
+
+    // Move the findings of old scans to the result archive
+    let result_archival = archival::spawn_periodic(app_state.clone());
+
+    // Opt-in usage reports
+    let telemetry_reports = telemetry::spawn_periodic(app_state.clone());
+
+    let db_pool = app_state.db.clone();
 
     // Build router
     let app = api::routes::create_router(app_state);
 
     // Start server
-    let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", config.server_port))
-        .await?;
-    tracing::info!("Server starting on port {}", config.server_port);
+    tracing::info!("Server starting on {}", config.listen_addr);
 
-    axum::serve(listener, app).await?;
+    let shutdown_pool = db_pool.clone();
+    let shutdown_worker_id = worker.id.clone();
+    server::serve(&config.listen_addr, app, async move {
+        shutdown_signal().await;
+        tracing::info!("Shutdown signal received, draining worker");
+        if let Err(e) = Worker::update_status(&shutdown_pool, &shutdown_worker_id, "draining").await {
+            tracing::warn!("Failed to mark worker as draining: {}", e);
+        }
+    })
+    .await?;
+
+    // Deregister worker
+    heartbeat.abort();
+    if let Some(integrity_check) = integrity_check {
+        integrity_check.abort();
+    }
+    if let Some(retention_purge) = retention_purge {
+        retention_purge.abort();
+    }
+    if let Some(result_archival) = result_archival {
+        result_archival.abort();
+    }
+    if let Some(telemetry_reports) = telemetry_reports {
+        telemetry_reports.abort();
+    }
+    Worker::update_status(&db_pool, &worker.id, "stopped").await?;
+    tracing::info!("Worker {} deregistered", worker.id);
 
     Ok(())
 }
+
+/// Resolve on Ctrl+C or SIGTERM (sent by Kubernetes when scaling down)
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
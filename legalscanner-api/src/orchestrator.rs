@@ -0,0 +1,188 @@
+//! High-level API for embedding the scanner in another Rust service without
+//! running the HTTP server.
+//!
+//! ```no_run
+//! use legalscanner_api::{config::Config, ScanOrchestrator};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let orchestrator = ScanOrchestrator::from_config(Config::from_env()?).await?;
+//! let outcome = orchestrator.scan_path("/path/to/checkout").await?;
+//!
+//! if let Some(risk) = &outcome.risk {
+//!     println!("risk {} ({})", risk.score, risk.level);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::handlers::scan_job::scan_checkout;
+use crate::api::models::RiskAssessment;
+use crate::codeowners;
+use crate::config::Config;
+use crate::db::models::Scan;
+use crate::error::AppError;
+use crate::progress::{record_event, ProgressHub};
+use crate::scanner::clamav::ClamAvScanner;
+use crate::scanner::documentation::DocumentationLicenseScanner;
+use crate::scanner::fossology::FossologyScanner;
+use crate::scanner::model::ModelLicenseScanner;
+use crate::scanner::semgrep::SemgrepScanner;
+use crate::scanner::sandbox::Sandbox;
+use crate::scanner::syft::SyftScanner;
+use crate::scanner::{ScanResult, Scanner};
+use crate::storage;
+use crate::telemetry;
+use crate::{db, git, AppState};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Result of scanning one local checkout
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanOutcome {
+    /// ID of the scan record, usable with the HTTP API if it shares the database
+    pub scan_id: String,
+    /// Findings merged across all scanners, one entry per file
+    pub results: Vec<ScanResult>,
+    /// `None` when risk scoring failed; the findings are still valid
+    pub risk: Option<RiskAssessment>,
+}
+
+/// Runs the full scan pipeline (all configured scanners, result storage and
+/// risk scoring) on local directories
+#[derive(Clone)]
+pub struct ScanOrchestrator {
+    state: AppState,
+}
+
+impl ScanOrchestrator {
+    /// Open the configured database and build scanners exactly as the server does
+    pub async fn from_config(config: Config) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            state: build_app_state(config).await?,
+        })
+    }
+
+    /// Use caller-provided scanners, database and result store
+    pub fn from_state(state: AppState) -> Self {
+        Self { state }
+    }
+
+    pub fn state(&self) -> &AppState {
+        &self.state
+    }
+
+    /// Scan a directory in place. The directory is read but never modified or
+    /// removed; Git metadata is used for naming when it is a checkout.
+    pub async fn scan_path(&self, path: impl AsRef<Path>) -> Result<ScanOutcome, AppError> {
+        let path = path.as_ref();
+        if !path.is_dir() {
+            return Err(AppError::Validation(format!(
+                "Scan path is not a directory: {}",
+                path.display()
+            )));
+        }
+
+        let path = path
+            .canonicalize()
+            .map_err(|e| AppError::Internal(format!("Failed to resolve scan path: {}", e)))?;
+        let source = format!("file://{}", path.display());
+
+        let scan = Scan::create(&self.state.db, source, None, None).await?;
+        let _ = record_event(&self.state, &scan.id, "created", None, None).await;
+        Scan::update_status(&self.state.db, &scan.id, "in_progress", None).await?;
+        codeowners::record(&self.state, &scan.id, &path).await;
+
+        match scan_checkout(&self.state, &scan.id, self.state.semgrep_scanner.clone(), &path).await {
+            Ok(outcome) => {
+                let _ = record_event(&self.state, &scan.id, "completed", None, None).await;
+                Ok(outcome)
+            }
+            Err(e) => {
+                let _ = Scan::update_status(&self.state.db, &scan.id, "failed", Some(e.to_string())).await;
+                let _ = Scan::set_error_class(&self.state.db, &scan.id, &telemetry::error_class(e.as_ref())).await;
+                let _ = record_event(&self.state, &scan.id, "failed", None, Some(&e.to_string())).await;
+                Err(AppError::Internal(format!("Scan {} failed: {}", scan.id, e)))
+            }
+        }
+    }
+}
+
+/// Connect to the database, run migrations and construct every scanner and
+/// the result store from configuration
+pub async fn build_app_state(config: Config) -> Result<AppState, Box<dyn std::error::Error>> {
+    // Initialize database
+    let db_pool = db::create_pool(&config.database_url, &db::PoolSettings::from_config(&config)).await?;
+    tracing::info!("Database connection pool created");
+
+    // Run migrations
+    db::run_migrations(&db_pool).await?;
+    tracing::info!("Database migrations completed");
+
+    // Initialize Fossology scanner
+    let fossology_scanner = FossologyScanner::new(
+        config.fossology_url.clone(),
+        config.fossology_api_token.clone(),
+    )
+    .with_upload_name_template(config.fossology_upload_name_template.clone())
+    .with_max_concurrent_jobs(config.fossology_max_concurrent_jobs);
+    tracing::info!("Fossology scanner initialized");
+
+    // Initialize Semgrep scanner
+    let semgrep_scanner = SemgrepScanner::from_config(&config);
+    tracing::info!("Semgrep scanner initialized");
+    if !semgrep_scanner.sandbox.is_disabled() {
+        tracing::info!("Scanner subprocesses run sandboxed: {:?}", semgrep_scanner.sandbox);
+    }
+
+    // Initialize optional ClamAV malware scanner
+    let malware_scanner: Option<Arc<dyn Scanner>> = match &config.clamav_address {
+        Some(address) => {
+            tracing::info!("ClamAV malware scanner enabled at {}", address);
+            Some(Arc::new(ClamAvScanner::new(address.clone())))
+        }
+        None => None,
+    };
+
+    // Initialize optional Syft package inventory
+    let package_scanner: Option<Arc<dyn Scanner>> = match &config.syft_binary {
+        Some(binary) => {
+            tracing::info!("Syft package inventory enabled ({})", binary);
+            Some(Arc::new(
+                SyftScanner::new(binary.clone()).with_sandbox(Sandbox::from_config(&config)),
+            ))
+        }
+        None => None,
+    };
+
+    // Documentation and dataset license detection runs in-process
+    let documentation_scanner = DocumentationLicenseScanner::new();
+    let model_scanner = ModelLicenseScanner::new();
+
+    // Initialize result store
+    let result_archive = storage::archive_from_config(&config)?;
+    if let Some(archive) = &result_archive {
+        tracing::info!("Result archive initialized ({})", archive.name());
+    }
+    let result_store =
+        storage::result_store_from_config(&config, &db_pool, result_archive.clone())?;
+    tracing::info!("Result store initialized ({})", result_store.name());
+
+    // Ensure workspace directory exists
+    git::workspace::ensure_base_dir(&config.temp_workspace_dir).await?;
+    tracing::info!("Workspace directory ready");
+
+    Ok(AppState {
+        db: db_pool,
+        config: Arc::new(config),
+        fossology_scanner: Arc::new(fossology_scanner),
+        semgrep_scanner: Arc::new(semgrep_scanner),
+        malware_scanner,
+        documentation_scanner: Arc::new(documentation_scanner),
+        package_scanner,
+        model_scanner: Arc::new(model_scanner),
+        result_store,
+        result_archive,
+        progress: ProgressHub::new(),
+    })
+}
@@ -0,0 +1,704 @@
+//! License policies. A policy lists the licenses an organization allows,
+//! denies or wants reviewed, and can cap the risk score and the severity of
+//! ECC findings. The organization's default policy applies to every scan, a
+//! project's policy to the scans of the project's repositories, and a scan's
+//! own policy to that scan. They are layered rule by rule into the scan's
+//! effective policy: a license takes the listing of the most specific policy
+//! that lists it, and a threshold the value of the most specific policy that
+//! sets it.
+//!
+//! Completed scans are evaluated against their policy and the outcome is
+//! stored with the violations: denied licenses, licenses missing from a
+//! non-empty allow list and exceeded thresholds fail, licenses that need
+//! review warn. Rejected and waived findings are left out. The violations
+//! are part of the CI gate verdict.
+
+use crate::db::models::{Policy, PolicyEvaluationRecord, Project, Scan, ScanResult};
+use crate::error::AppError;
+use crate::export::sarif::relative_uri;
+use crate::gate::GateOutcome;
+use crate::scan_diff::normalize_repository_url;
+use crate::screening::severity_rank;
+use crate::waiver;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use utoipa::ToSchema;
+
+/// Severities `ecc_fail_severity` accepts
+pub const ECC_SEVERITIES: &[&str] = &["low", "medium", "high", "critical"];
+
+/// A way the scan breaks its policy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PolicyViolation {
+    /// `denied_license`, `unlisted_license`, `review_license`,
+    /// `risk_score` or `ecc_severity`
+    pub rule: String,
+    /// `fail`, or `warn` for licenses that need review
+    pub outcome: GateOutcome,
+    /// The license, for license rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    pub message: String,
+    /// Affected files, relative to the repository root
+    pub files: Vec<String>,
+}
+
+/// Evaluation of a scan against its policy
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PolicyEvaluation {
+    pub scan_id: String,
+    pub policy: String,
+    /// `pass`, `warn` or `fail`: the worst outcome among the violations
+    pub outcome: GateOutcome,
+    /// False when the outcome is `fail`
+    pub passed: bool,
+    pub violations: Vec<PolicyViolation>,
+    pub evaluated_at: String,
+}
+
+impl PolicyEvaluation {
+    fn from_record(record: PolicyEvaluationRecord) -> Self {
+        let violations: Vec<PolicyViolation> =
+            serde_json::from_str(&record.violations).unwrap_or_default();
+        let outcome = worst_outcome(&violations);
+        PolicyEvaluation {
+            scan_id: record.scan_id,
+            policy: record.policy,
+            outcome,
+            passed: outcome != GateOutcome::Fail,
+            violations,
+            evaluated_at: record.evaluated_at,
+        }
+    }
+}
+
+/// Level a policy is assigned at, from the broadest to the most specific
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyLevel {
+    Organization,
+    Project,
+    Scan,
+}
+
+/// A policy that applies to a scan, and where it was assigned
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PolicySource {
+    pub level: PolicyLevel,
+    pub policy: String,
+    /// The project the policy is assigned to, for project policies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+}
+
+/// List a finding's license falls under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Listing {
+    Allowed,
+    Denied,
+    NeedsReview,
+    Unlisted,
+}
+
+/// Listing of a license in the effective policy
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EffectiveLicenseRule {
+    /// As written in the policy it comes from
+    pub license: String,
+    /// `allowed`, `denied` or `needs_review`
+    pub listing: Listing,
+    pub source: PolicySource,
+}
+
+/// Risk score limit of the effective policy
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EffectiveRiskLimit {
+    pub max_risk_score: i32,
+    pub source: PolicySource,
+}
+
+/// ECC severity threshold of the effective policy
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EffectiveEccThreshold {
+    pub ecc_fail_severity: String,
+    pub source: PolicySource,
+}
+
+/// The policies that apply to a scan, layered into one
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EffectivePolicy {
+    /// The policies layered, from the organization's default to the scan's
+    /// own
+    pub layers: Vec<PolicySource>,
+    /// Listed licenses, by license
+    pub licenses: Vec<EffectiveLicenseRule>,
+    /// The most specific policy with an allow list. While one applies,
+    /// licenses on none of the lists fail.
+    pub allow_list: Option<PolicySource>,
+    pub max_risk_score: Option<EffectiveRiskLimit>,
+    pub ecc_fail_severity: Option<EffectiveEccThreshold>,
+}
+
+impl EffectivePolicy {
+    /// Layer `policies`, ordered from the broadest to the most specific
+    pub fn layer(policies: Vec<(PolicySource, Policy)>) -> Self {
+        let mut effective = EffectivePolicy {
+            layers: Vec::new(),
+            licenses: Vec::new(),
+            allow_list: None,
+            max_risk_score: None,
+            ecc_fail_severity: None,
+        };
+        let mut licenses: BTreeMap<String, EffectiveLicenseRule> = BTreeMap::new();
+        for (source, policy) in policies {
+            let allowed = policy.allowed();
+            if !allowed.is_empty() {
+                effective.allow_list = Some(source.clone());
+            }
+            // Denied last, so it wins should a license be on several lists
+            for (list, listing) in [
+                (allowed, Listing::Allowed),
+                (policy.needs_review(), Listing::NeedsReview),
+                (policy.denied(), Listing::Denied),
+            ] {
+                for license in list {
+                    let rule = EffectiveLicenseRule {
+                        license: license.clone(),
+                        listing,
+                        source: source.clone(),
+                    };
+                    licenses.insert(license.to_lowercase(), rule);
+                }
+            }
+            if let Some(max_risk_score) = policy.max_risk_score {
+                effective.max_risk_score = Some(EffectiveRiskLimit {
+                    max_risk_score,
+                    source: source.clone(),
+                });
+            }
+            if let Some(ecc_fail_severity) = policy.ecc_fail_severity {
+                effective.ecc_fail_severity = Some(EffectiveEccThreshold {
+                    ecc_fail_severity,
+                    source: source.clone(),
+                });
+            }
+            effective.layers.push(source);
+        }
+        effective.licenses = licenses.into_values().collect();
+        effective
+    }
+
+    /// Names of the layered policies, e.g. `baseline > distribution`
+    pub fn name(&self) -> String {
+        self.layers
+            .iter()
+            .map(|l| l.policy.as_str())
+            .collect::<Vec<_>>()
+            .join(" > ")
+    }
+}
+
+/// Check the findings and risk score of a scan against `policy`
+pub fn evaluate(
+    policy: &EffectivePolicy,
+    scan: &Scan,
+    results: &[ScanResult],
+) -> Vec<PolicyViolation> {
+    let listed: HashMap<String, &EffectiveLicenseRule> = policy
+        .licenses
+        .iter()
+        .map(|rule| (rule.license.to_lowercase(), rule))
+        .collect();
+
+    // Files per license and list, in license order, with the policy that
+    // decided
+    let mut licenses: BTreeMap<(String, &'static str), (&str, BTreeSet<String>)> = BTreeMap::new();
+    for result in results
+        .iter()
+        .filter(|r| r.result_type == "license" && r.review_status != "rejected")
+    {
+        let Some(base) = result
+            .license_spdx_id
+            .as_deref()
+            .or(result.license_name.as_deref())
+        else {
+            continue;
+        };
+        let label = match result.license_exception.as_deref() {
+            Some(exception) => format!("{} WITH {}", base, exception),
+            None => base.to_string(),
+        };
+        // The most specific identifier on any list decides, so an allowed
+        // `GPL-2.0-only WITH Classpath-exception-2.0` is not failed by a
+        // denied `GPL-2.0-only`
+        let keys = [
+            Some(label.clone()),
+            result.license_spdx_id.clone(),
+            result.license_name.clone(),
+        ];
+        let decided = keys
+            .iter()
+            .flatten()
+            .find_map(|key| listed.get(&key.to_lowercase()))
+            .map(|rule| (rule.listing, &rule.source));
+        let (listing, source) = match (decided, &policy.allow_list) {
+            (Some(decided), _) => decided,
+            (None, Some(allow_list)) => (Listing::Unlisted, allow_list),
+            (None, None) => continue,
+        };
+        let rule = match listing {
+            Listing::Allowed => continue,
+            Listing::Denied => "denied_license",
+            Listing::NeedsReview => "review_license",
+            Listing::Unlisted => "unlisted_license",
+        };
+        licenses
+            .entry((label, rule))
+            .or_insert_with(|| (source.policy.as_str(), BTreeSet::new()))
+            .1
+            .insert(relative_uri(&result.file_path, &scan.id));
+    }
+
+    let mut violations: Vec<PolicyViolation> = licenses
+        .into_iter()
+        .map(|((license, rule), (policy_name, files))| {
+            let (outcome, verdict) = match rule {
+                "denied_license" => (GateOutcome::Fail, "is denied by"),
+                "review_license" => (GateOutcome::Warn, "needs review under"),
+                _ => (GateOutcome::Fail, "is not allowed by"),
+            };
+            PolicyViolation {
+                rule: rule.to_string(),
+                outcome,
+                message: format!(
+                    "{} {} policy {} ({} files)",
+                    license,
+                    verdict,
+                    policy_name,
+                    files.len()
+                ),
+                license: Some(license),
+                files: files.into_iter().collect(),
+            }
+        })
+        .collect();
+    // Failures first
+    violations.sort_by_key(|v| std::cmp::Reverse(v.outcome));
+
+    if let (Some(limit), Some(score)) = (&policy.max_risk_score, scan.risk_score) {
+        if score > limit.max_risk_score {
+            violations.push(PolicyViolation {
+                rule: "risk_score".to_string(),
+                outcome: GateOutcome::Fail,
+                license: None,
+                message: format!(
+                    "Risk score {} exceeds the maximum of {} set by policy {}",
+                    score, limit.max_risk_score, limit.source.policy
+                ),
+                files: Vec::new(),
+            });
+        }
+    }
+
+    if let Some(threshold) = &policy.ecc_fail_severity {
+        let severity = threshold.ecc_fail_severity.as_str();
+        let ecc: Vec<&ScanResult> = results
+            .iter()
+            .filter(|r| {
+                r.result_type == "ecc"
+                    && r.review_status != "rejected"
+                    && severity_rank(r.risk_severity.as_deref().unwrap_or("")) >= severity_rank(severity)
+            })
+            .collect();
+        if !ecc.is_empty() {
+            let files: BTreeSet<String> = ecc
+                .iter()
+                .map(|r| relative_uri(&r.file_path, &scan.id))
+                .collect();
+            violations.push(PolicyViolation {
+                rule: "ecc_severity".to_string(),
+                outcome: GateOutcome::Fail,
+                license: None,
+                message: format!(
+                    "{} ECC findings of severity {} or higher, failing policy {}",
+                    ecc.len(),
+                    severity,
+                    threshold.source.policy
+                ),
+                files: files.into_iter().collect(),
+            });
+        }
+    }
+
+    violations
+}
+
+/// The worst outcome among `violations`, `pass` without any
+pub fn worst_outcome(violations: &[PolicyViolation]) -> GateOutcome {
+    violations
+        .iter()
+        .map(|v| v.outcome)
+        .max()
+        .unwrap_or(GateOutcome::Pass)
+}
+
+/// The effective policy of a scan: the organization's default, the policy
+/// of the first project (by name) of its repository that has one, and the
+/// scan's own, as far as they are set. `None` if none is.
+pub async fn resolve(pool: &SqlitePool, scan: &Scan) -> Result<Option<EffectivePolicy>, sqlx::Error> {
+    let mut layers = Vec::new();
+    if let Some(policy) = Policy::find_default(pool).await? {
+        let source = PolicySource {
+            level: PolicyLevel::Organization,
+            policy: policy.name.clone(),
+            project_id: None,
+        };
+        layers.push((source, policy));
+    }
+    let repository = normalize_repository_url(&scan.git_url);
+    for project in Project::find_by_repository(pool, &repository).await? {
+        let Some(name) = &project.policy else {
+            continue;
+        };
+        if let Some(policy) = Policy::find_by_name(pool, name).await? {
+            let source = PolicySource {
+                level: PolicyLevel::Project,
+                policy: policy.name.clone(),
+                project_id: Some(project.id.clone()),
+            };
+            layers.push((source, policy));
+        }
+        break;
+    }
+    if let Some(name) = &scan.policy {
+        if let Some(policy) = Policy::find_by_name(pool, name).await? {
+            let source = PolicySource {
+                level: PolicyLevel::Scan,
+                policy: policy.name.clone(),
+                project_id: None,
+            };
+            layers.push((source, policy));
+        }
+    }
+
+    if layers.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(EffectivePolicy::layer(layers)))
+}
+
+/// Evaluate a completed scan against the policy that applies to it and
+/// store the outcome. Without a policy, a stored evaluation is removed and
+/// `None` returned.
+pub async fn evaluate_scan(
+    state: &AppState,
+    scan_id: &str,
+) -> Result<Option<PolicyEvaluation>, AppError> {
+    let scan = Scan::find_by_id(&state.db, scan_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", scan_id)))?;
+    let Some(policy) = resolve(&state.db, &scan).await? else {
+        PolicyEvaluationRecord::delete(&state.db, scan_id).await?;
+        return Ok(None);
+    };
+
+    let mut results = state.result_store.load_results(scan_id).await?;
+    if waiver::mark(&state.db, &scan, &mut results).await? > 0 {
+        results.retain(|r| r.waiver_id.is_none());
+    }
+
+    let violations = evaluate(&policy, &scan, &results);
+    let outcome = worst_outcome(&violations);
+    let violations_json = serde_json::to_string(&violations).unwrap_or_else(|_| "[]".to_string());
+    let record = PolicyEvaluationRecord::replace(
+        &state.db,
+        scan_id,
+        &policy.name(),
+        outcome.as_str(),
+        &violations_json,
+    )
+    .await?;
+    tracing::info!(
+        "Scan {} evaluated against policy {}: {}",
+        scan_id,
+        policy.name(),
+        outcome.as_str()
+    );
+
+    Ok(Some(PolicyEvaluation::from_record(record)))
+}
+
+/// The stored evaluation of a scan
+pub async fn load(pool: &SqlitePool, scan_id: &str) -> Result<Option<PolicyEvaluation>, sqlx::Error> {
+    Ok(PolicyEvaluationRecord::find_by_scan_id(pool, scan_id)
+        .await?
+        .map(PolicyEvaluation::from_record))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named_policy(name: &str, allowed: &[&str], denied: &[&str], review: &[&str]) -> Policy {
+        let json = |list: &[&str]| serde_json::to_string(list).unwrap();
+        Policy {
+            name: name.to_string(),
+            description: None,
+            allowed_licenses: json(allowed),
+            denied_licenses: json(denied),
+            review_licenses: json(review),
+            max_risk_score: None,
+            ecc_fail_severity: None,
+            is_default: false,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn source(level: PolicyLevel, policy: &Policy) -> PolicySource {
+        PolicySource {
+            level,
+            policy: policy.name.clone(),
+            project_id: None,
+        }
+    }
+
+    /// A project policy on its own
+    fn single(policy: Policy) -> EffectivePolicy {
+        EffectivePolicy::layer(vec![(source(PolicyLevel::Project, &policy), policy)])
+    }
+
+    fn policy(allowed: &[&str], denied: &[&str], review: &[&str]) -> EffectivePolicy {
+        single(named_policy("strict", allowed, denied, review))
+    }
+
+    fn license(file: &str, spdx_id: &str, exception: Option<&str>) -> ScanResult {
+        ScanResult {
+            id: 0,
+            license_name: Some(spdx_id.to_string()),
+            license_spdx_id: Some(spdx_id.to_string()),
+            license_exception: exception.map(str::to_string),
+            confidence: Some(1.0),
+            ..ScanResult::for_test("license", file)
+        }
+    }
+
+    fn ecc(file: &str, severity: &str) -> ScanResult {
+        ScanResult {
+            result_type: "ecc".to_string(),
+            license_name: None,
+            license_spdx_id: None,
+            risk_severity: Some(severity.to_string()),
+            ..license(file, "", None)
+        }
+    }
+
+    fn scan(risk_score: Option<i32>) -> Scan {
+        Scan {
+            id: "scan-1".to_string(),
+            git_url: "https://github.com/org/repo".to_string(),
+            status: "completed".to_string(),
+            error_message: None,
+            created_at: "2025-01-01 00:00:00".to_string(),
+            started_at: None,
+            completed_at: None,
+            created_by_key_id: None,
+            git_token: None,
+            fossology_status: "completed".to_string(),
+            semgrep_status: "completed".to_string(),
+            fossology_started_at: None,
+            fossology_completed_at: None,
+            semgrep_started_at: None,
+            semgrep_completed_at: None,
+            fossology_error: None,
+            semgrep_error: None,
+            risk_score,
+            risk_level: None,
+            risk_factors: None,
+            risk_algorithm_version: None,
+            risk_config_version: None,
+            risk_assessed_at: None,
+            semgrep_rulesets: None,
+            quick: false,
+            approved_at: None,
+            reviewed_at: None,
+            warnings: None,
+            parent_scan_id: None,
+            due_diligence: false,
+            callback_url: None,
+            scan_path: None,
+            branch: None,
+            commit_sha: None,
+            legal_hold_at: None,
+            legal_hold_reason: None,
+            submodules: false,
+            submodule_token_encrypted: None,
+            credential_id: None,
+            git_authorship: false,
+            results_purged_at: None,
+            results_archived_at: None,
+            deleted_at: None,
+            policy: None,
+        }
+    }
+
+    fn rules(violations: &[PolicyViolation]) -> Vec<(&str, Option<&str>, GateOutcome)> {
+        violations
+            .iter()
+            .map(|v| (v.rule.as_str(), v.license.as_deref(), v.outcome))
+            .collect()
+    }
+
+    #[test]
+    fn test_license_lists() {
+        let results = vec![
+            license("src/a.rs", "MIT", None),
+            license("src/b.rs", "GPL-3.0-only", None),
+            license("src/c.rs", "gpl-3.0-only", None),
+            license("src/d.rs", "MPL-2.0", None),
+            license("src/e.rs", "Zlib", None),
+        ];
+
+        let violations = evaluate(
+            &policy(&[], &["GPL-3.0-only"], &["MPL-2.0"]),
+            &scan(None),
+            &results,
+        );
+        assert_eq!(
+            rules(&violations),
+            vec![
+                ("denied_license", Some("GPL-3.0-only"), GateOutcome::Fail),
+                ("denied_license", Some("gpl-3.0-only"), GateOutcome::Fail),
+                ("review_license", Some("MPL-2.0"), GateOutcome::Warn),
+            ]
+        );
+        assert_eq!(worst_outcome(&violations), GateOutcome::Fail);
+
+        // With an allow list, anything not on a list fails
+        let violations = evaluate(&policy(&["mit"], &[], &["MPL-2.0"]), &scan(None), &results);
+        assert_eq!(
+            rules(&violations),
+            vec![
+                ("unlisted_license", Some("GPL-3.0-only"), GateOutcome::Fail),
+                ("unlisted_license", Some("Zlib"), GateOutcome::Fail),
+                ("unlisted_license", Some("gpl-3.0-only"), GateOutcome::Fail),
+                ("review_license", Some("MPL-2.0"), GateOutcome::Warn),
+            ]
+        );
+
+        let violations = evaluate(&policy(&[], &[], &["MPL-2.0"]), &scan(None), &results);
+        assert_eq!(worst_outcome(&violations), GateOutcome::Warn);
+        assert_eq!(violations[0].files, vec!["src/d.rs"]);
+    }
+
+    #[test]
+    fn test_exception_is_more_specific() {
+        let results = vec![
+            license("src/a.java", "GPL-2.0-only", Some("Classpath-exception-2.0")),
+            license("src/b.java", "GPL-2.0-only", None),
+        ];
+        let violations = evaluate(
+            &policy(
+                &["GPL-2.0-only WITH Classpath-exception-2.0"],
+                &["GPL-2.0-only"],
+                &[],
+            ),
+            &scan(None),
+            &results,
+        );
+        assert_eq!(
+            rules(&violations),
+            vec![("denied_license", Some("GPL-2.0-only"), GateOutcome::Fail)]
+        );
+        assert_eq!(violations[0].files, vec!["src/b.java"]);
+    }
+
+    #[test]
+    fn test_rejected_findings_are_ignored() {
+        let mut rejected = license("src/a.rs", "GPL-3.0-only", None);
+        rejected.review_status = "rejected".to_string();
+        let violations = evaluate(&policy(&[], &["GPL-3.0-only"], &[]), &scan(None), &[rejected]);
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let mut strict = named_policy("strict", &[], &[], &[]);
+        strict.max_risk_score = Some(40);
+        strict.ecc_fail_severity = Some("high".to_string());
+        let strict = single(strict);
+        let results = vec![
+            ecc("src/crypto.rs", "critical"),
+            ecc("src/crypto.rs", "high"),
+            ecc("src/hash.rs", "medium"),
+        ];
+
+        let violations = evaluate(&strict, &scan(Some(55)), &results);
+        assert_eq!(
+            rules(&violations),
+            vec![
+                ("risk_score", None, GateOutcome::Fail),
+                ("ecc_severity", None, GateOutcome::Fail),
+            ]
+        );
+        assert_eq!(violations[1].files, vec!["src/crypto.rs"]);
+
+        assert!(evaluate(&strict, &scan(Some(40)), &results[2..]).is_empty());
+    }
+
+    #[test]
+    fn test_layers_override_rule_by_rule() {
+        let mut baseline = named_policy("baseline", &[], &["GPL-3.0-only", "MPL-2.0"], &[]);
+        baseline.max_risk_score = Some(50);
+        baseline.ecc_fail_severity = Some("critical".to_string());
+        let mut distribution = named_policy("distribution", &[], &[], &["MPL-2.0"]);
+        distribution.ecc_fail_severity = Some("high".to_string());
+        let exemption = named_policy("exemption", &["gpl-3.0-only"], &[], &[]);
+        let effective = EffectivePolicy::layer(vec![
+            (source(PolicyLevel::Organization, &baseline), baseline),
+            (source(PolicyLevel::Project, &distribution), distribution),
+            (source(PolicyLevel::Scan, &exemption), exemption),
+        ]);
+        assert_eq!(effective.name(), "baseline > distribution > exemption");
+
+        let listings: Vec<(&str, Listing, &str)> = effective
+            .licenses
+            .iter()
+            .map(|r| (r.license.as_str(), r.listing, r.source.policy.as_str()))
+            .collect();
+        assert_eq!(
+            listings,
+            vec![
+                ("gpl-3.0-only", Listing::Allowed, "exemption"),
+                ("MPL-2.0", Listing::NeedsReview, "distribution"),
+            ]
+        );
+        assert_eq!(effective.allow_list.as_ref().unwrap().level, PolicyLevel::Scan);
+        let limit = effective.max_risk_score.as_ref().unwrap();
+        assert_eq!((limit.max_risk_score, limit.source.level), (50, PolicyLevel::Organization));
+        let ecc = effective.ecc_fail_severity.as_ref().unwrap();
+        assert_eq!(ecc.ecc_fail_severity, "high");
+        assert_eq!(ecc.source.level, PolicyLevel::Project);
+
+        let results = vec![
+            license("src/a.rs", "GPL-3.0-only", None),
+            license("src/b.rs", "MPL-2.0", None),
+            license("src/c.rs", "Zlib", None),
+        ];
+        let violations = evaluate(&effective, &scan(Some(60)), &results);
+        assert_eq!(
+            rules(&violations),
+            vec![
+                ("unlisted_license", Some("Zlib"), GateOutcome::Fail),
+                ("review_license", Some("MPL-2.0"), GateOutcome::Warn),
+                ("risk_score", None, GateOutcome::Fail),
+            ]
+        );
+        assert!(violations[0].message.contains("policy exemption"));
+        assert!(violations[1].message.contains("policy distribution"));
+        assert!(violations[2].message.contains("policy baseline"));
+    }
+}
@@ -0,0 +1,173 @@
+//! Live scan progress for `GET /api/v1/scans/:id/events`. Timeline events
+//! and scanner progress messages (upload percentage, Fossology job ETA) are
+//! broadcast to subscribers as they happen. Only timeline events are stored;
+//! progress messages are lost if nobody is listening.
+
+use crate::db::models::ScanEvent;
+use crate::AppState;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// Subscribers that fall this many updates behind skip the missed ones
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Event name of scanner progress messages, which are not timeline events
+pub const PROGRESS_EVENT: &str = "progress";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressUpdate {
+    pub scan_id: String,
+    /// Timeline event type, or `progress` for scanner progress messages
+    pub event: String,
+    /// Timeline event ID, absent for progress messages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scanner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_seconds: Option<i64>,
+}
+
+impl ProgressUpdate {
+    pub fn from_event(event: &ScanEvent) -> Self {
+        Self {
+            scan_id: event.scan_id.clone(),
+            event: event.event_type.clone(),
+            event_id: Some(event.id),
+            scanner: event.scanner.clone(),
+            message: event.detail.clone(),
+            percent: None,
+            eta_seconds: None,
+        }
+    }
+
+    /// The scan job has ended, nothing follows this update
+    pub fn is_terminal(&self) -> bool {
+        self.event == "completed" || self.event == "failed"
+    }
+}
+
+/// Broadcast channel shared by all scans; subscribers filter by scan ID
+#[derive(Clone)]
+pub struct ProgressHub {
+    sender: broadcast::Sender<ProgressUpdate>,
+}
+
+impl Default for ProgressHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn publish(&self, update: ProgressUpdate) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.sender.send(update);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ProgressUpdate> {
+        self.sender.subscribe()
+    }
+
+    /// Reporter for one scanner of one scan
+    pub fn reporter(&self, scan_id: &str, scanner: &str) -> ProgressReporter {
+        ProgressReporter {
+            target: Some((self.clone(), scan_id.to_string(), scanner.to_string())),
+        }
+    }
+}
+
+/// Handed to scanners to report intermediate progress. A disabled reporter
+/// drops everything, for scans run outside a scan job.
+#[derive(Clone, Default)]
+pub struct ProgressReporter {
+    target: Option<(ProgressHub, String, String)>,
+}
+
+impl ProgressReporter {
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn message(&self, message: &str) {
+        self.send(message, None, None);
+    }
+
+    pub fn percent(&self, message: &str, percent: u8) {
+        self.send(message, Some(percent.min(100)), None);
+    }
+
+    pub fn eta(&self, message: &str, eta_seconds: i64) {
+        self.send(message, None, Some(eta_seconds));
+    }
+
+    fn send(&self, message: &str, percent: Option<u8>, eta_seconds: Option<i64>) {
+        let Some((hub, scan_id, scanner)) = &self.target else {
+            return;
+        };
+        hub.publish(ProgressUpdate {
+            scan_id: scan_id.clone(),
+            event: PROGRESS_EVENT.to_string(),
+            event_id: None,
+            scanner: Some(scanner.clone()),
+            message: Some(message.to_string()),
+            percent,
+            eta_seconds,
+        });
+    }
+}
+
+/// Record a timeline event and broadcast it to live subscribers
+pub async fn record_event(
+    state: &AppState,
+    scan_id: &str,
+    event_type: &str,
+    scanner: Option<&str>,
+    detail: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let recorded = ScanEvent::record(&state.db, scan_id, event_type, scanner, detail).await;
+
+    state.progress.publish(ProgressUpdate {
+        scan_id: scan_id.to_string(),
+        event: event_type.to_string(),
+        event_id: recorded.as_ref().ok().copied(),
+        scanner: scanner.map(str::to_string),
+        message: detail.map(str::to_string),
+        percent: None,
+        eta_seconds: None,
+    });
+
+    recorded.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reporter_publishes_to_subscribers() {
+        let hub = ProgressHub::new();
+        let mut receiver = hub.subscribe();
+
+        hub.reporter("scan-1", "fossology")
+            .percent("Uploading to Fossology", 140);
+        ProgressReporter::disabled().message("dropped");
+
+        let update = receiver.recv().await.unwrap();
+        assert_eq!(update.scan_id, "scan-1");
+        assert_eq!(update.event, PROGRESS_EVENT);
+        assert_eq!(update.scanner.as_deref(), Some("fossology"));
+        assert_eq!(update.percent, Some(100));
+        assert!(!update.is_terminal());
+        assert!(receiver.try_recv().is_err());
+    }
+}
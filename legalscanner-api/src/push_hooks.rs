@@ -0,0 +1,245 @@
+//! Rescans on git push. GitHub and GitLab push webhooks pointed at
+//! `POST /api/v1/hooks/github` and `POST /api/v1/hooks/gitlab` start a scan
+//! of the pushed branch when the repository belongs to a project, so the
+//! scans keep up with the code without a CI step.
+//!
+//! Deliveries are only accepted with `PUSH_WEBHOOK_SECRET` set: GitHub signs
+//! the body with it (`X-Hub-Signature-256`), GitLab sends it as is
+//! (`X-Gitlab-Token`). Tag pushes, branch deletions and other events are
+//! acknowledged and ignored.
+
+use crate::export::processors::HmacSigner;
+use axum::http::HeaderMap;
+use serde::Deserialize;
+
+/// GitHub event name, e.g. `push` or `ping`
+pub const GITHUB_EVENT_HEADER: &str = "X-GitHub-Event";
+
+/// `sha256=` and the HMAC-SHA256 of the body, in hex
+pub const GITHUB_SIGNATURE_HEADER: &str = "X-Hub-Signature-256";
+
+/// GitLab event name, e.g. `Push Hook`
+pub const GITLAB_EVENT_HEADER: &str = "X-Gitlab-Event";
+
+/// Secret token configured on the GitLab webhook
+pub const GITLAB_TOKEN_HEADER: &str = "X-Gitlab-Token";
+
+/// Commit a deleted branch points to in a push event
+const NULL_COMMIT: &str = "0000000000000000000000000000000000000000";
+
+/// Provider sending push webhooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushProvider {
+    GitHub,
+    GitLab,
+}
+
+impl PushProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PushProvider::GitHub => "github",
+            PushProvider::GitLab => "gitlab",
+        }
+    }
+}
+
+/// Branch push reported by a webhook delivery
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Push {
+    /// Clone URL of the repository
+    pub repository: String,
+    pub branch: String,
+    /// Commit the branch was pushed to
+    pub commit: String,
+}
+
+/// What a webhook delivery asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookEvent {
+    Push(Push),
+    /// Nothing to scan, with the reason
+    Ignored(String),
+}
+
+#[derive(Deserialize)]
+struct GithubPush {
+    #[serde(rename = "ref")]
+    git_ref: String,
+    after: String,
+    #[serde(default)]
+    deleted: bool,
+    repository: GithubRepository,
+}
+
+#[derive(Deserialize)]
+struct GithubRepository {
+    clone_url: String,
+}
+
+#[derive(Deserialize)]
+struct GitlabPush {
+    #[serde(rename = "ref")]
+    git_ref: String,
+    after: String,
+    project: GitlabProject,
+}
+
+#[derive(Deserialize)]
+struct GitlabProject {
+    git_http_url: String,
+}
+
+/// Whether the delivery carries `secret`
+pub fn verify(provider: PushProvider, headers: &HeaderMap, body: &[u8], secret: &str) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    match provider {
+        PushProvider::GitHub => header(GITHUB_SIGNATURE_HEADER)
+            .and_then(|s| s.strip_prefix("sha256="))
+            .is_some_and(|signature| {
+                let expected = HmacSigner::new(secret).sign(body);
+                constant_time_eq(signature.to_lowercase().as_bytes(), expected.as_bytes())
+            }),
+        PushProvider::GitLab => header(GITLAB_TOKEN_HEADER)
+            .is_some_and(|token| constant_time_eq(token.as_bytes(), secret.as_bytes())),
+    }
+}
+
+/// The push a verified delivery reports, if it is one
+pub fn parse(
+    provider: PushProvider,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<HookEvent, String> {
+    let event = headers
+        .get(event_header(provider))
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    let (git_ref, commit, repository, deleted) = match provider {
+        PushProvider::GitHub if event == "push" => {
+            let push: GithubPush = serde_json::from_slice(body)
+                .map_err(|e| format!("Invalid GitHub push event: {}", e))?;
+            (
+                push.git_ref,
+                push.after,
+                push.repository.clone_url,
+                push.deleted,
+            )
+        }
+        PushProvider::GitLab if event == "Push Hook" => {
+            let push: GitlabPush = serde_json::from_slice(body)
+                .map_err(|e| format!("Invalid GitLab push event: {}", e))?;
+            let deleted = push.after == NULL_COMMIT;
+            (push.git_ref, push.after, push.project.git_http_url, deleted)
+        }
+        _ if event.is_empty() => {
+            return Err(format!("Missing {} header", event_header(provider)));
+        }
+        _ => {
+            return Ok(HookEvent::Ignored(format!(
+                "{} events are not scanned",
+                event
+            )))
+        }
+    };
+
+    let Some(branch) = git_ref.strip_prefix("refs/heads/") else {
+        return Ok(HookEvent::Ignored(format!("{} is not a branch", git_ref)));
+    };
+    if deleted {
+        return Ok(HookEvent::Ignored(format!("Branch {} was deleted", branch)));
+    }
+    Ok(HookEvent::Push(Push {
+        repository,
+        branch: branch.to_string(),
+        commit,
+    }))
+}
+
+fn event_header(provider: PushProvider) -> &'static str {
+    match provider {
+        PushProvider::GitHub => GITHUB_EVENT_HEADER,
+        PushProvider::GitLab => GITLAB_EVENT_HEADER,
+    }
+}
+
+/// Compare secrets without giving away through timing how much matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_verify_github_signature() {
+        let body = br#"{"zen":"Keep it logically awesome."}"#;
+        let signature = format!("sha256={}", HmacSigner::new("s3cret").sign(body));
+        let signed = headers(&[(GITHUB_SIGNATURE_HEADER, &signature)]);
+        assert!(verify(PushProvider::GitHub, &signed, body, "s3cret"));
+        assert!(!verify(PushProvider::GitHub, &signed, body, "other"));
+        assert!(!verify(PushProvider::GitHub, &signed, b"{}", "s3cret"));
+        assert!(!verify(
+            PushProvider::GitHub,
+            &HeaderMap::new(),
+            body,
+            "s3cret"
+        ));
+
+        let token = headers(&[(GITLAB_TOKEN_HEADER, "s3cret")]);
+        assert!(verify(PushProvider::GitLab, &token, body, "s3cret"));
+        assert!(!verify(PushProvider::GitLab, &token, body, "s3cre"));
+    }
+
+    #[test]
+    fn test_parse_push_events() {
+        let github = headers(&[(GITHUB_EVENT_HEADER, "push")]);
+        let body = br#"{
+            "ref": "refs/heads/main",
+            "after": "309cf2674ee7a0749978cf8265ab91a60aea0f7d",
+            "deleted": false,
+            "repository": { "clone_url": "https://github.com/acme/app.git" }
+        }"#;
+        assert_eq!(
+            parse(PushProvider::GitHub, &github, body).unwrap(),
+            HookEvent::Push(Push {
+                repository: "https://github.com/acme/app.git".to_string(),
+                branch: "main".to_string(),
+                commit: "309cf2674ee7a0749978cf8265ab91a60aea0f7d".to_string(),
+            })
+        );
+        let tag = br#"{"ref": "refs/tags/v1.0", "after": "309cf26",
+            "repository": { "clone_url": "https://github.com/acme/app.git" }}"#;
+        assert_eq!(
+            parse(PushProvider::GitHub, &github, tag).unwrap(),
+            HookEvent::Ignored("refs/tags/v1.0 is not a branch".to_string())
+        );
+        let ping = headers(&[(GITHUB_EVENT_HEADER, "ping")]);
+        assert!(matches!(
+            parse(PushProvider::GitHub, &ping, b"{}").unwrap(),
+            HookEvent::Ignored(_)
+        ));
+        assert!(parse(PushProvider::GitHub, &HeaderMap::new(), body).is_err());
+
+        let gitlab = headers(&[(GITLAB_EVENT_HEADER, "Push Hook")]);
+        let deleted = format!(
+            r#"{{"ref": "refs/heads/feature", "after": "{}",
+                "project": {{ "git_http_url": "https://gitlab.com/acme/app.git" }}}}"#,
+            NULL_COMMIT
+        );
+        assert_eq!(
+            parse(PushProvider::GitLab, &gitlab, deleted.as_bytes()).unwrap(),
+            HookEvent::Ignored("Branch feature was deleted".to_string())
+        );
+    }
+}
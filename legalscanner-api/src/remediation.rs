@@ -0,0 +1,294 @@
+//! Remediation guidance for risk factors: for each factor category, the
+//! actions that bring it down, most direct first. The guidance is looked up
+//! when an assessment is returned rather than stored with it, so stored
+//! assessments pick up revised guidance. Action IDs are stable and meant for
+//! tooling, e.g. to open a ticket per action.
+
+use crate::api::models::RiskFactor;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A suggested action
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RemediationAction {
+    /// Stable action ID, e.g. `add_license_file`
+    pub id: String,
+    pub description: String,
+    /// API endpoint that carries the action out, where there is one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+/// Guidance for one risk factor category
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Remediation {
+    /// Risk factor category, e.g. `copyleft_license`
+    pub category: String,
+    pub summary: String,
+    pub actions: Vec<RemediationAction>,
+}
+
+/// Action ID, description and endpoint
+type Action = (&'static str, &'static str, Option<&'static str>);
+
+const REVIEW_FINDINGS: Action = (
+    "review_findings",
+    "Review the findings and reject the false positives",
+    Some("POST /api/v1/scans/:id/results/review"),
+);
+const CURATE_LICENSE: Action = (
+    "curate_license",
+    "Record the correct license of the affected files as a curation, so later scans use it",
+    Some("POST /api/v1/curations"),
+);
+const REPLACE_DEPENDENCY: Action = (
+    "replace_dependency",
+    "Replace the affected component with an alternative under a permissive license",
+    None,
+);
+const LEGAL_REVIEW: Action = (
+    "legal_review",
+    "Ask legal to review how the affected code is used and distributed",
+    None,
+);
+const WAIVE: Action = (
+    "waive_findings",
+    "Waive findings legal has accepted, with a justification",
+    Some("POST /api/v1/waivers"),
+);
+
+/// Category, summary and actions of every risk factor category
+const GUIDANCE: &[(&str, &str, &[Action])] = &[
+    (
+        "malware",
+        "Remove the malicious files before anything else",
+        &[
+            (
+                "remove_malware",
+                "Delete the flagged files from the repository and its history, and find out how they got there",
+                None,
+            ),
+            (
+                "rescan",
+                "Scan the repository again once the files are gone",
+                Some("POST /api/v1/scans/:id/rerun"),
+            ),
+        ],
+    ),
+    (
+        "copyleft_license",
+        "Make sure distributing the product does not oblige you to release its source",
+        &[
+            REPLACE_DEPENDENCY,
+            (
+                "isolate_component",
+                "Keep the copyleft code in a separate program or service instead of linking it into yours",
+                None,
+            ),
+            LEGAL_REVIEW,
+            (
+                "approve_scan",
+                "Approve the scan once legal has accepted the licenses",
+                Some("POST /api/v1/scans/:id/approve"),
+            ),
+        ],
+    ),
+    (
+        "copyleft_license_exception",
+        "Confirm the license exception covers how the code is used",
+        &[
+            (
+                "check_exception_scope",
+                "Check that the code is only linked or used the way the exception permits, e.g. unmodified",
+                None,
+            ),
+            LEGAL_REVIEW,
+        ],
+    ),
+    (
+        "unknown_license",
+        "Find out under which terms the affected code may be used",
+        &[
+            (
+                "add_license_file",
+                "Add a LICENSE file with the project's license at the repository root",
+                None,
+            ),
+            (
+                "identify_license",
+                "Look up the license of the affected code where it came from, or ask its copyright holder",
+                None,
+            ),
+            CURATE_LICENSE,
+            REPLACE_DEPENDENCY,
+        ],
+    ),
+    (
+        "model_license",
+        "Check the use restrictions of the model licenses",
+        &[
+            (
+                "review_model_terms",
+                "Review the acceptable use policy and distribution terms of each model license",
+                None,
+            ),
+            LEGAL_REVIEW,
+            WAIVE,
+        ],
+    ),
+    (
+        "missing_spdx_id",
+        "Make the licenses machine readable",
+        &[
+            (
+                "add_spdx_headers",
+                "Add SPDX-License-Identifier headers to the affected files",
+                None,
+            ),
+            CURATE_LICENSE,
+        ],
+    ),
+    (
+        "low_confidence",
+        "Confirm the licenses the scanners were unsure of",
+        &[REVIEW_FINDINGS, CURATE_LICENSE],
+    ),
+    (
+        "license_diversity",
+        "Keep the set of licenses to comply with manageable",
+        &[
+            (
+                "consolidate_dependencies",
+                "Consolidate dependencies that do the same job under different licenses",
+                None,
+            ),
+            (
+                "ship_notice",
+                "Ship a NOTICE file with the attributions of every license",
+                Some("GET /api/v1/scans/:id/notice"),
+            ),
+        ],
+    ),
+    (
+        "ecc_critical_high",
+        "Clear the cryptography with export control before shipping",
+        &[
+            (
+                "submit_screening",
+                "Submit the scan for export control screening",
+                Some("POST /api/v1/scans/:id/screening"),
+            ),
+            (
+                "classify_cryptography",
+                "Have export compliance classify the cryptographic functionality (ECCN)",
+                None,
+            ),
+            (
+                "remove_cryptography",
+                "Remove cryptography the product does not need",
+                None,
+            ),
+        ],
+    ),
+    (
+        "ecc_medium_low",
+        "Confirm the lower-severity export control matches",
+        &[REVIEW_FINDINGS, WAIVE],
+    ),
+    (
+        "ecc_non_shipped",
+        "Confirm the matched code is not shipped",
+        &[
+            (
+                "confirm_not_shipped",
+                "Check that the affected tests, examples and tooling are left out of the product",
+                None,
+            ),
+            WAIVE,
+        ],
+    ),
+];
+
+/// Guidance for a risk factor category, if there is any
+pub fn for_category(category: &str) -> Option<Remediation> {
+    GUIDANCE
+        .iter()
+        .find(|(c, _, _)| *c == category)
+        .map(|(category, summary, actions)| Remediation {
+            category: category.to_string(),
+            summary: summary.to_string(),
+            actions: actions
+                .iter()
+                .map(|(id, description, endpoint)| RemediationAction {
+                    id: id.to_string(),
+                    description: description.to_string(),
+                    endpoint: endpoint.map(str::to_string),
+                })
+                .collect(),
+        })
+}
+
+/// Guidance for each category among the factors, in factor order
+pub fn for_factors(factors: &[RiskFactor]) -> Vec<Remediation> {
+    let mut remediation: Vec<Remediation> = Vec::new();
+    for factor in factors {
+        if remediation.iter().any(|r| r.category == factor.category) {
+            continue;
+        }
+        remediation.extend(for_category(&factor.category));
+    }
+    remediation
+}
+
+/// Guidance for every category
+pub fn all() -> Vec<Remediation> {
+    GUIDANCE
+        .iter()
+        .filter_map(|(category, _, _)| for_category(category))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn factor(category: &str) -> RiskFactor {
+        RiskFactor {
+            category: category.to_string(),
+            severity: "high".to_string(),
+            description: String::new(),
+            affected_count: 1,
+            details: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_for_factors() {
+        let remediation = for_factors(&[
+            factor("unknown_license"),
+            factor("no_such_category"),
+            factor("malware"),
+            factor("unknown_license"),
+        ]);
+        let categories: Vec<&str> = remediation.iter().map(|r| r.category.as_str()).collect();
+        assert_eq!(categories, vec!["unknown_license", "malware"]);
+        assert_eq!(remediation[0].actions[0].id, "add_license_file");
+    }
+
+    #[test]
+    fn test_guidance_is_unique() {
+        let categories: HashSet<&str> = GUIDANCE.iter().map(|(c, _, _)| *c).collect();
+        assert_eq!(categories.len(), GUIDANCE.len());
+        for remediation in all() {
+            assert!(!remediation.actions.is_empty(), "{}", remediation.category);
+            let ids: HashSet<&str> = remediation.actions.iter().map(|a| a.id.as_str()).collect();
+            assert_eq!(
+                ids.len(),
+                remediation.actions.len(),
+                "{}",
+                remediation.category
+            );
+        }
+    }
+}
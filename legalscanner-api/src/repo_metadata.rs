@@ -0,0 +1,484 @@
+//! Repository metadata captured when a scan clones the repository. The
+//! default branch, latest tag and the checked-out commit (SHA, date, author,
+//! tag, working tree size) are read from the checkout; for GitHub and
+//! GitLab repositories scanned with a token, the provider's API adds the
+//! display name, description, topics and contributor count. Reports show
+//! the metadata and projects created without a name are named after it.
+
+use crate::config::Config;
+use crate::db::models::{CommitProvenance, Scan, ScanRepositoryMetadata};
+use crate::AppState;
+use git2::Repository;
+use reqwest::header::{ACCEPT, LINK};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use std::path::Path;
+use std::time::Duration;
+
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Metadata of a repository, before it is stored with a scan
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepositoryMetadata {
+    /// `github` or `gitlab` when the provider's API was read
+    pub provider: Option<&'static str>,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub default_branch: Option<String>,
+    pub topics: Vec<String>,
+    pub latest_tag: Option<String>,
+    pub contributor_count: Option<i64>,
+    pub commit: CommitProvenance,
+}
+
+/// Store the metadata of a freshly cloned repository with its scan. The
+/// provider API is only queried with a token, the scan's or `GIT_TOKEN`.
+/// Best-effort: a failure only costs the metadata.
+pub async fn record(
+    state: &AppState,
+    scan_id: &str,
+    git_url: &str,
+    token: Option<&str>,
+    repo_root: &Path,
+) {
+    let mut metadata = read_checkout(repo_root).await;
+
+    let token = token
+        .map(str::to_string)
+        .or_else(|| std::env::var("GIT_TOKEN").ok())
+        .filter(|t| !t.is_empty());
+    if let (Some(api), Some(token)) = (ProviderApi::detect(git_url, &state.config), token) {
+        match api.fetch(&token).await {
+            Ok(remote) => {
+                metadata = RepositoryMetadata {
+                    default_branch: remote.default_branch.or(metadata.default_branch),
+                    latest_tag: metadata.latest_tag,
+                    commit: metadata.commit,
+                    ..remote
+                }
+            }
+            Err(e) => tracing::warn!(
+                "Failed to read {} metadata for scan {}: {}",
+                api.provider,
+                scan_id,
+                e
+            ),
+        }
+    }
+
+    match ScanRepositoryMetadata::upsert(
+        &state.db,
+        scan_id,
+        metadata.provider,
+        metadata.display_name.as_deref(),
+        metadata.description.as_deref(),
+        metadata.default_branch.as_deref(),
+        &metadata.topics,
+        metadata.latest_tag.as_deref(),
+        metadata.contributor_count,
+        &metadata.commit,
+    )
+    .await
+    {
+        Ok(_) => tracing::info!("Recorded repository metadata for scan {}", scan_id),
+        Err(e) => tracing::error!(
+            "Failed to store repository metadata for scan {}: {}",
+            scan_id,
+            e
+        ),
+    }
+}
+
+/// Metadata of the newest scan of a normalized repository URL that has any
+pub async fn latest_for_repository(
+    pool: &SqlitePool,
+    repository: &str,
+) -> Result<Option<ScanRepositoryMetadata>, sqlx::Error> {
+    let scans = Scan::find_by_repositories(pool, &[repository.to_string()]).await?;
+    for scan in scans.iter().rev() {
+        if let Some(metadata) = ScanRepositoryMetadata::find_by_scan_id(pool, &scan.id).await? {
+            return Ok(Some(metadata));
+        }
+    }
+    Ok(None)
+}
+
+/// Default branch, latest tag and checked-out commit of a checkout. Missing
+/// pieces are left as `None`.
+pub async fn read_checkout(path: &Path) -> RepositoryMetadata {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let Ok(repo) = Repository::open(&path) else {
+            return RepositoryMetadata::default();
+        };
+
+        RepositoryMetadata {
+            default_branch: default_branch(&repo),
+            latest_tag: latest_tag(&repo),
+            commit: commit_provenance(&repo, &path),
+            ..Default::default()
+        }
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Branch `origin/HEAD` points to, else the checked-out branch
+fn default_branch(repo: &Repository) -> Option<String> {
+    repo.find_reference("refs/remotes/origin/HEAD")
+        .ok()
+        .and_then(|r| {
+            r.symbolic_target()
+                .map(|t| t.trim_start_matches("refs/remotes/origin/").to_string())
+        })
+        .or_else(|| {
+            repo.head()
+                .ok()
+                .filter(|h| h.is_branch())
+                .and_then(|h| h.shorthand().map(str::to_string))
+        })
+}
+
+/// Tag of the most recent tagged commit
+fn latest_tag(repo: &Repository) -> Option<String> {
+    let names = repo.tag_names(None).ok()?;
+    names
+        .iter()
+        .flatten()
+        .filter_map(|name| {
+            let commit = repo
+                .revparse_single(&format!("refs/tags/{}", name))
+                .ok()?
+                .peel_to_commit()
+                .ok()?;
+            Some((commit.time().seconds(), name.to_string()))
+        })
+        .max()
+        .map(|(_, name)| name)
+}
+
+/// HEAD commit of a checkout, a tag pointing at it and the size of the
+/// working tree
+fn commit_provenance(repo: &Repository, path: &Path) -> CommitProvenance {
+    let head = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let head_tag = head.as_ref().and_then(|head| {
+        let names = repo.tag_names(None).ok()?;
+        names
+            .iter()
+            .flatten()
+            .filter(|name| {
+                repo.revparse_single(&format!("refs/tags/{}", name))
+                    .and_then(|tag| tag.peel_to_commit())
+                    .is_ok_and(|commit| commit.id() == head.id())
+            })
+            .max()
+            .map(str::to_string)
+    });
+    let (size_bytes, file_count) = working_tree_size(path);
+
+    CommitProvenance {
+        commit_sha: head.as_ref().map(|c| c.id().to_string()),
+        commit_date: head.as_ref().and_then(|c| {
+            chrono::DateTime::from_timestamp(c.time().seconds(), 0)
+                .map(|t| t.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        }),
+        commit_author: head
+            .as_ref()
+            .and_then(|c| c.author().name().map(str::to_string))
+            .filter(|name| !name.is_empty()),
+        head_tag,
+        size_bytes: Some(size_bytes),
+        file_count: Some(file_count),
+    }
+}
+
+/// Bytes and number of the regular files under `root`, skipping `.git` and
+/// not following symbolic links
+fn working_tree_size(root: &Path) -> (i64, i64) {
+    let (mut bytes, mut files) = (0, 0);
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if entry.file_name() != ".git" {
+                    pending.push(entry.path());
+                }
+            } else if file_type.is_file() {
+                bytes += entry.metadata().map(|m| m.len() as i64).unwrap_or(0);
+                files += 1;
+            }
+        }
+    }
+    (bytes, files)
+}
+
+/// REST API of the provider hosting a repository
+#[derive(Debug, PartialEq, Eq)]
+struct ProviderApi {
+    provider: &'static str,
+    base_url: String,
+    /// `owner/repo`, or `group/subgroup/project` on GitLab
+    project: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubRepository {
+    name: Option<String>,
+    description: Option<String>,
+    default_branch: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabProject {
+    name: Option<String>,
+    description: Option<String>,
+    default_branch: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+    // Before GitLab 14.5
+    #[serde(default)]
+    tag_list: Vec<String>,
+}
+
+impl ProviderApi {
+    /// API for an HTTP(S) URL on github.com, gitlab.com or a host named
+    /// `gitlab.*`; `None` for other hosts and SSH URLs
+    fn detect(git_url: &str, config: &Config) -> Option<ProviderApi> {
+        let url = Url::parse(git_url.trim()).ok()?;
+        if !matches!(url.scheme(), "https" | "http") {
+            return None;
+        }
+        let host = url.host_str()?.to_lowercase();
+        let project = url
+            .path()
+            .trim_matches('/')
+            .trim_end_matches(".git")
+            .to_string();
+        if !project.contains('/') {
+            return None;
+        }
+
+        if host == "github.com" || host == "www.github.com" {
+            Some(ProviderApi {
+                provider: "github",
+                base_url: config.github_api_url.trim_end_matches('/').to_string(),
+                project,
+            })
+        } else if host == "gitlab.com" || host.starts_with("gitlab.") {
+            Some(ProviderApi {
+                provider: "gitlab",
+                base_url: format!("{}/api/v4", url.origin().ascii_serialization()),
+                project,
+            })
+        } else {
+            None
+        }
+    }
+
+    async fn fetch(&self, token: &str) -> Result<RepositoryMetadata, reqwest::Error> {
+        let client = Client::builder()
+            .timeout(PROVIDER_TIMEOUT)
+            .user_agent(concat!("legalscanner/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        match self.provider {
+            "github" => self.fetch_github(&client, token).await,
+            _ => self.fetch_gitlab(&client, token).await,
+        }
+    }
+
+    async fn fetch_github(
+        &self,
+        client: &Client,
+        token: &str,
+    ) -> Result<RepositoryMetadata, reqwest::Error> {
+        let github = |url: String| {
+            client
+                .get(url)
+                .bearer_auth(token)
+                .header(ACCEPT, "application/vnd.github+json")
+        };
+
+        let repository: GitHubRepository =
+            github(format!("{}/repos/{}", self.base_url, self.project))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+        let contributors = github(format!(
+            "{}/repos/{}/contributors?per_page=1&anon=true",
+            self.base_url, self.project
+        ));
+
+        Ok(RepositoryMetadata {
+            provider: Some(self.provider),
+            display_name: repository.name,
+            description: repository.description.filter(|d| !d.is_empty()),
+            default_branch: repository.default_branch,
+            topics: repository.topics,
+            latest_tag: None,
+            contributor_count: contributor_count(contributors).await,
+            commit: CommitProvenance::default(),
+        })
+    }
+
+    async fn fetch_gitlab(
+        &self,
+        client: &Client,
+        token: &str,
+    ) -> Result<RepositoryMetadata, reqwest::Error> {
+        let gitlab = |url: String| client.get(url).header("PRIVATE-TOKEN", token);
+        let project_url = format!(
+            "{}/projects/{}",
+            self.base_url,
+            self.project.replace('/', "%2F")
+        );
+
+        let project: GitLabProject = gitlab(project_url.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let contributors = gitlab(format!(
+            "{}/repository/contributors?per_page=1",
+            project_url
+        ));
+
+        Ok(RepositoryMetadata {
+            provider: Some(self.provider),
+            display_name: project.name,
+            description: project.description.filter(|d| !d.is_empty()),
+            default_branch: project.default_branch,
+            topics: if project.topics.is_empty() {
+                project.tag_list
+            } else {
+                project.topics
+            },
+            latest_tag: None,
+            contributor_count: contributor_count(contributors).await,
+            commit: CommitProvenance::default(),
+        })
+    }
+}
+
+/// Size of a contributor list requested one per page: GitLab's `X-Total`
+/// header, else the page number of the `rel="last"` link, else the length of
+/// the single page. `None` if the request fails.
+async fn contributor_count(request: RequestBuilder) -> Option<i64> {
+    let response = request.send().await.ok()?.error_for_status().ok()?;
+    let headers = response.headers();
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    if let Some(total) = header("x-total").and_then(|t| t.parse().ok()) {
+        return Some(total);
+    }
+    if let Some(last) = header(LINK.as_str()).and_then(last_page) {
+        return Some(last);
+    }
+    // GitHub answers 204 for an empty repository
+    if response.status() == reqwest::StatusCode::NO_CONTENT {
+        return Some(0);
+    }
+    let page: Vec<serde_json::Value> = response.json().await.ok()?;
+    Some(page.len() as i64)
+}
+
+/// Page number of the `rel="last"` link of a paginated response's `Link` header
+fn last_page(link: &str) -> Option<i64> {
+    let last = link.split(',').find(|l| l.contains("rel=\"last\""))?;
+    let url = last
+        .split(';')
+        .next()?
+        .trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>');
+    Url::parse(url)
+        .ok()?
+        .query_pairs()
+        .find(|(key, _)| key == "page")
+        .and_then(|(_, page)| page.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{commit_fixture_changes, init_fixture_repo, tag_fixture, test_config};
+
+    #[test]
+    fn test_detect_provider() {
+        let config = test_config("/tmp".into());
+
+        let github = ProviderApi::detect("https://github.com/acme/app.git", &config).unwrap();
+        assert_eq!(github.provider, "github");
+        assert_eq!(github.base_url, config.github_api_url);
+        assert_eq!(github.project, "acme/app");
+
+        let gitlab =
+            ProviderApi::detect("https://gitlab.example.com:8443/group/sub/project", &config)
+                .unwrap();
+        assert_eq!(gitlab.provider, "gitlab");
+        assert_eq!(gitlab.base_url, "https://gitlab.example.com:8443/api/v4");
+        assert_eq!(gitlab.project, "group/sub/project");
+
+        assert_eq!(
+            ProviderApi::detect("https://bitbucket.org/acme/app", &config),
+            None
+        );
+        assert_eq!(
+            ProviderApi::detect("git@github.com:acme/app.git", &config),
+            None
+        );
+        assert_eq!(
+            ProviderApi::detect("https://github.com/acme", &config),
+            None
+        );
+    }
+
+    #[test]
+    fn test_last_page() {
+        let link = "<https://api.github.com/repositories/1/contributors?per_page=1&page=2>; rel=\"next\", \
+                    <https://api.github.com/repositories/1/contributors?per_page=1&page=42>; rel=\"last\"";
+        assert_eq!(last_page(link), Some(42));
+        assert_eq!(
+            last_page("<https://api.github.com/x?page=2>; rel=\"next\""),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_checkout() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("repo");
+        init_fixture_repo(&path, &[("README.md", "# App\n")]);
+        tag_fixture(&path, "v1.0.0");
+        commit_fixture_changes(&path, &[("README.md", "# App 2\n")], &[], "Update");
+        tag_fixture(&path, "v2.0.0");
+        let repo = Repository::open(&path).unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        let metadata = read_checkout(&path).await;
+        assert_eq!(metadata.default_branch, Some(branch));
+        assert_eq!(metadata.latest_tag.as_deref(), Some("v2.0.0"));
+        assert_eq!(metadata.provider, None);
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let commit = metadata.commit;
+        assert_eq!(commit.commit_sha, Some(head.id().to_string()));
+        assert_eq!(commit.head_tag.as_deref(), Some("v2.0.0"));
+        assert!(commit.commit_date.unwrap().ends_with('Z'));
+        assert!(commit.commit_author.is_some());
+        assert_eq!(commit.file_count, Some(1));
+        assert_eq!(commit.size_bytes, Some("# App 2\n".len() as i64));
+    }
+}
@@ -0,0 +1,187 @@
+//! Recalculation of stored risk scores. Each assessment is stored with the
+//! version of the scoring algorithm and a hash of the risk configuration
+//! (license weights and ECC path rules) it was made with, so a score that
+//! changed can be traced to a new algorithm or configuration rather than new
+//! findings.
+//!
+//! `POST /api/v1/scans/:id/risk/recalculate` rescores one completed scan,
+//! `POST /api/v1/admin/risk/recalculate` every completed scan assessed with
+//! other versions, or without an assessment at all. Archived findings are
+//! read back from the archive; scans whose findings were purged keep their
+//! assessment.
+
+use crate::api::handlers::risk::{
+    assess_results, current_config_version, store_risk_assessment, RISK_ALGORITHM_VERSION,
+};
+use crate::db::models::Scan;
+use crate::error::AppError;
+use crate::{policy, waiver, AppState};
+use chrono::Utc;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A risk score and the versions it was calculated with
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VersionedRiskScore {
+    pub score: i32,
+    pub level: String,
+    /// `None` for assessments stored before versions were recorded
+    pub algorithm_version: Option<i32>,
+    pub config_version: Option<String>,
+    /// `None` on a dry run
+    pub assessed_at: Option<String>,
+}
+
+/// Risk score of a scan before and after recalculation
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RiskRecalculation {
+    pub scan_id: String,
+    /// `None` if the scan had no risk assessment
+    pub previous: Option<VersionedRiskScore>,
+    pub current: VersionedRiskScore,
+    /// Whether the score or level differ from the previous assessment
+    pub changed: bool,
+}
+
+/// What a bulk recalculation rescored, or would rescore on a dry run
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct RescoreReport {
+    pub dry_run: bool,
+    /// Versions the scans were rescored with
+    pub algorithm_version: i32,
+    pub config_version: String,
+    pub scans_rescored: usize,
+    /// Scans whose score or level changed
+    pub changes: Vec<RiskRecalculation>,
+    /// Scans that could not be rescored, e.g. because their archive was
+    /// unavailable
+    pub scans_failed: Vec<String>,
+    pub rescored_at: String,
+}
+
+/// Rescore a completed scan from its findings with the current algorithm
+/// and risk configuration. With `dry_run`, the new assessment is not stored.
+pub async fn recalculate(
+    state: &AppState,
+    scan: &Scan,
+    dry_run: bool,
+) -> Result<RiskRecalculation, AppError> {
+    if scan.status != "completed" {
+        return Err(AppError::Conflict(format!(
+            "Scan {} is {}, only completed scans are scored",
+            scan.id, scan.status
+        )));
+    }
+    if scan.results_purged_at.is_some() {
+        return Err(AppError::Conflict(format!(
+            "Findings of scan {} were purged, its risk score can no longer be recalculated",
+            scan.id
+        )));
+    }
+
+    let mut results = state.result_store.load_results(&scan.id).await?;
+    // Waived findings do not count towards the score
+    if waiver::mark(&state.db, scan, &mut results).await? > 0 {
+        results.retain(|r| r.waiver_id.is_none());
+    }
+    let mut risk = assess_results(&state.db, &results).await?;
+    risk.preliminary = scan.quick;
+
+    let previous = stored_score(scan);
+    let changed = previous
+        .as_ref()
+        .is_none_or(|p| p.score != risk.score || p.level != risk.level);
+    let current = if dry_run {
+        VersionedRiskScore {
+            score: risk.score,
+            level: risk.level.clone(),
+            algorithm_version: risk.algorithm_version,
+            config_version: risk.config_version.clone(),
+            assessed_at: None,
+        }
+    } else {
+        store_risk_assessment(&state.db, &scan.id, &risk).await?;
+        // A policy may limit the risk score
+        if changed {
+            policy::evaluate_scan(state, &scan.id).await?;
+        }
+        tracing::info!(
+            "Risk score of scan {} recalculated: {:?} -> {} ({})",
+            scan.id,
+            previous.as_ref().map(|p| p.score),
+            risk.score,
+            risk.level
+        );
+        Scan::find_by_id(&state.db, &scan.id)
+            .await?
+            .as_ref()
+            .and_then(stored_score)
+            .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", scan.id)))?
+    };
+
+    Ok(RiskRecalculation {
+        scan_id: scan.id.clone(),
+        previous,
+        current,
+        changed,
+    })
+}
+
+/// Rescore the completed scans, with `outdated_only` only those assessed
+/// with other versions of the algorithm or risk configuration, or not at
+/// all. With `dry_run`, only report what would change.
+pub async fn recalculate_all(
+    state: &AppState,
+    outdated_only: bool,
+    dry_run: bool,
+) -> Result<RescoreReport, AppError> {
+    let config_version = current_config_version(&state.db).await?;
+    let mut report = RescoreReport {
+        dry_run,
+        algorithm_version: RISK_ALGORITHM_VERSION,
+        config_version,
+        ..Default::default()
+    };
+
+    let scans = Scan::find_for_rescoring(
+        &state.db,
+        RISK_ALGORITHM_VERSION,
+        &report.config_version,
+        outdated_only,
+    )
+    .await?;
+    for scan in scans {
+        match recalculate(state, &scan, dry_run).await {
+            Ok(recalculation) => {
+                report.scans_rescored += 1;
+                if recalculation.changed {
+                    report.changes.push(recalculation);
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Failed to recalculate the risk score of scan {}: {}", scan.id, e);
+                report.scans_failed.push(scan.id);
+            }
+        }
+    }
+
+    report.rescored_at = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    tracing::info!(
+        "Risk recalculation{}: {} scans rescored, {} changed, {} failed",
+        if dry_run { " (dry run)" } else { "" },
+        report.scans_rescored,
+        report.changes.len(),
+        report.scans_failed.len()
+    );
+    Ok(report)
+}
+
+fn stored_score(scan: &Scan) -> Option<VersionedRiskScore> {
+    Some(VersionedRiskScore {
+        score: scan.risk_score?,
+        level: scan.risk_level.clone()?,
+        algorithm_version: scan.risk_algorithm_version,
+        config_version: scan.risk_config_version.clone(),
+        assessed_at: scan.risk_assessed_at.clone(),
+    })
+}
@@ -0,0 +1,182 @@
+//! Data retention. Findings are what makes the database grow: once a scan
+//! has been finished for `RETENTION_RESULTS_DAYS`, its findings and the
+//! per-file details that go with them are purged, while the scan with its
+//! status, risk assessment, timeline and a summary of the findings is kept,
+//! and monthly result partitions left empty are dropped.
+//! After `RETENTION_SCANS_DAYS` the scan itself is deleted with everything
+//! stored for it. Either period is unlimited at 0, the default.
+//!
+//! A purge runs every `RETENTION_PURGE_INTERVAL_HOURS` and on request from
+//! `POST /api/v1/admin/purge`. Scans under legal hold are never purged,
+//! however old. SQLite reuses the pages freed by a purge; the file only
+//! shrinks when the purge is asked to vacuum.
+
+use crate::db::models::{result_partition, Project, Scan};
+use crate::error::AppError;
+use crate::git::workspace::Workspace;
+use crate::integrity;
+use crate::legal_hold;
+use crate::AppState;
+use chrono::Utc;
+use serde::Serialize;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+
+/// How long the findings and scans are kept, in days; 0 keeps them forever
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub results_days: u64,
+    pub scans_days: u64,
+}
+
+impl RetentionPolicy {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            results_days: config.retention_results_days,
+            scans_days: config.retention_scans_days,
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.results_days == 0 && self.scans_days == 0
+    }
+}
+
+/// What a purge removed, or would remove on a dry run
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct PurgeReport {
+    pub dry_run: bool,
+    /// Scans deleted with everything stored for them
+    pub scans_deleted: Vec<String>,
+    /// Scans whose findings were purged; the scans and their summaries are
+    /// kept
+    pub scans_purged: Vec<String>,
+    pub results_deleted: u64,
+    /// Expired scans left in place because they are under legal hold
+    pub scans_held: Vec<String>,
+    /// Scans whose result checksums were sealed again, recorded in the
+    /// integrity audit log
+    pub checksums_resealed: usize,
+    /// Monthly result partitions dropped because nothing was left in them
+    pub partitions_dropped: Vec<String>,
+    /// Whether the database file was vacuumed afterwards
+    pub vacuumed: bool,
+    pub purged_at: String,
+}
+
+/// Apply `policy` to the finished scans. With `dry_run`, only report what
+/// would be removed; with `vacuum`, shrink the database file afterwards.
+pub async fn purge(
+    state: &AppState,
+    policy: RetentionPolicy,
+    dry_run: bool,
+    vacuum: bool,
+) -> Result<PurgeReport, AppError> {
+    let mut report = PurgeReport {
+        dry_run,
+        ..Default::default()
+    };
+    let held_repositories = Project::held_repositories(&state.db).await?;
+
+    if policy.scans_days > 0 {
+        for scan in Scan::find_finished_before(&state.db, &cutoff(policy.scans_days)).await? {
+            if legal_hold::is_held(&scan, &held_repositories) {
+                report.scans_held.push(scan.id);
+                continue;
+            }
+            report.results_deleted += state.result_store.count_results(&scan.id).await?;
+            if !dry_run {
+                delete_scan(state, &scan.id).await?;
+            }
+            report.scans_deleted.push(scan.id);
+        }
+    }
+
+    if policy.results_days > 0 {
+        for scan in Scan::find_finished_before(&state.db, &cutoff(policy.results_days)).await? {
+            if scan.results_purged_at.is_some() || report.scans_deleted.contains(&scan.id) {
+                continue;
+            }
+            if legal_hold::is_held(&scan, &held_repositories) {
+                if !report.scans_held.contains(&scan.id) {
+                    report.scans_held.push(scan.id);
+                }
+                continue;
+            }
+            report.results_deleted += state.result_store.count_results(&scan.id).await?;
+            if !dry_run {
+                let summary = state.result_store.summarize(&scan.id).await?;
+                state.result_store.delete_results(&scan.id).await?;
+                Scan::mark_results_purged(&state.db, &scan.id, &summary).await?;
+            }
+            report.scans_purged.push(scan.id);
+        }
+    }
+
+    let removed = !report.scans_purged.is_empty() || !report.scans_deleted.is_empty();
+    if !dry_run && removed {
+        report.partitions_dropped = result_partition::drop_empty(&state.db).await?;
+    }
+    if !dry_run && !report.scans_purged.is_empty() {
+        report.checksums_resealed = integrity::reseal(state, "retention purge").await?;
+    }
+    if !dry_run && vacuum {
+        sqlx::query("VACUUM").execute(&state.db).await?;
+        report.vacuumed = true;
+    }
+
+    report.purged_at = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    tracing::info!(
+        "Retention purge{}: {} scans deleted, findings of {} scans purged, {} results, {} held",
+        if dry_run { " (dry run)" } else { "" },
+        report.scans_deleted.len(),
+        report.scans_purged.len(),
+        report.results_deleted,
+        report.scans_held.len()
+    );
+    Ok(report)
+}
+
+async fn delete_scan(state: &AppState, scan_id: &str) -> Result<(), AppError> {
+    state.result_store.delete_results(scan_id).await?;
+
+    let workspace = Workspace::new(state.config.temp_workspace_dir.clone(), scan_id.to_string());
+    if workspace.exists().await {
+        workspace
+            .cleanup()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to remove workspace: {}", e)))?;
+    }
+
+    Scan::delete_permanently(&state.db, scan_id).await?;
+    Ok(())
+}
+
+/// Stored timestamp `days` ago (UTC)
+fn cutoff(days: u64) -> String {
+    (Utc::now() - chrono::Duration::days(days as i64))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+/// Run [`purge`] with the configured policy every
+/// `RETENTION_PURGE_INTERVAL_HOURS`, the first time one interval after
+/// startup. `None` when nothing expires or periodic purges are disabled.
+pub fn spawn_periodic(state: AppState) -> Option<JoinHandle<()>> {
+    let policy = RetentionPolicy::from_config(&state.config);
+    let hours = state.config.retention_purge_interval_hours;
+    if policy.is_unlimited() || hours == 0 {
+        return None;
+    }
+    Some(tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(hours * 3600));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = purge(&state, policy, false, false).await {
+                tracing::warn!("Retention purge failed: {}", e);
+            }
+        }
+    }))
+}
@@ -0,0 +1,68 @@
+//! Legal review of scan findings. Every finding starts `pending`; reviewers
+//! approve or reject findings, one at a time or in bulk, and comment on them.
+//! A scan can be marked reviewed once none of its findings that trip a
+//! failing gate rule (copyleft licenses, critical or high ECC findings,
+//! malware) is still pending. Waived findings count as resolved.
+
+use crate::api::models::ScanReviewResponse;
+use crate::codeowners::violation;
+use crate::db::models::{Scan, ScanResult};
+use crate::error::AppError;
+use crate::{waiver, AppState};
+
+pub const REVIEW_STATUSES: [&str; 3] = ["pending", "approved", "rejected"];
+
+/// Whether a finding keeps its scan from being marked reviewed
+pub fn is_unresolved(result: &ScanResult) -> bool {
+    result.review_status == "pending" && result.waiver_id.is_none() && violation(result).is_some()
+}
+
+/// Review progress of a scan
+pub async fn summary(state: &AppState, scan: &Scan) -> Result<ScanReviewResponse, AppError> {
+    let mut results = state.result_store.load_results(&scan.id).await?;
+    waiver::mark(&state.db, scan, &mut results).await?;
+    Ok(summarize(scan, &results))
+}
+
+fn summarize(scan: &Scan, results: &[ScanResult]) -> ScanReviewResponse {
+    let count = |status: &str| results.iter().filter(|r| r.review_status == status).count() as i64;
+
+    ScanReviewResponse {
+        scan_id: scan.id.clone(),
+        reviewed_at: scan.reviewed_at.clone(),
+        pending: count("pending"),
+        approved: count("approved"),
+        rejected: count("rejected"),
+        unresolved_high_severity: results.iter().filter(|r| is_unresolved(r)).count() as i64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(result_type: &str, severity: Option<&str>, license: Option<&str>) -> ScanResult {
+        ScanResult {
+            license_name: license.map(str::to_string),
+            license_spdx_id: license.map(str::to_string),
+            risk_severity: severity.map(str::to_string),
+            ..ScanResult::for_test(result_type, "src/lib.rs")
+        }
+    }
+
+    #[test]
+    fn test_is_unresolved() {
+        let mut gpl = finding("license", None, Some("GPL-3.0-only"));
+        assert!(is_unresolved(&gpl));
+        gpl.review_status = "rejected".to_string();
+        assert!(!is_unresolved(&gpl));
+
+        let mut critical = finding("ecc", Some("critical"), None);
+        assert!(is_unresolved(&critical));
+        critical.waiver_id = Some("w-1".to_string());
+        assert!(!is_unresolved(&critical));
+
+        assert!(!is_unresolved(&finding("license", None, Some("MIT"))));
+        assert!(!is_unresolved(&finding("ecc", Some("low"), None)));
+    }
+}
@@ -0,0 +1,144 @@
+//! Random samples of scan findings for spot-checking scanner quality. The
+//! findings are grouped into strata, such as one per license, and the sample
+//! is drawn from the strata in turn so rare licenses are represented next to
+//! the ones found in thousands of files.
+
+use crate::db::models::ScanResult;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::BTreeMap;
+
+pub const STRATIFY_BY: [&str; 4] = ["license", "type", "severity", "none"];
+
+/// Findings of one stratum and the part of them drawn into the sample
+#[derive(Debug)]
+pub struct Stratum<T> {
+    pub key: String,
+    pub total: usize,
+    pub sampled: Vec<T>,
+}
+
+/// Stratum of a finding. Findings without a license or severity fall into
+/// a stratum named after their result type.
+pub fn stratum_key(result: &ScanResult, stratify: &str) -> String {
+    match stratify {
+        "license" => result
+            .license_spdx_id
+            .clone()
+            .or_else(|| result.license_name.clone())
+            .unwrap_or_else(|| result.result_type.clone()),
+        "type" => result.result_type.clone(),
+        "severity" => result
+            .risk_severity
+            .clone()
+            .unwrap_or_else(|| result.result_type.clone()),
+        _ => "all".to_string(),
+    }
+}
+
+/// Draw up to `n` items, taking one from each stratum in turn until the
+/// sample is full or every stratum is exhausted. Strata are ordered by key.
+pub fn sample<T, R: Rng>(
+    items: Vec<T>,
+    key: impl Fn(&T) -> String,
+    n: usize,
+    rng: &mut R,
+) -> Vec<Stratum<T>> {
+    let mut groups: BTreeMap<String, Vec<T>> = BTreeMap::new();
+    for item in items {
+        groups.entry(key(&item)).or_default().push(item);
+    }
+
+    // How many items each stratum contributes
+    let mut quotas = vec![0; groups.len()];
+    let sizes: Vec<usize> = groups.values().map(Vec::len).collect();
+    let mut remaining = n.min(sizes.iter().sum());
+    while remaining > 0 {
+        for (quota, size) in quotas.iter_mut().zip(&sizes) {
+            if remaining > 0 && *quota < *size {
+                *quota += 1;
+                remaining -= 1;
+            }
+        }
+    }
+
+    groups
+        .into_iter()
+        .zip(quotas)
+        .map(|((key, mut items), quota)| {
+            let total = items.len();
+            items.shuffle(rng);
+            items.truncate(quota);
+            Stratum {
+                key,
+                total,
+                sampled: items,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn licenses(counts: &[(&str, usize)]) -> Vec<(String, usize)> {
+        counts
+            .iter()
+            .flat_map(|(license, count)| (0..*count).map(move |i| (license.to_string(), i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_sample_draws_from_every_stratum() {
+        let items = licenses(&[("MIT", 1000), ("GPL-3.0-only", 2), ("Apache-2.0", 30)]);
+        let strata = sample(items, |(l, _)| l.clone(), 10, &mut StdRng::seed_from_u64(7));
+
+        let sampled: Vec<(&str, usize, usize)> = strata
+            .iter()
+            .map(|s| (s.key.as_str(), s.total, s.sampled.len()))
+            .collect();
+        assert_eq!(
+            sampled,
+            vec![
+                ("Apache-2.0", 30, 4),
+                ("GPL-3.0-only", 2, 2),
+                ("MIT", 1000, 4)
+            ]
+        );
+        assert!(strata[2].sampled.iter().all(|(l, _)| l == "MIT"));
+    }
+
+    #[test]
+    fn test_sample_is_bounded_by_findings() {
+        let items = licenses(&[("MIT", 3), ("BSD-3-Clause", 1)]);
+        let strata = sample(items, |(l, _)| l.clone(), 50, &mut StdRng::seed_from_u64(1));
+        assert_eq!(strata.iter().map(|s| s.sampled.len()).sum::<usize>(), 4);
+
+        let strata = sample(
+            licenses(&[]),
+            |(l, _)| l.clone(),
+            50,
+            &mut StdRng::seed_from_u64(1),
+        );
+        assert!(strata.is_empty());
+    }
+
+    #[test]
+    fn test_sample_is_reproducible_with_a_seed() {
+        let draw = |seed| {
+            sample(
+                licenses(&[("MIT", 500)]),
+                |(l, _)| l.clone(),
+                5,
+                &mut StdRng::seed_from_u64(seed),
+            )
+            .remove(0)
+            .sampled
+        };
+        assert_eq!(draw(42), draw(42));
+        assert_ne!(draw(42), draw(43));
+    }
+}
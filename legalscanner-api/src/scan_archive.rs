@@ -0,0 +1,359 @@
+//! Portable scan archives, for moving a scan to another instance: into an
+//! air-gapped environment for review, or to a new deployment. An archive is
+//! newline-delimited JSON with a `scan` record first, then one record per
+//! finding, review comment and timeline event.
+//!
+//! An imported scan keeps its ID, status, risk assessment and timestamps.
+//! Findings keep their review decisions but get new IDs, and comments are
+//! moved along with them. Clone tokens, credentials and legal holds stay
+//! behind, as do history findings, SWHIDs and other per-file details.
+
+use crate::db::models::scan::ScanSummary;
+use crate::db::models::scan_result::NewScanResult;
+use crate::db::models::{Scan, ScanEvent, ScanResult, ScanResultComment};
+use crate::error::AppError;
+use crate::integrity;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+
+/// `format` of the `scan` record
+pub const FORMAT: &str = "legalscanner-scan-archive";
+
+/// Archive version written, and the newest one imported
+pub const VERSION: u32 = 1;
+
+pub const CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// One line of an archive
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+pub enum Record {
+    Scan(Box<ArchivedScan>),
+    Result(Box<ScanResult>),
+    Comment(ScanResultComment),
+    Event(ScanEvent),
+}
+
+/// First line of an archive
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchivedScan {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub scan: Scan,
+    /// Summary kept for a scan whose findings retention purged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ScanSummary>,
+}
+
+/// A scan with everything its archive carries
+#[derive(Debug)]
+pub struct Archive {
+    pub scan: ArchivedScan,
+    pub results: Vec<ScanResult>,
+    pub comments: Vec<ScanResultComment>,
+    pub events: Vec<ScanEvent>,
+}
+
+/// What an import stored
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ArchiveImportReport {
+    pub scan_id: String,
+    pub git_url: String,
+    pub status: String,
+    pub results_imported: usize,
+    pub comments_imported: usize,
+    pub events_imported: usize,
+}
+
+/// Archive of a finished scan
+pub async fn export(state: &AppState, scan: &Scan) -> Result<Archive, AppError> {
+    if !matches!(scan.status.as_str(), "completed" | "failed") {
+        return Err(AppError::Validation(format!(
+            "Scan is not finished yet. Current status: {}",
+            scan.status
+        )));
+    }
+
+    let mut results = state.result_store.load_results(&scan.id).await?;
+    results.sort_by_key(|r| r.id);
+    let summary = match scan.results_purged_at {
+        Some(_) => Some(state.result_store.summarize(&scan.id).await?),
+        None => None,
+    };
+
+    Ok(Archive {
+        scan: ArchivedScan {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            scan: scan.clone(),
+            summary,
+        },
+        results,
+        comments: ScanResultComment::find_by_scan_id(&state.db, &scan.id).await?,
+        events: ScanEvent::find_by_scan_id(&state.db, &scan.id).await?,
+    })
+}
+
+/// One record per line
+pub fn write(archive: Archive) -> Result<String, AppError> {
+    let records = std::iter::once(Record::Scan(Box::new(archive.scan)))
+        .chain(
+            archive
+                .results
+                .into_iter()
+                .map(|r| Record::Result(Box::new(r))),
+        )
+        .chain(archive.comments.into_iter().map(Record::Comment))
+        .chain(archive.events.into_iter().map(Record::Event));
+
+    let mut content = String::new();
+    for record in records {
+        let line = serde_json::to_string(&record)
+            .map_err(|e| AppError::Internal(format!("Failed to write archive: {}", e)))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+    Ok(content)
+}
+
+/// Read an archive, checking that every record belongs to its scan
+pub fn parse(content: &str) -> Result<Archive, String> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let record = |(index, line): (usize, &str)| {
+        serde_json::from_str::<Record>(line).map_err(|e| format!("Line {}: {}", index + 1, e))
+    };
+
+    let scan = match lines.next().map(record).transpose()? {
+        Some(Record::Scan(scan)) => *scan,
+        Some(_) => return Err("The archive does not start with a scan record".to_string()),
+        None => return Err("The archive is empty".to_string()),
+    };
+    if scan.format != FORMAT {
+        return Err(format!("Not a scan archive: format '{}'", scan.format));
+    }
+    if scan.version > VERSION {
+        return Err(format!(
+            "Archive version {} is newer than the supported version {}",
+            scan.version, VERSION
+        ));
+    }
+    if !matches!(scan.scan.status.as_str(), "completed" | "failed") {
+        return Err(format!(
+            "Only finished scans can be imported, the scan is {}",
+            scan.scan.status
+        ));
+    }
+
+    let mut archive = Archive {
+        scan,
+        results: Vec::new(),
+        comments: Vec::new(),
+        events: Vec::new(),
+    };
+    let scan_id = archive.scan.scan.id.clone();
+    for (index, line) in lines {
+        let (record_scan_id, kind) = match record((index, line))? {
+            Record::Scan(_) => return Err(format!("Line {}: second scan record", index + 1)),
+            Record::Result(result) => {
+                NewScanResult::restored(&result)
+                    .map_err(|e| format!("Line {}: {}", index + 1, e))?;
+                let scan_id = result.scan_id.clone();
+                archive.results.push(*result);
+                (scan_id, "result")
+            }
+            Record::Comment(comment) => {
+                let scan_id = comment.scan_id.clone();
+                archive.comments.push(comment);
+                (scan_id, "comment")
+            }
+            Record::Event(event) => {
+                let scan_id = event.scan_id.clone();
+                archive.events.push(event);
+                (scan_id, "event")
+            }
+        };
+        if record_scan_id != scan_id {
+            return Err(format!(
+                "Line {}: {} of scan {}, not {}",
+                index + 1,
+                kind,
+                record_scan_id,
+                scan_id
+            ));
+        }
+    }
+
+    let result_ids: HashSet<i64> = archive.results.iter().map(|r| r.id).collect();
+    if let Some(comment) = archive
+        .comments
+        .iter()
+        .find(|c| !result_ids.contains(&c.result_id))
+    {
+        return Err(format!(
+            "Comment {} is on finding {}, which is not in the archive",
+            comment.id, comment.result_id
+        ));
+    }
+    Ok(archive)
+}
+
+/// Store an archived scan under its own ID, all of it or nothing
+pub async fn import(state: &AppState, archive: Archive) -> Result<ArchiveImportReport, AppError> {
+    let scan_id = archive.scan.scan.id.clone();
+    if let Some(existing) = Scan::find_by_id_including_deleted(&state.db, &scan_id).await? {
+        if existing.deleted_at.is_none() {
+            return Err(AppError::Conflict(format!(
+                "Scan {} already exists, delete it before importing it again",
+                scan_id
+            )));
+        }
+        // The archive replaces the deleted copy kept until purged
+        state.result_store.delete_results(&scan_id).await?;
+        Scan::delete_permanently(&state.db, &scan_id).await?;
+    }
+
+    let scan = Scan::import(&state.db, &archive.scan.scan, archive.scan.summary.as_ref()).await?;
+    let report = ArchiveImportReport {
+        scan_id: scan.id.clone(),
+        git_url: scan.git_url.clone(),
+        status: scan.status.clone(),
+        results_imported: archive.results.len(),
+        comments_imported: archive.comments.len(),
+        events_imported: archive.events.len(),
+    };
+    if let Err(e) = restore(state, &scan, archive).await {
+        // The scan goes, with its comments and events
+        let _ = state.result_store.delete_results(&scan.id).await;
+        let _ = Scan::delete_permanently(&state.db, &scan.id).await;
+        return Err(e);
+    }
+
+    tracing::info!(
+        "Imported scan {} of {} with {} findings",
+        scan.id,
+        scan.git_url,
+        report.results_imported
+    );
+    Ok(report)
+}
+
+async fn restore(state: &AppState, scan: &Scan, archive: Archive) -> Result<(), AppError> {
+    let archived_ids: Vec<i64> = archive.results.iter().map(|r| r.id).collect();
+    let ids = state
+        .result_store
+        .restore_results(&scan.id, archive.results)
+        .await?;
+    let ids: HashMap<i64, i64> = archived_ids.into_iter().zip(ids).collect();
+
+    for comment in &archive.comments {
+        if let Some(result_id) = ids.get(&comment.result_id) {
+            ScanResultComment::restore(&state.db, comment, *result_id).await?;
+        }
+    }
+    for event in &archive.events {
+        ScanEvent::restore(&state.db, event).await?;
+    }
+    if scan.status == "completed" {
+        integrity::seal(state, &scan.id).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan_record(status: &str) -> String {
+        serde_json::json!({
+            "record": "scan",
+            "format": FORMAT,
+            "version": VERSION,
+            "exported_at": "2025-02-18T10:00:00+00:00",
+            "scan": {
+                "id": "scan-1",
+                "git_url": "https://github.com/acme/app.git",
+                "status": status,
+                "created_at": "2025-02-18 09:00:00",
+                "fossology_status": "completed",
+                "semgrep_status": "completed",
+                "quick": false,
+                "due_diligence": false,
+                "submodules": false,
+                "git_authorship": false
+            }
+        })
+        .to_string()
+    }
+
+    fn result_record(id: i64, scan_id: &str, result_type: &str) -> String {
+        serde_json::json!({
+            "record": "result",
+            "id": id,
+            "scan_id": scan_id,
+            "file_path": "LICENSE",
+            "result_type": result_type,
+            "license_name": "MIT",
+            "license_spdx_id": "MIT",
+            "confidence": 1.0,
+            "review_status": "approved",
+            "reviewed_at": "2025-02-18 09:30:00"
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_archive() {
+        let comment = serde_json::json!({
+            "record": "comment",
+            "id": "c-1",
+            "scan_id": "scan-1",
+            "result_id": 7,
+            "body": "Checked with legal",
+            "author": "jane",
+            "created_at": "2025-02-18 09:31:00"
+        });
+        let content = format!(
+            "{}\n{}\n\n{}\n",
+            scan_record("completed"),
+            result_record(7, "scan-1", "license"),
+            comment
+        );
+        let archive = parse(&content).unwrap();
+        assert_eq!(archive.scan.scan.id, "scan-1");
+        assert_eq!(archive.results.len(), 1);
+        assert_eq!(archive.results[0].review_status, "approved");
+        assert_eq!(archive.comments[0].result_id, 7);
+
+        let written = write(archive).unwrap();
+        assert_eq!(written.lines().count(), 3);
+        assert_eq!(parse(&written).unwrap().results[0].id, 7);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_archives() {
+        assert!(parse("").is_err());
+        assert!(parse(&result_record(1, "scan-1", "license")).is_err());
+        assert!(parse(&scan_record("in_progress")).is_err());
+        assert!(parse(&scan_record("completed").replace(FORMAT, "other")).is_err());
+
+        let other_scan = format!(
+            "{}\n{}",
+            scan_record("completed"),
+            result_record(1, "scan-2", "license")
+        );
+        assert!(parse(&other_scan).unwrap_err().contains("scan-2"));
+        let unknown_type = format!(
+            "{}\n{}",
+            scan_record("completed"),
+            result_record(1, "scan-1", "trademark")
+        );
+        assert!(parse(&unknown_type).unwrap_err().contains("trademark"));
+    }
+}
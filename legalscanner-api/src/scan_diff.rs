@@ -0,0 +1,212 @@
+//! Comparison of two scans of the same repository, typically the previous
+//! and the upcoming release, to see whether the code got legally riskier.
+
+use crate::db::models::ScanResult;
+use crate::export::sarif::relative_uri;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use utoipa::ToSchema;
+
+/// A finding present in only one of the two scans. Line numbers and scanner
+/// confidence are left out so that code moving within a file is not reported.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, ToSchema)]
+pub struct FindingChange {
+    pub result_type: String,
+    /// Path relative to the repository root
+    pub file_path: String,
+    /// License, copyright statement, ECC check, malware signature or package
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+}
+
+/// A license found in only one of the two scans, with the files it covers
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct LicenseChange {
+    pub license: String,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScanDiff {
+    /// Licenses in the newer scan that the baseline did not contain anywhere
+    pub new_licenses: Vec<LicenseChange>,
+    /// Licenses of the baseline that no longer occur
+    pub removed_licenses: Vec<LicenseChange>,
+    pub new_ecc_findings: Vec<FindingChange>,
+    pub added_findings: Vec<FindingChange>,
+    pub removed_findings: Vec<FindingChange>,
+}
+
+/// Compare the results of `base_scan_id` with those of a newer scan
+pub fn diff_results(
+    base_scan_id: &str,
+    base: &[ScanResult],
+    scan_id: &str,
+    results: &[ScanResult],
+) -> ScanDiff {
+    let before = findings(base_scan_id, base);
+    let after = findings(scan_id, results);
+
+    let added: Vec<FindingChange> = after.difference(&before).cloned().collect();
+    let removed: Vec<FindingChange> = before.difference(&after).cloned().collect();
+
+    let licenses_before = license_files(&before);
+    let licenses_after = license_files(&after);
+
+    ScanDiff {
+        new_licenses: license_changes(&licenses_after, &licenses_before),
+        removed_licenses: license_changes(&licenses_before, &licenses_after),
+        new_ecc_findings: added
+            .iter()
+            .filter(|f| f.result_type == "ecc")
+            .cloned()
+            .collect(),
+        added_findings: added,
+        removed_findings: removed,
+    }
+}
+
+/// Whether two git URLs point at the same repository, ignoring a trailing
+/// `.git` or slash and letter case
+pub fn same_repository(a: &str, b: &str) -> bool {
+    normalize_repository_url(a) == normalize_repository_url(b)
+}
+
+/// Lowercase URL without a trailing slash or `.git` suffix
+pub fn normalize_repository_url(url: &str) -> String {
+    url.trim()
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_lowercase()
+}
+
+fn findings(scan_id: &str, results: &[ScanResult]) -> BTreeSet<FindingChange> {
+    results
+        .iter()
+        .filter_map(|result| finding_change(scan_id, result))
+        .collect()
+}
+
+/// Identity of a finding across scans: type, path relative to the scan root
+/// and value. `None` for results without a value to compare.
+pub fn finding_change(scan_id: &str, result: &ScanResult) -> Option<FindingChange> {
+    let value = match result.result_type.as_str() {
+        "license" | "model_license" => result
+            .license_spdx_id
+            .clone()
+            .or_else(|| result.license_name.clone()),
+        "copyright" => result.copyright_statement.clone(),
+        "ecc" => result
+            .ecc_check_id
+            .clone()
+            .or_else(|| result.raw_data.clone()),
+        "malware" => result.raw_data.clone(),
+        "package" => result.package_purl.clone().or_else(|| {
+            let name = result.package_name.as_deref()?;
+            Some(match result.package_version.as_deref() {
+                Some(version) => format!("{}@{}", name, version),
+                None => name.to_string(),
+            })
+        }),
+        _ => None,
+    }?;
+
+    Some(FindingChange {
+        result_type: result.result_type.clone(),
+        file_path: relative_uri(&result.file_path, scan_id),
+        value,
+        severity: result.risk_severity.clone(),
+    })
+}
+
+/// License -> files, for license findings
+fn license_files(findings: &BTreeSet<FindingChange>) -> BTreeMap<&str, Vec<&str>> {
+    let mut licenses: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for finding in findings.iter().filter(|f| f.result_type == "license") {
+        licenses
+            .entry(finding.value.as_str())
+            .or_default()
+            .push(finding.file_path.as_str());
+    }
+    licenses
+}
+
+/// Licenses of `from` that `other` does not contain
+fn license_changes(
+    from: &BTreeMap<&str, Vec<&str>>,
+    other: &BTreeMap<&str, Vec<&str>>,
+) -> Vec<LicenseChange> {
+    from.iter()
+        .filter(|(license, _)| !other.contains_key(*license))
+        .map(|(license, files)| LicenseChange {
+            license: license.to_string(),
+            files: files.iter().map(|f| f.to_string()).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(scan_id: &str, result_type: &str, file: &str, value: &str) -> ScanResult {
+        ScanResult {
+            scan_id: scan_id.to_string(),
+            license_spdx_id: (result_type == "license").then(|| value.to_string()),
+            copyright_statement: (result_type == "copyright").then(|| value.to_string()),
+            risk_severity: (result_type == "ecc").then(|| "high".to_string()),
+            ecc_check_id: (result_type == "ecc").then(|| value.to_string()),
+            ..ScanResult::for_test(
+                result_type,
+                &format!("/tmp/workspaces/{}/{}", scan_id, file),
+            )
+        }
+    }
+
+    #[test]
+    fn test_diff_results() {
+        let base = vec![
+            result("a", "license", "src/lib.rs", "MIT"),
+            result("a", "license", "src/old.rs", "BSD-3-Clause"),
+            result("a", "copyright", "src/lib.rs", "Copyright 2023 Acme"),
+        ];
+        let head = vec![
+            result("b", "license", "src/lib.rs", "MIT"),
+            result("b", "license", "src/gpl.rs", "GPL-3.0-only"),
+            result("b", "license", "src/new.rs", "MIT"),
+            result("b", "copyright", "src/lib.rs", "Copyright 2023 Acme"),
+            result("b", "ecc", "src/aes.rs", "crypto.aes"),
+        ];
+
+        let diff = diff_results("a", &base, "b", &head);
+
+        assert_eq!(
+            diff.new_licenses,
+            vec![LicenseChange {
+                license: "GPL-3.0-only".to_string(),
+                files: vec!["src/gpl.rs".to_string()],
+            }]
+        );
+        assert_eq!(diff.removed_licenses[0].license, "BSD-3-Clause");
+        assert_eq!(diff.new_ecc_findings.len(), 1);
+        assert_eq!(diff.new_ecc_findings[0].file_path, "src/aes.rs");
+        assert_eq!(diff.new_ecc_findings[0].severity.as_deref(), Some("high"));
+        // GPL file, the new MIT file and the ECC hit
+        assert_eq!(diff.added_findings.len(), 3);
+        assert_eq!(diff.removed_findings.len(), 1);
+        assert_eq!(diff.removed_findings[0].file_path, "src/old.rs");
+    }
+
+    #[test]
+    fn test_same_repository() {
+        assert!(same_repository(
+            "https://github.com/Org/Repo.git",
+            "https://github.com/org/repo/"
+        ));
+        assert!(!same_repository(
+            "https://github.com/org/repo",
+            "https://github.com/org/other"
+        ));
+    }
+}
@@ -0,0 +1,173 @@
+//! Git authorship scan. Walks the commits reachable from HEAD and attributes
+//! every file of the current tree to the authors of the commits that touched
+//! it, with each author's first and last commit to the file. Commit authors
+//! are copyright evidence of their own, separate from the notices Fossology
+//! finds in the files.
+
+use crate::scanner::traits::ScanError;
+use chrono::DateTime;
+use git2::{Delta, ObjectType, Repository, Sort, TreeWalkMode, TreeWalkResult};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileAuthor {
+    /// Path relative to the repository root
+    pub file_path: String,
+    pub author_name: String,
+    /// Lowercase; commits are attributed per address
+    pub author_email: String,
+    pub commit_count: i64,
+    pub first_commit_sha: String,
+    /// Author date, RFC 3339
+    pub first_commit_date: String,
+    pub last_commit_sha: String,
+    pub last_commit_date: String,
+}
+
+/// Attribute the files at HEAD to their commit authors, walking at most
+/// `max_commits` commits newest first (0 means no limit)
+pub async fn scan_authorship(
+    repo_path: &Path,
+    max_commits: usize,
+) -> Result<Vec<FileAuthor>, ScanError> {
+    let repo_path = repo_path.to_path_buf();
+    tokio::task::spawn_blocking(move || walk_authorship(&repo_path, max_commits))
+        .await
+        .map_err(|e| ScanError::Failed(format!("Authorship scan task failed: {}", e)))?
+        .map_err(|e| ScanError::Failed(format!("Authorship scan failed: {}", e)))
+}
+
+fn walk_authorship(repo_path: &Path, max_commits: usize) -> Result<Vec<FileAuthor>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+
+    let mut current: HashSet<String> = HashSet::new();
+    repo.head()?
+        .peel_to_tree()?
+        .walk(TreeWalkMode::PreOrder, |root, entry| {
+            if entry.kind() == Some(ObjectType::Blob) {
+                if let Some(name) = entry.name() {
+                    current.insert(format!("{}{}", root, name));
+                }
+            }
+            TreeWalkResult::Ok
+        })?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.set_sorting(Sort::TIME)?;
+
+    let limit = if max_commits == 0 {
+        usize::MAX
+    } else {
+        max_commits
+    };
+
+    // Keyed by path and author address, or name for commits without one
+    let mut authors: BTreeMap<(String, String), FileAuthor> = BTreeMap::new();
+    for oid in revwalk.take(limit) {
+        let commit = repo.find_commit(oid?)?;
+        // A merge repeats the changes of the commits it merges
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let author = commit.author();
+        let name = author.name().unwrap_or_default().trim().to_string();
+        let email = author.email().unwrap_or_default().trim().to_lowercase();
+        if name.is_empty() && email.is_empty() {
+            continue;
+        }
+        let commit_sha = commit.id().to_string();
+        let commit_date = DateTime::from_timestamp(author.when().seconds(), 0)
+            .map(|d| d.to_rfc3339())
+            .unwrap_or_default();
+
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+        for delta in diff.deltas() {
+            if delta.status() == Delta::Deleted {
+                continue;
+            }
+            let Some(path) = delta.new_file().path().and_then(|p| p.to_str()) else {
+                continue;
+            };
+            if !current.contains(path) {
+                continue;
+            }
+
+            let key = if email.is_empty() { &name } else { &email };
+            let file_author = authors
+                .entry((path.to_string(), key.clone()))
+                .or_insert_with(|| FileAuthor {
+                    file_path: path.to_string(),
+                    author_name: name.clone(),
+                    author_email: email.clone(),
+                    commit_count: 0,
+                    first_commit_sha: commit_sha.clone(),
+                    first_commit_date: commit_date.clone(),
+                    last_commit_sha: commit_sha.clone(),
+                    last_commit_date: commit_date.clone(),
+                });
+            file_author.commit_count += 1;
+            // The walk goes by commit time, newest first; author dates can
+            // be out of that order
+            if commit_date <= file_author.first_commit_date {
+                file_author.first_commit_sha = commit_sha.clone();
+                file_author.first_commit_date = commit_date.clone();
+            }
+            if commit_date > file_author.last_commit_date {
+                file_author.last_commit_sha = commit_sha.clone();
+                file_author.last_commit_date = commit_date.clone();
+            }
+        }
+    }
+
+    Ok(authors.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{commit_fixture_changes, init_fixture_repo};
+
+    #[tokio::test]
+    async fn test_scan_authorship() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        init_fixture_repo(
+            &repo,
+            &[
+                ("README.md", "# App\n"),
+                ("src/main.c", "int main() { return 0; }\n"),
+                ("old.c", "int old() { return 0; }\n"),
+            ],
+        );
+        commit_fixture_changes(
+            &repo,
+            &[("src/main.c", "int main() { return 1; }\n")],
+            &["old.c"],
+            "Change main",
+        );
+
+        let authors = scan_authorship(&repo, 0).await.unwrap();
+        let files: Vec<&str> = authors.iter().map(|a| a.file_path.as_str()).collect();
+        assert_eq!(files, ["README.md", "src/main.c"]);
+
+        let main = &authors[1];
+        assert_eq!(main.author_name, "Fixture");
+        assert_eq!(main.author_email, "fixture@example.com");
+        assert_eq!(main.commit_count, 2);
+        assert_ne!(main.first_commit_sha, main.last_commit_sha);
+        assert!(main.first_commit_date <= main.last_commit_date);
+        assert_eq!(authors[0].commit_count, 1);
+
+        // Only the newest commit
+        let authors = scan_authorship(&repo, 1).await.unwrap();
+        assert_eq!(authors.len(), 1);
+        assert_eq!(authors[0].file_path, "src/main.c");
+    }
+}
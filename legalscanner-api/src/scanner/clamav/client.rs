@@ -0,0 +1,179 @@
+use crate::scanner::traits::{MalwareFinding, ScanError, ScanResult, Scanner};
+use crate::scanner::walk::collect_files;
+use async_trait::async_trait;
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Chunk size for the clamd INSTREAM protocol
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Matches clamd's default StreamMaxLength; larger files are skipped
+const DEFAULT_MAX_FILE_SIZE: u64 = 25 * 1024 * 1024;
+
+/// Malware scanner backed by a clamd daemon reachable over TCP
+pub struct ClamAvScanner {
+    pub address: String,
+    pub timeout: Duration,
+    pub max_file_size: u64,
+}
+
+impl ClamAvScanner {
+    pub fn new(address: String) -> Self {
+        Self {
+            address,
+            timeout: Duration::from_secs(60), // per file
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send a single command to clamd and return its NUL-terminated reply
+    async fn command(&self, stream: &mut TcpStream, command: &str) -> Result<String, ScanError> {
+        stream.write_all(format!("z{}\0", command).as_bytes()).await?;
+        read_reply(stream).await
+    }
+
+    /// Stream a file to clamd with INSTREAM and return the raw verdict
+    async fn scan_file(&self, path: &Path) -> Result<String, ScanError> {
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut stream = TcpStream::connect(&self.address).await.map_err(|e| {
+            ScanError::Unavailable(format!("Failed to connect to clamd at {}: {}", self.address, e))
+        })?;
+
+        stream.write_all(b"zINSTREAM\0").await?;
+
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        loop {
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            stream.write_all(&(read as u32).to_be_bytes()).await?;
+            stream.write_all(&buffer[..read]).await?;
+        }
+        // Zero-length chunk terminates the stream
+        stream.write_all(&0u32.to_be_bytes()).await?;
+
+        read_reply(&mut stream).await
+    }
+}
+
+#[async_trait]
+impl Scanner for ClamAvScanner {
+    fn name(&self) -> &str {
+        "clamav"
+    }
+
+    async fn scan(&self, repo_path: &Path) -> Result<Vec<ScanResult>, ScanError> {
+        tracing::info!("Starting ClamAV scan for {:?}", repo_path);
+
+        let files = collect_files(repo_path).await?;
+        let mut results = Vec::new();
+
+        for path in files {
+            let size = tokio::fs::metadata(&path).await?.len();
+            if size > self.max_file_size {
+                tracing::warn!("Skipping {:?} for malware scan: {} bytes exceeds limit", path, size);
+                continue;
+            }
+
+            let reply = tokio::time::timeout(self.timeout, self.scan_file(&path))
+                .await
+                .map_err(|_| ScanError::Failed(format!("ClamAV timed out scanning {:?}", path)))??;
+
+            if let Some(signature) = parse_clamd_response(&reply)? {
+                let file_path = path
+                    .strip_prefix(repo_path)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .into_owned();
+                tracing::warn!("Malware detected in {}: {}", file_path, signature);
+
+                results.push(ScanResult {
+                    file_path,
+                    licenses: Vec::new(),
+                    copyrights: Vec::new(),
+                    ecc_findings: Vec::new(),
+                    malware_findings: vec![MalwareFinding {
+                        signature,
+                        source: "clamav".to_string(),
+                    }],
+                    package_findings: Vec::new(),
+                    model_licenses: Vec::new(),
+                });
+            }
+        }
+
+        tracing::info!("ClamAV scan completed, {} infected files", results.len());
+
+        Ok(results)
+    }
+
+    async fn health_check(&self) -> Result<(), ScanError> {
+        let mut stream = TcpStream::connect(&self.address).await.map_err(|e| {
+            ScanError::Unavailable(format!("Failed to connect to clamd at {}: {}", self.address, e))
+        })?;
+
+        let reply = self.command(&mut stream, "PING").await?;
+        if reply == "PONG" {
+            Ok(())
+        } else {
+            Err(ScanError::Unavailable(format!("Unexpected clamd reply: {}", reply)))
+        }
+    }
+}
+
+/// Read a NUL-terminated reply from clamd
+async fn read_reply(stream: &mut TcpStream) -> Result<String, ScanError> {
+    let mut reply = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        let read = stream.read(&mut byte).await?;
+        if read == 0 || byte[0] == 0 {
+            break;
+        }
+        reply.push(byte[0]);
+    }
+    Ok(String::from_utf8_lossy(&reply).trim().to_string())
+}
+
+/// Parse a clamd verdict. Returns the signature name if the stream is infected.
+pub fn parse_clamd_response(reply: &str) -> Result<Option<String>, ScanError> {
+    let verdict = reply.split_once(": ").map(|(_, v)| v).unwrap_or(reply);
+
+    if verdict == "OK" {
+        Ok(None)
+    } else if let Some(signature) = verdict.strip_suffix(" FOUND") {
+        Ok(Some(signature.to_string()))
+    } else {
+        Err(ScanError::Failed(format!("clamd error: {}", reply)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scanner_creation() {
+        let scanner = ClamAvScanner::new("localhost:3310".to_string());
+        assert_eq!(scanner.name(), "clamav");
+        assert_eq!(scanner.max_file_size, DEFAULT_MAX_FILE_SIZE);
+    }
+
+    #[test]
+    fn test_parse_clamd_response() {
+        assert_eq!(parse_clamd_response("stream: OK").unwrap(), None);
+        assert_eq!(
+            parse_clamd_response("stream: Eicar-Test-Signature FOUND").unwrap(),
+            Some("Eicar-Test-Signature".to_string())
+        );
+        assert!(parse_clamd_response("INSTREAM size limit exceeded. ERROR").is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+use utoipa::ToSchema;
+
+/// Root-level files that conventionally hold the project license text
+const LICENSE_FILE_NAMES: &[&str] = &[
+    "LICENSE",
+    "LICENSE.md",
+    "LICENSE.txt",
+    "LICENCE",
+    "LICENCE.md",
+    "LICENCE.txt",
+    "COPYING",
+    "COPYING.md",
+    "COPYING.txt",
+];
+
+/// A license the repository declares about itself
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeclaredLicense {
+    pub source: String,  // file the declaration was read from
+    pub license: String, // SPDX identifier or expression
+}
+
+/// Detect the licenses a repository declares in its root license files and
+/// package manifests. Does not inspect individual source files.
+pub async fn detect_declared_licenses(repo_path: &Path) -> Vec<DeclaredLicense> {
+    let mut declared = Vec::new();
+
+    for file_name in LICENSE_FILE_NAMES {
+        if let Ok(text) = tokio::fs::read_to_string(repo_path.join(file_name)).await {
+            match identify_license_text(&text) {
+                Some(license) => declared.push(DeclaredLicense {
+                    source: file_name.to_string(),
+                    license: license.to_string(),
+                }),
+                None => tracing::debug!("Could not identify license text in {}", file_name),
+            }
+        }
+    }
+
+    if let Ok(text) = tokio::fs::read_to_string(repo_path.join("Cargo.toml")).await {
+        if let Some(license) = manifest_license_field(&text) {
+            declared.push(DeclaredLicense {
+                source: "Cargo.toml".to_string(),
+                license,
+            });
+        }
+    }
+
+    if let Ok(text) = tokio::fs::read_to_string(repo_path.join("pyproject.toml")).await {
+        if let Some(license) = manifest_license_field(&text) {
+            declared.push(DeclaredLicense {
+                source: "pyproject.toml".to_string(),
+                license,
+            });
+        }
+    }
+
+    if let Ok(text) = tokio::fs::read_to_string(repo_path.join("package.json")).await {
+        if let Some(license) = serde_json::from_str::<serde_json::Value>(&text)
+            .ok()
+            .and_then(|v| v.get("license").and_then(|l| l.as_str()).map(str::to_string))
+        {
+            declared.push(DeclaredLicense {
+                source: "package.json".to_string(),
+                license,
+            });
+        }
+    }
+
+    declared
+}
+
+/// Identify a well-known license from its full text
+pub fn identify_license_text(text: &str) -> Option<&'static str> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let upper = text.to_uppercase();
+
+    // Order matters: more specific licenses must be checked before their families
+    if upper.contains("GNU AFFERO GENERAL PUBLIC LICENSE") {
+        Some("AGPL-3.0-only")
+    } else if upper.contains("GNU LESSER GENERAL PUBLIC LICENSE") {
+        if upper.contains("VERSION 2.1") {
+            Some("LGPL-2.1-only")
+        } else {
+            Some("LGPL-3.0-only")
+        }
+    } else if upper.contains("GNU GENERAL PUBLIC LICENSE") {
+        if upper.contains("VERSION 3") {
+            Some("GPL-3.0-only")
+        } else {
+            Some("GPL-2.0-only")
+        }
+    } else if upper.contains("APACHE LICENSE") && upper.contains("VERSION 2.0") {
+        Some("Apache-2.0")
+    } else if upper.contains("MOZILLA PUBLIC LICENSE VERSION 2.0") {
+        Some("MPL-2.0")
+    } else if upper.contains("PERMISSION IS HEREBY GRANTED, FREE OF CHARGE") {
+        Some("MIT")
+    } else if upper.contains("PERMISSION TO USE, COPY, MODIFY, AND/OR DISTRIBUTE") {
+        Some("ISC")
+    } else if upper.contains("REDISTRIBUTION AND USE IN SOURCE AND BINARY FORMS") {
+        if upper.contains("NEITHER THE NAME") {
+            Some("BSD-3-Clause")
+        } else {
+            Some("BSD-2-Clause")
+        }
+    } else if upper.contains("THIS IS FREE AND UNENCUMBERED SOFTWARE") {
+        Some("Unlicense")
+    } else if upper.contains("CC0 1.0 UNIVERSAL") {
+        Some("CC0-1.0")
+    } else {
+        None
+    }
+}
+
+/// Extract `license = "..."` from a TOML manifest
+fn manifest_license_field(text: &str) -> Option<String> {
+    let re = Regex::new(r#"(?m)^\s*license\s*=\s*(?:\{\s*text\s*=\s*)?"([^"]+)""#).unwrap();
+    re.captures(text).map(|c| c[1].trim().to_string())
+}
+
+/// Check whether a declared license (possibly an SPDX expression such as
+/// `MIT OR Apache-2.0`) satisfies the expected license
+pub fn license_matches(expected: &str, declared: &str) -> bool {
+    let expected = normalize_spdx_id(expected);
+    declared
+        .split(|c: char| c == '(' || c == ')' || c.is_whitespace() || c == '/')
+        .filter(|t| !t.is_empty() && !matches!(t.to_uppercase().as_str(), "OR" | "AND" | "WITH"))
+        .any(|t| normalize_spdx_id(t) == expected)
+}
+
+fn normalize_spdx_id(id: &str) -> String {
+    id.trim()
+        .trim_end_matches('+')
+        .trim_end_matches("-only")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identify_license_text() {
+        let mit = "MIT License\n\nPermission is hereby granted, free of charge, to any person";
+        assert_eq!(identify_license_text(mit), Some("MIT"));
+
+        let apache = "Apache License\n   Version 2.0, January 2004";
+        assert_eq!(identify_license_text(apache), Some("Apache-2.0"));
+
+        let lgpl = "GNU LESSER GENERAL PUBLIC LICENSE\n Version 2.1, February 1999";
+        assert_eq!(identify_license_text(lgpl), Some("LGPL-2.1-only"));
+
+        assert_eq!(identify_license_text("All rights reserved."), None);
+    }
+
+    #[test]
+    fn test_manifest_license_field() {
+        let cargo = "[package]\nname = \"demo\"\nlicense = \"MIT OR Apache-2.0\"\n";
+        assert_eq!(manifest_license_field(cargo), Some("MIT OR Apache-2.0".to_string()));
+
+        let pyproject = "[project]\nlicense = {text = \"BSD-3-Clause\"}\n";
+        assert_eq!(manifest_license_field(pyproject), Some("BSD-3-Clause".to_string()));
+    }
+
+    #[test]
+    fn test_license_matches() {
+        assert!(license_matches("MIT", "MIT OR Apache-2.0"));
+        assert!(license_matches("GPL-3.0", "GPL-3.0-only"));
+        assert!(license_matches("apache-2.0", "Apache-2.0"));
+        assert!(!license_matches("MIT", "BSD-3-Clause"));
+    }
+}
@@ -0,0 +1,268 @@
+use crate::scanner::traits::{LicenseCategory, LicenseFinding, ScanError, ScanResult, Scanner};
+use crate::scanner::walk::collect_files;
+use async_trait::async_trait;
+use regex::Regex;
+use std::path::Path;
+
+/// Documentation formats inspected for content licenses
+const DOC_EXTENSIONS: &[&str] = &["md", "markdown", "rst", "adoc", "txt"];
+
+/// File name prefixes that conventionally hold dataset licenses
+const DATA_LICENSE_PREFIXES: &[&str] = &["DATA_LICENSE", "DATA-LICENSE", "DATASET_LICENSE", "LICENSE-DATA", "LICENSE_DATA"];
+
+/// Licenses that only make sense for data, regardless of where they appear
+const DATA_ONLY_LICENSES: &[&str] = &["ODbL-1.0", "ODC-By-1.0", "PDDL-1.0", "CDLA-Permissive-1.0", "CDLA-Permissive-2.0", "CDLA-Sharing-1.0"];
+
+/// Larger files are skipped, they are unlikely to be hand-written docs
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Detects Creative Commons, documentation and dataset licenses in
+/// Markdown/reStructuredText files, dataset cards and data license files.
+/// Findings are categorized as `documentation` or `data`, separate from code licenses.
+pub struct DocumentationLicenseScanner;
+
+impl DocumentationLicenseScanner {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for DocumentationLicenseScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Scanner for DocumentationLicenseScanner {
+    fn name(&self) -> &str {
+        "documentation"
+    }
+
+    async fn scan(&self, repo_path: &Path) -> Result<Vec<ScanResult>, ScanError> {
+        tracing::info!("Starting documentation license scan for {:?}", repo_path);
+
+        let mut results = Vec::new();
+
+        for path in collect_files(repo_path).await? {
+            let file_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let is_data_license_file = is_data_license_file(&file_name);
+            let is_doc = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(|e| DOC_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+                .unwrap_or(false);
+
+            if !is_doc && !is_data_license_file {
+                continue;
+            }
+            if tokio::fs::metadata(&path).await?.len() > MAX_FILE_SIZE {
+                continue;
+            }
+
+            let Ok(text) = tokio::fs::read_to_string(&path).await else {
+                continue;
+            };
+
+            let is_dataset_card = is_dataset_card(&text);
+            let licenses = detect_content_licenses(&text);
+            if licenses.is_empty() {
+                continue;
+            }
+
+            let relative = path.strip_prefix(repo_path).unwrap_or(&path);
+            let in_data_dir = relative
+                .components()
+                .any(|c| matches!(c.as_os_str().to_str(), Some("data" | "datasets" | "dataset")));
+
+            results.push(ScanResult {
+                file_path: relative.to_string_lossy().into_owned(),
+                licenses: licenses
+                    .into_iter()
+                    .map(|spdx_id| {
+                        let category = if is_data_license_file
+                            || is_dataset_card
+                            || in_data_dir
+                            || DATA_ONLY_LICENSES.contains(&spdx_id.as_str())
+                        {
+                            LicenseCategory::Data
+                        } else {
+                            LicenseCategory::Documentation
+                        };
+                        LicenseFinding {
+                            name: spdx_id.clone(),
+                            spdx_id: Some(spdx_id),
+                            confidence: 0.9,
+                            category,
+                            provenance: None,
+                            agreement: None,
+                            exception: None,
+                        }
+                    })
+                    .collect(),
+                copyrights: Vec::new(),
+                ecc_findings: Vec::new(),
+                malware_findings: Vec::new(),
+                package_findings: Vec::new(),
+                model_licenses: Vec::new(),
+            });
+        }
+
+        tracing::info!(
+            "Documentation license scan completed, {} files with content licenses",
+            results.len()
+        );
+
+        Ok(results)
+    }
+
+    async fn health_check(&self) -> Result<(), ScanError> {
+        Ok(())
+    }
+}
+
+fn is_data_license_file(file_name: &str) -> bool {
+    let upper = file_name.to_uppercase();
+    DATA_LICENSE_PREFIXES.iter().any(|p| upper.starts_with(p))
+}
+
+/// Hugging Face style dataset cards carry YAML front matter with dataset keys
+fn is_dataset_card(text: &str) -> bool {
+    front_matter(text)
+        .map(|fm| {
+            fm.lines().any(|l| {
+                let key = l.split(':').next().unwrap_or("").trim();
+                matches!(key, "dataset_info" | "task_categories" | "size_categories" | "configs")
+            })
+        })
+        .unwrap_or(false)
+}
+
+pub(crate) fn front_matter(text: &str) -> Option<&str> {
+    let rest = text.strip_prefix("---")?;
+    let end = rest.find("\n---")?;
+    Some(&rest[..end])
+}
+
+/// Detect content licenses and return their SPDX identifiers (deduplicated, in order found)
+pub fn detect_content_licenses(text: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    let mut push = |id: String| {
+        if !found.contains(&id) {
+            found.push(id);
+        }
+    };
+
+    // YAML front matter, e.g. `license: cc-by-4.0` in dataset/model cards
+    if let Some(fm) = front_matter(text) {
+        let re = Regex::new(r"(?m)^license:\s*([A-Za-z0-9.\-]+)\s*$").unwrap();
+        for cap in re.captures_iter(fm) {
+            if let Some(id) = normalize_license_id(&cap[1]) {
+                push(id);
+            }
+        }
+    }
+
+    // Creative Commons deed URLs
+    let cc_url = Regex::new(r"(?i)creativecommons\.org/licenses/(by(?:-nc)?(?:-sa|-nd)?)/(\d\.\d)").unwrap();
+    for cap in cc_url.captures_iter(text) {
+        push(format!("CC-{}-{}", cap[1].to_uppercase(), &cap[2]));
+    }
+    if Regex::new(r"(?i)creativecommons\.org/publicdomain/zero/1\.0").unwrap().is_match(text) {
+        push("CC0-1.0".to_string());
+    }
+
+    // Short identifiers such as "CC BY-SA 4.0" or "CC-BY-4.0"
+    let cc_short = Regex::new(r"\bCC[- ]BY((?:-NC)?(?:-SA|-ND)?)[- ](\d\.\d)\b").unwrap();
+    for cap in cc_short.captures_iter(text) {
+        push(format!("CC-BY{}-{}", &cap[1], &cap[2]));
+    }
+
+    // Spelled-out Creative Commons names
+    let cc_long = Regex::new(
+        r"(?i)Creative Commons Attribution(-NonCommercial)?(-ShareAlike|-NoDerivatives|-NoDerivs)? (\d\.\d)",
+    )
+    .unwrap();
+    for cap in cc_long.captures_iter(text) {
+        let nc = if cap.get(1).is_some() { "-NC" } else { "" };
+        let variant = match cap.get(2).map(|m| m.as_str().to_lowercase()) {
+            Some(v) if v == "-sharealike" => "-SA",
+            Some(_) => "-ND",
+            None => "",
+        };
+        push(format!("CC-BY{}{}-{}", nc, variant, &cap[3]));
+    }
+
+    // Documentation and data licenses
+    let gfdl = Regex::new(r"(?i)GNU Free Documentation License,?\s+Version (1\.[123])").unwrap();
+    for cap in gfdl.captures_iter(text) {
+        push(format!("GFDL-{}-only", &cap[1]));
+    }
+    let upper = text.to_uppercase();
+    if upper.contains("OPEN DATABASE LICENSE") || text.contains("ODbL") {
+        push("ODbL-1.0".to_string());
+    }
+    if upper.contains("OPEN DATA COMMONS ATTRIBUTION LICENSE") {
+        push("ODC-By-1.0".to_string());
+    }
+    if upper.contains("PUBLIC DOMAIN DEDICATION AND LICENSE") {
+        push("PDDL-1.0".to_string());
+    }
+    let cdla = Regex::new(r"CDLA-(Permissive|Sharing)-(\d\.\d)").unwrap();
+    for cap in cdla.captures_iter(text) {
+        push(format!("CDLA-{}-{}", &cap[1], &cap[2]));
+    }
+
+    found
+}
+
+/// Map lowercase card identifiers (`cc-by-sa-4.0`, `odbl`) to SPDX identifiers
+fn normalize_license_id(id: &str) -> Option<String> {
+    let lower = id.to_lowercase();
+    if let Some(rest) = lower.strip_prefix("cc-by") {
+        return Some(format!("CC-BY{}", rest.to_uppercase()));
+    }
+    match lower.as_str() {
+        "cc0-1.0" => Some("CC0-1.0".to_string()),
+        "odbl" | "odbl-1.0" => Some("ODbL-1.0".to_string()),
+        "odc-by" | "odc-by-1.0" => Some("ODC-By-1.0".to_string()),
+        "pddl" | "pddl-1.0" => Some("PDDL-1.0".to_string()),
+        "cdla-permissive-1.0" => Some("CDLA-Permissive-1.0".to_string()),
+        "cdla-permissive-2.0" => Some("CDLA-Permissive-2.0".to_string()),
+        "cdla-sharing-1.0" => Some("CDLA-Sharing-1.0".to_string()),
+        "gfdl" | "gfdl-1.3" => Some("GFDL-1.3-only".to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_creative_commons() {
+        let text = "Content is licensed under [CC BY-SA 4.0](https://creativecommons.org/licenses/by-sa/4.0/).";
+        assert_eq!(detect_content_licenses(text), vec!["CC-BY-SA-4.0".to_string()]);
+
+        let text = "This work is licensed under a Creative Commons Attribution-NonCommercial 4.0 International License.";
+        assert_eq!(detect_content_licenses(text), vec!["CC-BY-NC-4.0".to_string()]);
+    }
+
+    #[test]
+    fn test_dataset_card() {
+        let card = "---\nlicense: cc-by-4.0\ntask_categories:\n- text-classification\n---\n# My dataset\n";
+        assert!(is_dataset_card(card));
+        assert_eq!(detect_content_licenses(card), vec!["CC-BY-4.0".to_string()]);
+        assert!(!is_dataset_card("# Plain readme\n"));
+    }
+
+    #[test]
+    fn test_data_license_file_names() {
+        assert!(is_data_license_file("DATA_LICENSE"));
+        assert!(is_data_license_file("LICENSE-DATA.txt"));
+        assert!(!is_data_license_file("LICENSE"));
+    }
+}
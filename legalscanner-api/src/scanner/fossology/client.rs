@@ -1,10 +1,20 @@
 use base64::{engine::general_purpose, Engine};
-use reqwest::Client;
+use futures_util::StreamExt;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 
-use crate::scanner::traits::ScanError;
+use crate::progress::ProgressReporter;
+use crate::scanner::traits::{ScanError, ScanSlot};
+
+/// Uploads/jobs allowed to run against the Fossology instance at once
+pub const DEFAULT_MAX_CONCURRENT_JOBS: usize = 2;
+
+/// Archives are streamed to Fossology in chunks of this size
+const UPLOAD_CHUNK_SIZE: usize = 256 * 1024;
 
 #[derive(Clone)]
 pub struct FossologyClient {
@@ -13,6 +23,8 @@ pub struct FossologyClient {
     username: String,
     password: String,
     client: Client,
+    /// Shared by every clone of this client so all scans draw from one pool
+    slots: Arc<Semaphore>,
 }
 
 #[derive(Debug, Serialize)]
@@ -21,14 +33,50 @@ pub struct UploadRequest {
     pub upload_description: String,
 }
 
+/// Fossology's `{code, message, type}` reply to creating an upload or job.
+/// `message` is the new ID on success, but newer Fossology versions put a
+/// text or an object there for some errors.
 #[derive(Debug, Deserialize)]
-pub struct UploadResponse {
+pub struct InfoResponse {
     pub code: i32,
-    pub message: i32,  // This is actually the upload_id
-    #[serde(rename = "type")]
+    pub message: InfoMessage,
+    #[serde(rename = "type", default)]
     pub response_type: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum InfoMessage {
+    Id(i32),
+    Text(String),
+    Other(serde_json::Value),
+}
+
+impl InfoMessage {
+    /// The ID, also when sent as a numeric string
+    fn id(&self) -> Option<i32> {
+        match self {
+            InfoMessage::Id(id) => Some(*id),
+            InfoMessage::Text(text) => text.trim().parse().ok(),
+            InfoMessage::Other(_) => None,
+        }
+    }
+
+    /// Human readable error: the text, or the `message`, `error` or `detail`
+    /// field of an object
+    fn describe(&self) -> String {
+        match self {
+            InfoMessage::Id(id) => id.to_string(),
+            InfoMessage::Text(text) => text.clone(),
+            InfoMessage::Other(value) => ["message", "error", "detail"]
+                .iter()
+                .find_map(|key| value.get(key).and_then(|v| v.as_str()))
+                .map(str::to_string)
+                .unwrap_or_else(|| value.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct JobRequest {
     pub upload_id: i32,
@@ -65,14 +113,6 @@ impl Default for AnalysisSpec {
     }
 }
 
-#[derive(Debug, Deserialize)]
-pub struct JobResponse {
-    pub code: i32,
-    pub message: i32,  // This is actually the job_id
-    #[serde(rename = "type")]
-    pub response_type: String,
-}
-
 #[derive(Debug, Deserialize)]
 pub struct JobStatus {
     pub id: i32,
@@ -94,6 +134,20 @@ pub struct LicenseFinding {
     pub match_percentage: f32,
 }
 
+/// Licenses a reviewer concluded for a file in Fossology's clearing UI
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClearingDecision {
+    pub file_path: String,
+    pub concluded: Vec<String>,
+}
+
+/// A file of an upload, identified in Fossology's upload tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct UploadItem {
+    pub item_id: i64,
+    pub file_path: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CopyrightResult {
     pub file_path: String,
@@ -112,6 +166,8 @@ pub struct CopyrightFinding {
 pub struct FossologyLicenseResponse {
     #[serde(rename = "filePath")]
     pub file_path: String,
+    #[serde(rename = "uploadTreeId", default)]
+    pub upload_tree_id: Option<i64>,
     pub findings: Option<FossologyFindings>,
 }
 
@@ -157,6 +213,67 @@ pub struct UploadHash {
     pub size: i64,
 }
 
+/// ID of the upload or job created by `action`, or Fossology's own error
+/// message when it refused
+fn created_id(action: &str, status: StatusCode, body: &str) -> Result<i32, ScanError> {
+    let Ok(info) = serde_json::from_str::<InfoResponse>(body) else {
+        let body: String = body.trim().chars().take(500).collect();
+        return Err(if status.is_success() {
+            ScanError::ParseError(format!("Unexpected {} response: {}", action, body))
+        } else {
+            ScanError::Failed(format!("{} failed: {} {}", action, status, body))
+        });
+    };
+
+    let is_error = !status.is_success() || info.response_type.eq_ignore_ascii_case("error");
+    match info.message.id() {
+        Some(id) if !is_error => Ok(id),
+        _ => Err(ScanError::Rejected(format!(
+            "{} failed: Fossology answered {}: {}",
+            action,
+            info.code,
+            info.message.describe()
+        ))),
+    }
+}
+
+/// Keep the upload name readable but safe to use as a file name
+/// Request body for an upload, reporting progress in steps of 10% as the
+/// archive is sent
+fn upload_body(archive: Vec<u8>, progress: ProgressReporter) -> reqwest::Body {
+    let total = archive.len().max(1);
+    let chunks: Vec<Vec<u8>> = archive
+        .chunks(UPLOAD_CHUNK_SIZE)
+        .map(<[u8]>::to_vec)
+        .collect();
+
+    let mut sent = 0;
+    let mut reported = 0;
+    let stream = futures_util::stream::iter(chunks).map(move |chunk| {
+        sent += chunk.len();
+        let percent = (sent * 100 / total) as u8;
+        if percent >= reported + 10 || (percent == 100 && reported < 100) {
+            reported = percent;
+            progress.percent("Uploading to Fossology", percent);
+        }
+        Ok::<_, std::io::Error>(chunk)
+    });
+
+    reqwest::Body::wrap_stream(stream)
+}
+
+fn archive_file_stem(upload_name: &str) -> String {
+    let stem: String = upload_name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '@') { c } else { '_' })
+        .collect();
+    if stem.is_empty() {
+        "repository".to_string()
+    } else {
+        stem
+    }
+}
+
 impl FossologyClient {
     pub fn new(base_url: String, api_token: String) -> Self {
         let client = Client::builder()
@@ -174,9 +291,35 @@ impl FossologyClient {
             username,
             password,
             client,
+            slots: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_JOBS)),
         }
     }
 
+    /// Limit how many scans may upload to and run jobs on Fossology at once
+    pub fn with_max_concurrent_jobs(mut self, max_jobs: usize) -> Self {
+        self.slots = Arc::new(Semaphore::new(max_jobs.max(1)));
+        self
+    }
+
+    /// Wait for an upload/job slot. Tokio's semaphore is fair, so scans are
+    /// granted slots in the order they asked for them.
+    pub async fn acquire_slot(&self) -> Result<ScanSlot, ScanError> {
+        let started = Instant::now();
+        let permit = self
+            .slots
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| ScanError::Unavailable("Fossology slot pool closed".to_string()))?;
+        let waited = started.elapsed();
+
+        if waited >= Duration::from_secs(1) {
+            tracing::info!("Waited {:?} for a Fossology slot", waited);
+        }
+
+        Ok(ScanSlot::new(permit, waited))
+    }
+
     fn auth_header(&self) -> String {
         // Try API token first, fall back to basic auth
         if !self.api_token.is_empty() && self.api_token != "your_token_here" {
@@ -213,17 +356,44 @@ impl FossologyClient {
         }
     }
 
+    /// Verify the API token is accepted, using an endpoint that requires
+    /// authentication (the version endpoint does not)
+    pub async fn check_auth(&self) -> Result<(), ScanError> {
+        let url = format!("{}/repo/api/v1/folders", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", &self.auth_header())
+            .send()
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            status @ (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => Err(
+                ScanError::Unavailable(format!("Fossology rejected the API token: {}", status)),
+            ),
+            status => Err(ScanError::Unavailable(format!(
+                "Fossology authentication check failed: {}",
+                status
+            ))),
+        }
+    }
+
     /// Upload a file or directory to Fossology
+    /// Fossology shows the uploaded file name as the upload name, so the
+    /// archive is named after `upload_name` as well as using it as description
     pub async fn upload_from_path(
         &self,
         path: &Path,
         folder_id: i32,
-        description: &str,
+        upload_name: &str,
+        progress: &ProgressReporter,
     ) -> Result<i32, ScanError> {
         tracing::info!("Uploading {:?} to Fossology folder {}", path, folder_id);
 
         // Create a tar.gz archive of the path
-        let archive_path = self.create_archive(path).await?;
+        let archive_path = Self::create_archive(path).await?;
 
         let url = format!("{}/repo/api/v1/uploads", self.base_url);
 
@@ -232,15 +402,19 @@ impl FossologyClient {
             ScanError::Failed(format!("Failed to read archive: {}", e))
         })?;
 
-        // Create multipart form
+        // Create multipart form, streaming the archive to report upload progress
+        let total = archive_bytes.len() as u64;
         let form = reqwest::multipart::Form::new()
-            .text("uploadDescription", description.to_string())
+            .text("uploadDescription", upload_name.to_string())
             .part(
                 "fileInput",
-                reqwest::multipart::Part::bytes(archive_bytes)
-                    .file_name("repository.tar.gz")
-                    .mime_str("application/gzip")
-                    .unwrap(),
+                reqwest::multipart::Part::stream_with_length(
+                    upload_body(archive_bytes, progress.clone()),
+                    total,
+                )
+                .file_name(format!("{}.tar.gz", archive_file_stem(upload_name)))
+                .mime_str("application/gzip")
+                .unwrap(),
             );
 
         let response = self
@@ -256,17 +430,11 @@ impl FossologyClient {
         // Clean up the archive
         tokio::fs::remove_file(&archive_path).await.ok();
 
-        if response.status().is_success() {
-            let upload_response: UploadResponse = response.json().await?;
-            tracing::info!("Upload successful, ID: {}", upload_response.message);
-            Ok(upload_response.message)
-        } else {
-            let error_text = response.text().await.unwrap_or_default();
-            Err(ScanError::Failed(format!(
-                "Upload failed: {}",
-                error_text
-            )))
-        }
+        let status = response.status();
+        let body = response.text().await?;
+        let upload_id = created_id("Upload", status, &body)?;
+        tracing::info!("Upload successful, ID: {}", upload_id);
+        Ok(upload_id)
     }
 
     /// Wait for upload to be ready (fully extracted and indexed by Fossology)
@@ -381,17 +549,11 @@ impl FossologyClient {
             .send()
             .await?;
 
-        if response.status().is_success() {
-            let job_response: JobResponse = response.json().await?;
-            tracing::info!("Job created successfully, ID: {}", job_response.message);
-            Ok(job_response.message)
-        } else {
-            let error_text = response.text().await.unwrap_or_default();
-            Err(ScanError::Failed(format!(
-                "Job creation failed: {}",
-                error_text
-            )))
-        }
+        let status = response.status();
+        let body = response.text().await?;
+        let job_id = created_id("Job creation", status, &body)?;
+        tracing::info!("Job created successfully, ID: {}", job_id);
+        Ok(job_id)
     }
 
     /// Get job status
@@ -417,7 +579,11 @@ impl FossologyClient {
     }
 
     /// Wait for a job to complete
-    pub async fn wait_for_job_completion(&self, job_id: i32) -> Result<(), ScanError> {
+    pub async fn wait_for_job_completion(
+        &self,
+        job_id: i32,
+        progress: &ProgressReporter,
+    ) -> Result<(), ScanError> {
         tracing::info!("Waiting for job {} to complete", job_id);
 
         let max_attempts = 120; // 10 minutes with 5-second intervals
@@ -448,6 +614,10 @@ impl FossologyClient {
                             return Err(ScanError::Failed(format!("Job {} failed", job_id)));
                         }
                         _ => {
+                            if let Some(eta) = status.eta {
+                                let message = format!("Fossology job {} {}", job_id, status.status);
+                                progress.eta(&message, eta as i64);
+                            }
                             // Still running, wait and try again
                             tokio::time::sleep(Duration::from_secs(5)).await;
                         }
@@ -569,6 +739,173 @@ impl FossologyClient {
         }
     }
 
+    /// Clearing decisions of an upload: every file a reviewer concluded
+    /// licenses for. Files without a decision are left out; `None` when the
+    /// upload does not exist.
+    pub async fn get_clearing_decisions(
+        &self,
+        upload_id: i32,
+    ) -> Result<Option<Vec<ClearingDecision>>, ScanError> {
+        tracing::info!("Fetching clearing decisions for upload {}", upload_id);
+
+        let url = format!(
+            "{}/repo/api/v1/uploads/{}/licenses",
+            self.base_url, upload_id
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", &self.auth_header())
+            .query(&[("agent", "nomos,monk,ojo"), ("containers", "false")])
+            .send()
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => {}
+            StatusCode::NOT_FOUND => return Ok(None),
+            status => {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(ScanError::Failed(format!(
+                    "Failed to get clearing decisions: {} - {}",
+                    status, error_text
+                )));
+            }
+        }
+
+        let responses: Vec<FossologyLicenseResponse> = response.json().await.map_err(|e| {
+            ScanError::ParseError(format!("Failed to parse license response: {}", e))
+        })?;
+
+        let decisions = responses
+            .into_iter()
+            .filter_map(|file| {
+                let concluded: Vec<String> = file
+                    .findings?
+                    .conclusion?
+                    .into_iter()
+                    .filter(|license| license != "No_license_found")
+                    .collect();
+                (!concluded.is_empty()).then_some(ClearingDecision {
+                    file_path: file.file_path,
+                    concluded,
+                })
+            })
+            .collect();
+        Ok(Some(decisions))
+    }
+
+    /// Files of an upload with their upload tree IDs, which clearing
+    /// decisions are recorded against. `None` when the upload does not exist.
+    pub async fn get_upload_items(
+        &self,
+        upload_id: i32,
+    ) -> Result<Option<Vec<UploadItem>>, ScanError> {
+        let url = format!(
+            "{}/repo/api/v1/uploads/{}/licenses",
+            self.base_url, upload_id
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", &self.auth_header())
+            .query(&[("agent", "nomos,monk,ojo"), ("containers", "false")])
+            .send()
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => {}
+            StatusCode::NOT_FOUND => return Ok(None),
+            status => {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(ScanError::Failed(format!(
+                    "Failed to get upload items: {} - {}",
+                    status, error_text
+                )));
+            }
+        }
+
+        let responses: Vec<FossologyLicenseResponse> = response.json().await.map_err(|e| {
+            ScanError::ParseError(format!("Failed to parse license response: {}", e))
+        })?;
+
+        Ok(Some(
+            responses
+                .into_iter()
+                .filter_map(|file| {
+                    Some(UploadItem {
+                        item_id: file.upload_tree_id?,
+                        file_path: file.file_path,
+                    })
+                })
+                .collect(),
+        ))
+    }
+
+    /// Record a clearing decision for one file of an upload: the concluded
+    /// licenses (Fossology short names) as an `Identified` decision, or an
+    /// `Irrelevant` decision for files without relevant licenses
+    pub async fn set_clearing_decision(
+        &self,
+        upload_id: i32,
+        item_id: i64,
+        concluded: &[String],
+        irrelevant: bool,
+    ) -> Result<(), ScanError> {
+        let item_url = format!(
+            "{}/repo/api/v1/uploads/{}/item/{}",
+            self.base_url, upload_id, item_id
+        );
+
+        if !irrelevant {
+            let licenses: Vec<_> = concluded
+                .iter()
+                .map(|name| serde_json::json!({ "shortName": name, "add": true }))
+                .collect();
+            let response = self
+                .client
+                .put(format!("{}/licenses", item_url))
+                .header("Authorization", &self.auth_header())
+                .json(&licenses)
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(ScanError::Failed(format!(
+                    "Failed to set licenses of item {}: {} - {}",
+                    item_id, status, error_text
+                )));
+            }
+        }
+
+        let decision_type = if irrelevant {
+            "Irrelevant"
+        } else {
+            "Identified"
+        };
+        let response = self
+            .client
+            .put(format!("{}/clearing-decision", item_url))
+            .header("Authorization", &self.auth_header())
+            .json(&serde_json::json!({
+                "decisionType": decision_type,
+                "globalDecision": false
+            }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ScanError::Failed(format!(
+                "Failed to set clearing decision of item {}: {} - {}",
+                item_id, status, error_text
+            )));
+        }
+        Ok(())
+    }
+
     /// Get copyright results for an upload
     pub async fn get_copyrights(&self, upload_id: i32) -> Result<Vec<CopyrightResult>, ScanError> {
         tracing::info!("Fetching copyright results for upload {}", upload_id);
@@ -635,8 +972,8 @@ impl FossologyClient {
         }
     }
 
-    /// Create a tar.gz archive of a directory
-    async fn create_archive(&self, path: &Path) -> Result<std::path::PathBuf, ScanError> {
+    /// Create a tar.gz archive of a directory in the system temp directory
+    pub(crate) async fn create_archive(path: &Path) -> Result<std::path::PathBuf, ScanError> {
         use std::process::Command;
 
         let archive_name = format!("{}.tar.gz", uuid::Uuid::new_v4());
@@ -671,3 +1008,33 @@ fn is_printable_text(text: &str) -> bool {
         c == '\t' || c == '\n' || c == '\r' || (!c.is_control() && c.is_ascii()) || (!c.is_ascii() && c.is_alphanumeric())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_created_id_tolerates_message_types() {
+        let ok = StatusCode::CREATED;
+        let body = r#"{"code": 201, "message": 42, "type": "INFO"}"#;
+        assert_eq!(created_id("Upload", ok, body).unwrap(), 42);
+        let body = r#"{"code": 201, "message": "42", "type": "INFO"}"#;
+        assert_eq!(created_id("Upload", ok, body).unwrap(), 42);
+
+        let body = r#"{"code": 404, "message": "Folder does not exist", "type": "ERROR"}"#;
+        let error = created_id("Upload", StatusCode::NOT_FOUND, body).unwrap_err();
+        assert!(matches!(&error, ScanError::Rejected(m) if m.ends_with("404: Folder does not exist")));
+
+        let body = r#"{"code": 400, "message": {"error": "Invalid analysis"}, "type": "ERROR"}"#;
+        let error = created_id("Job creation", StatusCode::OK, body).unwrap_err();
+        assert!(matches!(&error, ScanError::Rejected(m) if m.ends_with("400: Invalid analysis")));
+    }
+
+    #[test]
+    fn test_created_id_reports_unexpected_bodies() {
+        let error = created_id("Upload", StatusCode::OK, "<html>").unwrap_err();
+        assert!(matches!(&error, ScanError::ParseError(m) if m.contains("<html>")));
+        let error = created_id("Upload", StatusCode::BAD_GATEWAY, "").unwrap_err();
+        assert!(matches!(error, ScanError::Failed(_)));
+    }
+}
@@ -1,15 +1,23 @@
 mod client;
 mod parser;
 
-pub use client::FossologyClient;
+pub use client::{ClearingDecision, FossologyClient, UploadItem, DEFAULT_MAX_CONCURRENT_JOBS};
+pub use parser::map_to_spdx;
 
-use crate::scanner::traits::{ScanError, ScanResult, Scanner};
+use crate::git::{read_repo_info, RepoInfo};
+use crate::progress::ProgressReporter;
+use crate::scanner::traits::{ScanError, ScanResult, ScanSlot, Scanner};
 use async_trait::async_trait;
 use std::path::Path;
 
+/// Upload name shown in Fossology's UI.
+/// Placeholders: `{repo}`, `{branch}`, `{sha}` (short), `{full_sha}`
+pub const DEFAULT_UPLOAD_NAME_TEMPLATE: &str = "{repo}@{branch} ({sha})";
+
 pub struct FossologyScanner {
     client: FossologyClient,
     folder_id: i32,
+    upload_name_template: String,
 }
 
 impl FossologyScanner {
@@ -17,6 +25,7 @@ impl FossologyScanner {
         Self {
             client: FossologyClient::new(base_url, api_token),
             folder_id: 1, // Default folder - TODO: make configurable
+            upload_name_template: DEFAULT_UPLOAD_NAME_TEMPLATE.to_string(),
         }
     }
 
@@ -24,8 +33,36 @@ impl FossologyScanner {
         Self {
             client: FossologyClient::new(base_url, api_token),
             folder_id,
+            upload_name_template: DEFAULT_UPLOAD_NAME_TEMPLATE.to_string(),
         }
     }
+
+    pub fn with_upload_name_template(mut self, template: String) -> Self {
+        self.upload_name_template = template;
+        self
+    }
+
+    pub fn with_max_concurrent_jobs(mut self, max_jobs: usize) -> Self {
+        self.client = self.client.with_max_concurrent_jobs(max_jobs);
+        self
+    }
+}
+
+/// Fill the upload name template from repository metadata, falling back to
+/// the checkout directory name when the repository has no origin remote
+pub fn render_upload_name(template: &str, info: &RepoInfo, repo_path: &Path) -> String {
+    let repo = info.name.clone().unwrap_or_else(|| {
+        repo_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "repository".to_string())
+    });
+
+    template
+        .replace("{repo}", &repo)
+        .replace("{branch}", info.branch.as_deref().unwrap_or("detached"))
+        .replace("{full_sha}", info.commit_sha.as_deref().unwrap_or("unknown"))
+        .replace("{sha}", info.short_sha().unwrap_or("unknown"))
 }
 
 #[async_trait]
@@ -35,22 +72,30 @@ impl Scanner for FossologyScanner {
     }
 
     async fn scan(&self, repo_path: &Path) -> Result<Vec<ScanResult>, ScanError> {
+        self.scan_with_progress(repo_path, &ProgressReporter::disabled())
+            .await
+    }
+
+    async fn scan_with_progress(
+        &self,
+        repo_path: &Path,
+        progress: &ProgressReporter,
+    ) -> Result<Vec<ScanResult>, ScanError> {
         tracing::info!("Starting Fossology scan for {:?}", repo_path);
 
-        // 1. Upload repository to Fossology
+        // 1. Upload repository to Fossology under a name legal staff can navigate
+        let repo_info = read_repo_info(repo_path).await;
+        let upload_name = render_upload_name(&self.upload_name_template, &repo_info, repo_path);
         let upload_id = self
             .client
-            .upload_from_path(
-                repo_path,
-                self.folder_id,
-                &format!("Repository scan: {}", repo_path.display()),
-            )
+            .upload_from_path(repo_path, self.folder_id, &upload_name, progress)
             .await?;
 
         tracing::info!("Upload ID: {}", upload_id);
 
         // Wait for Fossology to fully process the upload
         // Polls upload status until extraction and indexing are complete
+        progress.message("Waiting for Fossology to unpack the upload");
         self.client.wait_for_upload_ready(upload_id).await?;
 
         // 2. Create scan job
@@ -59,9 +104,12 @@ impl Scanner for FossologyScanner {
         tracing::info!("Job ID: {}", job_id);
 
         // 3. Wait for job completion
-        self.client.wait_for_job_completion(job_id).await?;
+        self.client
+            .wait_for_job_completion(job_id, progress)
+            .await?;
 
         tracing::info!("Job completed, fetching results");
+        progress.message("Fetching Fossology results");
 
         // 4. Fetch license results
         let license_results = self.client.get_licenses(upload_id).await?;
@@ -83,6 +131,12 @@ impl Scanner for FossologyScanner {
         Ok(scan_results)
     }
 
+    /// Fossology is a single shared instance, so uploads and jobs are
+    /// throttled across all scans running in this process
+    async fn reserve_slot(&self) -> Result<Option<ScanSlot>, ScanError> {
+        self.client.acquire_slot().await.map(Some)
+    }
+
     async fn health_check(&self) -> Result<(), ScanError> {
         self.client.health_check().await
     }
@@ -100,4 +154,48 @@ mod tests {
         );
         assert_eq!(scanner.name(), "fossology");
     }
+
+    #[tokio::test]
+    async fn test_reserve_slot_limits_concurrency() {
+        let scanner = FossologyScanner::new(
+            "http://localhost:8081".to_string(),
+            "test-token".to_string(),
+        )
+        .with_max_concurrent_jobs(1);
+
+        let first = scanner.reserve_slot().await.unwrap().unwrap();
+        let second = tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            scanner.reserve_slot(),
+        )
+        .await;
+        assert!(second.is_err(), "second reservation should wait for the first");
+
+        drop(first);
+        let slot = scanner.reserve_slot().await.unwrap().unwrap();
+        assert!(slot.waited < std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_render_upload_name() {
+        let info = RepoInfo {
+            name: Some("legalscanner".to_string()),
+            branch: Some("main".to_string()),
+            commit_sha: Some("c04f4ed1a2b3c4d5".to_string()),
+        };
+        let path = Path::new("/tmp/legalscanner/7f0c");
+
+        assert_eq!(
+            render_upload_name(DEFAULT_UPLOAD_NAME_TEMPLATE, &info, path),
+            "legalscanner@main (c04f4ed)"
+        );
+        assert_eq!(
+            render_upload_name("{repo}-{full_sha}", &info, path),
+            "legalscanner-c04f4ed1a2b3c4d5"
+        );
+        assert_eq!(
+            render_upload_name(DEFAULT_UPLOAD_NAME_TEMPLATE, &RepoInfo::default(), path),
+            "7f0c@detached (unknown)"
+        );
+    }
 }
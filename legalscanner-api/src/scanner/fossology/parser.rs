@@ -1,4 +1,4 @@
-use crate::scanner::traits::{CopyrightFinding, LicenseFinding, ScanResult};
+use crate::scanner::traits::{CopyrightFinding, LicenseCategory, LicenseFinding, ScanResult};
 use regex::Regex;
 use std::collections::HashMap;
 
@@ -16,6 +16,9 @@ pub fn parse_license_results(results: Vec<LicenseResult>) -> Vec<ScanResult> {
                 licenses: Vec::new(),
                 copyrights: Vec::new(),
                 ecc_findings: Vec::new(),
+                malware_findings: Vec::new(),
+                package_findings: Vec::new(),
+                model_licenses: Vec::new(),
             });
 
         for finding in license_result.findings {
@@ -23,6 +26,10 @@ pub fn parse_license_results(results: Vec<LicenseResult>) -> Vec<ScanResult> {
                 name: finding.license.clone(),
                 spdx_id: finding.spdx_id.or_else(|| map_to_spdx(&finding.license)),
                 confidence: finding.match_percentage / 100.0,
+                category: LicenseCategory::Code,
+                provenance: None,
+                agreement: None,
+                exception: None,
             });
         }
     }
@@ -64,6 +71,9 @@ pub fn merge_copyright_results(
             licenses: Vec::new(),
             copyrights,
             ecc_findings: Vec::new(),
+            malware_findings: Vec::new(),
+            package_findings: Vec::new(),
+            model_licenses: Vec::new(),
         });
     }
 
@@ -0,0 +1,367 @@
+//! Due-diligence history scan. Walks the commits reachable from HEAD for
+//! licensed code that is gone from the current tree: deleted files carrying
+//! license or copyright notices, license texts that were replaced, and
+//! vendored third-party directories that were removed.
+
+use crate::scanner::declared::identify_license_text;
+use crate::scanner::traits::ScanError;
+use chrono::DateTime;
+use git2::{Delta, Oid, Repository, Sort, Tree};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Directories whose subdirectories conventionally hold copied third-party code
+const VENDOR_DIRS: &[&str] = &[
+    "vendor",
+    "vendored",
+    "third_party",
+    "third-party",
+    "thirdparty",
+    "external",
+    "node_modules",
+];
+
+/// Blobs above this size are not inspected for notices
+const MAX_BLOB_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryFindingKind {
+    /// A file with a license or copyright notice that is no longer at HEAD
+    DeletedFile,
+    /// A license file whose text changed to a different license, or was deleted
+    LicenseChange,
+    /// A vendored third-party directory that is no longer at HEAD
+    RemovedThirdParty,
+}
+
+impl HistoryFindingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryFindingKind::DeletedFile => "deleted_file",
+            HistoryFindingKind::LicenseChange => "license_change",
+            HistoryFindingKind::RemovedThirdParty => "removed_third_party",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryFinding {
+    pub kind: HistoryFindingKind,
+    /// Path relative to the repository root
+    pub file_path: String,
+    /// Commit that deleted or changed the file
+    pub commit_sha: String,
+    /// Commit time, RFC 3339
+    pub commit_date: String,
+    /// License the code was under before the commit
+    pub license: Option<String>,
+    pub detail: Option<String>,
+}
+
+/// A vendored directory removed at some point, keyed by its path
+struct RemovedVendorDir {
+    commit_sha: String,
+    commit_date: String,
+    files: usize,
+    license: Option<String>,
+}
+
+/// Scan the history of a checkout, newest commit first, stopping after
+/// `max_commits` commits (0 means no limit)
+pub async fn scan_history(
+    repo_path: &Path,
+    max_commits: usize,
+) -> Result<Vec<HistoryFinding>, ScanError> {
+    let repo_path = repo_path.to_path_buf();
+    tokio::task::spawn_blocking(move || walk_history(&repo_path, max_commits))
+        .await
+        .map_err(|e| ScanError::Failed(format!("History scan task failed: {}", e)))?
+        .map_err(|e| ScanError::Failed(format!("History scan failed: {}", e)))
+}
+
+fn walk_history(repo_path: &Path, max_commits: usize) -> Result<Vec<HistoryFinding>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let head_tree = repo.head()?.peel_to_tree()?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.set_sorting(Sort::TIME)?;
+
+    let limit = if max_commits == 0 {
+        usize::MAX
+    } else {
+        max_commits
+    };
+
+    let mut findings = Vec::new();
+    let mut deleted_paths: HashSet<String> = HashSet::new();
+    let mut vendor_dirs: BTreeMap<String, RemovedVendorDir> = BTreeMap::new();
+
+    for oid in revwalk.take(limit) {
+        let commit = repo.find_commit(oid?)?;
+        // Root commits only add files
+        let Ok(parent) = commit.parent(0) else {
+            continue;
+        };
+        let commit_sha = commit.id().to_string();
+        let commit_date = DateTime::from_timestamp(commit.time().seconds(), 0)
+            .map(|d| d.to_rfc3339())
+            .unwrap_or_default();
+
+        let diff = repo.diff_tree_to_tree(Some(&parent.tree()?), Some(&commit.tree()?), None)?;
+        for delta in diff.deltas() {
+            let status = delta.status();
+            if status != Delta::Deleted && status != Delta::Modified {
+                continue;
+            }
+            let Some(path) = delta.old_file().path().and_then(|p| p.to_str()) else {
+                continue;
+            };
+            let path = path.to_string();
+
+            // Vendored license files are reported with their directory
+            if is_license_file(&path) && vendor_package_dir(&path).is_none() {
+                let old =
+                    blob_text(&repo, delta.old_file().id()).and_then(|t| identify_license_text(&t));
+                let new = if status == Delta::Deleted {
+                    None
+                } else {
+                    blob_text(&repo, delta.new_file().id()).and_then(|t| identify_license_text(&t))
+                };
+                if let Some(old) = old.filter(|old| Some(*old) != new) {
+                    findings.push(HistoryFinding {
+                        kind: HistoryFindingKind::LicenseChange,
+                        file_path: path.clone(),
+                        commit_sha: commit_sha.clone(),
+                        commit_date: commit_date.clone(),
+                        license: Some(old.to_string()),
+                        detail: Some(match new {
+                            Some(new) => format!("{} replaced by {}", old, new),
+                            None if status == Delta::Deleted => "license file deleted".to_string(),
+                            None => format!("{} replaced by an unrecognized license", old),
+                        }),
+                    });
+                }
+            }
+
+            // Only files that are gone from HEAD, reported for their latest deletion
+            if status != Delta::Deleted
+                || in_tree(&head_tree, &path)
+                || !deleted_paths.insert(path.clone())
+            {
+                continue;
+            }
+
+            if let Some(dir) = vendor_package_dir(&path) {
+                if in_tree(&head_tree, &dir) {
+                    continue;
+                }
+                let removed = vendor_dirs.entry(dir).or_insert_with(|| RemovedVendorDir {
+                    commit_sha: commit_sha.clone(),
+                    commit_date: commit_date.clone(),
+                    files: 0,
+                    license: None,
+                });
+                removed.files += 1;
+                if removed.license.is_none() && is_license_file(&path) {
+                    removed.license = blob_text(&repo, delta.old_file().id())
+                        .and_then(|t| identify_license_text(&t))
+                        .map(str::to_string);
+                }
+                continue;
+            }
+
+            // Deleted license files are already reported as license changes
+            if is_license_file(&path) {
+                continue;
+            }
+            let Some(text) = blob_text(&repo, delta.old_file().id()) else {
+                continue;
+            };
+            let license = spdx_identifier(&text);
+            let copyright = copyright_notice(&text);
+            if license.is_some() || copyright.is_some() {
+                findings.push(HistoryFinding {
+                    kind: HistoryFindingKind::DeletedFile,
+                    file_path: path,
+                    commit_sha: commit_sha.clone(),
+                    commit_date: commit_date.clone(),
+                    license,
+                    detail: copyright,
+                });
+            }
+        }
+    }
+
+    findings.extend(
+        vendor_dirs
+            .into_iter()
+            .map(|(dir, removed)| HistoryFinding {
+                kind: HistoryFindingKind::RemovedThirdParty,
+                file_path: dir,
+                commit_sha: removed.commit_sha,
+                commit_date: removed.commit_date,
+                license: removed.license,
+                detail: Some(format!("{} files removed", removed.files)),
+            }),
+    );
+    findings.sort_by(|a, b| b.commit_date.cmp(&a.commit_date));
+
+    Ok(findings)
+}
+
+fn in_tree(tree: &Tree, path: &str) -> bool {
+    tree.get_path(Path::new(path)).is_ok()
+}
+
+/// Text of a blob, `None` for binary, oversized or missing blobs
+fn blob_text(repo: &Repository, id: Oid) -> Option<String> {
+    let blob = repo.find_blob(id).ok()?;
+    if blob.is_binary() || blob.size() > MAX_BLOB_SIZE {
+        return None;
+    }
+    String::from_utf8(blob.content().to_vec()).ok()
+}
+
+/// LICENSE, LICENCE and COPYING files in any directory, with any extension
+fn is_license_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path).to_uppercase();
+    ["LICENSE", "LICENCE", "COPYING"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+/// `vendor/foo/src/lib.c` -> `vendor/foo`. Files directly inside a vendor
+/// directory map to the directory itself.
+fn vendor_package_dir(path: &str) -> Option<String> {
+    let components: Vec<&str> = path.split('/').collect();
+    let index = components
+        .iter()
+        .position(|c| VENDOR_DIRS.contains(&c.to_lowercase().as_str()))?;
+    let end = if index + 2 < components.len() {
+        index + 2
+    } else {
+        index + 1
+    };
+    Some(components[..end].join("/"))
+}
+
+fn spdx_identifier(text: &str) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r"(?m)SPDX-License-Identifier:\s*(.+?)\s*(?:\*/|-->)?\s*$").unwrap()
+    });
+    re.captures(text)
+        .map(|c| c[1].to_string())
+        .filter(|l| !l.is_empty())
+}
+
+/// First copyright line, e.g. `Copyright (c) 2019 Acme Corp`
+fn copyright_notice(text: &str) -> Option<String> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r"(?im)^[\s#/*;!-]*(copyright\b.*?(?:\(c\)|©|\b\d{4}\b).*?)\s*(?:\*/)?\s*$")
+            .unwrap()
+    });
+    re.captures(text).map(|c| c[1].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{commit_fixture_changes, init_fixture_repo};
+
+    const MIT: &str =
+        "MIT License\n\nPermission is hereby granted, free of charge, to any person\n";
+    const APACHE: &str = "Apache License\n   Version 2.0, January 2004\n";
+
+    #[test]
+    fn test_vendor_package_dir() {
+        assert_eq!(
+            vendor_package_dir("vendor/zlib/src/inflate.c").as_deref(),
+            Some("vendor/zlib")
+        );
+        assert_eq!(
+            vendor_package_dir("src/third_party/json.hpp").as_deref(),
+            Some("src/third_party")
+        );
+        assert_eq!(vendor_package_dir("src/vendors.rs"), None);
+    }
+
+    #[test]
+    fn test_notices() {
+        let text =
+            "/*\n * Copyright (c) 2019 Acme Corp\n * SPDX-License-Identifier: GPL-2.0-only */\n";
+        assert_eq!(spdx_identifier(text).as_deref(), Some("GPL-2.0-only"));
+        assert_eq!(
+            copyright_notice(text).as_deref(),
+            Some("Copyright (c) 2019 Acme Corp")
+        );
+        assert_eq!(
+            copyright_notice("// copyright holders are listed below"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        init_fixture_repo(
+            &repo,
+            &[
+                ("LICENSE", MIT),
+                ("src/main.c", "int main() { return 0; }\n"),
+                (
+                    "src/gpl.c",
+                    "// SPDX-License-Identifier: GPL-2.0-only\nint f() { return 1; }\n",
+                ),
+                ("vendor/zlib/LICENSE", MIT),
+                ("vendor/zlib/inflate.c", "int inflate() { return 0; }\n"),
+                ("notes.txt", "nothing to see\n"),
+            ],
+        );
+        commit_fixture_changes(
+            &repo,
+            &[("LICENSE", APACHE)],
+            &[
+                "src/gpl.c",
+                "vendor/zlib/LICENSE",
+                "vendor/zlib/inflate.c",
+                "notes.txt",
+            ],
+            "Relicense and drop GPL code",
+        );
+
+        let findings = scan_history(&repo, 0).await.unwrap();
+        let kinds: Vec<(HistoryFindingKind, &str)> = findings
+            .iter()
+            .map(|f| (f.kind, f.file_path.as_str()))
+            .collect();
+        assert_eq!(findings.len(), 3, "{:?}", findings);
+        assert!(kinds.contains(&(HistoryFindingKind::LicenseChange, "LICENSE")));
+        assert!(kinds.contains(&(HistoryFindingKind::DeletedFile, "src/gpl.c")));
+        assert!(kinds.contains(&(HistoryFindingKind::RemovedThirdParty, "vendor/zlib")));
+
+        let license = findings
+            .iter()
+            .find(|f| f.kind == HistoryFindingKind::LicenseChange)
+            .unwrap();
+        assert_eq!(license.license.as_deref(), Some("MIT"));
+        assert_eq!(
+            license.detail.as_deref(),
+            Some("MIT replaced by Apache-2.0")
+        );
+
+        let vendored = findings
+            .iter()
+            .find(|f| f.kind == HistoryFindingKind::RemovedThirdParty)
+            .unwrap();
+        assert_eq!(vendored.license.as_deref(), Some("MIT"));
+        assert_eq!(vendored.detail.as_deref(), Some("2 files removed"));
+    }
+}
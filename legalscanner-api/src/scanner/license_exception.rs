@@ -0,0 +1,262 @@
+//! SPDX license exceptions such as `GPL-2.0-only WITH Classpath-exception-2.0`.
+//! Scanners report them in three ways: as a `WITH` expression, as one of the
+//! deprecated combined IDs like `GPL-2.0-with-classpath-exception`, or as a
+//! finding of its own next to the license it modifies. [`attach`] turns all
+//! three into a license finding with its exception in `exception`.
+
+use crate::scanner::traits::ScanResult;
+
+/// Deprecated SPDX IDs combining a license and an exception, with the
+/// license and exception they stand for
+const COMBINED_IDS: &[(&str, &str, &str)] = &[
+    (
+        "GPL-2.0-with-autoconf-exception",
+        "GPL-2.0-only",
+        "Autoconf-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-bison-exception",
+        "GPL-2.0-or-later",
+        "Bison-exception-2.2",
+    ),
+    (
+        "GPL-2.0-with-classpath-exception",
+        "GPL-2.0-only",
+        "Classpath-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-font-exception",
+        "GPL-2.0-only",
+        "Font-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-GCC-exception",
+        "GPL-2.0-only",
+        "GCC-exception-2.0",
+    ),
+    (
+        "GPL-3.0-with-autoconf-exception",
+        "GPL-3.0-only",
+        "Autoconf-exception-3.0",
+    ),
+    (
+        "GPL-3.0-with-GCC-exception",
+        "GPL-3.0-only",
+        "GCC-exception-3.1",
+    ),
+];
+
+/// SPDX exception ID for a scanner value, ignoring case and accepting
+/// spaces for dashes (`Classpath exception 2.0`)
+pub fn exception_id(name: &str) -> Option<&'static str> {
+    let name = name.trim().replace(' ', "-");
+    spdx::identifiers::EXCEPTIONS
+        .iter()
+        .find(|(id, _)| id.eq_ignore_ascii_case(&name))
+        .map(|(id, _)| *id)
+}
+
+/// Split a license carrying an exception into the license and the SPDX
+/// exception ID. Returns `None` for anything else, including `WITH`
+/// expressions naming an exception the SPDX list does not know.
+pub fn split(license: &str) -> Option<(String, &'static str)> {
+    let license = license.trim();
+    if let Some((_, id, exception)) = COMBINED_IDS
+        .iter()
+        .find(|(combined, _, _)| combined.eq_ignore_ascii_case(license))
+    {
+        return Some((id.to_string(), exception));
+    }
+
+    let lower = license.to_ascii_lowercase();
+    let at = lower.find(" with ")?;
+    let base = license[..at].trim();
+    // `WITH` binds to a single license; compound expressions stay as they are
+    if base.is_empty() || base.contains(char::is_whitespace) {
+        return None;
+    }
+    let exception = exception_id(&license[at + " with ".len()..])?;
+    Some((base.to_string(), exception))
+}
+
+/// Record license exceptions on the license findings of each file: split
+/// `WITH` expressions and combined IDs, and fold findings of an exception
+/// alone into the license of the same file it modifies, the GPL-family
+/// licenses if the file has several. An exception finding without such a
+/// license is kept. Returns the number of files changed.
+pub fn attach(results: &mut [ScanResult]) -> usize {
+    let mut changed = 0;
+    for result in results.iter_mut() {
+        let mut file_changed = false;
+        for license in result.licenses.iter_mut().filter(|l| l.exception.is_none()) {
+            let from_spdx_id = license.spdx_id.as_deref().and_then(split);
+            let from_name = split(&license.name);
+            let Some((id, exception)) = from_spdx_id.clone().or(from_name.clone()) else {
+                continue;
+            };
+            if let Some((name, _)) = from_name {
+                license.name = name;
+            }
+            license.spdx_id = match from_spdx_id {
+                Some((spdx_id, _)) => Some(spdx_id),
+                None => spdx::license_id(&id)
+                    .map(|l| l.name.to_string())
+                    .or(license.spdx_id.take()),
+            };
+            license.exception = Some(exception.to_string());
+            file_changed = true;
+        }
+
+        let exception_of = |name: &str, spdx_id: Option<&str>| {
+            spdx_id
+                .and_then(exception_id)
+                .or_else(|| exception_id(name))
+        };
+        let exceptions: Vec<(usize, &'static str)> = result
+            .licenses
+            .iter()
+            .enumerate()
+            .filter_map(|(i, l)| Some((i, exception_of(&l.name, l.spdx_id.as_deref())?)))
+            .collect();
+        let mut folded = Vec::new();
+        for (index, exception) in exceptions {
+            let licenses: Vec<usize> = result
+                .licenses
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| {
+                    l.exception.is_none() && exception_of(&l.name, l.spdx_id.as_deref()).is_none()
+                })
+                .map(|(i, _)| i)
+                .collect();
+            let targets: Vec<usize> = if licenses.len() == 1 {
+                licenses
+            } else {
+                licenses
+                    .into_iter()
+                    .filter(|i| {
+                        let license = &result.licenses[*i];
+                        license
+                            .spdx_id
+                            .as_deref()
+                            .unwrap_or(&license.name)
+                            .contains("GPL")
+                    })
+                    .collect()
+            };
+            if targets.is_empty() {
+                continue;
+            }
+            for target in targets {
+                result.licenses[target].exception = Some(exception.to_string());
+            }
+            folded.push(index);
+        }
+        if !folded.is_empty() {
+            let mut index = 0;
+            result.licenses.retain(|_| {
+                index += 1;
+                !folded.contains(&(index - 1))
+            });
+            file_changed = true;
+        }
+
+        if file_changed {
+            changed += 1;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::traits::{LicenseCategory, LicenseFinding};
+
+    fn license(name: &str, spdx_id: Option<&str>) -> LicenseFinding {
+        LicenseFinding {
+            name: name.to_string(),
+            spdx_id: spdx_id.map(str::to_string),
+            confidence: 0.9,
+            category: LicenseCategory::Code,
+            provenance: None,
+            agreement: None,
+            exception: None,
+        }
+    }
+
+    fn file(licenses: Vec<LicenseFinding>) -> ScanResult {
+        ScanResult {
+            file_path: "src/Main.java".to_string(),
+            licenses,
+            copyrights: Vec::new(),
+            ecc_findings: Vec::new(),
+            malware_findings: Vec::new(),
+            package_findings: Vec::new(),
+            model_licenses: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            split("GPL-2.0-only WITH Classpath-exception-2.0"),
+            Some(("GPL-2.0-only".to_string(), "Classpath-exception-2.0"))
+        );
+        assert_eq!(
+            split("Apache-2.0 with llvm-exception"),
+            Some(("Apache-2.0".to_string(), "LLVM-exception"))
+        );
+        assert_eq!(
+            split("GPL-2.0-with-classpath-exception"),
+            Some(("GPL-2.0-only".to_string(), "Classpath-exception-2.0"))
+        );
+        assert_eq!(split("GPL-2.0-only WITH Acme-exception"), None);
+        assert_eq!(
+            split("(MIT OR GPL-2.0-only) WITH Classpath-exception-2.0"),
+            None
+        );
+        assert_eq!(split("GPL-2.0-only"), None);
+        assert_eq!(
+            exception_id("Classpath exception 2.0"),
+            Some("Classpath-exception-2.0")
+        );
+    }
+
+    #[test]
+    fn test_attach_splits_expressions() {
+        let mut results = vec![file(vec![
+            license("GPL-2.0-with-classpath-exception", Some("GPL-2.0-only")),
+            license("MIT", Some("MIT")),
+        ])];
+        assert_eq!(attach(&mut results), 1);
+        let licenses = &results[0].licenses;
+        assert_eq!(licenses[0].name, "GPL-2.0-only");
+        assert_eq!(licenses[0].spdx_id.as_deref(), Some("GPL-2.0-only"));
+        assert_eq!(
+            licenses[0].exception.as_deref(),
+            Some("Classpath-exception-2.0")
+        );
+        assert_eq!(licenses[1].exception, None);
+        assert_eq!(attach(&mut results), 0);
+    }
+
+    #[test]
+    fn test_attach_folds_exception_findings() {
+        let mut results = vec![
+            file(vec![
+                license("MIT", Some("MIT")),
+                license("GPL-3.0", Some("GPL-3.0-only")),
+                license("GCC-exception-3.1", None),
+            ]),
+            file(vec![license("Classpath-exception-2.0", None)]),
+        ];
+        assert_eq!(attach(&mut results), 1);
+        let licenses = &results[0].licenses;
+        assert_eq!(licenses.len(), 2);
+        assert_eq!(licenses[0].exception, None);
+        assert_eq!(licenses[1].exception.as_deref(), Some("GCC-exception-3.1"));
+        // Nothing to attach it to
+        assert_eq!(results[1].licenses[0].name, "Classpath-exception-2.0");
+    }
+}
@@ -0,0 +1,114 @@
+use crate::config::Config;
+use crate::scanner::traits::ScanError;
+use crate::scanner::walk::collect_files;
+use std::path::Path;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Repository size thresholds above which a scan still runs but is flagged
+/// as possibly incomplete. A limit of 0 disables the check.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SoftLimits {
+    pub max_files: u64,
+    pub max_size_mb: u64,
+}
+
+impl SoftLimits {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_files: config.soft_limit_max_files,
+            max_size_mb: config.soft_limit_max_size_mb,
+        }
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.max_files == 0 && self.max_size_mb == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CheckoutStats {
+    pub file_count: u64,
+    pub total_bytes: u64,
+}
+
+/// Count the files of a checkout (excluding .git) and their total size
+pub async fn measure_checkout(root: &Path) -> Result<CheckoutStats, ScanError> {
+    let files = collect_files(root).await?;
+
+    let mut total_bytes = 0;
+    for file in &files {
+        total_bytes += tokio::fs::metadata(file).await?.len();
+    }
+
+    Ok(CheckoutStats {
+        file_count: files.len() as u64,
+        total_bytes,
+    })
+}
+
+/// Warnings for every soft limit the checkout exceeds
+pub fn limit_warnings(stats: CheckoutStats, limits: SoftLimits) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if limits.max_files > 0 && stats.file_count > limits.max_files {
+        warnings.push(format!(
+            "Repository contains {} files, above the soft limit of {}; \
+             scanners may time out and findings may be incomplete",
+            stats.file_count, limits.max_files
+        ));
+    }
+
+    let size_mb = stats.total_bytes.div_ceil(BYTES_PER_MB);
+    if limits.max_size_mb > 0 && stats.total_bytes > limits.max_size_mb * BYTES_PER_MB {
+        warnings.push(format!(
+            "Repository checkout is {} MB, above the soft limit of {} MB; \
+             the Fossology upload archive is correspondingly large and findings may be incomplete",
+            size_mb, limits.max_size_mb
+        ));
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_warnings() {
+        let limits = SoftLimits {
+            max_files: 100,
+            max_size_mb: 1,
+        };
+
+        let within = CheckoutStats {
+            file_count: 100,
+            total_bytes: BYTES_PER_MB,
+        };
+        assert!(limit_warnings(within, limits).is_empty());
+
+        let over = CheckoutStats {
+            file_count: 101,
+            total_bytes: BYTES_PER_MB + 1,
+        };
+        let warnings = limit_warnings(over, limits);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("101 files"));
+        assert!(warnings[1].contains("2 MB"));
+
+        assert!(limit_warnings(over, SoftLimits::default()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_measure_checkout_skips_git_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+
+        let stats = measure_checkout(dir.path()).await.unwrap();
+        assert_eq!(stats.file_count, 1);
+        assert_eq!(stats.total_bytes, 13);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::scanner::traits::{LicenseAgreement, LicenseFinding, ScanResult};
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+/// The scanner whose license conclusions win under `PreferFossology`
+const PRIMARY_SCANNER: &str = "fossology";
+
+/// How license findings from the primary license scanners (Fossology and
+/// Semgrep) are combined for each file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Keep every finding, each labelled with the scanner that produced it
+    #[default]
+    KeepAll,
+    /// Use Fossology's licenses for a file when it found any, otherwise fall
+    /// back to the other scanners
+    PreferFossology,
+    /// Keep only licenses reported by at least two scanners for the same file
+    Consensus,
+}
+
+impl MergeStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeStrategy::KeepAll => "keep-all",
+            MergeStrategy::PreferFossology => "prefer-fossology",
+            MergeStrategy::Consensus => "consensus",
+        }
+    }
+
+    /// Apply the strategy to results that were merged by file path. Each
+    /// surviving license gets `provenance` set to `<strategy>:<scanners>`.
+    /// Findings must have been tagged with `tag_origin` beforehand.
+    pub fn apply(&self, results: &mut Vec<ScanResult>) {
+        for result in results.iter_mut() {
+            let licenses = std::mem::take(&mut result.licenses);
+            result.licenses = match self {
+                MergeStrategy::KeepAll => licenses,
+                MergeStrategy::PreferFossology => {
+                    if licenses.iter().any(|l| origin(l) == PRIMARY_SCANNER) {
+                        licenses
+                            .into_iter()
+                            .filter(|l| origin(l) == PRIMARY_SCANNER)
+                            .collect()
+                    } else {
+                        licenses
+                    }
+                }
+                MergeStrategy::Consensus => consensus(licenses),
+            };
+
+            for license in &mut result.licenses {
+                license.provenance = Some(format!("{}:{}", self.as_str(), origin(license)));
+            }
+        }
+
+        if *self == MergeStrategy::Consensus {
+            results.retain(|r| {
+                !r.licenses.is_empty()
+                    || !r.copyrights.is_empty()
+                    || !r.ecc_findings.is_empty()
+                    || !r.malware_findings.is_empty()
+                    || !r.package_findings.is_empty()
+                    || !r.model_licenses.is_empty()
+            });
+        }
+    }
+}
+
+impl FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "keep-all" => Ok(MergeStrategy::KeepAll),
+            "prefer-fossology" => Ok(MergeStrategy::PreferFossology),
+            "consensus" => Ok(MergeStrategy::Consensus),
+            other => Err(format!(
+                "Invalid MERGE_STRATEGY '{}'. Must be 'keep-all', 'prefer-fossology' or 'consensus'",
+                other
+            )),
+        }
+    }
+}
+
+/// Label license findings that have no provenance yet with the scanner that produced them
+pub fn tag_origin(results: &mut [ScanResult], scanner: &str) {
+    for license in results.iter_mut().flat_map(|r| r.licenses.iter_mut()) {
+        if license.provenance.is_none() {
+            license.provenance = Some(scanner.to_string());
+        }
+    }
+}
+
+/// Compare the license scanners' findings for each file that more than one
+/// of them reported licenses for. Licenses reported by at least two scanners
+/// are marked corroborated and get the combined confidence of the agreeing
+/// scanners; the others are marked disputed for manual review. Run after
+/// `tag_origin` and before a [`MergeStrategy`] is applied.
+pub fn corroborate(results: &mut [ScanResult]) {
+    for result in results.iter_mut() {
+        let scanners: BTreeSet<&str> = result.licenses.iter().map(origin).collect();
+        if scanners.len() < 2 {
+            continue;
+        }
+
+        // Highest confidence per scanner for every license of the file
+        let mut reports: BTreeMap<String, BTreeMap<String, f32>> = BTreeMap::new();
+        for license in &result.licenses {
+            let confidence = reports
+                .entry(license_key(license))
+                .or_default()
+                .entry(origin(license).to_string())
+                .or_insert(0.0);
+            *confidence = confidence.max(license.confidence);
+        }
+
+        for license in &mut result.licenses {
+            let scanners = &reports[&license_key(license)];
+            if scanners.len() > 1 {
+                license.confidence = combined_confidence(scanners.values().copied());
+                license.agreement = Some(LicenseAgreement::Corroborated);
+            } else {
+                license.agreement = Some(LicenseAgreement::Disputed);
+            }
+        }
+    }
+}
+
+/// Probability that not all of the independent scanners are wrong
+fn combined_confidence(confidences: impl Iterator<Item = f32>) -> f32 {
+    let all_wrong: f32 = confidences.map(|c| 1.0 - c.clamp(0.0, 1.0)).product();
+    1.0 - all_wrong
+}
+
+fn origin(license: &LicenseFinding) -> &str {
+    license.provenance.as_deref().unwrap_or("unknown")
+}
+
+/// Scanners can spell the same license differently; compare SPDX IDs when known
+fn license_key(license: &LicenseFinding) -> String {
+    license
+        .spdx_id
+        .as_deref()
+        .unwrap_or(&license.name)
+        .to_lowercase()
+}
+
+/// Collapse agreeing findings into one per license, dropping single-scanner ones
+fn consensus(licenses: Vec<LicenseFinding>) -> Vec<LicenseFinding> {
+    let mut groups: Vec<(String, BTreeSet<String>, LicenseFinding)> = Vec::new();
+
+    for license in licenses {
+        let key = license_key(&license);
+        let scanner = origin(&license).to_string();
+        match groups.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((_, scanners, kept)) => {
+                scanners.insert(scanner);
+                // Prefer the finding that carries an SPDX ID, then the more confident one
+                if (kept.spdx_id.is_none() && license.spdx_id.is_some())
+                    || license.confidence > kept.confidence
+                {
+                    let provenance = kept.provenance.take();
+                    *kept = license;
+                    kept.provenance = provenance;
+                }
+            }
+            None => groups.push((key, BTreeSet::from([scanner]), license)),
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, scanners, _)| scanners.len() > 1)
+        .map(|(_, scanners, mut license)| {
+            license.provenance = Some(scanners.into_iter().collect::<Vec<_>>().join("+"));
+            license
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::LicenseCategory;
+
+    fn license(name: &str, spdx_id: Option<&str>, scanner: &str) -> LicenseFinding {
+        LicenseFinding {
+            name: name.to_string(),
+            spdx_id: spdx_id.map(str::to_string),
+            confidence: 0.9,
+            category: LicenseCategory::Code,
+            provenance: Some(scanner.to_string()),
+            agreement: None,
+            exception: None,
+        }
+    }
+
+    fn file(licenses: Vec<LicenseFinding>) -> ScanResult {
+        ScanResult {
+            file_path: "src/lib.rs".to_string(),
+            licenses,
+            copyrights: Vec::new(),
+            ecc_findings: Vec::new(),
+            malware_findings: Vec::new(),
+            package_findings: Vec::new(),
+            model_licenses: Vec::new(),
+        }
+    }
+
+    fn provenances(results: &[ScanResult]) -> Vec<&str> {
+        results[0]
+            .licenses
+            .iter()
+            .map(|l| l.provenance.as_deref().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_keep_all_labels_origin() {
+        let mut results = vec![file(vec![
+            license("MIT", Some("MIT"), "fossology"),
+            license("MIT", Some("MIT"), "semgrep"),
+        ])];
+        MergeStrategy::KeepAll.apply(&mut results);
+        assert_eq!(provenances(&results), vec!["keep-all:fossology", "keep-all:semgrep"]);
+    }
+
+    #[test]
+    fn test_prefer_fossology_falls_back() {
+        let mut results = vec![
+            file(vec![
+                license("MIT", Some("MIT"), "fossology"),
+                license("Apache-2.0", Some("Apache-2.0"), "semgrep"),
+            ]),
+            ScanResult {
+                file_path: "src/other.rs".to_string(),
+                ..file(vec![license("BSD-3-Clause", None, "semgrep")])
+            },
+        ];
+        MergeStrategy::PreferFossology.apply(&mut results);
+        assert_eq!(provenances(&results), vec!["prefer-fossology:fossology"]);
+        assert_eq!(
+            results[1].licenses[0].provenance.as_deref(),
+            Some("prefer-fossology:semgrep")
+        );
+    }
+
+    #[test]
+    fn test_consensus_requires_two_scanners() {
+        let mut results = vec![
+            file(vec![
+                license("MIT License", Some("MIT"), "fossology"),
+                license("MIT", Some("MIT"), "semgrep"),
+                license("GPL-2.0", Some("GPL-2.0-only"), "fossology"),
+            ]),
+            ScanResult {
+                file_path: "src/only-one.rs".to_string(),
+                ..file(vec![license("ISC", Some("ISC"), "semgrep")])
+            },
+        ];
+        MergeStrategy::Consensus.apply(&mut results);
+        assert_eq!(results.len(), 1);
+        assert_eq!(provenances(&results), vec!["consensus:fossology+semgrep"]);
+        assert_eq!(results[0].licenses[0].spdx_id.as_deref(), Some("MIT"));
+    }
+
+    #[test]
+    fn test_corroborate_boosts_agreeing_and_flags_disputed() {
+        // Fossology reports license names only
+        let mut mit = license("MIT", None, "fossology");
+        mit.confidence = 0.6;
+        let mut results = vec![
+            file(vec![
+                mit,
+                license("MIT", Some("MIT"), "semgrep"),
+                license("GPL-2.0", Some("GPL-2.0-only"), "semgrep"),
+            ]),
+            ScanResult {
+                file_path: "src/only-one.rs".to_string(),
+                ..file(vec![license("ISC", Some("ISC"), "semgrep")])
+            },
+        ];
+        corroborate(&mut results);
+
+        let agreements: Vec<_> = results[0].licenses.iter().map(|l| l.agreement).collect();
+        assert_eq!(
+            agreements,
+            vec![
+                Some(LicenseAgreement::Corroborated),
+                Some(LicenseAgreement::Corroborated),
+                Some(LicenseAgreement::Disputed),
+            ]
+        );
+        assert!((results[0].licenses[0].confidence - 0.96).abs() < 1e-6);
+        assert_eq!(results[0].licenses[2].confidence, 0.9);
+        assert_eq!(results[1].licenses[0].agreement, None);
+    }
+
+    #[test]
+    fn test_parse_merge_strategy() {
+        assert_eq!("Prefer-Fossology".parse(), Ok(MergeStrategy::PreferFossology));
+        assert!("union".parse::<MergeStrategy>().is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! In-memory scanner for exercising the scan pipeline without external services.
+//! Available to downstream crates with the `test-utils` feature.
+
+use crate::scanner::traits::{ScanError, ScanResult, Scanner};
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Scanner returning canned results (or a canned failure) for every scan
+pub struct MockScanner {
+    name: String,
+    results: Vec<ScanResult>,
+    error: Option<String>,
+    delay: Duration,
+    existing_files_only: bool,
+    scans: AtomicUsize,
+}
+
+impl MockScanner {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            results: Vec::new(),
+            error: None,
+            delay: Duration::ZERO,
+            existing_files_only: false,
+            scans: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn with_results(mut self, results: Vec<ScanResult>) -> Self {
+        self.results = results;
+        self
+    }
+
+    /// Make every scan fail with `ScanError::Failed(message)`
+    pub fn failing(mut self, message: impl Into<String>) -> Self {
+        self.error = Some(message.into());
+        self
+    }
+
+    /// Take `delay` to finish every scan, keeping scans in progress
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Only report results for files present in the scanned checkout, so
+    /// that deleting a file between scans removes its findings
+    pub fn existing_files_only(mut self) -> Self {
+        self.existing_files_only = true;
+        self
+    }
+
+    /// Number of times `scan` has been called
+    pub fn scan_count(&self) -> usize {
+        self.scans.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Scanner for MockScanner {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn scan(&self, repo_path: &Path) -> Result<Vec<ScanResult>, ScanError> {
+        self.scans.fetch_add(1, Ordering::SeqCst);
+
+        if !repo_path.exists() {
+            return Err(ScanError::Failed(format!(
+                "Repository path does not exist: {:?}",
+                repo_path
+            )));
+        }
+
+        tokio::time::sleep(self.delay).await;
+
+        match &self.error {
+            Some(message) => Err(ScanError::Failed(message.clone())),
+            None => Ok(self
+                .results
+                .iter()
+                .filter(|r| !self.existing_files_only || repo_path.join(&r.file_path).exists())
+                .cloned()
+                .collect()),
+        }
+    }
+
+    async fn health_check(&self) -> Result<(), ScanError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_scanner_counts_scans() {
+        let scanner = MockScanner::new("mock");
+        let dir = std::env::temp_dir();
+
+        assert!(scanner.scan(&dir).await.unwrap().is_empty());
+        assert_eq!(scanner.scan_count(), 1);
+
+        let failing = MockScanner::new("mock").failing("boom");
+        assert!(matches!(failing.scan(&dir).await, Err(ScanError::Failed(m)) if m == "boom"));
+    }
+}
@@ -1,5 +1,22 @@
+pub mod authorship;
+pub mod clamav;
+pub mod declared;
+pub mod documentation;
 pub mod fossology;
+pub mod history;
+pub mod license_exception;
+pub mod limits;
+pub mod merge;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock;
+pub mod model;
+pub mod sandbox;
 pub mod semgrep;
+pub mod syft;
 pub mod traits;
+pub(crate) mod walk;
 
-pub use traits::{CopyrightFinding, EccFinding, LicenseFinding, ScanError, ScanResult, Scanner};
+pub use traits::{
+    CopyrightFinding, EccFinding, LicenseAgreement, LicenseCategory, LicenseFinding,
+    MalwareFinding, ModelLicenseFinding, PackageFinding, ScanError, ScanResult, ScanSlot, Scanner,
+};
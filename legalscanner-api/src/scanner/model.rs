@@ -0,0 +1,255 @@
+use crate::scanner::documentation::front_matter;
+use crate::scanner::traits::{ModelLicenseFinding, ScanError, ScanResult, Scanner};
+use crate::scanner::walk::collect_files;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Model weight formats treated as ML artifacts
+const MODEL_EXTENSIONS: &[&str] = &["safetensors", "onnx", "gguf"];
+
+/// Reported when no model card or license file covers an artifact
+const NO_LICENSE: &str = "No_license_found";
+
+/// Model cards and license files larger than this are not read
+const MAX_CARD_SIZE: u64 = 1024 * 1024;
+
+/// Detects ML model artifacts and the license that covers them, taken from the
+/// nearest model card (`license:` front matter) or license file in the same
+/// directory or a parent directory.
+pub struct ModelLicenseScanner;
+
+impl ModelLicenseScanner {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ModelLicenseScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Scanner for ModelLicenseScanner {
+    fn name(&self) -> &str {
+        "model"
+    }
+
+    async fn scan(&self, repo_path: &Path) -> Result<Vec<ScanResult>, ScanError> {
+        let files = collect_files(repo_path).await?;
+
+        let artifacts: Vec<&PathBuf> = files.iter().filter(|p| model_format(p).is_some()).collect();
+        if artifacts.is_empty() {
+            return Ok(Vec::new());
+        }
+        tracing::info!("Found {} model artifacts in {:?}", artifacts.len(), repo_path);
+
+        // Licenses declared per directory, read lazily
+        let mut declared: HashMap<PathBuf, Option<(String, String)>> = HashMap::new();
+        let mut results = Vec::new();
+
+        for artifact in artifacts {
+            let mut license = None;
+            let mut dir = artifact.parent();
+            while let Some(current) = dir {
+                if !current.starts_with(repo_path) {
+                    break;
+                }
+                if !declared.contains_key(current) {
+                    let found = read_declared_license(current, &files).await;
+                    declared.insert(current.to_path_buf(), found);
+                }
+                if let Some(found) = &declared[current] {
+                    license = Some(found.clone());
+                    break;
+                }
+                dir = current.parent();
+            }
+
+            let (name, source) = match license {
+                Some((name, source_path)) => (name, Some(source_path)),
+                None => (NO_LICENSE.to_string(), None),
+            };
+            let relative = |p: &Path| p.strip_prefix(repo_path).unwrap_or(p).to_string_lossy().into_owned();
+
+            results.push(ScanResult {
+                file_path: relative(artifact),
+                licenses: Vec::new(),
+                copyrights: Vec::new(),
+                ecc_findings: Vec::new(),
+                malware_findings: Vec::new(),
+                package_findings: Vec::new(),
+                model_licenses: vec![ModelLicenseFinding {
+                    spdx_id: model_license_spdx_id(&name),
+                    license: name,
+                    format: model_format(artifact).unwrap_or_default().to_string(),
+                    source: source.map(|s| relative(Path::new(&s))),
+                }],
+            });
+        }
+
+        Ok(results)
+    }
+
+    async fn health_check(&self) -> Result<(), ScanError> {
+        Ok(())
+    }
+}
+
+fn model_format(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    MODEL_EXTENSIONS.iter().find(|e| **e == extension).copied()
+}
+
+/// Look for a model card or license file directly inside `dir`.
+/// Returns the license name and the file it was read from.
+async fn read_declared_license(dir: &Path, files: &[PathBuf]) -> Option<(String, String)> {
+    let mut candidates: Vec<&PathBuf> = files
+        .iter()
+        .filter(|f| f.parent() == Some(dir))
+        .filter(|f| {
+            let name = f.file_name().map(|n| n.to_string_lossy().to_uppercase()).unwrap_or_default();
+            name == "README.MD" || name.starts_with("LICENSE") || name.starts_with("LICENCE")
+        })
+        .collect();
+    // Prefer the model card's front matter over free-form license text
+    candidates.sort_by_key(|f| !f.file_name().map(|n| n.eq_ignore_ascii_case("README.md")).unwrap_or(false));
+
+    for candidate in candidates {
+        match tokio::fs::metadata(candidate).await {
+            Ok(metadata) if metadata.len() <= MAX_CARD_SIZE => {}
+            _ => continue,
+        }
+        let Ok(text) = tokio::fs::read_to_string(candidate).await else {
+            continue;
+        };
+        if let Some(license) = detect_model_license(&text) {
+            return Some((license, candidate.to_string_lossy().into_owned()));
+        }
+    }
+    None
+}
+
+/// Detect a model license from a model card's front matter or a license file's text
+pub fn detect_model_license(text: &str) -> Option<String> {
+    if let Some(fm) = front_matter(text) {
+        let mut license = None;
+        let mut license_name = None;
+        for line in fm.lines() {
+            if let Some(value) = line.strip_prefix("license:") {
+                license = Some(value.trim().trim_matches('"').to_string());
+            } else if let Some(value) = line.strip_prefix("license_name:") {
+                license_name = Some(value.trim().trim_matches('"').to_string());
+            }
+        }
+        match license.as_deref() {
+            // Custom licenses name themselves in `license_name`
+            Some("other") => return license_name.or(license),
+            Some(id) if !id.is_empty() => return Some(normalize_card_license(id)),
+            _ => {}
+        }
+    }
+
+    let upper = text.to_uppercase();
+    let known = [
+        ("LLAMA 2 COMMUNITY LICENSE", "Llama-2-Community"),
+        ("LLAMA 3.3 COMMUNITY LICENSE", "Llama-3.3-Community"),
+        ("LLAMA 3.2 COMMUNITY LICENSE", "Llama-3.2-Community"),
+        ("LLAMA 3.1 COMMUNITY LICENSE", "Llama-3.1-Community"),
+        ("LLAMA 3 COMMUNITY LICENSE", "Llama-3-Community"),
+        ("CREATIVEML OPEN RAIL-M", "CreativeML-OpenRAIL-M"),
+        ("BIGSCIENCE BLOOM RAIL", "BigScience-BLOOM-RAIL-1.0"),
+        ("BIGSCIENCE OPEN RAIL-M", "BigScience-OpenRAIL-M"),
+        ("OPEN RAIL", "OpenRAIL"),
+        ("RESPONSIBLE AI LICENSE", "OpenRAIL"),
+        ("GEMMA TERMS OF USE", "Gemma"),
+        ("APACHE LICENSE", "Apache-2.0"),
+        ("MIT LICENSE", "MIT"),
+    ];
+    known
+        .iter()
+        .find(|(marker, _)| upper.contains(marker))
+        .map(|(_, license)| license.to_string())
+}
+
+/// Map Hugging Face license identifiers to the names used in results
+fn normalize_card_license(id: &str) -> String {
+    match id.to_lowercase().as_str() {
+        "openrail" => "OpenRAIL".to_string(),
+        "openrail++" => "OpenRAIL++".to_string(),
+        "creativeml-openrail-m" => "CreativeML-OpenRAIL-M".to_string(),
+        "bigscience-openrail-m" => "BigScience-OpenRAIL-M".to_string(),
+        "bigscience-bloom-rail-1.0" => "BigScience-BLOOM-RAIL-1.0".to_string(),
+        "llama2" => "Llama-2-Community".to_string(),
+        "llama3" => "Llama-3-Community".to_string(),
+        "llama3.1" => "Llama-3.1-Community".to_string(),
+        "llama3.2" => "Llama-3.2-Community".to_string(),
+        "llama3.3" => "Llama-3.3-Community".to_string(),
+        "gemma" => "Gemma".to_string(),
+        "apache-2.0" => "Apache-2.0".to_string(),
+        "mit" => "MIT".to_string(),
+        _ => id.to_string(),
+    }
+}
+
+/// SPDX identifiers exist only for some model licenses
+fn model_license_spdx_id(license: &str) -> Option<String> {
+    match license {
+        "CreativeML-OpenRAIL-M" | "BigScience-OpenRAIL-M" | "BigScience-BLOOM-RAIL-1.0"
+        | "Apache-2.0" | "MIT" => Some(license.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_model_card_license() {
+        let card = "---\nlicense: llama3.1\npipeline_tag: text-generation\n---\n# Model\n";
+        assert_eq!(detect_model_license(card).as_deref(), Some("Llama-3.1-Community"));
+
+        let card = "---\nlicense: other\nlicense_name: acme-model-license\n---\n";
+        assert_eq!(detect_model_license(card).as_deref(), Some("acme-model-license"));
+    }
+
+    #[test]
+    fn test_detect_license_text() {
+        let text = "LLAMA 2 COMMUNITY LICENSE AGREEMENT\nLlama 2 Version Release Date: July 18, 2023";
+        assert_eq!(detect_model_license(text).as_deref(), Some("Llama-2-Community"));
+        assert_eq!(
+            detect_model_license("CreativeML Open RAIL-M\ndated August 22, 2022").as_deref(),
+            Some("CreativeML-OpenRAIL-M")
+        );
+        assert_eq!(detect_model_license("# Just a readme"), None);
+    }
+
+    #[test]
+    fn test_model_format() {
+        assert_eq!(model_format(Path::new("weights/model.safetensors")), Some("safetensors"));
+        assert_eq!(model_format(Path::new("model.GGUF")), Some("gguf"));
+        assert_eq!(model_format(Path::new("model.py")), None);
+    }
+
+    #[tokio::test]
+    async fn test_scan_associates_parent_license() {
+        let dir = std::env::temp_dir().join(format!("model-scan-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(dir.join("models/small")).await.unwrap();
+        tokio::fs::write(dir.join("models/README.md"), "---\nlicense: openrail\n---\n").await.unwrap();
+        tokio::fs::write(dir.join("models/small/model.onnx"), b"onnx").await.unwrap();
+        tokio::fs::write(dir.join("orphan.gguf"), b"gguf").await.unwrap();
+
+        let mut results = ModelLicenseScanner::new().scan(&dir).await.unwrap();
+        results.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].file_path, "models/small/model.onnx");
+        assert_eq!(results[0].model_licenses[0].license, "OpenRAIL");
+        assert_eq!(results[0].model_licenses[0].source.as_deref(), Some("models/README.md"));
+        assert_eq!(results[1].model_licenses[0].license, NO_LICENSE);
+    }
+}
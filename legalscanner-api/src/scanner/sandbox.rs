@@ -0,0 +1,299 @@
+//! Confinement of scanners that run as subprocesses: Semgrep in local mode
+//! and Syft. Scanners parse files taken from the repository, so a malicious
+//! repository that exploits a scanner must not get hold of the host. Each
+//! subprocess can be
+//!
+//! - moved into a cgroup v2 directory whose limits the operator set up,
+//! - given resource limits on address space, CPU time, processes and the
+//!   size of files it writes,
+//! - run as a separate unprivileged user, and
+//! - wrapped in bubblewrap, which mounts the filesystem read-only, gives the
+//!   scanner a private `/tmp` and cuts it off the network unless allowed.
+//!
+//! Everything is off by default. Semgrep in Docker mode already runs in its
+//! container; there only the user applies.
+
+use crate::config::Config;
+use crate::scanner::traits::ScanError;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sandbox {
+    /// `uid` and `gid` the scanner runs as; switching needs the API to run
+    /// as root
+    pub user: Option<(u32, u32)>,
+    /// Address space limit, 0 for none
+    pub memory_mb: u64,
+    /// CPU time limit, 0 for none
+    pub cpu_secs: u64,
+    /// Limit on processes of the scanner's user, 0 for none
+    pub max_processes: u64,
+    /// Largest file the scanner may write, 0 for none
+    pub max_file_size_mb: u64,
+    /// cgroup v2 directory the scanner joins before it starts
+    pub cgroup: Option<PathBuf>,
+    /// bubblewrap binary that isolates the filesystem and network
+    pub bwrap: Option<String>,
+    /// Keep the network reachable under bubblewrap, which Semgrep registry
+    /// rulesets need
+    pub network: bool,
+}
+
+impl Sandbox {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            user: config.scanner_sandbox_user,
+            memory_mb: config.scanner_sandbox_memory_mb,
+            cpu_secs: config.scanner_sandbox_cpu_secs,
+            max_processes: config.scanner_sandbox_max_processes,
+            max_file_size_mb: config.scanner_sandbox_max_file_size_mb,
+            cgroup: config.scanner_sandbox_cgroup.clone(),
+            bwrap: config.scanner_sandbox_bwrap.clone(),
+            network: config.scanner_sandbox_network,
+        }
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.bwrap.is_none() && !self.confines_process()
+    }
+
+    /// Whether the process itself is confined, apart from bubblewrap
+    fn confines_process(&self) -> bool {
+        self.user.is_some()
+            || self.cgroup.is_some()
+            || self.memory_mb > 0
+            || self.cpu_secs > 0
+            || self.max_processes > 0
+            || self.max_file_size_mb > 0
+    }
+
+    /// Command running `program` with `args` in the sandbox. `workspace` is
+    /// the checkout the scanner reads, mounted read-only under bubblewrap.
+    /// The process is killed when the command's future is dropped.
+    pub fn command(
+        &self,
+        program: &str,
+        args: &[String],
+        workspace: Option<&Path>,
+    ) -> Result<Command, ScanError> {
+        let mut cmd = match &self.bwrap {
+            Some(bwrap) => {
+                let mut cmd = Command::new(bwrap);
+                cmd.args(self.bwrap_args(workspace)?).arg(program);
+                cmd
+            }
+            None => Command::new(program),
+        };
+        cmd.args(args).kill_on_drop(true);
+        self.confine(&mut cmd)?;
+        Ok(cmd)
+    }
+
+    /// bubblewrap arguments up to the program: the host filesystem read-only,
+    /// a private `/tmp` as home and the workspace bound over it
+    fn bwrap_args(&self, workspace: Option<&Path>) -> Result<Vec<String>, ScanError> {
+        let mut args: Vec<String> = [
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+            "--tmpfs",
+            "/tmp",
+        ]
+        .map(String::from)
+        .to_vec();
+        if let Some(workspace) = workspace {
+            let workspace = std::path::absolute(workspace)?
+                .to_string_lossy()
+                .into_owned();
+            args.extend(["--ro-bind".to_string(), workspace.clone(), workspace]);
+        }
+        args.extend(["--setenv", "HOME", "/tmp", "--unshare-all"].map(String::from));
+        if self.network {
+            args.push("--share-net".to_string());
+        }
+        args.extend(["--die-with-parent", "--new-session", "--"].map(String::from));
+        Ok(args)
+    }
+
+    /// Join the cgroup, lower the resource limits and drop to the sandbox
+    /// user between fork and exec, in that order: joining a cgroup and
+    /// switching users need the privileges given up last.
+    #[cfg(unix)]
+    fn confine(&self, cmd: &mut Command) -> Result<(), ScanError> {
+        if !self.confines_process() {
+            return Ok(());
+        }
+        let limits: Vec<_> = [
+            (libc::RLIMIT_AS, self.memory_mb * BYTES_PER_MB),
+            (libc::RLIMIT_CPU, self.cpu_secs),
+            (libc::RLIMIT_NPROC, self.max_processes),
+            (libc::RLIMIT_FSIZE, self.max_file_size_mb * BYTES_PER_MB),
+        ]
+        .into_iter()
+        .filter(|(_, value)| *value > 0)
+        .collect();
+        let cgroup_procs = match &self.cgroup {
+            Some(cgroup) => {
+                let procs = cgroup.join("cgroup.procs");
+                Some(
+                    std::ffi::CString::new(procs.to_string_lossy().into_owned()).map_err(|_| {
+                        ScanError::Unavailable(format!("Invalid cgroup path {}", cgroup.display()))
+                    })?,
+                )
+            }
+            None => None,
+        };
+        let user = self.user;
+
+        // SAFETY: the closure runs in the forked child and only makes
+        // async-signal-safe system calls, without allocating
+        unsafe {
+            cmd.pre_exec(move || {
+                if let Some(procs) = &cgroup_procs {
+                    // Writing 0 moves the writing process
+                    let fd = libc::open(procs.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+                    if fd < 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+                    let error = std::io::Error::last_os_error();
+                    libc::close(fd);
+                    if written != 1 {
+                        return Err(error);
+                    }
+                }
+                for (resource, value) in &limits {
+                    let limit = libc::rlimit {
+                        rlim_cur: *value as libc::rlim_t,
+                        rlim_max: *value as libc::rlim_t,
+                    };
+                    if libc::setrlimit(*resource, &limit) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                if let Some((uid, gid)) = user {
+                    if libc::setgroups(1, &gid) != 0
+                        || libc::setgid(gid) != 0
+                        || libc::setuid(uid) != 0
+                    {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn confine(&self, _cmd: &mut Command) -> Result<(), ScanError> {
+        if self.confines_process() {
+            return Err(ScanError::Unavailable(
+                "Scanner sandbox limits are not supported on this platform".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// `uid:gid` for a `docker exec`, whose container provides the rest of
+    /// the isolation
+    pub fn docker_user(&self) -> Option<String> {
+        self.user.map(|(uid, gid)| format!("{}:{}", uid, gid))
+    }
+}
+
+/// Parse `SCANNER_SANDBOX_USER`, a numeric `uid` or `uid:gid`. The group
+/// defaults to the `uid`. Root is refused, it would not confine anything.
+pub fn parse_user(value: &str) -> Result<Option<(u32, u32)>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let invalid = || {
+        format!(
+            "Invalid SCANNER_SANDBOX_USER '{}' (expected a numeric uid or uid:gid)",
+            value
+        )
+    };
+    let (uid, gid) = match value.split_once(':') {
+        Some((uid, gid)) => (uid, gid),
+        None => (value, value),
+    };
+    let uid: u32 = uid.trim().parse().map_err(|_| invalid())?;
+    let gid: u32 = gid.trim().parse().map_err(|_| invalid())?;
+    if uid == 0 || gid == 0 {
+        return Err("SCANNER_SANDBOX_USER must not be root".to_string());
+    }
+    Ok(Some((uid, gid)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_user() {
+        assert_eq!(parse_user(""), Ok(None));
+        assert_eq!(parse_user("1500"), Ok(Some((1500, 1500))));
+        assert_eq!(parse_user("1500:1600"), Ok(Some((1500, 1600))));
+        assert!(parse_user("scanner").is_err());
+        assert!(parse_user("0").is_err());
+        assert!(parse_user("1500:0").is_err());
+    }
+
+    #[test]
+    fn test_bwrap_args_bind_workspace_read_only() {
+        let sandbox = Sandbox {
+            bwrap: Some("bwrap".to_string()),
+            ..Sandbox::default()
+        };
+        let args = sandbox
+            .bwrap_args(Some(Path::new("/tmp/scans/abc")))
+            .unwrap();
+        let joined = args.join(" ");
+        assert!(joined.starts_with("--ro-bind / / "));
+        assert!(joined.contains("--tmpfs /tmp --ro-bind /tmp/scans/abc /tmp/scans/abc"));
+        assert!(joined.contains("--unshare-all"));
+        assert!(!joined.contains("--share-net"));
+        assert_eq!(args.last().map(String::as_str), Some("--"));
+
+        let sandbox = Sandbox {
+            network: true,
+            ..sandbox
+        };
+        assert!(sandbox
+            .bwrap_args(None)
+            .unwrap()
+            .contains(&"--share-net".to_string()));
+        assert!(!sandbox.is_disabled());
+        assert!(Sandbox::default().is_disabled());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_applies_limits() {
+        let sandbox = Sandbox {
+            cpu_secs: 7,
+            max_file_size_mb: 1,
+            ..Sandbox::default()
+        };
+        let args = ["-c".to_string(), "ulimit -t; ulimit -f".to_string()];
+        let output = sandbox
+            .command("sh", &args, None)
+            .unwrap()
+            .output()
+            .await
+            .unwrap();
+        assert!(output.status.success());
+        let limits = String::from_utf8_lossy(&output.stdout);
+        // ulimit -f counts 512-byte blocks
+        assert_eq!(limits.split_whitespace().collect::<Vec<_>>(), ["7", "2048"]);
+    }
+}
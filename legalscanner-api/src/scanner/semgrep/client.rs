@@ -1,13 +1,51 @@
+use crate::scanner::sandbox::Sandbox;
 use crate::scanner::traits::{ScanError, ScanResult, Scanner};
-use crate::scanner::semgrep::parser::parse_semgrep_output;
+use crate::scanner::semgrep::parser::{parse_semgrep_output_with_categories, CheckCategoryMap};
 use async_trait::async_trait;
-use std::path::Path;
-use std::process::Command;
+use bollard::container::LogOutput;
+use bollard::exec::{CreateExecOptions, StartExecResults};
+use bollard::Docker;
+use futures_util::StreamExt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::Duration;
 
+/// Rules directory mounted read-only into the Semgrep container
+pub const RULES_DIR: &str = "/semgrep-rules/";
+
+/// Default ruleset: custom ECC/crypto export control rules
+pub const DEFAULT_RULESET: &str = "/semgrep-rules/ecc-crypto-detection.yaml";
+
+/// How the Semgrep CLI is invoked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemgrepMode {
+    /// `semgrep` inside the `legalscanner-semgrep` container via the Docker API
+    Docker,
+    /// A locally installed `semgrep` binary run directly on the workspace path
+    Local,
+}
+
+impl FromStr for SemgrepMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "docker" => Ok(SemgrepMode::Docker),
+            "local" => Ok(SemgrepMode::Local),
+            other => Err(format!("Invalid SEMGREP_MODE '{}'. Must be 'local' or 'docker'", other)),
+        }
+    }
+}
+
 pub struct SemgrepScanner {
     pub container_name: String,
     pub timeout: Duration,
+    pub rulesets: Vec<String>,
+    pub check_categories: CheckCategoryMap,
+    pub mode: SemgrepMode,
+    pub binary: String,
+    pub local_rules_dir: PathBuf,
+    pub sandbox: Sandbox,
 }
 
 impl SemgrepScanner {
@@ -15,48 +53,112 @@ impl SemgrepScanner {
         Self {
             container_name: "legalscanner-semgrep".to_string(),
             timeout: Duration::from_secs(300), // 5 minutes default
+            rulesets: vec![DEFAULT_RULESET.to_string()],
+            check_categories: CheckCategoryMap::default(),
+            mode: SemgrepMode::Docker,
+            binary: "semgrep".to_string(),
+            local_rules_dir: PathBuf::from("./semgrep-rules"),
+            sandbox: Sandbox::default(),
         }
     }
 
+    /// Build a scanner from the server configuration
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        let scanner = Self::new()
+            .with_rulesets(config.semgrep_rulesets.clone())
+            .with_check_categories(config.semgrep_check_categories.clone())
+            .with_sandbox(Sandbox::from_config(config));
+
+        match config.semgrep_mode {
+            SemgrepMode::Docker => scanner,
+            SemgrepMode::Local => scanner.with_local_install(
+                config.semgrep_binary.clone(),
+                config.semgrep_local_rules_dir.clone(),
+            ),
+        }
+    }
+
+    /// Run a locally installed binary instead of the Docker container.
+    /// Rule files under `/semgrep-rules/` are resolved against `rules_dir`.
+    pub fn with_local_install(mut self, binary: String, rules_dir: PathBuf) -> Self {
+        self.mode = SemgrepMode::Local;
+        self.binary = binary;
+        self.local_rules_dir = rules_dir;
+        self
+    }
+
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
         self
     }
 
-    /// Execute Semgrep scan in Docker container
+    /// Rule files or registry rulesets passed to Semgrep as `--config` values.
+    /// An empty list keeps the current rulesets.
+    pub fn with_rulesets(mut self, rulesets: Vec<String>) -> Self {
+        if !rulesets.is_empty() {
+            self.rulesets = rulesets;
+        }
+        self
+    }
+
+    pub fn with_check_categories(mut self, check_categories: CheckCategoryMap) -> Self {
+        self.check_categories = check_categories;
+        self
+    }
+
+    /// Confine the local binary; in Docker mode only the sandbox user applies
+    pub fn with_sandbox(mut self, sandbox: Sandbox) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Execute Semgrep scan in Docker container or with the local binary
     async fn execute_scan(&self, repo_path: &Path) -> Result<String, ScanError> {
         let repo_path_str = repo_path
             .to_str()
             .ok_or_else(|| ScanError::Failed("Invalid repository path".to_string()))?;
 
-        tracing::info!("Executing Semgrep scan on {}", repo_path_str);
+        tracing::info!("Executing Semgrep scan on {} ({:?} mode)", repo_path_str, self.mode);
 
-        // Run Semgrep via docker exec
-        // We mount ./tmp -> /scans in the semgrep container
-        // API uses /app/tmp/scans/SCAN_ID, so we need /scans/scans/SCAN_ID in the container
-        let scan_path = format!("/scans/scans/{}",
-            repo_path.file_name()
-                .and_then(|n| n.to_str())
-                .ok_or_else(|| ScanError::Failed("Could not determine repo folder name".to_string()))?
-        );
+        let scan_path = match self.mode {
+            SemgrepMode::Local => repo_path_str.to_string(),
+            // Run Semgrep via docker exec
+            // We mount ./tmp -> /scans in the semgrep container
+            // API uses /app/tmp/scans/SCAN_ID, so we need /scans/scans/SCAN_ID in the container
+            SemgrepMode::Docker => format!("/scans/scans/{}",
+                repo_path.file_name()
+                    .and_then(|n| n.to_str())
+                    .ok_or_else(|| ScanError::Failed("Could not determine repo folder name".to_string()))?
+            ),
+        };
 
-        let output = Command::new("docker")
-            .args(&[
-                "exec",
-                &self.container_name,
-                "semgrep",
-                "--config", "/semgrep-rules/ecc-crypto-detection.yaml",  // Use custom ECC/crypto export control rules
-                "--json",
-                "--no-git-ignore",  // Scan all files
-                "--max-memory", "2000",  // Limit memory usage
-                &scan_path,
-            ])
-            .output()
-            .map_err(|e| ScanError::Failed(format!("Failed to execute Semgrep: {}", e)))?;
-
-        if !output.status.success() {
+        let mut cmd: Vec<String> = Vec::new();
+        for ruleset in &self.rulesets {
+            cmd.push("--config".to_string());
+            cmd.push(self.resolve_ruleset(ruleset));
+        }
+        cmd.extend([
+            "--json".to_string(),
+            "--no-git-ignore".to_string(),  // Scan all files
+            "--max-memory".to_string(), "2000".to_string(),  // Limit memory usage
+            scan_path,
+        ]);
+
+        tracing::debug!("Semgrep rulesets: {:?}", self.rulesets);
+
+        let output =
+            tokio::time::timeout(self.timeout, self.run_semgrep(cmd, Some(repo_path), self.timeout))
+                .await
+                .map_err(|_| {
+                    ScanError::Failed(format!(
+                        "Semgrep scan timed out after {} seconds",
+                        self.timeout.as_secs()
+                    ))
+                })??;
+
+        if !output.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            tracing::error!("Semgrep failed with status {}: {}", output.status, stderr);
+            tracing::error!("Semgrep failed with exit code {:?}: {}", output.exit_code, stderr);
 
             // Check if it's just warnings/info, not a complete failure
             if !output.stdout.is_empty() {
@@ -74,6 +176,131 @@ impl SemgrepScanner {
 
         Ok(json_output)
     }
+
+    /// Map container rule paths to the local rules directory in local mode
+    fn resolve_ruleset(&self, ruleset: &str) -> String {
+        match (self.mode, ruleset.strip_prefix(RULES_DIR)) {
+            (SemgrepMode::Local, Some(relative)) => {
+                self.local_rules_dir.join(relative).to_string_lossy().into_owned()
+            }
+            _ => ruleset.to_string(),
+        }
+    }
+
+    /// Run Semgrep with the given arguments using the configured mode.
+    /// `workspace` is the checkout Semgrep reads. Callers stop waiting after
+    /// `timeout`; Semgrep is stopped then as well.
+    async fn run_semgrep(
+        &self,
+        args: Vec<String>,
+        workspace: Option<&Path>,
+        timeout: Duration,
+    ) -> Result<ExecOutput, ScanError> {
+        match self.mode {
+            SemgrepMode::Docker => self.exec_in_container(container_command(args, timeout)).await,
+            SemgrepMode::Local => {
+                // kill_on_drop ensures the process is stopped if the timeout fires
+                let output = self
+                    .sandbox
+                    .command(&self.binary, &args, workspace)?
+                    .output()
+                    .await
+                    .map_err(|e| {
+                        ScanError::Unavailable(format!("Failed to run {}: {}", self.binary, e))
+                    })?;
+
+                Ok(ExecOutput {
+                    exit_code: output.status.code().map(i64::from),
+                    stdout: output.stdout,
+                    stderr: output.stderr,
+                })
+            }
+        }
+    }
+
+    /// Run a command in the Semgrep container through the Docker API
+    /// and collect its output without blocking the async runtime
+    async fn exec_in_container(&self, cmd: Vec<String>) -> Result<ExecOutput, ScanError> {
+        let docker = Docker::connect_with_local_defaults()
+            .map_err(|e| ScanError::Unavailable(format!("Failed to connect to Docker: {}", e)))?;
+
+        let exec = docker
+            .create_exec(
+                &self.container_name,
+                CreateExecOptions {
+                    cmd: Some(cmd),
+                    user: self.sandbox.docker_user(),
+                    attach_stdout: Some(true),
+                    attach_stderr: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| ScanError::Failed(format!("Failed to create Semgrep exec: {}", e)))?;
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        match docker
+            .start_exec(&exec.id, None)
+            .await
+            .map_err(|e| ScanError::Failed(format!("Failed to execute Semgrep: {}", e)))?
+        {
+            StartExecResults::Attached { mut output, .. } => {
+                while let Some(chunk) = output.next().await {
+                    match chunk.map_err(|e| {
+                        ScanError::Failed(format!("Failed to read Semgrep output: {}", e))
+                    })? {
+                        LogOutput::StdOut { message } => stdout.extend_from_slice(&message),
+                        LogOutput::StdErr { message } => stderr.extend_from_slice(&message),
+                        _ => {}
+                    }
+                }
+            }
+            StartExecResults::Detached => {
+                return Err(ScanError::Failed(
+                    "Semgrep exec unexpectedly started detached".to_string(),
+                ));
+            }
+        }
+
+        let exit_code = docker
+            .inspect_exec(&exec.id)
+            .await
+            .map_err(|e| ScanError::Failed(format!("Failed to inspect Semgrep exec: {}", e)))?
+            .exit_code;
+
+        Ok(ExecOutput {
+            exit_code,
+            stdout,
+            stderr,
+        })
+    }
+}
+
+/// `semgrep` with `args` as run in the container. Dropping the exec stream
+/// does not stop the process in the container, so `timeout` stops it there.
+fn container_command(args: Vec<String>, timeout: Duration) -> Vec<String> {
+    let mut cmd = vec![
+        "timeout".to_string(),
+        timeout.as_secs().max(1).to_string(),
+        "semgrep".to_string(),
+    ];
+    cmd.extend(args);
+    cmd
+}
+
+/// Exit code and output of a Semgrep run, in the container or locally
+struct ExecOutput {
+    exit_code: Option<i64>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+impl ExecOutput {
+    fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
 }
 
 impl Default for SemgrepScanner {
@@ -95,7 +322,7 @@ impl Scanner for SemgrepScanner {
         let json_output = self.execute_scan(repo_path).await?;
 
         // Parse output
-        let results = parse_semgrep_output(&json_output)?;
+        let results = parse_semgrep_output_with_categories(&json_output, &self.check_categories)?;
 
         tracing::info!("Semgrep scan completed, found {} files with findings", results.len());
 
@@ -105,17 +332,16 @@ impl Scanner for SemgrepScanner {
     async fn health_check(&self) -> Result<(), ScanError> {
         tracing::debug!("Checking Semgrep container health");
 
-        let output = Command::new("docker")
-            .args(&[
-                "exec",
-                &self.container_name,
-                "semgrep",
-                "--version",
-            ])
-            .output()
-            .map_err(|e| ScanError::Unavailable(format!("Failed to check Semgrep version: {}", e)))?;
+        let timeout = Duration::from_secs(30);
+        let output = tokio::time::timeout(
+            timeout,
+            self.run_semgrep(vec!["--version".to_string()], None, timeout),
+        )
+        .await
+        .map_err(|_| ScanError::Unavailable("Semgrep version check timed out".to_string()))?
+        .map_err(|e| ScanError::Unavailable(format!("Failed to check Semgrep version: {}", e)))?;
 
-        if !output.status.success() {
+        if !output.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(ScanError::Unavailable(format!(
                 "Semgrep is not available: {}",
@@ -130,6 +356,29 @@ impl Scanner for SemgrepScanner {
     }
 }
 
+/// Validate a ruleset reference: either a Semgrep registry ruleset (`p/...`, `r/...`)
+/// or a YAML file inside the mounted rules directory
+pub fn validate_ruleset(ruleset: &str) -> Result<(), String> {
+    let is_registry = (ruleset.starts_with("p/") || ruleset.starts_with("r/"))
+        && ruleset.len() > 2
+        && ruleset
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '-' | '_' | '.'));
+
+    let is_local_rule_file = ruleset.starts_with(RULES_DIR)
+        && !ruleset.contains("..")
+        && (ruleset.ends_with(".yaml") || ruleset.ends_with(".yml"));
+
+    if is_registry || is_local_rule_file {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid Semgrep ruleset '{}'. Must be a registry ruleset (p/..., r/...) or a YAML file under {}",
+            ruleset, RULES_DIR
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,9 +390,49 @@ mod tests {
         assert_eq!(scanner.container_name, "legalscanner-semgrep");
     }
 
+    #[test]
+    fn test_scanner_with_rulesets() {
+        let scanner = SemgrepScanner::new();
+        assert_eq!(scanner.rulesets, vec![DEFAULT_RULESET.to_string()]);
+
+        let scanner = scanner.with_rulesets(vec!["p/secrets".to_string()]);
+        assert_eq!(scanner.rulesets, vec!["p/secrets".to_string()]);
+    }
+
+    #[test]
+    fn test_local_mode_resolves_rules_dir() {
+        let scanner = SemgrepScanner::new()
+            .with_local_install("semgrep".to_string(), PathBuf::from("/opt/rules"));
+        assert_eq!(scanner.mode, SemgrepMode::Local);
+        assert_eq!(
+            scanner.resolve_ruleset(DEFAULT_RULESET),
+            "/opt/rules/ecc-crypto-detection.yaml"
+        );
+        assert_eq!(scanner.resolve_ruleset("p/secrets"), "p/secrets");
+        assert_eq!("local".parse::<SemgrepMode>(), Ok(SemgrepMode::Local));
+        assert!("podman".parse::<SemgrepMode>().is_err());
+    }
+
+    #[test]
+    fn test_validate_ruleset() {
+        assert!(validate_ruleset("p/secrets").is_ok());
+        assert!(validate_ruleset("/semgrep-rules/ecc-crypto-detection.yaml").is_ok());
+        assert!(validate_ruleset("/semgrep-rules/../etc/passwd.yaml").is_err());
+        assert!(validate_ruleset("https://example.com/rules.yaml").is_err());
+        assert!(validate_ruleset("p/").is_err());
+    }
+
     #[test]
     fn test_scanner_with_timeout() {
         let scanner = SemgrepScanner::new().with_timeout(Duration::from_secs(60));
         assert_eq!(scanner.timeout, Duration::from_secs(60));
     }
+
+    #[test]
+    fn test_container_command_enforces_timeout() {
+        let cmd = container_command(vec!["--json".to_string()], Duration::from_secs(300));
+        assert_eq!(cmd, vec!["timeout", "300", "semgrep", "--json"]);
+        let cmd = container_command(Vec::new(), Duration::from_millis(200));
+        assert_eq!(cmd, vec!["timeout", "1", "semgrep"]);
+    }
 }
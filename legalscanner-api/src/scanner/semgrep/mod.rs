@@ -1,5 +1,7 @@
 mod client;
 mod parser;
 
-pub use client::SemgrepScanner;
-pub use parser::parse_semgrep_output;
+pub use client::{validate_ruleset, SemgrepMode, SemgrepScanner};
+pub use parser::{
+    parse_semgrep_output, parse_semgrep_output_with_categories, CheckCategoryMap, FindingCategory,
+};
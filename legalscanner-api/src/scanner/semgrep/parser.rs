@@ -1,6 +1,67 @@
-use crate::scanner::traits::{EccFinding, ScanError, ScanResult};
+use crate::scanner::traits::{
+    CopyrightFinding, EccFinding, LicenseCategory, LicenseFinding, ScanError, ScanResult,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Result type a Semgrep finding is stored as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindingCategory {
+    Ecc,
+    License,
+    Copyright,
+}
+
+impl FromStr for FindingCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ecc" => Ok(FindingCategory::Ecc),
+            "license" => Ok(FindingCategory::License),
+            "copyright" => Ok(FindingCategory::Copyright),
+            other => Err(format!(
+                "Unknown Semgrep finding category '{}'. Must be one of: ecc, license, copyright",
+                other
+            )),
+        }
+    }
+}
+
+/// Maps Semgrep check IDs to result categories by prefix.
+/// Check IDs without a matching prefix are treated as ECC findings.
+#[derive(Debug, Clone, Default)]
+pub struct CheckCategoryMap {
+    prefixes: Vec<(String, FindingCategory)>,
+}
+
+impl CheckCategoryMap {
+    /// Parse a comma-separated list of `check_id_prefix=category` entries,
+    /// e.g. `generic.secrets.=ecc,license-headers.=license`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut prefixes = Vec::new();
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (prefix, category) = entry.split_once('=').ok_or_else(|| {
+                format!("Invalid Semgrep category mapping '{}', expected prefix=category", entry)
+            })?;
+            prefixes.push((prefix.trim().to_string(), category.parse()?));
+        }
+
+        Ok(Self { prefixes })
+    }
+
+    /// Returns the category for a check ID using the longest matching prefix
+    pub fn category_for(&self, check_id: &str) -> FindingCategory {
+        self.prefixes
+            .iter()
+            .filter(|(prefix, _)| check_id.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, category)| *category)
+            .unwrap_or(FindingCategory::Ecc)
+    }
+}
 
 /// Semgrep JSON output structure
 #[derive(Debug, Deserialize, Serialize)]
@@ -44,6 +105,14 @@ pub struct SemgrepError {
 
 /// Parse Semgrep JSON output and convert to ScanResult format
 pub fn parse_semgrep_output(json_output: &str) -> Result<Vec<ScanResult>, ScanError> {
+    parse_semgrep_output_with_categories(json_output, &CheckCategoryMap::default())
+}
+
+/// Parse Semgrep JSON output, routing each finding to the result type its check ID maps to
+pub fn parse_semgrep_output_with_categories(
+    json_output: &str,
+    categories: &CheckCategoryMap,
+) -> Result<Vec<ScanResult>, ScanError> {
     let semgrep_output: SemgrepOutput = serde_json::from_str(json_output)
         .map_err(|e| ScanError::ParseError(format!("Failed to parse Semgrep JSON: {}", e)))?;
 
@@ -57,9 +126,55 @@ pub fn parse_semgrep_output(json_output: &str) -> Result<Vec<ScanResult>, ScanEr
     }
 
     // Group findings by file path
-    let mut results_by_file: HashMap<String, Vec<EccFinding>> = HashMap::new();
+    let mut results_by_file: HashMap<String, ScanResult> = HashMap::new();
 
     for result in semgrep_output.results {
+        let entry = results_by_file
+            .entry(result.path.clone())
+            .or_insert_with(|| ScanResult {
+                file_path: result.path.clone(),
+                licenses: Vec::new(),
+                copyrights: Vec::new(),
+                ecc_findings: Vec::new(),
+                malware_findings: Vec::new(),
+                package_findings: Vec::new(),
+                model_licenses: Vec::new(),
+            });
+
+        match categories.category_for(&result.check_id) {
+            FindingCategory::License => {
+                let metadata_str = |key: &str| {
+                    result.extra.metadata.get(key).and_then(|v| v.as_str()).map(str::to_string)
+                };
+                entry.licenses.push(LicenseFinding {
+                    name: metadata_str("license").unwrap_or_else(|| result.extra.message.clone()),
+                    spdx_id: metadata_str("spdx_id"),
+                    confidence: 1.0,
+                    category: LicenseCategory::Code,
+                    provenance: None,
+                    agreement: None,
+                    exception: None,
+                });
+                continue;
+            }
+            FindingCategory::Copyright => {
+                let statement = result
+                    .lines
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .unwrap_or(&result.extra.message)
+                    .to_string();
+                entry.copyrights.push(CopyrightFinding {
+                    statement,
+                    holders: Vec::new(),
+                    years: Vec::new(),
+                });
+                continue;
+            }
+            FindingCategory::Ecc => {}
+        }
+
         // Build detailed content message with matched code
         let content = if let Some(matched_code) = &result.lines {
             let matched_code = matched_code.trim();
@@ -80,22 +195,11 @@ pub fn parse_semgrep_output(json_output: &str) -> Result<Vec<ScanResult>, ScanEr
             check_id: Some(result.check_id.clone()),
         };
 
-        results_by_file
-            .entry(result.path.clone())
-            .or_insert_with(Vec::new)
-            .push(finding);
+        entry.ecc_findings.push(finding);
     }
 
     // Convert to ScanResult format
-    let mut scan_results = Vec::new();
-    for (file_path, ecc_findings) in results_by_file {
-        scan_results.push(ScanResult {
-            file_path,
-            licenses: Vec::new(),
-            copyrights: Vec::new(),
-            ecc_findings,
-        });
-    }
+    let scan_results: Vec<ScanResult> = results_by_file.into_values().collect();
 
     tracing::info!("Parsed {} Semgrep findings", scan_results.len());
 
@@ -158,6 +262,38 @@ mod tests {
         assert_eq!(finding.check_id, Some("rust.crypto.aes-usage".to_string()));
     }
 
+    #[test]
+    fn test_category_mapping() {
+        let categories =
+            CheckCategoryMap::parse("license-headers.=license, copyright.=copyright").unwrap();
+        assert_eq!(categories.category_for("license-headers.apache"), FindingCategory::License);
+        assert_eq!(categories.category_for("copyright.notice"), FindingCategory::Copyright);
+        assert_eq!(categories.category_for("rust.crypto.aes-usage"), FindingCategory::Ecc);
+        assert!(CheckCategoryMap::parse("foo=secrets").is_err());
+
+        let json = r#"{
+            "results": [
+                {
+                    "path": "src/lib.rs",
+                    "start": {"line": 1, "col": 1},
+                    "end": {"line": 1, "col": 40},
+                    "check_id": "license-headers.apache",
+                    "extra": {
+                        "message": "Apache license header",
+                        "severity": "INFO",
+                        "metadata": {"license": "Apache-2.0", "spdx_id": "Apache-2.0"}
+                    }
+                }
+            ]
+        }"#;
+
+        let results = parse_semgrep_output_with_categories(json, &categories).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].ecc_findings.is_empty());
+        assert_eq!(results[0].licenses[0].name, "Apache-2.0");
+        assert_eq!(results[0].licenses[0].spdx_id.as_deref(), Some("Apache-2.0"));
+    }
+
     #[test]
     fn test_severity_mapping() {
         assert_eq!(map_severity("ERROR"), "high");
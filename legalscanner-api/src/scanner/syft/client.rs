@@ -0,0 +1,94 @@
+use super::parser::parse_syft_output;
+use crate::scanner::sandbox::Sandbox;
+use crate::scanner::traits::{ScanError, ScanResult, Scanner};
+use async_trait::async_trait;
+use std::path::Path;
+use std::time::Duration;
+
+/// Package inventory scanner backed by a locally installed `syft` binary
+pub struct SyftScanner {
+    pub binary: String,
+    pub timeout: Duration,
+    pub sandbox: Sandbox,
+}
+
+impl SyftScanner {
+    pub fn new(binary: String) -> Self {
+        Self {
+            binary,
+            timeout: Duration::from_secs(600), // 10 minutes
+            sandbox: Sandbox::default(),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_sandbox(mut self, sandbox: Sandbox) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+}
+
+#[async_trait]
+impl Scanner for SyftScanner {
+    fn name(&self) -> &str {
+        "syft"
+    }
+
+    async fn scan(&self, repo_path: &Path) -> Result<Vec<ScanResult>, ScanError> {
+        tracing::info!("Starting Syft package inventory for {:?}", repo_path);
+
+        let source = format!("dir:{}", repo_path.to_string_lossy());
+
+        let args = [source, "-o".to_string(), "syft-json".to_string(), "-q".to_string()];
+
+        // The sandboxed command is killed when the timeout drops it
+        let output = tokio::time::timeout(
+            self.timeout,
+            self.sandbox
+                .command(&self.binary, &args, Some(repo_path))?
+                .output(),
+        )
+        .await
+        .map_err(|_| ScanError::Failed(format!("Syft timed out after {:?}", self.timeout)))?
+        .map_err(|e| ScanError::Unavailable(format!("Failed to run {}: {}", self.binary, e)))?;
+
+        if !output.status.success() {
+            return Err(ScanError::Failed(format!(
+                "Syft exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let results = parse_syft_output(&String::from_utf8_lossy(&output.stdout))?;
+
+        tracing::info!(
+            "Syft package inventory completed, {} packages",
+            results.iter().map(|r| r.package_findings.len()).sum::<usize>()
+        );
+
+        Ok(results)
+    }
+
+    async fn health_check(&self) -> Result<(), ScanError> {
+        let output = self
+            .sandbox
+            .command(&self.binary, &["version".to_string()], None)?
+            .output()
+            .await
+            .map_err(|e| ScanError::Unavailable(format!("Failed to run {}: {}", self.binary, e)))?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(ScanError::Unavailable(format!(
+                "{} version exited with {}",
+                self.binary, output.status
+            )))
+        }
+    }
+}
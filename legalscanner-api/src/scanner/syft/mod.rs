@@ -0,0 +1,5 @@
+mod client;
+mod parser;
+
+pub use client::SyftScanner;
+pub use parser::parse_syft_output;
@@ -0,0 +1,159 @@
+use crate::scanner::traits::{PackageFinding, ScanError, ScanResult};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Used when Syft reports a package without any location
+const UNKNOWN_LOCATION: &str = "(unknown)";
+
+#[derive(Debug, Deserialize)]
+struct SyftDocument {
+    #[serde(default)]
+    artifacts: Vec<SyftArtifact>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SyftArtifact {
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(rename = "type")]
+    package_type: String,
+    #[serde(default)]
+    licenses: Vec<SyftLicense>,
+    #[serde(default)]
+    locations: Vec<SyftLocation>,
+    #[serde(default)]
+    purl: Option<String>,
+}
+
+/// Syft emits plain strings in older releases and objects in newer ones
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SyftLicense {
+    Plain(String),
+    Detailed {
+        value: String,
+        #[serde(default, rename = "spdxExpression")]
+        spdx_expression: String,
+    },
+}
+
+impl SyftLicense {
+    fn expression(&self) -> &str {
+        match self {
+            SyftLicense::Plain(value) => value,
+            SyftLicense::Detailed { value, spdx_expression } if spdx_expression.is_empty() => value,
+            SyftLicense::Detailed { spdx_expression, .. } => spdx_expression,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SyftLocation {
+    path: String,
+}
+
+/// Parse `syft -o syft-json` output into package findings grouped by manifest path
+pub fn parse_syft_output(json: &str) -> Result<Vec<ScanResult>, ScanError> {
+    let document: SyftDocument = serde_json::from_str(json)
+        .map_err(|e| ScanError::ParseError(format!("Failed to parse Syft output: {}", e)))?;
+
+    let mut by_path: BTreeMap<String, Vec<PackageFinding>> = BTreeMap::new();
+
+    for artifact in document.artifacts {
+        let file_path = artifact
+            .locations
+            .first()
+            .map(|l| l.path.trim_start_matches('/').to_string())
+            .unwrap_or_else(|| UNKNOWN_LOCATION.to_string());
+
+        let mut licenses: Vec<&str> = Vec::new();
+        for license in &artifact.licenses {
+            let expression = license.expression();
+            if !expression.is_empty() && !licenses.contains(&expression) {
+                licenses.push(expression);
+            }
+        }
+
+        let declared_license = match licenses.len() {
+            0 => None,
+            1 => Some(licenses[0].to_string()),
+            _ => Some(format!("({})", licenses.join(" AND "))),
+        };
+
+        by_path.entry(file_path).or_default().push(PackageFinding {
+            name: artifact.name,
+            version: Some(artifact.version).filter(|v| !v.is_empty()),
+            ecosystem: artifact.package_type,
+            declared_license,
+            purl: artifact.purl.filter(|p| !p.is_empty()),
+        });
+    }
+
+    Ok(by_path
+        .into_iter()
+        .map(|(file_path, package_findings)| ScanResult {
+            file_path,
+            licenses: Vec::new(),
+            copyrights: Vec::new(),
+            ecc_findings: Vec::new(),
+            malware_findings: Vec::new(),
+            package_findings,
+            model_licenses: Vec::new(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_syft_output() {
+        let json = r#"{
+            "artifacts": [
+                {
+                    "name": "serde",
+                    "version": "1.0.210",
+                    "type": "rust-crate",
+                    "licenses": [{"value": "MIT OR Apache-2.0", "spdxExpression": "MIT OR Apache-2.0", "type": "declared"}],
+                    "locations": [{"path": "/Cargo.lock"}],
+                    "purl": "pkg:cargo/serde@1.0.210"
+                },
+                {
+                    "name": "left-pad",
+                    "version": "1.3.0",
+                    "type": "npm",
+                    "licenses": ["WTFPL"],
+                    "locations": [{"path": "/ui/package-lock.json"}]
+                },
+                {
+                    "name": "tokio",
+                    "version": "",
+                    "type": "rust-crate",
+                    "locations": [{"path": "/Cargo.lock"}]
+                }
+            ]
+        }"#;
+
+        let results = parse_syft_output(json).unwrap();
+        assert_eq!(results.len(), 2);
+
+        assert_eq!(results[0].file_path, "Cargo.lock");
+        assert_eq!(results[0].package_findings.len(), 2);
+        let serde = &results[0].package_findings[0];
+        assert_eq!(serde.declared_license.as_deref(), Some("MIT OR Apache-2.0"));
+        assert_eq!(serde.purl.as_deref(), Some("pkg:cargo/serde@1.0.210"));
+        assert_eq!(results[0].package_findings[1].version, None);
+        assert_eq!(results[0].package_findings[1].declared_license, None);
+
+        assert_eq!(results[1].file_path, "ui/package-lock.json");
+        assert_eq!(results[1].package_findings[0].ecosystem, "npm");
+        assert_eq!(results[1].package_findings[0].declared_license.as_deref(), Some("WTFPL"));
+    }
+
+    #[test]
+    fn test_parse_invalid_output() {
+        assert!(parse_syft_output("not json").is_err());
+    }
+}
@@ -1,6 +1,9 @@
+use crate::progress::ProgressReporter;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::time::Duration;
+use tokio::sync::OwnedSemaphorePermit;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanResult {
@@ -8,6 +11,12 @@ pub struct ScanResult {
     pub licenses: Vec<LicenseFinding>,
     pub copyrights: Vec<CopyrightFinding>,
     pub ecc_findings: Vec<EccFinding>,
+    #[serde(default)]
+    pub malware_findings: Vec<MalwareFinding>,
+    #[serde(default)]
+    pub package_findings: Vec<PackageFinding>,
+    #[serde(default)]
+    pub model_licenses: Vec<ModelLicenseFinding>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +24,59 @@ pub struct LicenseFinding {
     pub name: String,
     pub spdx_id: Option<String>,
     pub confidence: f32,
+    #[serde(default)]
+    pub category: LicenseCategory,
+    /// Scanner(s) that reported the license, prefixed with the merge strategy
+    /// that kept it once merged (e.g. `consensus:fossology+semgrep`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<String>,
+    /// Whether the other license scanners agree, set for files that more
+    /// than one of them reported licenses for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agreement: Option<LicenseAgreement>,
+    /// SPDX exception the license is granted with, e.g.
+    /// `Classpath-exception-2.0`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exception: Option<String>,
+}
+
+/// What kind of content a license finding applies to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LicenseCategory {
+    #[default]
+    Code,
+    Documentation,
+    Data,
+}
+
+impl LicenseCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LicenseCategory::Code => "code",
+            LicenseCategory::Documentation => "documentation",
+            LicenseCategory::Data => "data",
+        }
+    }
+}
+
+/// Outcome of comparing the license scanners' findings for a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LicenseAgreement {
+    /// Another scanner reported the same license
+    Corroborated,
+    /// Other scanners reported licenses for the file, but not this one
+    Disputed,
+}
+
+impl LicenseAgreement {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LicenseAgreement::Corroborated => "corroborated",
+            LicenseAgreement::Disputed => "disputed",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +98,34 @@ pub struct EccFinding {
     pub check_id: Option<String>, // Semgrep rule ID
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MalwareFinding {
+    pub signature: String, // e.g. "Eicar-Test-Signature"
+    pub source: String,    // "clamav"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageFinding {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub ecosystem: String, // e.g. "npm", "rust-crate", "python"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub declared_license: Option<String>, // SPDX expression when available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purl: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelLicenseFinding {
+    pub license: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spdx_id: Option<String>,
+    pub format: String, // "safetensors", "onnx", "gguf"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>, // Model card or license file the license was read from
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ScanError {
     #[error("Scanner unavailable: {0}")]
@@ -47,6 +137,10 @@ pub enum ScanError {
     #[error("Parse error: {0}")]
     ParseError(String),
 
+    /// The backend answered with an error message of its own
+    #[error("Request rejected: {0}")]
+    Rejected(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -54,6 +148,23 @@ pub enum ScanError {
     Http(#[from] reqwest::Error),
 }
 
+/// Capacity reserved on a shared scanning backend; released when dropped
+#[derive(Debug)]
+pub struct ScanSlot {
+    /// Time spent queued before the slot was granted
+    pub waited: Duration,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl ScanSlot {
+    pub fn new(permit: OwnedSemaphorePermit, waited: Duration) -> Self {
+        Self {
+            waited,
+            _permit: permit,
+        }
+    }
+}
+
 #[async_trait]
 pub trait Scanner: Send + Sync {
     /// Returns the name/identifier of this scanner
@@ -63,6 +174,24 @@ pub trait Scanner: Send + Sync {
     /// Returns a list of results for each file scanned
     async fn scan(&self, repo_path: &Path) -> Result<Vec<ScanResult>, ScanError>;
 
+    /// Like `scan`, additionally reporting intermediate progress (upload
+    /// percentage, job ETA) to live subscribers. Scanners without anything
+    /// to report just scan.
+    async fn scan_with_progress(
+        &self,
+        repo_path: &Path,
+        _progress: &ProgressReporter,
+    ) -> Result<Vec<ScanResult>, ScanError> {
+        self.scan(repo_path).await
+    }
+
+    /// Waits for capacity on a backend shared between concurrent scans.
+    /// Hold the returned slot until `scan` finishes. Scanners without a
+    /// concurrency limit return `None` immediately.
+    async fn reserve_slot(&self) -> Result<Option<ScanSlot>, ScanError> {
+        Ok(None)
+    }
+
     /// Checks if the scanner is available and healthy
     async fn health_check(&self) -> Result<(), ScanError>;
 }
@@ -0,0 +1,24 @@
+use crate::scanner::traits::ScanError;
+use std::path::{Path, PathBuf};
+
+/// Recursively list regular files, skipping the .git directory
+pub(crate) async fn collect_files(root: &Path) -> Result<Vec<PathBuf>, ScanError> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let file_type = entry.file_type().await?;
+            if file_type.is_dir() {
+                if entry.file_name() != ".git" {
+                    pending.push(entry.path());
+                }
+            } else if file_type.is_file() {
+                files.push(entry.path());
+            }
+        }
+    }
+
+    Ok(files)
+}
@@ -0,0 +1,241 @@
+//! Export control screening hook. Scans with ECC findings at or above
+//! `SCREENING_MIN_SEVERITY` are submitted to an external trade compliance
+//! system, and approval is refused until that system clears them.
+
+use crate::config::Config;
+use crate::db::models::{Scan, ScanResult, ScanScreening};
+use crate::error::AppError;
+use crate::storage::ResultQuery;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Stored webhook responses are truncated to this many bytes
+const MAX_RESPONSE_BODY: usize = 4096;
+
+/// Payload POSTed to the screening webhook
+#[derive(Debug, Serialize)]
+pub struct ScreeningRequest {
+    pub scan_id: String,
+    pub git_url: String,
+    pub min_severity: String,
+    pub findings: Vec<ScreeningFinding>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScreeningFinding {
+    pub file_path: String,
+    pub severity: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_number: Option<i32>,
+}
+
+/// Optional decision in the webhook's response body. Systems that decide
+/// asynchronously reply without one and report back via the screening API.
+#[derive(Debug, Default, Deserialize)]
+struct WebhookDecision {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    reference: Option<String>,
+}
+
+/// Submit the scan for screening if it has qualifying ECC findings and a
+/// webhook is configured. Returns `None` when no screening is needed.
+pub async fn screen_if_required(
+    state: &AppState,
+    scan_id: &str,
+) -> Result<Option<ScanScreening>, AppError> {
+    if state.config.screening_webhook_url.is_none() {
+        return Ok(None);
+    }
+
+    let query = ResultQuery {
+        result_type: Some("ecc".to_string()),
+        ..Default::default()
+    };
+    let results = state.result_store.query_results(scan_id, &query).await?;
+    if qualifying_findings(&results, &state.config.screening_min_severity).is_empty() {
+        return Ok(None);
+    }
+
+    submit(state, scan_id).await.map(Some)
+}
+
+/// Send the scan's qualifying ECC findings to the screening webhook and
+/// record the outcome. Transport failures are recorded with status `error`.
+pub async fn submit(state: &AppState, scan_id: &str) -> Result<ScanScreening, AppError> {
+    let url = state.config.screening_webhook_url.as_deref().ok_or_else(|| {
+        AppError::Validation("Export control screening is not configured".to_string())
+    })?;
+
+    let scan = Scan::find_by_id(&state.db, scan_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Scan {} not found", scan_id)))?;
+    // Through the result store, the findings may have been archived since
+    let query = ResultQuery {
+        result_type: Some("ecc".to_string()),
+        ..Default::default()
+    };
+    let mut results = state.result_store.query_results(scan_id, &query).await?;
+    results.sort_by(|a, b| {
+        b.risk_severity
+            .cmp(&a.risk_severity)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+
+    let request = ScreeningRequest {
+        scan_id: scan.id.clone(),
+        git_url: scan.git_url.clone(),
+        min_severity: state.config.screening_min_severity.clone(),
+        findings: qualifying_findings(&results, &state.config.screening_min_severity),
+    };
+
+    tracing::info!(
+        "Submitting scan {} for export control screening ({} findings)",
+        scan_id,
+        request.findings.len()
+    );
+
+    let screening = match send(&state.config, url, &request).await {
+        Ok((code, body)) => {
+            let (status, reference) = interpret_response(code, &body);
+            ScanScreening::upsert(
+                &state.db,
+                scan_id,
+                status,
+                reference.as_deref(),
+                Some(code as i32),
+                Some(truncate(&body)),
+            )
+            .await?
+        }
+        Err(e) => {
+            tracing::error!("Screening webhook failed for scan {}: {}", scan_id, e);
+            ScanScreening::upsert(&state.db, scan_id, "error", None, None, Some(&e.to_string()))
+                .await?
+        }
+    };
+
+    tracing::info!("Scan {} screening status: {}", scan_id, screening.status);
+    Ok(screening)
+}
+
+async fn send(
+    config: &Config,
+    url: &str,
+    request: &ScreeningRequest,
+) -> Result<(u16, String), reqwest::Error> {
+    let client = reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build()?;
+
+    let mut builder = client.post(url).json(request);
+    if let Some(token) = &config.screening_webhook_token {
+        builder = builder.bearer_auth(token);
+    }
+
+    let response = builder.send().await?;
+    let code = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    Ok((code, body))
+}
+
+/// Map the webhook's HTTP response to a screening status. A 2xx response
+/// without a recognised decision leaves the screening pending.
+fn interpret_response(code: u16, body: &str) -> (&'static str, Option<String>) {
+    if !(200..300).contains(&code) {
+        return ("error", None);
+    }
+
+    let decision: WebhookDecision = serde_json::from_str(body).unwrap_or_default();
+    let status = match decision.status.as_deref().map(str::to_lowercase).as_deref() {
+        Some("cleared") => "cleared",
+        Some("blocked") => "blocked",
+        _ => "pending",
+    };
+    (status, decision.reference)
+}
+
+/// ECC findings at or above the severity threshold
+fn qualifying_findings(results: &[ScanResult], min_severity: &str) -> Vec<ScreeningFinding> {
+    let threshold = severity_rank(min_severity);
+    results
+        .iter()
+        .filter(|r| r.result_type == "ecc")
+        .filter(|r| severity_rank(r.risk_severity.as_deref().unwrap_or_default()) >= threshold)
+        .map(|r| ScreeningFinding {
+            file_path: r.file_path.clone(),
+            severity: r.risk_severity.clone().unwrap_or_default(),
+            check_id: r.ecc_check_id.clone(),
+            source: r.ecc_source.clone(),
+            line_number: r.ecc_line_number,
+        })
+        .collect()
+}
+
+pub(crate) fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "critical" => 4,
+        "high" => 3,
+        "medium" => 2,
+        "low" => 1,
+        _ => 0,
+    }
+}
+
+pub(crate) fn truncate(body: &str) -> &str {
+    if body.len() <= MAX_RESPONSE_BODY {
+        return body;
+    }
+    let mut end = MAX_RESPONSE_BODY;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    &body[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ecc(severity: &str) -> ScanResult {
+        ScanResult {
+            risk_severity: Some(severity.to_string()),
+            ecc_source: Some("semgrep".to_string()),
+            ecc_line_number: Some(12),
+            ecc_check_id: Some("ecc.aes".to_string()),
+            ..ScanResult::for_test("ecc", "src/crypto.rs")
+        }
+    }
+
+    #[test]
+    fn test_qualifying_findings_respects_threshold() {
+        let results = vec![ecc("low"), ecc("high"), ecc("critical")];
+
+        assert_eq!(qualifying_findings(&results, "critical").len(), 1);
+        assert_eq!(qualifying_findings(&results, "high").len(), 2);
+        assert_eq!(qualifying_findings(&results, "low").len(), 3);
+    }
+
+    #[test]
+    fn test_interpret_response() {
+        assert_eq!(
+            interpret_response(200, r#"{"status": "Cleared", "reference": "TC-1"}"#),
+            ("cleared", Some("TC-1".to_string()))
+        );
+        assert_eq!(interpret_response(200, r#"{"status": "blocked"}"#).0, "blocked");
+        assert_eq!(interpret_response(202, "accepted").0, "pending");
+        assert_eq!(interpret_response(500, r#"{"status": "cleared"}"#).0, "error");
+    }
+
+    #[test]
+    fn test_truncate_keeps_char_boundary() {
+        let body = "é".repeat(MAX_RESPONSE_BODY);
+        assert!(truncate(&body).len() <= MAX_RESPONSE_BODY);
+    }
+}
@@ -0,0 +1,277 @@
+//! `legalscanner-api --self-test`: checks everything a scan depends on and
+//! prints one line per check, so deployments can gate on the exit code
+//! before sending traffic to a new instance.
+
+use crate::config::Config;
+use crate::db;
+use crate::scanner::fossology::FossologyClient;
+use crate::scanner::semgrep::SemgrepScanner;
+use crate::scanner::Scanner;
+use std::future::Future;
+use std::path::Path;
+use std::time::Duration;
+
+/// Upper bound for each network or container check
+const CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    Failed,
+    /// Not run because a check it depends on failed
+    Skipped,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "OK",
+            CheckStatus::Failed => "FAILED",
+            CheckStatus::Skipped => "SKIPPED",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn from_outcome(name: &'static str, outcome: Result<String, String>) -> Self {
+        match outcome {
+            Ok(detail) => Self {
+                name,
+                status: CheckStatus::Ok,
+                detail,
+            },
+            Err(detail) => Self {
+                name,
+                status: CheckStatus::Failed,
+                detail,
+            },
+        }
+    }
+
+    fn skipped(name: &'static str, reason: &str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Skipped,
+            detail: reason.to_string(),
+        }
+    }
+}
+
+/// Load the configuration from the environment and run every check
+pub async fn run() -> Vec<CheckResult> {
+    match Config::from_env() {
+        Ok(config) => {
+            let mut results = vec![CheckResult::from_outcome(
+                "configuration",
+                Ok("loaded from environment".to_string()),
+            )];
+            results.extend(run_checks(&config).await);
+            results
+        }
+        Err(e) => {
+            let mut results = vec![CheckResult::from_outcome(
+                "configuration",
+                Err(e.to_string()),
+            )];
+            for name in CHECKS {
+                results.push(CheckResult::skipped(name, "configuration is invalid"));
+            }
+            results
+        }
+    }
+}
+
+/// Names of the checks [`run_checks`] performs, in order
+const CHECKS: [&str; 6] = [
+    "database",
+    "fossology",
+    "fossology auth",
+    "semgrep",
+    "workspace",
+    "archive",
+];
+
+/// Run every check against `config`
+pub async fn run_checks(config: &Config) -> Vec<CheckResult> {
+    let mut results = vec![CheckResult::from_outcome(
+        "database",
+        check_database(&config.database_url, &db::PoolSettings::from_config(config)).await,
+    )];
+
+    let fossology = FossologyClient::new(
+        config.fossology_url.clone(),
+        config.fossology_api_token.clone(),
+    );
+    let reachable = with_timeout(fossology.health_check()).await;
+    let reachable_ok = reachable.is_ok();
+    results.push(CheckResult::from_outcome(
+        "fossology",
+        reachable.map(|_| format!("reachable at {}", config.fossology_url)),
+    ));
+    results.push(if reachable_ok {
+        CheckResult::from_outcome(
+            "fossology auth",
+            with_timeout(fossology.check_auth())
+                .await
+                .map(|_| "API token accepted".to_string()),
+        )
+    } else {
+        CheckResult::skipped("fossology auth", "Fossology is unreachable")
+    });
+
+    results.push(CheckResult::from_outcome(
+        "semgrep",
+        with_timeout(SemgrepScanner::from_config(config).health_check())
+            .await
+            .map(|_| format!("available ({:?} mode)", config.semgrep_mode)),
+    ));
+
+    let workspace = check_workspace(&config.temp_workspace_dir).await;
+    let workspace_ok = workspace.is_ok();
+    results.push(CheckResult::from_outcome("workspace", workspace));
+    results.push(if workspace_ok {
+        CheckResult::from_outcome("archive", check_archive(&config.temp_workspace_dir).await)
+    } else {
+        CheckResult::skipped("archive", "workspace is not writable")
+    });
+
+    results
+}
+
+/// Whether every check passed; skipped checks count as failures
+pub fn passed(results: &[CheckResult]) -> bool {
+    results.iter().all(|r| r.status == CheckStatus::Ok)
+}
+
+/// Plain-text table of the results
+pub fn render_table(results: &[CheckResult]) -> String {
+    let name_width = results
+        .iter()
+        .map(|r| r.name.len())
+        .chain(["CHECK".len()])
+        .max()
+        .unwrap_or_default();
+    let status_width = "SKIPPED".len();
+
+    let mut table = format!(
+        "{:<name_width$}  {:<status_width$}  DETAIL\n",
+        "CHECK", "STATUS"
+    );
+    for result in results {
+        table.push_str(&format!(
+            "{:<name_width$}  {:<status_width$}  {}\n",
+            result.name,
+            result.status.as_str(),
+            result.detail
+        ));
+    }
+    table
+}
+
+async fn with_timeout<E: std::fmt::Display>(
+    check: impl Future<Output = Result<(), E>>,
+) -> Result<(), String> {
+    match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err(format!("timed out after {:?}", CHECK_TIMEOUT)),
+    }
+}
+
+async fn check_database(
+    database_url: &str,
+    settings: &db::PoolSettings,
+) -> Result<String, String> {
+    let pool = db::create_pool(database_url, settings)
+        .await
+        .map_err(|e| format!("cannot open {}: {}", database_url, e))?;
+    db::run_migrations(&pool)
+        .await
+        .map_err(|e| format!("migrations failed: {}", e))?;
+    pool.close().await;
+    Ok("connected, migrations applied".to_string())
+}
+
+async fn check_workspace(dir: &Path) -> Result<String, String> {
+    let probe = dir.join(format!(".self-test-{}", uuid::Uuid::new_v4()));
+    let write = async {
+        tokio::fs::create_dir_all(dir).await?;
+        tokio::fs::write(&probe, b"self-test").await?;
+        tokio::fs::remove_file(&probe).await
+    };
+    write
+        .await
+        .map_err(|e| format!("cannot write to {}: {}", dir.display(), e))?;
+    Ok(format!("{} is writable", dir.display()))
+}
+
+/// Archive a small directory the way Fossology uploads are packed
+async fn check_archive(workspace_dir: &Path) -> Result<String, String> {
+    let dir = workspace_dir.join(format!(".self-test-{}", uuid::Uuid::new_v4()));
+    let outcome = async {
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| e.to_string())?;
+        tokio::fs::write(dir.join("LICENSE"), b"MIT License\n")
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let archive = FossologyClient::create_archive(&dir)
+            .await
+            .map_err(|e| e.to_string())?;
+        let size = tokio::fs::metadata(&archive).await.map(|m| m.len());
+        tokio::fs::remove_file(&archive).await.ok();
+
+        match size {
+            Ok(size) if size > 0 => Ok(format!("tar.gz created ({} bytes)", size)),
+            _ => Err("tar produced no archive".to_string()),
+        }
+    }
+    .await;
+
+    tokio::fs::remove_dir_all(&dir).await.ok();
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_workspace_and_archive_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("workspaces");
+
+        assert!(check_workspace(&workspace).await.is_ok());
+        assert!(check_archive(&workspace).await.is_ok());
+        // Probes are cleaned up
+        assert_eq!(std::fs::read_dir(&workspace).unwrap().count(), 0);
+
+        let file = dir.path().join("file");
+        std::fs::write(&file, b"").unwrap();
+        assert!(check_workspace(&file.join("workspaces")).await.is_err());
+    }
+
+    #[test]
+    fn test_render_table_and_verdict() {
+        let mut results = vec![
+            CheckResult::from_outcome("database", Ok("connected".to_string())),
+            CheckResult::from_outcome("fossology auth", Err("401".to_string())),
+        ];
+        let table = render_table(&results);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "CHECK           STATUS   DETAIL");
+        assert_eq!(lines[1], "database        OK       connected");
+        assert_eq!(lines[2], "fossology auth  FAILED   401");
+        assert!(!passed(&results));
+
+        results.pop();
+        assert!(passed(&results));
+    }
+}
@@ -0,0 +1,456 @@
+use crate::db::models::scan::ScanSummary;
+use crate::db::models::scan_result::{ScanLicenseCount, ScanSeverityCount};
+use crate::db::models::{Scan, ScanResult as StoredResult};
+use crate::scanner::ScanResult;
+use crate::storage::traits::{
+    count_ecc, count_licenses, filter_results, summarize_licenses, LicenseFiles, ResultQuery,
+    ResultStore, StorageError,
+};
+use async_trait::async_trait;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// `format` of an archive of findings
+const ARCHIVE_FORMAT: &str = "legalscanner-results";
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Object storage for the archived findings of old scans, see
+/// [`crate::archival`]. Each archive is one compressed JSON object.
+#[async_trait]
+pub trait ArchiveStorage: Send + Sync {
+    /// Returns the name of this archive backend
+    fn name(&self) -> &str;
+
+    /// Stores `data` under `key`, replacing what was stored there
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), StorageError>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+
+    /// Removes the object, if it exists
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+}
+
+/// Key of the archive of a scan's findings
+pub fn archive_key(scan_id: &str) -> String {
+    format!("{}.json.gz", scan_id)
+}
+
+#[derive(Serialize, Deserialize)]
+struct ArchivedResults {
+    format: String,
+    version: u32,
+    scan_id: String,
+    results: Vec<StoredResult>,
+}
+
+/// Gzip-compressed JSON of the findings of a scan
+pub fn encode(scan_id: &str, results: Vec<StoredResult>) -> Result<Vec<u8>, StorageError> {
+    let archive = ArchivedResults {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        scan_id: scan_id.to_string(),
+        results,
+    };
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut encoder, &archive)
+        .map_err(|e| StorageError::Failed(format!("Failed to encode archive: {}", e)))?;
+    encoder
+        .finish()
+        .map_err(|e| StorageError::Failed(format!("Failed to compress archive: {}", e)))
+}
+
+/// The findings in an archive written by [`encode`] for the scan
+pub fn decode(scan_id: &str, data: &[u8]) -> Result<Vec<StoredResult>, StorageError> {
+    let mut json = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut json).map_err(|e| {
+        StorageError::Failed(format!("Archive of scan {} is corrupt: {}", scan_id, e))
+    })?;
+    let archive: ArchivedResults = serde_json::from_slice(&json).map_err(|e| {
+        StorageError::Failed(format!("Archive of scan {} is corrupt: {}", scan_id, e))
+    })?;
+    if archive.format != ARCHIVE_FORMAT || archive.version != ARCHIVE_VERSION {
+        return Err(StorageError::Failed(format!(
+            "Archive of scan {} has unsupported format {} version {}",
+            scan_id, archive.format, archive.version
+        )));
+    }
+    if archive.scan_id != scan_id {
+        return Err(StorageError::Failed(format!(
+            "Archive of scan {} holds the findings of scan {}",
+            scan_id, archive.scan_id
+        )));
+    }
+    Ok(archive.results)
+}
+
+/// Archives as files in a directory
+pub struct DiskArchiveStorage {
+    dir: PathBuf,
+}
+
+impl DiskArchiveStorage {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Keys are file names; scan IDs of imported scans come from elsewhere
+    fn path_for(&self, key: &str) -> Result<PathBuf, StorageError> {
+        let valid = !key.is_empty()
+            && !key.starts_with('.')
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+        if !valid {
+            return Err(StorageError::Failed(format!(
+                "Invalid archive key '{}'",
+                key
+            )));
+        }
+        Ok(self.dir.join(key))
+    }
+}
+
+#[async_trait]
+impl ArchiveStorage for DiskArchiveStorage {
+    fn name(&self) -> &str {
+        "disk"
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), StorageError> {
+        let path = self.path_for(key)?;
+        let unavailable = |e: std::io::Error| {
+            StorageError::Unavailable(format!("Failed to write {}: {}", path.display(), e))
+        };
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(unavailable)?;
+
+        // Written next to the archive and renamed, so a reader never sees
+        // half of it
+        let part = self.dir.join(format!(".{}.part", key));
+        let write = |part: &Path| -> std::io::Result<()> {
+            let mut file = std::fs::File::create(part)?;
+            file.write_all(&data)?;
+            file.sync_all()
+        };
+        if let Err(e) = write(&part) {
+            let _ = tokio::fs::remove_file(&part).await;
+            return Err(unavailable(e));
+        }
+        tokio::fs::rename(&part, &path).await.map_err(unavailable)
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let path = self.path_for(key)?;
+        tokio::fs::read(&path).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                StorageError::NotFound(format!("Archive {} is missing", path.display()))
+            }
+            _ => StorageError::Unavailable(format!("Failed to read {}: {}", path.display(), e)),
+        })
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(StorageError::Unavailable(
+                format!("Failed to delete {}: {}", path.display(), e),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Reads the findings of archived scans back from the archive and passes
+/// everything else to the store holding the findings of the other scans
+pub struct ArchivingResultStore {
+    inner: Arc<dyn ResultStore>,
+    archive: Option<Arc<dyn ArchiveStorage>>,
+    pool: SqlitePool,
+}
+
+impl ArchivingResultStore {
+    /// Without `archive`, archived findings cannot be read, e.g. once the
+    /// archive was unconfigured
+    pub fn new(
+        inner: Arc<dyn ResultStore>,
+        archive: Option<Arc<dyn ArchiveStorage>>,
+        pool: SqlitePool,
+    ) -> Self {
+        Self {
+            inner,
+            archive,
+            pool,
+        }
+    }
+
+    fn archive(&self, scan_id: &str) -> Result<&Arc<dyn ArchiveStorage>, StorageError> {
+        self.archive.as_ref().ok_or_else(|| {
+            StorageError::Unavailable(format!(
+                "Findings of scan {} are archived, but RESULT_ARCHIVE is not configured",
+                scan_id
+            ))
+        })
+    }
+
+    /// The scans among `scan_ids` whose findings are archived, with their
+    /// archive keys, and the others
+    async fn split_archived(
+        &self,
+        scan_ids: &[String],
+    ) -> Result<(Vec<(String, String)>, Vec<String>), StorageError> {
+        let archived: Vec<(String, String)> = Scan::archived_results(&self.pool)
+            .await?
+            .into_iter()
+            .filter(|(scan_id, _)| scan_ids.contains(scan_id))
+            .collect();
+        let live = scan_ids
+            .iter()
+            .filter(|id| !archived.iter().any(|(scan_id, _)| scan_id == *id))
+            .cloned()
+            .collect();
+        Ok((archived, live))
+    }
+
+    async fn load_archived(
+        &self,
+        scan_id: &str,
+        key: &str,
+    ) -> Result<Vec<StoredResult>, StorageError> {
+        let data = self.archive(scan_id)?.get(key).await?;
+        decode(scan_id, &data)
+    }
+}
+
+#[async_trait]
+impl ResultStore for ArchivingResultStore {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn store_results(
+        &self,
+        scan_id: &str,
+        results: Vec<ScanResult>,
+    ) -> Result<(), StorageError> {
+        self.inner.store_results(scan_id, results).await
+    }
+
+    async fn restore_results(
+        &self,
+        scan_id: &str,
+        results: Vec<StoredResult>,
+    ) -> Result<Vec<i64>, StorageError> {
+        self.inner.restore_results(scan_id, results).await
+    }
+
+    async fn load_results(&self, scan_id: &str) -> Result<Vec<StoredResult>, StorageError> {
+        match Scan::results_archive_key(&self.pool, scan_id).await? {
+            Some(key) => self.load_archived(scan_id, &key).await,
+            None => self.inner.load_results(scan_id).await,
+        }
+    }
+
+    async fn query_results(
+        &self,
+        scan_id: &str,
+        query: &ResultQuery,
+    ) -> Result<Vec<StoredResult>, StorageError> {
+        match Scan::results_archive_key(&self.pool, scan_id).await? {
+            Some(key) => Ok(filter_results(
+                scan_id,
+                self.load_archived(scan_id, &key).await?,
+                query,
+            )),
+            None => self.inner.query_results(scan_id, query).await,
+        }
+    }
+
+    async fn count_results(&self, scan_id: &str) -> Result<u64, StorageError> {
+        match Scan::results_archive_key(&self.pool, scan_id).await? {
+            Some(key) => Ok(self.load_archived(scan_id, &key).await?.len() as u64),
+            None => self.inner.count_results(scan_id).await,
+        }
+    }
+
+    async fn summarize(&self, scan_id: &str) -> Result<ScanSummary, StorageError> {
+        // Archived or purged findings leave the summary taken before
+        match Scan::kept_summary(&self.pool, scan_id).await? {
+            Some(summary) => Ok(summary),
+            None => self.inner.summarize(scan_id).await,
+        }
+    }
+
+    async fn license_summary(
+        &self,
+        scan_id: &str,
+        example_limit: usize,
+    ) -> Result<Vec<LicenseFiles>, StorageError> {
+        match Scan::results_archive_key(&self.pool, scan_id).await? {
+            Some(key) => Ok(summarize_licenses(
+                &self.load_archived(scan_id, &key).await?,
+                example_limit,
+            )),
+            None => self.inner.license_summary(scan_id, example_limit).await,
+        }
+    }
+
+    async fn license_counts(
+        &self,
+        scan_ids: &[String],
+    ) -> Result<Vec<ScanLicenseCount>, StorageError> {
+        let (archived, live) = self.split_archived(scan_ids).await?;
+        let mut counts = self.inner.license_counts(&live).await?;
+        for (scan_id, key) in archived {
+            counts.extend(count_licenses(
+                &scan_id,
+                &self.load_archived(&scan_id, &key).await?,
+            ));
+        }
+        Ok(counts)
+    }
+
+    async fn ecc_counts(
+        &self,
+        scan_ids: &[String],
+    ) -> Result<Vec<ScanSeverityCount>, StorageError> {
+        let (archived, live) = self.split_archived(scan_ids).await?;
+        let mut counts = self.inner.ecc_counts(&live).await?;
+        for (scan_id, key) in archived {
+            counts.extend(count_ecc(
+                &scan_id,
+                &self.load_archived(&scan_id, &key).await?,
+            ));
+        }
+        Ok(counts)
+    }
+
+    async fn existing_ids(&self, scan_id: &str, ids: &[i64]) -> Result<Vec<i64>, StorageError> {
+        match Scan::results_archive_key(&self.pool, scan_id).await? {
+            Some(key) => Ok(self
+                .load_archived(scan_id, &key)
+                .await?
+                .into_iter()
+                .map(|r| r.id)
+                .filter(|id| ids.contains(id))
+                .collect()),
+            None => self.inner.existing_ids(scan_id, ids).await,
+        }
+    }
+
+    async fn set_review_status(
+        &self,
+        scan_id: &str,
+        ids: &[i64],
+        status: &str,
+    ) -> Result<u64, StorageError> {
+        if Scan::results_archive_key(&self.pool, scan_id).await?.is_some() {
+            return Err(StorageError::Failed(format!(
+                "Findings of scan {} are archived and can no longer be reviewed",
+                scan_id
+            )));
+        }
+        self.inner.set_review_status(scan_id, ids, status).await
+    }
+
+    async fn discard_archived(&self, scan_id: &str) -> Result<(), StorageError> {
+        self.inner.discard_archived(scan_id).await
+    }
+
+    async fn delete_results(&self, scan_id: &str) -> Result<(), StorageError> {
+        if let Some(key) = Scan::results_archive_key(&self.pool, scan_id).await? {
+            self.archive(scan_id)?.delete(&key).await?;
+            Scan::clear_results_archive(&self.pool, scan_id).await?;
+        }
+        self.inner.delete_results(scan_id).await
+    }
+
+    async fn delete_results_containing(&self, text: &str) -> Result<u64, StorageError> {
+        let mut deleted = self.inner.delete_results_containing(text).await?;
+
+        let contains = |value: &Option<String>| {
+            value
+                .as_deref()
+                .is_some_and(|v| v.to_lowercase().contains(text))
+        };
+        for (scan_id, key) in Scan::archived_results(&self.pool).await? {
+            let results = self.load_archived(&scan_id, &key).await?;
+            let (erased, kept): (Vec<StoredResult>, Vec<StoredResult>) =
+                results.into_iter().partition(|r| {
+                    contains(&r.copyright_statement)
+                        || contains(&r.copyright_holders)
+                        || contains(&r.raw_data)
+                });
+            if erased.is_empty() {
+                continue;
+            }
+            self.archive(&scan_id)?
+                .put(&key, encode(&scan_id, kept)?)
+                .await?;
+            let ids: Vec<i64> = erased.iter().map(|r| r.id).collect();
+            crate::db::models::ScanResultComment::delete_for_results(&self.pool, &scan_id, &ids)
+                .await?;
+            deleted += erased.len() as u64;
+        }
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: i64) -> StoredResult {
+        StoredResult {
+            id,
+            license_name: Some("MIT".to_string()),
+            license_spdx_id: Some("MIT".to_string()),
+            confidence: Some(0.87),
+            review_status: "approved".to_string(),
+            reviewed_at: Some("2025-01-02 03:04:05".to_string()),
+            ..StoredResult::for_test("license", "src/lib.rs")
+        }
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let results = vec![result(1), result(2)];
+        let checksum = crate::integrity::checksum(&results);
+
+        let data = encode("scan-1", results).unwrap();
+        let decoded = decode("scan-1", &data).unwrap();
+        assert_eq!(crate::integrity::checksum(&decoded), checksum);
+        assert_eq!(decoded[1].review_status, "approved");
+
+        assert!(decode("scan-2", &data).is_err());
+        assert!(decode("scan-1", b"not gzip").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_disk_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = DiskArchiveStorage::new(dir.path().join("archive"));
+
+        storage
+            .put("scan-1.json.gz", b"first".to_vec())
+            .await
+            .unwrap();
+        storage
+            .put("scan-1.json.gz", b"second".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(storage.get("scan-1.json.gz").await.unwrap(), b"second");
+
+        storage.delete("scan-1.json.gz").await.unwrap();
+        storage.delete("scan-1.json.gz").await.unwrap();
+        assert!(storage.get("scan-1.json.gz").await.is_err());
+
+        for key in ["../escape.json.gz", "a/b.json.gz", ".hidden", ""] {
+            assert!(storage.put(key, Vec::new()).await.is_err(), "{}", key);
+        }
+    }
+}
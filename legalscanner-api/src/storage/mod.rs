@@ -0,0 +1,120 @@
+pub mod archive;
+pub mod parquet;
+pub mod postgres;
+pub mod s3;
+pub mod sqlite;
+pub mod traits;
+
+pub use archive::{ArchiveStorage, ArchivingResultStore, DiskArchiveStorage};
+pub use parquet::ParquetResultStore;
+pub use postgres::PostgresResultStore;
+pub use s3::S3ArchiveStorage;
+pub use sqlite::SqliteResultStore;
+pub use traits::{LicenseFiles, ResultQuery, ResultStore, StorageError};
+
+use crate::config::Config;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+/// The archive configured with `RESULT_ARCHIVE`, if any
+pub fn archive_from_config(config: &Config) -> Result<Option<Arc<dyn ArchiveStorage>>, String> {
+    match config.result_archive.as_str() {
+        "none" => Ok(None),
+        "disk" => Ok(Some(Arc::new(DiskArchiveStorage::new(
+            config.result_archive_dir.clone(),
+        )))),
+        "s3" => Ok(Some(s3_storage(
+            "RESULT_ARCHIVE=s3",
+            "RESULT_ARCHIVE_S3",
+            &config.result_archive_s3_bucket,
+            &config.result_archive_s3_region,
+            &config.result_archive_s3_endpoint,
+            &config.result_archive_s3_prefix,
+            (
+                &config.result_archive_s3_access_key_id,
+                &config.result_archive_s3_secret_access_key,
+            ),
+        )?)),
+        other => Err(format!("Unsupported RESULT_ARCHIVE backend: {}", other)),
+    }
+}
+
+/// The result store configured with `RESULT_STORE`, reading the findings of
+/// archived scans from `archive`
+pub fn result_store_from_config(
+    config: &Config,
+    pool: &SqlitePool,
+    archive: Option<Arc<dyn ArchiveStorage>>,
+) -> Result<Arc<dyn ResultStore>, String> {
+    let store: Arc<dyn ResultStore> = match config.result_store.as_str() {
+        "sqlite" => Arc::new(
+            SqliteResultStore::new(pool.clone()).with_partitioning(config.result_partitioning),
+        ),
+        "postgres" => {
+            let url = config
+                .result_store_postgres_url
+                .as_deref()
+                .ok_or("RESULT_STORE=postgres requires RESULT_STORE_POSTGRES_URL")?;
+            Arc::new(
+                PostgresResultStore::connect_lazy(
+                    url,
+                    config.database_max_connections,
+                    pool.clone(),
+                )
+                .map_err(|e| e.to_string())?,
+            )
+        }
+        "parquet" => {
+            let objects: Arc<dyn ArchiveStorage> = match config.result_store_s3_bucket {
+                Some(_) => s3_storage(
+                    "RESULT_STORE_S3_BUCKET",
+                    "RESULT_STORE_S3",
+                    &config.result_store_s3_bucket,
+                    &config.result_store_s3_region,
+                    &config.result_store_s3_endpoint,
+                    &config.result_store_s3_prefix,
+                    (
+                        &config.result_store_s3_access_key_id,
+                        &config.result_store_s3_secret_access_key,
+                    ),
+                )?,
+                None => Arc::new(DiskArchiveStorage::new(config.result_store_dir.clone())),
+            };
+            Arc::new(ParquetResultStore::new(objects, pool.clone()))
+        }
+        other => return Err(format!("Unsupported RESULT_STORE backend: {}", other)),
+    };
+    Ok(Arc::new(ArchivingResultStore::new(
+        store,
+        archive,
+        pool.clone(),
+    )))
+}
+
+/// A bucket configured with the `{variables}_*` settings, which `setting`
+/// requires
+fn s3_storage(
+    setting: &str,
+    variables: &str,
+    bucket: &Option<String>,
+    region: &str,
+    endpoint: &Option<String>,
+    prefix: &str,
+    (access_key_id, secret_access_key): (&Option<String>, &Option<String>),
+) -> Result<Arc<dyn ArchiveStorage>, String> {
+    let required = |value: &Option<String>, name: &str| {
+        value
+            .clone()
+            .ok_or_else(|| format!("{} requires {}_{}", setting, variables, name))
+    };
+    let storage = S3ArchiveStorage::new(
+        required(bucket, "BUCKET")?,
+        region.to_string(),
+        endpoint.clone(),
+        prefix.to_string(),
+        required(access_key_id, "ACCESS_KEY_ID")?,
+        required(secret_access_key, "SECRET_ACCESS_KEY")?,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(Arc::new(storage))
+}
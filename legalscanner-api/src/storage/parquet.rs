@@ -0,0 +1,505 @@
+use crate::db::models::scan_result::NewScanResult;
+use crate::db::models::{Scan, ScanResult as StoredResult, ScanResultComment};
+use crate::scanner::ScanResult;
+use crate::storage::archive::ArchiveStorage;
+use crate::storage::traits::{ResultStore, StorageError};
+use arrow::array::{Array, ArrayRef, Float32Array, Int32Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+const ROW_GROUP_SIZE: usize = 65_536;
+
+/// Key of the Parquet object holding the findings of a scan
+pub fn object_key(scan_id: &str) -> String {
+    format!("{}.parquet", scan_id)
+}
+
+/// Stores the findings of each scan as one Snappy-compressed Parquet object,
+/// on disk or in an S3 bucket, for deployments whose findings outgrow the
+/// database. The objects can be queried in place by columnar engines.
+///
+/// Objects are written whole: reviews and data erasure rewrite the objects
+/// they change. Scan metadata and review comments stay in SQLite, which
+/// also allocates the finding IDs so they stay unique across stores.
+pub struct ParquetResultStore {
+    objects: Arc<dyn ArchiveStorage>,
+    pool: SqlitePool,
+    // Serializes read-modify-write of objects within this process
+    writes: Mutex<()>,
+}
+
+impl ParquetResultStore {
+    pub fn new(objects: Arc<dyn ArchiveStorage>, pool: SqlitePool) -> Self {
+        Self {
+            objects,
+            pool,
+            writes: Mutex::new(()),
+        }
+    }
+
+    /// The stored findings of the scan, none if it has no object
+    async fn read(&self, scan_id: &str) -> Result<Vec<StoredResult>, StorageError> {
+        match self.objects.get(&object_key(scan_id)).await {
+            Ok(data) => decode(scan_id, data),
+            Err(StorageError::NotFound(_)) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn write(
+        &self,
+        scan_id: &str,
+        mut results: Vec<StoredResult>,
+    ) -> Result<(), StorageError> {
+        results.sort_by(|a, b| {
+            (&a.file_path, &a.result_type, a.id).cmp(&(&b.file_path, &b.result_type, b.id))
+        });
+        self.objects
+            .put(&object_key(scan_id), encode(&results)?)
+            .await
+    }
+
+    /// Adds `rows` to the findings of the scan. Returns their IDs.
+    async fn append(
+        &self,
+        scan_id: &str,
+        rows: Vec<NewScanResult>,
+    ) -> Result<Vec<i64>, StorageError> {
+        let first_id = {
+            let mut conn = self.pool.acquire().await?;
+            StoredResult::allocate_ids(&mut conn, rows.len()).await?
+        };
+        let ids: Vec<i64> = (first_id..first_id + rows.len() as i64).collect();
+
+        let _guard = self.writes.lock().await;
+        let mut results = self.read(scan_id).await?;
+        results.extend(
+            rows.into_iter()
+                .zip(&ids)
+                .map(|(row, id)| row.into_stored(*id, scan_id)),
+        );
+        self.write(scan_id, results).await?;
+        Ok(ids)
+    }
+}
+
+#[async_trait]
+impl ResultStore for ParquetResultStore {
+    fn name(&self) -> &str {
+        "parquet"
+    }
+
+    async fn store_results(
+        &self,
+        scan_id: &str,
+        results: Vec<ScanResult>,
+    ) -> Result<(), StorageError> {
+        self.append(scan_id, NewScanResult::rows(results)).await?;
+        Ok(())
+    }
+
+    async fn restore_results(
+        &self,
+        scan_id: &str,
+        results: Vec<StoredResult>,
+    ) -> Result<Vec<i64>, StorageError> {
+        let rows = results
+            .iter()
+            .map(NewScanResult::restored)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(StorageError::Failed)?;
+        self.append(scan_id, rows).await
+    }
+
+    async fn load_results(&self, scan_id: &str) -> Result<Vec<StoredResult>, StorageError> {
+        self.read(scan_id).await
+    }
+
+    async fn set_review_status(
+        &self,
+        scan_id: &str,
+        ids: &[i64],
+        status: &str,
+    ) -> Result<u64, StorageError> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let _guard = self.writes.lock().await;
+        let mut results = self.read(scan_id).await?;
+        let reviewed_at = (status != "pending")
+            .then(|| chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        let mut updated = 0;
+        for result in results
+            .iter_mut()
+            .filter(|r| ids.contains(&r.id) && r.review_status != status)
+        {
+            result.review_status = status.to_string();
+            result.reviewed_at = reviewed_at.clone();
+            updated += 1;
+        }
+        if updated > 0 {
+            self.write(scan_id, results).await?;
+        }
+        Ok(updated)
+    }
+
+    async fn discard_archived(&self, scan_id: &str) -> Result<(), StorageError> {
+        self.objects.delete(&object_key(scan_id)).await
+    }
+
+    async fn delete_results(&self, scan_id: &str) -> Result<(), StorageError> {
+        self.objects.delete(&object_key(scan_id)).await?;
+        ScanResultComment::delete_for_scan(&self.pool, scan_id).await?;
+        Ok(())
+    }
+
+    /// Reads the object of every scan with findings, so it takes a while
+    /// with many scans
+    async fn delete_results_containing(&self, text: &str) -> Result<u64, StorageError> {
+        let contains = |value: &Option<String>| {
+            value
+                .as_deref()
+                .is_some_and(|v| v.to_lowercase().contains(text))
+        };
+
+        let _guard = self.writes.lock().await;
+        let mut deleted = 0;
+        for scan_id in Scan::ids_with_stored_results(&self.pool).await? {
+            let results = self.read(&scan_id).await?;
+            let (erased, kept): (Vec<StoredResult>, Vec<StoredResult>) =
+                results.into_iter().partition(|r| {
+                    contains(&r.copyright_statement)
+                        || contains(&r.copyright_holders)
+                        || contains(&r.raw_data)
+                });
+            if erased.is_empty() {
+                continue;
+            }
+            self.write(&scan_id, kept).await?;
+            let ids: Vec<i64> = erased.iter().map(|r| r.id).collect();
+            ScanResultComment::delete_for_results(&self.pool, &scan_id, &ids).await?;
+            deleted += erased.len() as u64;
+        }
+        Ok(deleted)
+    }
+}
+
+fn schema() -> Schema {
+    let text = |name: &str, nullable: bool| Field::new(name, DataType::Utf8, nullable);
+    Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        text("scan_id", false),
+        text("file_path", false),
+        text("result_type", false),
+        text("license_name", true),
+        text("license_spdx_id", true),
+        text("license_exception", true),
+        text("copyright_statement", true),
+        text("copyright_holders", true),
+        text("copyright_years", true),
+        Field::new("confidence", DataType::Float32, true),
+        text("raw_data", true),
+        text("risk_severity", true),
+        text("ecc_source", true),
+        Field::new("ecc_line_number", DataType::Int32, true),
+        text("ecc_check_id", true),
+        text("license_category", true),
+        text("package_name", true),
+        text("package_version", true),
+        text("package_ecosystem", true),
+        text("package_purl", true),
+        text("merge_provenance", true),
+        text("license_agreement", true),
+        text("review_status", false),
+        text("reviewed_at", true),
+    ])
+}
+
+/// Parquet file of findings, in the order given
+fn encode(results: &[StoredResult]) -> Result<Vec<u8>, StorageError> {
+    let failed = |e: &dyn std::fmt::Display| {
+        StorageError::Failed(format!("Failed to encode findings as Parquet: {}", e))
+    };
+    let schema = Arc::new(schema());
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_max_row_group_size(ROW_GROUP_SIZE)
+        .build();
+
+    let mut buffer = Vec::new();
+    let mut writer =
+        ArrowWriter::try_new(&mut buffer, schema.clone(), Some(props)).map_err(|e| failed(&e))?;
+    for chunk in results.chunks(ROW_GROUP_SIZE) {
+        let batch = record_batch(schema.clone(), chunk).map_err(|e| failed(&e))?;
+        writer.write(&batch).map_err(|e| failed(&e))?;
+    }
+    writer.close().map_err(|e| failed(&e))?;
+    Ok(buffer)
+}
+
+fn record_batch(
+    schema: Arc<Schema>,
+    results: &[StoredResult],
+) -> Result<RecordBatch, arrow::error::ArrowError> {
+    fn strings<'a>(
+        results: &'a [StoredResult],
+        f: impl Fn(&'a StoredResult) -> Option<&'a str>,
+    ) -> ArrayRef {
+        Arc::new(StringArray::from(results.iter().map(f).collect::<Vec<_>>()))
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Int64Array::from(
+            results.iter().map(|r| r.id).collect::<Vec<_>>(),
+        )),
+        strings(results, |r| Some(r.scan_id.as_str())),
+        strings(results, |r| Some(r.file_path.as_str())),
+        strings(results, |r| Some(r.result_type.as_str())),
+        strings(results, |r| r.license_name.as_deref()),
+        strings(results, |r| r.license_spdx_id.as_deref()),
+        strings(results, |r| r.license_exception.as_deref()),
+        strings(results, |r| r.copyright_statement.as_deref()),
+        strings(results, |r| r.copyright_holders.as_deref()),
+        strings(results, |r| r.copyright_years.as_deref()),
+        Arc::new(Float32Array::from(
+            results.iter().map(|r| r.confidence).collect::<Vec<_>>(),
+        )),
+        strings(results, |r| r.raw_data.as_deref()),
+        strings(results, |r| r.risk_severity.as_deref()),
+        strings(results, |r| r.ecc_source.as_deref()),
+        Arc::new(Int32Array::from(
+            results
+                .iter()
+                .map(|r| r.ecc_line_number)
+                .collect::<Vec<_>>(),
+        )),
+        strings(results, |r| r.ecc_check_id.as_deref()),
+        strings(results, |r| r.license_category.as_deref()),
+        strings(results, |r| r.package_name.as_deref()),
+        strings(results, |r| r.package_version.as_deref()),
+        strings(results, |r| r.package_ecosystem.as_deref()),
+        strings(results, |r| r.package_purl.as_deref()),
+        strings(results, |r| r.merge_provenance.as_deref()),
+        strings(results, |r| r.license_agreement.as_deref()),
+        strings(results, |r| Some(r.review_status.as_str())),
+        strings(results, |r| r.reviewed_at.as_deref()),
+    ];
+    RecordBatch::try_new(schema, columns)
+}
+
+/// The findings in a Parquet object written by [`encode`] for the scan
+fn decode(scan_id: &str, data: Vec<u8>) -> Result<Vec<StoredResult>, StorageError> {
+    let corrupt = |e: &dyn std::fmt::Display| {
+        StorageError::Failed(format!("Findings of scan {} are corrupt: {}", scan_id, e))
+    };
+    let reader = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(data))
+        .and_then(|builder| builder.with_batch_size(ROW_GROUP_SIZE).build())
+        .map_err(|e| corrupt(&e))?;
+
+    let mut results = Vec::new();
+    for batch in reader {
+        let batch = batch.map_err(|e| corrupt(&e))?;
+        let text = |name: &str| column::<StringArray>(&batch, name).map_err(|e| corrupt(&e));
+        let ids = column::<Int64Array>(&batch, "id").map_err(|e| corrupt(&e))?;
+        let scan_ids = text("scan_id")?;
+        let file_paths = text("file_path")?;
+        let result_types = text("result_type")?;
+        let license_names = text("license_name")?;
+        let license_spdx_ids = text("license_spdx_id")?;
+        let license_exceptions = text("license_exception")?;
+        let copyright_statements = text("copyright_statement")?;
+        let copyright_holders = text("copyright_holders")?;
+        let copyright_years = text("copyright_years")?;
+        let confidences = column::<Float32Array>(&batch, "confidence").map_err(|e| corrupt(&e))?;
+        let raw_data = text("raw_data")?;
+        let risk_severities = text("risk_severity")?;
+        let ecc_sources = text("ecc_source")?;
+        let ecc_line_numbers =
+            column::<Int32Array>(&batch, "ecc_line_number").map_err(|e| corrupt(&e))?;
+        let ecc_check_ids = text("ecc_check_id")?;
+        let license_categories = text("license_category")?;
+        let package_names = text("package_name")?;
+        let package_versions = text("package_version")?;
+        let package_ecosystems = text("package_ecosystem")?;
+        let package_purls = text("package_purl")?;
+        let merge_provenances = text("merge_provenance")?;
+        let license_agreements = text("license_agreement")?;
+        let review_statuses = text("review_status")?;
+        let reviewed_ats = text("reviewed_at")?;
+
+        for row in 0..batch.num_rows() {
+            if scan_ids.value(row) != scan_id {
+                return Err(corrupt(&format!(
+                    "they hold a finding of scan {}",
+                    scan_ids.value(row)
+                )));
+            }
+            results.push(StoredResult {
+                id: ids.value(row),
+                scan_id: scan_id.to_string(),
+                file_path: file_paths.value(row).to_string(),
+                result_type: result_types.value(row).to_string(),
+                license_name: optional(license_names, row),
+                license_spdx_id: optional(license_spdx_ids, row),
+                license_exception: optional(license_exceptions, row),
+                copyright_statement: optional(copyright_statements, row),
+                copyright_holders: optional(copyright_holders, row),
+                copyright_years: optional(copyright_years, row),
+                confidence: (!confidences.is_null(row)).then(|| confidences.value(row)),
+                raw_data: optional(raw_data, row),
+                risk_severity: optional(risk_severities, row),
+                ecc_source: optional(ecc_sources, row),
+                ecc_line_number: (!ecc_line_numbers.is_null(row))
+                    .then(|| ecc_line_numbers.value(row)),
+                ecc_check_id: optional(ecc_check_ids, row),
+                license_category: optional(license_categories, row),
+                package_name: optional(package_names, row),
+                package_version: optional(package_versions, row),
+                package_ecosystem: optional(package_ecosystems, row),
+                package_purl: optional(package_purls, row),
+                merge_provenance: optional(merge_provenances, row),
+                license_agreement: optional(license_agreements, row),
+                review_status: review_statuses.value(row).to_string(),
+                reviewed_at: optional(reviewed_ats, row),
+                waiver_id: None,
+                submodule: None,
+            });
+        }
+    }
+    Ok(results)
+}
+
+fn column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> Result<&'a T, String> {
+    batch
+        .column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<T>())
+        .ok_or_else(|| format!("column {} is missing or has the wrong type", name))
+}
+
+fn optional(column: &StringArray, row: usize) -> Option<String> {
+    (!column.is_null(row)).then(|| column.value(row).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::{CopyrightFinding, LicenseFinding};
+    use crate::storage::archive::DiskArchiveStorage;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_parquet_result_store() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::run_migrations(&pool).await.unwrap();
+        let scan = Scan::create(
+            &pool,
+            "https://github.com/user/repo.git".to_string(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let store = ParquetResultStore::new(
+            Arc::new(DiskArchiveStorage::new(dir.path().to_path_buf())),
+            pool,
+        );
+
+        assert!(store.load_results(&scan.id).await.unwrap().is_empty());
+        store
+            .store_results(
+                &scan.id,
+                vec![ScanResult {
+                    file_path: "src/main.rs".to_string(),
+                    licenses: vec![LicenseFinding {
+                        name: "MIT".to_string(),
+                        spdx_id: Some("MIT".to_string()),
+                        confidence: 0.9,
+                        category: Default::default(),
+                        provenance: None,
+                        agreement: None,
+                        exception: None,
+                    }],
+                    copyrights: vec![CopyrightFinding {
+                        statement: "Copyright 2024 Jane Doe".to_string(),
+                        holders: vec!["Jane Doe".to_string()],
+                        years: vec!["2024".to_string()],
+                    }],
+                    ecc_findings: Vec::new(),
+                    malware_findings: Vec::new(),
+                    package_findings: Vec::new(),
+                    model_licenses: Vec::new(),
+                }],
+            )
+            .await
+            .unwrap();
+        assert!(dir.path().join(object_key(&scan.id)).exists());
+
+        let loaded = store.load_results(&scan.id).await.unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[1].result_type, "license");
+        assert_eq!(loaded[1].license_spdx_id.as_deref(), Some("MIT"));
+        assert_eq!(loaded[1].confidence, Some(0.9));
+        assert_eq!(loaded[1].review_status, "pending");
+        let summary = store.summarize(&scan.id).await.unwrap();
+        assert_eq!((summary.total_files, summary.unique_licenses), (1, 1));
+
+        let license_id = loaded[1].id;
+        assert_eq!(
+            store
+                .existing_ids(&scan.id, &[license_id, -1])
+                .await
+                .unwrap(),
+            vec![license_id]
+        );
+        assert_eq!(
+            store
+                .set_review_status(&scan.id, &[license_id], "approved")
+                .await
+                .unwrap(),
+            1
+        );
+        let reviewed = store.load_results(&scan.id).await.unwrap();
+        assert_eq!(reviewed[1].review_status, "approved");
+        assert!(reviewed[1].reviewed_at.is_some());
+
+        assert_eq!(
+            store.delete_results_containing("jane doe").await.unwrap(),
+            1
+        );
+        let remaining = store.load_results(&scan.id).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, license_id);
+
+        store.delete_results(&scan.id).await.unwrap();
+        assert!(store.load_results(&scan.id).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_decode_rejects_findings_of_another_scan() {
+        let result = NewScanResult {
+            file_path: "src/lib.rs".to_string(),
+            result_type: "license",
+            license_name: Some("MIT".to_string()),
+            ..Default::default()
+        }
+        .into_stored(7, "scan-1");
+        let data = encode(&[result]).unwrap();
+
+        assert_eq!(decode("scan-1", data.clone()).unwrap()[0].id, 7);
+        assert!(decode("scan-2", data).is_err());
+        assert!(decode("scan-1", b"not parquet".to_vec()).is_err());
+    }
+}
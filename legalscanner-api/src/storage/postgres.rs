@@ -0,0 +1,436 @@
+use crate::db::models::scan_result::{NewScanResult, ScanLicenseCount, ScanSeverityCount};
+use crate::db::models::{ScanResult as StoredResult, ScanResultComment};
+use crate::scanner::ScanResult;
+use crate::storage::traits::{LicenseFiles, ResultQuery, ResultStore, StorageError};
+use async_trait::async_trait;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::{Postgres, QueryBuilder, SqlitePool};
+use std::collections::BTreeMap;
+use tokio::sync::OnceCell;
+
+/// Rows per INSERT statement. Each row binds 25 parameters, which keeps a
+/// statement well under Postgres' limit of 65535.
+const INSERT_BATCH_ROWS: usize = 1000;
+
+const SCHEMA: [&str; 2] = [
+    r#"
+    CREATE TABLE IF NOT EXISTS scan_results (
+        id BIGINT PRIMARY KEY,
+        scan_id TEXT NOT NULL,
+        file_path TEXT NOT NULL,
+        result_type TEXT NOT NULL,
+        license_name TEXT,
+        license_spdx_id TEXT,
+        license_exception TEXT,
+        copyright_statement TEXT,
+        copyright_holders TEXT,
+        copyright_years TEXT,
+        confidence REAL,
+        raw_data TEXT,
+        risk_severity TEXT,
+        ecc_source TEXT,
+        ecc_line_number INTEGER,
+        ecc_check_id TEXT,
+        license_category TEXT,
+        package_name TEXT,
+        package_version TEXT,
+        package_ecosystem TEXT,
+        package_purl TEXT,
+        merge_provenance TEXT,
+        license_agreement TEXT,
+        review_status TEXT NOT NULL DEFAULT 'pending',
+        reviewed_at TEXT
+    )
+    "#,
+    "CREATE INDEX IF NOT EXISTS idx_scan_results_scan_id ON scan_results (scan_id, id)",
+];
+
+/// Stores findings in a `scan_results` table in Postgres, one row per
+/// finding with license and copyright columns inline. The table is created
+/// on first use.
+///
+/// Scan metadata and review comments stay in SQLite, which also allocates
+/// the finding IDs so they stay unique across stores.
+pub struct PostgresResultStore {
+    pg: PgPool,
+    sqlite: SqlitePool,
+    schema: OnceCell<()>,
+}
+
+impl PostgresResultStore {
+    /// Connections are opened on first use, so the API starts while
+    /// Postgres is still coming up
+    pub fn connect_lazy(
+        url: &str,
+        max_connections: u32,
+        sqlite: SqlitePool,
+    ) -> Result<Self, StorageError> {
+        let pg = PgPoolOptions::new()
+            .max_connections(max_connections)
+            .connect_lazy(url)
+            .map_err(|e| StorageError::Failed(format!("Invalid Postgres URL: {}", e)))?;
+        Ok(Self {
+            pg,
+            sqlite,
+            schema: OnceCell::new(),
+        })
+    }
+
+    async fn pool(&self) -> Result<&PgPool, StorageError> {
+        self.schema
+            .get_or_try_init(|| async {
+                for statement in SCHEMA {
+                    sqlx::query(statement)
+                        .execute(&self.pg)
+                        .await
+                        .map_err(unavailable)?;
+                }
+                Ok::<_, StorageError>(())
+            })
+            .await?;
+        Ok(&self.pg)
+    }
+
+    /// Inserts `rows` with IDs from `first_id` on, all of them or none
+    async fn insert(
+        &self,
+        scan_id: &str,
+        first_id: i64,
+        rows: &[NewScanResult],
+    ) -> Result<(), StorageError> {
+        let mut tx = self.pool().await?.begin().await.map_err(unavailable)?;
+        for (index, batch) in rows.chunks(INSERT_BATCH_ROWS).enumerate() {
+            let batch_first_id = first_id + (index * INSERT_BATCH_ROWS) as i64;
+            let mut sql = QueryBuilder::<Postgres>::new(
+                "INSERT INTO scan_results (id, scan_id, file_path, result_type, license_name, \
+                 license_spdx_id, license_exception, copyright_statement, copyright_holders, \
+                 copyright_years, confidence, raw_data, risk_severity, ecc_source, \
+                 ecc_line_number, ecc_check_id, license_category, package_name, \
+                 package_version, package_ecosystem, package_purl, merge_provenance, \
+                 license_agreement, review_status, reviewed_at) ",
+            );
+            sql.push_values(
+                batch.iter().zip(batch_first_id..),
+                |mut values, (row, id)| {
+                    values
+                        .push_bind(id)
+                        .push_bind(scan_id)
+                        .push_bind(&row.file_path)
+                        .push_bind(row.result_type)
+                        .push_bind(&row.license_name)
+                        .push_bind(&row.license_spdx_id)
+                        .push_bind(&row.license_exception)
+                        .push_bind(&row.copyright_statement)
+                        .push_bind(&row.copyright_holders)
+                        .push_bind(&row.copyright_years)
+                        .push_bind(row.confidence)
+                        .push_bind(&row.raw_data)
+                        .push_bind(&row.risk_severity)
+                        .push_bind(&row.ecc_source)
+                        .push_bind(row.ecc_line_number)
+                        .push_bind(&row.ecc_check_id)
+                        .push_bind(row.license_category)
+                        .push_bind(&row.package_name)
+                        .push_bind(&row.package_version)
+                        .push_bind(&row.package_ecosystem)
+                        .push_bind(&row.package_purl)
+                        .push_bind(&row.merge_provenance)
+                        .push_bind(row.license_agreement)
+                        .push_bind(row.review_status.as_deref().unwrap_or("pending"))
+                        .push_bind(&row.reviewed_at);
+                },
+            );
+            sql.build().execute(&mut *tx).await.map_err(unavailable)?;
+        }
+        tx.commit().await.map_err(unavailable)
+    }
+
+    async fn allocate_ids(&self, count: usize) -> Result<i64, StorageError> {
+        let mut conn = self.sqlite.acquire().await?;
+        Ok(StoredResult::allocate_ids(&mut conn, count).await?)
+    }
+}
+
+#[async_trait]
+impl ResultStore for PostgresResultStore {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    async fn store_results(
+        &self,
+        scan_id: &str,
+        results: Vec<ScanResult>,
+    ) -> Result<(), StorageError> {
+        let rows = NewScanResult::rows(results);
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let first_id = self.allocate_ids(rows.len()).await?;
+        self.insert(scan_id, first_id, &rows).await
+    }
+
+    async fn restore_results(
+        &self,
+        scan_id: &str,
+        results: Vec<StoredResult>,
+    ) -> Result<Vec<i64>, StorageError> {
+        let rows = results
+            .iter()
+            .map(NewScanResult::restored)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(StorageError::Failed)?;
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+        let first_id = self.allocate_ids(rows.len()).await?;
+        self.insert(scan_id, first_id, &rows).await?;
+        Ok((first_id..first_id + rows.len() as i64).collect())
+    }
+
+    async fn load_results(&self, scan_id: &str) -> Result<Vec<StoredResult>, StorageError> {
+        sqlx::query_as::<_, StoredResult>(
+            "SELECT * FROM scan_results WHERE scan_id = $1 ORDER BY file_path, result_type, id",
+        )
+        .bind(scan_id)
+        .fetch_all(self.pool().await?)
+        .await
+        .map_err(unavailable)
+    }
+
+    async fn count_results(&self, scan_id: &str) -> Result<u64, StorageError> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM scan_results WHERE scan_id = $1")
+            .bind(scan_id)
+            .fetch_one(self.pool().await?)
+            .await
+            .map_err(unavailable)?;
+        Ok(count as u64)
+    }
+
+    async fn query_results(
+        &self,
+        scan_id: &str,
+        query: &ResultQuery,
+    ) -> Result<Vec<StoredResult>, StorageError> {
+        let mut sql = QueryBuilder::<Postgres>::new("SELECT * FROM scan_results WHERE scan_id = ");
+        sql.push_bind(scan_id);
+
+        if let Some(result_type) = &query.result_type {
+            sql.push(" AND result_type = ").push_bind(result_type);
+        }
+        if let Some(license) = &query.license {
+            sql.push(" AND (lower(license_spdx_id) = lower(")
+                .push_bind(license)
+                .push(") OR lower(license_name) = lower(")
+                .push_bind(license)
+                .push("))");
+        }
+        if let Some(severity) = &query.severity {
+            sql.push(" AND lower(risk_severity) = lower(")
+                .push_bind(severity)
+                .push(")");
+        }
+        if let Some(prefix) = &query.file_prefix {
+            // Scanners record either repository-relative paths or absolute
+            // paths inside the scan's workspace directory
+            sql.push(" AND (starts_with(file_path, ")
+                .push_bind(prefix)
+                .push(") OR strpos(file_path, ")
+                .push_bind(format!("/{}/{}", scan_id, prefix))
+                .push(") > 0)");
+        }
+        if let Some(review_status) = &query.review_status {
+            sql.push(" AND review_status = ").push_bind(review_status);
+        }
+        if let Some(after_id) = query.after_id {
+            sql.push(" AND id > ").push_bind(after_id);
+        }
+        sql.push(" ORDER BY id");
+        if let Some(limit) = query.limit {
+            sql.push(" LIMIT ").push_bind(limit);
+        }
+
+        sql.build_query_as::<StoredResult>()
+            .fetch_all(self.pool().await?)
+            .await
+            .map_err(unavailable)
+    }
+
+    async fn license_summary(
+        &self,
+        scan_id: &str,
+        example_limit: usize,
+    ) -> Result<Vec<LicenseFiles>, StorageError> {
+        let rows: Vec<(String, Option<String>, i64, Vec<String>)> = sqlx::query_as(
+            r#"
+            WITH license_files AS (
+                SELECT DISTINCT license_name, license_spdx_id, file_path
+                FROM scan_results
+                WHERE scan_id = $1 AND result_type = 'license' AND license_name IS NOT NULL
+            ),
+            ranked AS (
+                SELECT
+                    license_name,
+                    license_spdx_id,
+                    file_path,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY license_name, license_spdx_id ORDER BY file_path
+                    ) as file_rank
+                FROM license_files
+            )
+            SELECT
+                license_name,
+                license_spdx_id,
+                COUNT(*) as file_count,
+                COALESCE(
+                    array_agg(file_path ORDER BY file_path) FILTER (WHERE file_rank <= $2),
+                    '{}'
+                ) as example_paths
+            FROM ranked
+            GROUP BY license_name, license_spdx_id
+            ORDER BY file_count DESC, license_name, license_spdx_id
+            "#,
+        )
+        .bind(scan_id)
+        .bind(example_limit as i64)
+        .fetch_all(self.pool().await?)
+        .await
+        .map_err(unavailable)?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(license_name, license_spdx_id, file_count, example_paths)| LicenseFiles {
+                    license_name,
+                    license_spdx_id,
+                    file_count,
+                    example_paths,
+                },
+            )
+            .collect())
+    }
+
+    async fn license_counts(
+        &self,
+        scan_ids: &[String],
+    ) -> Result<Vec<ScanLicenseCount>, StorageError> {
+        sqlx::query_as::<_, ScanLicenseCount>(
+            r#"
+            SELECT
+                scan_id,
+                COALESCE(license_spdx_id, license_name) as license,
+                COUNT(DISTINCT file_path) as file_count
+            FROM scan_results
+            WHERE scan_id = ANY($1)
+                AND result_type = 'license'
+                AND COALESCE(license_spdx_id, license_name) IS NOT NULL
+            GROUP BY scan_id, license
+            "#,
+        )
+        .bind(scan_ids)
+        .fetch_all(self.pool().await?)
+        .await
+        .map_err(unavailable)
+    }
+
+    async fn ecc_counts(
+        &self,
+        scan_ids: &[String],
+    ) -> Result<Vec<ScanSeverityCount>, StorageError> {
+        sqlx::query_as::<_, ScanSeverityCount>(
+            r#"
+            SELECT scan_id, risk_severity, COUNT(*) as finding_count
+            FROM scan_results
+            WHERE scan_id = ANY($1) AND result_type = 'ecc'
+            GROUP BY scan_id, risk_severity
+            "#,
+        )
+        .bind(scan_ids)
+        .fetch_all(self.pool().await?)
+        .await
+        .map_err(unavailable)
+    }
+
+    async fn existing_ids(&self, scan_id: &str, ids: &[i64]) -> Result<Vec<i64>, StorageError> {
+        sqlx::query_scalar("SELECT id FROM scan_results WHERE scan_id = $1 AND id = ANY($2)")
+            .bind(scan_id)
+            .bind(ids)
+            .fetch_all(self.pool().await?)
+            .await
+            .map_err(unavailable)
+    }
+
+    async fn set_review_status(
+        &self,
+        scan_id: &str,
+        ids: &[i64],
+        status: &str,
+    ) -> Result<u64, StorageError> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        // Same timestamp format as SQLite's datetime('now')
+        let updated = sqlx::query(
+            r#"
+            UPDATE scan_results
+            SET review_status = $1,
+                reviewed_at = CASE WHEN $1 = 'pending' THEN NULL
+                    ELSE to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') END
+            WHERE scan_id = $2 AND review_status <> $1 AND id = ANY($3)
+            "#,
+        )
+        .bind(status)
+        .bind(scan_id)
+        .bind(ids)
+        .execute(self.pool().await?)
+        .await
+        .map_err(unavailable)?;
+        Ok(updated.rows_affected())
+    }
+
+    async fn discard_archived(&self, scan_id: &str) -> Result<(), StorageError> {
+        sqlx::query("DELETE FROM scan_results WHERE scan_id = $1")
+            .bind(scan_id)
+            .execute(self.pool().await?)
+            .await
+            .map_err(unavailable)?;
+        Ok(())
+    }
+
+    async fn delete_results(&self, scan_id: &str) -> Result<(), StorageError> {
+        self.discard_archived(scan_id).await?;
+        ScanResultComment::delete_for_scan(&self.sqlite, scan_id).await?;
+        Ok(())
+    }
+
+    async fn delete_results_containing(&self, text: &str) -> Result<u64, StorageError> {
+        let erased: Vec<(String, i64)> = sqlx::query_as(
+            r#"
+            DELETE FROM scan_results
+            WHERE strpos(lower(coalesce(copyright_statement, '')), $1) > 0
+               OR strpos(lower(coalesce(copyright_holders, '')), $1) > 0
+               OR strpos(lower(coalesce(raw_data, '')), $1) > 0
+            RETURNING scan_id, id
+            "#,
+        )
+        .bind(text)
+        .fetch_all(self.pool().await?)
+        .await
+        .map_err(unavailable)?;
+
+        let mut by_scan: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+        for (scan_id, id) in &erased {
+            by_scan.entry(scan_id.clone()).or_default().push(*id);
+        }
+        for (scan_id, ids) in by_scan {
+            ScanResultComment::delete_for_results(&self.sqlite, &scan_id, &ids).await?;
+        }
+        Ok(erased.len() as u64)
+    }
+}
+
+/// Errors of the Postgres store are reported as an unavailable backend, so
+/// they are not mistaken for errors of the SQLite database
+fn unavailable(e: sqlx::Error) -> StorageError {
+    StorageError::Unavailable(format!("Postgres: {}", e))
+}
@@ -0,0 +1,662 @@
+use crate::db::models::result_partition::{self, ResultPartitioning};
+use crate::db::models::scan_result::{NewScanResult, ScanLicenseCount, ScanSeverityCount};
+use crate::db::models::scan::ScanSummary;
+use crate::db::models::{Scan, ScanResult as StoredResult};
+use crate::scanner::ScanResult;
+use crate::storage::traits::{LicenseFiles, ResultQuery, ResultStore, StorageError};
+use async_trait::async_trait;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+
+/// Stores findings in the `scan_results` table, or its monthly partitions,
+/// alongside scan metadata
+pub struct SqliteResultStore {
+    pool: SqlitePool,
+    partitioning: ResultPartitioning,
+}
+
+impl SqliteResultStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            partitioning: ResultPartitioning::default(),
+        }
+    }
+
+    /// Store the findings of new scans in partitions
+    pub fn with_partitioning(mut self, partitioning: ResultPartitioning) -> Self {
+        self.partitioning = partitioning;
+        self
+    }
+}
+
+#[async_trait]
+impl ResultStore for SqliteResultStore {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    async fn store_results(
+        &self,
+        scan_id: &str,
+        results: Vec<ScanResult>,
+    ) -> Result<(), StorageError> {
+        let rows = NewScanResult::rows(results);
+
+        // One transaction, so a scan never ends up with part of its findings
+        let mut tx = self.pool.begin().await?;
+        result_partition::assign(&mut tx, scan_id, self.partitioning).await?;
+        StoredResult::insert_batch(&mut tx, scan_id, &rows).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn restore_results(
+        &self,
+        scan_id: &str,
+        results: Vec<StoredResult>,
+    ) -> Result<Vec<i64>, StorageError> {
+        let rows = results
+            .iter()
+            .map(NewScanResult::restored)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(StorageError::Failed)?;
+
+        let mut tx = self.pool.begin().await?;
+        let table = result_partition::assign(&mut tx, scan_id, self.partitioning).await?;
+        StoredResult::insert_batch(&mut tx, scan_id, &rows).await?;
+        // IDs are allocated in row order, and the scan has no other findings
+        let ids = sqlx::query_scalar(&format!(
+            "SELECT id FROM {} WHERE scan_id = ? ORDER BY id",
+            table
+        ))
+        .bind(scan_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(ids)
+    }
+
+    async fn load_results(&self, scan_id: &str) -> Result<Vec<StoredResult>, StorageError> {
+        Ok(StoredResult::find_by_scan_id(&self.pool, scan_id).await?)
+    }
+
+    async fn query_results(
+        &self,
+        scan_id: &str,
+        query: &ResultQuery,
+    ) -> Result<Vec<StoredResult>, StorageError> {
+        let source = result_partition::source_for(&self.pool, scan_id).await?;
+        let mut sql =
+            QueryBuilder::<Sqlite>::new(format!("SELECT * FROM {} WHERE scan_id = ", source));
+        sql.push_bind(scan_id);
+
+        if let Some(result_type) = &query.result_type {
+            sql.push(" AND result_type = ").push_bind(result_type);
+        }
+        if let Some(license) = &query.license {
+            sql.push(" AND (lower(license_spdx_id) = lower(")
+                .push_bind(license)
+                .push(") OR lower(license_name) = lower(")
+                .push_bind(license)
+                .push("))");
+        }
+        if let Some(severity) = &query.severity {
+            sql.push(" AND lower(risk_severity) = lower(")
+                .push_bind(severity)
+                .push(")");
+        }
+        if let Some(prefix) = &query.file_prefix {
+            // Scanners record either repository-relative paths or absolute
+            // paths inside the scan's workspace directory
+            sql.push(" AND (substr(file_path, 1, length(")
+                .push_bind(prefix)
+                .push(")) = ")
+                .push_bind(prefix)
+                .push(" OR instr(file_path, ")
+                .push_bind(format!("/{}/{}", scan_id, prefix))
+                .push(") > 0)");
+        }
+        if let Some(review_status) = &query.review_status {
+            sql.push(" AND review_status = ").push_bind(review_status);
+        }
+        if let Some(after_id) = query.after_id {
+            sql.push(" AND id > ").push_bind(after_id);
+        }
+        sql.push(" ORDER BY id");
+        if let Some(limit) = query.limit {
+            sql.push(" LIMIT ").push_bind(limit);
+        }
+
+        Ok(sql
+            .build_query_as::<StoredResult>()
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    async fn count_results(&self, scan_id: &str) -> Result<u64, StorageError> {
+        Ok(StoredResult::count_by_scan_id(&self.pool, scan_id).await? as u64)
+    }
+
+    async fn summarize(&self, scan_id: &str) -> Result<ScanSummary, StorageError> {
+        Ok(Scan::get_summary(&self.pool, scan_id).await?)
+    }
+
+    async fn license_summary(
+        &self,
+        scan_id: &str,
+        example_limit: usize,
+    ) -> Result<Vec<LicenseFiles>, StorageError> {
+        let rows = StoredResult::license_summary(&self.pool, scan_id, example_limit as i64).await?;
+        rows.into_iter()
+            .map(|row| {
+                let mut example_paths: Vec<String> = serde_json::from_str(&row.example_paths)
+                    .map_err(|e| StorageError::Failed(format!("Invalid example paths: {}", e)))?;
+                example_paths.sort();
+                Ok(LicenseFiles {
+                    license_name: row.license_name,
+                    license_spdx_id: row.license_spdx_id,
+                    file_count: row.file_count,
+                    example_paths,
+                })
+            })
+            .collect()
+    }
+
+    async fn license_counts(
+        &self,
+        scan_ids: &[String],
+    ) -> Result<Vec<ScanLicenseCount>, StorageError> {
+        Ok(StoredResult::license_counts(&self.pool, scan_ids).await?)
+    }
+
+    async fn ecc_counts(
+        &self,
+        scan_ids: &[String],
+    ) -> Result<Vec<ScanSeverityCount>, StorageError> {
+        Ok(StoredResult::ecc_counts(&self.pool, scan_ids).await?)
+    }
+
+    async fn existing_ids(&self, scan_id: &str, ids: &[i64]) -> Result<Vec<i64>, StorageError> {
+        Ok(StoredResult::existing_ids(&self.pool, scan_id, ids).await?)
+    }
+
+    async fn set_review_status(
+        &self,
+        scan_id: &str,
+        ids: &[i64],
+        status: &str,
+    ) -> Result<u64, StorageError> {
+        Ok(StoredResult::set_review_status(&self.pool, scan_id, ids, status).await?)
+    }
+
+    async fn discard_archived(&self, _scan_id: &str) -> Result<(), StorageError> {
+        // Scan::mark_results_archived removed them with the same transaction
+        Ok(())
+    }
+
+    async fn delete_results(&self, scan_id: &str) -> Result<(), StorageError> {
+        StoredResult::delete_by_scan_id(&self.pool, scan_id).await?;
+        Ok(())
+    }
+
+    async fn delete_results_containing(&self, text: &str) -> Result<u64, StorageError> {
+        Ok(StoredResult::delete_containing(&self.pool, text).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::scan_result::INSERT_BATCH_ROWS;
+    use crate::db::models::Scan;
+    use crate::scanner::{
+        CopyrightFinding, EccFinding, LicenseCategory, LicenseFinding, MalwareFinding,
+    };
+    use crate::storage::traits::{count_ecc, count_licenses, summarize_licenses};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_store_and_load_results() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::run_migrations(&pool).await.unwrap();
+
+        let scan = Scan::create(&pool, "https://github.com/user/repo.git".to_string(), None, None)
+            .await
+            .unwrap();
+
+        let store = SqliteResultStore::new(pool);
+        store
+            .store_results(
+                &scan.id,
+                vec![ScanResult {
+                    file_path: "src/main.rs".to_string(),
+                    licenses: vec![LicenseFinding {
+                        name: "MIT".to_string(),
+                        spdx_id: Some("MIT".to_string()),
+                        confidence: 1.0,
+                        category: LicenseCategory::Code,
+                        provenance: None,
+                        agreement: None,
+                        exception: None,
+                    }],
+                    copyrights: Vec::new(),
+                    ecc_findings: Vec::new(),
+                    malware_findings: Vec::new(),
+                    package_findings: Vec::new(),
+                    model_licenses: Vec::new(),
+                }],
+            )
+            .await
+            .unwrap();
+
+        let loaded = store.load_results(&scan.id).await.unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].license_name.as_deref(), Some("MIT"));
+        assert_eq!(store.count_results(&scan.id).await.unwrap(), 1);
+
+        store.delete_results(&scan.id).await.unwrap();
+        assert!(store.load_results(&scan.id).await.unwrap().is_empty());
+        assert_eq!(store.count_results(&scan.id).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_query_results_filters_and_pages() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::run_migrations(&pool).await.unwrap();
+
+        let scan = Scan::create(&pool, "https://github.com/user/repo.git".to_string(), None, None)
+            .await
+            .unwrap();
+
+        let license = |file: &str, name: &str| ScanResult {
+            file_path: file.to_string(),
+            licenses: vec![LicenseFinding {
+                name: name.to_string(),
+                spdx_id: Some(name.to_string()),
+                confidence: 1.0,
+                category: LicenseCategory::Code,
+                provenance: None,
+                agreement: None,
+                exception: None,
+            }],
+            copyrights: Vec::new(),
+            ecc_findings: Vec::new(),
+            malware_findings: Vec::new(),
+            package_findings: Vec::new(),
+            model_licenses: Vec::new(),
+        };
+
+        let store = SqliteResultStore::new(pool);
+        store
+            .store_results(
+                &scan.id,
+                vec![
+                    license("src/a.rs", "MIT"),
+                    license(&format!("/tmp/ws/{}/src/b.rs", scan.id), "MIT"),
+                    license("src/c.rs", "Apache-2.0"),
+                    license("docs/d.md", "MIT"),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let query = ResultQuery {
+            license: Some("mit".to_string()),
+            file_prefix: Some("src/".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let first = store.query_results(&scan.id, &query).await.unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].file_path, "src/a.rs");
+
+        let next = ResultQuery {
+            after_id: Some(first[0].id),
+            ..query.clone()
+        };
+        let second = store.query_results(&scan.id, &next).await.unwrap();
+        assert_eq!(second.len(), 1);
+        assert!(second[0].file_path.ends_with("/src/b.rs"));
+
+        // The in-memory fallback agrees with the SQL implementation
+        let all = store.load_results(&scan.id).await.unwrap();
+        let unpaged = ResultQuery {
+            limit: None,
+            ..query
+        };
+        assert_eq!(
+            all.iter().filter(|r| unpaged.matches(&scan.id, r)).count(),
+            store.query_results(&scan.id, &unpaged).await.unwrap().len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_license_summary() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::run_migrations(&pool).await.unwrap();
+
+        let scan = Scan::create(&pool, "https://github.com/user/repo.git".to_string(), None, None)
+            .await
+            .unwrap();
+
+        let mut results = findings(4);
+        results[3].licenses[0].name = "Apache-2.0".to_string();
+        results[3].licenses[0].spdx_id = Some("Apache-2.0".to_string());
+        let store = SqliteResultStore::new(pool);
+        store.store_results(&scan.id, results).await.unwrap();
+
+        let summary = store.license_summary(&scan.id, 2).await.unwrap();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].license_name, "MIT");
+        assert_eq!(summary[0].file_count, 3);
+        assert_eq!(summary[0].example_paths, ["src/file0.c", "src/file1.c"]);
+        assert_eq!(summary[1].license_name, "Apache-2.0");
+
+        // The in-memory fallback agrees with the SQL implementation
+        let all = store.load_results(&scan.id).await.unwrap();
+        assert_eq!(summarize_licenses(&all, 2), summary);
+    }
+
+    #[tokio::test]
+    async fn test_counts_across_scans() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::run_migrations(&pool).await.unwrap();
+
+        let store = SqliteResultStore::new(pool.clone());
+        let mut scan_ids = Vec::new();
+        for files in [2, 3] {
+            let scan = Scan::create(&pool, "https://github.com/user/repo.git".to_string(), None, None)
+                .await
+                .unwrap();
+            let mut results = findings(files);
+            results[0].ecc_findings = vec![EccFinding {
+                content: "AES-256".to_string(),
+                risk_severity: "high".to_string(),
+                source: Some("semgrep".to_string()),
+                line_number: Some(1),
+                check_id: None,
+            }];
+            store.store_results(&scan.id, results).await.unwrap();
+            scan_ids.push(scan.id);
+        }
+
+        let licenses = store.license_counts(&scan_ids[..1]).await.unwrap();
+        assert_eq!(licenses.len(), 1);
+        assert_eq!(licenses[0].license, "MIT");
+        assert_eq!(licenses[0].file_count, 2);
+
+        // The in-memory fallback agrees with the SQL implementation
+        let mut licenses = store.license_counts(&scan_ids).await.unwrap();
+        let mut ecc = store.ecc_counts(&scan_ids).await.unwrap();
+        licenses.sort_by(|a, b| a.scan_id.cmp(&b.scan_id));
+        ecc.sort_by(|a, b| a.scan_id.cmp(&b.scan_id));
+        let (mut expected_licenses, mut expected_ecc) = (Vec::new(), Vec::new());
+        for scan_id in &scan_ids {
+            let all = store.load_results(scan_id).await.unwrap();
+            expected_licenses.extend(count_licenses(scan_id, &all));
+            expected_ecc.extend(count_ecc(scan_id, &all));
+        }
+        expected_licenses.sort_by(|a, b| a.scan_id.cmp(&b.scan_id));
+        expected_ecc.sort_by(|a, b| a.scan_id.cmp(&b.scan_id));
+        assert_eq!(licenses, expected_licenses);
+        assert_eq!(ecc, expected_ecc);
+        assert_eq!(ecc[0].risk_severity.as_deref(), Some("high"));
+    }
+
+    fn findings(files: usize) -> Vec<ScanResult> {
+        (0..files)
+            .map(|i| ScanResult {
+                file_path: format!("src/file{}.c", i),
+                licenses: vec![LicenseFinding {
+                    name: "MIT".to_string(),
+                    spdx_id: Some("MIT".to_string()),
+                    confidence: 0.9,
+                    category: LicenseCategory::Code,
+                    provenance: Some("union:fossology".to_string()),
+                    agreement: None,
+                    exception: None,
+                }],
+                copyrights: vec![CopyrightFinding {
+                    statement: "Copyright 2024 Acme".to_string(),
+                    holders: vec!["Acme".to_string()],
+                    years: vec!["2024".to_string()],
+                }],
+                ecc_findings: Vec::new(),
+                malware_findings: Vec::new(),
+                package_findings: Vec::new(),
+                model_licenses: Vec::new(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_store_results_in_batches() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::run_migrations(&pool).await.unwrap();
+
+        let scan = Scan::create(&pool, "https://github.com/user/repo.git".to_string(), None, None)
+            .await
+            .unwrap();
+
+        // Two findings per file, several INSERT statements in all
+        let mut results = findings(INSERT_BATCH_ROWS + 1);
+        results[0].malware_findings.push(MalwareFinding {
+            signature: "Eicar-Test-Signature".to_string(),
+            source: "clamav".to_string(),
+        });
+        let store = SqliteResultStore::new(pool);
+        store.store_results(&scan.id, results).await.unwrap();
+
+        let loaded = store.load_results(&scan.id).await.unwrap();
+        assert_eq!(loaded.len(), 2 * (INSERT_BATCH_ROWS + 1) + 1);
+        let copyright = loaded
+            .iter()
+            .find(|r| r.result_type == "copyright")
+            .unwrap();
+        assert_eq!(copyright.copyright_holders.as_deref(), Some(r#"["Acme"]"#));
+        assert_eq!(copyright.review_status, "pending");
+        let license = loaded.iter().find(|r| r.result_type == "license").unwrap();
+        assert_eq!(license.license_category.as_deref(), Some("code"));
+        assert_eq!(license.merge_provenance.as_deref(), Some("union:fossology"));
+        let malware = loaded.iter().find(|r| r.result_type == "malware").unwrap();
+        assert_eq!(malware.raw_data.as_deref(), Some("Eicar-Test-Signature"));
+        assert_eq!(malware.risk_severity.as_deref(), Some("critical"));
+        assert_eq!(malware.ecc_source.as_deref(), Some("clamav"));
+    }
+
+    #[tokio::test]
+    async fn test_store_results_normalizes_licenses_and_copyrights() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::run_migrations(&pool).await.unwrap();
+
+        let url = "https://github.com/user/repo.git".to_string();
+        let first = Scan::create(&pool, url.clone(), None, None).await.unwrap();
+        let second = Scan::create(&pool, url, None, None).await.unwrap();
+        let store = SqliteResultStore::new(pool.clone());
+        store.store_results(&first.id, findings(3)).await.unwrap();
+        store.store_results(&second.id, findings(2)).await.unwrap();
+
+        // One row per distinct license and statement across files and scans
+        let count = |table: &'static str| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM {}", table))
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap()
+            }
+        };
+        assert_eq!(count("licenses").await, 1);
+        assert_eq!(count("copyright_statements").await, 1);
+        let inline: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM scan_results \
+             WHERE license_name IS NOT NULL OR copyright_statement IS NOT NULL",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(inline, 0);
+
+        let loaded = store.load_results(&second.id).await.unwrap();
+        assert_eq!(loaded.len(), 4);
+        let license = loaded.iter().find(|r| r.result_type == "license").unwrap();
+        assert_eq!(license.license_name.as_deref(), Some("MIT"));
+        assert_eq!(license.license_spdx_id.as_deref(), Some("MIT"));
+        let copyright = loaded
+            .iter()
+            .find(|r| r.result_type == "copyright")
+            .unwrap();
+        assert_eq!(
+            copyright.copyright_statement.as_deref(),
+            Some("Copyright 2024 Acme")
+        );
+        assert_eq!(copyright.copyright_years.as_deref(), Some(r#"["2024"]"#));
+
+        let query = ResultQuery {
+            license: Some("mit".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(store.query_results(&first.id, &query).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_store_results_keeps_license_exceptions() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::run_migrations(&pool).await.unwrap();
+
+        let scan = Scan::create(&pool, "https://github.com/user/repo.git".to_string(), None, None)
+            .await
+            .unwrap();
+        let mut results = findings(2);
+        for result in &mut results {
+            result.licenses[0].name = "GPL-2.0-only".to_string();
+            result.licenses[0].spdx_id = Some("GPL-2.0-only".to_string());
+        }
+        results[1].licenses[0].exception = Some("Classpath-exception-2.0".to_string());
+        let store = SqliteResultStore::new(pool.clone());
+        store.store_results(&scan.id, results).await.unwrap();
+
+        // The license with and without the exception are distinct
+        let licenses: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM licenses")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(licenses, 2);
+        let loaded = store.load_results(&scan.id).await.unwrap();
+        let exceptions: Vec<Option<&str>> = loaded
+            .iter()
+            .filter(|r| r.result_type == "license")
+            .map(|r| r.license_exception.as_deref())
+            .collect();
+        assert_eq!(exceptions, vec![None, Some("Classpath-exception-2.0")]);
+    }
+
+    #[tokio::test]
+    async fn test_store_results_is_atomic() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::db::run_migrations(&pool).await.unwrap();
+
+        let scan = Scan::create(&pool, "https://github.com/user/repo.git".to_string(), None, None)
+            .await
+            .unwrap();
+
+        // Fail on a row of the second INSERT, after the first went through
+        sqlx::query(
+            "CREATE TRIGGER reject_file BEFORE INSERT ON scan_results \
+             WHEN NEW.file_path = 'src/file500.c' BEGIN SELECT RAISE(ABORT, 'rejected'); END",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let store = SqliteResultStore::new(pool);
+        assert!(store
+            .store_results(&scan.id, findings(INSERT_BATCH_ROWS + 1))
+            .await
+            .is_err());
+        assert!(store.load_results(&scan.id).await.unwrap().is_empty());
+    }
+
+    /// Compares the batched inserts with one INSERT per finding on a database
+    /// file. Run with `cargo test --release store_results_benchmark --
+    /// --ignored --nocapture`.
+    #[tokio::test]
+    #[ignore]
+    async fn store_results_benchmark() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}", dir.path().join("bench.db").display());
+        let pool = crate::db::create_pool(&url, &Default::default()).await.unwrap();
+        crate::db::run_migrations(&pool).await.unwrap();
+        let url = "https://github.com/user/repo.git".to_string();
+        let files = 25_000;
+
+        let scan = Scan::create(&pool, url.clone(), None, None).await.unwrap();
+        let started = Instant::now();
+        for result in findings(files) {
+            let file_path = &result.file_path;
+            let rows = result
+                .licenses
+                .into_iter()
+                .map(|license| NewScanResult::license(file_path, license))
+                .chain(
+                    result
+                        .copyrights
+                        .into_iter()
+                        .map(|copyright| NewScanResult::copyright(file_path, copyright)),
+                );
+            for row in rows {
+                let mut conn = pool.acquire().await.unwrap();
+                StoredResult::insert_batch(&mut conn, &scan.id, &[row])
+                    .await
+                    .unwrap();
+            }
+        }
+        let one_by_one = started.elapsed();
+
+        let scan = Scan::create(&pool, url, None, None).await.unwrap();
+        let store = SqliteResultStore::new(pool);
+        let started = Instant::now();
+        store.store_results(&scan.id, findings(files)).await.unwrap();
+        let batched = started.elapsed();
+
+        println!(
+            "{} findings: one INSERT each {:?}, batched {:?}",
+            2 * files,
+            one_by_one,
+            batched
+        );
+    }
+}
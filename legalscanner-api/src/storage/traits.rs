@@ -0,0 +1,298 @@
+use crate::db::models::ScanResult as StoredResult;
+use crate::db::models::scan::ScanSummary;
+use crate::db::models::scan_result::{ScanLicenseCount, ScanSeverityCount};
+use crate::export::sarif::relative_uri;
+use crate::scanner::ScanResult;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Storage backend unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Object not found: {0}")]
+    NotFound(String),
+
+    #[error("Storage operation failed: {0}")]
+    Failed(String),
+}
+
+/// Filters and cursor for [`ResultStore::query_results`]. Results are
+/// returned in ID order so `after_id` can resume where a page ended.
+#[derive(Debug, Default, Clone)]
+pub struct ResultQuery {
+    pub result_type: Option<String>,
+    /// SPDX ID or license name, compared case-insensitively
+    pub license: Option<String>,
+    pub severity: Option<String>,
+    /// Repository-relative path prefix
+    pub file_prefix: Option<String>,
+    /// pending, approved or rejected
+    pub review_status: Option<String>,
+    pub after_id: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+impl ResultQuery {
+    pub fn matches(&self, scan_id: &str, result: &StoredResult) -> bool {
+        let eq_ignore_case = |value: &Option<String>, wanted: &str| {
+            value
+                .as_deref()
+                .is_some_and(|v| v.eq_ignore_ascii_case(wanted))
+        };
+
+        self.result_type
+            .as_deref()
+            .is_none_or(|t| result.result_type == t)
+            && self.license.as_deref().is_none_or(|l| {
+                eq_ignore_case(&result.license_spdx_id, l)
+                    || eq_ignore_case(&result.license_name, l)
+            })
+            && self
+                .severity
+                .as_deref()
+                .is_none_or(|s| eq_ignore_case(&result.risk_severity, s))
+            && self.file_prefix.as_deref().is_none_or(|p| {
+                relative_uri(&result.file_path, scan_id).starts_with(p)
+            })
+            && self
+                .review_status
+                .as_deref()
+                .is_none_or(|s| result.review_status == s)
+            && self.after_id.is_none_or(|id| result.id > id)
+    }
+}
+
+/// The findings among `results` that match `query`, in ID order
+pub fn filter_results(
+    scan_id: &str,
+    results: Vec<StoredResult>,
+    query: &ResultQuery,
+) -> Vec<StoredResult> {
+    let mut results: Vec<StoredResult> = results
+        .into_iter()
+        .filter(|r| query.matches(scan_id, r))
+        .collect();
+    results.sort_by_key(|r| r.id);
+    if let Some(limit) = query.limit {
+        results.truncate(limit.max(0) as usize);
+    }
+    results
+}
+
+/// Files in which a scan found one license, see
+/// [`ResultStore::license_summary`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LicenseFiles {
+    pub license_name: String,
+    pub license_spdx_id: Option<String>,
+    pub file_count: i64,
+    /// Alphabetically first paths, as the scanner recorded them
+    pub example_paths: Vec<String>,
+}
+
+/// Files per license among `results`, most common first, with up to
+/// `example_limit` of the files as examples
+pub fn summarize_licenses(results: &[StoredResult], example_limit: usize) -> Vec<LicenseFiles> {
+    let mut files: BTreeMap<(&str, Option<&str>), BTreeSet<&str>> = BTreeMap::new();
+    for result in results.iter().filter(|r| r.result_type == "license") {
+        if let Some(name) = result.license_name.as_deref() {
+            files
+                .entry((name, result.license_spdx_id.as_deref()))
+                .or_default()
+                .insert(&result.file_path);
+        }
+    }
+
+    let mut licenses: Vec<LicenseFiles> = files
+        .into_iter()
+        .map(|((name, spdx_id), paths)| LicenseFiles {
+            license_name: name.to_string(),
+            license_spdx_id: spdx_id.map(str::to_string),
+            file_count: paths.len() as i64,
+            example_paths: paths
+                .into_iter()
+                .take(example_limit)
+                .map(str::to_string)
+                .collect(),
+        })
+        .collect();
+    // Most common first; the map already ordered them by name
+    licenses.sort_by_key(|l| std::cmp::Reverse(l.file_count));
+    licenses
+}
+
+/// Files per license (SPDX ID, else name) among the findings of a scan
+pub fn count_licenses(scan_id: &str, results: &[StoredResult]) -> Vec<ScanLicenseCount> {
+    let mut files: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for result in results.iter().filter(|r| r.result_type == "license") {
+        if let Some(license) = result
+            .license_spdx_id
+            .as_deref()
+            .or(result.license_name.as_deref())
+        {
+            files.entry(license).or_default().insert(&result.file_path);
+        }
+    }
+    files
+        .into_iter()
+        .map(|(license, paths)| ScanLicenseCount {
+            scan_id: scan_id.to_string(),
+            license: license.to_string(),
+            file_count: paths.len() as i64,
+        })
+        .collect()
+}
+
+/// ECC findings per severity among the findings of a scan
+pub fn count_ecc(scan_id: &str, results: &[StoredResult]) -> Vec<ScanSeverityCount> {
+    let mut counts: BTreeMap<Option<&str>, i64> = BTreeMap::new();
+    for result in results.iter().filter(|r| r.result_type == "ecc") {
+        *counts.entry(result.risk_severity.as_deref()).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(severity, finding_count)| ScanSeverityCount {
+            scan_id: scan_id.to_string(),
+            risk_severity: severity.map(str::to_string),
+            finding_count,
+        })
+        .collect()
+}
+
+/// Persistence backend for per-file scan findings.
+///
+/// Scan metadata (status, risk assessment, etc.) always lives in the relational
+/// database; only the potentially very large set of findings goes through this
+/// trait so that deployments can move it to a different backend.
+#[async_trait]
+pub trait ResultStore: Send + Sync {
+    /// Returns the name/identifier of this storage backend
+    fn name(&self) -> &str;
+
+    /// Persists all findings produced for a scan, all of them or none
+    async fn store_results(
+        &self,
+        scan_id: &str,
+        results: Vec<ScanResult>,
+    ) -> Result<(), StorageError>;
+
+    /// Persists findings exported from another instance for a new scan, with
+    /// their review decisions, all of them or none. Returns the IDs they were
+    /// stored under, in the order given.
+    async fn restore_results(
+        &self,
+        scan_id: &str,
+        results: Vec<StoredResult>,
+    ) -> Result<Vec<i64>, StorageError>;
+
+    /// Loads all findings for a scan, ordered by file path and result type
+    async fn load_results(&self, scan_id: &str) -> Result<Vec<StoredResult>, StorageError>;
+
+    /// Loads the findings for a scan that match `query`, in ID order.
+    ///
+    /// The default implementation filters [`ResultStore::load_results`] in
+    /// memory; backends that can filter natively should override it.
+    async fn query_results(
+        &self,
+        scan_id: &str,
+        query: &ResultQuery,
+    ) -> Result<Vec<StoredResult>, StorageError> {
+        Ok(filter_results(
+            scan_id,
+            self.load_results(scan_id).await?,
+            query,
+        ))
+    }
+
+    /// Number of findings of the scan. The default implementation counts
+    /// [`ResultStore::load_results`].
+    async fn count_results(&self, scan_id: &str) -> Result<u64, StorageError> {
+        Ok(self.load_results(scan_id).await?.len() as u64)
+    }
+
+    /// Summary of the findings of the scan. The default implementation
+    /// summarizes [`ResultStore::load_results`].
+    async fn summarize(&self, scan_id: &str) -> Result<ScanSummary, StorageError> {
+        Ok(ScanSummary::from_results(&self.load_results(scan_id).await?))
+    }
+
+    /// Files per license of the scan, most common first, with up to
+    /// `example_limit` of the files as examples. The default implementation
+    /// aggregates [`ResultStore::load_results`] in memory.
+    async fn license_summary(
+        &self,
+        scan_id: &str,
+        example_limit: usize,
+    ) -> Result<Vec<LicenseFiles>, StorageError> {
+        Ok(summarize_licenses(
+            &self.load_results(scan_id).await?,
+            example_limit,
+        ))
+    }
+
+    /// Files per license (SPDX ID, else name) of each of the scans, for
+    /// statistics across scans. The default implementation aggregates
+    /// [`ResultStore::load_results`] of one scan after the other in memory.
+    async fn license_counts(
+        &self,
+        scan_ids: &[String],
+    ) -> Result<Vec<ScanLicenseCount>, StorageError> {
+        let mut counts = Vec::new();
+        for scan_id in scan_ids {
+            counts.extend(count_licenses(scan_id, &self.load_results(scan_id).await?));
+        }
+        Ok(counts)
+    }
+
+    /// ECC findings per severity of each of the scans. The default
+    /// implementation aggregates [`ResultStore::load_results`] of one scan
+    /// after the other in memory.
+    async fn ecc_counts(
+        &self,
+        scan_ids: &[String],
+    ) -> Result<Vec<ScanSeverityCount>, StorageError> {
+        let mut counts = Vec::new();
+        for scan_id in scan_ids {
+            counts.extend(count_ecc(scan_id, &self.load_results(scan_id).await?));
+        }
+        Ok(counts)
+    }
+
+    /// The IDs among `ids` that are findings of the scan
+    async fn existing_ids(&self, scan_id: &str, ids: &[i64]) -> Result<Vec<i64>, StorageError> {
+        Ok(self
+            .load_results(scan_id)
+            .await?
+            .into_iter()
+            .map(|r| r.id)
+            .filter(|id| ids.contains(id))
+            .collect())
+    }
+
+    /// Sets the review status of findings of a scan, stamping `reviewed_at`
+    /// unless the status is `pending`. Findings already in that status are
+    /// left alone. Returns the number of findings changed.
+    async fn set_review_status(
+        &self,
+        scan_id: &str,
+        ids: &[i64],
+        status: &str,
+    ) -> Result<u64, StorageError>;
+
+    /// Removes the findings of a scan once they were archived, keeping
+    /// their review comments
+    async fn discard_archived(&self, scan_id: &str) -> Result<(), StorageError>;
+
+    /// Removes all findings for a scan
+    async fn delete_results(&self, scan_id: &str) -> Result<(), StorageError>;
+
+    /// Irreversibly removes findings of any scan whose copyright statement,
+    /// holders or raw scanner output contain `text` (lowercase). Returns the
+    /// number of findings removed.
+    async fn delete_results_containing(&self, text: &str) -> Result<u64, StorageError>;
+}
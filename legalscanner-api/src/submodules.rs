@@ -0,0 +1,110 @@
+//! Git submodules of scanned repositories. Scans created with `submodules`
+//! check them out recursively after cloning, so the code they pull in is
+//! scanned too, and findings are traced back to the submodule they came from.
+
+use crate::db::models::{Scan, ScanResult, ScanSubmodule};
+use crate::export::sarif::repository_path;
+use crate::git::{update_submodules, GitAuth};
+use crate::AppState;
+use sqlx::SqlitePool;
+use std::path::Path;
+
+/// Check out the submodules of a fresh clone and store where they came from.
+/// Submodules that cannot be checked out are recorded with their error, and
+/// returned as warnings, since the scan misses their code.
+pub async fn checkout(
+    state: &AppState,
+    scan_id: &str,
+    repo_root: &Path,
+    auth: Option<&GitAuth>,
+) -> Result<Vec<String>, git2::Error> {
+    let checkouts = update_submodules(repo_root, auth).await?;
+
+    let mut warnings = Vec::new();
+    for checkout in &checkouts {
+        if let Some(error) = &checkout.error {
+            warnings.push(format!(
+                "Submodule {} could not be checked out and was not scanned: {}",
+                checkout.path, error
+            ));
+        }
+        if let Err(e) = ScanSubmodule::create(
+            &state.db,
+            scan_id,
+            &checkout.path,
+            checkout.url.as_deref(),
+            checkout.commit_sha.as_deref(),
+            checkout.error.as_deref(),
+        )
+        .await
+        {
+            tracing::error!("Failed to store submodule {}: {}", checkout.path, e);
+        }
+    }
+
+    tracing::info!(
+        "Checked out {} of {} submodules",
+        checkouts.len() - warnings.len(),
+        checkouts.len()
+    );
+    Ok(warnings)
+}
+
+/// Set `submodule` on the findings in files of a submodule checked out for
+/// the scan. Returns how many findings came from submodules.
+pub async fn mark(
+    pool: &SqlitePool,
+    scan: &Scan,
+    results: &mut [ScanResult],
+) -> Result<usize, sqlx::Error> {
+    if !scan.submodules {
+        return Ok(0);
+    }
+    let submodules = ScanSubmodule::find_by_scan_id(pool, &scan.id).await?;
+
+    let mut marked = 0;
+    for result in results.iter_mut() {
+        let path = repository_path(&result.file_path, &scan.id, scan.scan_path.as_deref());
+        result.submodule = containing_submodule(submodules.iter().map(|s| s.path.as_str()), &path)
+            .map(str::to_string);
+        if result.submodule.is_some() {
+            marked += 1;
+        }
+    }
+    Ok(marked)
+}
+
+/// Path of the innermost submodule containing the repository-relative
+/// `path`, if any
+fn containing_submodule<'a>(
+    submodules: impl IntoIterator<Item = &'a str>,
+    path: &str,
+) -> Option<&'a str> {
+    submodules
+        .into_iter()
+        .filter(|submodule| {
+            path.strip_prefix(submodule)
+                .is_some_and(|rest| rest.starts_with('/'))
+        })
+        .max_by_key(|submodule| submodule.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_containing_submodule_prefers_innermost() {
+        let submodules = ["vendor/lib", "vendor/lib/deps/zlib", "vendor/other"];
+        assert_eq!(
+            containing_submodule(submodules, "vendor/lib/src/a.c"),
+            Some("vendor/lib")
+        );
+        assert_eq!(
+            containing_submodule(submodules, "vendor/lib/deps/zlib/inflate.c"),
+            Some("vendor/lib/deps/zlib")
+        );
+        assert_eq!(containing_submodule(submodules, "vendor/library.c"), None);
+        assert_eq!(containing_submodule(submodules, "src/main.rs"), None);
+    }
+}
@@ -0,0 +1,331 @@
+//! Software Heritage identifiers. Every file of a checkout gets its content
+//! SWHID (`swh:1:cnt:` and the git blob hash) when it is scanned, and the
+//! scanned commit is identified by its revision SWHID. With
+//! `SOFTWARE_HERITAGE_URL` set, the archive is asked which contents it
+//! already knows and, with a token for its provenance API, which upstream
+//! repository a known file comes from: vendored or copied code then shows
+//! up in the SBOM with its origin.
+//!
+//! The archive is never asked without the URL, SWHIDs are derived from the
+//! code and can reveal what a private repository contains.
+
+use crate::db::models::ScanFileSwhid;
+use crate::progress::record_event;
+use crate::scan_diff::normalize_repository_url;
+use crate::scanner::walk::collect_files;
+use crate::scanner::ScanError;
+use crate::AppState;
+use git2::{ObjectType, Oid};
+use reqwest::Client;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const ARCHIVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// SWHIDs the archive accepts in one `known` request
+const KNOWN_BATCH_SIZE: usize = 1000;
+
+/// Content SWHID of a file, before the archive is asked about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSwhid {
+    /// Path relative to the scanned directory
+    pub file_path: String,
+    pub swhid: String,
+    /// Whether the archive holds the content, `None` when it was not asked
+    pub known: Option<bool>,
+    /// Upstream repository the archive found the content in
+    pub origin: Option<String>,
+}
+
+/// Content SWHID of file contents
+pub fn content_swhid(content: &[u8]) -> Result<String, git2::Error> {
+    Ok(format!(
+        "swh:1:cnt:{}",
+        Oid::hash_object(ObjectType::Blob, content)?
+    ))
+}
+
+/// Revision SWHID of a git commit
+pub fn revision_swhid(commit_sha: &str) -> String {
+    format!("swh:1:rev:{}", commit_sha)
+}
+
+/// Content SWHIDs of the files under `root`, in path order
+pub async fn compute(root: &Path) -> Result<Vec<FileSwhid>, ScanError> {
+    let files = collect_files(root).await?;
+    let root = root.to_path_buf();
+    tokio::task::spawn_blocking(move || hash_files(&root, files))
+        .await
+        .map_err(|e| ScanError::Failed(format!("SWHID task failed: {}", e)))?
+}
+
+fn hash_files(root: &Path, files: Vec<PathBuf>) -> Result<Vec<FileSwhid>, ScanError> {
+    let mut swhids = files
+        .into_iter()
+        .map(|file| {
+            let oid = Oid::hash_file(ObjectType::Blob, &file).map_err(|e| {
+                ScanError::Failed(format!("Failed to hash {}: {}", file.display(), e))
+            })?;
+            let file_path = file
+                .strip_prefix(root)
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .into_owned();
+            Ok(FileSwhid {
+                file_path,
+                swhid: format!("swh:1:cnt:{}", oid),
+                known: None,
+                origin: None,
+            })
+        })
+        .collect::<Result<Vec<_>, ScanError>>()?;
+    swhids.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    Ok(swhids)
+}
+
+/// Compute and store the content SWHIDs of a checkout, and look them up in
+/// the archive when configured. Best-effort: a failure only costs the
+/// identifiers.
+pub async fn record(state: &AppState, scan_id: &str, git_url: &str, root: &Path) {
+    if !state.config.swhid_enabled {
+        return;
+    }
+
+    let mut swhids = match compute(root).await {
+        Ok(swhids) => swhids,
+        Err(e) => {
+            tracing::warn!("Failed to compute SWHIDs for scan {}: {}", scan_id, e);
+            return;
+        }
+    };
+
+    if let Some(url) = &state.config.software_heritage_url {
+        let _ = record_event(
+            state,
+            scan_id,
+            "scanner_started",
+            Some("software_heritage"),
+            None,
+        )
+        .await;
+        let archive = Archive::new(url, state.config.software_heritage_token.as_deref());
+        match archive
+            .identify(
+                &mut swhids,
+                git_url,
+                state.config.software_heritage_max_lookups,
+            )
+            .await
+        {
+            Ok(()) => {
+                let _ = record_event(
+                    state,
+                    scan_id,
+                    "scanner_finished",
+                    Some("software_heritage"),
+                    None,
+                )
+                .await;
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Software Heritage lookup failed for scan {}: {}",
+                    scan_id,
+                    e
+                );
+                let _ = record_event(
+                    state,
+                    scan_id,
+                    "scanner_failed",
+                    Some("software_heritage"),
+                    Some(&e.to_string()),
+                )
+                .await;
+            }
+        }
+    }
+
+    match ScanFileSwhid::store(&state.db, scan_id, &swhids).await {
+        Ok(()) => tracing::info!("Recorded {} SWHIDs for scan {}", swhids.len(), scan_id),
+        Err(e) => tracing::error!("Failed to store SWHIDs for scan {}: {}", scan_id, e),
+    }
+}
+
+/// Software Heritage archive API, e.g. `https://archive.softwareheritage.org/api/1`
+struct Archive<'a> {
+    url: &'a str,
+    token: Option<&'a str>,
+}
+
+impl<'a> Archive<'a> {
+    fn new(url: &'a str, token: Option<&'a str>) -> Self {
+        Self { url, token }
+    }
+
+    /// Mark the contents the archive knows and, with a token, find the
+    /// origin of up to `max_lookups` of them. Origins that are the scanned
+    /// repository itself say nothing about where the code comes from.
+    async fn identify(
+        &self,
+        swhids: &mut [FileSwhid],
+        git_url: &str,
+        max_lookups: usize,
+    ) -> Result<(), reqwest::Error> {
+        let client = Client::builder()
+            .timeout(ARCHIVE_TIMEOUT)
+            .user_agent(concat!("legalscanner/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        let mut unique: Vec<&str> = swhids.iter().map(|f| f.swhid.as_str()).collect();
+        unique.sort_unstable();
+        unique.dedup();
+        let mut known: HashMap<String, bool> = HashMap::new();
+        for batch in unique.chunks(KNOWN_BATCH_SIZE) {
+            known.extend(self.known(&client, batch).await?);
+        }
+        for file in swhids.iter_mut() {
+            file.known = Some(known.get(&file.swhid).copied().unwrap_or(false));
+        }
+
+        if self.token.is_none() || max_lookups == 0 {
+            return Ok(());
+        }
+        let scanned = normalize_repository_url(git_url);
+        let mut origins: HashMap<String, Option<String>> = HashMap::new();
+        for file in swhids.iter_mut().filter(|f| f.known == Some(true)) {
+            if !origins.contains_key(&file.swhid) {
+                if origins.len() >= max_lookups {
+                    continue;
+                }
+                let origin = self
+                    .whereis(&client, &file.swhid)
+                    .await?
+                    .as_deref()
+                    .and_then(origin_of)
+                    .filter(|o| normalize_repository_url(o) != scanned);
+                origins.insert(file.swhid.clone(), origin);
+            }
+            file.origin = origins[&file.swhid].clone();
+        }
+        Ok(())
+    }
+
+    async fn known(
+        &self,
+        client: &Client,
+        swhids: &[&str],
+    ) -> Result<HashMap<String, bool>, reqwest::Error> {
+        #[derive(serde::Deserialize)]
+        struct Known {
+            known: bool,
+        }
+
+        let mut request = client.post(format!("{}/known/", self.url)).json(swhids);
+        if let Some(token) = self.token {
+            request = request.bearer_auth(token);
+        }
+        let known: HashMap<String, Known> =
+            request.send().await?.error_for_status()?.json().await?;
+        Ok(known
+            .into_iter()
+            .map(|(swhid, k)| (swhid, k.known))
+            .collect())
+    }
+
+    /// Qualified SWHID of a place the content was found, if any
+    async fn whereis(
+        &self,
+        client: &Client,
+        swhid: &str,
+    ) -> Result<Option<String>, reqwest::Error> {
+        let mut request = client.get(format!("{}/provenance/whereis/{}/", self.url, swhid));
+        if let Some(token) = self.token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await?.error_for_status()?.json().await
+    }
+}
+
+/// The `origin` qualifier of a qualified SWHID, percent-decoded
+pub fn origin_of(qualified: &str) -> Option<String> {
+    let encoded = qualified
+        .split(';')
+        .skip(1)
+        .find_map(|qualifier| qualifier.strip_prefix("origin="))?;
+
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| encoded.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok().filter(|o| !o.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_swhid() {
+        // The empty blob, as `git hash-object /dev/null` prints it
+        assert_eq!(
+            content_swhid(b"").unwrap(),
+            "swh:1:cnt:e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"
+        );
+        assert_eq!(
+            revision_swhid("309cf2674ee7a0749978cf8265ab91a60aea0f7d"),
+            "swh:1:rev:309cf2674ee7a0749978cf8265ab91a60aea0f7d"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compute_hashes_files_by_relative_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/empty.c"), "").unwrap();
+        std::fs::write(dir.path().join("README.md"), "# App\n").unwrap();
+
+        let swhids = compute(dir.path()).await.unwrap();
+        let paths: Vec<&str> = swhids.iter().map(|f| f.file_path.as_str()).collect();
+        assert_eq!(paths, ["README.md", "src/empty.c"]);
+        assert_eq!(swhids[0].swhid, content_swhid(b"# App\n").unwrap());
+        assert_eq!(
+            swhids[1].swhid,
+            "swh:1:cnt:e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"
+        );
+        assert_eq!(swhids[1].known, None);
+    }
+
+    #[test]
+    fn test_origin_of() {
+        assert_eq!(
+            origin_of(
+                "swh:1:cnt:e69de29bb2d1d6434b8b29ae775ad8c2e48c5391;\
+                 origin=https://github.com/madler/zlib;visit=swh:1:snp:abc;anchor=swh:1:rev:def"
+            )
+            .as_deref(),
+            Some("https://github.com/madler/zlib")
+        );
+        assert_eq!(
+            origin_of("swh:1:cnt:abc;origin=https://example.com/a%3Bb%25c").as_deref(),
+            Some("https://example.com/a;b%c")
+        );
+        assert_eq!(origin_of("swh:1:cnt:abc;anchor=swh:1:rev:def"), None);
+        assert_eq!(origin_of("swh:1:cnt:abc"), None);
+    }
+}
@@ -0,0 +1,274 @@
+//! Opt-in anonymous usage telemetry. With `TELEMETRY_URL` set, one worker of
+//! the installation POSTs a [`TelemetryReport`] there every
+//! `TELEMETRY_INTERVAL_HOURS`: the version running, how many scans were
+//! created, completed and failed in the period, and what kind of errors
+//! failed them. Nothing about the repositories is reported, no URLs, paths,
+//! findings or error messages: a failure is counted by the type of its error.
+//!
+//! Nothing is sent without the URL. `GET /api/v1/admin/telemetry` shows the
+//! report of the current period either way, so it can be reviewed before
+//! opting in.
+
+use crate::db::models::{ScanEvent, TelemetryState};
+use crate::error::AppError;
+use crate::scanner::ScanError;
+use crate::storage::StorageError;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+
+const REPORT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often the workers check whether a report is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Format of `scan_events.occurred_at`
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+/// Usage of an installation over one period
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TelemetryReport {
+    /// Random ID, tells the reports of different installations apart
+    pub installation_id: String,
+    pub version: String,
+    /// Start of the period, YYYY-MM-DD HH:MM:SS.SSS (UTC)
+    pub period_start: String,
+    /// End of the period, exclusive
+    pub period_end: String,
+    pub scans_created: i64,
+    pub scans_completed: i64,
+    pub scans_failed: i64,
+    /// Failed scans by the class of their error, e.g. `git_net` or
+    /// `scanner_unavailable`
+    pub error_classes: BTreeMap<String, i64>,
+    /// Scanner failures by scanner, including best-effort scanners whose
+    /// failure did not fail the scan
+    pub scanner_failures: BTreeMap<String, i64>,
+}
+
+/// Report the usage recorded in `[from, to)`
+pub async fn build(
+    pool: &SqlitePool,
+    installation_id: &str,
+    from: &str,
+    to: &str,
+) -> Result<TelemetryReport, sqlx::Error> {
+    let mut report = TelemetryReport {
+        installation_id: installation_id.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        period_start: from.to_string(),
+        period_end: to.to_string(),
+        scans_created: 0,
+        scans_completed: 0,
+        scans_failed: 0,
+        error_classes: BTreeMap::new(),
+        scanner_failures: BTreeMap::new(),
+    };
+
+    for (event_type, scanner, count) in ScanEvent::count_between(pool, from, to).await? {
+        match (event_type.as_str(), scanner) {
+            ("created", _) => report.scans_created += count,
+            ("completed", _) => report.scans_completed += count,
+            ("failed", _) => report.scans_failed += count,
+            ("scanner_failed", Some(scanner)) => {
+                *report.scanner_failures.entry(scanner).or_default() += count
+            }
+            _ => {}
+        }
+    }
+    report.error_classes = ScanEvent::count_failures_by_class(pool, from, to)
+        .await?
+        .into_iter()
+        .collect();
+
+    Ok(report)
+}
+
+/// The report for the period since the last one, as it would be sent now
+pub async fn preview(state: &AppState) -> Result<TelemetryReport, sqlx::Error> {
+    let telemetry = TelemetryState::load(&state.db).await?;
+    let now = Utc::now();
+    let from = period_start(&telemetry, now, state.config.telemetry_interval_hours);
+    build(
+        &state.db,
+        &telemetry.installation_id,
+        &from,
+        &now.format(TIMESTAMP_FORMAT).to_string(),
+    )
+    .await
+}
+
+/// Send the report for the period since the last one if a period has
+/// passed and no other worker sent it. Returns whether this worker did.
+pub async fn report_if_due(state: &AppState) -> Result<bool, AppError> {
+    let Some(url) = &state.config.telemetry_url else {
+        return Ok(false);
+    };
+
+    let telemetry = TelemetryState::load(&state.db).await?;
+    let now = Utc::now();
+    let hours = state.config.telemetry_interval_hours;
+    let from = period_start(&telemetry, now, hours);
+    let previous = telemetry.last_reported_at.as_deref();
+    if previous.is_some_and(|last| last > period_start_before(now, hours).as_str()) {
+        return Ok(false);
+    }
+    let to = now.format(TIMESTAMP_FORMAT).to_string();
+    if !TelemetryState::advance(&state.db, previous, Some(&to)).await? {
+        return Ok(false);
+    }
+
+    let report = build(&state.db, &telemetry.installation_id, &from, &to).await?;
+    if let Err(e) = send(url, &report).await {
+        // Give the period back, the next check reports it with the next one
+        TelemetryState::advance(&state.db, Some(&to), previous).await?;
+        return Err(AppError::Internal(format!(
+            "Failed to send telemetry report: {}",
+            e
+        )));
+    }
+    Ok(true)
+}
+
+async fn send(url: &str, report: &TelemetryReport) -> Result<(), reqwest::Error> {
+    Client::builder()
+        .timeout(REPORT_TIMEOUT)
+        .user_agent(concat!("legalscanner/", env!("CARGO_PKG_VERSION")))
+        .build()?
+        .post(url)
+        .json(report)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Where the next report starts: the end of the last one, or one interval
+/// back for the first
+fn period_start(telemetry: &TelemetryState, now: DateTime<Utc>, hours: u64) -> String {
+    telemetry
+        .last_reported_at
+        .clone()
+        .unwrap_or_else(|| period_start_before(now, hours))
+}
+
+fn period_start_before(now: DateTime<Utc>, hours: u64) -> String {
+    (now - chrono::Duration::hours(hours as i64))
+        .format(TIMESTAMP_FORMAT)
+        .to_string()
+}
+
+/// Check hourly whether a report is due, the first time one hour after
+/// startup. `None` unless telemetry was opted into.
+pub fn spawn_periodic(state: AppState) -> Option<JoinHandle<()>> {
+    if state.config.telemetry_url.is_none() || state.config.telemetry_interval_hours == 0 {
+        return None;
+    }
+    Some(tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match report_if_due(&state).await {
+                Ok(true) => tracing::info!("Telemetry report sent"),
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Telemetry report failed: {}", e),
+            }
+        }
+    }))
+}
+
+/// Kind of error that failed a scan, from the type of the error alone: the
+/// message may name the repository and is never looked at
+pub fn error_class(error: &(dyn std::error::Error + 'static)) -> String {
+    if let Some(e) = error.downcast_ref::<git2::Error>() {
+        return git_error_class(e);
+    }
+    if let Some(e) = error.downcast_ref::<ScanError>() {
+        return match e {
+            ScanError::Unavailable(_) => "scanner_unavailable",
+            ScanError::Failed(_) => "scanner_failed",
+            ScanError::ParseError(_) => "scanner_parse_error",
+            ScanError::Rejected(_) => "scanner_rejected",
+            ScanError::Io(_) => "scanner_io",
+            ScanError::Http(_) => "scanner_http",
+        }
+        .to_string();
+    }
+    if let Some(e) = error.downcast_ref::<AppError>() {
+        return match e {
+            AppError::Git(e) => return git_error_class(e),
+            AppError::Database(_) => "database",
+            AppError::Scanner(_) => "scanner",
+            AppError::Docker(_) => "docker",
+            AppError::Storage(_) => "storage",
+            AppError::Unauthorized => "unauthorized",
+            AppError::NotFound(_) => "not_found",
+            AppError::Validation(_) => "validation",
+            AppError::Conflict(_) => "conflict",
+            AppError::Unprocessable(_) => "unprocessable",
+            AppError::PayloadTooLarge(_) => "payload_too_large",
+            AppError::Internal(_) => "internal",
+        }
+        .to_string();
+    }
+    if error.is::<sqlx::Error>() {
+        "database".to_string()
+    } else if error.is::<StorageError>() {
+        "storage".to_string()
+    } else if error.is::<std::io::Error>() {
+        "io".to_string()
+    } else {
+        "other".to_string()
+    }
+}
+
+/// `git_auth`, `git_net` and the like; errors raised by the clone itself,
+/// such as a timeout, are plain `git`
+fn git_error_class(error: &git2::Error) -> String {
+    match (error.code(), error.class()) {
+        (git2::ErrorCode::Auth, _) => "git_auth".to_string(),
+        (_, git2::ErrorClass::None) => "git".to_string(),
+        (_, class) => format!("git_{:?}", class).to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_class_ignores_message() {
+        let scanner: Box<dyn std::error::Error + Send + Sync> = Box::new(
+            ScanError::Unavailable("https://github.com/acme/secret".to_string()),
+        );
+        assert_eq!(error_class(scanner.as_ref()), "scanner_unavailable");
+
+        let auth = git2::Error::new(
+            git2::ErrorCode::Auth,
+            git2::ErrorClass::Http,
+            "authentication required for https://github.com/acme/secret",
+        );
+        assert_eq!(error_class(&auth), "git_auth");
+        let network = git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Net,
+            "failed to resolve address",
+        );
+        assert_eq!(error_class(&AppError::Git(network)), "git_net");
+        assert_eq!(
+            error_class(&git2::Error::from_str("clone timed out after 60s")),
+            "git"
+        );
+
+        let message: Box<dyn std::error::Error + Send + Sync> =
+            "Malware scan failed: acme/secret".into();
+        assert_eq!(error_class(message.as_ref()), "other");
+    }
+}
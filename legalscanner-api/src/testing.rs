@@ -0,0 +1,254 @@
+//! Helpers for running the API against in-memory state in integration tests.
+//! Enabled with the `test-utils` feature.
+
+use crate::config::Config;
+use crate::db::models::result_partition::ResultPartitioning;
+use crate::git::DEFAULT_GIT_URL_SCHEMES;
+use crate::progress::ProgressHub;
+use crate::scanner::documentation::DocumentationLicenseScanner;
+use crate::scanner::fossology::{DEFAULT_MAX_CONCURRENT_JOBS, DEFAULT_UPLOAD_NAME_TEMPLATE};
+use crate::scanner::merge::MergeStrategy;
+use crate::scanner::model::ModelLicenseScanner;
+use crate::scanner::semgrep::{CheckCategoryMap, SemgrepMode};
+use crate::scanner::Scanner;
+use crate::server::ListenAddr;
+use crate::storage;
+use crate::AppState;
+use sqlx::sqlite::{SqlitePoolOptions, SqliteSynchronous};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub use crate::scanner::mock::MockScanner;
+
+/// Configuration that touches nothing outside `workspace_dir`
+pub fn test_config(workspace_dir: PathBuf) -> Config {
+    Config {
+        database_url: "sqlite::memory:".to_string(),
+        // An in-memory database has no journal to share between connections
+        database_max_connections: 1,
+        database_busy_timeout_ms: 5000,
+        database_wal: false,
+        database_synchronous: SqliteSynchronous::Normal,
+        fossology_url: "http://127.0.0.1:0".to_string(),
+        fossology_api_token: "test-token".to_string(),
+        fossology_upload_name_template: DEFAULT_UPLOAD_NAME_TEMPLATE.to_string(),
+        fossology_max_concurrent_jobs: DEFAULT_MAX_CONCURRENT_JOBS,
+        temp_workspace_dir: workspace_dir.clone(),
+        listen_addr: ListenAddr::any(0),
+        api_key_salt: "test-salt".to_string(),
+        result_store: "sqlite".to_string(),
+        result_store_postgres_url: None,
+        result_store_dir: workspace_dir.join("results"),
+        result_store_s3_bucket: None,
+        result_store_s3_region: "us-east-1".to_string(),
+        result_store_s3_endpoint: None,
+        result_store_s3_prefix: String::new(),
+        result_store_s3_access_key_id: None,
+        result_store_s3_secret_access_key: None,
+        semgrep_rulesets: Vec::new(),
+        semgrep_check_categories: CheckCategoryMap::default(),
+        semgrep_mode: SemgrepMode::Local,
+        semgrep_binary: "semgrep".to_string(),
+        semgrep_local_rules_dir: PathBuf::from("./semgrep-rules"),
+        clamav_address: None,
+        syft_binary: None,
+        scanner_sandbox_user: None,
+        scanner_sandbox_memory_mb: 0,
+        scanner_sandbox_cpu_secs: 0,
+        scanner_sandbox_max_processes: 0,
+        scanner_sandbox_max_file_size_mb: 0,
+        scanner_sandbox_cgroup: None,
+        scanner_sandbox_bwrap: None,
+        scanner_sandbox_network: false,
+        merge_strategy: MergeStrategy::default(),
+        quick_scan_timeout_secs: 60,
+        screening_webhook_url: None,
+        screening_webhook_token: None,
+        screening_min_severity: "critical".to_string(),
+        anonymized_analytics_enabled: false,
+        analytics_instance_label: None,
+        soft_limit_max_files: 0,
+        soft_limit_max_size_mb: 0,
+        due_diligence_max_commits: 0,
+        git_authorship_max_commits: 0,
+        worker_name: "legalscanner-test".to_string(),
+        response_compression: Vec::new(),
+        compression_min_size: 1024,
+        export_post_processors: Vec::new(),
+        export_custom_fields: Vec::new(),
+        export_signing_key: None,
+        webhook_secret: None,
+        webhook_max_attempts: 3,
+        webhook_retry_delay_secs: 0,
+        public_base_url: None,
+        scan_lock_per_repository: true,
+        admin_api_token: None,
+        github_api_url: "http://127.0.0.1:0".to_string(),
+        gitlab_api_url: "http://127.0.0.1:0".to_string(),
+        bitbucket_api_url: "http://127.0.0.1:0".to_string(),
+        azure_devops_url: "http://127.0.0.1:0".to_string(),
+        upload_max_size_mb: 16,
+        upload_max_extracted_mb: 64,
+        integrity_check_interval_hours: 0,
+        local_scan_base_dirs: Vec::new(),
+        // Fixture repositories are cloned from disk
+        git_url_schemes: DEFAULT_GIT_URL_SCHEMES
+            .into_iter()
+            .chain(["file"])
+            .map(String::from)
+            .collect(),
+        remote_check_timeout_secs: 10,
+        clone_timeout_secs: 60,
+        credentials_encryption_key: Some("test-credentials-key".to_string()),
+        swhid_enabled: true,
+        software_heritage_url: None,
+        software_heritage_token: None,
+        software_heritage_max_lookups: 100,
+        telemetry_url: None,
+        telemetry_interval_hours: 24,
+        retention_results_days: 0,
+        retention_scans_days: 0,
+        retention_purge_interval_hours: 0,
+        result_partitioning: ResultPartitioning::default(),
+        result_archive: "none".to_string(),
+        result_archive_dir: workspace_dir.join("archive"),
+        result_archive_s3_bucket: None,
+        result_archive_s3_region: "us-east-1".to_string(),
+        result_archive_s3_endpoint: None,
+        result_archive_s3_prefix: String::new(),
+        result_archive_s3_access_key_id: None,
+        result_archive_s3_secret_access_key: None,
+        result_archive_after_days: 0,
+        result_archive_interval_hours: 0,
+        push_webhook_secret: None,
+    }
+}
+
+/// App state backed by a migrated in-memory database. Semgrep is replaced by
+/// an empty `MockScanner`; optional scanners are disabled.
+pub async fn test_state(config: Config, fossology_scanner: Arc<dyn Scanner>) -> AppState {
+    // A single connection keeps every query on the same in-memory database
+    let db = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect(&config.database_url)
+        .await
+        .expect("failed to open in-memory database");
+    crate::db::run_migrations(&db)
+        .await
+        .expect("failed to run migrations");
+
+    let result_archive =
+        storage::archive_from_config(&config).expect("invalid result archive configuration");
+    AppState {
+        result_store: storage::result_store_from_config(&config, &db, result_archive.clone())
+            .expect("invalid result store configuration"),
+        result_archive,
+        db,
+        config: Arc::new(config),
+        fossology_scanner,
+        semgrep_scanner: Arc::new(MockScanner::new("semgrep")),
+        malware_scanner: None,
+        documentation_scanner: Arc::new(DocumentationLicenseScanner::new()),
+        package_scanner: None,
+        model_scanner: Arc::new(ModelLicenseScanner::new()),
+        progress: ProgressHub::new(),
+    }
+}
+
+/// Create a git repository at `path` with one commit containing `files`
+/// (relative path, contents). Returns a `file://` URL that can be scanned.
+pub fn init_fixture_repo(path: &Path, files: &[(&str, &str)]) -> String {
+    git2::Repository::init(path).expect("failed to init fixture repository");
+    commit_fixture_changes(path, files, &[], "Initial commit");
+
+    format!("file://{}", path.display())
+}
+
+/// Add a commit to a fixture repository that writes `files` and deletes
+/// `removed` (relative paths)
+pub fn commit_fixture_changes(
+    path: &Path,
+    files: &[(&str, &str)],
+    removed: &[&str],
+    message: &str,
+) {
+    let repo = git2::Repository::open(path).expect("failed to open fixture repository");
+
+    for (relative, contents) in files {
+        let file = path.join(relative);
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).expect("failed to create fixture directory");
+        }
+        std::fs::write(&file, contents).expect("failed to write fixture file");
+    }
+    for relative in removed {
+        std::fs::remove_file(path.join(relative)).expect("failed to remove fixture file");
+    }
+
+    let mut index = repo.index().expect("failed to open index");
+    index
+        .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+        .expect("failed to stage fixture files");
+    index
+        .update_all(["*"].iter(), None)
+        .expect("failed to stage removed fixture files");
+    index.write().expect("failed to write index");
+    let tree_id = index.write_tree().expect("failed to write tree");
+    let tree = repo.find_tree(tree_id).expect("failed to find tree");
+    let signature =
+        git2::Signature::now("Fixture", "fixture@example.com").expect("failed to create signature");
+    let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )
+    .expect("failed to commit fixture");
+}
+
+/// Tag the HEAD commit of a fixture repository
+pub fn tag_fixture(path: &Path, tag: &str) {
+    let repo = git2::Repository::open(path).expect("failed to open fixture repository");
+    let head = repo
+        .head()
+        .and_then(|h| h.peel_to_commit())
+        .expect("fixture repository has no commits");
+    repo.tag_lightweight(tag, head.as_object(), false)
+        .expect("failed to tag fixture");
+}
+
+/// Add the repository at `url` as a submodule at `relative` of a fixture
+/// repository and commit it
+pub fn add_fixture_submodule(path: &Path, url: &str, relative: &str) {
+    let repo = git2::Repository::open(path).expect("failed to open fixture repository");
+    let mut submodule = repo
+        .submodule(url, Path::new(relative), true)
+        .expect("failed to add submodule");
+    submodule.clone(None).expect("failed to clone submodule");
+    submodule.add_finalize().expect("failed to stage submodule");
+
+    let mut index = repo.index().expect("failed to open index");
+    let tree_id = index.write_tree().expect("failed to write tree");
+    let tree = repo.find_tree(tree_id).expect("failed to find tree");
+    let signature =
+        git2::Signature::now("Fixture", "fixture@example.com").expect("failed to create signature");
+    let parent = repo
+        .head()
+        .and_then(|h| h.peel_to_commit())
+        .expect("fixture repository has no commits");
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        &format!("Add submodule {}", relative),
+        &tree,
+        &[&parent],
+    )
+    .expect("failed to commit submodule");
+}
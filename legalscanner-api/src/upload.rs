@@ -0,0 +1,401 @@
+//! Scans of uploaded source archives. Third-party code often arrives as a
+//! source drop rather than a repository: the archive is stored next to the
+//! workspaces until the scan job extracts it in place of a clone. Such scans
+//! carry an `upload://<name>` URL.
+
+use crate::config::Config;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+pub const UPLOAD_SCHEME: &str = "upload://";
+
+/// Suffixes stripped from the file name to name the upload
+const ARCHIVE_SUFFIXES: [&str; 4] = [".tar.gz", ".tgz", ".zip", ".tar"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+    Tar,
+}
+
+impl ArchiveFormat {
+    /// Recognize an archive by its first bytes
+    pub fn detect(header: &[u8]) -> Option<Self> {
+        if header.starts_with(b"PK\x03\x04") {
+            Some(Self::Zip)
+        } else if header.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::TarGz)
+        } else if header.get(257..262) == Some(b"ustar".as_slice()) {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
+    pub fn detect_file(path: &Path) -> io::Result<Option<Self>> {
+        let mut header = Vec::with_capacity(512);
+        File::open(path)?.take(512).read_to_end(&mut header)?;
+        Ok(Self::detect(&header))
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ExtractStats {
+    pub files: u64,
+    pub bytes: u64,
+    /// Symlinks, hard links and special files, which are not extracted
+    pub skipped: u64,
+}
+
+pub fn is_upload(git_url: &str) -> bool {
+    git_url.starts_with(UPLOAD_SCHEME)
+}
+
+/// Scan URL of an upload, named after the archive file unless a name is given
+pub fn upload_url(name: Option<&str>, file_name: Option<&str>) -> String {
+    let name = name
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .or_else(|| file_name.map(name_from_file))
+        .unwrap_or_default();
+
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let name = name.trim_matches(|c| c == '-' || c == '.');
+
+    format!(
+        "{}{}",
+        UPLOAD_SCHEME,
+        if name.is_empty() { "upload" } else { name }
+    )
+}
+
+fn name_from_file(file_name: &str) -> String {
+    let base = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+    ARCHIVE_SUFFIXES
+        .iter()
+        .find_map(|suffix| {
+            base.len()
+                .checked_sub(suffix.len())
+                .filter(|&at| base[at..].eq_ignore_ascii_case(suffix))
+                .map(|at| &base[..at])
+        })
+        .unwrap_or(base)
+        .to_string()
+}
+
+/// Where an uploaded archive waits for its scan job
+pub fn archive_path(base_dir: &Path, scan_id: &str) -> PathBuf {
+    base_dir.join("uploads").join(scan_id)
+}
+
+/// Extract the uploaded archive of a scan into its workspace and delete the
+/// archive
+pub async fn extract_upload(
+    config: &Config,
+    scan_id: &str,
+    workspace_path: &Path,
+) -> Result<ExtractStats, String> {
+    let archive = archive_path(&config.temp_workspace_dir, scan_id);
+    let dest = workspace_path.to_path_buf();
+    let max_bytes = config.upload_max_extracted_mb * 1024 * 1024;
+
+    let source = archive.clone();
+    let result = tokio::task::spawn_blocking(move || extract(&source, &dest, max_bytes))
+        .await
+        .map_err(|e| format!("Archive extraction panicked: {}", e))?;
+
+    if let Err(e) = tokio::fs::remove_file(&archive).await {
+        tracing::warn!("Failed to remove uploaded archive {:?}: {}", archive, e);
+    }
+    result
+}
+
+/// Extract a zip, tar or tar.gz archive into `dest`. Entries must stay
+/// inside `dest`; links are skipped. A single top-level directory, as in
+/// `project-1.2.0/...`, is removed so paths match the repository layout.
+pub fn extract(archive: &Path, dest: &Path, max_bytes: u64) -> Result<ExtractStats, String> {
+    let format = ArchiveFormat::detect_file(archive)
+        .map_err(|e| format!("Failed to read archive: {}", e))?
+        .ok_or_else(|| "Unsupported archive format, expected zip, tar or tar.gz".to_string())?;
+
+    let mut extractor = Extractor {
+        dest,
+        remaining: max_bytes,
+        max_bytes,
+        stats: ExtractStats::default(),
+    };
+    let file = File::open(archive).map_err(|e| format!("Failed to open archive: {}", e))?;
+    match format {
+        ArchiveFormat::Zip => extractor.zip(file)?,
+        ArchiveFormat::TarGz => extractor.tar(flate2::read::GzDecoder::new(file))?,
+        ArchiveFormat::Tar => extractor.tar(file)?,
+    }
+
+    flatten_single_root(dest).map_err(|e| format!("Failed to arrange extracted files: {}", e))?;
+    Ok(extractor.stats)
+}
+
+struct Extractor<'a> {
+    dest: &'a Path,
+    remaining: u64,
+    max_bytes: u64,
+    stats: ExtractStats,
+}
+
+impl Extractor<'_> {
+    fn zip(&mut self, file: File) -> Result<(), String> {
+        let mut archive =
+            zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip archive: {}", e))?;
+
+        for i in 0..archive.len() {
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| format!("Invalid zip archive: {}", e))?;
+            let path = entry
+                .enclosed_name()
+                .and_then(|p| relative_path(&p))
+                .ok_or_else(|| unsafe_entry(entry.name()))?;
+
+            if entry.is_dir() {
+                self.create_dir(&path)?;
+            } else if entry.is_symlink() {
+                self.stats.skipped += 1;
+            } else {
+                self.write_file(&path, &mut entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn tar(&mut self, reader: impl Read) -> Result<(), String> {
+        let mut archive = tar::Archive::new(reader);
+        let entries = archive
+            .entries()
+            .map_err(|e| format!("Invalid tar archive: {}", e))?;
+
+        for entry in entries {
+            let mut entry = entry.map_err(|e| format!("Invalid tar archive: {}", e))?;
+            let raw_path = entry
+                .path()
+                .map_err(|e| format!("Invalid tar archive: {}", e))?
+                .into_owned();
+            let entry_type = entry.header().entry_type();
+            // PAX headers and GNU long names are applied by the tar crate
+            if !(entry_type.is_file() || entry_type.is_dir()) {
+                self.stats.skipped += 1;
+                continue;
+            }
+
+            let Some(path) = relative_path(&raw_path) else {
+                // `./` itself
+                if entry_type.is_dir() && raw_path.components().all(|c| c == Component::CurDir) {
+                    continue;
+                }
+                return Err(unsafe_entry(&raw_path.display().to_string()));
+            };
+
+            if entry_type.is_dir() {
+                self.create_dir(&path)?;
+            } else {
+                self.write_file(&path, &mut entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<(), String> {
+        fs::create_dir_all(self.dest.join(path))
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))
+    }
+
+    fn write_file(&mut self, path: &Path, reader: &mut impl Read) -> Result<(), String> {
+        let target = self.dest.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let mut file = File::create(&target)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        // One byte over the budget tells an exact fit from an overflow
+        let written = io::copy(&mut reader.take(self.remaining + 1), &mut file)
+            .map_err(|e| format!("Failed to extract {}: {}", path.display(), e))?;
+        if written > self.remaining {
+            drop(file);
+            let _ = fs::remove_file(&target);
+            return Err(format!(
+                "Archive exceeds {} MB once extracted",
+                self.max_bytes / (1024 * 1024)
+            ));
+        }
+        file.flush()
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+        self.remaining -= written;
+        self.stats.files += 1;
+        self.stats.bytes += written;
+        Ok(())
+    }
+}
+
+fn unsafe_entry(name: &str) -> String {
+    format!("Archive entry '{}' points outside the archive", name)
+}
+
+/// The entry path without `.` components, or `None` if it is absolute,
+/// climbs out with `..` or is empty
+fn relative_path(path: &Path) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+/// Move the contents of a lone top-level directory up into `dest`
+fn flatten_single_root(dest: &Path) -> io::Result<()> {
+    let entries: Vec<fs::DirEntry> = fs::read_dir(dest)?.collect::<Result<_, _>>()?;
+    let [root] = entries.as_slice() else {
+        return Ok(());
+    };
+    if !root.file_type()?.is_dir() {
+        return Ok(());
+    }
+
+    // Staged outside `dest`, a child may have the root's name
+    let staging = dest.with_extension("root");
+    fs::rename(root.path(), &staging)?;
+    for child in fs::read_dir(&staging)? {
+        let child = child?;
+        fs::rename(child.path(), dest.join(child.file_name()))?;
+    }
+    fs::remove_dir(&staging)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zip::write::SimpleFileOptions;
+
+    fn zip_archive(path: &Path, files: &[(&str, &str)]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    fn tar_gz_archive(path: &Path, files: &[(&str, &str)]) {
+        let encoder =
+            flate2::write::GzEncoder::new(File::create(path).unwrap(), Default::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    fn read(path: &Path) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn test_extract_tar_gz_strips_single_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("drop.tgz");
+        tar_gz_archive(
+            &archive,
+            &[
+                ("vendor-1.2/LICENSE", "MIT License"),
+                ("vendor-1.2/src/lib.rs", "fn main() {}"),
+            ],
+        );
+
+        let dest = dir.path().join("scan-1");
+        fs::create_dir(&dest).unwrap();
+        let stats = extract(&archive, &dest, 1024).unwrap();
+
+        assert_eq!(stats.files, 2);
+        assert_eq!(read(&dest.join("LICENSE")), "MIT License");
+        assert_eq!(read(&dest.join("src/lib.rs")), "fn main() {}");
+        assert!(!dest.join("vendor-1.2").exists());
+    }
+
+    #[test]
+    fn test_extract_zip_keeps_several_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("drop.zip");
+        zip_archive(&archive, &[("a/x.c", "x"), ("b/y.c", "y")]);
+
+        let dest = dir.path().join("scan-1");
+        fs::create_dir(&dest).unwrap();
+        extract(&archive, &dest, 1024).unwrap();
+
+        assert_eq!(read(&dest.join("a/x.c")), "x");
+        assert_eq!(read(&dest.join("b/y.c")), "y");
+    }
+
+    #[test]
+    fn test_extract_rejects_traversal_and_oversized_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("scan-1");
+        fs::create_dir(&dest).unwrap();
+
+        let archive = dir.path().join("evil.zip");
+        zip_archive(&archive, &[("../evil.sh", "rm -rf /")]);
+        assert!(extract(&archive, &dest, 1024)
+            .unwrap_err()
+            .contains("outside the archive"));
+        assert!(!dir.path().join("evil.sh").exists());
+
+        let archive = dir.path().join("big.zip");
+        zip_archive(&archive, &[("a.txt", "0123456789")]);
+        assert!(extract(&archive, &dest, 9).unwrap_err().contains("exceeds"));
+
+        let archive = dir.path().join("notes.txt");
+        fs::write(&archive, "not an archive").unwrap();
+        assert!(extract(&archive, &dest, 1024)
+            .unwrap_err()
+            .contains("Unsupported"));
+    }
+
+    #[test]
+    fn test_upload_url() {
+        assert_eq!(
+            upload_url(None, Some("vendor-lib-1.2.tar.gz")),
+            "upload://vendor-lib-1.2"
+        );
+        assert_eq!(
+            upload_url(Some("Acme SDK"), Some("x.zip")),
+            "upload://Acme-SDK"
+        );
+        assert_eq!(upload_url(None, None), "upload://upload");
+        assert!(is_upload("upload://vendor"));
+        assert!(!is_upload("https://github.com/acme/app"));
+    }
+}
@@ -1,23 +1,48 @@
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
 use argon2::{
     password_hash::{PasswordHasher, SaltString},
     Argon2,
 };
 use base64::{engine::general_purpose, Engine};
 use rand::Rng;
+use sha2::{Digest, Sha256};
 
 const API_KEY_LENGTH: usize = 32;
 const API_KEY_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+const SHARE_TOKEN_LENGTH: usize = 43; // ~256 bits of entropy
+const NONCE_LENGTH: usize = 12;
 
-/// Generate a random API key
-pub fn generate_api_key() -> String {
+fn random_chars(len: usize) -> String {
     let mut rng = rand::thread_rng();
-    let key: String = (0..API_KEY_LENGTH)
+    (0..len)
         .map(|_| {
             let idx = rng.gen_range(0..API_KEY_CHARS.len());
             API_KEY_CHARS[idx] as char
         })
-        .collect();
-    format!("lgs_{}", key) // lgs = legal scanner
+        .collect()
+}
+
+/// Generate a random API key
+pub fn generate_api_key() -> String {
+    format!("lgs_{}", random_chars(API_KEY_LENGTH)) // lgs = legal scanner
+}
+
+/// Generate an unguessable token for a public scan share link
+pub fn generate_share_token() -> String {
+    format!("lgsh_{}", random_chars(SHARE_TOKEN_LENGTH)) // lgsh = legal scanner share
+}
+
+/// Hash of a share token to store and look it up by. Tokens carry ~256
+/// bits of entropy, so a fast hash is enough and the public endpoint does
+/// not run Argon2 for every request.
+pub fn hash_share_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
 }
 
 /// Hash an API key using Argon2
@@ -43,6 +68,52 @@ pub fn verify_api_key(key: &str, salt: &str) -> Result<String, argon2::password_
     hash_api_key(key, salt)
 }
 
+/// AES-256-GCM cipher keyed with the SHA-256 of `key`, so any passphrase
+/// can be configured
+fn secret_cipher(key: &str) -> Aes256Gcm {
+    let key = Sha256::digest(key.as_bytes());
+    Aes256Gcm::new(&key)
+}
+
+/// Encrypt a stored secret. `context`, e.g. the ID of the row, is
+/// authenticated too, so the ciphertext cannot be copied to another row.
+/// Returns base64 of the random nonce followed by the ciphertext.
+pub fn encrypt_secret(key: &str, context: &str, secret: &str) -> Result<String, String> {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rand::thread_rng().fill(&mut nonce);
+    let payload = Payload {
+        msg: secret.as_bytes(),
+        aad: context.as_bytes(),
+    };
+    let ciphertext = secret_cipher(key)
+        .encrypt(&Nonce::from(nonce), payload)
+        .map_err(|_| "Failed to encrypt secret".to_string())?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(general_purpose::STANDARD.encode(sealed))
+}
+
+/// Decrypt a secret sealed by [`encrypt_secret`] with the same key and context
+pub fn decrypt_secret(key: &str, context: &str, encrypted: &str) -> Result<String, String> {
+    let sealed = general_purpose::STANDARD
+        .decode(encrypted)
+        .map_err(|e| format!("Invalid encrypted secret: {}", e))?;
+    if sealed.len() < NONCE_LENGTH {
+        return Err("Invalid encrypted secret: too short".to_string());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+    let nonce: [u8; NONCE_LENGTH] = nonce.try_into().expect("split at the nonce length");
+    let payload = Payload {
+        msg: ciphertext,
+        aad: context.as_bytes(),
+    };
+    let secret = secret_cipher(key)
+        .decrypt(&Nonce::from(nonce), payload)
+        .map_err(|_| "Secret cannot be decrypted, was the key changed?".to_string())?;
+    String::from_utf8(secret).map_err(|_| "Decrypted secret is not UTF-8".to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,6 +125,22 @@ mod tests {
         assert_eq!(key.len(), 4 + API_KEY_LENGTH);
     }
 
+    #[test]
+    fn test_generate_share_token() {
+        let token = generate_share_token();
+        assert!(token.starts_with("lgsh_"));
+        assert_eq!(token.len(), 5 + SHARE_TOKEN_LENGTH);
+        assert_ne!(token, generate_share_token());
+    }
+
+    #[test]
+    fn test_hash_share_token() {
+        let hash = hash_share_token("lgsh_token");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_share_token("lgsh_token"));
+        assert_ne!(hash, hash_share_token("lgsh_other"));
+    }
+
     #[test]
     fn test_hash_and_verify() {
         let key = "test_api_key_123";
@@ -65,4 +152,21 @@ mod tests {
         // Same key and salt should produce same hash
         assert_eq!(hash1, hash2);
     }
+
+    #[test]
+    fn test_encrypt_and_decrypt_secret() {
+        let encrypted = encrypt_secret("key", "cred-1", "ghp_secret").unwrap();
+        assert!(!encrypted.contains("ghp_secret"));
+        assert_ne!(
+            encrypted,
+            encrypt_secret("key", "cred-1", "ghp_secret").unwrap()
+        );
+        assert_eq!(
+            decrypt_secret("key", "cred-1", &encrypted).unwrap(),
+            "ghp_secret"
+        );
+
+        assert!(decrypt_secret("other-key", "cred-1", &encrypted).is_err());
+        assert!(decrypt_secret("key", "cred-2", &encrypted).is_err());
+    }
 }
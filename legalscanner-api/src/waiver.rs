@@ -0,0 +1,165 @@
+//! Waivers: findings a reviewer accepted, with a justification and an
+//! optional expiry. A waiver belongs to one scan or to every scan of a
+//! repository and matches findings by path, ECC check ID and/or license.
+//! Waivers are matched when results are read, so creating or deleting one
+//! takes effect on existing scans: waived findings are flagged in results
+//! and exports and left out of the risk score.
+
+use crate::api::handlers::risk::{calculate_risk_score, store_risk_assessment};
+use crate::db::models::{Scan, ScanResult, Waiver};
+use crate::error::AppError;
+use crate::export::sarif::repository_path;
+use crate::scan_diff::normalize_repository_url;
+use crate::AppState;
+use sqlx::SqlitePool;
+
+/// Whether `waiver` covers `result`, a finding at the repository-relative
+/// `path`. Every criterion the waiver sets must match; a `file_path` ending
+/// in `/` covers the whole directory.
+pub fn matches(waiver: &Waiver, path: &str, result: &ScanResult) -> bool {
+    let eq_ignore_case = |value: &Option<String>, wanted: &str| {
+        value
+            .as_deref()
+            .is_some_and(|v| v.eq_ignore_ascii_case(wanted))
+    };
+
+    waiver.file_path.as_deref().is_none_or(|p| {
+        if p.ends_with('/') {
+            path.starts_with(p)
+        } else {
+            path == p
+        }
+    }) && waiver
+        .check_id
+        .as_deref()
+        .is_none_or(|c| result.ecc_check_id.as_deref() == Some(c))
+        && waiver.license.as_deref().is_none_or(|l| {
+            eq_ignore_case(&result.license_spdx_id, l) || eq_ignore_case(&result.license_name, l)
+        })
+}
+
+/// Set `waiver_id` on the findings covered by an unexpired waiver of the
+/// scan or its repository. Returns how many findings are waived.
+pub async fn mark(
+    pool: &SqlitePool,
+    scan: &Scan,
+    results: &mut [ScanResult],
+) -> Result<usize, sqlx::Error> {
+    let waivers =
+        Waiver::find_active(pool, &scan.id, &normalize_repository_url(&scan.git_url)).await?;
+    Ok(mark_waived(
+        &waivers,
+        &scan.id,
+        scan.scan_path.as_deref(),
+        results,
+    ))
+}
+
+fn mark_waived(
+    waivers: &[Waiver],
+    scan_id: &str,
+    scan_path: Option<&str>,
+    results: &mut [ScanResult],
+) -> usize {
+    if waivers.is_empty() {
+        return 0;
+    }
+
+    let mut waived = 0;
+    for result in results.iter_mut() {
+        let path = repository_path(&result.file_path, scan_id, scan_path);
+        result.waiver_id = waivers
+            .iter()
+            .find(|w| matches(w, &path, result))
+            .map(|w| w.id.clone());
+        if result.waiver_id.is_some() {
+            waived += 1;
+        }
+    }
+    waived
+}
+
+/// Recalculate the stored risk score of the completed scans `waiver` applies
+/// to, after it was created or deleted. Returns how many scans were scored.
+pub async fn rescore(state: &AppState, waiver: &Waiver) -> Result<usize, AppError> {
+    let pool = &state.db;
+    let scans = match (&waiver.scan_id, &waiver.repository) {
+        (Some(scan_id), _) => Scan::find_by_id(pool, scan_id).await?.into_iter().collect(),
+        (None, Some(repository)) => {
+            Scan::find_by_repositories(pool, std::slice::from_ref(repository)).await?
+        }
+        (None, None) => Vec::new(),
+    };
+
+    let mut rescored = 0;
+    // Scans whose findings were purged or archived keep their assessment
+    for scan in scans.iter().filter(|s| {
+        s.status == "completed" && s.results_purged_at.is_none() && s.results_archived_at.is_none()
+    }) {
+        let risk = calculate_risk_score(state, &scan.id).await?;
+        store_risk_assessment(pool, &scan.id, &risk).await?;
+        rescored += 1;
+    }
+    Ok(rescored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn waiver(file_path: Option<&str>, check_id: Option<&str>, license: Option<&str>) -> Waiver {
+        Waiver {
+            id: "w-1".to_string(),
+            scan_id: Some("scan-1".to_string()),
+            repository: None,
+            file_path: file_path.map(str::to_string),
+            check_id: check_id.map(str::to_string),
+            license: license.map(str::to_string),
+            justification: "Reviewed".to_string(),
+            expires_at: None,
+            created_at: String::new(),
+        }
+    }
+
+    fn finding(file_path: &str, check_id: Option<&str>, license: Option<&str>) -> ScanResult {
+        let result_type = if check_id.is_some() { "ecc" } else { "license" };
+        ScanResult {
+            license_name: license.map(str::to_string),
+            ecc_check_id: check_id.map(str::to_string),
+            ..ScanResult::for_test(result_type, file_path)
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let gpl = finding("vendor/gpl.c", None, Some("GPL-3.0-only"));
+        let aes = finding("src/aes.rs", Some("ecc.crypto.aes"), None);
+
+        let vendor = waiver(Some("vendor/"), None, None);
+        assert!(matches(&vendor, "vendor/gpl.c", &gpl));
+        let vendor_file = waiver(Some("vendor"), None, None);
+        assert!(!matches(&vendor_file, "vendor/gpl.c", &gpl));
+        let gpl_waiver = waiver(None, None, Some("gpl-3.0-only"));
+        assert!(matches(&gpl_waiver, "vendor/gpl.c", &gpl));
+        let vendor_mit = waiver(Some("vendor/"), None, Some("MIT"));
+        assert!(!matches(&vendor_mit, "vendor/gpl.c", &gpl));
+        let aes_waiver = waiver(None, Some("ecc.crypto.aes"), None);
+        assert!(matches(&aes_waiver, "src/aes.rs", &aes));
+        assert!(!matches(&aes_waiver, "vendor/gpl.c", &gpl));
+    }
+
+    #[test]
+    fn test_mark_waived() {
+        let mut results = vec![
+            finding("/work/scan-1/vendor/gpl.c", None, Some("GPL-3.0-only")),
+            finding("/work/scan-1/src/lib.rs", None, Some("GPL-3.0-only")),
+        ];
+
+        // Scan limited to services/api
+        let waivers = vec![waiver(Some("services/api/vendor/"), None, None)];
+        let waived = mark_waived(&waivers, "scan-1", Some("services/api"), &mut results);
+        assert_eq!(waived, 1);
+        assert_eq!(results[0].waiver_id.as_deref(), Some("w-1"));
+        assert_eq!(results[1].waiver_id, None);
+    }
+}
@@ -0,0 +1,408 @@
+//! Scan completion callbacks. Scans created with a `callback_url` get a JSON
+//! POST when they complete or fail, signed with `WEBHOOK_SECRET` the same
+//! way signed SBOM exports are. Failed deliveries are retried with
+//! exponential backoff and every attempt is recorded in `webhook_deliveries`.
+//!
+//! Operators can replace the JSON payload of an event with a stored
+//! [`NotificationTemplate`], e.g. Slack blocks for an incoming-webhook URL.
+//! Templates reference payload fields as `{{scan_id}}`, `{{links.results}}`
+//! and so on; see [`TEMPLATE_VARIABLES`].
+
+use crate::codeowners::{scan_owners, violating_owners};
+use crate::config::Config;
+use crate::db::models::{NotificationTemplate, Scan, WebhookDelivery};
+use crate::export::processors::{HmacSigner, SIGNATURE_HEADER};
+use crate::screening::truncate;
+use crate::AppState;
+use serde::Serialize;
+use std::time::Duration;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Header naming the callback event, e.g. `scan.completed`
+pub const EVENT_HEADER: &str = "X-LegalScanner-Event";
+
+/// Events a callback is sent for
+pub const CALLBACK_EVENTS: &[&str] = &["scan.completed", "scan.failed"];
+
+/// Content type of the built-in payload and the default for templates
+pub const DEFAULT_CONTENT_TYPE: &str = "application/json";
+
+/// Placeholders a notification template may use
+pub const TEMPLATE_VARIABLES: &[&str] = &[
+    "event",
+    "scan_id",
+    "git_url",
+    "status",
+    "error_message",
+    "completed_at",
+    "risk_score",
+    "risk_level",
+    "owners",
+    "links.scan",
+    "links.results",
+    "links.sbom",
+    "links.gate",
+];
+
+/// Payload POSTed to a scan's callback URL
+#[derive(Debug, Serialize)]
+pub struct CallbackPayload {
+    pub event: String,
+    pub scan_id: String,
+    pub git_url: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk_score: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk_level: Option<String>,
+    /// CODEOWNERS owners of files with gate violations, for routing
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    pub links: CallbackLinks,
+}
+
+/// API URLs of the scan, absolute when `PUBLIC_BASE_URL` is set
+#[derive(Debug, Serialize)]
+pub struct CallbackLinks {
+    pub scan: String,
+    pub results: String,
+    pub sbom: String,
+    pub gate: String,
+}
+
+impl CallbackPayload {
+    pub fn new(scan: &Scan, base_url: Option<&str>) -> Self {
+        let scan_url = format!("{}/api/v1/scans/{}", base_url.unwrap_or_default(), scan.id);
+        Self {
+            event: format!("scan.{}", scan.status),
+            scan_id: scan.id.clone(),
+            git_url: scan.git_url.clone(),
+            status: scan.status.clone(),
+            error_message: scan.error_message.clone(),
+            completed_at: scan.completed_at.clone(),
+            risk_score: scan.risk_score,
+            risk_level: scan.risk_level.clone(),
+            owners: Vec::new(),
+            links: CallbackLinks {
+                results: format!("{}/results", scan_url),
+                sbom: format!("{}/sbom", scan_url),
+                gate: format!("{}/gate", scan_url),
+                scan: scan_url,
+            },
+        }
+    }
+}
+
+impl CallbackPayload {
+    /// Value of a template variable; missing optional fields render empty
+    fn variable(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "event" => self.event.clone(),
+            "scan_id" => self.scan_id.clone(),
+            "git_url" => self.git_url.clone(),
+            "status" => self.status.clone(),
+            "error_message" => self.error_message.clone().unwrap_or_default(),
+            "completed_at" => self.completed_at.clone().unwrap_or_default(),
+            "risk_score" => self
+                .risk_score
+                .map(|score| score.to_string())
+                .unwrap_or_default(),
+            "risk_level" => self.risk_level.clone().unwrap_or_default(),
+            "owners" => self.owners.join(", "),
+            "links.scan" => self.links.scan.clone(),
+            "links.results" => self.links.results.clone(),
+            "links.sbom" => self.links.sbom.clone(),
+            "links.gate" => self.links.gate.clone(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+fn is_json(content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    essence.eq_ignore_ascii_case("application/json") || essence.ends_with("+json")
+}
+
+/// Substitute `{{variable}}` placeholders in `template`. For JSON content
+/// types values are escaped so they can sit inside a JSON string literal.
+pub fn render_template(
+    template: &str,
+    content_type: &str,
+    payload: &CallbackPayload,
+) -> Result<String, String> {
+    let escape = is_json(content_type);
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| "Unterminated '{{' in template".to_string())?;
+        let name = after[..end].trim();
+        let value = payload
+            .variable(name)
+            .ok_or_else(|| format!("Unknown template variable '{}'", name))?;
+
+        if escape {
+            let quoted = serde_json::to_string(&value).map_err(|e| e.to_string())?;
+            rendered.push_str(&quoted[1..quoted.len() - 1]);
+        } else {
+            rendered.push_str(&value);
+        }
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+
+    Ok(rendered)
+}
+
+/// Check a template before it is stored: every placeholder must be known and,
+/// for JSON content types, the rendered body must be valid JSON
+pub fn validate_template(content_type: &str, body: &str) -> Result<(), String> {
+    if content_type.trim().is_empty() {
+        return Err("content_type must not be empty".to_string());
+    }
+    if reqwest::header::HeaderValue::from_str(content_type).is_err() {
+        return Err(format!("Invalid content_type '{}'", content_type));
+    }
+
+    let rendered = render_template(body, content_type, &sample_payload())?;
+    if is_json(content_type) {
+        serde_json::from_str::<serde_json::Value>(&rendered)
+            .map_err(|e| format!("Template does not render to valid JSON: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Payload with every field set and characters that need JSON escaping
+fn sample_payload() -> CallbackPayload {
+    let scan_url = "https://legalscanner.example.com/api/v1/scans/sample".to_string();
+    CallbackPayload {
+        event: "scan.failed".to_string(),
+        scan_id: "sample".to_string(),
+        git_url: "https://github.com/example/repo.git".to_string(),
+        status: "failed".to_string(),
+        error_message: Some("clone failed: \"repo\" not found\n".to_string()),
+        completed_at: Some("2025-01-01T00:00:00Z".to_string()),
+        risk_score: Some(42),
+        risk_level: Some("medium".to_string()),
+        owners: vec!["@acme/payments".to_string(), "@alice".to_string()],
+        links: CallbackLinks {
+            results: format!("{}/results", scan_url),
+            sbom: format!("{}/sbom", scan_url),
+            gate: format!("{}/gate", scan_url),
+            scan: scan_url,
+        },
+    }
+}
+
+/// Request body and content type for `payload`: the event's template if one
+/// is stored, the built-in JSON otherwise
+async fn render_body(
+    state: &AppState,
+    payload: &CallbackPayload,
+) -> Result<(Vec<u8>, String), serde_json::Error> {
+    match NotificationTemplate::find_by_event(&state.db, &payload.event).await {
+        Ok(Some(template)) => {
+            match render_template(&template.body, &template.content_type, payload) {
+                Ok(body) => return Ok((body.into_bytes(), template.content_type)),
+                Err(e) => tracing::warn!(
+                    "Notification template for {} failed to render, sending default payload: {}",
+                    payload.event,
+                    e
+                ),
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!(
+            "Failed to load notification template for {}, sending default payload: {}",
+            payload.event,
+            e
+        ),
+    }
+
+    Ok((
+        serde_json::to_vec(payload)?,
+        DEFAULT_CONTENT_TYPE.to_string(),
+    ))
+}
+
+/// Callback URLs must be absolute http(s) URLs
+pub fn validate_callback_url(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid callback_url: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err("callback_url must be an http:// or https:// URL".to_string());
+    }
+    Ok(())
+}
+
+/// Notify the scan's callback URL, if it has one, that the scan completed or
+/// failed. Retries until the receiver answers with a 2xx status or
+/// `WEBHOOK_MAX_ATTEMPTS` is reached.
+pub async fn notify(state: &AppState, scan_id: &str) {
+    let scan = match Scan::find_by_id(&state.db, scan_id).await {
+        Ok(Some(scan)) => scan,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!("Failed to load scan {} for its callback: {}", scan_id, e);
+            return;
+        }
+    };
+    let Some(url) = scan.callback_url.clone() else {
+        return;
+    };
+
+    let mut payload = CallbackPayload::new(&scan, state.config.public_base_url.as_deref());
+    match scan_owners(state, &scan).await {
+        Ok(Some((_, summaries))) => payload.owners = violating_owners(&summaries),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to attribute findings of scan {}: {}", scan_id, e),
+    }
+    let (body, content_type) = match render_body(state, &payload).await {
+        Ok(rendered) => rendered,
+        Err(e) => {
+            tracing::error!("Failed to serialize callback for scan {}: {}", scan_id, e);
+            return;
+        }
+    };
+
+    let max_attempts = state.config.webhook_max_attempts.max(1);
+    for attempt in 1..=max_attempts {
+        let (status, code, response_body, error) =
+            match send(&state.config, &url, &payload.event, &content_type, &body).await {
+                Ok((code, response)) if (200..300).contains(&code) => {
+                    ("delivered", Some(code as i32), Some(response), None)
+                }
+                Ok((code, response)) => ("failed", Some(code as i32), Some(response), None),
+                Err(e) => ("failed", None, None, Some(e.to_string())),
+            };
+
+        if let Err(e) = WebhookDelivery::record(
+            &state.db,
+            scan_id,
+            &url,
+            &payload.event,
+            attempt,
+            status,
+            code,
+            response_body.as_deref().map(truncate),
+            error.as_deref(),
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to record callback delivery for scan {}: {}",
+                scan_id,
+                e
+            );
+        }
+
+        if status == "delivered" {
+            tracing::info!("Delivered {} callback for scan {}", payload.event, scan_id);
+            return;
+        }
+
+        tracing::warn!(
+            "Callback attempt {}/{} for scan {} failed: {}",
+            attempt,
+            max_attempts,
+            scan_id,
+            error.unwrap_or_else(|| format!("HTTP {}", code.unwrap_or_default()))
+        );
+        if attempt < max_attempts {
+            tokio::time::sleep(retry_delay(state.config.webhook_retry_delay_secs, attempt)).await;
+        }
+    }
+
+    tracing::error!(
+        "Giving up on {} callback for scan {} after {} attempts",
+        payload.event,
+        scan_id,
+        max_attempts
+    );
+}
+
+async fn send(
+    config: &Config,
+    url: &str,
+    event: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Result<(u16, String), reqwest::Error> {
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()?;
+
+    let mut builder = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, content_type)
+        .header(EVENT_HEADER, event)
+        .body(body.to_vec());
+    if let Some(secret) = &config.webhook_secret {
+        let signature = format!("sha256={}", HmacSigner::new(secret).sign(body));
+        builder = builder.header(SIGNATURE_HEADER, signature);
+    }
+
+    let response = builder.send().await?;
+    let code = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    Ok((code, body))
+}
+
+/// `WEBHOOK_RETRY_DELAY_SECS`, doubled after every failed attempt
+fn retry_delay(base_secs: u64, attempt: u32) -> Duration {
+    Duration::from_secs(base_secs << (attempt - 1).min(10))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_callback_url() {
+        assert!(validate_callback_url("https://ci.example.com/hooks/legal").is_ok());
+        assert!(validate_callback_url("http://localhost:9000/").is_ok());
+        assert!(validate_callback_url("ftp://example.com/hook").is_err());
+        assert!(validate_callback_url("/relative/path").is_err());
+    }
+
+    #[test]
+    fn test_render_template_escapes_json_values() {
+        let payload = sample_payload();
+        let slack = r#"{"text": "Scan {{scan_id}} {{status}}: {{error_message}}"}"#;
+        let rendered = render_template(slack, "application/json", &payload).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(
+            value["text"],
+            "Scan sample failed: clone failed: \"repo\" not found\n"
+        );
+
+        let text = render_template("{{ risk_level }} ({{risk_score}})", "text/plain", &payload);
+        assert_eq!(text.unwrap(), "medium (42)");
+    }
+
+    #[test]
+    fn test_validate_template() {
+        assert!(validate_template("application/json", r#"{"id": "{{scan_id}}"}"#).is_ok());
+        assert!(validate_template("text/plain", "Scan {{scan_id}} finished").is_ok());
+        assert!(validate_template("application/json", "{{scan_id}}").is_err());
+        assert!(validate_template("text/plain", "{{unknown}}").is_err());
+        assert!(validate_template("text/plain", "{{scan_id").is_err());
+        assert!(validate_template("", "body").is_err());
+    }
+
+    #[test]
+    fn test_retry_delay_doubles() {
+        assert_eq!(retry_delay(10, 1), Duration::from_secs(10));
+        assert_eq!(retry_delay(10, 3), Duration::from_secs(40));
+        assert_eq!(retry_delay(0, 2), Duration::ZERO);
+    }
+}
@@ -0,0 +1,241 @@
+//! Admin tools: integrity checks, telemetry and moving scans between
+//! instances
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use common::{get_json, license_result, request, request_as, run_scan, setup, FIXTURE_FILES};
+use http_body_util::BodyExt;
+use legalscanner_api::{
+    api::routes::create_router,
+    telemetry,
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::ServiceExt;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn test_integrity_check_reports_tampered_results() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![
+        license_result("LICENSE", "MIT", 1.0),
+        license_result("src/lib.rs", "MIT", 0.9),
+    ]));
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    let state = test_state(config, fossology).await;
+    let db = state.db.clone();
+    let app = create_router(state);
+    let admin = Some("admin-secret");
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+
+    let verify = "/api/v1/admin/integrity/verify";
+    let (status, _) = request_as(&app, "POST", verify, None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, report) = request_as(&app, "POST", verify, admin, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", report);
+    assert_eq!(report["database_ok"], true);
+    assert_eq!(report["scans_verified"], 1);
+    assert_eq!(report["discrepancies"], json!([]));
+
+    // Reviews change legitimately, the stored findings must not
+    sqlx::query("UPDATE scan_results SET review_status = 'approved' WHERE scan_id = ?")
+        .bind(scan_id)
+        .execute(&db)
+        .await
+        .unwrap();
+    let (_, report) = request_as(&app, "POST", verify, admin, None).await;
+    assert_eq!(report["discrepancies"], json!([]));
+
+    sqlx::query("UPDATE scan_results SET license_spdx_id = 'Apache-2.0' WHERE file_path = 'LICENSE'")
+        .execute(&db)
+        .await
+        .unwrap();
+    let (_, report) = request_as(&app, "POST", verify, admin, None).await;
+    assert_eq!(report["scans_verified"], 0);
+    let discrepancy = &report["discrepancies"][0];
+    assert_eq!(discrepancy["kind"], "checksum_mismatch");
+    assert_eq!(discrepancy["target_id"], scan_id);
+    assert_ne!(discrepancy["expected"], discrepancy["actual"]);
+
+    let events_uri = format!("/api/v1/admin/integrity/events?scan_id={}", scan_id);
+    let (status, events) = request_as(&app, "GET", &events_uri, admin, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(events["events"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_telemetry_reports_usage_without_repository_data() {
+    let collector = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/usage"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&collector)
+        .await;
+
+    let fossology = Arc::new(MockScanner::new("fossology").failing("cannot reach fossology"));
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    let state = test_state(config, fossology).await;
+    let app = create_router(state.clone());
+
+    let failed = run_scan(&app, json!({ "git_url": git_url })).await;
+    assert_eq!(failed["status"], "failed", "{}", failed);
+    let quick = run_scan(&app, json!({ "git_url": git_url, "quick": true })).await;
+    assert_eq!(quick["status"], "completed", "{}", quick);
+
+    // Nothing is sent until a URL is configured
+    assert!(!telemetry::report_if_due(&state).await.unwrap());
+    let (status, preview) =
+        request_as(&app, "GET", "/api/v1/admin/telemetry", Some("admin-secret"), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", preview);
+    assert_eq!(preview["scans_created"], 2);
+    assert_eq!(preview["scans_completed"], 1);
+    assert_eq!(preview["scans_failed"], 1);
+    assert_eq!(preview["error_classes"], json!({ "scanner_failed": 1 }));
+    assert_eq!(preview["scanner_failures"], json!({ "fossology": 1 }));
+
+    let mut config = (*state.config).clone();
+    config.telemetry_url = Some(format!("{}/usage", collector.uri()));
+    let state = legalscanner_api::AppState {
+        config: Arc::new(config),
+        ..state
+    };
+    assert!(telemetry::report_if_due(&state).await.unwrap());
+    // The period was reported, the next one is not due yet
+    assert!(!telemetry::report_if_due(&state).await.unwrap());
+
+    let requests = collector.received_requests().await.unwrap();
+    let report: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(report["installation_id"], preview["installation_id"]);
+    assert_eq!(report["scans_failed"], 1);
+    assert_eq!(report["error_classes"], preview["error_classes"]);
+    let body = String::from_utf8_lossy(&requests[0].body);
+    assert!(!body.contains("fixture"), "{}", body);
+    assert!(!body.contains("cannot reach"), "{}", body);
+}
+
+async fn import_archive(app: &Router, token: Option<&str>, archive: &[u8]) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/api/v1/admin/scans/import")
+        .header("content-type", "application/x-ndjson");
+    if let Some(token) = token {
+        builder = builder.header("authorization", format!("Bearer {}", token));
+    }
+    let response = app
+        .clone()
+        .oneshot(builder.body(Body::from(archive.to_vec())).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_scan_archive_moves_scan_to_another_instance() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![
+        license_result("src/lib.rs", "MIT", 1.0),
+        license_result("vendor/gpl.c", "GPL-3.0-only", 1.0),
+    ]));
+    let (source, git_url, dir) = setup(fossology.clone()).await;
+    let scan = run_scan(&source, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let (status, _) = request(
+        &source,
+        "POST",
+        &format!("/api/v1/scans/{}/results/review", scan_id),
+        Some(json!({
+            "status": "approved",
+            "result_type": "license",
+            "comment": "Build tooling only",
+            "author": "legal@example.com"
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let response = source
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/scans/{}/archive", scan_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+    let archive = response.into_body().collect().await.unwrap().to_bytes();
+    let records: Vec<Value> = archive
+        .split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).unwrap())
+        .collect();
+    assert_eq!(records[0]["record"], "scan");
+    assert!(records[0]["scan"].get("git_token").is_none());
+    let count = |record: &str| records.iter().filter(|r| r["record"] == record).count();
+    assert_eq!(count("result"), 2);
+    assert_eq!(count("comment"), 2);
+    assert!(count("event") > 0);
+
+    // The target instance knows nothing of the scan
+    let mut config = test_config(dir.path().join("target"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    let target = create_router(test_state(config, fossology).await);
+    let (status, _) = import_archive(&target, None, &archive).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, report) = import_archive(&target, Some("admin-secret"), b"{}").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", report);
+
+    let (status, report) = import_archive(&target, Some("admin-secret"), &archive).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", report);
+    assert_eq!(report["scan_id"], scan_id);
+    assert_eq!(report["results_imported"], 2);
+    assert_eq!(report["comments_imported"], 2);
+
+    let imported = get_json(&target, &format!("/api/v1/scans/{}", scan_id)).await;
+    assert_eq!(imported["status"], "completed");
+    assert_eq!(imported["risk_score"], scan["risk_score"]);
+    let results = get_json(&target, &format!("/api/v1/scans/{}/results", scan_id)).await;
+    let licenses = results["results"]["licenses"].as_array().unwrap();
+    assert_eq!(licenses.len(), 2);
+    assert!(licenses.iter().all(|f| f["review_status"] == "approved"));
+    let comments = get_json(
+        &target,
+        &format!(
+            "/api/v1/scans/{}/results/{}/comments",
+            scan_id, licenses[0]["id"]
+        ),
+    )
+    .await;
+    assert_eq!(comments["comments"][0]["body"], "Build tooling only");
+    let source_timeline = get_json(&source, &format!("/api/v1/scans/{}/timeline", scan_id)).await;
+    let timeline = get_json(&target, &format!("/api/v1/scans/{}/timeline", scan_id)).await;
+    assert_eq!(timeline["events"], source_timeline["events"]);
+
+    let verify = "/api/v1/admin/integrity/verify";
+    let (_, integrity) = request_as(&target, "POST", verify, Some("admin-secret"), None).await;
+    assert_eq!(integrity["scans_verified"], 1, "{}", integrity);
+    assert_eq!(integrity["discrepancies"], json!([]));
+
+    let (status, _) = import_archive(&target, Some("admin-secret"), &archive).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
@@ -0,0 +1,163 @@
+//! Signed scan completion callbacks and their payload templates
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{get_json, request, run_scan, setup, FIXTURE_FILES};
+use legalscanner_api::{
+    api::routes::create_router,
+    export::processors::HmacSigner,
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+};
+use serde_json::{json, Value};
+use std::{sync::Arc, time::Duration};
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn test_completion_callback_is_signed_and_retried() {
+    let receiver = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&receiver)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .and(header("x-legalscanner-event", "scan.completed"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&receiver)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.webhook_secret = Some("callback-secret".to_string());
+    let app = create_router(test_state(config, Arc::new(MockScanner::new("fossology"))).await);
+
+    let callback_url = format!("{}/hook", receiver.uri());
+    let scan = run_scan(
+        &app,
+        json!({ "git_url": git_url, "callback_url": callback_url }),
+    )
+    .await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+
+    // The callback is sent after the terminal timeline event
+    let uri = format!("/api/v1/scans/{}/webhook-deliveries", scan_id);
+    let mut deliveries = Value::Null;
+    for _ in 0..50 {
+        deliveries = get_json(&app, &uri).await;
+        if deliveries["deliveries"].as_array().unwrap().len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let attempts = deliveries["deliveries"].as_array().unwrap();
+    assert_eq!(attempts.len(), 2, "{}", deliveries);
+    assert_eq!(attempts[0]["status"], "failed");
+    assert_eq!(attempts[0]["response_code"], 503);
+    assert_eq!(attempts[1]["status"], "delivered");
+    assert_eq!(attempts[1]["attempt"], 2);
+
+    let requests = receiver.received_requests().await.unwrap();
+    let delivered = requests.last().unwrap();
+    let signature = delivered.headers["x-legalscanner-signature"]
+        .to_str()
+        .unwrap();
+    assert_eq!(
+        signature,
+        format!(
+            "sha256={}",
+            HmacSigner::new("callback-secret").sign(&delivered.body)
+        )
+    );
+    let payload: Value = serde_json::from_slice(&delivered.body).unwrap();
+    assert_eq!(payload["event"], "scan.completed");
+    assert_eq!(payload["scan_id"], scan_id);
+    assert_eq!(
+        payload["links"]["results"],
+        format!("/api/v1/scans/{}/results", scan_id)
+    );
+
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": git_url, "callback_url": "ftp://example.com/hook" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_notification_template_shapes_callback_payload() {
+    let receiver = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/slack"))
+        .and(header("content-type", "application/json"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&receiver)
+        .await;
+
+    let (app, git_url, _dir) = setup(Arc::new(MockScanner::new("fossology"))).await;
+
+    let slack = r#"{"text": "Scan of {{git_url}} {{status}}", "blocks": [{"type": "section", "text": {"type": "mrkdwn", "text": "<{{links.results}}|Results> ({{risk_level}})"}}]}"#;
+    let uri = "/api/v1/notification-templates/scan.completed";
+    let (status, body) = request(&app, "PUT", uri, Some(json!({ "body": slack }))).await;
+    assert_eq!(status, StatusCode::OK, "{}", String::from_utf8_lossy(&body));
+
+    let (status, _) = request(
+        &app,
+        "PUT",
+        "/api/v1/notification-templates/scan.started",
+        Some(json!({ "body": slack })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = request(&app, "PUT", uri, Some(json!({ "body": "{{nope}}" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let templates = get_json(&app, "/api/v1/notification-templates").await;
+    assert_eq!(templates["templates"][0]["event"], "scan.completed");
+    assert_eq!(
+        templates["templates"][0]["content_type"],
+        "application/json"
+    );
+
+    let scan = run_scan(
+        &app,
+        json!({ "git_url": git_url, "callback_url": format!("{}/slack", receiver.uri()) }),
+    )
+    .await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+
+    let mut requests = Vec::new();
+    for _ in 0..50 {
+        requests = receiver.received_requests().await.unwrap();
+        if !requests.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(requests.len(), 1);
+    let payload: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(payload["text"], format!("Scan of {} completed", git_url));
+    assert_eq!(
+        payload["blocks"][0]["text"]["text"],
+        format!(
+            "</api/v1/scans/{}/results|Results> ({})",
+            scan_id,
+            scan["risk_assessment"]["level"].as_str().unwrap_or_default()
+        )
+    );
+
+    let (status, _) = request(&app, "DELETE", uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = request(&app, "DELETE", uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
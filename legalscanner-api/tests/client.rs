@@ -0,0 +1,154 @@
+//! Contract test for `legalscanner-client`: drive the real router over HTTP
+//! with the typed client, so response changes that break its models fail here
+
+use legalscanner_api::{
+    api::routes::create_router,
+    scanner::{LicenseFinding, ScanResult},
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+};
+use legalscanner_client::{
+    ClientError, CreateScanRequest, GateOutcome, LegalScannerClient, ListScansQuery,
+    RerunScanRequest, ResultsQuery, SbomFormat,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_client_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(
+        &dir.path().join("fixture"),
+        &[("src/lib.rs", "// SPDX-License-Identifier: MIT\n")],
+    );
+
+    let fossology = MockScanner::new("fossology").with_results(vec![ScanResult {
+        file_path: "src/lib.rs".to_string(),
+        licenses: vec![LicenseFinding {
+            name: "MIT".to_string(),
+            spdx_id: Some("MIT".to_string()),
+            confidence: 1.0,
+            category: Default::default(),
+            provenance: None,
+            agreement: None,
+            exception: None,
+        }],
+        copyrights: Vec::new(),
+        ecc_findings: Vec::new(),
+        malware_findings: Vec::new(),
+        package_findings: Vec::new(),
+        model_licenses: Vec::new(),
+    }]);
+    let state = test_state(
+        test_config(dir.path().join("workspaces")),
+        Arc::new(fossology),
+    )
+    .await;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, create_router(state)).await.unwrap();
+    });
+
+    let client = LegalScannerClient::new(format!("http://{}", address));
+
+    let created = client
+        .create_scan(&CreateScanRequest::new(git_url))
+        .await
+        .unwrap();
+    assert_eq!(created.status, "pending");
+
+    let scan = client
+        .wait_for_completion(
+            &created.scan_id,
+            Duration::from_millis(100),
+            Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+    assert_eq!(scan.status, "completed", "{:?}", scan);
+
+    wait_for_job(&client, &created.scan_id).await;
+    let results = client
+        .get_results(&created.scan_id, &ResultsQuery::default())
+        .await
+        .unwrap();
+    assert!(results
+        .results
+        .licenses
+        .iter()
+        .any(|l| l.spdx_id.as_deref() == Some("MIT")));
+
+    let page = client.list_scans(&ListScansQuery::default()).await.unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(page.scans[0].scan_id, created.scan_id);
+
+    let sbom = client
+        .export_sbom(&created.scan_id, SbomFormat::Json)
+        .await
+        .unwrap();
+    assert!(String::from_utf8_lossy(&sbom).contains("SPDX-2.3"));
+
+    let rerun = client
+        .rerun_scan(&created.scan_id, &RerunScanRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        rerun.parent_scan_id.as_deref(),
+        Some(created.scan_id.as_str())
+    );
+    wait_for_job(&client, &rerun.scan_id).await;
+    let rerun = client.get_scan(&rerun.scan_id).await.unwrap();
+    assert_eq!(
+        rerun.parent_scan_id.as_deref(),
+        Some(created.scan_id.as_str())
+    );
+
+    // Same repository, same mock findings
+    let diff = client
+        .diff_scans(&created.scan_id, &rerun.scan_id)
+        .await
+        .unwrap();
+    assert!(diff.new_licenses.is_empty());
+    assert!(diff.added_findings.is_empty());
+    assert_eq!(diff.risk.delta, Some(0));
+
+    let gate = client.get_gate(&created.scan_id).await.unwrap();
+    assert_eq!(gate.scan_id, created.scan_id);
+    assert_eq!(gate.schema_version, 1);
+    let verdict = match gate.verdict {
+        GateOutcome::Pass => "PASS",
+        GateOutcome::Warn => "WARN",
+        GateOutcome::Fail => "FAIL",
+    };
+    let text = client.get_gate_text(&created.scan_id).await.unwrap();
+    assert_eq!(
+        text.lines().next(),
+        Some(format!("{} {}", verdict, created.scan_id).as_str())
+    );
+    assert_eq!(text.lines().count(), gate.violations.len() + 1);
+
+    let history = client.get_history(&created.scan_id).await.unwrap();
+    assert!(!history.due_diligence);
+    assert!(history.findings.is_empty());
+
+    let missing = client.get_scan("does-not-exist").await.unwrap_err();
+    assert!(matches!(missing, ClientError::Api { status: 404, .. }));
+}
+
+/// Results are stored after the status flips to completed, so wait for the
+/// job's terminal timeline event (see tests/pipeline.rs)
+async fn wait_for_job(client: &LegalScannerClient, scan_id: &str) {
+    for _ in 0..100 {
+        let timeline = client.get_timeline(scan_id).await.unwrap();
+        if timeline
+            .events
+            .iter()
+            .any(|e| e.event_type == "completed" || e.event_type == "failed")
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("scan job {} did not finish", scan_id);
+}
@@ -0,0 +1,263 @@
+//! Shared fixtures for the integration tests
+// Each test crate only uses some of them
+#![allow(dead_code)]
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use http_body_util::BodyExt;
+use legalscanner_api::{
+    api::routes::create_router,
+    scanner::{LicenseFinding, ScanResult, Scanner},
+    testing::{init_fixture_repo, test_config, test_state},
+};
+use serde_json::{json, Value};
+use std::{sync::Arc, time::Duration};
+use tower::ServiceExt;
+use wiremock::{
+    matchers::{method, path, path_regex},
+    Mock, MockServer, ResponseTemplate,
+};
+
+pub const UPLOAD_ID: i32 = 42;
+pub const JOB_ID: i32 = 7;
+
+/// Start a fake Fossology REST API that accepts any upload and reports
+/// MIT for `LICENSE` and `src/lib.rs`, plus one copyright statement
+pub async fn fake_fossology() -> MockServer {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repo/api/v1/version"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "version": "4.4.0" })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/repo/api/v1/uploads"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+            "code": 201,
+            "message": UPLOAD_ID,
+            "type": "INFO"
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path(format!("/repo/api/v1/uploads/{}", UPLOAD_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": UPLOAD_ID,
+            "folderid": 1,
+            "foldername": "Software Repository",
+            "uploadname": "fixture.tar.gz",
+            "hash": {
+                "sha1": "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+                "md5": "d41d8cd98f00b204e9800998ecf8427e",
+                "sha256": "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+                "size": 0
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/repo/api/v1/jobs"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+            "code": 201,
+            "message": JOB_ID,
+            "type": "INFO"
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path(format!("/repo/api/v1/jobs/{}", JOB_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": JOB_ID,
+            "name": "fixture",
+            "status": "Completed",
+            "eta": 0
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/repo/api/v1/uploads/\d+/licenses$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "filePath": "fixture/LICENSE", "findings": { "scanner": ["MIT"], "conclusion": null } },
+            { "filePath": "fixture/src/lib.rs", "findings": { "scanner": ["MIT"], "conclusion": null } },
+            { "filePath": "fixture/README.md", "findings": { "scanner": ["No_license_found"], "conclusion": null } }
+        ])))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/repo/api/v1/uploads/\d+/copyrights$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "copyright": "Copyright (c) 2024 Fixture Authors", "filePath": ["fixture/LICENSE"] }
+        ])))
+        .mount(&server)
+        .await;
+
+    server
+}
+
+pub const FIXTURE_FILES: &[(&str, &str)] = &[
+    (
+        "LICENSE",
+        "MIT License\n\nCopyright (c) 2024 Fixture Authors\n",
+    ),
+    (
+        "src/lib.rs",
+        "// SPDX-License-Identifier: MIT\npub fn add(a: i32, b: i32) -> i32 { a + b }\n",
+    ),
+    ("README.md", "# fixture\n"),
+];
+
+pub async fn request(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Vec<u8>) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(
+            body.map(|b| Body::from(b.to_string()))
+                .unwrap_or_else(Body::empty),
+        )
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, bytes.to_vec())
+}
+
+pub async fn get_json(app: &Router, uri: &str) -> Value {
+    let (status, body) = request(app, "GET", uri, None).await;
+    assert_eq!(
+        status,
+        StatusCode::OK,
+        "GET {} failed: {}",
+        uri,
+        String::from_utf8_lossy(&body)
+    );
+    serde_json::from_slice(&body).unwrap()
+}
+
+/// Create a scan and wait for the background job
+pub async fn run_scan(app: &Router, payload: Value) -> Value {
+    start_and_wait(app, "/api/v1/scans", Some(payload)).await
+}
+
+/// POST to an endpoint that starts a scan and wait for the background job
+pub async fn start_and_wait(app: &Router, uri: &str, payload: Option<Value>) -> Value {
+    let (status, body) = request(app, "POST", uri, payload).await;
+    assert_eq!(
+        status,
+        StatusCode::CREATED,
+        "{}",
+        String::from_utf8_lossy(&body)
+    );
+    let scan_id = serde_json::from_slice::<Value>(&body).unwrap()["scan_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    wait_for_scan(app, &scan_id).await
+}
+
+/// The scan status flips to completed once the scanners finish, before
+/// results are stored, so wait for the job's terminal timeline event
+pub async fn wait_for_scan(app: &Router, scan_id: &str) -> Value {
+    for _ in 0..100 {
+        let timeline = get_json(app, &format!("/api/v1/scans/{}/timeline", scan_id)).await;
+        let finished = timeline["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|e| e["event_type"] == "completed" || e["event_type"] == "failed");
+        if finished {
+            return get_json(app, &format!("/api/v1/scans/{}", scan_id)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("scan {} did not finish", scan_id);
+}
+
+pub async fn setup(fossology: Arc<dyn Scanner>) -> (Router, String, tempfile::TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let state = test_state(test_config(dir.path().join("workspaces")), fossology).await;
+    (create_router(state), git_url, dir)
+}
+
+pub fn license_result(file_path: &str, spdx_id: &str, confidence: f32) -> ScanResult {
+    ScanResult {
+        file_path: file_path.to_string(),
+        licenses: vec![LicenseFinding {
+            name: spdx_id.to_string(),
+            spdx_id: Some(spdx_id.to_string()),
+            confidence,
+            category: Default::default(),
+            provenance: None,
+            agreement: None,
+            exception: None,
+        }],
+        copyrights: Vec::new(),
+        ecc_findings: Vec::new(),
+        malware_findings: Vec::new(),
+        package_findings: Vec::new(),
+        model_licenses: Vec::new(),
+    }
+}
+
+pub async fn erase(app: &Router, token: Option<&str>, payload: Value) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/api/v1/admin/erasure")
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header("authorization", format!("Bearer {}", token));
+    }
+    let response = app
+        .clone()
+        .oneshot(builder.body(Body::from(payload.to_string())).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// Request with an optional admin token
+pub async fn request_as(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    token: Option<&str>,
+    payload: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header("authorization", format!("Bearer {}", token));
+    }
+    let body = payload
+        .map(|p| Body::from(p.to_string()))
+        .unwrap_or_else(Body::empty);
+    let response = app
+        .clone()
+        .oneshot(builder.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
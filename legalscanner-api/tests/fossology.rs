@@ -0,0 +1,206 @@
+//! Curations shared with Fossology in both directions
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{get_json, license_result, request, run_scan, FIXTURE_FILES};
+use legalscanner_api::{
+    api::routes::create_router,
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use wiremock::{
+    matchers::{body_json, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn test_fossology_clearing_decisions_curate_later_scans() {
+    let fossology = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repo/api/v1/uploads/42/licenses"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "filePath": "release.tar.gz/release.tar/fixture/vendor/gpl.c",
+                "findings": { "scanner": ["GPL-3.0-only"], "conclusion": ["MIT"] }
+            },
+            {
+                "filePath": "release.tar.gz/release.tar/fixture/src/generated.rs",
+                "findings": { "scanner": ["GPL-2.0-only"], "conclusion": ["Void"] }
+            },
+            {
+                "filePath": "release.tar.gz/release.tar/fixture/README.md",
+                "findings": { "scanner": ["MIT"], "conclusion": [] }
+            },
+            {
+                "filePath": "release.tar.gz/release.tar/other/lib.rs",
+                "findings": { "scanner": [], "conclusion": ["Apache-2.0"] }
+            }
+        ])))
+        .mount(&fossology)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/repo/api/v1/uploads/7/licenses"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&fossology)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.fossology_url = fossology.uri();
+    let scanner = MockScanner::new("fossology").with_results(vec![
+        license_result("src/lib.rs", "MIT", 1.0),
+        license_result("vendor/gpl.c", "GPL-3.0-only", 1.0),
+        license_result("src/generated.rs", "GPL-2.0-only", 1.0),
+    ]);
+    let app = create_router(test_state(config, Arc::new(scanner)).await);
+
+    let import = json!({ "git_url": git_url, "upload_id": 42, "path_prefix": "fixture" });
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/curations/import/fossology",
+        Some(import),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", String::from_utf8_lossy(&body));
+    let report: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["concluded"], 1);
+    assert_eq!(report["irrelevant"], 1);
+    assert_eq!(report["skipped"], 1);
+
+    let missing = json!({ "git_url": git_url, "upload_id": 7 });
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/curations/import/fossology",
+        Some(missing),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let summary = get_json(&app, &format!("/api/v1/scans/{}/licenses", scan_id)).await;
+    assert_eq!(
+        summary["licenses"].as_array().unwrap().len(),
+        1,
+        "{}",
+        summary
+    );
+    assert_eq!(summary["licenses"][0]["license"], "MIT");
+    assert_eq!(summary["licenses"][0]["file_count"], 2);
+
+    let uri = format!("/api/v1/curations?git_url={}.git", git_url);
+    let curations = get_json(&app, &uri).await;
+    let curations = curations["curations"].as_array().unwrap();
+    assert_eq!(curations.len(), 2);
+    assert_eq!(curations[0]["file_path"], "src/generated.rs");
+    assert_eq!(curations[0]["irrelevant"], true);
+    assert_eq!(curations[1]["file_path"], "vendor/gpl.c");
+    assert_eq!(curations[1]["source_reference"], "42");
+
+    let uri = format!("/api/v1/curations/{}", curations[0]["id"].as_str().unwrap());
+    let (status, _) = request(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = request(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_curations_are_exported_to_fossology() {
+    let fossology = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repo/api/v1/uploads/42/licenses"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "filePath": "release.zip/fixture/vendor/gpl.c",
+                "findings": { "scanner": ["GPL-3.0-only"], "conclusion": ["MIT"] }
+            },
+            {
+                "filePath": "release.zip/fixture/src/generated.rs",
+                "findings": { "scanner": ["GPL-2.0-only"], "conclusion": ["Void"] }
+            }
+        ])))
+        .mount(&fossology)
+        .await;
+    // A later upload of the repository, not yet cleared
+    Mock::given(method("GET"))
+        .and(path("/repo/api/v1/uploads/43/licenses"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "filePath": "app.tar.gz/app.tar/vendor/gpl.c",
+                "uploadTreeId": 501,
+                "findings": { "scanner": ["GPL-3.0-only"], "conclusion": [] }
+            },
+            {
+                "filePath": "app.tar.gz/app.tar/src/lib.rs",
+                "uploadTreeId": 502,
+                "findings": { "scanner": ["MIT"], "conclusion": [] }
+            }
+        ])))
+        .mount(&fossology)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/repo/api/v1/uploads/43/item/501/licenses"))
+        .and(body_json(json!([{ "shortName": "MIT", "add": true }])))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&fossology)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/repo/api/v1/uploads/43/item/501/clearing-decision"))
+        .and(body_json(
+            json!({ "decisionType": "Identified", "globalDecision": false }),
+        ))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&fossology)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.fossology_url = fossology.uri();
+    let app = create_router(test_state(config, Arc::new(MockScanner::new("fossology"))).await);
+
+    let import = json!({ "git_url": git_url, "upload_id": 42, "path_prefix": "fixture" });
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/curations/import/fossology",
+        Some(import),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let export = json!({ "git_url": git_url, "upload_id": 43 });
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/curations/export/fossology",
+        Some(export),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", String::from_utf8_lossy(&body));
+    let report: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["concluded"], 1, "{}", report);
+    assert_eq!(report["irrelevant"], 0);
+    assert_eq!(report["unmatched"], 1);
+    assert_eq!(report["unchanged"], 0);
+
+    // The upload the curations came from already records them
+    let export = json!({ "git_url": git_url, "upload_id": 42, "path_prefix": "fixture" });
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/curations/export/fossology",
+        Some(export),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let report: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(report["unchanged"], 2, "{}", report);
+}
@@ -0,0 +1,217 @@
+//! Findings tracked across the scans of a repository and its git history
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{erase, get_json, license_result, request, run_scan, FIXTURE_FILES};
+use legalscanner_api::{
+    api::routes::create_router,
+    testing::{commit_fixture_changes, init_fixture_repo, test_config, test_state, MockScanner},
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_finding_lifecycle_across_scans() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology")
+            .with_results(vec![
+                license_result("src/lib.rs", "MIT", 1.0),
+                license_result("src/gpl.c", "GPL-3.0-only", 1.0),
+            ])
+            .existing_files_only(),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = dir.path().join("fixture");
+    let mut files = FIXTURE_FILES.to_vec();
+    files.push(("src/gpl.c", "/* GPL-3.0-only */\n"));
+    let git_url = init_fixture_repo(&fixture, &files);
+    let state = test_state(test_config(dir.path().join("workspaces")), fossology).await;
+    let app = create_router(state);
+
+    let first = run_scan(&app, json!({ "git_url": git_url })).await;
+    assert_eq!(first["status"], "completed", "{}", first);
+
+    let uri = format!("/api/v1/findings?result_type=license&git_url={}", git_url);
+    let findings = get_json(&app, &uri).await;
+    let findings = findings["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 2, "{:?}", findings);
+    assert!(findings.iter().all(|f| f["state"] == "open"));
+    let gpl = findings
+        .iter()
+        .find(|f| f["value"] == "GPL-3.0-only")
+        .unwrap();
+    let gpl_uri = format!("/api/v1/findings/{}", gpl["id"].as_str().unwrap());
+    assert_eq!(gpl["file_path"], "src/gpl.c");
+    assert_eq!(gpl["first_scan_id"], first["scan_id"]);
+
+    let (status, body) = request(
+        &app,
+        "POST",
+        &format!("{}/triage", gpl_uri),
+        Some(json!({ "note": "replacing with an MIT implementation" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let triaged: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(triaged["state"], "triaged");
+    assert!(triaged["triaged_at"].is_string());
+    assert_eq!(triaged["note"], "replacing with an MIT implementation");
+
+    let (status, _) = request(&app, "POST", &format!("{}/triage", gpl_uri), None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // The next scan no longer finds the deleted file
+    commit_fixture_changes(&fixture, &[], &["src/gpl.c"], "Drop GPL code");
+    let second = run_scan(&app, json!({ "git_url": git_url })).await;
+    let resolved = get_json(&app, &gpl_uri).await;
+    assert_eq!(resolved["state"], "resolved", "{}", resolved);
+    assert_eq!(resolved["resolved_scan_id"], second["scan_id"]);
+    assert!(resolved["resolved_at"].is_string());
+
+    let open = get_json(&app, &format!("/api/v1/findings?state=open&git_url={}", git_url)).await;
+    assert_eq!(open["findings"].as_array().unwrap().len(), 1);
+    assert_eq!(open["findings"][0]["value"], "MIT");
+
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/projects",
+        Some(json!({ "repositories": [git_url] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let project: Value = serde_json::from_slice(&body).unwrap();
+    let report = get_json(
+        &app,
+        &format!(
+            "/api/v1/projects/{}/findings/report?since=2000-01-01",
+            project["project_id"].as_str().unwrap()
+        ),
+    )
+    .await;
+    assert_eq!(report["totals"]["open"], 1, "{}", report);
+    assert_eq!(report["totals"]["resolved"], 1);
+    assert_eq!(report["opened_since"], 2);
+    assert_eq!(report["resolved_since"], 1);
+    assert_eq!(report["unresolved_since"], 0);
+    assert_eq!(report["licenses"][0]["license"], "GPL-3.0-only");
+    assert_eq!(report["licenses"][0]["resolved"], 1);
+
+    let (status, body) = request(&app, "POST", &format!("{}/reopen", gpl_uri), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let reopened: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(reopened["state"], "open");
+    assert!(reopened["resolved_at"].is_null());
+    assert!(reopened["reopened_at"].is_string());
+
+    let (status, _) = request(&app, "GET", "/api/v1/findings?state=fixed", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_due_diligence_scan_reports_history() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("fixture");
+    let mut files = FIXTURE_FILES.to_vec();
+    files.push((
+        "src/gpl.c",
+        "/* Copyright (c) 2019 Copyleft Corp\n * SPDX-License-Identifier: GPL-2.0-only */\n",
+    ));
+    let git_url = init_fixture_repo(&repo, &files);
+    commit_fixture_changes(&repo, &[], &["src/gpl.c"], "Remove GPL code");
+    let state = test_state(
+        test_config(dir.path().join("workspaces")),
+        Arc::new(MockScanner::new("fossology")),
+    )
+    .await;
+    let app = create_router(state);
+
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": git_url, "quick": true, "due_diligence": true })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url, "due_diligence": true })).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    assert_eq!(scan["due_diligence"], true);
+
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let history = get_json(&app, &format!("/api/v1/scans/{}/history", scan_id)).await;
+    let findings = history["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 1, "{}", history);
+    assert_eq!(findings[0]["kind"], "deleted_file");
+    assert_eq!(findings[0]["file_path"], "src/gpl.c");
+    assert_eq!(findings[0]["license"], "GPL-2.0-only");
+    assert_eq!(findings[0]["detail"], "Copyright (c) 2019 Copyleft Corp");
+
+    let (status, markdown) = request(
+        &app,
+        "GET",
+        &format!("/api/v1/scans/{}/markdown", scan_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(String::from_utf8_lossy(&markdown).contains("## History"));
+}
+
+#[tokio::test]
+async fn test_git_authorship_records_commit_authors() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("fixture");
+    let git_url = init_fixture_repo(&repo, FIXTURE_FILES);
+    commit_fixture_changes(&repo, &[("README.md", "# App 2\n")], &[], "Update readme");
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    let app = create_router(test_state(config, Arc::new(MockScanner::new("fossology"))).await);
+
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": git_url, "scan_path": "src", "git_authorship": true })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url, "git_authorship": true })).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    assert_eq!(scan["git_authorship"], true);
+
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let authors = get_json(&app, &format!("/api/v1/scans/{}/authors", scan_id)).await;
+    let files = authors["files"].as_array().unwrap();
+    assert_eq!(files.len(), FIXTURE_FILES.len(), "{}", authors);
+    let readme = files.iter().find(|f| f["file_path"] == "README.md").unwrap();
+    assert_eq!(readme["author_name"], "Fixture");
+    assert_eq!(readme["author_email"], "fixture@example.com");
+    assert_eq!(readme["commit_count"], 2);
+    assert_ne!(readme["first_commit_sha"], readme["last_commit_sha"]);
+
+    // Commit authors are registered apart from copyright notices
+    let holders = get_json(&app, "/api/v1/copyright-holders?q=fixture").await;
+    let holder_id = holders[0]["id"].as_i64().unwrap();
+    let holder = get_json(&app, &format!("/api/v1/copyright-holders/{}", holder_id)).await;
+    assert_eq!(holder["projects"][0]["file_count"], 0, "{}", holder);
+    assert_eq!(
+        holder["projects"][0]["commit_file_count"],
+        FIXTURE_FILES.len() as i64
+    );
+
+    let (status, report) = erase(
+        &app,
+        Some("admin-secret"),
+        json!({ "email": "fixture@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", report);
+    assert_eq!(report["file_authors_deleted"], FIXTURE_FILES.len() as i64);
+    assert_eq!(report["copyright_holders_deleted"], 1);
+    let authors = get_json(&app, &format!("/api/v1/scans/{}/authors", scan_id)).await;
+    assert_eq!(authors["files"], json!([]));
+}
@@ -0,0 +1,160 @@
+//! Bulk import of the repositories of a GitHub organization, GitLab group
+//! or Bitbucket workspace
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{request_as, wait_for_scan, FIXTURE_FILES};
+use legalscanner_api::{
+    api::routes::create_router,
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+};
+use serde_json::json;
+use std::sync::Arc;
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn test_github_org_import_scans_matching_repositories() {
+    let dir = tempfile::tempdir().unwrap();
+    let scanned = init_fixture_repo(&dir.path().join("api"), FIXTURE_FILES);
+    let archived = init_fixture_repo(&dir.path().join("legacy"), FIXTURE_FILES);
+    let untagged = init_fixture_repo(&dir.path().join("docs"), FIXTURE_FILES);
+
+    let github = MockServer::start().await;
+    let repo = |name: &str, url: &str, topics: &[&str], archived: bool| {
+        json!({
+            "name": name, "clone_url": url, "default_branch": "main",
+            "visibility": "private", "private": true, "topics": topics,
+            "archived": archived, "fork": false,
+        })
+    };
+    let next = format!("<{}/organizations/7/repos?page=2>; rel=\"next\"", github.uri());
+    Mock::given(method("GET"))
+        .and(path("/orgs/acme/repos"))
+        .and(header("authorization", "Bearer ghp_org"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("link", next.as_str())
+                .set_body_json(json!([repo("api", &scanned, &["compliance"], false)])),
+        )
+        .mount(&github)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/organizations/7/repos"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            repo("legacy", &archived, &["compliance"], true),
+            repo("docs", &untagged, &["website"], false),
+        ])))
+        .mount(&github)
+        .await;
+
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.github_api_url = github.uri();
+    let state = test_state(config, Arc::new(MockScanner::new("fossology"))).await;
+    let app = create_router(state);
+
+    let import = |payload| request_as(&app, "POST", "/api/v1/import/github-org", None, Some(payload));
+    let (status, _) = import(json!({ "org": "acme" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = import(json!({ "org": "acme", "git_token": "t", "visibility": "secret" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let payload = json!({ "org": "acme", "git_token": "ghp_org", "topics": ["compliance"], "dry_run": true });
+    let (status, preview) = import(payload).await;
+    assert_eq!(status, StatusCode::OK, "{}", preview);
+    assert_eq!(preview["listed"], 3);
+    assert_eq!(preview["repositories"].as_array().unwrap().len(), 1);
+    assert!(preview["repositories"][0]["scan"].is_null());
+
+    let payload = json!({ "org": "acme", "git_token": "ghp_org", "topics": ["compliance"], "include_archived": true });
+    let (status, imported) = import(payload).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", imported);
+    let names: Vec<&str> = imported["repositories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["api", "legacy"]);
+    for repository in imported["repositories"].as_array().unwrap() {
+        let scan = wait_for_scan(&app, repository["scan"]["scan_id"].as_str().unwrap()).await;
+        assert_eq!(scan["status"], "completed", "{}", scan);
+    }
+}
+
+#[tokio::test]
+async fn test_repository_import_from_gitlab_and_bitbucket() {
+    let dir = tempfile::tempdir().unwrap();
+    let service = init_fixture_repo(&dir.path().join("service"), FIXTURE_FILES);
+    let fork = init_fixture_repo(&dir.path().join("fork"), FIXTURE_FILES);
+    let widgets = init_fixture_repo(&dir.path().join("widgets"), FIXTURE_FILES);
+
+    let gitlab = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/groups/acme%2Fplatform/projects"))
+        .and(header("authorization", "Bearer glpat-group"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {
+                "path_with_namespace": "acme/platform/service", "http_url_to_repo": service,
+                "default_branch": "main", "visibility": "internal", "topics": [],
+                "archived": false,
+            },
+            {
+                "path_with_namespace": "acme/platform/fork", "http_url_to_repo": fork,
+                "visibility": "internal", "archived": false,
+                "forked_from_project": { "id": 1 },
+            },
+        ])))
+        .mount(&gitlab)
+        .await;
+
+    let bitbucket = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repositories/acme"))
+        .and(header("authorization", "Bearer bb-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "values": [{
+                "full_name": "acme/widgets", "is_private": true,
+                "mainbranch": { "name": "main" },
+                "links": { "clone": [
+                    { "name": "https", "href": widgets },
+                    { "name": "ssh", "href": "git@bitbucket.org:acme/widgets.git" },
+                ] },
+            }],
+        })))
+        .mount(&bitbucket)
+        .await;
+
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.gitlab_api_url = format!("{}/", gitlab.uri());
+    config.bitbucket_api_url = bitbucket.uri();
+    let state = test_state(config, Arc::new(MockScanner::new("fossology"))).await;
+    let app = create_router(state);
+
+    let import = |payload| request_as(&app, "POST", "/api/v1/import/repositories", None, Some(payload));
+    let (status, _) = import(json!({ "provider": "gitea", "namespace": "acme", "git_token": "t" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = import(json!({ "provider": "azure_devops", "namespace": "acme", "git_token": "t" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let payload = json!({ "provider": "gitlab", "namespace": "acme/platform", "git_token": "glpat-group" });
+    let (status, imported) = import(payload).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", imported);
+    assert_eq!(imported["listed"], 2);
+    let repositories = imported["repositories"].as_array().unwrap();
+    assert_eq!(repositories.len(), 1);
+    assert_eq!(repositories[0]["name"], "acme/platform/service");
+    assert_eq!(repositories[0]["visibility"], "internal");
+    let scan = wait_for_scan(&app, repositories[0]["scan"]["scan_id"].as_str().unwrap()).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+
+    let payload = json!({ "provider": "bitbucket", "namespace": "acme", "git_token": "bb-token", "dry_run": true });
+    let (status, preview) = import(payload).await;
+    assert_eq!(status, StatusCode::OK, "{}", preview);
+    assert_eq!(preview["repositories"][0]["git_url"], widgets.as_str());
+    assert_eq!(preview["repositories"][0]["default_branch"], "main");
+    assert_eq!(preview["repositories"][0]["visibility"], "private");
+}
@@ -0,0 +1,65 @@
+//! Library embedding: run the pipeline on a local directory without the HTTP server
+
+use legalscanner_api::{
+    scanner::{LicenseFinding, ScanResult},
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+    AppError, ScanOrchestrator,
+};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_scan_path_returns_results_and_risk() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("fixture");
+    init_fixture_repo(&repo, &[("src/lib.rs", "// GPL code\n")]);
+
+    let fossology = MockScanner::new("fossology").with_results(vec![ScanResult {
+        file_path: "src/lib.rs".to_string(),
+        licenses: vec![LicenseFinding {
+            name: "GPL-3.0-only".to_string(),
+            spdx_id: Some("GPL-3.0-only".to_string()),
+            confidence: 1.0,
+            category: Default::default(),
+            provenance: None,
+            agreement: None,
+            exception: None,
+        }],
+        copyrights: Vec::new(),
+        ecc_findings: Vec::new(),
+        malware_findings: Vec::new(),
+        package_findings: Vec::new(),
+        model_licenses: Vec::new(),
+    }]);
+    let state = test_state(
+        test_config(dir.path().join("workspaces")),
+        Arc::new(fossology),
+    )
+    .await;
+    let orchestrator = ScanOrchestrator::from_state(state);
+
+    let outcome = orchestrator.scan_path(&repo).await.unwrap();
+
+    assert!(outcome.results.iter().any(|r| r.file_path == "src/lib.rs"));
+    let risk = outcome.risk.expect("risk assessment");
+    assert!(risk
+        .factors
+        .iter()
+        .any(|f| f.category == "copyleft_license"));
+    // The checkout is scanned in place, not cleaned up
+    assert!(repo.join("src/lib.rs").exists());
+}
+
+#[tokio::test]
+async fn test_scan_path_rejects_missing_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = test_state(
+        test_config(dir.path().join("workspaces")),
+        Arc::new(MockScanner::new("fossology")),
+    )
+    .await;
+
+    let result = ScanOrchestrator::from_state(state)
+        .scan_path(dir.path().join("missing"))
+        .await;
+    assert!(matches!(result, Err(AppError::Validation(_))));
+}
@@ -0,0 +1,469 @@
+//! End-to-end scan pipeline tests: create → clone a local fixture → scan →
+//! results → SBOM, with no containers or network services involved.
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use common::{get_json, license_result, request, run_scan, setup, start_and_wait, FIXTURE_FILES};
+use http_body_util::BodyExt;
+use legalscanner_api::{
+    api::routes::create_router,
+    scanner::{fossology::FossologyScanner, LicenseFinding, ScanResult},
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+};
+use serde_json::{json, Value};
+use std::{sync::Arc, time::Duration};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_pipeline_with_mock_scanner() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![ScanResult {
+        file_path: "src/lib.rs".to_string(),
+        licenses: vec![LicenseFinding {
+            name: "MIT".to_string(),
+            spdx_id: Some("MIT".to_string()),
+            confidence: 1.0,
+            category: Default::default(),
+            provenance: None,
+            agreement: None,
+            exception: None,
+        }],
+        copyrights: Vec::new(),
+        ecc_findings: Vec::new(),
+        malware_findings: Vec::new(),
+        package_findings: Vec::new(),
+        model_licenses: Vec::new(),
+    }]));
+    let (app, git_url, _dir) = setup(fossology.clone()).await;
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    assert_eq!(fossology.scan_count(), 1);
+
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let results = get_json(&app, &format!("/api/v1/scans/{}/results", scan_id)).await;
+    assert!(results.to_string().contains("src/lib.rs"));
+
+    let sbom = get_json(&app, &format!("/api/v1/scans/{}/sbom", scan_id)).await;
+    assert_eq!(sbom["spdxVersion"], "SPDX-2.3");
+    assert!(sbom["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|f| f["fileName"] == "src/lib.rs"));
+}
+
+#[tokio::test]
+async fn test_scanner_agreement_and_disputes() {
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let fossology = MockScanner::new("fossology").with_results(vec![
+        license_result("src/lib.rs", "MIT", 0.6),
+        license_result("src/main.rs", "MIT", 1.0),
+    ]);
+    let mut state = test_state(
+        test_config(dir.path().join("workspaces")),
+        Arc::new(fossology),
+    )
+    .await;
+    state.semgrep_scanner = Arc::new(MockScanner::new("semgrep").with_results(vec![
+        license_result("src/lib.rs", "MIT", 0.5),
+        license_result("src/main.rs", "Apache-2.0", 1.0),
+    ]));
+    let app = create_router(state);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+
+    let results = get_json(
+        &app,
+        &format!("/api/v1/scans/{}/results?type=license", scan_id),
+    )
+    .await;
+    let lib: Vec<&Value> = results["results"]["licenses"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|l| l["file_path"] == "src/lib.rs")
+        .collect();
+    assert_eq!(lib.len(), 2);
+    for finding in lib {
+        assert_eq!(finding["agreement"], "corroborated");
+        assert!((finding["confidence"].as_f64().unwrap() - 0.8).abs() < 1e-6);
+    }
+
+    let disputes = get_json(&app, &format!("/api/v1/scans/{}/results/disputed", scan_id)).await;
+    let files = disputes["files"].as_array().unwrap();
+    assert_eq!(files.len(), 1, "{}", disputes);
+    assert_eq!(files[0]["file_path"], "src/main.rs");
+    let licenses = files[0]["licenses"].as_array().unwrap();
+    assert_eq!(licenses.len(), 2);
+    assert!(licenses.iter().all(|l| l["agreement"] == "disputed"));
+}
+
+#[tokio::test]
+async fn test_result_sample_covers_rare_licenses() {
+    let mut results: Vec<ScanResult> = (0..20)
+        .map(|i| license_result(&format!("src/m{}.rs", i), "MIT", 1.0))
+        .collect();
+    results.push(license_result("vendor/gpl.c", "GPL-3.0-only", 1.0));
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(results));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let uri = format!(
+        "/api/v1/scans/{}/results/sample?n=4&type=license&seed=7",
+        scan["scan_id"].as_str().unwrap()
+    );
+    let sample = get_json(&app, &uri).await;
+    assert_eq!(sample["stratify"], "license");
+    assert_eq!(
+        sample["findings"].as_array().unwrap().len(),
+        4,
+        "{}",
+        sample
+    );
+    let gpl = sample["strata"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["key"] == "GPL-3.0-only")
+        .unwrap();
+    assert_eq!(gpl["sampled"], 1);
+    assert!(sample["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|f| f["file_path"] == "vendor/gpl.c" && f["stratum"] == "GPL-3.0-only"));
+
+    // The same seed draws the same sample
+    assert_eq!(get_json(&app, &uri).await, sample);
+
+    let (status, _) = request(&app, "GET", &format!("{}&stratify=file", uri), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_pipeline_against_fake_fossology() {
+    let server = common::fake_fossology().await;
+    let fossology = Arc::new(FossologyScanner::new(
+        server.uri(),
+        "test-token".to_string(),
+    ));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    assert_eq!(scan["fossology_status"], "completed");
+
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let results = get_json(&app, &format!("/api/v1/scans/{}/results", scan_id)).await;
+    let text = results.to_string();
+    assert!(text.contains("fixture/src/lib.rs"));
+    assert!(text.contains("Fixture Authors"));
+
+    let (status, notice) = request(
+        &app,
+        "GET",
+        &format!("/api/v1/scans/{}/notice", scan_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(String::from_utf8_lossy(&notice).contains("Fixture Authors"));
+}
+
+#[tokio::test]
+async fn test_failing_scanner_fails_scan() {
+    let fossology = Arc::new(MockScanner::new("fossology").failing("fossology unavailable"));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    assert_eq!(scan["fossology_status"], "failed", "{}", scan);
+    assert!(scan["fossology_error"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("fossology unavailable"));
+}
+
+#[tokio::test]
+async fn test_unreachable_repository_is_rejected() {
+    let (app, git_url, dir) = setup(Arc::new(MockScanner::new("fossology"))).await;
+
+    let missing = format!("file://{}", dir.path().join("missing").display());
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": missing })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert!(
+        error["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("Repository is not reachable"),
+        "{}",
+        error
+    );
+
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": git_url, "branch": "does-not-exist" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert!(
+        error["error"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("Branch does-not-exist does not exist"),
+        "{}",
+        error
+    );
+
+    // Nothing was queued
+    let scans = get_json(&app, "/api/v1/scans").await;
+    assert_eq!(scans.as_array().unwrap().len(), 0);
+}
+
+/// Event names of an SSE response body, in order
+fn sse_event_names(body: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(body)
+        .lines()
+        .filter_map(|line| line.strip_prefix("event: "))
+        .map(str::to_string)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_event_stream_follows_scan_until_completed() {
+    let (app, git_url, _dir) = setup(Arc::new(MockScanner::new("fossology"))).await;
+
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": git_url })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let scan_id = serde_json::from_slice::<Value>(&body).unwrap()["scan_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let uri = format!("/api/v1/scans/{}/events", scan_id);
+
+    // Subscribed while the job runs: the stream ends by itself on completion
+    let (status, body) =
+        tokio::time::timeout(Duration::from_secs(30), request(&app, "GET", &uri, None))
+            .await
+            .expect("event stream did not end");
+    assert_eq!(status, StatusCode::OK);
+    let live = sse_event_names(&body);
+    assert_eq!(live.first().map(String::as_str), Some("created"));
+    assert_eq!(live.last().map(String::as_str), Some("completed"));
+    assert!(live.iter().any(|e| e == "clone_started"));
+    assert!(live.iter().any(|e| e == "results_stored"));
+    // Replayed and live timeline events are not sent twice
+    assert_eq!(live.iter().filter(|e| *e == "clone_finished").count(), 1);
+
+    // Connecting after the fact replays the timeline and ends
+    let (_, body) = request(&app, "GET", &uri, None).await;
+    let replayed = sse_event_names(&body);
+    assert!(replayed.ends_with(&["completed".to_string()]));
+    assert_eq!(
+        replayed.iter().filter(|e| *e != "progress").count(),
+        live.iter().filter(|e| *e != "progress").count()
+    );
+
+    let (status, _) = request(&app, "GET", "/api/v1/scans/missing/events", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_rerun_failed_scanners_only() {
+    let fossology = Arc::new(MockScanner::new("fossology").failing("fossology unavailable"));
+    let (app, git_url, _dir) = setup(fossology.clone()).await;
+
+    let parent = run_scan(&app, json!({ "git_url": git_url })).await;
+    assert_eq!(parent["status"], "failed", "{}", parent);
+    assert_eq!(parent["semgrep_status"], "completed");
+    let parent_id = parent["scan_id"].as_str().unwrap();
+
+    let uri = format!("/api/v1/scans/{}/rerun", parent_id);
+    let rerun = start_and_wait(&app, &uri, Some(json!({ "failed_only": true }))).await;
+    assert_eq!(rerun["parent_scan_id"], parent_id, "{}", rerun);
+    assert_eq!(rerun["semgrep_status"], "skipped");
+    assert_eq!(rerun["fossology_status"], "failed");
+    assert_eq!(fossology.scan_count(), 2);
+    assert!(rerun["warnings"][0]
+        .as_str()
+        .unwrap()
+        .starts_with("Semgrep completed in scan"));
+
+    // A plain re-run repeats every scanner
+    let rerun = start_and_wait(&app, &uri, None).await;
+    assert_eq!(rerun["semgrep_status"], "completed", "{}", rerun);
+    assert_eq!(rerun["warnings"], json!([]));
+    assert_eq!(fossology.scan_count(), 3);
+
+    let (status, _) = request(&app, "POST", "/api/v1/scans/missing/rerun", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_quick_scan_skips_fossology() {
+    let fossology = Arc::new(MockScanner::new("fossology"));
+    let (app, git_url, _dir) = setup(fossology.clone()).await;
+
+    let scan = run_scan(&app, json!({ "git_url": git_url, "quick": true })).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    assert_eq!(scan["quick"], true);
+    assert_eq!(scan["fossology_status"], "pending");
+    assert_eq!(scan["risk_assessment"]["preliminary"], true);
+    assert_eq!(fossology.scan_count(), 0);
+
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let timeline = get_json(&app, &format!("/api/v1/scans/{}/timeline", scan_id)).await;
+    assert!(timeline["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|e| e["event_type"] == "scanner_skipped" && e["scanner"] == "fossology"));
+}
+
+#[tokio::test]
+async fn test_list_scans_paginates_and_filters() {
+    let fossology = Arc::new(MockScanner::new("fossology"));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    run_scan(&app, json!({ "git_url": git_url })).await;
+    run_scan(&app, json!({ "git_url": git_url })).await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/scans?per_page=1&page=2&status=completed&sort=created_at")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-total-count"], "2");
+    assert_eq!(response.headers()["x-page"], "2");
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let page: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(page.as_array().unwrap().len(), 1);
+
+    let failed = get_json(&app, "/api/v1/scans?status=failed").await;
+    assert!(failed.as_array().unwrap().is_empty());
+
+    let other_repo = get_json(&app, "/api/v1/scans?git_url_contains=not-this-repo").await;
+    assert!(other_repo.as_array().unwrap().is_empty());
+
+    let (status, _) = request(&app, "GET", "/api/v1/scans?sort=-size", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = request(&app, "GET", "/api/v1/scans?created_after=yesterday", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let since = get_json(&app, "/api/v1/scans?created_after=2000-01-01").await;
+    assert_eq!(since.as_array().unwrap().len(), 2);
+    let before = get_json(&app, "/api/v1/scans?created_before=2000-01-01").await;
+    assert!(before.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_running_scan_is_reused_for_same_repository_and_branch() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_delay(Duration::from_secs(1)));
+    let (app, git_url, dir) = setup(fossology).await;
+    let fixture = git2::Repository::open(dir.path().join("fixture")).unwrap();
+    let head = fixture.head().unwrap().peel_to_commit().unwrap();
+    fixture.branch("release", &head, false).unwrap();
+
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": git_url })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let first: Value = serde_json::from_slice(&body).unwrap();
+
+    // A CI retry of the same repository gets the running scan back
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": format!("{}/", git_url) })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let reused: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(reused["scan_id"], first["scan_id"]);
+
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": git_url, "branch": "release" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let release: Value = serde_json::from_slice(&body).unwrap();
+    assert_ne!(release["scan_id"], first["scan_id"]);
+    assert_eq!(release["branch"], "release");
+
+    let scans = get_json(&app, "/api/v1/scans").await;
+    assert_eq!(scans.as_array().unwrap().len(), 2);
+
+    // Once the scan finished, the repository can be scanned again
+    let first_id = first["scan_id"].as_str().unwrap();
+    for _ in 0..100 {
+        let scan = get_json(&app, &format!("/api/v1/scans/{}", first_id)).await;
+        if scan["status"] == "completed" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let next = run_scan(&app, json!({ "git_url": git_url })).await;
+    assert_ne!(next["scan_id"], first["scan_id"]);
+}
+
+#[tokio::test]
+async fn test_soft_limit_warns_but_completes() {
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.soft_limit_max_files = 2;
+    let state = test_state(config, Arc::new(MockScanner::new("fossology"))).await;
+    let app = create_router(state);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    let warnings = scan["warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].as_str().unwrap().contains("3 files"));
+
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let (status, markdown) = request(
+        &app,
+        "GET",
+        &format!("/api/v1/scans/{}/markdown", scan_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(String::from_utf8_lossy(&markdown).contains("> **Warning:**"));
+}
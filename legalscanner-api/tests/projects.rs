@@ -0,0 +1,395 @@
+//! Projects grouping repositories, with their license policies and
+//! composite scans
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{get_json, license_result, request, run_scan, setup, wait_for_scan, FIXTURE_FILES};
+use legalscanner_api::testing::{init_fixture_repo, tag_fixture, MockScanner};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_repository_metadata_names_projects() {
+    let fossology = Arc::new(MockScanner::new("fossology"));
+    let (app, git_url, dir) = setup(fossology).await;
+    tag_fixture(&dir.path().join("fixture"), "v2.1.0");
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let metadata = get_json(&app, &format!("/api/v1/scans/{}/repository", scan_id)).await;
+    assert_eq!(metadata["latest_tag"], "v2.1.0", "{}", metadata);
+    assert!(metadata["default_branch"].is_string());
+    // No token and not a GitHub or GitLab URL
+    assert_eq!(metadata["provider"], Value::Null);
+    assert_eq!(metadata["topics"], json!([]));
+    let sha = metadata["commit_sha"].as_str().unwrap();
+    assert_eq!(sha.len(), 40);
+    assert_eq!(metadata["head_tag"], "v2.1.0");
+    assert!(metadata["commit_date"].as_str().unwrap().ends_with('Z'));
+    assert!(metadata["commit_author"].is_string());
+    assert!(metadata["file_count"].as_i64().unwrap() > 0);
+    assert!(metadata["size_bytes"].as_i64().unwrap() > 0);
+    assert!(metadata["languages"].is_array());
+
+    let detail = get_json(&app, &format!("/api/v1/scans/{}", scan_id)).await;
+    assert_eq!(detail["repository"]["commit_sha"], sha);
+    assert_eq!(detail["repository"]["file_count"], metadata["file_count"]);
+
+    let uri = format!("/api/v1/scans/{}/markdown", scan_id);
+    let (status, markdown) = request(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let markdown = String::from_utf8_lossy(&markdown);
+    assert!(markdown.contains("| Latest tag | v2.1.0 |"));
+    assert!(markdown.contains(&format!("| Commit | {} (v2.1.0) |", sha)));
+
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/projects",
+        Some(json!({ "repositories": [git_url] })),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::CREATED,
+        "{}",
+        String::from_utf8_lossy(&body)
+    );
+    let project: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(project["name"], "fixture");
+
+    let (status, _) = request(&app, "POST", "/api/v1/projects", Some(json!({}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_project_groups_scans_of_its_repositories() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology")
+            .with_results(vec![license_result("src/lib.rs", "Apache-2.0", 1.0)]),
+    );
+    let (app, web_url, dir) = setup(fossology).await;
+    let mut files = FIXTURE_FILES.to_vec();
+    files.push((
+        "Cargo.toml",
+        "[package]\nname = \"api\"\nlicense = \"Apache-2.0\"\n",
+    ));
+    let api_url = init_fixture_repo(&dir.path().join("api"), &files);
+
+    // Scans made before the project existed belong to it as well
+    let first = run_scan(&app, json!({ "git_url": web_url })).await;
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/projects",
+        Some(json!({
+            "name": "Checkout",
+            "repositories": [format!("{}.git/", web_url), api_url],
+        })),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::CREATED,
+        "{}",
+        String::from_utf8_lossy(&body)
+    );
+    let project: Value = serde_json::from_slice(&body).unwrap();
+    let project_id = project["project_id"].as_str().unwrap();
+    assert_eq!(project["repositories"].as_array().unwrap().len(), 2);
+
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/projects",
+        Some(json!({ "name": "Checkout" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let second = run_scan(&app, json!({ "git_url": web_url })).await;
+    run_scan(&app, json!({ "git_url": api_url })).await;
+
+    let latest = get_json(
+        &app,
+        &format!("/api/v1/projects/{}/scans/latest", project_id),
+    )
+    .await;
+    let repositories = latest["repositories"].as_array().unwrap();
+    assert_eq!(repositories.len(), 2);
+    assert!(repositories
+        .iter()
+        .all(|r| r["latest_completed_scan"]["status"] == "completed"));
+    assert!(repositories
+        .iter()
+        .any(|r| r["latest_scan"]["scan_id"] == second["scan_id"]));
+
+    let trend = get_json(&app, &format!("/api/v1/projects/{}/risk-trend", project_id)).await;
+    let points = trend["points"].as_array().unwrap();
+    assert_eq!(points.len(), 3);
+    assert_eq!(points[0]["scan_id"], first["scan_id"]);
+
+    let sbom = get_json(&app, &format!("/api/v1/projects/{}/sbom", project_id)).await;
+    assert_eq!(sbom["packages"][0]["name"], "Checkout");
+    let text = sbom.to_string();
+    assert!(text.contains("Apache-2.0"), "{}", text);
+    assert!(text.contains("SPDXRef-R2-Package"), "{}", text);
+
+    let (status, _) = request(
+        &app,
+        "DELETE",
+        &format!("/api/v1/projects/{}", project_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = request(
+        &app,
+        "GET",
+        &format!("/api/v1/projects/{}", project_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    get_json(
+        &app,
+        &format!("/api/v1/scans/{}", first["scan_id"].as_str().unwrap()),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_scans_are_evaluated_against_license_policies() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![
+        license_result("src/lib.rs", "MIT", 1.0),
+        license_result("src/tree.rs", "MPL-2.0", 1.0),
+        license_result("vendor/gpl.c", "GPL-3.0-only", 1.0),
+    ]));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    let (status, _) = request(
+        &app,
+        "PUT",
+        "/api/v1/policies/distribution",
+        Some(json!({ "denied_licenses": ["MIT"], "allowed_licenses": ["mit"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = request(
+        &app,
+        "PUT",
+        "/api/v1/policies/distribution",
+        Some(json!({
+            "denied_licenses": ["GPL-3.0-only", " gpl-3.0-only "],
+            "review_licenses": ["MPL-2.0"],
+            "ecc_fail_severity": "High",
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", String::from_utf8_lossy(&body));
+    let policy: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(policy["denied_licenses"], json!(["GPL-3.0-only"]));
+    assert_eq!(policy["ecc_fail_severity"], "high");
+    let (status, _) = request(
+        &app,
+        "PUT",
+        "/api/v1/policies/internal",
+        Some(json!({ "review_licenses": ["GPL-3.0-only"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = request(
+        &app,
+        "POST",
+        "/api/v1/projects",
+        Some(json!({ "name": "Web", "repositories": [git_url.clone()] })),
+    )
+    .await;
+    let project: Value = serde_json::from_slice(&body).unwrap();
+    let policy_uri = format!("/api/v1/projects/{}/policy", project["project_id"].as_str().unwrap());
+    let (status, _) = request(&app, "PUT", &policy_uri, Some(json!({ "policy": "missing" }))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, body) = request(&app, "PUT", &policy_uri, Some(json!({ "policy": "distribution" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    let project: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(project["policy"], "distribution");
+
+    // Completed scans of the project's repositories are evaluated
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    assert_eq!(scan["policy_outcome"], "fail", "{}", scan);
+    let evaluation_uri = format!("/api/v1/scans/{}/policy-evaluation", scan_id);
+    let evaluation = get_json(&app, &evaluation_uri).await;
+    assert_eq!(evaluation["policy"], "distribution");
+    assert_eq!(evaluation["passed"], false);
+    let violations = evaluation["violations"].as_array().unwrap();
+    assert_eq!(violations.len(), 2, "{}", evaluation);
+    assert_eq!(violations[0]["rule"], "denied_license");
+    assert_eq!(violations[0]["license"], "GPL-3.0-only");
+    assert_eq!(violations[0]["files"], json!(["vendor/gpl.c"]));
+    assert_eq!(violations[1]["rule"], "review_license");
+    assert_eq!(violations[1]["outcome"], "warn");
+
+    // A failing policy fails the gate
+    let gate_uri = format!("/api/v1/scans/{}/gate", scan_id);
+    let gate = get_json(&app, &gate_uri).await;
+    assert_eq!(gate["verdict"], "fail", "{}", gate);
+    let policy_failure = gate["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|v| v["rule"] == "license_policy" && v["outcome"] == "fail")
+        .unwrap();
+    assert_eq!(policy_failure["waivable"], false);
+    assert_eq!(policy_failure["details"], json!(["vendor/gpl.c"]));
+
+    // The scan's own policy overrides the rules it sets
+    let scan_policy_uri = format!("/api/v1/scans/{}/policy", scan_id);
+    let (status, body) = request(&app, "PUT", &scan_policy_uri, Some(json!({ "policy": "internal" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    let evaluation: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(evaluation["policy"], "distribution > internal");
+    assert_eq!(evaluation["outcome"], "warn");
+    assert_eq!(evaluation["passed"], true);
+    assert_eq!(evaluation["violations"].as_array().unwrap().len(), 2, "{}", evaluation);
+
+    // The organization's default applies beneath both
+    let (status, _) = request(
+        &app,
+        "PUT",
+        "/api/v1/policies/baseline",
+        Some(json!({ "denied_licenses": ["MPL-2.0", "AGPL-3.0-only"], "max_risk_score": 100 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = request(&app, "GET", "/api/v1/default-policy", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) =
+        request(&app, "PUT", "/api/v1/default-policy", Some(json!({ "policy": "missing" }))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, body) =
+        request(&app, "PUT", "/api/v1/default-policy", Some(json!({ "policy": "baseline" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    let baseline: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(baseline["is_default"], true);
+
+    let effective = get_json(&app, &format!("/api/v1/scans/{}/effective-policy", scan_id)).await;
+    let levels: Vec<&str> = effective["layers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["level"].as_str().unwrap())
+        .collect();
+    assert_eq!(levels, vec!["organization", "project", "scan"], "{}", effective);
+    assert_eq!(effective["layers"][1]["project_id"], project["project_id"]);
+    let license_rule = |license: &str| {
+        effective["licenses"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["license"] == license)
+            .cloned()
+            .unwrap()
+    };
+    let agpl = license_rule("AGPL-3.0-only");
+    assert_eq!((&agpl["listing"], &agpl["source"]["policy"]), (&json!("denied"), &json!("baseline")));
+    let mpl = license_rule("MPL-2.0");
+    assert_eq!((&mpl["listing"], &mpl["source"]["level"]), (&json!("needs_review"), &json!("project")));
+    let gpl = license_rule("GPL-3.0-only");
+    assert_eq!((&gpl["listing"], &gpl["source"]["level"]), (&json!("needs_review"), &json!("scan")));
+    assert_eq!(effective["max_risk_score"]["source"]["policy"], "baseline");
+    assert_eq!(effective["ecc_fail_severity"]["ecc_fail_severity"], "high");
+    assert_eq!(effective["ecc_fail_severity"]["source"]["policy"], "distribution");
+    assert_eq!(effective["allow_list"], Value::Null);
+
+    let (_, body) = request(&app, "POST", &evaluation_uri, None).await;
+    let evaluation: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(evaluation["policy"], "baseline > distribution > internal");
+    assert_eq!(evaluation["outcome"], "warn");
+    let (status, _) = request(&app, "DELETE", "/api/v1/default-policy", None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = request(&app, "DELETE", "/api/v1/default-policy", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Once no policy applies, the evaluation is dropped
+    let (status, _) = request(&app, "DELETE", &scan_policy_uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(get_json(&app, &evaluation_uri).await["policy"], "distribution");
+    let (status, _) = request(&app, "DELETE", "/api/v1/policies/distribution", None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = request(&app, "POST", &evaluation_uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = request(&app, "GET", &evaluation_uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) =
+        request(&app, "GET", &format!("/api/v1/scans/{}/effective-policy", scan_id), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let policies = get_json(&app, "/api/v1/policies").await;
+    assert_eq!(policies["policies"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_composite_scan_covers_every_repository() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology")
+            .with_results(vec![license_result("src/lib.rs", "GPL-3.0-only", 1.0)]),
+    );
+    let (app, web_url, dir) = setup(fossology).await;
+    let api_url = init_fixture_repo(&dir.path().join("api"), FIXTURE_FILES);
+    run_scan(&app, json!({ "git_url": web_url })).await;
+    run_scan(&app, json!({ "git_url": api_url })).await;
+
+    let (status, body) = request(
+        &app,
+        "POST",
+        "/api/v1/projects",
+        Some(json!({ "name": "Storefront", "repositories": [web_url, api_url] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let project: Value = serde_json::from_slice(&body).unwrap();
+    let uri = format!(
+        "/api/v1/projects/{}/composite-scans",
+        project["project_id"].as_str().unwrap()
+    );
+
+    let (status, body) = request(&app, "POST", &uri, None).await;
+    assert_eq!(
+        status,
+        StatusCode::CREATED,
+        "{}",
+        String::from_utf8_lossy(&body)
+    );
+    let composite: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(composite["product"], "Storefront");
+    let scans = composite["scans"].as_array().unwrap();
+    assert_eq!(scans.len(), 2);
+    for scan in scans {
+        wait_for_scan(&app, scan["scan_id"].as_str().unwrap()).await;
+    }
+
+    let composite_uri = format!(
+        "/api/v1/composite-scans/{}",
+        composite["composite_scan_id"].as_str().unwrap()
+    );
+    let composite = get_json(&app, &composite_uri).await;
+    assert_eq!(composite["status"], "completed", "{}", composite);
+    assert!(composite["risk_assessment"]["score"].as_i64().unwrap() > 0);
+    let listed = get_json(&app, &uri).await;
+    assert_eq!(listed[0]["composite_scan_id"], composite["composite_scan_id"]);
+
+    let sbom = get_json(&app, &format!("{}/sbom", composite_uri)).await;
+    assert_eq!(sbom["packages"][0]["name"], "Storefront");
+    assert!(sbom.to_string().contains("SPDXRef-R2-Package"));
+
+    let (status, body) = request(&app, "GET", &format!("{}/notice", composite_uri), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let notice = String::from_utf8(body).unwrap();
+    assert!(notice.starts_with("THIRD-PARTY SOFTWARE NOTICES AND INFORMATION"));
+    assert!(notice.contains(&format!("  {} (scan ", web_url)), "{}", notice);
+    assert!(notice.contains(&format!("  {} (scan ", api_url)));
+    assert_eq!(notice.matches("\nGPL-3.0-only\n").count(), 1);
+}
@@ -0,0 +1,172 @@
+//! GitHub and GitLab push webhooks re-scanning project repositories
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use common::{get_json, license_result, request, wait_for_scan, FIXTURE_FILES};
+use http_body_util::BodyExt;
+use legalscanner_api::{
+    api::routes::create_router,
+    export::processors::HmacSigner,
+    testing::{commit_fixture_changes, init_fixture_repo, test_config, test_state, MockScanner},
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// POST a webhook delivery with its provider headers
+async fn deliver(app: &Router, uri: &str, headers: &[(&str, &str)], body: &str) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json");
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    let response = app
+        .clone()
+        .oneshot(builder.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_push_webhooks_rescan_project_repositories() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology").with_results(vec![license_result("src/lib.rs", "MIT", 1.0)]),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = dir.path().join("fixture");
+    let git_url = init_fixture_repo(&fixture, FIXTURE_FILES);
+    let repo = git2::Repository::open(&fixture).unwrap();
+    let head = repo.head().unwrap();
+    let branch = head.shorthand().unwrap().to_string();
+    let commit = head.target().unwrap().to_string();
+    let push = json!({
+        "ref": format!("refs/heads/{}", branch),
+        "after": commit,
+        "deleted": false,
+        "repository": { "clone_url": git_url }
+    })
+    .to_string();
+    let signature = format!("sha256={}", HmacSigner::new("hook-secret").sign(push.as_bytes()));
+    let github = [("X-GitHub-Event", "push"), ("X-Hub-Signature-256", signature.as_str())];
+
+    // Refused until a secret is configured
+    let state = test_state(test_config(dir.path().join("workspaces")), fossology.clone()).await;
+    let (status, _) = deliver(&create_router(state), "/api/v1/hooks/github", &github, &push).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.push_webhook_secret = Some("hook-secret".to_string());
+    let app = create_router(test_state(config, fossology).await);
+    let forged = [("X-GitHub-Event", "push"), ("X-Hub-Signature-256", "sha256=00")];
+    let (status, _) = deliver(&app, "/api/v1/hooks/github", &forged, &push).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Repositories outside any project are not scanned
+    let (status, response) = deliver(&app, "/api/v1/hooks/github", &github, &push).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert!(response["ignored"].is_string(), "{}", response);
+    assert!(response.get("scan").is_none(), "{}", response);
+
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/projects",
+        Some(json!({ "name": "App", "repositories": [git_url] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, response) = deliver(&app, "/api/v1/hooks/github", &github, &push).await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{}", response);
+    assert_eq!(response["projects"], json!(["App"]));
+    assert_eq!(response["branch"], branch);
+    let scan_id = response["scan"]["scan_id"].as_str().unwrap();
+    let scan = wait_for_scan(&app, scan_id).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    assert_eq!(scan["branch"], branch);
+    let timeline = get_json(&app, &format!("/api/v1/scans/{}/timeline", scan_id)).await;
+    assert_eq!(
+        timeline["events"][0]["detail"],
+        format!("github push to {} at {}", branch, &commit[..12])
+    );
+
+    // GitLab sends the secret itself; tag pushes are acknowledged only
+    let tag = json!({
+        "ref": "refs/tags/v1.0",
+        "after": "309cf2674ee7a0749978cf8265ab91a60aea0f7d",
+        "project": { "git_http_url": git_url }
+    })
+    .to_string();
+    let gitlab = [("X-Gitlab-Event", "Push Hook"), ("X-Gitlab-Token", "hook-secret")];
+    let (status, response) = deliver(&app, "/api/v1/hooks/gitlab", &gitlab, &tag).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["ignored"], "refs/tags/v1.0 is not a branch");
+    let wrong = [("X-Gitlab-Event", "Push Hook"), ("X-Gitlab-Token", "guess")];
+    let (status, _) = deliver(&app, "/api/v1/hooks/gitlab", &wrong, &tag).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_push_webhooks_scan_the_pushed_commit_of_a_branch() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology").with_results(vec![license_result("src/lib.rs", "MIT", 1.0)]),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let fixture = dir.path().join("fixture");
+    let git_url = init_fixture_repo(&fixture, FIXTURE_FILES);
+
+    // Two commits on a feature branch, the push reports the first
+    let repo = git2::Repository::open(&fixture).unwrap();
+    let default_branch = repo.head().unwrap().name().unwrap().to_string();
+    let initial = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.branch("feature", &initial, false).unwrap();
+    repo.set_head("refs/heads/feature").unwrap();
+    commit_fixture_changes(&fixture, &[("src/feature.rs", "// MIT\n")], &[], "Add feature");
+    let pushed = repo.head().unwrap().target().unwrap().to_string();
+    commit_fixture_changes(&fixture, &[("src/later.rs", "// MIT\n")], &[], "Later change");
+    repo.set_head(&default_branch).unwrap();
+
+    let push = json!({
+        "ref": "refs/heads/feature",
+        "after": pushed,
+        "deleted": false,
+        "repository": { "clone_url": git_url }
+    })
+    .to_string();
+    let signature = format!("sha256={}", HmacSigner::new("hook-secret").sign(push.as_bytes()));
+    let github = [("X-GitHub-Event", "push"), ("X-Hub-Signature-256", signature.as_str())];
+
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.push_webhook_secret = Some("hook-secret".to_string());
+    let app = create_router(test_state(config, fossology).await);
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/projects",
+        Some(json!({ "name": "App", "repositories": [git_url] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, response) = deliver(&app, "/api/v1/hooks/github", &github, &push).await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{}", response);
+    assert_eq!(response["scan"]["commit_sha"], pushed);
+    let scan_id = response["scan"]["scan_id"].as_str().unwrap();
+    let scan = wait_for_scan(&app, scan_id).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    assert_eq!(scan["branch"], "feature");
+
+    // The branch's file at the pushed commit, not the later one
+    let detail = get_json(&app, &format!("/api/v1/scans/{}", scan_id)).await;
+    assert_eq!(detail["repository"]["commit_sha"], pushed, "{}", detail);
+    assert_eq!(detail["repository"]["file_count"], FIXTURE_FILES.len() + 1);
+}
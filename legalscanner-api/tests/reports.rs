@@ -0,0 +1,526 @@
+//! Reports and exports built from the findings of a scan
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use common::{get_json, license_result, request, run_scan, setup, FIXTURE_FILES};
+use http_body_util::BodyExt;
+use legalscanner_api::{
+    api::routes::create_router,
+    export::processors::{HmacSigner, SIGNATURE_HEADER},
+    scanner::{CopyrightFinding, EccFinding, LicenseFinding, ScanResult},
+    swhid::content_swhid,
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+};
+use serde_json::{json, Value};
+use std::{sync::Arc, time::Duration};
+use tower::ServiceExt;
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn test_license_summary_groups_files_by_license() {
+    let mut results: Vec<ScanResult> = (0..7)
+        .map(|i| license_result(&format!("src/m{}.rs", i), "MIT", 1.0))
+        .collect();
+    results.push(license_result("vendor/gpl.c", "GPL-3.0-only", 1.0));
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(results));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+
+    let summary = get_json(&app, &format!("/api/v1/scans/{}/licenses", scan_id)).await;
+    let licenses = summary["licenses"].as_array().unwrap();
+    assert_eq!(licenses.len(), 2, "{}", summary);
+
+    assert_eq!(licenses[0]["license"], "MIT");
+    assert_eq!(licenses[0]["spdx_id"], "MIT");
+    assert_eq!(licenses[0]["file_count"], 7);
+    assert_eq!(
+        licenses[0]["example_paths"],
+        json!([
+            "src/m0.rs",
+            "src/m1.rs",
+            "src/m2.rs",
+            "src/m3.rs",
+            "src/m4.rs"
+        ])
+    );
+    assert_eq!(licenses[0]["risk_weight"], 0);
+
+    assert_eq!(licenses[1]["license"], "GPL-3.0-only");
+    assert_eq!(licenses[1]["file_count"], 1);
+    assert_eq!(licenses[1]["example_paths"], json!(["vendor/gpl.c"]));
+    assert_eq!(licenses[1]["risk_weight"], 10);
+
+    // Without a license file or manifest, the most common license wins
+    let concluded = &summary["concluded_license"];
+    assert_eq!(concluded["concluded"], "MIT");
+    assert_eq!(concluded["declared"], "NOASSERTION");
+    assert_eq!(concluded["basis"], "majority");
+    let rationale = concluded["rationale"].as_str().unwrap();
+    assert!(rationale.contains("in 7 of 8 files"), "{}", rationale);
+    assert!(rationale.contains("GPL-3.0-only (1)"), "{}", rationale);
+
+    let sbom = get_json(&app, &format!("/api/v1/scans/{}/sbom", scan_id)).await;
+    assert_eq!(sbom["packages"][0]["licenseConcluded"], "MIT");
+    assert_eq!(sbom["packages"][0]["licenseComments"], concluded["rationale"]);
+
+    let (status, _) = request(&app, "GET", "/api/v1/scans/missing/licenses", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_coverage_per_language() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut files = FIXTURE_FILES.to_vec();
+    files.push(("tools/release.py", "print('release')\n"));
+    files.push(("tools/check.py", "print('check')\n"));
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), &files);
+    let results = vec![
+        license_result("src/lib.rs", "MIT", 1.0),
+        license_result("LICENSE", "MIT", 1.0),
+    ];
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(results));
+    let state = test_state(test_config(dir.path().join("workspaces")), fossology).await;
+    let app = create_router(state);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+
+    let coverage = get_json(&app, &format!("/api/v1/scans/{}/coverage", scan_id)).await;
+    let languages = coverage["languages"].as_array().unwrap();
+    assert_eq!(languages.len(), 2, "{}", coverage);
+    assert_eq!(languages[0]["language"], "Python");
+    assert_eq!(languages[0]["file_count"], 2);
+    assert_eq!(languages[0]["licensed_files"], 0);
+    assert_eq!(languages[0]["coverage_percent"], 0.0);
+    assert_eq!(languages[1]["language"], "Rust");
+    assert_eq!(languages[1]["licensed_files"], 1);
+    assert_eq!(languages[1]["coverage_percent"], 100.0);
+    assert_eq!(languages[1]["licenses"], json!([{ "license": "MIT", "file_count": 1 }]));
+
+    let (status, _) = request(&app, "GET", "/api/v1/scans/missing/coverage", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_stats_aggregate_completed_scans() {
+    let mut ecc = license_result("src/lib.rs", "MIT", 1.0);
+    ecc.ecc_findings.push(EccFinding {
+        content: "RSA key generation".to_string(),
+        risk_severity: "high".to_string(),
+        source: Some("semgrep".to_string()),
+        line_number: Some(1),
+        check_id: Some("ecc.crypto.rsa".to_string()),
+    });
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![
+        ecc,
+        license_result("vendor/gpl.c", "GPL-3.0-only", 1.0),
+    ]));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    run_scan(&app, json!({ "git_url": git_url })).await;
+    run_scan(&app, json!({ "git_url": format!("{}/", git_url) })).await;
+
+    let stats = get_json(&app, "/api/v1/stats?top_licenses=1").await;
+    assert_eq!(
+        stats["scans_by_status"],
+        json!([{ "status": "completed", "scan_count": 2 }])
+    );
+    assert!(stats["average_scan_duration_secs"].as_f64().unwrap() >= 0.0);
+    assert_eq!(
+        stats["top_licenses"],
+        json!([{ "license": "GPL-3.0-only", "scan_count": 2, "file_count": 2 }])
+    );
+
+    // Both scans are of the same repository
+    let repositories = stats["repositories_by_risk_level"].as_array().unwrap();
+    assert_eq!(repositories.len(), 1, "{}", stats);
+    assert_eq!(repositories[0]["repository_count"], 1);
+
+    let days = stats["ecc_findings_by_day"].as_array().unwrap();
+    assert_eq!(days.len(), 1, "{}", stats);
+    assert_eq!(days[0]["finding_count"], 2);
+    assert_eq!(days[0]["high"], 2);
+    assert_eq!(days[0]["critical"], 0);
+
+    let (status, _) = request(&app, "GET", "/api/v1/stats?days=0", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_findings_are_attributed_to_codeowners() {
+    let receiver = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&receiver)
+        .await;
+
+    let mut files = FIXTURE_FILES.to_vec();
+    files.push((
+        ".github/CODEOWNERS",
+        "*          @acme/legal\n/vendor/   @acme/platform\n",
+    ));
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), &files);
+    let fossology = MockScanner::new("fossology").with_results(vec![
+        license_result("src/lib.rs", "MIT", 1.0),
+        license_result("vendor/gpl.c", "GPL-3.0-only", 1.0),
+    ]);
+    let state = test_state(
+        test_config(dir.path().join("workspaces")),
+        Arc::new(fossology),
+    )
+    .await;
+    let app = create_router(state);
+
+    let callback_url = format!("{}/hook", receiver.uri());
+    let scan = run_scan(
+        &app,
+        json!({ "git_url": git_url, "callback_url": callback_url }),
+    )
+    .await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+
+    let uri = format!("/api/v1/scans/{}/owners", scan_id);
+    let ownership = get_json(&app, &uri).await;
+    assert_eq!(ownership["codeowners_path"], ".github/CODEOWNERS");
+    let owners = ownership["owners"].as_array().unwrap();
+    assert_eq!(owners[0]["owner"], "@acme/platform", "{}", ownership);
+    assert_eq!(owners[0]["file_count"], 1);
+    assert_eq!(owners[0]["violations"][0]["rule"], "copyleft_license");
+    assert_eq!(owners[0]["violations"][0]["file_path"], "vendor/gpl.c");
+    assert_eq!(owners[1]["owner"], "@acme/legal");
+    assert_eq!(owners[1]["violations"], json!([]));
+
+    let filtered = get_json(&app, &format!("{}?owner=@ACME/legal", uri)).await;
+    assert_eq!(filtered["owners"].as_array().unwrap().len(), 1);
+    assert_eq!(filtered["owners"][0]["owner"], "@acme/legal");
+
+    // The callback names the teams whose paths have violations
+    let mut requests = Vec::new();
+    for _ in 0..50 {
+        requests = receiver.received_requests().await.unwrap();
+        if !requests.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let payload: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(payload["owners"], json!(["@acme/platform"]));
+
+    // Repositories without CODEOWNERS have nothing to attribute
+    let (app, git_url, _dir) = setup(Arc::new(MockScanner::new("fossology"))).await;
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let uri = format!("/api/v1/scans/{}/owners", scan["scan_id"].as_str().unwrap());
+    let ownership = get_json(&app, &uri).await;
+    assert_eq!(ownership["codeowners_path"], Value::Null);
+    assert_eq!(ownership["owners"], json!([]));
+}
+
+#[tokio::test]
+async fn test_viewer_export_embeds_findings() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![
+        license_result("src/lib.rs", "MIT", 1.0),
+        license_result("vendor/gpl.c", "GPL-3.0-only", 1.0),
+    ]));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let uri = format!("/api/v1/scans/{}/viewer", scan["scan_id"].as_str().unwrap());
+    let (status, body) = request(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let html = String::from_utf8(body).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(!html.contains("__LEGALSCANNER_REPORT__"));
+    assert!(html.contains("\"file_path\":\"vendor/gpl.c\""), "{}", html);
+    assert!(html.contains("\"spdx_id\":\"GPL-3.0-only\""));
+    // No external resources, so the file opens without network access
+    assert!(!html.contains("src=\"http"));
+    assert!(!html.contains("href=\"http"));
+}
+
+#[tokio::test]
+async fn test_custom_fields_on_scans_and_reports() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology").with_results(vec![license_result("src/lib.rs", "MIT", 1.0)]),
+    );
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    let (status, _) = request(
+        &app,
+        "PUT",
+        "/api/v1/custom-fields/eccn",
+        Some(json!({
+            "label": "Export classification",
+            "field_type": "enum",
+            "options": ["EAR99", "5D002"],
+            "required": true
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = request(
+        &app,
+        "PUT",
+        "/api/v1/custom-fields/contract_id",
+        Some(json!({ "field_type": "string", "pattern": "^C-[0-9]+$" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let fields = get_json(&app, "/api/v1/custom-fields").await;
+    assert_eq!(fields["fields"].as_array().unwrap().len(), 2);
+
+    // Required field missing, then an invalid enum value
+    for custom_fields in [json!({}), json!({ "eccn": "3A001" })] {
+        let (status, _) = request(
+            &app,
+            "POST",
+            "/api/v1/scans",
+            Some(json!({ "git_url": git_url, "custom_fields": custom_fields })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let scan = run_scan(
+        &app,
+        json!({ "git_url": git_url, "custom_fields": { "eccn": "EAR99" } }),
+    )
+    .await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    assert_eq!(scan["custom_fields"], json!({ "eccn": "EAR99" }));
+
+    let uri = format!("/api/v1/scans/{}/custom-fields", scan_id);
+    let (status, _) = request(
+        &app,
+        "PUT",
+        &uri,
+        Some(json!({ "values": { "contract_id": "42" } })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = request(
+        &app,
+        "PUT",
+        &uri,
+        Some(json!({ "values": { "contract_id": "C-42" } })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let values: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        values["values"],
+        json!({ "contract_id": "C-42", "eccn": "EAR99" })
+    );
+
+    let (_, markdown) = request(
+        &app,
+        "GET",
+        &format!("/api/v1/scans/{}/markdown", scan_id),
+        None,
+    )
+    .await;
+    let markdown = String::from_utf8(markdown).unwrap();
+    assert!(
+        markdown.contains("| Export classification | EAR99 |"),
+        "{}",
+        markdown
+    );
+    assert!(markdown.contains("| contract_id | C-42 |"));
+
+    let sbom = get_json(&app, &format!("/api/v1/scans/{}/sbom", scan_id)).await;
+    assert_eq!(
+        sbom["creationInfo"]["comment"],
+        "contract_id: C-42\nExport classification: EAR99"
+    );
+
+    // Deleting the definition drops its values
+    let (status, _) = request(&app, "DELETE", "/api/v1/custom-fields/contract_id", None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let values = get_json(&app, &uri).await;
+    assert_eq!(values["values"], json!({ "eccn": "EAR99" }));
+}
+
+#[tokio::test]
+async fn test_sbom_export_post_processors() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![ScanResult {
+        file_path: "src/lib.rs".to_string(),
+        licenses: Vec::new(),
+        copyrights: vec![CopyrightFinding {
+            statement: "Copyright 2024 Jane Doe <jane@example.com>".to_string(),
+            holders: vec!["Jane Doe".to_string()],
+            years: vec!["2024".to_string()],
+        }],
+        ecc_findings: Vec::new(),
+        malware_findings: Vec::new(),
+        package_findings: Vec::new(),
+        model_licenses: Vec::new(),
+    }]));
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.export_post_processors = vec![
+        "redact-emails".to_string(),
+        "custom-fields".to_string(),
+        "sign".to_string(),
+    ];
+    config.export_custom_fields = vec![("contract".to_string(), "ACME-42".to_string())];
+    config.export_signing_key = Some("secret".to_string());
+    let app = create_router(test_state(config, fossology).await);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let uri = format!("/api/v1/scans/{}/sbom", scan["scan_id"].as_str().unwrap());
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(&uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let signature = response.headers()[SIGNATURE_HEADER]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+
+    assert_eq!(
+        signature,
+        format!("sha256={}", HmacSigner::new("secret").sign(&body))
+    );
+    let sbom: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(sbom["creationInfo"]["comment"], "contract: ACME-42");
+    let text = sbom.to_string();
+    assert!(text.contains("Jane Doe <[redacted]>"), "{}", text);
+    assert!(!text.contains("jane@example.com"));
+}
+
+#[tokio::test]
+async fn test_swhids_identify_upstream_origins_in_sbom() {
+    let lib_swhid = content_swhid(FIXTURE_FILES[1].1.as_bytes()).unwrap();
+    let archive = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/known/"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ &lib_swhid: { "known": true } })),
+        )
+        .mount(&archive)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/provenance/whereis/{}/", lib_swhid)))
+        .and(header("authorization", "Bearer swh-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!(format!(
+            "{};origin=https://github.com/upstream/adder;visit=swh:1:snp:0000000000000000000000000000000000000000",
+            lib_swhid
+        ))))
+        .expect(1)
+        .mount(&archive)
+        .await;
+
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![ScanResult {
+        file_path: "src/lib.rs".to_string(),
+        licenses: vec![LicenseFinding {
+            name: "MIT".to_string(),
+            spdx_id: Some("MIT".to_string()),
+            confidence: 1.0,
+            category: Default::default(),
+            provenance: None,
+            agreement: None,
+            exception: None,
+        }],
+        copyrights: Vec::new(),
+        ecc_findings: Vec::new(),
+        malware_findings: Vec::new(),
+        package_findings: Vec::new(),
+        model_licenses: Vec::new(),
+    }]));
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.software_heritage_url = Some(archive.uri());
+    config.software_heritage_token = Some("swh-token".to_string());
+    let app = create_router(test_state(config, fossology).await);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    let scan_id = scan["scan_id"].as_str().unwrap();
+
+    let swhids = get_json(&app, &format!("/api/v1/scans/{}/swhids", scan_id)).await;
+    let files = swhids["files"].as_array().unwrap();
+    assert_eq!(files.len(), FIXTURE_FILES.len(), "{}", swhids);
+    let lib = files.iter().find(|f| f["file_path"] == "src/lib.rs").unwrap();
+    assert_eq!(lib["swhid"], lib_swhid);
+    assert_eq!(lib["known"], true);
+    assert_eq!(lib["origin"], "https://github.com/upstream/adder");
+    let readme = files.iter().find(|f| f["file_path"] == "README.md").unwrap();
+    assert_eq!(readme["known"], false);
+    assert_eq!(readme["origin"], Value::Null);
+
+    let revision = swhids["revision_swhid"].as_str().unwrap();
+    assert!(revision.starts_with("swh:1:rev:"), "{}", revision);
+
+    let sbom = get_json(&app, &format!("/api/v1/scans/{}/sbom", scan_id)).await;
+    let refs = sbom["packages"][0]["externalRefs"].as_array().unwrap();
+    assert!(refs.iter().any(|r| r["referenceCategory"] == "PERSISTENT-ID"
+        && r["referenceType"] == "swh"
+        && r["referenceLocator"] == revision));
+    let file = &sbom["files"][0];
+    assert_eq!(file["fileName"], "src/lib.rs");
+    assert_eq!(
+        file["comment"],
+        format!(
+            "SWHID: {}; Upstream origin: https://github.com/upstream/adder",
+            lib_swhid
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_export_control_report() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![ScanResult {
+        file_path: "src/lib.rs".to_string(),
+        licenses: Vec::new(),
+        copyrights: Vec::new(),
+        ecc_findings: vec![EccFinding {
+            content: "RSA implementation detected.\n\n\
+                      Matched code: `RsaPrivateKey::new(&mut rng, 4096)`"
+                .to_string(),
+            risk_severity: "high".to_string(),
+            source: Some("semgrep".to_string()),
+            line_number: Some(7),
+            check_id: Some("semgrep-rules.ecc-rsa-implementation".to_string()),
+        }],
+        malware_findings: Vec::new(),
+        package_findings: Vec::new(),
+        model_licenses: Vec::new(),
+    }]));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let uri = format!(
+        "/api/v1/scans/{}/export-control/report",
+        scan["scan_id"].as_str().unwrap()
+    );
+    let (status, body) = request(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let report = String::from_utf8(body).unwrap();
+    assert!(report.contains("| Public key cryptography |"), "{}", report);
+    assert!(report.contains(
+        "| src/lib.rs:7 | ecc-rsa-implementation | high | `RsaPrivateKey::new(&mut rng, 4096)` | pending |"
+    ));
+    assert!(report.contains("Proposed classification: **5D002**"));
+    assert!(report.contains("### Trade compliance reviewer"));
+
+    let uri = "/api/v1/scans/missing/export-control/report";
+    let (status, _) = request(&app, "GET", uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,247 @@
+//! Result store backends: monthly partitions, Parquet and archived findings
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{get_json, license_result, request, request_as, run_scan, FIXTURE_FILES};
+use legalscanner_api::{
+    api::routes::create_router,
+    db::models::result_partition::ResultPartitioning,
+    storage::SqliteResultStore,
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+};
+use serde_json::json;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_archived_results_are_read_back_from_the_archive() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology").with_results(vec![license_result("src/lib.rs", "MIT", 1.0)]),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    config.result_archive = "disk".to_string();
+    config.result_archive_dir = dir.path().join("archive");
+    let state = test_state(config, fossology).await;
+    let db = state.db.clone();
+    let app = create_router(state);
+    let admin = Some("admin-secret");
+
+    // Scans finished 40 days ago and now
+    let mut scan_ids = Vec::new();
+    for days in [40, 0] {
+        let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+        let scan_id = scan["scan_id"].as_str().unwrap().to_string();
+        sqlx::query("UPDATE scans SET completed_at = datetime('now', ?) WHERE id = ?")
+            .bind(format!("-{} days", days))
+            .bind(&scan_id)
+            .execute(&db)
+            .await
+            .unwrap();
+        scan_ids.push(scan_id);
+    }
+    let old = &scan_ids[0];
+    let before = get_json(&app, &format!("/api/v1/scans/{}", old)).await;
+    let results_before = get_json(&app, &format!("/api/v1/scans/{}/results", old)).await;
+    let licenses_before = get_json(&app, &format!("/api/v1/scans/{}/licenses", old)).await;
+
+    let archive = "/api/v1/admin/archive";
+    let (status, _) = request_as(&app, "POST", archive, None, Some(json!({}))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    // Nothing is archived by default
+    let (status, _) = request_as(&app, "POST", archive, admin, Some(json!({}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let dry_run = json!({ "older_than_days": 30, "dry_run": true });
+    let (status, report) = request_as(&app, "POST", archive, admin, Some(dry_run)).await;
+    assert_eq!(status, StatusCode::OK, "{}", report);
+    assert_eq!(report["scans_archived"], json!([old]));
+    assert!(!dir.path().join("archive").exists());
+
+    let policy = json!({ "older_than_days": 30 });
+    let (status, report) = request_as(&app, "POST", archive, admin, Some(policy.clone())).await;
+    assert_eq!(status, StatusCode::OK, "{}", report);
+    assert_eq!(report["scans_archived"], json!([old]));
+    assert!(report["bytes_written"].as_u64().unwrap() > 0, "{}", report);
+    assert!(dir.path().join("archive").join(format!("{}.json.gz", old)).exists());
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM all_scan_results WHERE scan_id = ?")
+        .bind(old)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(stored, 0);
+
+    // The same API serves the archived findings
+    let archived = get_json(&app, &format!("/api/v1/scans/{}", old)).await;
+    assert!(archived["results_archived_at"].is_string(), "{}", archived);
+    assert_eq!(archived["summary"], before["summary"]);
+    assert_eq!(archived["risk_assessment"], before["risk_assessment"]);
+    let results = get_json(&app, &format!("/api/v1/scans/{}/results", old)).await;
+    assert_eq!(results["results"], results_before["results"]);
+    let licenses = get_json(&app, &format!("/api/v1/scans/{}/licenses", old)).await;
+    assert_eq!(licenses, licenses_before);
+    let verify = "/api/v1/admin/integrity/verify";
+    let (_, integrity) = request_as(&app, "POST", verify, admin, None).await;
+    assert_eq!(integrity["discrepancies"], json!([]), "{}", integrity);
+
+    // Archived findings are read-only
+    let review = json!({ "status": "approved" });
+    let uri = format!("/api/v1/scans/{}/results/review", old);
+    let (status, _) = request(&app, "POST", &uri, Some(review)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (_, report) = request_as(&app, "POST", archive, admin, Some(policy)).await;
+    assert_eq!(report["scans_archived"], json!([]));
+}
+
+#[tokio::test]
+async fn test_result_partitions_route_by_scan_month() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology").with_results(vec![license_result("src/lib.rs", "MIT", 1.0)]),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    let state = test_state(config, fossology).await;
+    let db = state.db.clone();
+
+    // Findings stored before partitioning stay in scan_results
+    let legacy = run_scan(&create_router(state.clone()), json!({ "git_url": git_url })).await;
+    let mut config = (*state.config).clone();
+    config.result_partitioning = ResultPartitioning::Monthly;
+    let state = legalscanner_api::AppState {
+        result_store: Arc::new(
+            SqliteResultStore::new(db.clone()).with_partitioning(config.result_partitioning),
+        ),
+        config: Arc::new(config),
+        ..state
+    };
+    let app = create_router(state);
+
+    // One scan created in November 2024, one now
+    sqlx::query(
+        "CREATE TRIGGER backdate AFTER INSERT ON scans BEGIN \
+         UPDATE scans SET created_at = '2024-11-05 10:00:00' WHERE id = NEW.id; END",
+    )
+    .execute(&db)
+    .await
+    .unwrap();
+    let old = run_scan(&app, json!({ "git_url": git_url })).await;
+    sqlx::query("DROP TRIGGER backdate").execute(&db).await.unwrap();
+    let recent = run_scan(&app, json!({ "git_url": git_url })).await;
+
+    let mut tables = Vec::new();
+    let mut ids = Vec::new();
+    for scan in [&legacy, &old, &recent] {
+        assert_eq!(scan["status"], "completed", "{}", scan);
+        let scan_id = scan["scan_id"].as_str().unwrap();
+        let table: Option<String> =
+            sqlx::query_scalar("SELECT results_table FROM scans WHERE id = ?")
+                .bind(scan_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        tables.push(table);
+        let results = get_json(&app, &format!("/api/v1/scans/{}/results", scan_id)).await;
+        let licenses = results["results"]["licenses"].as_array().unwrap();
+        assert_eq!(licenses.len(), 1, "{}", results);
+        ids.push(licenses[0]["id"].as_i64().unwrap());
+    }
+    assert_eq!(tables[0], None);
+    assert_eq!(tables[1].as_deref(), Some("scan_results_202411"));
+    assert!(tables[2].as_ref().unwrap().starts_with("scan_results_20"));
+    assert_ne!(tables[1], tables[2]);
+    let mut unique = ids.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    assert_eq!(unique.len(), 3, "{:?}", ids);
+
+    // Reviews and comments reach findings in a partition
+    let old_id = old["scan_id"].as_str().unwrap();
+    let (status, _) = request(
+        &app,
+        "POST",
+        &format!("/api/v1/scans/{}/results/review", old_id),
+        Some(json!({ "status": "approved", "result_type": "license", "comment": "Checked" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let uri = format!("/api/v1/scans/{}/results?review_status=approved", old_id);
+    let approved = get_json(&app, &uri).await;
+    assert_eq!(approved["results"]["licenses"].as_array().unwrap().len(), 1, "{}", approved);
+
+    // Statistics span every partition
+    let stats = get_json(&app, "/api/v1/stats?top_licenses=1").await;
+    assert_eq!(
+        stats["top_licenses"],
+        json!([{ "license": "MIT", "scan_count": 3, "file_count": 3 }])
+    );
+
+    // A partition emptied by retention is dropped
+    sqlx::query("UPDATE scans SET completed_at = datetime('now', '-400 days') WHERE id = ?")
+        .bind(old_id)
+        .execute(&db)
+        .await
+        .unwrap();
+    let policy = json!({ "results_older_than_days": 100 });
+    let (status, report) =
+        request_as(&app, "POST", "/api/v1/admin/purge", Some("admin-secret"), Some(policy)).await;
+    assert_eq!(status, StatusCode::OK, "{}", report);
+    assert_eq!(report["partitions_dropped"], json!(["scan_results_202411"]));
+    let purged = get_json(&app, &format!("/api/v1/scans/{}", old_id)).await;
+    assert_eq!(purged["summary"]["files_with_licenses"], 1, "{}", purged);
+    let comments: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM scan_result_comments")
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(comments, 0);
+    let stats = get_json(&app, "/api/v1/stats?top_licenses=1").await;
+    assert_eq!(stats["top_licenses"][0]["scan_count"], 2, "{}", stats);
+}
+
+#[tokio::test]
+async fn test_findings_in_parquet_result_store() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology").with_results(vec![license_result("src/lib.rs", "GPL-3.0", 1.0)]),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.result_store = "parquet".to_string();
+    config.result_store_dir = dir.path().join("results");
+    let state = test_state(config, fossology).await;
+    let db = state.db.clone();
+    let app = create_router(state);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    assert!(dir.path().join("results").join(format!("{}.parquet", scan_id)).exists());
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM all_scan_results")
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(stored, 0);
+
+    // Summary and risk score come from the Parquet object
+    let scan = get_json(&app, &format!("/api/v1/scans/{}", scan_id)).await;
+    assert_eq!(scan["summary"]["files_with_licenses"], 1, "{}", scan);
+    assert!(scan["risk_assessment"]["score"].as_i64().unwrap() > 0, "{}", scan);
+
+    let results = get_json(&app, &format!("/api/v1/scans/{}/results", scan_id)).await;
+    let gpl = results["results"]["licenses"][0].clone();
+    assert_eq!(gpl["spdx_id"], "GPL-3.0", "{}", results);
+    let (status, body) = request(
+        &app,
+        "POST",
+        &format!("/api/v1/scans/{}/results/review", scan_id),
+        Some(json!({ "status": "approved", "result_ids": [gpl["id"]] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", String::from_utf8_lossy(&body));
+    let uri = format!("/api/v1/scans/{}/results?review_status=approved", scan_id);
+    let approved = get_json(&app, &uri).await;
+    assert_eq!(approved["results"]["licenses"][0]["id"], gpl["id"], "{}", approved);
+}
@@ -0,0 +1,365 @@
+//! Removing data: retention purges, erasure requests, soft deletion and
+//! legal holds
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use common::{
+    erase, get_json, license_result, request, request_as, run_scan, wait_for_scan, FIXTURE_FILES,
+};
+use http_body_util::BodyExt;
+use legalscanner_api::{
+    api::routes::create_router,
+    scanner::{CopyrightFinding, ScanResult},
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_erasure_deletes_email_findings_and_repository_scans() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![
+        license_result("LICENSE", "MIT", 1.0),
+        ScanResult {
+            file_path: "src/lib.rs".to_string(),
+            licenses: Vec::new(),
+            copyrights: vec![CopyrightFinding {
+                statement: "Copyright 2024 Jane Doe <Jane@Example.com>".to_string(),
+                holders: vec!["Jane Doe".to_string()],
+                years: vec!["2024".to_string()],
+            }],
+            ecc_findings: Vec::new(),
+            malware_findings: Vec::new(),
+            package_findings: Vec::new(),
+            model_licenses: Vec::new(),
+        },
+    ]));
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    let state = test_state(config, fossology).await;
+    let app = create_router(state.clone());
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let results_uri = format!("/api/v1/scans/{}/results", scan_id);
+
+    let (status, _) = erase(&app, None, json!({ "email": "jane@example.com" })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = erase(&app, Some("wrong"), json!({ "email": "jane@example.com" })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = erase(&app, Some("admin-secret"), json!({})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, report) = erase(
+        &app,
+        Some("admin-secret"),
+        json!({ "email": "jane@example.com" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", report);
+    assert_eq!(report["results_deleted"], 1);
+    assert_eq!(report["checksums_resealed"], 1);
+    let results = get_json(&app, &results_uri).await.to_string();
+    assert!(!results.to_lowercase().contains("jane@example.com"));
+    assert!(results.contains("LICENSE"));
+    // The statement is gone from the normalization table as well
+    let statements: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM copyright_statements")
+        .fetch_one(&state.db)
+        .await
+        .unwrap();
+    assert_eq!(statements, 0);
+
+    let (status, report) = erase(
+        &app,
+        Some("admin-secret"),
+        json!({ "git_url": format!("{}.git", git_url) }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", report);
+    assert_eq!(report["scans_deleted"], json!([scan_id]));
+    assert_eq!(report["results_deleted"], 1);
+    let (status, _) = request(&app, "GET", &format!("/api/v1/scans/{}", scan_id), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(get_json(&app, "/api/v1/scans")
+        .await
+        .as_array()
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_legal_hold_blocks_deletion() {
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let other_url = init_fixture_repo(&dir.path().join("other"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    let fossology = Arc::new(MockScanner::new("fossology"));
+    let app = create_router(test_state(config, fossology).await);
+    let admin = Some("admin-secret");
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let hold_uri = format!("/api/v1/admin/scans/{}/legal-hold", scan_id);
+
+    let reason = json!({ "reason": "Matter 2025-014" });
+    let (status, _) = request_as(&app, "PUT", &hold_uri, None, Some(reason.clone())).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let blank = json!({ "reason": " " });
+    let (status, _) = request_as(&app, "PUT", &hold_uri, admin, Some(blank)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, hold) = request_as(&app, "PUT", &hold_uri, admin, Some(reason)).await;
+    assert_eq!(status, StatusCode::OK, "{}", hold);
+    assert_eq!(hold["legal_hold_reason"], "Matter 2025-014");
+
+    let scan_uri = format!("/api/v1/scans/{}", scan_id);
+    let (status, _) = request(&app, "DELETE", &scan_uri, None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = erase(&app, admin, json!({ "git_url": git_url })).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // Held project scans survive deleting everything else
+    let other = run_scan(&app, json!({ "git_url": other_url })).await;
+    let unrelated = run_scan(&app, json!({ "git_url": git_url })).await;
+    let (_, body) = request(
+        &app,
+        "POST",
+        "/api/v1/projects",
+        Some(json!({ "name": "Litigation", "repositories": [other_url] })),
+    )
+    .await;
+    let project: Value = serde_json::from_slice(&body).unwrap();
+    let project_hold_uri = format!(
+        "/api/v1/admin/projects/{}/legal-hold",
+        project["project_id"].as_str().unwrap()
+    );
+    let reason = json!({ "reason": "Audit" });
+    let (status, _) = request_as(&app, "PUT", &project_hold_uri, admin, Some(reason)).await;
+    assert_eq!(status, StatusCode::OK);
+    let project_uri = format!("/api/v1/projects/{}", project["project_id"].as_str().unwrap());
+    let (status, _) = request(&app, "DELETE", &project_uri, None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, body) = request(&app, "DELETE", "/api/v1/scans", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let deleted: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(deleted, json!({ "deleted": 1, "held": 2 }));
+    let unrelated_uri = format!("/api/v1/scans/{}", unrelated["scan_id"].as_str().unwrap());
+    let (status, _) = request(&app, "GET", &unrelated_uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    get_json(&app, &format!("/api/v1/scans/{}", other["scan_id"].as_str().unwrap())).await;
+
+    // Only an admin lifts a hold, and the audit log keeps both events
+    let (status, _) = request_as(&app, "DELETE", &hold_uri, None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, lifted) = request_as(&app, "DELETE", &hold_uri, admin, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(lifted["legal_hold_at"], Value::Null);
+    let (status, _) = request_as(&app, "DELETE", &hold_uri, admin, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = request(&app, "DELETE", &scan_uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let events_uri = format!("/api/v1/admin/legal-holds/events?target_id={}", scan_id);
+    let (status, log) = request_as(&app, "GET", &events_uri, admin, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let actions: Vec<&str> = log["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(actions, ["placed", "lifted"]);
+}
+
+#[tokio::test]
+async fn test_deleted_scans_are_audited_and_restorable() {
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    let fossology = Arc::new(
+        MockScanner::new("fossology").with_results(vec![license_result("src/lib.rs", "MIT", 1.0)]),
+    );
+    let app = create_router(test_state(config, fossology).await);
+    let admin = Some("admin-secret");
+
+    let (status, key) = request_as(
+        &app,
+        "POST",
+        "/api/v1/api-keys",
+        None,
+        Some(json!({ "name": "ci" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let key_id = key["id"].as_str().unwrap();
+    let with_key = |method: &str, uri: &str, payload: Option<Value>| {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("x-api-key", key["key"].as_str().unwrap())
+            .body(
+                payload
+                    .map(|p| Body::from(p.to_string()))
+                    .unwrap_or_else(Body::empty),
+            )
+            .unwrap();
+        app.clone().oneshot(request)
+    };
+
+    let response = with_key("POST", "/api/v1/scans", Some(json!({ "git_url": git_url })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let scan_id = serde_json::from_slice::<Value>(&body).unwrap()["scan_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    wait_for_scan(&app, &scan_id).await;
+
+    let scan_uri = format!("/api/v1/scans/{}", scan_id);
+    let response = with_key("DELETE", &scan_uri, None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let (status, _) = request(&app, "GET", &scan_uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let scans = get_json(&app, "/api/v1/scans").await;
+    assert!(scans.as_array().unwrap().is_empty(), "{}", scans);
+
+    // Only an admin restores, and the findings are back with the scan
+    let restore_uri = format!("/api/v1/admin/scans/{}/restore", scan_id);
+    let (status, _) = request_as(&app, "POST", &restore_uri, None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, restored) = request_as(&app, "POST", &restore_uri, admin, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", restored);
+    let (status, _) = request_as(&app, "POST", &restore_uri, admin, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let results = get_json(&app, &format!("{}/results", scan_uri)).await;
+    assert!(!results["results"]["licenses"].as_array().unwrap().is_empty(), "{}", results);
+
+    let log_uri = format!("/api/v1/admin/audit-log?target_id={}", scan_id);
+    let (status, _) = request_as(&app, "GET", &log_uri, None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, log) = request_as(&app, "GET", &log_uri, admin, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let entries: Vec<(&str, &str, &Value)> = log["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| {
+            (
+                e["action"].as_str().unwrap(),
+                e["actor"].as_str().unwrap(),
+                &e["api_key_id"],
+            )
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            ("create", "api_key", &json!(key_id)),
+            ("delete", "api_key", &json!(key_id)),
+            ("restore", "admin", &Value::Null),
+        ]
+    );
+
+    let (_, log) = request_as(&app, "GET", "/api/v1/admin/audit-log?action=create", admin, None).await;
+    let targets: Vec<&str> = log["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["target_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(targets, ["api_key", "scan"]);
+}
+
+#[tokio::test]
+async fn test_retention_purge_keeps_summaries_and_held_scans() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology").with_results(vec![license_result("src/lib.rs", "MIT", 1.0)]),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    let state = test_state(config, fossology).await;
+    let db = state.db.clone();
+    let app = create_router(state);
+    let admin = Some("admin-secret");
+
+    // Scans finished 200, 40 and 0 days ago, and one held for 200 days
+    let mut scan_ids = Vec::new();
+    for days in [200, 40, 0, 200] {
+        let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+        let scan_id = scan["scan_id"].as_str().unwrap().to_string();
+        sqlx::query("UPDATE scans SET completed_at = datetime('now', ?) WHERE id = ?")
+            .bind(format!("-{} days", days))
+            .bind(&scan_id)
+            .execute(&db)
+            .await
+            .unwrap();
+        scan_ids.push(scan_id);
+    }
+    let hold_uri = format!("/api/v1/admin/scans/{}/legal-hold", scan_ids[3]);
+    let reason = json!({ "reason": "Matter 2025-014" });
+    let (status, _) = request_as(&app, "PUT", &hold_uri, admin, Some(reason)).await;
+    assert_eq!(status, StatusCode::OK);
+    let before = get_json(&app, &format!("/api/v1/scans/{}", scan_ids[1])).await;
+
+    let purge = "/api/v1/admin/purge";
+    let (status, _) = request_as(&app, "POST", purge, None, Some(json!({}))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    // Nothing expires by default
+    let (status, _) = request_as(&app, "POST", purge, admin, Some(json!({}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let policy = json!({ "results_older_than_days": 30, "scans_older_than_days": 100 });
+    let mut dry_run = policy.clone();
+    dry_run["dry_run"] = json!(true);
+    let (status, report) = request_as(&app, "POST", purge, admin, Some(dry_run)).await;
+    assert_eq!(status, StatusCode::OK, "{}", report);
+    assert_eq!(report["scans_deleted"], json!([scan_ids[0]]));
+    assert_eq!(report["scans_purged"], json!([scan_ids[1]]));
+    assert_eq!(report["scans_held"], json!([scan_ids[3]]));
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM scans")
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(count, 4);
+
+    let (status, report) = request_as(&app, "POST", purge, admin, Some(policy.clone())).await;
+    assert_eq!(status, StatusCode::OK, "{}", report);
+    assert_eq!(report["dry_run"], false);
+    assert!(report["results_deleted"].as_u64().unwrap() > 0, "{}", report);
+    assert_eq!(report["checksums_resealed"], 1);
+
+    let (status, _) = request(&app, "GET", &format!("/api/v1/scans/{}", scan_ids[0]), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let purged = get_json(&app, &format!("/api/v1/scans/{}", scan_ids[1])).await;
+    assert!(purged["results_purged_at"].is_string(), "{}", purged);
+    assert_eq!(purged["summary"], before["summary"]);
+    assert_eq!(purged["risk_assessment"], before["risk_assessment"]);
+    let results = get_json(&app, &format!("/api/v1/scans/{}/results", scan_ids[1])).await;
+    assert_eq!(results["results"]["licenses"], json!([]), "{}", results);
+    for kept in [&scan_ids[2], &scan_ids[3]] {
+        let results = get_json(&app, &format!("/api/v1/scans/{}/results", kept)).await;
+        assert_eq!(results["results"]["licenses"].as_array().unwrap().len(), 1, "{}", results);
+    }
+
+    // Purged findings are not reported as corruption, and a second purge
+    // finds nothing left to do
+    let verify = "/api/v1/admin/integrity/verify";
+    let (_, integrity) = request_as(&app, "POST", verify, admin, None).await;
+    assert_eq!(integrity["discrepancies"], json!([]), "{}", integrity);
+    let (_, report) = request_as(&app, "POST", purge, admin, Some(policy)).await;
+    assert_eq!(report["scans_deleted"], json!([]));
+    assert_eq!(report["scans_purged"], json!([]));
+}
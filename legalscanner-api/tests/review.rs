@@ -0,0 +1,374 @@
+//! Review, waivers, screening and risk scoring of findings
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{get_json, license_result, request, request_as, run_scan, setup, FIXTURE_FILES};
+use legalscanner_api::{
+    api::routes::create_router,
+    scanner::{EccFinding, ScanResult},
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn test_waived_findings_leave_risk_score() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![
+        license_result("src/lib.rs", "MIT", 1.0),
+        license_result("vendor/gpl.c", "GPL-3.0-only", 1.0),
+    ]));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let score = scan["risk_assessment"]["score"].as_i64().unwrap();
+    assert!(score > 0, "{}", scan);
+
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/waivers",
+        Some(json!({ "scan_id": scan_id, "license": "GPL-3.0-only", "justification": "" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/waivers",
+        Some(json!({ "scan_id": scan_id, "justification": "Reviewed" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let waiver = json!({
+        "git_url": format!("{}.git", git_url),
+        "file_path": "vendor/",
+        "license": "gpl-3.0-only",
+        "justification": "Build tooling, not distributed",
+        "expires_at": "2999-01-01"
+    });
+    let (status, body) = request(&app, "POST", "/api/v1/waivers", Some(waiver)).await;
+    assert_eq!(
+        status,
+        StatusCode::CREATED,
+        "{}",
+        String::from_utf8_lossy(&body)
+    );
+    let waiver: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(waiver["rescored_scans"], 1);
+    assert_eq!(waiver["expires_at"], "2999-01-01 00:00:00");
+    let waiver_id = waiver["id"].as_str().unwrap();
+
+    let scan_uri = format!("/api/v1/scans/{}", scan_id);
+    let waived = get_json(&app, &scan_uri).await;
+    assert!(waived["risk_assessment"]["score"].as_i64().unwrap() < score, "{}", waived);
+
+    let results = get_json(&app, &format!("{}/results", scan_uri)).await;
+    let licenses = results["results"]["licenses"].as_array().unwrap();
+    let gpl = licenses
+        .iter()
+        .find(|l| l["file_path"] == "vendor/gpl.c")
+        .unwrap();
+    assert_eq!(gpl["waiver_id"], waiver_id);
+    let mit = licenses
+        .iter()
+        .find(|l| l["file_path"] == "src/lib.rs")
+        .unwrap();
+    assert_eq!(mit["waiver_id"], Value::Null);
+
+    let export_uri = format!("{}/export?format=csv", scan_uri);
+    let (status, body) = request(&app, "GET", &export_uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let csv = String::from_utf8(body).unwrap();
+    let gpl_row = csv.lines().find(|l| l.starts_with("vendor/gpl.c")).unwrap();
+    assert!(gpl_row.ends_with(waiver_id), "{}", csv);
+
+    let export_uri = format!("{}/results/export?format=parquet", scan_uri);
+    let (status, parquet) = request(&app, "GET", &export_uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(&parquet[..4], b"PAR1");
+    assert_eq!(&parquet[parquet.len() - 4..], b"PAR1");
+
+    let listed = get_json(&app, &format!("/api/v1/waivers?git_url={}", git_url)).await;
+    assert_eq!(listed["waivers"].as_array().unwrap().len(), 1);
+
+    let uri = format!("/api/v1/waivers/{}", waiver_id);
+    let (status, _) = request(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let restored = get_json(&app, &scan_uri).await;
+    assert_eq!(restored["risk_assessment"]["score"].as_i64().unwrap(), score);
+    let (status, _) = request(&app, "DELETE", &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_dual_licensed_files_are_scored_by_the_lesser_license() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![
+        license_result("src/lib.rs", "GPL-2.0-only OR MIT", 1.0),
+        license_result("src/parse.rs", "mit or gpl-2.0", 1.0),
+        license_result("vendor/gpl.c", "GPL-3.0-only AND MIT", 1.0),
+    ]));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let factors = scan["risk_assessment"]["factors"].as_array().unwrap();
+    let copyleft = factors
+        .iter()
+        .find(|f| f["category"] == "copyleft_license")
+        .unwrap();
+    assert_eq!(copyleft["details"], json!(["GPL-3.0-only (1 files)"]), "{}", scan);
+    assert!(factors.iter().all(|f| f["category"] != "missing_spdx_id"), "{}", scan);
+
+    let summary = get_json(
+        &app,
+        &format!("/api/v1/scans/{}/licenses", scan["scan_id"].as_str().unwrap()),
+    )
+    .await;
+    let weight = |license: &str| {
+        summary["licenses"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|l| l["license"] == license)
+            .unwrap()["risk_weight"]
+            .as_i64()
+            .unwrap()
+    };
+    assert_eq!(weight("GPL-2.0-only OR MIT"), 0);
+    assert_eq!(weight("GPL-3.0-only AND MIT"), 10);
+}
+
+#[tokio::test]
+async fn test_review_workflow() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![
+        license_result("src/lib.rs", "MIT", 1.0),
+        license_result("vendor/gpl.c", "GPL-3.0-only", 1.0),
+    ]));
+    let (app, git_url, _dir) = setup(fossology).await;
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let review_uri = format!("/api/v1/scans/{}/review", scan_id);
+
+    let review = get_json(&app, &review_uri).await;
+    assert_eq!(review["unresolved_high_severity"], 1, "{}", review);
+    let (status, _) = request(&app, "POST", &review_uri, None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, body) = request(
+        &app,
+        "POST",
+        &format!("/api/v1/scans/{}/results/review", scan_id),
+        Some(json!({
+            "status": "approved",
+            "result_type": "license",
+            "comment": "Build tooling only",
+            "author": "legal@example.com"
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", String::from_utf8_lossy(&body));
+    let reviewed: Value = serde_json::from_slice(&body).unwrap();
+    assert!(reviewed["updated"].as_u64().unwrap() >= 2, "{}", reviewed);
+    assert_eq!(reviewed["review"]["unresolved_high_severity"], 0);
+
+    let uri = format!("/api/v1/scans/{}/results?review_status=pending", scan_id);
+    let pending = get_json(&app, &uri).await;
+    assert!(!pending.to_string().contains("vendor/gpl.c"), "{}", pending);
+
+    let results = get_json(&app, &format!("/api/v1/scans/{}/results", scan_id)).await;
+    let gpl = results["results"]["licenses"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["file_path"] == "vendor/gpl.c")
+        .unwrap()
+        .clone();
+    assert_eq!(gpl["review_status"], "approved");
+
+    let comments_uri = format!("/api/v1/scans/{}/results/{}/comments", scan_id, gpl["id"]);
+    let (status, _) = request(
+        &app,
+        "POST",
+        &comments_uri,
+        Some(json!({ "body": "Confirmed with the vendor" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let comments = get_json(&app, &comments_uri).await;
+    let bodies: Vec<&str> = comments["comments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["body"].as_str().unwrap())
+        .collect();
+    assert_eq!(bodies, ["Build tooling only", "Confirmed with the vendor"]);
+
+    let (status, body) = request(&app, "POST", &review_uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", String::from_utf8_lossy(&body));
+    let review: Value = serde_json::from_slice(&body).unwrap();
+    assert!(review["reviewed_at"].is_string());
+
+    // Reopening the GPL finding withdraws the reviewed mark
+    let (status, body) = request(
+        &app,
+        "POST",
+        &format!("/api/v1/scans/{}/results/review", scan_id),
+        Some(json!({ "status": "pending", "result_ids": [gpl["id"]] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let reviewed: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(reviewed["review"]["reviewed_at"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_critical_ecc_blocks_approval_until_screening_clears() {
+    let screening = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/screen"))
+        .and(header("authorization", "Bearer screening-token"))
+        .respond_with(ResponseTemplate::new(202).set_body_json(json!({ "reference": "TC-7" })))
+        .expect(1)
+        .mount(&screening)
+        .await;
+
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![ScanResult {
+        file_path: "src/lib.rs".to_string(),
+        licenses: Vec::new(),
+        copyrights: Vec::new(),
+        ecc_findings: vec![EccFinding {
+            content: "AES-256 key schedule".to_string(),
+            risk_severity: "critical".to_string(),
+            source: Some("semgrep".to_string()),
+            line_number: Some(2),
+            check_id: Some("ecc.crypto.aes".to_string()),
+        }],
+        malware_findings: Vec::new(),
+        package_findings: Vec::new(),
+        model_licenses: Vec::new(),
+    }]));
+
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.screening_webhook_url = Some(format!("{}/screen", screening.uri()));
+    config.screening_webhook_token = Some("screening-token".to_string());
+    let app = create_router(test_state(config, fossology).await);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    assert_eq!(scan["screening_status"], "pending", "{}", scan);
+    let scan_id = scan["scan_id"].as_str().unwrap();
+
+    let approve = format!("/api/v1/scans/{}/approve", scan_id);
+    let (status, _) = request(&app, "POST", &approve, None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, body) = request(
+        &app,
+        "PUT",
+        &format!("/api/v1/scans/{}/screening", scan_id),
+        Some(json!({ "status": "cleared" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{}", String::from_utf8_lossy(&body));
+    let screening_record: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(screening_record["reference"], "TC-7");
+
+    let (status, body) = request(&app, "POST", &approve, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let approval: Value = serde_json::from_slice(&body).unwrap();
+    assert!(approval["approved_at"].is_string());
+}
+
+#[tokio::test]
+async fn test_risk_recalculation_after_config_change() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![
+        license_result("src/lib.rs", "MIT", 1.0),
+        license_result("vendor/gpl.c", "GPL-3.0-only", 1.0),
+    ]));
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    let state = test_state(config, fossology).await;
+    let db = state.db.clone();
+    let app = create_router(state);
+    let admin = Some("admin-secret");
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let risk = &scan["risk_assessment"];
+    let score = risk["score"].as_i64().unwrap();
+    let config_version = risk["config_version"].as_str().unwrap().to_string();
+    assert_eq!(risk["algorithm_version"], 1, "{}", scan);
+    assert_eq!(config_version.len(), 16);
+    assert!(risk["assessed_at"].is_string(), "{}", scan);
+
+    let recalculate_uri = format!("/api/v1/scans/{}/risk/recalculate", scan_id);
+    let (status, body) = request(&app, "POST", &recalculate_uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let recalculation: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(recalculation["changed"], false, "{}", recalculation);
+    assert_eq!(recalculation["previous"]["score"], score);
+    assert_eq!(recalculation["current"]["config_version"], config_version.as_str());
+    let (status, _) = request(&app, "POST", "/api/v1/scans/missing/risk/recalculate", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let bulk_uri = "/api/v1/admin/risk/recalculate";
+    let (status, _) = request_as(&app, "POST", bulk_uri, None, Some(json!({}))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (_, report) = request_as(&app, "POST", bulk_uri, admin, Some(json!({}))).await;
+    assert_eq!(report["scans_rescored"], 0, "{}", report);
+
+    sqlx::query("UPDATE risk_config SET risk_weight = 30 WHERE license_pattern = 'GPL-3.0%'")
+        .execute(&db)
+        .await
+        .unwrap();
+
+    let (status, report) =
+        request_as(&app, "POST", bulk_uri, admin, Some(json!({ "dry_run": true }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["scans_rescored"], 1, "{}", report);
+    assert_ne!(report["config_version"], config_version.as_str());
+    let change = &report["changes"][0];
+    assert_eq!(change["previous"]["score"], score);
+    assert!(change["current"]["score"].as_i64().unwrap() > score, "{}", report);
+    assert!(change["current"]["assessed_at"].is_null());
+    let unchanged = get_json(&app, &format!("/api/v1/scans/{}", scan_id)).await;
+    assert_eq!(unchanged["risk_assessment"]["score"], score);
+
+    let (_, report) = request_as(&app, "POST", bulk_uri, admin, Some(json!({}))).await;
+    assert_eq!(report["scans_rescored"], 1, "{}", report);
+    let rescored = get_json(&app, &format!("/api/v1/scans/{}", scan_id)).await;
+    assert_eq!(
+        rescored["risk_assessment"]["score"],
+        report["changes"][0]["current"]["score"]
+    );
+    assert_eq!(
+        rescored["risk_assessment"]["config_version"],
+        report["config_version"]
+    );
+    let (_, report) = request_as(&app, "POST", bulk_uri, admin, Some(json!({}))).await;
+    assert_eq!(report["scans_rescored"], 0, "{}", report);
+
+    // Assessments stored before versions were recorded are outdated
+    sqlx::query("UPDATE scans SET risk_algorithm_version = NULL, risk_config_version = NULL")
+        .execute(&db)
+        .await
+        .unwrap();
+    let legacy = get_json(&app, &format!("/api/v1/scans/{}", scan_id)).await;
+    assert!(legacy["risk_assessment"]["algorithm_version"].is_null(), "{}", legacy);
+    let (_, report) = request_as(&app, "POST", bulk_uri, admin, Some(json!({}))).await;
+    assert_eq!(report["scans_rescored"], 1, "{}", report);
+    assert_eq!(report["changes"], json!([]));
+    let (_, report) =
+        request_as(&app, "POST", bulk_uri, admin, Some(json!({ "all": true, "dry_run": true }))).await;
+    assert_eq!(report["scans_rescored"], 1, "{}", report);
+}
@@ -0,0 +1,54 @@
+//! Read-only share links to scan reports
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{get_json, license_result, request, run_scan, FIXTURE_FILES};
+use legalscanner_api::{
+    api::routes::create_router,
+    testing::{init_fixture_repo, test_config, test_state, MockScanner},
+    utils::crypto::hash_share_token,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_share_link_serves_report_until_revoked() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology").with_results(vec![license_result("LICENSE", "MIT", 1.0)]),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let state = test_state(test_config(dir.path().join("workspaces")), fossology).await;
+    let db = state.db.clone();
+    let app = create_router(state);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url })).await;
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let shares = format!("/api/v1/scans/{}/shares", scan_id);
+    let (status, body) = request(&app, "POST", &shares, Some(json!({}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let share: Value = serde_json::from_slice(&body).unwrap();
+    let token = share["token"].as_str().unwrap();
+
+    // Only the SHA-256 of the token is stored
+    let stored: String = sqlx::query_scalar("SELECT token_hash FROM scan_shares WHERE id = ?")
+        .bind(share["id"].as_str().unwrap())
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(stored, hash_share_token(token));
+
+    let url = share["url"].as_str().unwrap();
+    let report = get_json(&app, url).await;
+    assert_eq!(report["status"], "completed");
+    assert_eq!(report["licenses"][0]["license"], "MIT", "{}", report);
+    let (status, _) = request(&app, "GET", "/api/v1/shared/lgsh_unknown", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let revoke = format!("{}/{}", shares, share["id"].as_str().unwrap());
+    let (status, _) = request(&app, "DELETE", &revoke, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = request(&app, "GET", url, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,395 @@
+//! Scan sources besides a plain clone: uploaded archives, local directories,
+//! subtrees, submodules and registered credentials
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use common::{
+    get_json, license_result, request, request_as, run_scan, setup, start_and_wait, wait_for_scan,
+    FIXTURE_FILES,
+};
+use http_body_util::BodyExt;
+use legalscanner_api::{
+    api::routes::create_router,
+    scanner::semgrep::SemgrepMode,
+    testing::{add_fixture_submodule, init_fixture_repo, test_config, test_state, MockScanner},
+    AppState,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_submodules_are_checked_out_and_traced() {
+    let fossology = Arc::new(MockScanner::new("fossology").with_results(vec![
+        license_result("src/lib.rs", "MIT", 1.0),
+        license_result("vendor/zlib/zlib.h", "Zlib", 1.0),
+    ]));
+    let (app, git_url, dir) = setup(fossology).await;
+    let fixture = dir.path().join("fixture");
+    let zlib_url = init_fixture_repo(&dir.path().join("zlib"), &[("zlib.h", "/* zlib */\n")]);
+    add_fixture_submodule(&fixture, &zlib_url, "vendor/zlib");
+    let gone = dir.path().join("gone");
+    let gone_url = init_fixture_repo(&gone, &[("README", "gone\n")]);
+    add_fixture_submodule(&fixture, &gone_url, "vendor/gone");
+    std::fs::remove_dir_all(&gone).unwrap();
+
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": git_url, "submodule_token": "t" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url, "submodules": true })).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    let submodules = scan["submodules"].as_array().unwrap();
+    assert_eq!(submodules.len(), 2, "{}", scan);
+    assert_eq!(submodules[1]["path"], "vendor/zlib");
+    assert_eq!(submodules[1]["url"], zlib_url.as_str());
+    assert_eq!(submodules[1]["commit_sha"].as_str().unwrap().len(), 40);
+    assert!(submodules[1].get("error").is_none());
+    assert_eq!(submodules[0]["path"], "vendor/gone");
+    assert!(submodules[0]["error"].is_string());
+    let warnings = scan["warnings"].as_array().unwrap();
+    assert!(warnings[0].as_str().unwrap().contains("vendor/gone"));
+
+    let uri = format!("/api/v1/scans/{}/results", scan["scan_id"].as_str().unwrap());
+    let results = get_json(&app, &uri).await;
+    let licenses = results["results"]["licenses"].as_array().unwrap();
+    let zlib = licenses.iter().find(|l| l["spdx_id"] == "Zlib").unwrap();
+    assert_eq!(zlib["submodule"], "vendor/zlib");
+    let mit = licenses.iter().find(|l| l["spdx_id"] == "MIT").unwrap();
+    assert_eq!(mit["submodule"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_submodule_tokens_are_stored_encrypted() {
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let payload = json!({ "git_url": git_url, "submodules": true, "submodule_token": "sub-secret" });
+
+    let mut config = test_config(dir.path().join("unkeyed"));
+    config.credentials_encryption_key = None;
+    let unkeyed = create_router(test_state(config, Arc::new(MockScanner::new("fossology"))).await);
+    let (status, _) = request(&unkeyed, "POST", "/api/v1/scans", Some(payload.clone())).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let state = test_state(
+        test_config(dir.path().join("workspaces")),
+        Arc::new(MockScanner::new("fossology")),
+    )
+    .await;
+    let db = state.db.clone();
+    let app = create_router(state);
+    let scan = run_scan(&app, payload).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    assert!(!scan.to_string().contains("sub-secret"));
+
+    // Re-runs seal the token again for their own scan
+    let rerun = format!("/api/v1/scans/{}/rerun", scan["scan_id"].as_str().unwrap());
+    let rerun_scan = start_and_wait(&app, &rerun, None).await;
+    assert_eq!(rerun_scan["status"], "completed", "{}", rerun_scan);
+
+    let stored: Vec<String> = sqlx::query_scalar(
+        "SELECT submodule_token_encrypted FROM scans WHERE submodule_token_encrypted IS NOT NULL",
+    )
+    .fetch_all(&db)
+    .await
+    .unwrap();
+    assert_eq!(stored.len(), 2);
+    assert!(stored.iter().all(|token| !token.contains("sub-secret")));
+    assert_ne!(stored[0], stored[1]);
+}
+
+/// Multipart body with the archive as `file` followed by text fields
+fn multipart_body(boundary: &str, archive: &[u8], fields: &[(&str, &str)]) -> Vec<u8> {
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"vendor-lib-1.2.tar.gz\"\r\nContent-Type: application/gzip\r\n\r\n",
+        boundary
+    )
+    .into_bytes();
+    body.extend_from_slice(archive);
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "\r\n--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}",
+                boundary, name, value
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+async fn upload(app: &Router, body: Vec<u8>, boundary: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/v1/scans/upload")
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn test_upload_scan_extracts_archive() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology").with_results(vec![license_result("src/lib.rs", "MIT", 1.0)]),
+    );
+    let (app, _git_url, _dir) = setup(fossology.clone()).await;
+
+    // Release tarballs wrap the sources in a versioned directory
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        Vec::new(),
+        flate2::Compression::default(),
+    ));
+    for (path, content) in FIXTURE_FILES {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(
+                &mut header,
+                format!("vendor-lib-1.2/{}", path),
+                content.as_bytes(),
+            )
+            .unwrap();
+    }
+    let archive = builder.into_inner().unwrap().finish().unwrap();
+
+    let boundary = "legalscanner-test-boundary";
+    let body = multipart_body(boundary, &archive, &[("name", "vendor-lib")]);
+    let (status, created) = upload(&app, body, boundary).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", created);
+    assert_eq!(created["git_url"], "upload://vendor-lib");
+
+    let scan_id = created["scan_id"].as_str().unwrap();
+    let scan = wait_for_scan(&app, scan_id).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    assert_eq!(fossology.scan_count(), 1);
+
+    let timeline = get_json(&app, &format!("/api/v1/scans/{}/timeline", scan_id)).await;
+    let extracted = format!("{} files extracted", FIXTURE_FILES.len());
+    assert!(timeline["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|e| e["event_type"] == "clone_finished" && e["detail"] == extracted.as_str()));
+
+    // The archive is gone, so there is nothing to scan again
+    let (status, _) = request(
+        &app,
+        "POST",
+        &format!("/api/v1/scans/{}/rerun", scan_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let body = multipart_body(boundary, b"not an archive", &[]);
+    let (status, error) = upload(&app, body, boundary).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", error);
+}
+
+#[tokio::test]
+async fn test_scan_path_limits_scan_to_subtree() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut files = FIXTURE_FILES.to_vec();
+    files.push((
+        "services/payments/Cargo.toml",
+        "[package]\nname = \"payments\"\nlicense = \"Apache-2.0\"\n",
+    ));
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), &files);
+    let state = test_state(
+        test_config(dir.path().join("workspaces")),
+        Arc::new(MockScanner::new("fossology")),
+    )
+    .await;
+    let app = create_router(state);
+
+    let scan = run_scan(
+        &app,
+        json!({ "git_url": git_url, "quick": true, "scan_path": "/services/payments/" }),
+    )
+    .await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    assert_eq!(scan["scan_path"], "services/payments");
+
+    // Declared licenses are read from the subtree root, the repository's
+    // own LICENSE is out of scope
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let results = get_json(&app, &format!("/api/v1/scans/{}/results", scan_id)).await;
+    let text = results.to_string();
+    assert!(text.contains("Apache-2.0"), "{}", text);
+    assert!(text.contains("\"Cargo.toml\""), "{}", text);
+    assert!(!text.contains("MIT"), "{}", text);
+
+    let sbom = get_json(&app, &format!("/api/v1/scans/{}/sbom", scan_id)).await;
+    assert_eq!(sbom["packages"][0]["name"], "fixture/services/payments");
+
+    let missing = run_scan(
+        &app,
+        json!({ "git_url": git_url, "subpath": "services/ledger" }),
+    )
+    .await;
+    assert_eq!(missing["status"], "failed");
+    assert!(missing["error_message"]
+        .as_str()
+        .unwrap()
+        .contains("services/ledger"));
+
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": git_url, "scan_path": "../outside" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_local_path_scan_runs_in_place() {
+    let fossology = Arc::new(
+        MockScanner::new("fossology").with_results(vec![license_result("src/lib.rs", "MIT", 1.0)]),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let builds = dir.path().join("builds");
+    init_fixture_repo(&builds.join("checkout"), FIXTURE_FILES);
+    std::fs::create_dir_all(dir.path().join("elsewhere")).unwrap();
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    config.local_scan_base_dirs = vec![builds.clone()];
+    let state = test_state(config, fossology.clone()).await;
+    let app = create_router(state.clone());
+
+    let checkout = builds.join("checkout").to_string_lossy().to_string();
+    let admin = Some("admin-secret");
+    let create = |token, payload| request_as(&app, "POST", "/api/v1/scans", token, Some(payload));
+    let (status, _) = create(None, json!({ "path": checkout })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let outside = dir.path().join("elsewhere").to_string_lossy().to_string();
+    let (status, _) = create(admin, json!({ "path": outside })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = create(admin, json!({ "path": checkout, "branch": "main" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, created) = create(admin, json!({ "path": checkout })).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", created);
+    let scan = wait_for_scan(&app, created["scan_id"].as_str().unwrap()).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    assert!(scan["git_url"].as_str().unwrap().starts_with("local://"));
+    assert_eq!(fossology.scan_count(), 1);
+
+    // The checkout is scanned in place and left as it was
+    assert!(builds.join("checkout/src/lib.rs").exists());
+    let rerun = format!("/api/v1/scans/{}/rerun", scan["scan_id"].as_str().unwrap());
+    let (status, _) = request(&app, "POST", &rerun, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // The Semgrep container cannot read directories on the server
+    let mut config = (*state.config).clone();
+    config.semgrep_mode = SemgrepMode::Docker;
+    let docker = create_router(AppState {
+        config: Arc::new(config),
+        ..state
+    });
+    let payload = json!({ "path": checkout });
+    let (status, _) = request_as(&docker, "POST", "/api/v1/scans", admin, Some(payload)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = request_as(&docker, "POST", &rerun, admin, None).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_scan_clones_with_registered_credential() {
+    let dir = tempfile::tempdir().unwrap();
+    let git_url = init_fixture_repo(&dir.path().join("fixture"), FIXTURE_FILES);
+    let mut config = test_config(dir.path().join("workspaces"));
+    config.admin_api_token = Some("admin-secret".to_string());
+    let state = test_state(config, Arc::new(MockScanner::new("fossology"))).await;
+    let db = state.db.clone();
+    let app = create_router(state);
+    let admin = Some("admin-secret");
+
+    let gitlab = json!({ "name": "ci", "provider": "gitlab", "secret": "glpat-secret\n" });
+    let create = |token, payload| request_as(&app, "POST", "/api/v1/credentials", token, Some(payload));
+    let (status, _) = create(None, gitlab.clone()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let gitea = json!({ "name": "gt", "provider": "gitea", "secret": "x" });
+    let (status, _) = create(admin, gitea).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, credential) = create(admin, gitlab.clone()).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", credential);
+    assert_eq!(credential["provider"], "gitlab");
+    assert!(!credential.to_string().contains("glpat-secret"));
+    let (status, _) = create(admin, gitlab).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let credential_id = credential["id"].as_str().unwrap();
+
+    // Only the ciphertext is stored
+    let stored: String = sqlx::query_scalar("SELECT secret_encrypted FROM credentials")
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert!(!stored.contains("glpat-secret"));
+
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": git_url, "credential_id": credential_id, "git_token": "t" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = request(
+        &app,
+        "POST",
+        "/api/v1/scans",
+        Some(json!({ "git_url": git_url, "credential_id": "missing" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let scan = run_scan(&app, json!({ "git_url": git_url, "credential_id": credential_id })).await;
+    assert_eq!(scan["status"], "completed", "{}", scan);
+    let scan_id = scan["scan_id"].as_str().unwrap();
+    let token: Option<String> = sqlx::query_scalar("SELECT git_token FROM scans WHERE id = ?")
+        .bind(scan_id)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(token, None);
+    let credential = get_json(&app, &format!("/api/v1/credentials/{}", credential_id)).await;
+    assert!(credential["last_used_at"].is_string());
+
+    // Re-runs clone with the same credential, until it is deleted
+    let rerun = format!("/api/v1/scans/{}/rerun", scan_id);
+    let rerun_scan = start_and_wait(&app, &rerun, None).await;
+    assert_eq!(rerun_scan["status"], "completed", "{}", rerun_scan);
+    assert_eq!(rerun_scan["credential_id"], credential_id);
+
+    let credential_uri = format!("/api/v1/credentials/{}", credential_id);
+    let (status, _) = request_as(&app, "DELETE", &credential_uri, None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = request_as(&app, "DELETE", &credential_uri, admin, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = request(&app, "POST", &rerun, None).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -0,0 +1,260 @@
+use crate::error::ClientError;
+use crate::models::{
+    ApproveScanResponse, CreateScanRequest, GateReport, ListScansQuery, RerunScanRequest,
+    ResultsQuery, SbomFormat, ScanDetails, ScanDiffResponse, ScanHistoryResponse, ScanPage,
+    ScanResponse, ScanResultsResponse, ScanTimelineResponse,
+};
+use reqwest::{header::HeaderMap, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use std::time::{Duration, Instant};
+
+/// Header carrying the API key, as sent by the web UI
+const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Async client for the Legal Scanner REST API
+#[derive(Debug, Clone)]
+pub struct LegalScannerClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl LegalScannerClient {
+    /// `base_url` is the server root, e.g. `http://localhost:8080`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Use a preconfigured reqwest client (timeouts, proxies, TLS roots)
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// POST /api/v1/scans
+    pub async fn create_scan(
+        &self,
+        request: &CreateScanRequest,
+    ) -> Result<ScanResponse, ClientError> {
+        let response = self.send(self.post("/api/v1/scans").json(request)).await?;
+        json(response).await
+    }
+
+    /// GET /api/v1/scans
+    pub async fn list_scans(&self, query: &ListScansQuery) -> Result<ScanPage, ClientError> {
+        let response = self.send(self.get("/api/v1/scans").query(query)).await?;
+
+        let headers = response.headers().clone();
+        let scans: Vec<ScanResponse> = json(response).await?;
+        Ok(ScanPage {
+            total: header_i64(&headers, "x-total-count").unwrap_or(scans.len() as i64),
+            page: header_i64(&headers, "x-page").unwrap_or(1),
+            per_page: header_i64(&headers, "x-per-page").unwrap_or(scans.len() as i64),
+            scans,
+        })
+    }
+
+    /// GET /api/v1/scans/:id
+    pub async fn get_scan(&self, scan_id: &str) -> Result<ScanDetails, ClientError> {
+        let response = self
+            .send(self.get(&format!("/api/v1/scans/{}", scan_id)))
+            .await?;
+        json(response).await
+    }
+
+    /// DELETE /api/v1/scans/:id
+    pub async fn delete_scan(&self, scan_id: &str) -> Result<(), ClientError> {
+        let path = format!("/api/v1/scans/{}", scan_id);
+        self.send(self.http.delete(self.url(&path))).await?;
+        Ok(())
+    }
+
+    /// GET /api/v1/scans/:id/results
+    pub async fn get_results(
+        &self,
+        scan_id: &str,
+        query: &ResultsQuery,
+    ) -> Result<ScanResultsResponse, ClientError> {
+        let path = format!("/api/v1/scans/{}/results", scan_id);
+        let response = self.send(self.get(&path).query(query)).await?;
+        json(response).await
+    }
+
+    /// GET /api/v1/scans/:id/timeline
+    pub async fn get_timeline(&self, scan_id: &str) -> Result<ScanTimelineResponse, ClientError> {
+        let path = format!("/api/v1/scans/{}/timeline", scan_id);
+        let response = self.send(self.get(&path)).await?;
+        json(response).await
+    }
+
+    /// GET /api/v1/scans/:id/history - findings of a due-diligence scan
+    pub async fn get_history(&self, scan_id: &str) -> Result<ScanHistoryResponse, ClientError> {
+        let path = format!("/api/v1/scans/{}/history", scan_id);
+        let response = self.send(self.get(&path)).await?;
+        json(response).await
+    }
+
+    /// GET /api/v1/scans/:id/diff/:other_id - changes from `scan_id` to `other_id`
+    pub async fn diff_scans(
+        &self,
+        scan_id: &str,
+        other_id: &str,
+    ) -> Result<ScanDiffResponse, ClientError> {
+        let path = format!("/api/v1/scans/{}/diff/{}", scan_id, other_id);
+        let response = self.send(self.get(&path)).await?;
+        json(response).await
+    }
+
+    /// GET /api/v1/scans/:id/gate - CI policy verdict of a finished scan
+    pub async fn get_gate(&self, scan_id: &str) -> Result<GateReport, ClientError> {
+        let path = format!("/api/v1/scans/{}/gate", scan_id);
+        let response = self.send(self.get(&path)).await?;
+        json(response).await
+    }
+
+    /// GET /api/v1/scans/:id/gate?format=text - the plaintext verdict
+    pub async fn get_gate_text(&self, scan_id: &str) -> Result<String, ClientError> {
+        let path = format!("/api/v1/scans/{}/gate", scan_id);
+        let response = self
+            .send(self.get(&path).query(&[("format", "text")]))
+            .await?;
+        Ok(response.text().await?)
+    }
+
+    /// POST /api/v1/scans/:id/approve
+    pub async fn approve_scan(&self, scan_id: &str) -> Result<ApproveScanResponse, ClientError> {
+        let path = format!("/api/v1/scans/{}/approve", scan_id);
+        let response = self.send(self.post(&path)).await?;
+        json(response).await
+    }
+
+    /// POST /api/v1/scans/:id/rerun - starts a new scan linked to `scan_id`
+    pub async fn rerun_scan(
+        &self,
+        scan_id: &str,
+        request: &RerunScanRequest,
+    ) -> Result<ScanResponse, ClientError> {
+        let path = format!("/api/v1/scans/{}/rerun", scan_id);
+        let response = self.send(self.post(&path).json(request)).await?;
+        json(response).await
+    }
+
+    /// GET /api/v1/scans/:id/sbom - the SPDX 2.3 document as raw bytes
+    pub async fn export_sbom(
+        &self,
+        scan_id: &str,
+        format: SbomFormat,
+    ) -> Result<Vec<u8>, ClientError> {
+        let path = format!("/api/v1/scans/{}/sbom", scan_id);
+        let response = self
+            .send(self.get(&path).query(&[("format", format.as_str())]))
+            .await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Poll a scan until it completes or fails. A failed scan is returned,
+    /// not turned into an error, so callers can inspect its error message.
+    pub async fn wait_for_completion(
+        &self,
+        scan_id: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<ScanDetails, ClientError> {
+        let started = Instant::now();
+        loop {
+            let scan = self.get_scan(scan_id).await?;
+            if scan.is_finished() {
+                return Ok(scan);
+            }
+            if started.elapsed() >= timeout {
+                return Err(ClientError::Timeout(scan_id.to_string()));
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn get(&self, path: &str) -> RequestBuilder {
+        self.http.get(self.url(path))
+    }
+
+    fn post(&self, path: &str) -> RequestBuilder {
+        self.http.post(self.url(path))
+    }
+
+    /// Attach the API key and turn error statuses into [`ClientError::Api`]
+    async fn send(&self, request: RequestBuilder) -> Result<Response, ClientError> {
+        let request = match &self.api_key {
+            Some(key) => request.header(API_KEY_HEADER, key),
+            None => request,
+        };
+
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        Err(ClientError::Api {
+            status: status.as_u16(),
+            message: error_message(&body),
+        })
+    }
+}
+
+async fn json<T: DeserializeOwned>(response: Response) -> Result<T, ClientError> {
+    let bytes = response.bytes().await?;
+    serde_json::from_slice(&bytes).map_err(|e| ClientError::InvalidResponse(e.to_string()))
+}
+
+fn header_i64(headers: &HeaderMap, name: &str) -> Option<i64> {
+    headers.get(name)?.to_str().ok()?.parse().ok()
+}
+
+/// The API reports errors as `{"error": ..., "details": ...}`
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| {
+            v.get("details")
+                .or_else(|| v.get("error"))
+                .and_then(|m| m.as_str())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(
+            error_message(
+                r#"{"error": "Scan x not found", "details": "Resource not found: Scan x not found"}"#
+            ),
+            "Resource not found: Scan x not found"
+        );
+        assert_eq!(error_message("Bad Gateway"), "Bad Gateway");
+    }
+
+    #[test]
+    fn test_base_url_trailing_slash() {
+        let client = LegalScannerClient::new("http://localhost:8080/");
+        assert_eq!(client.url("/health"), "http://localhost:8080/health");
+    }
+}
@@ -0,0 +1,26 @@
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The API answered with a non-success status
+    #[error("API error ({status}): {message}")]
+    Api { status: u16, message: String },
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Timed out waiting for scan {0}")]
+    Timeout(String),
+}
+
+impl ClientError {
+    /// HTTP status of an API error, if the request reached the server
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ClientError::Api { status, .. } => Some(*status),
+            ClientError::Http(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Typed async client for the Legal Scanner REST API.
+//!
+//! ```no_run
+//! use legalscanner_client::{CreateScanRequest, LegalScannerClient};
+//! use std::time::Duration;
+//!
+//! # async fn run() -> Result<(), legalscanner_client::ClientError> {
+//! let client = LegalScannerClient::new("http://localhost:8080").with_api_key("lgs_...");
+//! let scan = client
+//!     .create_scan(&CreateScanRequest::new("https://github.com/org/repo.git"))
+//!     .await?;
+//! let details = client
+//!     .wait_for_completion(&scan.scan_id, Duration::from_secs(5), Duration::from_secs(3600))
+//!     .await?;
+//! println!("risk: {:?}", details.risk_assessment.map(|r| r.level));
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod client;
+pub mod error;
+pub mod models;
+
+pub use client::LegalScannerClient;
+pub use error::ClientError;
+pub use models::*;
@@ -0,0 +1,441 @@
+//! Request and response types of the REST API. Field names and optionality
+//! follow `legalscanner-api`'s `api::models` and handler JSON exactly; the
+//! API's `tests/client.rs` round-trips them against the real router.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Scan models
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateScanRequest {
+    pub git_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_token: Option<String>,
+    /// Semgrep rule files / registry rulesets overriding the server defaults
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semgrep_rulesets: Option<Vec<String>>,
+    /// Time-boxed preview: skip Fossology and run only fast analyses
+    pub quick: bool,
+    /// Also scan the git history for licensed code removed from HEAD
+    pub due_diligence: bool,
+    /// Notified with a signed POST when the scan completes or fails
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>,
+    /// Only check out and scan this subdirectory, e.g. `services/payments`;
+    /// the API also accepts it as `subpath`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan_path: Option<String>,
+    /// Values of the deployment's custom fields, by field key
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_fields: BTreeMap<String, serde_json::Value>,
+}
+
+impl CreateScanRequest {
+    pub fn new(git_url: impl Into<String>) -> Self {
+        Self {
+            git_url: git_url.into(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanResponse {
+    pub scan_id: String,
+    pub status: String,
+    pub created_at: String,
+    pub git_url: String,
+    pub fossology_status: String,
+    pub semgrep_status: String,
+    #[serde(default)]
+    pub quick: bool,
+    #[serde(default)]
+    pub due_diligence: bool,
+    #[serde(default)]
+    pub risk_score: Option<i32>,
+    #[serde(default)]
+    pub risk_level: Option<String>,
+    /// Scan this one re-runs
+    #[serde(default)]
+    pub parent_scan_id: Option<String>,
+    /// Repository subdirectory the scan is limited to
+    #[serde(default)]
+    pub scan_path: Option<String>,
+    /// Requested branch, `None` for the default branch
+    #[serde(default)]
+    pub branch: Option<String>,
+}
+
+/// Body of `POST /api/v1/scans/:id/rerun`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RerunScanRequest {
+    /// Only repeat the scanners that failed in the original scan
+    pub failed_only: bool,
+}
+
+/// `GET /api/v1/scans/:id`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanDetails {
+    pub scan_id: String,
+    pub git_url: String,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub fossology_status: String,
+    pub semgrep_status: String,
+    pub fossology_error: Option<String>,
+    pub semgrep_error: Option<String>,
+    #[serde(default)]
+    pub quick: bool,
+    #[serde(default)]
+    pub due_diligence: bool,
+    #[serde(default)]
+    pub approved_at: Option<String>,
+    #[serde(default)]
+    pub screening_status: Option<String>,
+    /// Soft limits exceeded or scanners skipped; findings may be incomplete
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    #[serde(default)]
+    pub parent_scan_id: Option<String>,
+    #[serde(default)]
+    pub scan_path: Option<String>,
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Custom field values, by field key
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, serde_json::Value>,
+    pub summary: Option<ScanSummary>,
+    pub risk_assessment: Option<RiskAssessment>,
+}
+
+impl ScanDetails {
+    /// Whether the scan reached a terminal status
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status.as_str(), "completed" | "failed")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanSummary {
+    pub total_files: i64,
+    pub files_with_licenses: i64,
+    pub files_with_copyrights: i64,
+    pub unique_licenses: i64,
+    pub unique_copyrights: i64,
+}
+
+/// Query for `GET /api/v1/scans`; unset fields use the server defaults
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListScansQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_url_contains: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_after: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_before: Option<String>,
+    /// Field name, prefixed with `-` for descending order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+}
+
+/// One page of `GET /api/v1/scans`, with the totals from the response headers
+#[derive(Debug, Clone)]
+pub struct ScanPage {
+    pub scans: Vec<ScanResponse>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+// Result models
+/// Query for `GET /api/v1/scans/:id/results`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResultsQuery {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub result_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanResultsResponse {
+    pub scan_id: String,
+    pub repository_url: String,
+    pub scan_date: String,
+    pub status: String,
+    pub results: ScanResults,
+    /// Set when more results follow; pass it back as `cursor`
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScanResults {
+    pub licenses: Vec<LicenseFinding>,
+    pub documentation_licenses: Vec<LicenseFinding>,
+    pub data_licenses: Vec<LicenseFinding>,
+    pub copyrights: Vec<CopyrightFinding>,
+    pub ecc_findings: Vec<EccFinding>,
+    pub malware_findings: Vec<MalwareFinding>,
+    pub packages: Vec<PackageFinding>,
+    pub model_licenses: Vec<ModelLicenseFinding>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LicenseFinding {
+    pub file_path: String,
+    pub license: Option<String>,
+    pub spdx_id: Option<String>,
+    pub confidence: Option<f32>,
+    #[serde(default)]
+    pub provenance: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CopyrightFinding {
+    pub file_path: String,
+    pub statement: Option<String>,
+    #[serde(default)]
+    pub holders: Vec<String>,
+    #[serde(default)]
+    pub years: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EccFinding {
+    pub file_path: String,
+    pub content: Option<String>,
+    pub risk_severity: Option<String>,
+    pub source: Option<String>,
+    pub line_number: Option<i32>,
+    pub check_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MalwareFinding {
+    pub file_path: String,
+    pub signature: Option<String>,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackageFinding {
+    pub file_path: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub ecosystem: Option<String>,
+    pub declared_license: Option<String>,
+    pub purl: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelLicenseFinding {
+    pub file_path: String,
+    pub license: Option<String>,
+    pub spdx_id: Option<String>,
+    pub format: Option<String>,
+    pub source: Option<String>,
+}
+
+// Risk Assessment models
+#[derive(Debug, Clone, Deserialize)]
+pub struct RiskAssessment {
+    pub score: i32,
+    pub level: String,
+    pub factors: Vec<RiskFactor>,
+    /// Set for quick scans, where Fossology did not run
+    #[serde(default)]
+    pub preliminary: bool,
+    /// Scoring algorithm and risk configuration the score was calculated
+    /// with, `None` for scores stored before versions were recorded
+    #[serde(default)]
+    pub algorithm_version: Option<i32>,
+    #[serde(default)]
+    pub config_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RiskFactor {
+    pub category: String,
+    pub severity: String,
+    pub description: String,
+    pub affected_count: i32,
+    #[serde(default)]
+    pub details: Vec<String>,
+}
+
+// Timeline models
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanTimelineResponse {
+    pub scan_id: String,
+    pub events: Vec<TimelineEvent>,
+    /// Milliseconds between the first and last event
+    pub total_duration_ms: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimelineEvent {
+    pub event_type: String,
+    #[serde(default)]
+    pub scanner: Option<String>,
+    #[serde(default)]
+    pub detail: Option<String>,
+    pub timestamp: String,
+    #[serde(default)]
+    pub duration_ms: Option<i64>,
+}
+
+// History models
+/// `GET /api/v1/scans/:id/history`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanHistoryResponse {
+    pub scan_id: String,
+    pub due_diligence: bool,
+    pub findings: Vec<HistoryFinding>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistoryFinding {
+    /// `deleted_file`, `license_change` or `removed_third_party`
+    pub kind: String,
+    pub file_path: String,
+    pub commit_sha: String,
+    pub commit_date: String,
+    pub license: Option<String>,
+    pub detail: Option<String>,
+}
+
+// Diff models
+/// `GET /api/v1/scans/:id/diff/:other_id`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanDiffResponse {
+    /// Baseline scan
+    pub scan_id: String,
+    pub other_scan_id: String,
+    pub git_url: String,
+    pub risk: RiskScoreDelta,
+    pub new_licenses: Vec<LicenseChange>,
+    pub removed_licenses: Vec<LicenseChange>,
+    pub new_ecc_findings: Vec<FindingChange>,
+    pub added_findings: Vec<FindingChange>,
+    pub removed_findings: Vec<FindingChange>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RiskScoreDelta {
+    pub score: Option<i32>,
+    pub other_score: Option<i32>,
+    /// Positive when the other scan is riskier
+    pub delta: Option<i32>,
+    pub level: Option<String>,
+    pub other_level: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LicenseChange {
+    pub license: String,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FindingChange {
+    pub result_type: String,
+    pub file_path: String,
+    pub value: String,
+    #[serde(default)]
+    pub severity: Option<String>,
+}
+
+// Policy gate models
+/// `GET /api/v1/scans/:id/gate`
+#[derive(Debug, Clone, Deserialize)]
+pub struct GateReport {
+    pub schema_version: u32,
+    pub scan_id: String,
+    pub verdict: GateOutcome,
+    /// A failing verdict that approving the scan would lift
+    pub waivable: bool,
+    pub approved: bool,
+    #[serde(default)]
+    pub risk_score: Option<i32>,
+    #[serde(default)]
+    pub risk_level: Option<String>,
+    pub violations: Vec<GateViolation>,
+}
+
+impl GateReport {
+    /// Whether a pipeline should block on this verdict
+    pub fn is_failing(&self) -> bool {
+        self.verdict == GateOutcome::Fail
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GateOutcome {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GateViolation {
+    pub rule: String,
+    pub outcome: GateOutcome,
+    pub waivable: bool,
+    pub waived: bool,
+    pub message: String,
+    #[serde(default)]
+    pub details: Vec<String>,
+}
+
+// Approval models
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApproveScanResponse {
+    pub scan_id: String,
+    pub approved_at: Option<String>,
+    #[serde(default)]
+    pub screening_status: Option<String>,
+}
+
+// Export models
+/// SBOM serialization, as accepted by `GET /api/v1/scans/:id/sbom`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SbomFormat {
+    #[default]
+    Json,
+    Yaml,
+    TagValue,
+    Rdf,
+}
+
+impl SbomFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SbomFormat::Json => "json",
+            SbomFormat::Yaml => "yaml",
+            SbomFormat::TagValue => "tag-value",
+            SbomFormat::Rdf => "rdf",
+        }
+    }
+}
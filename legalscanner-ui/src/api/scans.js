@@ -1,10 +1,13 @@
 import client from './client'
 
-export const createScan = async (gitUrl, gitToken = null) => {
+export const createScan = async (gitUrl, gitToken = null, subpath = null) => {
   const payload = { git_url: gitUrl }
   if (gitToken) {
     payload.git_token = gitToken
   }
+  if (subpath) {
+    payload.subpath = subpath
+  }
   const response = await client.post('/api/v1/scans', payload)
   return response.data
 }
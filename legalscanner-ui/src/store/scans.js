@@ -23,11 +23,11 @@ export const useScansStore = defineStore('scans', () => {
     }
   }
 
-  const createScan = async (gitUrl, gitToken = null) => {
+  const createScan = async (gitUrl, gitToken = null, subpath = null) => {
     loading.value = true
     error.value = null
     try {
-      const scan = await scansApi.createScan(gitUrl, gitToken)
+      const scan = await scansApi.createScan(gitUrl, gitToken, subpath)
       scans.value.unshift(scan)
       return scan
     } catch (e) {